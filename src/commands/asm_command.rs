@@ -0,0 +1,149 @@
+//! `asm` command: assembles instructions written in the syntax of the disassembly.
+//!
+//! See [`crate::reverse::asm`] for the accepted syntax. The output is a listing, a patch spec
+//! for the `patch` command, or the raw bytes.
+
+use crate::reverse::asm::{assemble, AsmTarget, AssembledInstruction};
+use crate::reverse::raw_input::{parse_offset, parse_sbpf_version};
+use crate::Commands;
+use anyhow::{anyhow, Context, Result};
+use log::debug;
+use std::io::Read;
+use std::path::Path;
+
+pub struct AsmCmd {
+    pub input: Option<String>,
+    pub bytecodes_file: Option<String>,
+    pub pc: String,
+    pub sbpf_version: String,
+    pub format: String,
+    pub output: Option<String>,
+}
+
+impl AsmCmd {
+    pub fn new_from_clap(cmd: &Commands) -> Self {
+        match cmd {
+            Commands::Asm {
+                input,
+                bytecodes_file,
+                pc,
+                sbpf_version,
+                format,
+                output,
+            } => Self {
+                input: input.clone(),
+                bytecodes_file: bytecodes_file.clone(),
+                pc: pc.clone(),
+                sbpf_version: sbpf_version.clone(),
+                format: format.clone(),
+                output: output.clone(),
+            },
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Formats the assembled instructions as a listing: instruction pointer, bytes and source.
+fn listing(instructions: &[AssembledInstruction]) -> String {
+    instructions
+        .iter()
+        .map(|insn| {
+            format!(
+                "{:>6}  {:<32}  {}\n",
+                insn.pc,
+                hex::encode(&insn.bytes),
+                insn.source
+            )
+        })
+        .collect()
+}
+
+/// Formats the assembled instructions as a patch spec replacing the instructions at their pc.
+fn patch_spec(pc: usize, instructions: &[AssembledInstruction]) -> Result<String> {
+    let spec = serde_json::json!({
+        "patches": [{
+            "pc": pc,
+            "instructions": instructions
+                .iter()
+                .map(|insn| hex::encode(&insn.bytes))
+                .collect::<Vec<_>>(),
+            "comment": instructions
+                .iter()
+                .map(|insn| insn.source.as_str())
+                .collect::<Vec<_>>()
+                .join("; "),
+        }]
+    });
+    Ok(format!("{}\n", serde_json::to_string_pretty(&spec)?))
+}
+
+/// Runs the asm command: assembles the input and writes it in the requested format.
+///
+/// # Errors
+///
+/// If the input or the program can't be read, an instruction is invalid, or `--format bin` is
+/// used without `--output`.
+pub fn run(cmd: &AsmCmd) -> Result<()> {
+    let source = match &cmd.input {
+        Some(input) => {
+            std::fs::read_to_string(input).with_context(|| format!("Reading {}", input))?
+        }
+        None => {
+            let mut source = String::new();
+            std::io::stdin()
+                .read_to_string(&mut source)
+                .context("Reading stdin")?;
+            source
+        }
+    };
+    let pc = parse_offset(cmd.pc.trim().strip_prefix("lbb_").unwrap_or(&cmd.pc))?;
+    let target = match &cmd.bytecodes_file {
+        Some(bytecodes_file) => AsmTarget::from_elf(
+            &std::fs::read(bytecodes_file)
+                .with_context(|| format!("Reading {}", bytecodes_file))?,
+        )?,
+        None => AsmTarget::new(parse_sbpf_version(&cmd.sbpf_version)?),
+    };
+    debug!("Assembling at pc {} for {:?}", pc, target.sbpf_version);
+
+    let instructions = assemble(&source, pc, &target)?;
+    let bytes = match cmd.format.as_str() {
+        "listing" => listing(&instructions).into_bytes(),
+        "patch" => patch_spec(pc, &instructions)?.into_bytes(),
+        "bin" if cmd.output.is_none() => {
+            return Err(anyhow!("--format bin needs an --output file"));
+        }
+        _ => instructions
+            .iter()
+            .flat_map(|insn| insn.bytes.clone())
+            .collect(),
+    };
+    match &cmd.output {
+        Some(output) => std::fs::write(Path::new(output), bytes)
+            .with_context(|| format!("Writing {}", output))?,
+        None => print!("{}", String::from_utf8_lossy(&bytes)),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sbpf::program::SBPFVersion;
+
+    #[test]
+    fn test_patch_spec() {
+        let instructions =
+            assemble("mov64 r0, 0\nexit", 42, &AsmTarget::new(SBPFVersion::V0)).unwrap();
+        let spec: crate::reverse::patch::PatchSpec =
+            serde_json::from_str(&patch_spec(42, &instructions).unwrap()).unwrap();
+        assert_eq!(
+            spec.patches[0].instructions,
+            vec!["b700000000000000", "9500000000000000"]
+        );
+        assert_eq!(
+            spec.patches[0].comment.as_deref(),
+            Some("mov64 r0, 0; exit")
+        );
+    }
+}
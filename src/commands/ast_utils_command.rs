@@ -4,48 +4,59 @@ use log::info;
 use std::fs;
 use std::path::Path;
 use crate::engines::starlark_engine::StarlarkEngine;
-use crate::parsers::syn_ast::{ast_to_json_with_positions, enrich_ast_with_source_lines};
+use crate::parsers::syn_ast::{
+    ast_to_json_with_positions, enrich_ast_with_source_lines, ColumnEncoding,
+};
 use crate::state::sast_state::SynAst;
 
 pub struct AstUtilsCmd {
     pub file_path: String,
     pub starlark_syn_ast: bool,
+    pub column_encoding: ColumnEncoding,
 }
 
 impl AstUtilsCmd {
     pub fn new_from_clap(cmd: &Commands) -> Self {
         match cmd {
-            Commands::AstUtils { file_path, starlark_syn_ast } => Self {
+            Commands::AstUtils { file_path, starlark_syn_ast, column_encoding } => Self {
                 file_path: file_path.clone(),
                 starlark_syn_ast: *starlark_syn_ast,
+                column_encoding: column_encoding.parse().unwrap_or_default(),
             },
             _ => unreachable!(),
         }
     }
 }
 
-fn generate_ast_from_file(file_path: &str) -> Result<syn::File> {
+fn generate_ast_from_file(file_path: &str) -> Result<(String, syn::File)> {
     info!("Generating AST for file: {}", file_path);
     let file_contents = fs::read_to_string(file_path)
         .with_context(|| format!("Unable to read file: {}", file_path))?;
-    syn::parse_file(&file_contents)
-        .with_context(|| format!("Unable to parse file: {}", file_path))
+    let ast = syn::parse_file(&file_contents)
+        .with_context(|| format!("Unable to parse file: {}", file_path))?;
+    Ok((file_contents, ast))
 }
 
 pub fn run(cmd: &AstUtilsCmd) -> Result<()> {
-    let ast = generate_ast_from_file(&cmd.file_path)?;
+    let (file_contents, ast) = generate_ast_from_file(&cmd.file_path)?;
     if !cmd.starlark_syn_ast {
         println!("{}", syn_serde::json::to_string_pretty(&ast));
         return Ok(())
     }
 
-    let ast_positions = enrich_ast_with_source_lines(&ast, Path::new(cmd.file_path.as_str()));
+    let ast_positions = enrich_ast_with_source_lines(
+        &ast,
+        Path::new(cmd.file_path.as_str()),
+        &file_contents,
+        cmd.column_encoding,
+    );
 
     let ast_json = ast_to_json_with_positions(&ast, &ast_positions);
     let prepared = StarlarkEngine::new().eval_get_prepared_ast("get_prepared_ast", String::new(), &SynAst {
         ast: ast.clone(),
         ast_positions,
         ast_json,
+        ast_json_file: None,
         results: vec![],
     }).with_context(|| "Failed to evaluate prepared AST with Starlark engine")?;
 
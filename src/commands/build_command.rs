@@ -1,15 +1,49 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use crate::helpers::{
-    check_binary_installed, create_dir_if_not_exists, get_project_type, BeforeCheck, ProjectType,
+    check_binary_installed, create_dir_if_not_exists, get_project_type, BeforeCheck, OutputSink,
+    OutputStream, ProjectType,
 };
-use crate::state::build_state::BuildState;
+use crate::helpers::run_layout::RunDir;
+use crate::helpers::toolchains;
+use crate::state::build_state::{BuildArtifact, BuildState, BuildToolchain};
 use crate::{helpers, Commands};
-use log::{debug, error};
+use indicatif::ProgressBar;
+use log::{debug, error, info};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
 
+/// File listing the successful builds of a run, written in the run directory.
+pub const BUILD_MANIFEST_FILE: &str = "build-manifest.json";
+
+/// Artifacts emitted by rustc unless `--emit` is given.
+pub const DEFAULT_EMIT: &str = "asm,llvm-bc,llvm-ir,obj,metadata,link,dep-info,mir";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildProfile {
+    Release,
+    Debug,
+}
+
+#[derive(Clone)]
 pub struct BuildCmd {
-    pub target_dir: String,
+    pub target_dirs: Vec<String>,
+    /// Number of projects built at the same time.
+    pub jobs: usize,
     pub out_dir: String,
-    pub unsafe_version_switch: bool,
+    /// Name of the run directory in `<out_dir>/build`.
+    pub label: Option<String>,
+    /// Build Anchor projects with the Anchor CLI version of their `Anchor.toml`, installed
+    /// in a sol-azy toolchain directory.
+    pub version_switch: bool,
+    pub features: Vec<String>,
+    pub profile: BuildProfile,
+    pub clean: bool,
+    /// Kinds given to rustc `--emit`, none means the rustc defaults.
+    pub emit: Vec<String>,
+    pub extra_rustflags: Option<String>,
+    /// Echo the output of the build commands.
+    pub verbose: bool,
 }
 
 impl BuildCmd {
@@ -17,34 +51,212 @@ impl BuildCmd {
         match cmd {
             Commands::Build {
                 target_dir,
+                jobs,
                 out_dir,
-                unsafe_version_switch
+                label,
+                version_switch,
+                features,
+                release: _,
+                debug,
+                no_clean,
+                emit,
+                rustflags,
+                verbose,
             } => Self {
-                target_dir: target_dir.clone(),
+                target_dirs: target_dir.clone(),
+                jobs: (*jobs).max(1),
                 out_dir: out_dir.clone(),
-                unsafe_version_switch: *unsafe_version_switch,
+                label: label.clone(),
+                version_switch: *version_switch,
+                features: features
+                    .iter()
+                    .map(|feature| feature.trim().to_string())
+                    .filter(|feature| !feature.is_empty())
+                    .collect(),
+                profile: if *debug {
+                    BuildProfile::Debug
+                } else {
+                    BuildProfile::Release
+                },
+                clean: !*no_clean,
+                emit: emit
+                    .iter()
+                    .map(|kind| kind.trim().to_string())
+                    .filter(|kind| !kind.is_empty() && kind != "none")
+                    .collect(),
+                extra_rustflags: rustflags.clone(),
+                verbose: *verbose,
             },
             _ => unreachable!(),
         }
     }
+
+    /// Returns the `RUSTFLAGS` of the build: the `--emit` list followed by the user flags.
+    pub fn rustflags(&self) -> String {
+        let mut rustflags = vec![];
+        if !self.emit.is_empty() {
+            rustflags.push(format!("--emit={}", self.emit.join(",")));
+        }
+        if let Some(extra) = self.extra_rustflags.as_deref().map(str::trim) {
+            if !extra.is_empty() {
+                rustflags.push(extra.to_string());
+            }
+        }
+        rustflags.join(" ")
+    }
+
+    /// Returns the arguments given to `cargo build-sbf` (after `--` for `anchor build`).
+    pub fn cargo_build_args(&self) -> Vec<String> {
+        let mut args = vec![];
+        if !self.features.is_empty() {
+            args.push("--features".to_string());
+            args.push(self.features.join(","));
+        }
+        if self.profile == BuildProfile::Debug {
+            args.push("--debug".to_string());
+        }
+        args
+    }
+
+    /// Returns `true` if several projects are built at the same time.
+    fn is_parallel(&self) -> bool {
+        self.jobs > 1 && self.target_dirs.len() > 1
+    }
+
+    /// Returns a spinner for a build step, or a hidden one (with a log line) when several
+    /// projects are built at the same time, since their spinners would overwrite each other.
+    fn progress(&self, msg: String) -> ProgressBar {
+        if self.is_parallel() {
+            info!("{}", msg);
+            helpers::events::progress(msg);
+            ProgressBar::hidden()
+        } else {
+            helpers::spinner::get_new_spinner(msg)
+        }
+    }
+
+    /// Returns the path of the log of the build commands of a project:
+    /// `<out-dir>/build-<project>-<hash>.log`, the hash of its path telling apart the projects
+    /// with the same directory name (e.g. `a/program` and `b/program` built with `--jobs`).
+    pub fn log_file(&self, target_dir: &str) -> PathBuf {
+        Path::new(&self.out_dir).join(format!(
+            "build-{}-{}.log",
+            project_name(target_dir),
+            path_hash(target_dir)
+        ))
+    }
+
+    /// Runs a build step (`cargo clean`, `anchor build`, ...) in `target_dir`.
+    ///
+    /// The output is streamed to the log of the project, echoed with `--verbose`, and the
+    /// cargo status lines (`Compiling ...`) are shown in the spinner.
+    fn run_step(
+        &self,
+        target_dir: &str,
+        command_name: &str,
+        args: &[&str],
+        env_vars: Vec<(&str, &str)>,
+        done_msg: &str,
+    ) -> anyhow::Result<()> {
+        let program = Path::new(command_name)
+            .file_stem()
+            .map_or(command_name.into(), |name| name.to_string_lossy());
+        let step = format!("{} {}", program, args.first().unwrap_or(&""));
+        let spinner = self.progress(format!("Running `{}` in {}", step, target_dir));
+        let compiled = AtomicUsize::new(0);
+        let on_line = |_: OutputStream, line: &str| {
+            if self.verbose {
+                if self.is_parallel() {
+                    info!("[{}] {}", target_dir, line);
+                } else {
+                    spinner.println(line);
+                }
+            }
+            if let Some(status) = cargo_status(line) {
+                if status.starts_with("Compiling") {
+                    compiled.fetch_add(1, Ordering::Relaxed);
+                }
+                let msg = format!(
+                    "Running `{}` in {} ({} crates compiled) {}",
+                    step,
+                    target_dir,
+                    compiled.load(Ordering::Relaxed),
+                    status
+                );
+                helpers::events::progress(msg.as_str());
+                spinner.set_message(msg);
+            }
+        };
+        let log_file = self.log_file(target_dir);
+        let res = helpers::run_command_streamed(
+            command_name,
+            args,
+            env_vars,
+            Some(Path::new(target_dir)),
+            &OutputSink {
+                log_file: Some(&log_file),
+                on_line: Some(&on_line),
+            },
+        );
+        match &res {
+            Ok(_) => spinner.finish_with_message(done_msg.to_string()),
+            Err(_) => {
+                spinner.finish_and_clear();
+                error!("Full output of `{}` in {}", step, log_file.display());
+            }
+        }
+        res.map(|_| ())
+    }
+}
+
+/// Returns the name of a project: the name of its directory.
+fn project_name(target_dir: &str) -> String {
+    std::fs::canonicalize(target_dir)
+        .ok()
+        .and_then(|dir| dir.file_name().map(|name| name.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "project".to_string())
+}
+
+/// Returns a short hash of the canonical path of `target_dir`.
+fn path_hash(target_dir: &str) -> String {
+    let path = std::fs::canonicalize(target_dir).unwrap_or_else(|_| PathBuf::from(target_dir));
+    hex::encode(&Sha256::digest(path.to_string_lossy().as_bytes())[..4])
+}
+
+/// Status verbs printed by cargo at the start of its progress lines.
+const CARGO_STATUSES: [&str; 8] = [
+    "Compiling",
+    "Checking",
+    "Downloading",
+    "Downloaded",
+    "Updating",
+    "Locking",
+    "Finished",
+    "Building",
+];
+
+/// Returns the trimmed line if it is a cargo status line (`   Compiling foo v0.1.0 (...)`).
+fn cargo_status(line: &str) -> Option<&str> {
+    let line = line.trim();
+    let verb = line.split_whitespace().next()?;
+    CARGO_STATUSES.contains(&verb).then_some(line)
 }
 
-/// Runs a series of preconditions before attempting to build the project.
+/// Runs a series of preconditions before attempting to build the projects.
 ///
 /// This includes checking for required binaries (`anchor`, `cargo`),
-/// verifying that the target directory exists, and that the output
+/// verifying that the target directories exist, and that the output
 /// directory exists or can be created.
 ///
 /// # Arguments
 ///
-/// * `target_dir` - The path to the project to be built.
-/// * `out_dir` - The output directory for the build artifacts.
+/// * `cmd` - The build command, with the projects to be built and the output directory.
 ///
 /// # Returns
 ///
 /// `true` if all checks passed, otherwise `false`.
 fn checks_before_build(cmd: &BuildCmd) -> bool {
-    [
+    let mut checks = vec![
         BeforeCheck {
             error_msg: "`anchor` isn't installed".to_string(),
             result: check_binary_installed(&"anchor".to_string()),
@@ -53,163 +265,392 @@ fn checks_before_build(cmd: &BuildCmd) -> bool {
             error_msg: "`cargo` isn't installed".to_string(),
             result: check_binary_installed(&"cargo".to_string()),
         },
-        BeforeCheck {
-            error_msg: format!("Target directory {} doesn't exist", cmd.target_dir),
-            result: std::path::Path::new(&cmd.target_dir).exists(),
-        },
-        BeforeCheck {
-            error_msg: format!(
-                "Output directory {} doesn't exist and can't be created",
-                cmd.out_dir
-            ),
-            result: create_dir_if_not_exists(&cmd.out_dir),
-        },
-    ]
-    .iter()
-    .map(|check| {
-        if !check.result {
-            error!("{}", check.error_msg);
-            return false;
-        }
-        return true;
-    })
-    .all(|check| check)
+    ];
+    checks.extend(cmd.target_dirs.iter().map(|target_dir| BeforeCheck {
+        error_msg: format!("Target directory {} doesn't exist", target_dir),
+        result: std::path::Path::new(target_dir).exists(),
+    }));
+    checks.push(BeforeCheck {
+        error_msg: format!(
+            "Output directory {} doesn't exist and can't be created",
+            cmd.out_dir
+        ),
+        result: create_dir_if_not_exists(&cmd.out_dir),
+    });
+    checks
+        .iter()
+        .map(|check| {
+            if !check.result {
+                error!("{}", check.error_msg);
+                return false;
+            }
+            true
+        })
+        .all(|check| check)
+}
+
+/// Error of [`run`] when some of the projects failed to build, keeping the builds that
+/// succeeded (which are listed in the build manifest).
+#[derive(thiserror::Error, Debug)]
+#[error("{failures} of {total} project(s) failed to build")]
+pub struct BuildFailures {
+    /// Number of projects that failed to build.
+    pub failures: usize,
+    /// Number of projects to build.
+    pub total: usize,
+    /// The `BuildState`s of the projects that were built.
+    pub states: Vec<BuildState>,
 }
 
-/// Main entry point to build a project, automatically selecting the build process
-/// based on the project type (Anchor or raw SBF).
+/// Main entry point to build the projects, automatically selecting the build process
+/// of each one based on its type (Anchor or raw SBF).
+///
+/// Up to `jobs` projects are built at the same time. Each build runs its commands in its
+/// own project directory, the working directory of sol-azy is never changed. The logs and the
+/// build manifest are written to the run directory `<out_dir>/build/<label or timestamp>`, with
+/// its `run.json`.
 ///
 /// # Arguments
 ///
-/// * `target_dir` - The path to the project to build.
-/// * `out_dir` - The path to output build artifacts.
+/// * `cmd` - The build command, with the projects to build and the build options.
 ///
 /// # Returns
 ///
-/// A `BuildState` on success, or an error if the build fails or the project type is unknown.
-pub fn run(cmd: &BuildCmd) -> anyhow::Result<BuildState> {
-    debug!("Starting build process for {}", cmd.target_dir);
+/// A `BuildState` per project on success, or an error if a build fails (each failure is
+/// logged) or a project type is unknown. When only some of the builds fail, the error is a
+/// [`BuildFailures`] with the `BuildState`s of the other projects.
+pub fn run(cmd: &BuildCmd) -> anyhow::Result<Vec<BuildState>> {
+    debug!("Starting build process for {:?}", cmd.target_dirs);
 
     if !checks_before_build(cmd) {
         error!("Can't build project, see errors above.");
         return Err(anyhow::anyhow!("Can't build project, see errors above."));
     }
 
-    match get_project_type(&cmd.target_dir) {
-        ProjectType::Anchor => build_anchor_project(cmd),
-        ProjectType::Sbf => build_sbf_project(cmd),
+    let mut run_dir = RunDir::create(Path::new(&cmd.out_dir), "build", cmd.label.as_deref())?;
+    for target_dir in &cmd.target_dirs {
+        run_dir.add_input(target_dir);
+    }
+    run_dir.set_option("jobs", cmd.jobs);
+    run_dir.set_option("version_switch", cmd.version_switch);
+    run_dir.set_option("features", &cmd.features);
+    run_dir.set_option("debug", cmd.profile == BuildProfile::Debug);
+    run_dir.set_option("clean", cmd.clean);
+    run_dir.set_option("rustflags", cmd.rustflags());
+    let run_cmd = BuildCmd {
+        out_dir: run_dir.dir_string(),
+        ..cmd.clone()
+    };
+    run_dir.finish(build_projects(&run_cmd))
+}
+
+/// Builds all the projects of `cmd`, `cmd.jobs` at a time, into the run directory `cmd.out_dir`.
+fn build_projects(cmd: &BuildCmd) -> anyhow::Result<Vec<BuildState>> {
+    // installed before the builds, so that parallel builds don't install the same version
+    if cmd.version_switch {
+        install_anchor_toolchains(cmd)?;
+    }
+
+    let workers = cmd.jobs.min(cmd.target_dirs.len()).max(1);
+    let next_project = AtomicUsize::new(0);
+    let mut results = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        let index = next_project.fetch_add(1, Ordering::Relaxed);
+                        let Some(target_dir) = cmd.target_dirs.get(index) else {
+                            break;
+                        };
+                        results.push((index, build_project(cmd, target_dir)));
+                    }
+                    results
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("Build worker panicked"))
+            .collect::<Vec<_>>()
+    });
+    results.sort_by_key(|(index, _)| *index);
+
+    let mut states = Vec::new();
+    let mut failures = 0;
+    for (index, result) in results {
+        match result {
+            Ok(state) => states.push(state),
+            Err(e) => {
+                error!("Build of {} failed: {}", cmd.target_dirs[index], e);
+                failures += 1;
+            }
+        }
+    }
+    if !states.is_empty() {
+        write_build_manifest(cmd, &states)?;
+    }
+    if failures > 0 {
+        return Err(BuildFailures {
+            failures,
+            total: cmd.target_dirs.len(),
+            states,
+        }
+        .into());
+    }
+    Ok(states)
+}
+
+/// Installs the Anchor CLI versions required by the `Anchor.toml` of the Anchor projects in
+/// their sol-azy toolchain directories (see [`toolchains`]).
+fn install_anchor_toolchains(cmd: &BuildCmd) -> anyhow::Result<()> {
+    let versions: BTreeSet<String> = cmd
+        .target_dirs
+        .iter()
+        .filter(|target_dir| get_project_type(target_dir) == ProjectType::Anchor)
+        .filter_map(|target_dir| helpers::get_anchor_version(Path::new(target_dir)).ok().flatten())
+        .collect();
+    for version in versions {
+        let spinner = helpers::spinner::get_new_spinner(format!("Installing Anchor {}...", version));
+        let res = toolchains::install_anchor_toolchain(&version);
+        match &res {
+            Ok(bin) => spinner.finish_with_message(format!("Anchor {} available at {}", version, bin.display())),
+            Err(_) => spinner.finish_and_clear(),
+        }
+        res?;
+    }
+    Ok(())
+}
+
+/// Returns the first line of `<program> <args>`, used to record the toolchain of a build.
+fn tool_version(program: &str, args: &[&str]) -> Option<String> {
+    helpers::run_command(program, args, vec![])
+        .ok()?
+        .lines()
+        .next()
+        .map(|line| line.trim().to_string())
+}
+
+/// Lists the programs (`.so`) built in `<target_dir>/target/deploy`, with their hashes.
+fn deployed_artifacts(target_dir: &str) -> Vec<BuildArtifact> {
+    let deploy_dir = Path::new(target_dir).join("target").join("deploy");
+    let mut artifacts: Vec<BuildArtifact> = std::fs::read_dir(&deploy_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "so"))
+                .filter_map(|path| {
+                    let bytes = std::fs::read(&path).ok()?;
+                    Some(BuildArtifact {
+                        path: path.to_string_lossy().to_string(),
+                        sha256: hex::encode(Sha256::digest(&bytes)),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    artifacts.sort_by(|a, b| a.path.cmp(&b.path));
+    artifacts
+}
+
+/// Writes the states of the successful builds to `<out-dir>/build-manifest.json`.
+fn write_build_manifest(cmd: &BuildCmd, states: &[BuildState]) -> anyhow::Result<()> {
+    let path = Path::new(&cmd.out_dir).join(BUILD_MANIFEST_FILE);
+    std::fs::write(&path, serde_json::to_string_pretty(states)?)?;
+    info!("Build manifest written to {}", path.display());
+    Ok(())
+}
+
+/// Builds a single project according to its type.
+fn build_project(cmd: &BuildCmd, target_dir: &str) -> anyhow::Result<BuildState> {
+    // the log gathers the output of all the steps of this build
+    let _ = std::fs::write(cmd.log_file(target_dir), "");
+    match get_project_type(&target_dir.to_string()) {
+        ProjectType::Anchor => build_anchor_project(cmd, target_dir),
+        ProjectType::Sbf | ProjectType::Pinocchio | ProjectType::Steel => {
+            build_sbf_project(cmd, target_dir)
+        }
         ProjectType::Unknown => Err(anyhow::anyhow!("Unknown project type.")),
     }
 }
 
+/// Runs `cargo clean` in the project, unless `--no-clean` was given.
+fn clean_project(cmd: &BuildCmd, target_dir: &str) -> anyhow::Result<()> {
+    if !cmd.clean {
+        debug!("Skipping `cargo clean` in {}", target_dir);
+        return Ok(());
+    }
+    cmd.run_step(target_dir, "cargo", &["clean"], vec![], "Cleaned previous build artifacts")
+}
+
 /// Builds a project using the Anchor framework by running `anchor build`.
 ///
-/// This function sets the working directory, cleans previous build artifacts,
-/// and then runs the Anchor CLI tool with appropriate `RUSTFLAGS`.
+/// This function cleans previous build artifacts (unless disabled), and then
+/// runs, from the project directory, the Anchor CLI tool with the configured
+/// `RUSTFLAGS`, features and profile.
 ///
 /// # Arguments
 ///
+/// * `cmd` - The build options.
 /// * `target_dir` - The path to the Anchor project.
-/// * `out_dir` - The output directory for build artifacts.
 ///
 /// # Returns
 ///
 /// A `BuildState` object if the build is successful, or an error otherwise.
-fn build_anchor_project(cmd: &BuildCmd) -> anyhow::Result<BuildState> {
-    debug!("Building anchor project {}", cmd.target_dir);
+fn build_anchor_project(cmd: &BuildCmd, target_dir: &str) -> anyhow::Result<BuildState> {
+    debug!("Building anchor project {}", target_dir);
     
-    let anchor_version = helpers::get_anchor_version(Path::new(&cmd.target_dir.clone()))?;
-    match anchor_version { 
-        Some(version) => {
-            debug!("Detected Anchor version {}", version);
-            if cmd.unsafe_version_switch {
-                let spinner = helpers::spinner::get_new_spinner(format!("Switching Anchor to {}...", version));
-                helpers::switch_anchor_version(version.as_str())?;
-                spinner.finish_with_message(format!("Switched Anchor to {}", version));
-            }
-        },
-        None => {}
+    let anchor_version = helpers::get_anchor_version(Path::new(target_dir))?;
+    let mut anchor = "anchor".to_string();
+    let mut managed = false;
+    if let Some(version) = anchor_version {
+        debug!("Detected Anchor version {}", version);
+        if cmd.version_switch {
+            anchor = toolchains::anchor_toolchain_bin(&version)?
+                .to_string_lossy()
+                .to_string();
+            managed = true;
+        }
     }
-    
-
-    let current_dir = std::env::current_dir()?;
-    std::env::set_current_dir(cmd.target_dir.clone())?;
-
-    let spinner = helpers::spinner::get_new_spinner(format!("Running `cargo clean` in {}", cmd.target_dir));
-    let res = helpers::run_command("cargo", &["clean"], vec![]);
-    spinner.finish_with_message("Cleaned previous build artifacts");
 
-    std::env::set_current_dir(current_dir)?;
-    res?;
-    let current_dir = std::env::current_dir()?;
-    std::env::set_current_dir(cmd.target_dir.clone())?;
+    clean_project(cmd, target_dir)?;
 
-    let spinner = helpers::spinner::get_new_spinner(format!("Running `anchor build` in {}", cmd.target_dir));
-    let res = helpers::run_command(
-        "anchor",
-        &["build", "--skip-lint"],
-        vec![(
-            "RUSTFLAGS",
-            "--emit=asm,llvm-bc,llvm-ir,obj,metadata,link,dep-info,mir",
-        )],
-    );
-    spinner.finish_with_message("Built project");
+    let mut args = vec!["build".to_string(), "--skip-lint".to_string()];
+    let cargo_args = cmd.cargo_build_args();
+    if !cargo_args.is_empty() {
+        args.push("--".to_string());
+        args.extend(cargo_args);
+    }
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let rustflags = cmd.rustflags();
+    debug!("Running `{} {}` with RUSTFLAGS=\"{}\"", anchor, args.join(" "), rustflags);
 
-    std::env::set_current_dir(current_dir)?;
-    res?;
+    cmd.run_step(
+        target_dir,
+        &anchor,
+        &args,
+        vec![("RUSTFLAGS", rustflags.as_str())],
+        "Built project",
+    )?;
 
     Ok(BuildState {
-        name: "".to_string(),
-        target_dir: cmd.target_dir.clone(),
+        name: project_name(target_dir),
+        target_dir: target_dir.to_string(),
         out_dir: cmd.out_dir.clone(),
+        toolchain: BuildToolchain {
+            version: tool_version(&anchor, &["--version"]),
+            path: if managed {
+                anchor.clone()
+            } else {
+                helpers::find_binary(&anchor)
+                    .map_or(anchor.clone(), |path| path.to_string_lossy().to_string())
+            },
+            managed,
+        },
+        artifacts: deployed_artifacts(target_dir),
     })
 }
 
 /// Builds a raw Solana SBF project using `cargo build-sbf`.
 ///
-/// Similar to the Anchor build process, this resets the environment,
-/// performs a clean, and invokes the build with specific `RUSTFLAGS`.
+/// Similar to the Anchor build process, this performs a clean (unless disabled),
+/// and invokes the build with the configured `RUSTFLAGS`, features and profile.
 ///
 /// # Arguments
 ///
+/// * `cmd` - The build options.
 /// * `target_dir` - The path to the SBF project.
-/// * `out_dir` - The output directory for build artifacts.
 ///
 /// # Returns
 ///
 /// A `BuildState` object if the build is successful, or an error otherwise.
-pub fn build_sbf_project(cmd: &BuildCmd) -> anyhow::Result<BuildState> {
-    debug!("Building sbf project {}", cmd.target_dir);
+pub fn build_sbf_project(cmd: &BuildCmd, target_dir: &str) -> anyhow::Result<BuildState> {
+    debug!("Building sbf project {}", target_dir);
 
-    let current_dir = std::env::current_dir()?;
-    std::env::set_current_dir(cmd.target_dir.clone())?;
+    clean_project(cmd, target_dir)?;
 
-    let spinner = helpers::spinner::get_new_spinner(format!("Running `cargo clean` in {}", cmd.target_dir));
-    let res = helpers::run_command("cargo", &["clean"], vec![]);
-    spinner.finish_with_message("Cleaned previous build artifacts");
-    
-    std::env::set_current_dir(current_dir)?;
-    res?;
-    let current_dir = std::env::current_dir()?;
-    std::env::set_current_dir(cmd.target_dir.clone())?;
+    let mut args = vec!["build-sbf".to_string()];
+    args.extend(cmd.cargo_build_args());
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let rustflags = cmd.rustflags();
+    debug!("Running `cargo {}` with RUSTFLAGS=\"{}\"", args.join(" "), rustflags);
 
-    let spinner = helpers::spinner::get_new_spinner(format!("Running `cargo build-sbf` in {}", cmd.target_dir));
-    let res = helpers::run_command(
+    cmd.run_step(
+        target_dir,
         "cargo",
-        &["build-sbf"],
-        vec![(
-            "RUSTFLAGS",
-            "--emit=asm,llvm-bc,llvm-ir,obj,metadata,link,dep-info,mir",
-        )],
-    );
-    spinner.finish_with_message("Built project");
-
-    std::env::set_current_dir(current_dir)?;
-    res?;
+        &args,
+        vec![("RUSTFLAGS", rustflags.as_str())],
+        "Built project",
+    )?;
 
     Ok(BuildState {
-        name: "".to_string(),
-        target_dir: cmd.target_dir.clone(),
+        name: project_name(target_dir),
+        target_dir: target_dir.to_string(),
         out_dir: cmd.out_dir.clone(),
+        toolchain: BuildToolchain {
+            version: tool_version("cargo", &["build-sbf", "--version"]),
+            path: helpers::find_binary("cargo")
+                .map_or("cargo".to_string(), |path| path.to_string_lossy().to_string()),
+            managed: false,
+        },
+        artifacts: deployed_artifacts(target_dir),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn parse(args: &[&str]) -> BuildCmd {
+        let cli = crate::Cli::parse_from(
+            ["sol-azy", "build", "-d", "project", "-r", "out"]
+                .iter()
+                .chain(args),
+        );
+        BuildCmd::new_from_clap(&cli.command.unwrap())
+    }
+
+    #[test]
+    fn test_build_options() {
+        let cmd = parse(&[]);
+        assert!(cmd.clean);
+        assert_eq!(cmd.target_dirs, ["project"]);
+        assert!(!cmd.is_parallel());
+        assert_eq!(cmd.rustflags(), format!("--emit={}", DEFAULT_EMIT));
+        assert!(cmd.cargo_build_args().is_empty());
+
+        let cmd = parse(&[
+            "--features",
+            "a, b",
+            "--debug",
+            "--no-clean",
+            "--emit",
+            "asm,obj",
+            "--rustflags",
+            "-C opt-level=1",
+        ]);
+        assert!(!cmd.clean);
+        assert_eq!(cmd.rustflags(), "--emit=asm,obj -C opt-level=1");
+        assert_eq!(cmd.cargo_build_args(), ["--features", "a,b", "--debug"]);
+
+        let cmd = parse(&["--emit", "none", "--release", "-d", "other", "-j", "2"]);
+        assert!(cmd.is_parallel());
+        assert_eq!(cmd.profile, BuildProfile::Release);
+        assert_eq!(cmd.rustflags(), "");
+
+        // the projects with the same directory name have their own log
+        assert_ne!(cmd.log_file("a/program"), cmd.log_file("b/program"));
+        assert_eq!(cmd.log_file("a/program"), cmd.log_file("a/program"));
+    }
+
+    #[test]
+    fn test_cargo_status() {
+        assert_eq!(
+            cargo_status("   Compiling anchor-lang v0.30.1"),
+            Some("Compiling anchor-lang v0.30.1")
+        );
+        assert_eq!(cargo_status("warning: unused variable"), None);
+    }
+}
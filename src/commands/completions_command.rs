@@ -0,0 +1,70 @@
+//! `completions` command and hidden `--generate-man` flag: shell completion scripts and man
+//! pages, both generated from the clap definitions of the CLI so that they follow its commands
+//! and options.
+
+use crate::{Cli, Commands};
+use anyhow::{Context, Result};
+use clap::CommandFactory;
+use clap_complete::Shell;
+use log::info;
+use std::io::Write;
+use std::path::Path;
+
+/// Name of the binary, as completed by the shells.
+const BIN_NAME: &str = "sol-azy";
+
+pub struct CompletionsCmd {
+    pub shell: Shell,
+}
+
+impl CompletionsCmd {
+    pub fn new_from_clap(cmd: &Commands) -> Self {
+        match cmd {
+            Commands::Completions { shell } => Self { shell: *shell },
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Returns the completion script of `shell`.
+fn completion_script(shell: Shell) -> Vec<u8> {
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut Cli::command(), BIN_NAME, &mut script);
+    script
+}
+
+/// Writes the completion script of the shell to stdout.
+pub fn run(cmd: &CompletionsCmd) -> Result<()> {
+    std::io::stdout()
+        .write_all(&completion_script(cmd.shell))
+        .context("Writing the completion script")
+}
+
+/// Writes the man pages of the CLI to `out_dir`, created if needed: `sol-azy.1`, and
+/// `sol-azy-<command>.1` for each command.
+pub fn generate_man(out_dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(out_dir).with_context(|| format!("Creating {}", out_dir.display()))?;
+    clap_mangen::generate_to(Cli::command(), out_dir)
+        .with_context(|| format!("Writing the man pages to {}", out_dir.display()))?;
+    info!("Man pages written to {}", out_dir.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_man() {
+        let out_dir = Path::new("temp_test_dir_man");
+        generate_man(out_dir).unwrap();
+        let page = std::fs::read_to_string(out_dir.join("sol-azy.1")).unwrap();
+        assert!(page.contains("completions"));
+        let sast = std::fs::read_to_string(out_dir.join("sol-azy-sast.1")).unwrap();
+        assert!(sast.contains("strict"));
+        std::fs::remove_dir_all(out_dir).unwrap();
+
+        let script = String::from_utf8(completion_script(Shell::Bash)).unwrap();
+        assert!(script.contains("--rules-dir"));
+    }
+}
@@ -0,0 +1,412 @@
+//! `doctor` command: upfront diagnosis of the toolchain used by sol-azy.
+//!
+//! Checks the tools needed by `build` (cargo, rustc, `cargo build-sbf` and its platform-tools,
+//! the Solana and Anchor CLIs) and by `reverse` (graphviz), compares the installed versions with
+//! the `[toolchain]` of the project's `Anchor.toml`, and prints how to fix what is missing.
+
+use crate::helpers::events::{self, Event};
+use crate::helpers::{check_binary_installed, find_binary, get_project_type, ProjectType};
+use crate::Commands;
+use log::debug;
+use prettytable::{format, Cell, Row, Table};
+use regex::Regex;
+use std::path::Path;
+use std::process::Command;
+
+pub struct DoctorCmd {
+    pub target_dir: Option<String>,
+}
+
+impl DoctorCmd {
+    pub fn new_from_clap(cmd: &Commands) -> Self {
+        match cmd {
+            Commands::Doctor { target_dir } => Self {
+                target_dir: target_dir.clone(),
+            },
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+impl std::fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckStatus::Ok => write!(f, "OK"),
+            CheckStatus::Warning => write!(f, "WARNING"),
+            CheckStatus::Error => write!(f, "ERROR"),
+        }
+    }
+}
+
+/// Result of a single check of the environment.
+#[derive(Debug, Clone)]
+pub struct DoctorCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub details: String,
+    /// How to fix the problem, for the checks that are not `Ok`.
+    pub remediation: Option<String>,
+}
+
+impl DoctorCheck {
+    fn ok(name: &str, details: String) -> Self {
+        Self {
+            name: name.to_string(),
+            status: CheckStatus::Ok,
+            details,
+            remediation: None,
+        }
+    }
+
+    fn failed(name: &str, status: CheckStatus, details: String, remediation: String) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            details,
+            remediation: Some(remediation),
+        }
+    }
+}
+
+/// Runs `bin args` and returns its trimmed output (stdout, or stderr if stdout is empty, as
+/// `dot -V` prints its version on stderr), or `None` if the command can't be run or fails.
+fn tool_output(bin: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(find_binary(bin)?).args(args).output().ok()?;
+    if !output.status.success() {
+        debug!(
+            "`{} {}` failed: {}",
+            bin,
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !stdout.is_empty() {
+        return Some(stdout);
+    }
+    Some(String::from_utf8_lossy(&output.stderr).trim().to_string())
+}
+
+/// Extracts the first `x.y[.z]` version number of a tool output.
+pub fn parse_version(output: &str) -> Option<String> {
+    Regex::new(r"\d+\.\d+(\.\d+)?")
+        .ok()?
+        .find(output)
+        .map(|m| m.as_str().to_string())
+}
+
+/// Returns `true` if `installed` matches the `expected` version, ignoring a leading `v` and
+/// the components missing from `expected` (`0.30` matches `0.30.1`).
+pub fn version_matches(installed: &str, expected: &str) -> bool {
+    let installed: Vec<&str> = installed
+        .trim()
+        .trim_start_matches('v')
+        .split('.')
+        .collect();
+    let expected: Vec<&str> = expected.trim().trim_start_matches('v').split('.').collect();
+    expected.len() <= installed.len() && expected.iter().zip(&installed).all(|(e, i)| e == i)
+}
+
+/// Checks a tool by running it with `args` and reading its version.
+fn check_tool(
+    name: &str,
+    bin: &str,
+    args: &[&str],
+    status_if_missing: CheckStatus,
+    remediation: &str,
+) -> (DoctorCheck, Option<String>) {
+    if !check_binary_installed(&bin.to_string()) {
+        return (
+            DoctorCheck::failed(
+                name,
+                status_if_missing,
+                format!("`{}` not found in PATH", bin),
+                remediation.to_string(),
+            ),
+            None,
+        );
+    }
+    match tool_output(bin, args) {
+        Some(output) => {
+            let version = parse_version(&output);
+            let details = output.lines().collect::<Vec<_>>().join(", ");
+            (DoctorCheck::ok(name, details), version)
+        }
+        None => (
+            DoctorCheck::failed(
+                name,
+                status_if_missing,
+                format!("`{} {}` failed", bin, args.join(" ")),
+                remediation.to_string(),
+            ),
+            None,
+        ),
+    }
+}
+
+/// Compares an installed version with the one pinned in the `[toolchain]` of `Anchor.toml`.
+fn check_pinned_version(
+    name: &str,
+    installed: Option<&str>,
+    expected: &str,
+    remediation: String,
+) -> DoctorCheck {
+    match installed {
+        Some(installed) if version_matches(installed, expected) => DoctorCheck::ok(
+            name,
+            format!("{} matches Anchor.toml ({})", installed, expected),
+        ),
+        Some(installed) => DoctorCheck::failed(
+            name,
+            CheckStatus::Warning,
+            format!("{} installed, Anchor.toml requires {}", installed, expected),
+            remediation,
+        ),
+        None => DoctorCheck::failed(
+            name,
+            CheckStatus::Warning,
+            format!(
+                "Unknown installed version, Anchor.toml requires {}",
+                expected
+            ),
+            remediation,
+        ),
+    }
+}
+
+/// Returns the `[toolchain]` table (`anchor_version`, `solana_version`) of an `Anchor.toml`.
+fn anchor_toolchain(project_dir: &Path) -> (Option<String>, Option<String>) {
+    let toolchain = std::fs::read_to_string(project_dir.join("Anchor.toml"))
+        .ok()
+        .and_then(|content| content.parse::<toml::Value>().ok())
+        .and_then(|value| value.get("toolchain").cloned());
+    let get = |key: &str| {
+        toolchain
+            .as_ref()
+            .and_then(|toolchain| toolchain.get(key))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+    (get("anchor_version"), get("solana_version"))
+}
+
+/// Runs all the checks.
+///
+/// # Arguments
+///
+/// * `target_dir` - Optional project whose `Anchor.toml` pins the expected versions. The
+///   Anchor CLI is only required for Anchor projects.
+pub fn run_checks(target_dir: Option<&str>) -> Vec<DoctorCheck> {
+    let project_type = target_dir
+        .map(|dir| get_project_type(&dir.to_string()))
+        .unwrap_or(ProjectType::Unknown);
+    let mut checks = vec![];
+
+    let (check, _) = check_tool(
+        "cargo",
+        "cargo",
+        &["--version"],
+        CheckStatus::Error,
+        "Install Rust with rustup: https://rustup.rs",
+    );
+    checks.push(check);
+
+    let (check, _) = check_tool(
+        "rustc",
+        "rustc",
+        &["--version"],
+        CheckStatus::Error,
+        "Install Rust with rustup: https://rustup.rs",
+    );
+    checks.push(check);
+
+    let (check, solana_version) = check_tool(
+        "solana CLI",
+        "solana",
+        &["--version"],
+        CheckStatus::Error,
+        "Install the Solana CLI: sh -c \"$(curl -sSfL https://release.anza.xyz/stable/install)\", then add it to your PATH",
+    );
+    checks.push(check);
+
+    let (check, _) = check_tool(
+        "cargo build-sbf",
+        "cargo",
+        &["build-sbf", "--version"],
+        CheckStatus::Error,
+        "`cargo build-sbf` and the platform-tools come with the Solana CLI: install it and make sure its bin directory is in your PATH",
+    );
+    checks.push(check);
+
+    let anchor_status = if project_type == ProjectType::Anchor {
+        CheckStatus::Error
+    } else {
+        CheckStatus::Warning
+    };
+    let (check, anchor_version) = check_tool(
+        "anchor CLI",
+        "anchor",
+        &["--version"],
+        anchor_status,
+        "Install the Anchor CLI with avm: cargo install --git https://github.com/coral-xyz/anchor avm --force && avm install latest && avm use latest",
+    );
+    checks.push(check);
+
+    if project_type == ProjectType::Anchor {
+        let (expected_anchor, expected_solana) =
+            anchor_toolchain(Path::new(target_dir.unwrap_or(".")));
+        if let Some(expected) = expected_anchor {
+            checks.push(check_pinned_version(
+                "anchor version",
+                anchor_version.as_deref(),
+                &expected,
+                format!(
+                    "Run `avm install {0} && avm use {0}`, or build with `sol-azy build --version-switch`",
+                    expected
+                ),
+            ));
+        }
+        if let Some(expected) = expected_solana {
+            checks.push(check_pinned_version(
+                "solana version",
+                solana_version.as_deref(),
+                &expected,
+                format!(
+                    "Run `agave-install init {}` (or `solana-install init {}`)",
+                    expected, expected
+                ),
+            ));
+        }
+    }
+
+    let (check, _) = check_tool(
+        "graphviz",
+        "dot",
+        &["-V"],
+        CheckStatus::Warning,
+        "Install graphviz (e.g. `apt install graphviz` or `brew install graphviz`) to render the CFG .dot files",
+    );
+    checks.push(check);
+
+    checks
+}
+
+/// Emits a `finding` event per failed check, with its remediation.
+fn emit_check_events(checks: &[DoctorCheck]) {
+    for check in checks.iter().filter(|check| check.status != CheckStatus::Ok) {
+        events::emit(Event::Finding {
+            rule: check.name.clone(),
+            severity: check.status.to_string(),
+            certainty: "High".to_string(),
+            ratings: None,
+            location: "environment".to_string(),
+            description: Some(match &check.remediation {
+                Some(remediation) => format!("{}. {}", check.details, remediation),
+                None => check.details.clone(),
+            }),
+        });
+    }
+}
+
+/// Prints the checks as a table, followed by the remediation steps.
+fn print_checks(checks: &[DoctorCheck]) {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.set_titles(Row::new(vec![
+        Cell::new("Check"),
+        Cell::new("Status"),
+        Cell::new("Details"),
+    ]));
+    for check in checks {
+        table.add_row(Row::new(vec![
+            Cell::new(&check.name),
+            Cell::new(&check.status.to_string()),
+            Cell::new(&check.details),
+        ]));
+    }
+    table.printstd();
+
+    let remediations: Vec<&DoctorCheck> = checks
+        .iter()
+        .filter(|check| check.remediation.is_some())
+        .collect();
+    if remediations.is_empty() {
+        println!("\nEverything looks good.");
+        return;
+    }
+    println!("\nHow to fix:");
+    for check in remediations {
+        println!(
+            "  - [{}] {}: {}",
+            check.status,
+            check.name,
+            check.remediation.as_deref().unwrap_or_default()
+        );
+    }
+}
+
+/// Checks the environment and prints the diagnosis.
+///
+/// # Returns
+///
+/// The checks, or an error if a required tool is missing.
+pub fn run(cmd: &DoctorCmd) -> anyhow::Result<Vec<DoctorCheck>> {
+    if let Some(dir) = &cmd.target_dir {
+        if !Path::new(dir).exists() {
+            return Err(anyhow::anyhow!("Target directory {} doesn't exist", dir));
+        }
+    }
+    let checks = run_checks(cmd.target_dir.as_deref());
+    emit_check_events(&checks);
+    if events::human_output() {
+        print_checks(&checks);
+    }
+
+    let errors = checks
+        .iter()
+        .filter(|check| check.status == CheckStatus::Error)
+        .count();
+    if errors > 0 {
+        return Err(anyhow::anyhow!(
+            "{} required tool(s) missing or misconfigured, see above",
+            errors
+        ));
+    }
+    Ok(checks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions() {
+        assert_eq!(
+            parse_version("anchor-cli 0.30.1").as_deref(),
+            Some("0.30.1")
+        );
+        assert_eq!(
+            parse_version("solana-cli 1.18.26 (src:d9f20e95; feat:3241752014, client:Agave)")
+                .as_deref(),
+            Some("1.18.26")
+        );
+        assert_eq!(
+            parse_version("dot - graphviz version 2.43.0 (0)").as_deref(),
+            Some("2.43.0")
+        );
+        assert_eq!(parse_version("no version"), None);
+
+        assert!(version_matches("0.30.1", "0.30.1"));
+        assert!(version_matches("0.30.1", "v0.30"));
+        assert!(!version_matches("0.29.0", "0.30.1"));
+        assert!(!version_matches("0.30", "0.30.1"));
+    }
+}
@@ -9,8 +9,8 @@ use std::path::Path;
 /// # Arguments
 ///
 /// * `config_path` - Path to the user-provided JSON config listing functions to be added.
-/// * `reduced_path` - Path to the reduced DOT file that will be updated.
-/// * `full_path` - Path to the full DOT file used as a reference.
+/// * `reduced_path` - Path to the reduced graph (`cfg.json` or DOT file) that will be updated.
+/// * `full_path` - Path to the full graph (`cfg.json` or DOT file) used as a reference.
 ///
 /// # Returns
 ///
@@ -41,14 +41,15 @@ fn checks_before_dotting(config_path: &str, reduced_path: &str, full_path: &str)
     .all(|check| check)
 }
 
-/// Runs the dotting command, which updates a reduced `.dot` file with
+/// Runs the dotting command, which updates a reduced graph (`cfg.json` or `.dot` file) with
 /// additional functions specified in a user-supplied configuration file.
 ///
 /// # Arguments
 ///
 /// * `config_path` - Path to the JSON configuration file containing function identifiers.
-/// * `reduced_dot_path` - Path to the reduced DOT file to be edited.
-/// * `full_dot_path` - Path to the full DOT file used to retrieve missing nodes/edges.
+/// * `reduced_dot_path` - Path to the reduced graph to be edited.
+/// * `full_dot_path` - Path to the full graph used to retrieve missing nodes/edges.
+/// * `depth` - If set, the functions reachable within `depth` calls are added as well.
 ///
 /// # Returns
 ///
@@ -60,7 +61,12 @@ fn checks_before_dotting(config_path: &str, reduced_path: &str, full_path: &str)
 /// - One or more input files are missing.
 /// - The configuration format is invalid.
 /// - The update process fails internally.
-pub fn run(config_path: String, reduced_dot_path: String, full_dot_path: String) -> Result<()> {
+pub fn run(
+    config_path: String,
+    reduced_dot_path: String,
+    full_dot_path: String,
+    depth: Option<usize>,
+) -> Result<()> {
     debug!("Starting dotting from config '{}'", config_path);
 
     if !checks_before_dotting(&config_path, &reduced_dot_path, &full_dot_path) {
@@ -69,7 +75,7 @@ pub fn run(config_path: String, reduced_dot_path: String, full_dot_path: String)
         ));
     }
 
-    editor_add_functions(config_path, reduced_dot_path, full_dot_path)?;
+    editor_add_functions(config_path, reduced_dot_path, full_dot_path, depth)?;
     Ok(())
 }
 
@@ -1,10 +1,82 @@
+use crate::fetcher::cache::{self, FetchMetadata, FETCH_METADATA_FILE, PROGRAMS_CACHE_DIR};
 use crate::fetcher::fetch_bytecode_to;
+use crate::fetcher::fetch_to;
+use crate::fetcher::history::fetch_program_at_slot_to;
+use crate::fetcher::layout::{heuristic_layout, idl_layout, render_layout};
+use crate::fetcher::prefetch::{export_resolved_pubkeys, resolve_pubkeys, MAX_RESOLVED_CANDIDATES};
+use crate::fetcher::rpc::RpcClient;
 use crate::fetcher::MAINNET_RPC;
+use crate::helpers::run_layout::RunDir;
+use crate::recap::idl::load_idl;
+use crate::reverse::account_names::AccountNames;
+use crate::reverse::entropy::{find_high_entropy_regions, find_pubkey_candidates};
+use crate::reverse::security_txt::export_program_info;
+use crate::Commands;
 use anyhow::Result;
-use log::{debug, error};
-use reqwest::Client;
-use serde_json::json;
-use std::path::Path;
+use log::{debug, error, info, warn};
+use std::path::{Path, PathBuf};
+
+pub struct FetcherCmd {
+    pub program_id: String,
+    pub out_dir: String,
+    /// Name of the run directory in `<out_dir>/fetcher`.
+    pub label: Option<String>,
+    pub rpc_url: Option<String>,
+    pub at_slot: Option<u64>,
+    /// Accepts a non-executable account and writes the memory map of its data.
+    pub layout: bool,
+    /// IDL used to segment the data of the account.
+    pub idl: Option<String>,
+    /// Account type of the IDL, found by discriminator when `None`.
+    pub account_type: Option<String>,
+    /// Wordlist of account names hashed to identify the account type.
+    pub account_names: Option<String>,
+    /// Source tree whose struct names are hashed to identify the account type.
+    pub account_sources: Option<String>,
+    /// Downloads the program even if its current deployment is cached.
+    pub force: bool,
+    /// Resolves the keys of the data sections of the program on-chain.
+    pub resolve_pubkeys: bool,
+    /// Requests per second sent to the RPC.
+    pub rpc_rate: u32,
+}
+
+impl FetcherCmd {
+    pub fn new_from_clap(cmd: &Commands) -> Self {
+        match cmd {
+            Commands::Fetcher {
+                program_id,
+                out_dir,
+                label,
+                rpc_url,
+                at_slot,
+                layout,
+                idl,
+                account_type,
+                account_names,
+                account_sources,
+                force,
+                resolve_pubkeys,
+                rpc_rate,
+            } => Self {
+                program_id: program_id.clone(),
+                out_dir: out_dir.clone(),
+                label: label.clone(),
+                rpc_url: rpc_url.clone(),
+                at_slot: *at_slot,
+                layout: *layout,
+                idl: idl.clone(),
+                account_type: account_type.clone(),
+                account_names: account_names.clone(),
+                account_sources: account_sources.clone(),
+                force: *force,
+                resolve_pubkeys: *resolve_pubkeys,
+                rpc_rate: *rpc_rate,
+            },
+            _ => unreachable!(),
+        }
+    }
+}
 
 /// Represents possible validation errors when preparing to fetch a program's bytecode.
 ///
@@ -42,7 +114,7 @@ enum FetchPrecheckError {
 async fn checks_before_fetch(
     out_dir: &str,
     program_id: &str,
-    rpc_url: &str,
+    rpc: &RpcClient,
 ) -> Result<(), FetchPrecheckError> {
     let out_path = Path::new(out_dir);
     if !out_path.is_dir() {
@@ -51,39 +123,14 @@ async fn checks_before_fetch(
         debug!("Output directory '{}' created successfully.", out_dir);
     }
 
-    let client = Client::new();
-
-    let request_body = json!({
-        "jsonrpc": "2.0",
-        "id": 1,
-        "method": "getAccountInfo",
-        "params": [
-            program_id,
-            { "encoding": "jsonParsed" }
-        ]
-    });
-
-    let res = client
-        .post(rpc_url)
-        .json(&request_body)
-        .send()
+    // the data isn't needed to tell whether the account is a program
+    let account = rpc
+        .get_account(program_id, Some(0))
         .await
-        .map_err(|_| FetchPrecheckError::ProgramAccountNotFound(program_id.to_string()))?;
-
-    let res_json: serde_json::Value = res
-        .json()
-        .await
-        .map_err(|_| FetchPrecheckError::ProgramAccountNotFound(program_id.to_string()))?;
-
-    let account = &res_json["result"]["value"];
-    if account.is_null() {
-        return Err(FetchPrecheckError::ProgramAccountNotFound(
-            program_id.to_string(),
-        ));
-    }
+        .map_err(|_| FetchPrecheckError::ProgramAccountNotFound(program_id.to_string()))?
+        .ok_or_else(|| FetchPrecheckError::ProgramAccountNotFound(program_id.to_string()))?;
 
-    let executable = account["executable"].as_bool().unwrap_or(false);
-    if !executable {
+    if !account.executable {
         return Err(FetchPrecheckError::ProgramNotExecutable(
             program_id.to_string(),
         ));
@@ -95,30 +142,55 @@ async fn checks_before_fetch(
 /// Runs the fetcher command to download bytecode of a program from the Solana blockchain.
 ///
 /// This function validates the program's existence, ensures the output directory exists
-/// (creating it if necessary), and writes the bytecode to `<out_dir>/fetched_program.so`.
+/// (creating it if necessary), and writes the bytecode to `fetched_program.so` in the run
+/// directory `<out_dir>/fetcher/<label or timestamp>`, with its `run.json`.
+/// When `at_slot` is set, the deployment that was live at that slot is written to
+/// `fetched_program_slot_<slot>.so` instead. The embedded `security.txt`, if any,
+/// is extracted into `program_info.out`, and the sha256 of the program is recorded in
+/// `fetched_program.json`. The current deployment is reused from `.solazy_cache/programs` when
+/// it was already fetched, unless `force` is set.
 ///
 /// # Arguments
 ///
-/// * `program_id` - The Solana program ID to fetch.
-/// * `out_dir` - Directory where `fetched_program.so` will be written.
-/// * `rpc_url` - Optional Solana RPC endpoint. If `None`, defaults to mainnet.
+/// * `cmd` - A reference to the `FetcherCmd` struct, containing:
+///   * `program_id` - The Solana program ID to fetch.
+///   * `out_dir` - Directory where the run directories are created.
+///   * `label` - Optional name of the run directory (the start time by default).
+///   * `rpc_url` - Optional Solana RPC endpoint. If `None`, defaults to mainnet.
+///   * `at_slot` - Optional slot at which the wanted deployment was live.
+///   * `force` - Whether to download the program even if it is cached.
+///   * `resolve_pubkeys` - Whether to resolve the keys of the data sections on-chain
+///     (`rodata_pubkeys.out`).
+///   * `rpc_rate` - Maximum number of requests per second sent to the RPC, by every step.
 ///
 /// # Returns
 ///
-/// * `Ok(())` if fetching and writing succeed.
+/// * `Ok(PathBuf)` with the written file if fetching and writing succeed.
 /// * `Err(anyhow::Error)` if the program doesn't exist, isn't executable,
 ///   the RPC fails, or the output file can't be written.
-pub async fn run(
-    program_id: String,
-    out_dir: String,
-    rpc_url: Option<String>,
-) -> anyhow::Result<()> {
-    let rpc_url_unwrapped = rpc_url.clone().unwrap_or_else(|| MAINNET_RPC.to_string());
+pub async fn run(cmd: &FetcherCmd) -> anyhow::Result<PathBuf> {
+    let rpc_url_unwrapped = cmd
+        .rpc_url
+        .clone()
+        .unwrap_or_else(|| MAINNET_RPC.to_string());
+
+    let rpc = RpcClient::with_rate(&rpc_url_unwrapped, cmd.rpc_rate);
 
-    debug!("Starting fetch for program ID '{}'", program_id);
+    debug!("Starting fetch for program ID '{}'", cmd.program_id);
 
-    match checks_before_fetch(&out_dir, &program_id, &rpc_url_unwrapped).await {
-        Ok(_) => {} // continue
+    let mut data_account = false;
+    match checks_before_fetch(&cmd.out_dir, &cmd.program_id, &rpc).await {
+        Ok(_) => {
+            if cmd.layout {
+                warn!("'{}' is a program, --layout is ignored", cmd.program_id);
+            }
+        }
+        Err(FetchPrecheckError::ProgramNotExecutable(pid))
+            if cmd.layout && cmd.at_slot.is_none() =>
+        {
+            debug!("'{}' is a data account, fetching its data", pid);
+            data_account = true;
+        }
         Err(FetchPrecheckError::OutputDirCreationFailed(dir)) => {
             return Err(anyhow::anyhow!(
                 "Failed to create output directory '{}'",
@@ -131,13 +203,184 @@ pub async fn run(
         }
         Err(FetchPrecheckError::ProgramNotExecutable(pid)) => {
             error!("Program exists but is not executable: {}", pid);
-            return Err(anyhow::anyhow!("Program '{}' is not executable", pid));
+            return Err(anyhow::anyhow!(
+                "Program '{}' is not executable (use --layout to fetch a data account)",
+                pid
+            ));
         }
     }
 
-    fetch_bytecode_to(&out_dir, Some(rpc_url_unwrapped.clone()), &program_id).await?;
+    let mut run_dir = RunDir::create(Path::new(&cmd.out_dir), "fetcher", cmd.label.as_deref())?;
+    run_dir.set_option("program_id", &cmd.program_id);
+    run_dir.set_option("rpc_url", &rpc_url_unwrapped);
+    run_dir.set_option("at_slot", cmd.at_slot);
+    run_dir.set_option("layout", cmd.layout);
+    run_dir.set_option("account_type", &cmd.account_type);
+    run_dir.set_option("force", cmd.force);
+    run_dir.set_option("resolve_pubkeys", cmd.resolve_pubkeys);
+    run_dir.set_option("rpc_rate", cmd.rpc_rate);
+    for input in cmd
+        .idl
+        .iter()
+        .chain(&cmd.account_names)
+        .chain(&cmd.account_sources)
+    {
+        run_dir.add_input(input);
+    }
+    let out_dir = run_dir.dir_string();
+    let result = if data_account {
+        fetch_account_into(cmd, &rpc, &out_dir).await
+    } else {
+        fetch_into(cmd, &rpc, &out_dir).await
+    };
+    run_dir.finish(result)
+}
 
-    Ok(())
+/// Fetches the program into the run directory `out_dir`, writes its metadata, extracts its
+/// `security.txt` and, with `resolve_pubkeys`, resolves the keys of its data sections.
+async fn fetch_into(cmd: &FetcherCmd, rpc: &RpcClient, out_dir: &str) -> anyhow::Result<PathBuf> {
+    let (out_path, metadata) = match cmd.at_slot {
+        Some(slot) => {
+            let out_path = fetch_program_at_slot_to(out_dir, rpc, &cmd.program_id, slot).await?;
+            let mut metadata =
+                FetchMetadata::new(&cmd.program_id, None, &std::fs::read(&out_path)?);
+            metadata.at_slot = Some(slot);
+            (out_path, metadata)
+        }
+        None => fetch_current_into(cmd, rpc, out_dir).await?,
+    };
+    metadata.save(&Path::new(out_dir).join(FETCH_METADATA_FILE))?;
+    info!("Program sha256: {}", metadata.sha256);
+
+    // Extract the embedded security.txt (if any) right away, next to the fetched program
+    let program = std::fs::read(&out_path)?;
+    let mut details = vec![("program_id", cmd.program_id.clone())];
+    if let Some(slot) = cmd.at_slot {
+        details.push(("slot", slot.to_string()));
+    }
+    let high_entropy_regions = find_high_entropy_regions(&program);
+    if let Some(security_txt) =
+        export_program_info(&program, &details, &high_entropy_regions, out_dir)?
+    {
+        info!(
+            "security.txt found (contacts: {})",
+            security_txt.get("contacts").unwrap_or("none")
+        );
+    }
+
+    if cmd.resolve_pubkeys {
+        let candidates = find_pubkey_candidates(&program);
+        if candidates.len() > MAX_RESOLVED_CANDIDATES {
+            warn!(
+                "{} key candidates in the data sections, only the first {} are resolved",
+                candidates.len(),
+                MAX_RESOLVED_CANDIDATES
+            );
+        }
+        let resolved = resolve_pubkeys(rpc, &candidates).await?;
+        let path = export_resolved_pubkeys(&resolved, candidates.len(), Path::new(out_dir))?;
+        info!(
+            "{} of {} key candidates found on-chain, written to {}",
+            resolved.len(),
+            candidates.len(),
+            path.display()
+        );
+    }
+
+    Ok(out_path)
+}
+
+/// Fetches the current deployment of the program into `out_dir` (`fetched_program.so`), from
+/// the cache when it holds this deployment, and caches it otherwise.
+///
+/// A program whose deployment can't be identified (e.g. loader v4) is always downloaded, and
+/// is not cached if it was upgraded while being downloaded.
+async fn fetch_current_into(
+    cmd: &FetcherCmd,
+    rpc: &RpcClient,
+    out_dir: &str,
+) -> anyhow::Result<(PathBuf, FetchMetadata)> {
+    let out_path = Path::new(out_dir).join("fetched_program.so");
+    let cache_dir = Path::new(PROGRAMS_CACHE_DIR);
+    let deployment = cache::current_deployment(rpc, &cmd.program_id).await?;
+    let key = deployment.cache_key();
+    debug!("Deployment of '{}': {:?}", cmd.program_id, deployment);
+
+    if let Some(key) = key.as_deref().filter(|_| !cmd.force) {
+        if let Some((elf, mut metadata)) = cache::lookup(cache_dir, &cmd.program_id, key) {
+            info!(
+                "Program unchanged since {}, reused from {}",
+                metadata.fetched_at, PROGRAMS_CACHE_DIR
+            );
+            std::fs::write(&out_path, elf)?;
+            metadata.from_cache = true;
+            return Ok((out_path, metadata));
+        }
+    }
+
+    fetch_bytecode_to(out_dir, rpc, &cmd.program_id).await?;
+    let elf = std::fs::read(&out_path)?;
+    let mut metadata = FetchMetadata::new(&cmd.program_id, Some(&deployment), &elf);
+    if let Some(key) = key {
+        if cache::current_deployment(rpc, &cmd.program_id).await? != deployment {
+            // the bytecode may be of either deployment
+            warn!(
+                "'{}' was upgraded during the fetch, not cached",
+                cmd.program_id
+            );
+            metadata.deploy_slot = None;
+        } else if let Err(e) = cache::store(cache_dir, &key, &elf, &metadata) {
+            warn!("Failed to cache the program: {}", e);
+        }
+    }
+    Ok((out_path, metadata))
+}
+
+/// Fetches the data of a non-executable account into the run directory `out_dir`
+/// (`fetched_account.bin`) and writes its memory map (`account_layout.txt`), segmented with
+/// the IDL when one is given, by heuristics otherwise. The account type is identified by its
+/// discriminator among the common account names, the ones of the IDL, of `account_names` and
+/// of the structs of `account_sources`.
+async fn fetch_account_into(
+    cmd: &FetcherCmd,
+    rpc: &RpcClient,
+    out_dir: &str,
+) -> anyhow::Result<PathBuf> {
+    // a bad IDL is reported before any request
+    let idl = cmd
+        .idl
+        .as_ref()
+        .map(|idl_path| load_idl(Path::new(idl_path)))
+        .transpose()?;
+    let account_names = AccountNames::load(
+        cmd.idl.as_deref().map(Path::new),
+        cmd.account_names.as_deref().map(Path::new),
+        cmd.account_sources.as_deref().map(Path::new),
+    )?;
+    fetch_to(out_dir, rpc, &cmd.program_id).await?;
+    let out_path = Path::new(out_dir).join("fetched_account.bin");
+    let data = std::fs::read(&out_path)?;
+
+    match account_names.identify(&data) {
+        Some(name) => info!("Account type identified by its discriminator: {}", name),
+        None => debug!(
+            "Discriminator not found among {} account names",
+            account_names.len()
+        ),
+    }
+    let segments = match &idl {
+        Some(idl) => idl_layout(&data, idl, cmd.account_type.as_deref())?,
+        None => heuristic_layout(&data, &account_names),
+    };
+    let layout_path = Path::new(out_dir).join("account_layout.txt");
+    std::fs::write(&layout_path, render_layout(&data, &segments))?;
+    info!(
+        "Account layout ({} segments) written to {}",
+        segments.len(),
+        layout_path.display()
+    );
+
+    Ok(out_path)
 }
 
 #[cfg(test)]
@@ -152,7 +395,7 @@ mod tests {
 
         let fake_program = "Missing11111111111111111111111111111111111111";
 
-        let result = checks_before_fetch(out_dir, fake_program, MAINNET_RPC).await;
+        let result = checks_before_fetch(out_dir, fake_program, &RpcClient::new(MAINNET_RPC)).await;
         assert!(matches!(
             result,
             Err(FetchPrecheckError::ProgramAccountNotFound(_))
@@ -169,7 +412,8 @@ mod tests {
         // A known non-executable account on-chain (e.g., a system account or buffer)
         let non_exec_account = "SysvarC1ock11111111111111111111111111111111"; // Clock sysvar is not executable
 
-        let result = checks_before_fetch(out_dir, non_exec_account, MAINNET_RPC).await;
+        let result =
+            checks_before_fetch(out_dir, non_exec_account, &RpcClient::new(MAINNET_RPC)).await;
         assert!(matches!(
             result,
             Err(FetchPrecheckError::ProgramNotExecutable(_))
@@ -185,7 +429,8 @@ mod tests {
 
         let valid_program = "4MangoMjqJ2firMokCjjGgoK8d4MXcrgL7XJaL3w6fVg"; // Mango V4 proxy program (randomly choosen)
 
-        let result = checks_before_fetch(out_dir, valid_program, MAINNET_RPC).await;
+        let result =
+            checks_before_fetch(out_dir, valid_program, &RpcClient::new(MAINNET_RPC)).await;
         assert!(result.is_ok());
 
         fs::remove_dir_all(out_dir).unwrap();
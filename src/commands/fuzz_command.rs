@@ -0,0 +1,130 @@
+//! `fuzz` command: harness spec of the fuzzer.
+//!
+//! The fuzzing engine is not implemented yet: the command generates the skeleton harness spec of
+//! an Anchor IDL, and validates a harness spec. See [`crate::fuzz::harness`].
+
+use crate::fuzz::harness::HarnessSpec;
+use crate::helpers::BeforeCheck;
+use crate::recap::idl::load_idl;
+use crate::Commands;
+use anyhow::Result;
+use log::{error, info, warn};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+pub struct FuzzCmd {
+    pub spec: Option<String>,
+    pub idl: Option<String>,
+    pub init_spec: Option<String>,
+}
+
+impl FuzzCmd {
+    pub fn new_from_clap(cmd: &Commands) -> Self {
+        match cmd {
+            Commands::Fuzz {
+                spec,
+                idl,
+                init_spec,
+            } => Self {
+                spec: spec.clone(),
+                idl: idl.clone(),
+                init_spec: init_spec.clone(),
+            },
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Verifies that the inputs exist, and that the skeleton doesn't overwrite a spec.
+fn checks_before_fuzz(cmd: &FuzzCmd) -> bool {
+    let mut checks = vec![];
+    for (kind, path) in [("Harness spec", &cmd.spec), ("IDL", &cmd.idl)] {
+        if let Some(path) = path {
+            checks.push(BeforeCheck {
+                error_msg: format!("{} '{}' does not exist.", kind, path),
+                result: Path::new(path).exists(),
+            });
+        }
+    }
+    if let Some(init_spec) = &cmd.init_spec {
+        checks.push(BeforeCheck {
+            error_msg: format!("'{}' already exists, not overwriting it.", init_spec),
+            result: !Path::new(init_spec).exists(),
+        });
+    }
+    checks
+        .iter()
+        .map(|check| {
+            if !check.result {
+                error!("{}", check.error_msg);
+                return false;
+            }
+            true
+        })
+        .all(|check| check)
+}
+
+/// Logs the accounts of each instruction of a valid spec, with the addresses known before
+/// fuzzing (fixed, or PDAs whose seeds don't depend on an argument or a generated account).
+fn describe_spec(spec: &HarnessSpec) -> Result<()> {
+    let program_id = Pubkey::from_str(&spec.program_id)?;
+    for instruction in &spec.instructions {
+        let addresses = instruction.resolve_addresses(&program_id, &HashMap::new())?;
+        info!(
+            "{}: {} argument(s), {} account(s)",
+            instruction.name,
+            instruction.args.len(),
+            instruction.accounts.len()
+        );
+        for (account, address) in instruction.accounts.iter().zip(addresses) {
+            let address = match address {
+                Some(address) => address.to_string(),
+                None if !account.seeds.is_empty() => "PDA, derived per input".to_string(),
+                None => "generated".to_string(),
+            };
+            info!("    {}: {}", account.name, address);
+        }
+    }
+    Ok(())
+}
+
+/// Runs the fuzz command: writes the skeleton harness spec of `--idl` to `--init-spec`, or
+/// validates `--spec` and lists its accounts.
+///
+/// # Errors
+///
+/// If an input is missing, `--init-spec` exists, or the IDL or the spec is invalid.
+pub fn run(cmd: &FuzzCmd) -> Result<()> {
+    if !checks_before_fuzz(cmd) {
+        return Err(anyhow::anyhow!(
+            "Fuzz prerequisites failed. Check the paths above."
+        ));
+    }
+
+    match (&cmd.spec, &cmd.idl, &cmd.init_spec) {
+        (_, Some(idl), Some(init_spec)) => {
+            let spec = HarnessSpec::from_idl(&load_idl(Path::new(idl))?);
+            spec.save(Path::new(init_spec))?;
+            info!(
+                "Skeleton harness spec of {} instruction(s) written to '{}'",
+                spec.instructions.len(),
+                init_spec
+            );
+            if let Err(e) = spec.validate() {
+                warn!("The skeleton has to be completed: {}", e);
+            }
+            Ok(())
+        }
+        (Some(spec), _, _) => {
+            let spec = HarnessSpec::load(Path::new(spec))?;
+            describe_spec(&spec)?;
+            warn!("The fuzzing engine is not implemented yet: the harness spec was only validated.");
+            Ok(())
+        }
+        _ => Err(anyhow::anyhow!(
+            "Nothing to do: give a harness spec (--spec), or an IDL to generate one from (--idl with --init-spec)."
+        )),
+    }
+}
@@ -0,0 +1,143 @@
+//! `import-names` command: merges the function names of another tool into `annotations.json`.
+//!
+//! See [`crate::reverse::names`] for the formats read.
+
+use crate::helpers::BeforeCheck;
+use crate::reverse::annotations::{AnnotationFile, ANNOTATIONS_FILENAME};
+use crate::reverse::elf_sections;
+use crate::reverse::names::{import_names, read_names, ImportSummary};
+use crate::Commands;
+use anyhow::{Context, Result};
+use log::{debug, error, warn};
+use std::path::{Path, PathBuf};
+
+pub struct ImportNamesCmd {
+    pub bytecodes_file: String,
+    pub input: String,
+    pub annotations: Option<String>,
+}
+
+impl ImportNamesCmd {
+    pub fn new_from_clap(cmd: &Commands) -> Self {
+        match cmd {
+            Commands::ImportNames {
+                bytecodes_file,
+                input,
+                annotations,
+            } => Self {
+                bytecodes_file: bytecodes_file.clone(),
+                input: input.clone(),
+                annotations: annotations.clone(),
+            },
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Verifies that the program and the names file exist.
+fn checks_before_import(cmd: &ImportNamesCmd) -> bool {
+    [
+        BeforeCheck {
+            error_msg: format!(
+                "Target bytecodes file '{}' does not exist.",
+                cmd.bytecodes_file
+            ),
+            result: Path::new(&cmd.bytecodes_file).exists(),
+        },
+        BeforeCheck {
+            error_msg: format!("Names file '{}' does not exist.", cmd.input),
+            result: Path::new(&cmd.input).exists(),
+        },
+    ]
+    .iter()
+    .map(|check| {
+        if !check.result {
+            error!("{}", check.error_msg);
+            return false;
+        }
+        true
+    })
+    .all(|check| check)
+}
+
+/// Path of the annotations file: `--annotations`, or the `annotations.json` next to the
+/// program, read by `reverse` by default.
+fn annotations_path(cmd: &ImportNamesCmd) -> PathBuf {
+    match &cmd.annotations {
+        Some(annotations) => PathBuf::from(annotations),
+        None => Path::new(&cmd.bytecodes_file).with_file_name(ANNOTATIONS_FILENAME),
+    }
+}
+
+/// Runs the import-names command: reads the names (and comments) of the input, converts their
+/// addresses into instruction pointers with the `.text` address of the program, and merges them
+/// into the annotations file, created if needed.
+///
+/// # Returns
+///
+/// The path of the annotations file and what was imported.
+///
+/// # Errors
+///
+/// If a file is missing or unreadable, or the names file or the existing annotations file is
+/// invalid. The annotations file is left untouched in that case.
+pub fn run(cmd: &ImportNamesCmd) -> Result<(PathBuf, ImportSummary)> {
+    debug!(
+        "Importing names of '{}' from '{}'",
+        cmd.bytecodes_file, cmd.input
+    );
+
+    if !checks_before_import(cmd) {
+        return Err(anyhow::anyhow!(
+            "Import prerequisites failed. Check that all paths exist."
+        ));
+    }
+
+    let elf = std::fs::read(&cmd.bytecodes_file)
+        .with_context(|| format!("Reading {}", cmd.bytecodes_file))?;
+    let text_address = elf_sections::elf_sections(&elf)
+        .iter()
+        .find(|section| section.name == ".text")
+        .map(|section| section.address)
+        .ok_or_else(|| anyhow::anyhow!("No .text section in {}", cmd.bytecodes_file))?;
+    let (names, comments) = read_names(Path::new(&cmd.input))?;
+
+    let path = annotations_path(cmd);
+    let mut annotations = if path.is_file() {
+        AnnotationFile::load(&path)?
+    } else {
+        AnnotationFile::default()
+    };
+    let summary = import_names(&mut annotations, &names, &comments, text_address);
+    if summary.skipped > 0 {
+        warn!(
+            "{} entries skipped: default names, or addresses outside of .text (at {:#x}).",
+            summary.skipped, text_address
+        );
+    }
+    annotations.save(&path)?;
+    Ok((path, summary))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_annotations_path() {
+        let mut cmd = ImportNamesCmd {
+            bytecodes_file: "bytecodes/program.so".to_string(),
+            input: "names.csv".to_string(),
+            annotations: None,
+        };
+        assert_eq!(
+            annotations_path(&cmd),
+            PathBuf::from("bytecodes/annotations.json")
+        );
+        cmd.annotations = Some("program.annotations.json".to_string());
+        assert_eq!(
+            annotations_path(&cmd),
+            PathBuf::from("program.annotations.json")
+        );
+    }
+}
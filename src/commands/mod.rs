@@ -2,18 +2,36 @@
 //!
 //! This module provides submodules for each top-level command supported by the CLI:
 //!
+//! - [`asm_command`] — Assembles instructions written in the syntax of the disassembly.
 //! - [`build_command`] — Handles building Anchor or SBF Solana programs.
+//! - [`completions_command`] — Generates the shell completion scripts and the man pages.
+//! - [`doctor_command`] — Checks the toolchain and explains how to fix it.
+//! - [`fuzz_command`] — Generates and validates the harness spec of the fuzzer.
+//! - [`import_names_command`] — Imports the function names of Ghidra or Binary Ninja into `annotations.json`.
+//! - [`patch_command`] — Applies a JSON patch spec to the instructions of a compiled program.
+//! - [`query_command`] — Lists the functions and blocks of a previous `reverse` run matching filters.
 //! - [`sast_command`] — Runs SAST (static analysis) using custom Starlark rules.
+//! - [`scan_all_command`] — Runs SAST (and recap) on the projects of a `solazy-workspace.toml`.
 //! - [`reverse_command`] — Performs reverse engineering on compiled eBPF bytecode
 //!   (disassembly, CFG generation, etc.).
+//! - [`serve_command`] — Runs `sast`, `reverse` and `fetcher` jobs submitted over an HTTP API.
 //!
 //! Each subcommand encapsulates its logic, parsing, validation, and execution paths.
 //! These are used internally by [`AppState`](crate::state::app_state::AppState) to handle `clap` commands.
 
+pub mod asm_command;
 pub mod ast_utils_command;
 pub mod build_command;
+pub mod completions_command;
+pub mod doctor_command;
 pub mod dotting_command;
 pub mod fetcher_command;
+pub mod fuzz_command;
+pub mod import_names_command;
+pub mod patch_command;
+pub mod query_command;
 pub mod reverse_command;
 pub mod sast_command;
+pub mod scan_all_command;
+pub mod serve_command;
 pub mod recap_command;
\ No newline at end of file
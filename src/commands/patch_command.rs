@@ -0,0 +1,143 @@
+//! `patch` command: applies a JSON patch spec to the instructions of a compiled program.
+//!
+//! See [`crate::reverse::patch`] for the format of the spec.
+
+use crate::helpers::BeforeCheck;
+use crate::reverse::patch::{apply_patches, PatchSpec};
+use crate::Commands;
+use anyhow::{Context, Result};
+use log::{debug, error, info};
+use std::path::{Path, PathBuf};
+
+pub struct PatchCmd {
+    pub bytecodes_file: String,
+    pub spec: String,
+    pub output: Option<String>,
+}
+
+impl PatchCmd {
+    pub fn new_from_clap(cmd: &Commands) -> Self {
+        match cmd {
+            Commands::Patch {
+                bytecodes_file,
+                spec,
+                output,
+            } => Self {
+                bytecodes_file: bytecodes_file.clone(),
+                spec: spec.clone(),
+                output: output.clone(),
+            },
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Verifies that the program and the patch spec exist.
+fn checks_before_patch(cmd: &PatchCmd) -> bool {
+    [
+        BeforeCheck {
+            error_msg: format!(
+                "Target bytecodes file '{}' does not exist.",
+                cmd.bytecodes_file
+            ),
+            result: Path::new(&cmd.bytecodes_file).exists(),
+        },
+        BeforeCheck {
+            error_msg: format!("Patch spec '{}' does not exist.", cmd.spec),
+            result: Path::new(&cmd.spec).exists(),
+        },
+    ]
+    .iter()
+    .map(|check| {
+        if !check.result {
+            error!("{}", check.error_msg);
+            return false;
+        }
+        true
+    })
+    .all(|check| check)
+}
+
+/// Path of the patched program: `--output`, or `<input stem>.patched.so` next to the input.
+fn output_path(cmd: &PatchCmd) -> PathBuf {
+    match &cmd.output {
+        Some(output) => PathBuf::from(output),
+        None => {
+            let input = Path::new(&cmd.bytecodes_file);
+            let stem = input
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| "program".to_string());
+            input.with_file_name(format!("{}.patched.so", stem))
+        }
+    }
+}
+
+/// Runs the patch command: applies the spec, logs the patched instructions and writes the
+/// patched program.
+///
+/// # Returns
+///
+/// The path of the patched program.
+///
+/// # Errors
+///
+/// If a file is missing or unreadable, the spec is invalid, or the patched program doesn't
+/// pass the verifier. Nothing is written in that case.
+pub fn run(cmd: &PatchCmd) -> Result<PathBuf> {
+    debug!("Patching '{}' with '{}'", cmd.bytecodes_file, cmd.spec);
+
+    if !checks_before_patch(cmd) {
+        return Err(anyhow::anyhow!(
+            "Patch prerequisites failed. Check that all paths exist."
+        ));
+    }
+
+    let elf = std::fs::read(&cmd.bytecodes_file)
+        .with_context(|| format!("Reading {}", cmd.bytecodes_file))?;
+    let spec = PatchSpec::load(Path::new(&cmd.spec))?;
+    let (patched, applied) = apply_patches(&elf, &spec)?;
+
+    for patch in &applied {
+        info!(
+            "Patched pc {}..{}{}",
+            patch.start,
+            patch.end,
+            patch
+                .comment
+                .as_ref()
+                .map(|comment| format!(" ({})", comment))
+                .unwrap_or_default()
+        );
+        for before in &patch.before {
+            info!("  - {}", before);
+        }
+        for after in &patch.after {
+            info!("  + {}", after);
+        }
+    }
+
+    let output = output_path(cmd);
+    std::fs::write(&output, patched).with_context(|| format!("Writing {}", output.display()))?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_path() {
+        let mut cmd = PatchCmd {
+            bytecodes_file: "bytecodes/program.so".to_string(),
+            spec: "patches.json".to_string(),
+            output: None,
+        };
+        assert_eq!(
+            output_path(&cmd),
+            PathBuf::from("bytecodes/program.patched.so")
+        );
+        cmd.output = Some("out.so".to_string());
+        assert_eq!(output_path(&cmd), PathBuf::from("out.so"));
+    }
+}
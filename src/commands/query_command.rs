@@ -0,0 +1,100 @@
+//! `query` command: answers questions on the artifacts of a previous `reverse` run, without
+//! analyzing the program again.
+//!
+//! See [`crate::reverse::query`] for the artifacts read and the filters.
+
+use crate::helpers::BeforeCheck;
+use crate::reverse::query::{render_matches, Artifacts, Query};
+use crate::Commands;
+use anyhow::Result;
+use log::{debug, error};
+use std::path::Path;
+
+pub struct QueryCmd {
+    pub artifacts: String,
+    pub calls: Option<String>,
+    pub strings: Option<String>,
+    pub min_instructions: Option<usize>,
+    pub function: Option<String>,
+    pub functions: bool,
+}
+
+impl QueryCmd {
+    pub fn new_from_clap(cmd: &Commands) -> Self {
+        match cmd {
+            Commands::Query {
+                artifacts,
+                calls,
+                strings,
+                min_instructions,
+                function,
+                functions,
+            } => Self {
+                artifacts: artifacts.clone(),
+                calls: calls.clone(),
+                strings: strings.clone(),
+                min_instructions: *min_instructions,
+                function: function.clone(),
+                functions: *functions,
+            },
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Verifies that the artifacts directory exists.
+fn checks_before_query(cmd: &QueryCmd) -> bool {
+    [BeforeCheck {
+        error_msg: format!("Artifacts directory '{}' does not exist.", cmd.artifacts),
+        result: Path::new(&cmd.artifacts).is_dir(),
+    }]
+    .iter()
+    .map(|check| {
+        if !check.result {
+            error!("{}", check.error_msg);
+            return false;
+        }
+        true
+    })
+    .all(|check| check)
+}
+
+/// Runs the query command: loads the blocks of the reverse run directory `artifacts`
+/// (`cfg.json`, or `disassembly.json`) and prints the ones matching all the filters.
+///
+/// # Arguments
+///
+/// * `cmd` - A reference to the `QueryCmd` struct, containing:
+///   * `artifacts` - Run directory of `reverse`, e.g. `./out/reverse/latest`.
+///   * `calls` - Regex of the syscalls and functions the blocks call.
+///   * `strings` - Regex of the immediate strings the blocks load.
+///   * `min_instructions` - Minimum number of instructions of the blocks.
+///   * `function` - Regex of the name of the functions holding the blocks.
+///   * `functions` - Whether to list the functions holding the blocks instead of the blocks.
+///
+/// # Returns
+///
+/// The number of lines printed.
+pub fn run(cmd: &QueryCmd) -> Result<usize> {
+    let query = Query::new(
+        cmd.calls.as_deref(),
+        cmd.strings.as_deref(),
+        cmd.min_instructions,
+        cmd.function.as_deref(),
+    )?;
+    if !checks_before_query(cmd) {
+        return Err(anyhow::anyhow!(
+            "Query prerequisites failed. Check that the artifacts directory exists."
+        ));
+    }
+
+    let artifacts = Artifacts::load(Path::new(&cmd.artifacts))?;
+    debug!(
+        "{} blocks read from {}",
+        artifacts.blocks.len(),
+        artifacts.source.display()
+    );
+    let output = render_matches(&query.run(&artifacts), cmd.functions);
+    print!("{}", output);
+    Ok(output.lines().count())
+}
@@ -1,17 +1,38 @@
 use std::path::Path;
 use crate::Commands;
+use crate::helpers::bundle::Bundle;
+use crate::helpers::run_layout::RunDir;
 use crate::helpers::BeforeCheck;
 use log::{debug, error};
 
 pub struct RecapCmd {
     pub anchor_path: Option<String>,
+    /// Zip archive or npm tarball holding the Anchor workspace, recapped instead of `anchor_path`.
+    pub bundle: Option<String>,
+    /// Directory where the run directories are created, `recap-solazy.md` is written to the
+    /// launch directory if `None`.
+    pub out_dir: Option<String>,
+    /// Name of the run directory in `<out_dir>/recap`.
+    pub label: Option<String>,
+    /// Whether the ownership graphs of the instructions are written next to the recap.
+    pub graphs: bool,
 }
 
 impl RecapCmd {
     pub fn new_from_clap(cmd: &Commands) -> Self {
         match cmd {
-            Commands::Recap { anchor_path } => Self {
+            Commands::Recap {
+                anchor_path,
+                bundle,
+                out_dir,
+                label,
+                graphs,
+            } => Self {
                 anchor_path: anchor_path.clone(),
+                bundle: bundle.clone(),
+                out_dir: out_dir.clone(),
+                label: label.clone(),
+                graphs: *graphs,
             },
             _ => unreachable!(),
         }
@@ -59,10 +80,51 @@ pub(crate) fn checks_before_recap(anchor_path: &Option<String>) -> bool {
 pub fn run(cmd: &RecapCmd) -> anyhow::Result<()> {
     debug!("Starting recap process for {:?}", cmd.anchor_path);
 
+    // resolved before the precheck changes the working directory
+    let cwd = std::env::current_dir()?;
+    let out_dir = cmd.out_dir.as_ref().map(|out_dir| cwd.join(out_dir));
+
+    // removed once the recap is written
+    let bundle = cmd
+        .bundle
+        .as_deref()
+        .map(|bundle| Bundle::extract(&cwd.join(bundle)))
+        .transpose()?;
+    let anchor_path = match &bundle {
+        Some(bundle) => Some(bundle.anchor_root()?.to_string_lossy().into_owned()),
+        None => cmd.anchor_path.clone(),
+    };
+
     // quick precheck just to see if the optionnally supplied path is ok
-    if !checks_before_recap(&cmd.anchor_path) {
+    if !checks_before_recap(&anchor_path) {
         return Err(anyhow::anyhow!("Can't launch recap, see errors above."));
     }
-    
-    crate::recap::recap_project(cmd.anchor_path.clone())
+
+    let result = recap_to(cmd, anchor_path, out_dir.as_deref(), &cwd);
+    // the working directory is left before the extracted bundle is removed
+    if bundle.is_some() {
+        std::env::set_current_dir(&cwd)?;
+    }
+    result
+}
+
+/// Writes the recap of the project at `anchor_path` to `./recap-solazy.md`, or to a run
+/// directory of `out_dir`.
+fn recap_to(
+    cmd: &RecapCmd,
+    anchor_path: Option<String>,
+    out_dir: Option<&Path>,
+    cwd: &Path,
+) -> anyhow::Result<()> {
+    let Some(out_dir) = out_dir else {
+        return crate::recap::recap_project(anchor_path, None, cmd.graphs);
+    };
+    let mut run_dir = RunDir::create(out_dir, "recap", cmd.label.as_deref())?;
+    match &cmd.bundle {
+        Some(bundle) => run_dir.add_input(&cwd.join(bundle).to_string_lossy()),
+        None => run_dir.add_input(&std::env::current_dir()?.to_string_lossy()),
+    }
+    run_dir.set_option("graphs", cmd.graphs);
+    let result = crate::recap::recap_project(anchor_path, Some(&run_dir.dir), cmd.graphs);
+    run_dir.finish(result)
 }
\ No newline at end of file
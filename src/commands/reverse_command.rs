@@ -1,7 +1,170 @@
+use crate::helpers::bundle::Bundle;
+use crate::helpers::run_layout::RunDir;
 use crate::helpers::BeforeCheck;
-use crate::reverse::{analyze_program, ReverseOutputMode};
+use crate::reverse::annotations::ANNOTATIONS_FILENAME;
+use crate::reverse::disass::DisassFormat;
+use crate::reverse::function_filter::{FunctionFilter, PcRange};
+use crate::reverse::passes::{PassRegistry, PassToggles};
+use crate::reverse::raw_input::{parse_offset, parse_sbpf_version, RawFormat, RawInput};
+use crate::reverse::rusteq::RustEqMode;
+use crate::reverse::{analyze_program, ReverseOptions, ReverseOutputMode};
+use crate::Commands;
 use anyhow::Result;
 use log::{debug, error, info};
+use std::path::Path;
+
+pub struct ReverseCmd {
+    pub mode: String,
+    pub out_dir: String,
+    /// Name of the run directory in `<out_dir>/reverse`.
+    pub label: Option<String>,
+    /// The program to analyze, or with `bundle` the name of the program of the bundle.
+    pub bytecodes_file: Option<String>,
+    /// Zip archive or npm tarball holding the program, its IDL and metadata.
+    pub bundle: Option<String>,
+    pub labeling: bool,
+    pub no_labeling: bool,
+    pub reduced: bool,
+    pub only_entrypoint: bool,
+    /// Root of the reduced CFG (name or address) instead of the entrypoint.
+    pub entry: Option<String>,
+    pub idl: Option<String>,
+    /// Wordlist of account names hashed to name the account discriminators.
+    pub account_names: Option<String>,
+    /// Source tree whose struct names are hashed to name the account discriminators.
+    pub account_sources: Option<String>,
+    pub cfg_chunk: Option<usize>,
+    pub stats: bool,
+    pub signatures: Option<String>,
+    pub export_signatures: bool,
+    pub raw: bool,
+    pub raw_format: String,
+    pub text_offset: String,
+    pub sbpf_version: String,
+    pub no_rusteq: bool,
+    pub rusteq_only: bool,
+    pub disass_format: String,
+    pub show_bytes: bool,
+    pub stable_ids: bool,
+    pub only_function: Vec<String>,
+    pub skip_libs: bool,
+    pub range: Option<String>,
+    pub pass: Vec<String>,
+    pub no_pass: Vec<String>,
+    pub annotations: Option<String>,
+    pub export_names: bool,
+    pub fuzz_corpus: Option<String>,
+    pub tui: bool,
+}
+
+impl ReverseCmd {
+    pub fn new_from_clap(cmd: &Commands) -> Self {
+        match cmd {
+            Commands::Reverse {
+                mode,
+                out_dir,
+                label,
+                bytecodes_file,
+                bundle,
+                labeling,
+                no_labeling,
+                reduced,
+                only_entrypoint,
+                entry,
+                idl,
+                account_names,
+                account_sources,
+                cfg_chunk,
+                stats,
+                signatures,
+                export_signatures,
+                raw,
+                raw_format,
+                text_offset,
+                sbpf_version,
+                no_rusteq,
+                rusteq_only,
+                disass_format,
+                show_bytes,
+                stable_ids,
+                only_function,
+                skip_libs,
+                range,
+                pass,
+                no_pass,
+                annotations,
+                export_names,
+                fuzz_corpus,
+                tui,
+            } => Self {
+                mode: mode.clone(),
+                out_dir: out_dir.clone(),
+                label: label.clone(),
+                bytecodes_file: bytecodes_file.clone(),
+                bundle: bundle.clone(),
+                labeling: *labeling,
+                no_labeling: *no_labeling,
+                reduced: *reduced,
+                only_entrypoint: *only_entrypoint,
+                entry: entry.clone(),
+                idl: idl.clone(),
+                account_names: account_names.clone(),
+                account_sources: account_sources.clone(),
+                cfg_chunk: *cfg_chunk,
+                stats: *stats,
+                signatures: signatures.clone(),
+                export_signatures: *export_signatures,
+                raw: *raw,
+                raw_format: raw_format.clone(),
+                text_offset: text_offset.clone(),
+                sbpf_version: sbpf_version.clone(),
+                no_rusteq: *no_rusteq,
+                rusteq_only: *rusteq_only,
+                disass_format: disass_format.clone(),
+                show_bytes: *show_bytes,
+                stable_ids: *stable_ids,
+                only_function: only_function.clone(),
+                skip_libs: *skip_libs,
+                range: range.clone(),
+                pass: pass.clone(),
+                no_pass: no_pass.clone(),
+                annotations: annotations.clone(),
+                export_names: *export_names,
+                fuzz_corpus: fuzz_corpus.clone(),
+                tui: *tui,
+            },
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Turns the `--labeling` / `--no-labeling` flags into a labeling override, `None` letting the
+/// analysis detect it from the ELF symbol table.
+fn labeling_override(labeling: bool, no_labeling: bool) -> Option<bool> {
+    match (labeling, no_labeling) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    }
+}
+
+/// Path of the annotations file: `--annotations`, or the `annotations.json` next to the
+/// program `bytecodes_file` if there is one.
+fn annotations_path(cmd: &ReverseCmd, bytecodes_file: &str) -> Result<Option<String>> {
+    match &cmd.annotations {
+        Some(path) if !Path::new(path).is_file() => Err(anyhow::anyhow!(
+            "Annotations file '{}' does not exist.",
+            path
+        )),
+        Some(path) => Ok(Some(path.clone())),
+        None => {
+            let sidecar = Path::new(bytecodes_file).with_file_name(ANNOTATIONS_FILENAME);
+            Ok(sidecar
+                .is_file()
+                .then(|| sidecar.to_string_lossy().into_owned()))
+        }
+    }
+}
 
 /// Verifies that the required files and directories exist before running reverse analysis.
 ///
@@ -59,18 +222,51 @@ fn checks_before_reverse(bytecodes_file: &String, out_dir: &String) -> bool {
 /// Dispatches the reverse engineering workflow based on a user-specified mode.
 ///
 /// Converts a string-based mode (`"disass"`, `"cfg"`, `"both"`)
-/// into a `ReverseOutputMode` enum and calls `analyze_program` accordingly.
+/// into a `ReverseOutputMode` enum and calls `analyze_program` accordingly. The outputs are
+/// written to the run directory `<out_dir>/reverse/<label or timestamp>`, with its `run.json`.
 ///
 /// # Arguments
 ///
-/// * `mode` - A string indicating which analysis mode to use.
-/// * `out_dir` - The path to the directory where output files will be written.
-/// * `bytecodes_file` - Path to the compiled eBPF bytecode (.so file).
-/// * `labeling` - Whether to enable symbol and section labeling in the analysis.
-/// * `reduced` - If enabled, limits CFG generation to functions defined after the program entrypoint,
-///   which helps reduce noise from unrelated or prelinked functions in the bytecode.
-/// * `only_entrypoint` - If true, generates a minimal CFG containing only the entrypoint function (`cluster_{entry}`),
-///   allowing manual expansion afterward using tools like the `dotting` module.
+/// * `cmd` - A reference to the `ReverseCmd` struct, containing command-line arguments:
+///   * `mode` - A string indicating which analysis mode to use.
+///   * `out_dir` - The path to the directory where the run directories are created.
+///   * `label` - Optional name of the run directory (the start time by default).
+///   * `bytecodes_file` - Path to the compiled eBPF bytecode (.so file), or with `bundle` the
+///     name of the program to analyze when the bundle holds several.
+///   * `bundle` - Optional zip archive or npm tarball, extracted to a temporary directory, whose
+///     program is analyzed, with its IDL unless `idl` is given.
+///   * `labeling` / `no_labeling` - Force symbol and section labeling on or off; by default it is
+///     enabled when the ELF has a symbol table.
+///   * `reduced` - If enabled, limits CFG generation to the functions reachable from the program entrypoint,
+///     which helps reduce noise from unrelated or prelinked functions in the bytecode.
+///   * `only_entrypoint` - If true, generates a minimal CFG containing only the entrypoint function (`cluster_{entry}`),
+///     allowing manual expansion afterward using tools like the `dotting` module.
+///   * `entry` - Optional function or block (name or address) used as the root of the reduced CFG
+///     instead of the entrypoint, the CFG holding the functions reachable from it.
+///   * `idl` - Optional path to an Anchor IDL used to name the recognized dispatch targets, error codes
+///     and account discriminators.
+///   * `account_names`, `account_sources` - Optional wordlist and source tree whose names are hashed
+///     to name the account discriminators of the program.
+///   * `cfg_chunk` - Optional number of functions per `.dot` file to split the CFG into.
+///   * `stats` - Whether to export the instruction statistics (JSON and CSV).
+///   * `signatures` - Optional `.sig` file or directory used to identify library functions.
+///   * `export_signatures` - Whether to export the signatures of the named functions.
+///   * `raw`, `raw_format`, `text_offset`, `sbpf_version` - Read the input as raw instructions
+///     (memory dump, hex dump or base64) instead of an ELF.
+///   * `no_rusteq`, `rusteq_only` - Omit the Rust equivalent column of the disassembly, or only
+///     write the Rust equivalent.
+///   * `disass_format` - Format of the disassembly (`solazy`, `objdump` or `json`).
+///   * `show_bytes` - Whether the bytes of the instructions are written in the disassembly.
+///   * `stable_ids` - Name the CFG nodes after the hash of their function, with `id_map.json`.
+///   * `only_function`, `skip_libs`, `range` - Restrict the disassembly, the CFG and the
+///     function summary to some functions.
+///   * `pass`, `no_pass` - Analysis passes run on top of the default ones, or skipped.
+///   * `annotations` - Optional annotations file, `annotations.json` next to the program if it
+///     exists.
+///   * `export_names` - Whether to export the names for Ghidra and Binary Ninja.
+///   * `fuzz_corpus` - Optional corpus directory of a fuzz workspace where the fuzzing seeds
+///     are copied.
+///   * `tui` - Whether to browse the disassembly in the terminal once the outputs are written.
 ///
 /// # Returns
 ///
@@ -81,17 +277,31 @@ fn checks_before_reverse(bytecodes_file: &String, out_dir: &String) -> bool {
 ///
 /// Returns an error if the provided `mode` string does not match any known `ReverseOutputMode`,
 /// or if the reverse analysis fails to initialize properly.
-pub fn run(
-    mode: String,
-    out_dir: String,
-    bytecodes_file: String,
-    labeling: bool,
-    reduced: bool,
-    only_entrypoint: bool,
-) -> Result<()> {
+pub fn run(cmd: &ReverseCmd) -> Result<()> {
+    // removed once the analysis is over
+    let bundle = cmd
+        .bundle
+        .as_deref()
+        .map(|bundle| Bundle::extract(Path::new(bundle)))
+        .transpose()?;
+    let (bytecodes_file, idl) = match &bundle {
+        Some(bundle) => {
+            let program = bundle.program(cmd.bytecodes_file.as_deref())?;
+            let idl = cmd.idl.clone().or_else(|| {
+                bundle
+                    .idl_of(program)
+                    .map(|idl| idl.to_string_lossy().into_owned())
+            });
+            (program.to_string_lossy().into_owned(), idl)
+        }
+        None => (
+            cmd.bytecodes_file.clone().unwrap_or_default(),
+            cmd.idl.clone(),
+        ),
+    };
     debug!("Starting reverse process for {}", bytecodes_file);
 
-    if !checks_before_reverse(&bytecodes_file, &out_dir) {
+    if !checks_before_reverse(&bytecodes_file, &cmd.out_dir) {
         error!(
             "Can't launch reverse analysis on '{}', see errors above.",
             bytecodes_file
@@ -102,22 +312,122 @@ pub fn run(
         ));
     }
 
-    let output_mode = match mode.as_str() {
-        "disass" => ReverseOutputMode::Disassembly(out_dir),
-        "cfg" => ReverseOutputMode::ControlFlowGraph(out_dir),
-        "both" => ReverseOutputMode::DisassemblyAndCFG(out_dir),
+    let output_mode: fn(String) -> ReverseOutputMode = match cmd.mode.as_str() {
+        "disass" => ReverseOutputMode::Disassembly,
+        "cfg" => ReverseOutputMode::ControlFlowGraph,
+        "both" => ReverseOutputMode::DisassemblyAndCFG,
         other => {
             return Err(anyhow::anyhow!("Unknown reverse mode: {}", other));
         }
     };
 
-    analyze_program(
-        output_mode,
+    let raw = if cmd.raw {
+        Some(RawInput {
+            format: RawFormat::parse(&cmd.raw_format)?,
+            text_offset: parse_offset(&cmd.text_offset)?,
+            sbpf_version: parse_sbpf_version(&cmd.sbpf_version)?,
+        })
+    } else {
+        None
+    };
+
+    let disass_format = DisassFormat::parse(&cmd.disass_format)?;
+    let functions = FunctionFilter {
+        only_functions: cmd.only_function.clone(),
+        skip_libs: cmd.skip_libs,
+        range: cmd.range.as_deref().map(PcRange::parse).transpose()?,
+    };
+    let passes = PassToggles {
+        enabled: cmd.pass.clone(),
+        disabled: cmd.no_pass.clone(),
+    };
+    // unknown pass names are reported before the run directory is created
+    PassRegistry::builtin().select(&passes)?;
+    let annotations = annotations_path(cmd, &bytecodes_file)?;
+
+    let mut run_dir = RunDir::create(Path::new(&cmd.out_dir), "reverse", cmd.label.as_deref())?;
+    match (&cmd.bundle, &bundle) {
+        (Some(path), Some(bundle)) => {
+            run_dir.add_input(path);
+            let program = Path::new(&bytecodes_file);
+            run_dir.set_option(
+                "bundle_program",
+                program.strip_prefix(&bundle.dir).unwrap_or(program),
+            );
+        }
+        _ => run_dir.add_input(&bytecodes_file),
+    }
+    // the files of the bundle are recorded with it
+    for input in idl
+        .iter()
+        .chain(&cmd.signatures)
+        .chain(&cmd.account_names)
+        .chain(&cmd.account_sources)
+        .chain(&annotations)
+        .filter(|input| {
+            !bundle
+                .as_ref()
+                .is_some_and(|bundle| bundle.contains(Path::new(input)))
+        })
+    {
+        run_dir.add_input(input);
+    }
+    run_dir.set_option("mode", &cmd.mode);
+    run_dir.set_option("labeling", cmd.labeling);
+    run_dir.set_option("no_labeling", cmd.no_labeling);
+    run_dir.set_option("reduced", cmd.reduced);
+    run_dir.set_option("only_entrypoint", cmd.only_entrypoint);
+    run_dir.set_option("entry", &cmd.entry);
+    run_dir.set_option("cfg_chunk", cmd.cfg_chunk);
+    run_dir.set_option("stats", cmd.stats);
+    run_dir.set_option("export_signatures", cmd.export_signatures);
+    run_dir.set_option("no_rusteq", cmd.no_rusteq);
+    run_dir.set_option("rusteq_only", cmd.rusteq_only);
+    run_dir.set_option("disass_format", &cmd.disass_format);
+    run_dir.set_option("show_bytes", cmd.show_bytes);
+    run_dir.set_option("stable_ids", cmd.stable_ids);
+    run_dir.set_option("only_function", &cmd.only_function);
+    run_dir.set_option("skip_libs", cmd.skip_libs);
+    run_dir.set_option("range", &cmd.range);
+    run_dir.set_option("pass", &cmd.pass);
+    run_dir.set_option("no_pass", &cmd.no_pass);
+    run_dir.set_option("export_names", cmd.export_names);
+    run_dir.set_option("fuzz_corpus", &cmd.fuzz_corpus);
+    if cmd.raw {
+        run_dir.set_option("raw_format", &cmd.raw_format);
+        run_dir.set_option("text_offset", &cmd.text_offset);
+        run_dir.set_option("sbpf_version", &cmd.sbpf_version);
+    }
+
+    let result = analyze_program(
+        output_mode(run_dir.dir_string()),
         bytecodes_file,
-        labeling,
-        reduced,
-        only_entrypoint,
-    )
+        &ReverseOptions {
+            labeling: labeling_override(cmd.labeling, cmd.no_labeling),
+            reduced: cmd.reduced,
+            only_entrypoint: cmd.only_entrypoint,
+            entry: cmd.entry.clone(),
+            idl,
+            account_names: cmd.account_names.clone(),
+            account_sources: cmd.account_sources.clone(),
+            cfg_chunk: cmd.cfg_chunk,
+            stats: cmd.stats,
+            signatures: cmd.signatures.clone(),
+            export_signatures: cmd.export_signatures,
+            raw,
+            rusteq: RustEqMode::from_flags(cmd.no_rusteq, cmd.rusteq_only),
+            disass_format,
+            show_bytes: cmd.show_bytes,
+            stable_ids: cmd.stable_ids,
+            functions,
+            passes,
+            annotations,
+            export_names: cmd.export_names,
+            fuzz_corpus: cmd.fuzz_corpus.clone(),
+            tui: cmd.tui,
+        },
+    );
+    run_dir.finish(result)
 }
 
 #[cfg(test)]
@@ -1,15 +1,65 @@
+use crate::deps::AdvisoryDb;
+use crate::engines::rules_git::{fetch_rules, RulesGitSource, RULES_CACHE_DIR};
+use crate::engines::starlark_engine::{RuleFilter, RuleLimits, RuleTrust};
+use crate::fixer;
+use crate::helpers::anchor_toml::{anchor_library_dirs, anchor_program_dirs, crate_names};
+use crate::helpers::events;
+use crate::helpers::timing::{self, TimingReport};
 use crate::helpers::{get_project_type, BeforeCheck, ProjectType};
+use crate::parsers::cfg::{filter_syn_ast_map, CfgSet};
 use crate::parsers::syn_ast;
-use crate::state::sast_state::SastState;
+use crate::parsers::syn_ast::{ColumnEncoding, ParseOptions};
+use crate::printers::relational_printer::RelationalPrinter;
+use crate::reverse::correlation::{correlate_findings, CompiledStatus, ProgramFacts};
+use crate::state::baseline::BASELINE_FILE;
+use crate::state::build_state::BuildState;
+use crate::state::sast_state::{SastState, SynAstMap};
+use crate::state::taxonomy::Taxonomy;
 use crate::{helpers, Commands};
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
 
 pub struct SastCmd {
     pub target_dir: String,
     pub rules_dir: Option<String>,
+    pub rules_git: Option<String>,
     pub syn_scan_only: bool,
     pub use_internal_rules: bool,
     pub recursive: bool,
+    pub column_encoding: ColumnEncoding,
+    pub rule_filter: RuleFilter,
+    pub rule_trust: RuleTrust,
+    pub rule_limits: RuleLimits,
+    /// Compiled programs the findings are correlated with.
+    pub bytecodes: Vec<String>,
+    /// `build-manifest.json` whose programs of the project are correlated with the findings.
+    pub build_manifest: Option<String>,
+    /// Directory receiving the relational tables of the scan.
+    pub export_db: Option<String>,
+    /// Taxonomy file in which the findings are rated, instead of severity / certainty.
+    pub taxonomy: Option<String>,
+    /// Whether the engine errors (rules failing on a file) make the scan fail.
+    pub strict: bool,
+    /// Whether the findings are browsed in the terminal interface instead of printed.
+    pub tui: bool,
+    /// Baseline of the triaged findings, `<target_dir>/.solazy-baseline.json` if `None`.
+    pub baseline: Option<String>,
+    /// Advisories updating the shipped ones, for the audit of the `Cargo.lock`.
+    pub advisories: Option<String>,
+    /// Configuration of the deployed program, the code it disables being removed from the
+    /// syntax trees. `None` scans the code of every configuration.
+    pub cfg: Option<CfgSet>,
+    /// Whether the fixes suggested by the rules are applied to the sources.
+    pub fix: bool,
+    /// Whether the fixes are printed as a unified diff instead of applied.
+    pub dry_run: bool,
+    /// File receiving the time spent in each stage of the scan.
+    pub timing_report: Option<String>,
+    /// Size in bytes above which a source file is skipped instead of scanned.
+    pub max_file_size: Option<u64>,
+    /// Whether the JSON syntax trees are spilled to disk instead of kept in memory.
+    pub spill_ast: bool,
 }
 
 impl SastCmd {
@@ -19,26 +69,117 @@ impl SastCmd {
             Commands::Sast {
                 target_dir,
                 rules_dir,
+                rules_git,
                 syn_scan_only,
                 use_internal_rules,
                 recursive,
+                column_encoding,
+                enable_rules,
+                disable_rules,
+                trusted_rules_only,
+                trust_rules,
+                rule_max_steps,
+                rule_timeout,
+                rule_max_heap_mb,
+                bytecodes,
+                build_manifest,
+                export_db,
+                taxonomy,
+                strict,
+                tui,
+                baseline,
+                advisories,
+                features,
+                cfgs,
+                fix,
+                dry_run,
+                timing_report,
+                max_file_size,
+                spill_ast,
             } => {
 
-                if !use_internal_rules && rules_dir.is_none() {
+                if !use_internal_rules && rules_dir.is_none() && rules_git.is_none() {
                     error!("Rules directory must be specified when only using external rules.");
                     std::process::exit(1);
                 }
+                let cfg = CfgSet::from_options(features, cfgs).unwrap_or_else(|e| {
+                    error!("{}", e);
+                    std::process::exit(1);
+                });
+                let rule_limits =
+                    RuleLimits::from_options(*rule_max_steps, *rule_timeout, *rule_max_heap_mb)
+                        .unwrap_or_else(|e| {
+                            error!("{}", e);
+                            std::process::exit(1);
+                        });
                 Self {
                     target_dir: target_dir.clone(),
                     rules_dir: rules_dir.clone(),
+                    rules_git: rules_git.clone(),
                     syn_scan_only: *syn_scan_only,
                     use_internal_rules: *use_internal_rules,
                     recursive: *recursive,
+                    column_encoding: column_encoding.parse().unwrap_or_default(),
+                    rule_filter: RuleFilter {
+                        enable: enable_rules.clone(),
+                        disable: disable_rules.clone(),
+                    },
+                    rule_trust: RuleTrust {
+                        trusted_only: *trusted_rules_only,
+                        allowed: trust_rules.clone(),
+                    },
+                    rule_limits,
+                    bytecodes: bytecodes.clone(),
+                    build_manifest: build_manifest.clone(),
+                    export_db: export_db.clone(),
+                    taxonomy: taxonomy.clone(),
+                    strict: *strict,
+                    tui: *tui,
+                    baseline: baseline.clone(),
+                    advisories: advisories.clone(),
+                    cfg,
+                    fix: *fix,
+                    dry_run: *dry_run,
+                    timing_report: timing_report.clone(),
+                    max_file_size: *max_file_size,
+                    spill_ast: *spill_ast,
                 }
             },
             _ => unreachable!(),
         }
     }
+
+    /// Returns how the source files are parsed, with the memory guardrails of the command.
+    fn parse_options(&self) -> ParseOptions {
+        ParseOptions {
+            column_encoding: self.column_encoding,
+            max_file_size: self.max_file_size,
+            spill_dir: self.spill_ast.then(spill_dir),
+        }
+    }
+}
+
+/// Returns the directory to which the JSON syntax trees of the process are spilled with
+/// `--spill-ast`.
+fn spill_dir() -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("sol-azy-ast-{}", std::process::id()))
+}
+
+/// Removes the spilled syntax trees when dropped, once the run is over (or failed).
+struct SpilledTrees(Option<std::path::PathBuf>);
+
+impl Drop for SpilledTrees {
+    fn drop(&mut self) {
+        if let Some(dir) = self.0.take().filter(|dir| dir.exists()) {
+            if let Err(e) = std::fs::remove_dir_all(&dir) {
+                warn!(
+                    "Failed to remove the spilled syntax trees {}: {}",
+                    dir.display(),
+                    e
+                );
+            }
+        }
+    }
 }
 
 /// Runs a series of checks before launching SAST analysis.
@@ -53,7 +194,7 @@ impl SastCmd {
 ///
 /// Returns `true` if all checks pass, `false` otherwise.
 fn checks_before_sast(cmd: &SastCmd) -> bool {
-    [
+    let mut checks = vec![
         BeforeCheck {
             error_msg: format!("Target directory {} doesn't exist", cmd.target_dir),
             result: std::path::Path::new(&cmd.target_dir).exists(),
@@ -62,16 +203,33 @@ fn checks_before_sast(cmd: &SastCmd) -> bool {
             error_msg: format!("Rules directory {:?} doesn't exist", cmd.rules_dir),
             result: std::path::Path::new(&cmd.rules_dir.clone().unwrap_or(std::env::temp_dir().to_string_lossy().to_string())).exists(),
         },
-    ]
-    .iter()
-    .map(|check| {
-        if !check.result {
-            error!("{}", check.error_msg);
-            return false;
-        }
-        return true;
-    })
-    .all(|check| check)
+    ];
+    checks.extend(cmd.bytecodes.iter().map(|bytecode| BeforeCheck {
+        error_msg: format!("Bytecode file {} doesn't exist", bytecode),
+        result: std::path::Path::new(bytecode).is_file(),
+    }));
+    checks.extend(cmd.build_manifest.iter().map(|manifest| BeforeCheck {
+        error_msg: format!("Build manifest {} doesn't exist", manifest),
+        result: std::path::Path::new(manifest).is_file(),
+    }));
+    checks.extend(cmd.taxonomy.iter().map(|taxonomy| BeforeCheck {
+        error_msg: format!("Taxonomy file {} doesn't exist", taxonomy),
+        result: std::path::Path::new(taxonomy).is_file(),
+    }));
+    checks.extend(cmd.advisories.iter().map(|advisories| BeforeCheck {
+        error_msg: format!("Advisories file {} doesn't exist", advisories),
+        result: std::path::Path::new(advisories).is_file(),
+    }));
+    checks
+        .iter()
+        .map(|check| {
+            if !check.result {
+                error!("{}", check.error_msg);
+                return false;
+            }
+            return true;
+        })
+        .all(|check| check)
 }
 
 /// Launches the static analysis (SAST) workflow.
@@ -86,10 +244,138 @@ fn checks_before_sast(cmd: &SastCmd) -> bool {
 /// # Returns
 ///
 /// A `Result` containing a vector of `SastState` objects on success, or an error if any
-/// checks fail or the project type is unsupported.
+/// checks fail, the project type is unsupported, or, with `--strict`, a rule failed on a file.
 pub fn run(cmd: &SastCmd) -> anyhow::Result<Vec<SastState>> {
+    if cmd.timing_report.is_some() {
+        timing::enable();
+    }
+    let _spilled_trees = SpilledTrees(cmd.spill_ast.then(spill_dir));
+    let span = timing::span("sast");
+    let states = run_with_type(cmd, None)?;
+    if let Some(export_dir) = &cmd.export_db {
+        timing::time("export", || {
+            RelationalPrinter::export(&states, std::path::Path::new(export_dir))
+        })?;
+    }
+    if cmd.fix {
+        timing::time("fix", || fix_sources(&states, cmd.dry_run))?;
+    }
+    drop(span);
+    if let Some(report) = &cmd.timing_report {
+        TimingReport::collect().write(std::path::Path::new(report))?;
+        info!("Timing report written to {}", report);
+    }
+    if cmd.tui {
+        let baseline = match &cmd.baseline {
+            Some(baseline) => std::path::PathBuf::from(baseline),
+            None => std::path::Path::new(&cmd.target_dir).join(BASELINE_FILE),
+        };
+        crate::tui::sast::run(&states, &baseline)?;
+    }
+    let engine_errors: usize = states.iter().map(|state| state.engine_errors().len()).sum();
+    if cmd.strict && engine_errors > 0 {
+        return Err(anyhow::anyhow!(
+            "{} rule evaluation(s) failed on {}, see the engine errors above (--strict)",
+            engine_errors,
+            cmd.target_dir
+        ));
+    }
+    Ok(states)
+}
+
+/// Applies the fixes suggested by the rules to the sources, or prints them as a unified diff
+/// with `dry_run`.
+///
+/// When the events are written to stdout, the diff goes to stderr and the summary to the logs.
+fn fix_sources(states: &[SastState], dry_run: bool) -> anyhow::Result<()> {
+    let fixes = fixer::collect_fixes(states)?;
+    let applied: usize = fixes.iter().map(|file| file.applied.len()).sum();
+    let skipped: usize = fixes.iter().map(|file| file.skipped).sum();
+    let files = fixes.iter().filter(|file| !file.edits.is_empty()).count();
+    let summary = if dry_run {
+        for file in fixes.iter().filter(|file| !file.edits.is_empty()) {
+            if events::human_output() {
+                print!("{}", file.diff());
+            } else {
+                eprint!("{}", file.diff());
+            }
+        }
+        format!(
+            "{} fix(es) to apply to {} file(s), {} skipped (--dry-run)",
+            applied, files, skipped
+        )
+    } else {
+        fixer::apply_fixes(&fixes)?;
+        format!(
+            "{} fix(es) applied to {} file(s), {} skipped",
+            applied, files, skipped
+        )
+    };
+    if events::human_output() {
+        println!("{}", summary);
+    } else {
+        info!("{}", summary);
+    }
+    Ok(())
+}
+
+/// Launches the static analysis (SAST) workflow on a project whose type may already be known.
+///
+/// # Arguments
+///
+/// * `cmd` - A reference to the `SastCmd` struct, containing command-line arguments.
+/// * `project_type` - The type of the project, detected from its files if `None`. Ignored
+///   when scanning recursively.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of `SastState` objects on success, or an error if any
+/// checks fail or the project type is unsupported.
+pub fn run_with_type(
+    cmd: &SastCmd,
+    project_type: Option<ProjectType>,
+) -> anyhow::Result<Vec<SastState>> {
     debug!("Starting SAST process for {}", cmd.target_dir);
 
+    if let Some(spec) = &cmd.rules_git {
+        // the fetched repository is then loaded like a rules directory
+        let rules_dir = timing::time("fetch", || {
+            fetch_rules(
+                &RulesGitSource::parse(spec)?,
+                std::path::Path::new(RULES_CACHE_DIR),
+            )
+        })?;
+        return run_with_type(
+            &SastCmd {
+                target_dir: cmd.target_dir.clone(),
+                rules_dir: Some(rules_dir.to_string_lossy().to_string()),
+                rules_git: None,
+                syn_scan_only: cmd.syn_scan_only,
+                use_internal_rules: cmd.use_internal_rules,
+                recursive: cmd.recursive,
+                column_encoding: cmd.column_encoding,
+                rule_filter: cmd.rule_filter.clone(),
+                rule_trust: cmd.rule_trust.clone(),
+                rule_limits: cmd.rule_limits,
+                bytecodes: cmd.bytecodes.clone(),
+                build_manifest: cmd.build_manifest.clone(),
+                export_db: None,
+                taxonomy: cmd.taxonomy.clone(),
+                strict: cmd.strict,
+                tui: cmd.tui,
+                baseline: cmd.baseline.clone(),
+                advisories: cmd.advisories.clone(),
+                cfg: cmd.cfg.clone(),
+                fix: cmd.fix,
+                dry_run: cmd.dry_run,
+                timing_report: None,
+                max_file_size: cmd.max_file_size,
+                spill_ast: cmd.spill_ast,
+            },
+            project_type,
+        );
+    }
+
     if !checks_before_sast(cmd) {
         error!(
             "Can't launch SAST on directory {}, see errors above.",
@@ -104,9 +390,11 @@ pub fn run(cmd: &SastCmd) -> anyhow::Result<Vec<SastState>> {
     if cmd.recursive {
         scan_directory_recursively(cmd)
     } else {
-        match get_project_type(&cmd.target_dir) {
+        match project_type.unwrap_or_else(|| get_project_type(&cmd.target_dir)) {
             ProjectType::Anchor => Ok(vec![sast_anchor_project(cmd)?]),
-            ProjectType::Sbf => Ok(vec![sast_sbf_project(cmd)?]),
+            project_type @ (ProjectType::Sbf | ProjectType::Pinocchio | ProjectType::Steel) => {
+                Ok(vec![sast_sbf_project(cmd, project_type)?])
+            }
             ProjectType::Unknown => Err(anyhow::anyhow!("Unknown project type.")),
         }
     }
@@ -114,19 +402,85 @@ pub fn run(cmd: &SastCmd) -> anyhow::Result<Vec<SastState>> {
 
 /// Recursively scans a directory for projects and runs SAST analysis on them.
 ///
-/// It skips common directories like `node_modules`, `target`, and hidden directories.
+/// Nested projects (e.g. a workspace root and its members) may share source files: each file
+/// is attributed to the nearest enclosing project and parsed once, so that its findings are
+/// only reported once.
 ///
 /// # Arguments
 ///
-/// * `cmd` - A reference to the `SastCmd` struct. The `target_dir` is updated for each recursive call.
+/// * `cmd` - A reference to the `SastCmd` struct, whose `target_dir` is the directory to scan.
 ///
 /// # Returns
 ///
 /// A `Result` containing a vector of `SastState` for all analyzed projects, or an I/O error.
 fn scan_directory_recursively(cmd: &SastCmd) -> anyhow::Result<Vec<SastState>> {
+    let walk_span = timing::span("walk");
+    let mut projects = Vec::new();
+    find_projects(std::path::Path::new(&cmd.target_dir), &mut projects)?;
+    let files = attribute_files(&projects);
+    let dependents = library_dependents(&projects);
+    drop(walk_span);
+
+    let parse_options = cmd.parse_options();
     let mut results = Vec::new();
-    let path = std::path::Path::new(&cmd.target_dir);
+    for ((project_dir, project_type), files) in projects.into_iter().zip(files) {
+        info!("Found {} project at {}", project_type, project_dir);
+        let mut syn_ast_map = SynAstMap::new();
+        for file in files {
+            if let Err(e) = syn_ast::parse_rust_file_with(&file, &mut syn_ast_map, &parse_options) {
+                error!("Error parsing Rust file {:?}: {}", file, e);
+            }
+        }
+        let project_cmd = SastCmd {
+            target_dir: project_dir,
+            rules_dir: cmd.rules_dir.clone(),
+            rules_git: None,
+            syn_scan_only: cmd.syn_scan_only,
+            use_internal_rules: cmd.use_internal_rules,
+            recursive: true,
+            column_encoding: cmd.column_encoding,
+            rule_filter: cmd.rule_filter.clone(),
+            rule_trust: cmd.rule_trust.clone(),
+            rule_limits: cmd.rule_limits,
+            bytecodes: cmd.bytecodes.clone(),
+            build_manifest: cmd.build_manifest.clone(),
+            export_db: None,
+            taxonomy: cmd.taxonomy.clone(),
+            strict: cmd.strict,
+            tui: cmd.tui,
+            baseline: cmd.baseline.clone(),
+            advisories: cmd.advisories.clone(),
+            cfg: cmd.cfg.clone(),
+            fix: cmd.fix,
+            dry_run: cmd.dry_run,
+            timing_report: None,
+            max_file_size: cmd.max_file_size,
+            spill_ast: cmd.spill_ast,
+        };
+        results.push(sast_parsed_project(
+            &project_cmd,
+            project_type,
+            syn_ast_map,
+            &cmd.target_dir,
+            &dependents,
+        )?);
+    }
 
+    Ok(results)
+}
+
+/// Recursively collects the projects found in a directory, parents first.
+///
+/// It skips common directories like `node_modules`, `target`, and hidden directories.
+///
+/// # Arguments
+///
+/// * `path` - The directory to search.
+/// * `projects` - The list to which the `(directory, type)` of the projects are added.
+fn find_projects(
+    path: &std::path::Path,
+    projects: &mut Vec<(String, ProjectType)>,
+) -> anyhow::Result<()> {
     // Skip certain directories commonly not needed for scanning
     let dir_name = path
         .file_name()
@@ -138,49 +492,139 @@ fn scan_directory_recursively(cmd: &SastCmd) -> anyhow::Result<Vec<SastState>> {
         || dir_name == "target"
         || dir_name == "build"
     {
-        return Ok(results);
+        return Ok(());
     }
 
-    // Check if the current directory is a project
-    let project_type = get_project_type(&cmd.target_dir);
+    let target_dir = path.to_string_lossy().to_string();
+    let project_type = get_project_type(&target_dir);
     if project_type != ProjectType::Unknown {
-        info!("Found {} project at {}", project_type, cmd.target_dir);
-        let result = match project_type {
-            ProjectType::Anchor => sast_anchor_project(cmd)?,
-            ProjectType::Sbf => sast_sbf_project(cmd)?,
-            ProjectType::Unknown => unreachable!(),
+        projects.push((target_dir, project_type));
+    }
+
+    if path.is_dir() {
+        let mut sub_paths = std::fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        sub_paths.sort();
+        for sub_path in sub_paths.into_iter().filter(|sub_path| sub_path.is_dir()) {
+            find_projects(&sub_path, projects)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the directories holding the sources of a project.
+///
+/// For Anchor projects, these are the `src/` directories of the programs declared in
+/// `Anchor.toml` and of the library crates they depend on by path, or the whole `programs/`
+/// directory if no program could be found.
+fn project_source_dirs(target_dir: &str, project_type: ProjectType) -> Vec<std::path::PathBuf> {
+    let target_dir = std::path::Path::new(target_dir);
+    match project_type {
+        ProjectType::Anchor => match anchor_program_dirs(target_dir) {
+            Some(program_dirs) => {
+                let libraries = anchor_library_dirs(target_dir, &program_dirs);
+                program_dirs
+                    .iter()
+                    .chain(libraries.keys())
+                    .map(|dir| dir.join("src"))
+                    .collect()
+            }
+            None => vec![target_dir.join("programs")],
+        },
+        ProjectType::Sbf | ProjectType::Pinocchio | ProjectType::Steel => {
+            vec![target_dir.join("src")]
+        }
+        ProjectType::Unknown => vec![],
+    }
+}
+
+/// Returns the programs depending on each file of the library crates of the Anchor projects,
+/// by canonical path: a library shared by the programs of several workspaces is scanned once,
+/// in a single project, and its findings are attributed to all of them.
+fn library_dependents(
+    projects: &[(String, ProjectType)],
+) -> HashMap<std::path::PathBuf, BTreeSet<String>> {
+    let program_name = |dir: &std::path::Path| {
+        crate_names(dir).into_iter().next().unwrap_or_else(|| {
+            dir.file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string()
+        })
+    };
+    let mut dependents: HashMap<std::path::PathBuf, BTreeSet<String>> = HashMap::new();
+    for (dir, _) in projects
+        .iter()
+        .filter(|(_, project_type)| *project_type == ProjectType::Anchor)
+    {
+        let root = std::path::Path::new(dir);
+        let Some(program_dirs) = anchor_program_dirs(root) else {
+            continue;
         };
-        results.push(result);
-    }
-
-    // Always check subdirectories if recursion is enabled
-    if path.is_dir() && cmd.recursive {
-        for entry in std::fs::read_dir(path)? {
-            let entry = entry?;
-            let sub_path = entry.path();
-
-            if sub_path.is_dir() {
-                let sub_cmd = SastCmd {
-                    target_dir: sub_path.to_string_lossy().to_string(),
-                    rules_dir: cmd.rules_dir.clone(),
-                    syn_scan_only: cmd.syn_scan_only,
-                    use_internal_rules: cmd.use_internal_rules,
-                    recursive: true,
-                };
-
-                // Continue recursion with subdirectories
-                let sub_results = scan_directory_recursively(&sub_cmd)?;
-                results.extend(sub_results);
+        for (library, programs) in anchor_library_dirs(root, &program_dirs) {
+            for file in syn_ast::find_rust_files(&library.join("src")) {
+                let canonical = std::fs::canonicalize(&file).unwrap_or(file);
+                dependents
+                    .entry(canonical)
+                    .or_default()
+                    .extend(programs.iter().map(|program| program_name(program)));
             }
         }
     }
+    dependents
+}
 
-    Ok(results)
+/// Attributes each source file to a single project: among the projects whose sources include
+/// the file, the one with the deepest directory (the nearest enclosing project).
+///
+/// Files are compared by canonical path, so a file reachable through several projects (or
+/// symlinks) is only kept once.
+///
+/// # Returns
+///
+/// The files of each project, in the order of `projects`.
+fn attribute_files(projects: &[(String, ProjectType)]) -> Vec<Vec<std::path::PathBuf>> {
+    let depth = |dir: &str| {
+        std::fs::canonicalize(dir)
+            .map(|dir| dir.components().count())
+            .unwrap_or_default()
+    };
+    let depths: Vec<usize> = projects.iter().map(|(dir, _)| depth(dir)).collect();
+
+    // canonical path -> (project index, path as found)
+    let mut owners: HashMap<std::path::PathBuf, (usize, std::path::PathBuf)> = HashMap::new();
+    for (index, (dir, project_type)) in projects.iter().enumerate() {
+        for source_dir in project_source_dirs(dir, *project_type) {
+            for file in syn_ast::find_rust_files(&source_dir) {
+                let canonical = std::fs::canonicalize(&file).unwrap_or_else(|_| file.clone());
+                match owners.get(&canonical) {
+                    Some((owner, _)) if depths[*owner] >= depths[index] => {
+                        debug!("{:?} already attributed to {}", file, projects[*owner].0);
+                    }
+                    _ => {
+                        owners.insert(canonical, (index, file));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut files = vec![Vec::new(); projects.len()];
+    for (owner, file) in owners.into_values() {
+        files[owner].push(file);
+    }
+    for project_files in files.iter_mut() {
+        project_files.sort();
+    }
+    files
 }
 
 /// Performs static analysis on an Anchor-based project.
 ///
-/// Syntax trees are generated from the `programs/` directory.
+/// Syntax trees are generated from the programs declared in `Anchor.toml`, or from the
+/// `programs/` directory if it declares none.
 ///
 /// # Arguments
 ///
@@ -190,56 +634,148 @@ fn scan_directory_recursively(cmd: &SastCmd) -> anyhow::Result<Vec<SastState>> {
 ///
 /// A `Result` containing a populated `SastState` on success, or an error if analysis fails.
 fn sast_anchor_project(cmd: &SastCmd) -> anyhow::Result<SastState> {
-    let spinner = helpers::spinner::get_new_spinner(format!("Performing sast scan on {} anchor project...", cmd.target_dir));
-    
-    // ? FUTURE: Use Anchor.toml to get programs paths?
-    let mut sast_state = SastState::new(
-        syn_ast::get_syn_ast_recursive(&format!("{}/programs", cmd.target_dir))?,
-        cmd.rules_dir.clone(),
-        cmd.use_internal_rules,
-    )?;
-
-    match sast_state.apply_rules() {
-        Ok(_) => {}
-        Err(_e) => {
-            error!("Cannot apply rules to the project: {}", cmd.target_dir);
-            spinner.finish_using_style();
-            return Err(anyhow::anyhow!(
-                "Cannot apply rules to the project: {}",
-                cmd.target_dir
-            ));
-        }
+    let mut syn_ast_map = SynAstMap::new();
+    for source_dir in project_source_dirs(&cmd.target_dir, ProjectType::Anchor) {
+        syn_ast_map.extend(syn_ast::get_syn_ast_recursive_with(
+            &source_dir.to_string_lossy(),
+            &cmd.parse_options(),
+        )?);
     }
-    spinner.finish_using_style();
-    
-    sast_state.print_results(&cmd.target_dir)?;
-
-    if cmd.syn_scan_only {
-        return Ok(sast_state);
-    }
-    Ok(sast_state)
+    let dependents = library_dependents(&[(cmd.target_dir.clone(), ProjectType::Anchor)]);
+    sast_parsed_project(
+        cmd,
+        ProjectType::Anchor,
+        syn_ast_map,
+        &cmd.target_dir,
+        &dependents,
+    )
 }
 
-/// Performs static analysis on a Solana SBF project.
+/// Performs static analysis on a native Solana SBF project, written with `solana-program`,
+/// Pinocchio or Steel.
 ///
 /// Syntax trees are generated from the `src/` directory.
 ///
 /// # Arguments
 ///
 /// * `cmd` - A reference to the `SastCmd` struct, containing command-line arguments.
+/// * `project_type` - The type of the project, for display.
 ///
 /// # Returns
 ///
 /// A `Result` containing a populated `SastState` on success, or an error if analysis fails.
-fn sast_sbf_project(cmd: &SastCmd) -> anyhow::Result<SastState> {
-    let spinner = helpers::spinner::get_new_spinner(format!("Performing sast scan on {} sbf project...", cmd.target_dir));
-    
+fn sast_sbf_project(cmd: &SastCmd, project_type: ProjectType) -> anyhow::Result<SastState> {
     // ? FUTURE: Use Cargo.toml to get programs paths?
-    let mut sast_state = SastState::new(
-        syn_ast::get_syn_ast_recursive(&format!("{}/src", cmd.target_dir))?,
-        cmd.rules_dir.clone(),
-        cmd.use_internal_rules,
+    let syn_ast_map = syn_ast::get_syn_ast_recursive_with(
+        &format!("{}/src", cmd.target_dir),
+        &cmd.parse_options(),
     )?;
+    sast_parsed_project(
+        cmd,
+        project_type,
+        syn_ast_map,
+        &cmd.target_dir,
+        &HashMap::new(),
+    )
+}
+
+/// Returns the programs of the project to correlate the findings with: the `--bytecode`
+/// files, and the programs recorded for the project in the `--build-manifest`.
+///
+/// The IDL of each program is looked up in `<target_dir>/target/idl`.
+fn project_programs(cmd: &SastCmd) -> anyhow::Result<Vec<ProgramFacts>> {
+    let mut paths: Vec<std::path::PathBuf> =
+        cmd.bytecodes.iter().map(std::path::PathBuf::from).collect();
+
+    if let Some(manifest) = &cmd.build_manifest {
+        let content = std::fs::read_to_string(manifest)?;
+        let states: Vec<BuildState> = serde_json::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Invalid build manifest {}: {}", manifest, e))?;
+        let project = std::fs::canonicalize(&cmd.target_dir)?;
+        match states
+            .iter()
+            .find(|state| std::fs::canonicalize(&state.target_dir).ok().as_ref() == Some(&project))
+        {
+            Some(state) => {
+                for artifact in &state.artifacts {
+                    let bytes = std::fs::read(&artifact.path)?;
+                    if hex::encode(Sha256::digest(&bytes)) != artifact.sha256 {
+                        warn!(
+                            "{} changed since the build recorded in {}",
+                            artifact.path, manifest
+                        );
+                    }
+                    paths.push(std::path::PathBuf::from(&artifact.path));
+                }
+            }
+            None => warn!("No build of {} in {}", cmd.target_dir, manifest),
+        }
+    }
+
+    let idl_dir = std::path::Path::new(&cmd.target_dir)
+        .join("target")
+        .join("idl");
+    paths
+        .iter()
+        .map(|path| {
+            let idl = path
+                .file_stem()
+                .map(|stem| idl_dir.join(stem).with_extension("json"))
+                .filter(|idl| idl.is_file());
+            ProgramFacts::load(path, idl.as_deref())
+        })
+        .collect()
+}
+
+/// Applies the rules to the already parsed sources of a project and prints the results.
+///
+/// # Arguments
+///
+/// * `cmd` - A reference to the `SastCmd` struct, whose `target_dir` is the project.
+/// * `project_type` - The type of the project, for display.
+/// * `syn_ast_map` - The parsed source files of the project.
+/// * `root` - The scanned directory, to which the paths of the findings are made relative
+///   (the project itself, or the directory scanned with `--recursive`).
+/// * `dependents` - The programs depending on the files of library crates (see
+///   [`library_dependents`]).
+///
+/// # Returns
+///
+/// A `Result` containing a populated `SastState` on success, or an error if analysis fails.
+fn sast_parsed_project(
+    cmd: &SastCmd,
+    project_type: ProjectType,
+    mut syn_ast_map: SynAstMap,
+    root: &str,
+    dependents: &HashMap<std::path::PathBuf, BTreeSet<String>>,
+) -> anyhow::Result<SastState> {
+    if let Some(cfg) = &cmd.cfg {
+        timing::time("cfg", || {
+            filter_syn_ast_map(&mut syn_ast_map, cfg, cmd.column_encoding)
+        });
+    }
+
+    let kind = match project_type {
+        ProjectType::Anchor => "anchor",
+        ProjectType::Pinocchio => "pinocchio",
+        ProjectType::Steel => "steel",
+        _ => "sbf",
+    };
+    let spinner = helpers::spinner::get_new_spinner(format!(
+        "Performing sast scan on {} {} project...",
+        cmd.target_dir, kind
+    ));
+
+    let mut sast_state = timing::time("setup", || {
+        SastState::new(
+            syn_ast_map,
+            cmd.rules_dir.clone(),
+            cmd.use_internal_rules,
+            &cmd.rule_filter,
+            &cmd.rule_trust,
+            cmd.rule_limits,
+        )
+    })?;
 
     match sast_state.apply_rules() {
         Ok(_) => {}
@@ -254,10 +790,128 @@ fn sast_sbf_project(cmd: &SastCmd) -> anyhow::Result<SastState> {
     }
     spinner.finish_using_style();
 
-    sast_state.print_results(&cmd.target_dir)?;
+    // the audit of the dependencies is one of the internal checks
+    let lock_file = std::path::Path::new(&cmd.target_dir).join("Cargo.lock");
+    if cmd.use_internal_rules && lock_file.is_file() {
+        let _span = timing::span("deps");
+        let advisories = AdvisoryDb::load(cmd.advisories.as_deref().map(std::path::Path::new))?;
+        sast_state.audit_dependencies(&lock_file, &advisories, &cmd.rule_filter)?;
+    }
+
+    if let Some(taxonomy) = &cmd.taxonomy {
+        sast_state.apply_taxonomy(&Taxonomy::load(std::path::Path::new(taxonomy))?);
+    }
+
+    let correlate_span = timing::span("correlate");
+    let programs = project_programs(cmd)?;
+    if !programs.is_empty() {
+        let summary = correlate_findings(&mut sast_state, &programs);
+        if let Some(removed) = summary.get(&CompiledStatus::Removed) {
+            info!(
+                "{} finding(s) in code removed by the compiler, see the `compiled` metadata.",
+                removed
+            );
+        }
+    }
+
+    drop(correlate_span);
+
+    sast_state.record_dependent_programs(dependents);
+    sast_state.relativize_paths(std::path::Path::new(root));
 
-    if cmd.syn_scan_only {
-        return Ok(sast_state);
+    if !cmd.tui {
+        timing::time("print", || sast_state.print_results(&cmd.target_dir))?;
     }
+
     Ok(sast_state)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_projects_files_attribution() {
+        let root = std::env::temp_dir().join(format!("sol-azy-nested-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        for (file, content) in [
+            ("Anchor.toml", ""),
+            ("programs/member/Cargo.toml", "[dependencies]\nsolana-program = \"1\"\n"),
+            ("programs/member/src/lib.rs", ""),
+            ("programs/member/src/state.rs", ""),
+            ("programs/other/src/lib.rs", ""),
+        ] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        let mut projects = Vec::new();
+        find_projects(&root, &mut projects).unwrap();
+        let types: Vec<ProjectType> = projects.iter().map(|(_, ty)| *ty).collect();
+        assert_eq!(types, vec![ProjectType::Anchor, ProjectType::Sbf]);
+
+        let files = attribute_files(&projects);
+        let names = |files: &Vec<std::path::PathBuf>| {
+            files
+                .iter()
+                .map(|file| {
+                    file.strip_prefix(&root)
+                        .unwrap()
+                        .to_string_lossy()
+                        .replace('\\', "/")
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&files[0]), vec!["programs/other/src/lib.rs"]);
+        assert_eq!(
+            names(&files[1]),
+            vec!["programs/member/src/lib.rs", "programs/member/src/state.rs"]
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_shared_library_attribution() {
+        let root = std::env::temp_dir().join(format!("sol-azy-monorepo-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let anchor_toml = "[workspace]\nmembers = [\"programs/*\"]\n";
+        let common = "[dependencies]\ncommon = { path = \"../../../libs/common\" }\n";
+        for (file, content) in [
+            ("lending/Anchor.toml", anchor_toml),
+            ("lending/programs/lending/Cargo.toml", common),
+            ("lending/programs/lending/src/lib.rs", ""),
+            ("vault/Anchor.toml", anchor_toml),
+            ("vault/programs/vault/Cargo.toml", common),
+            ("vault/programs/vault/src/lib.rs", ""),
+            ("libs/common/Cargo.toml", "[package]\nname = \"common\"\n"),
+            ("libs/common/src/lib.rs", ""),
+        ] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        let mut projects = Vec::new();
+        find_projects(&root, &mut projects).unwrap();
+        assert_eq!(projects.len(), 2);
+
+        // the library is scanned once, with the first workspace
+        let files = attribute_files(&projects);
+        let library = root.join("libs/common/src/lib.rs");
+        assert_eq!(files.iter().flatten().filter(|f| **f == library).count(), 1);
+        assert!(files[0].contains(&library));
+
+        // and attributed to the programs of both
+        let dependents = library_dependents(&projects);
+        let canonical = std::fs::canonicalize(&library).unwrap();
+        assert_eq!(
+            dependents[&canonical],
+            BTreeSet::from(["lending".to_string(), "vault".to_string()])
+        );
+        assert_eq!(dependents.len(), 1);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
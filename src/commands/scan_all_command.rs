@@ -0,0 +1,611 @@
+//! Config-driven scan of several projects (`scan-all`).
+//!
+//! The targets are listed in a `solazy-workspace.toml` file:
+//!
+//! ```toml
+//! [defaults]
+//! rules_dir = "audit-rules"
+//!
+//! [[targets]]
+//! name = "vault"
+//! path = "programs/vault"
+//! type = "anchor"
+//! recap = true
+//!
+//! [[targets]]
+//! path = "native/escrow"
+//! disable_rules = ["anchor"]
+//! ```
+//!
+//! Each target is analyzed with the SAST engine (and optionally the recap), then all the results
+//! are gathered in a single markdown report.
+
+use crate::commands::sast_command::{self, SastCmd};
+use crate::engines::starlark_engine::{
+    RuleFilter, RuleLimits, RuleTrust, DEFAULT_RULE_MAX_HEAP_MB, DEFAULT_RULE_MAX_STEPS,
+    DEFAULT_RULE_TIMEOUT_SECS,
+};
+use crate::helpers::{get_project_type, ProjectType};
+use crate::parsers::cfg::CfgSet;
+use crate::parsers::syn_ast::ColumnEncoding;
+use crate::state::sast_state::{SastState, Severity, SynAstMapExt, SynAstResult};
+use crate::Commands;
+use anyhow::{anyhow, Context, Result};
+use log::{debug, error, info};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Default name of the workspace file.
+pub const WORKSPACE_FILE: &str = "solazy-workspace.toml";
+
+/// Default name of the aggregated report, written in the launch directory.
+pub const REPORT_FILE: &str = "solazy-scan-report.md";
+
+pub struct ScanAllCmd {
+    pub config: String,
+    pub out: Option<String>,
+}
+
+impl ScanAllCmd {
+    pub fn new_from_clap(cmd: &Commands) -> Self {
+        match cmd {
+            Commands::ScanAll { config, out } => Self {
+                config: config.clone(),
+                out: out.clone(),
+            },
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Project type declared for a target, detected from its files when omitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetType {
+    Anchor,
+    Sbf,
+    Pinocchio,
+    Steel,
+}
+
+impl From<TargetType> for ProjectType {
+    fn from(target_type: TargetType) -> Self {
+        match target_type {
+            TargetType::Anchor => ProjectType::Anchor,
+            TargetType::Sbf => ProjectType::Sbf,
+            TargetType::Pinocchio => ProjectType::Pinocchio,
+            TargetType::Steel => ProjectType::Steel,
+        }
+    }
+}
+
+/// Scan options, set for all the targets in `[defaults]` or per target.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TargetOptions {
+    pub rules_dir: Option<String>,
+    pub rules_git: Option<String>,
+    pub use_internal_rules: Option<bool>,
+    pub enable_rules: Option<Vec<String>>,
+    pub disable_rules: Option<Vec<String>>,
+    pub trusted_rules_only: Option<bool>,
+    pub trust_rules: Option<Vec<String>>,
+    /// Execution limits of each rule, 0 disables the limit.
+    pub rule_max_steps: Option<u64>,
+    pub rule_timeout: Option<u64>,
+    pub rule_max_heap_mb: Option<usize>,
+    pub recursive: Option<bool>,
+    pub column_encoding: Option<String>,
+    /// Taxonomy file in which the findings are rated, like `sast --taxonomy`.
+    pub taxonomy: Option<String>,
+    /// Advisories updating the shipped ones, like `sast --advisories`.
+    pub advisories: Option<String>,
+    /// Configuration of the deployed program, like `sast --features` / `--cfg`.
+    pub features: Option<Vec<String>>,
+    pub cfg: Option<Vec<String>>,
+    /// Also generates the recap of the target (Anchor projects only).
+    pub recap: Option<bool>,
+}
+
+impl TargetOptions {
+    /// Returns the options set in `self`, falling back on `defaults` for the others.
+    fn or(&self, defaults: &TargetOptions) -> TargetOptions {
+        TargetOptions {
+            rules_dir: self
+                .rules_dir
+                .clone()
+                .or_else(|| defaults.rules_dir.clone()),
+            rules_git: self
+                .rules_git
+                .clone()
+                .or_else(|| defaults.rules_git.clone()),
+            use_internal_rules: self.use_internal_rules.or(defaults.use_internal_rules),
+            enable_rules: self
+                .enable_rules
+                .clone()
+                .or_else(|| defaults.enable_rules.clone()),
+            disable_rules: self
+                .disable_rules
+                .clone()
+                .or_else(|| defaults.disable_rules.clone()),
+            trusted_rules_only: self.trusted_rules_only.or(defaults.trusted_rules_only),
+            trust_rules: self
+                .trust_rules
+                .clone()
+                .or_else(|| defaults.trust_rules.clone()),
+            rule_max_steps: self.rule_max_steps.or(defaults.rule_max_steps),
+            rule_timeout: self.rule_timeout.or(defaults.rule_timeout),
+            rule_max_heap_mb: self.rule_max_heap_mb.or(defaults.rule_max_heap_mb),
+            recursive: self.recursive.or(defaults.recursive),
+            column_encoding: self
+                .column_encoding
+                .clone()
+                .or_else(|| defaults.column_encoding.clone()),
+            taxonomy: self.taxonomy.clone().or_else(|| defaults.taxonomy.clone()),
+            advisories: self
+                .advisories
+                .clone()
+                .or_else(|| defaults.advisories.clone()),
+            features: self.features.clone().or_else(|| defaults.features.clone()),
+            cfg: self.cfg.clone().or_else(|| defaults.cfg.clone()),
+            recap: self.recap.or(defaults.recap),
+        }
+    }
+}
+
+/// A project to scan.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkspaceTarget {
+    /// Name used in the report, the last component of `path` if omitted.
+    pub name: Option<String>,
+    /// Path of the project, relative to the workspace file.
+    pub path: String,
+    #[serde(rename = "type")]
+    pub target_type: Option<TargetType>,
+    #[serde(flatten)]
+    pub options: TargetOptions,
+    /// Keys left over by `options`, rejected by [`WorkspaceConfig::parse`].
+    #[serde(flatten)]
+    unknown: BTreeMap<String, toml::Value>,
+}
+
+impl WorkspaceTarget {
+    pub fn display_name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| {
+            Path::new(&self.path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| self.path.clone())
+        })
+    }
+}
+
+/// Content of a `solazy-workspace.toml` file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkspaceConfig {
+    #[serde(default)]
+    pub defaults: TargetOptions,
+    #[serde(default)]
+    pub targets: Vec<WorkspaceTarget>,
+}
+
+impl WorkspaceConfig {
+    pub fn parse(content: &str) -> Result<Self> {
+        let config: Self = toml::from_str(content)?;
+        if config.targets.is_empty() {
+            return Err(anyhow!("No [[targets]] listed"));
+        }
+        for target in &config.targets {
+            if let Some(key) = target.unknown.keys().next() {
+                return Err(anyhow!("Unknown key `{}` in target {}", key, target.path));
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Cannot read workspace file {}", path.display()))?;
+        Self::parse(&content).with_context(|| format!("Invalid workspace file {}", path.display()))
+    }
+}
+
+/// Outcome of the scan of one target.
+struct TargetReport {
+    name: String,
+    path: PathBuf,
+    project_type: ProjectType,
+    states: Vec<SastState>,
+    recap: Option<String>,
+    error: Option<String>,
+}
+
+/// Resolves `path` against the directory of the workspace file.
+fn resolve(base_dir: &Path, path: &str) -> PathBuf {
+    let path = Path::new(path);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        base_dir.join(path)
+    }
+}
+
+/// Runs the SAST analysis (and the recap, if enabled) on a single target.
+fn scan_target(target: &WorkspaceTarget, options: &TargetOptions, path: &Path) -> TargetReport {
+    let target_dir = path.to_string_lossy().to_string();
+    let project_type = target
+        .target_type
+        .map(ProjectType::from)
+        .unwrap_or_else(|| get_project_type(&target_dir));
+    let mut report = TargetReport {
+        name: target.display_name(),
+        path: path.to_path_buf(),
+        project_type,
+        states: vec![],
+        recap: None,
+        error: None,
+    };
+
+    let use_internal_rules = options.use_internal_rules.unwrap_or(true);
+    if !use_internal_rules && options.rules_dir.is_none() && options.rules_git.is_none() {
+        report.error =
+            Some("no rules: set rules_dir or rules_git, or use_internal_rules".to_string());
+        return report;
+    }
+    let cfg = match CfgSet::from_options(
+        options.features.as_deref().unwrap_or_default(),
+        options.cfg.as_deref().unwrap_or_default(),
+    ) {
+        Ok(cfg) => cfg,
+        Err(e) => {
+            report.error = Some(e.to_string());
+            return report;
+        }
+    };
+
+    let rule_limits = match RuleLimits::from_options(
+        options.rule_max_steps.unwrap_or(DEFAULT_RULE_MAX_STEPS),
+        options.rule_timeout.unwrap_or(DEFAULT_RULE_TIMEOUT_SECS),
+        options.rule_max_heap_mb.unwrap_or(DEFAULT_RULE_MAX_HEAP_MB),
+    ) {
+        Ok(rule_limits) => rule_limits,
+        Err(e) => {
+            report.error = Some(e.to_string());
+            return report;
+        }
+    };
+
+    let cmd = SastCmd {
+        target_dir: target_dir.clone(),
+        rules_dir: options.rules_dir.clone(),
+        rules_git: options.rules_git.clone(),
+        syn_scan_only: false,
+        use_internal_rules,
+        recursive: options.recursive.unwrap_or(false),
+        column_encoding: options
+            .column_encoding
+            .as_deref()
+            .and_then(|encoding| encoding.parse::<ColumnEncoding>().ok())
+            .unwrap_or_default(),
+        rule_filter: RuleFilter {
+            enable: options.enable_rules.clone().unwrap_or_default(),
+            disable: options.disable_rules.clone().unwrap_or_default(),
+        },
+        rule_trust: RuleTrust {
+            trusted_only: options.trusted_rules_only.unwrap_or(false),
+            allowed: options.trust_rules.clone().unwrap_or_default(),
+        },
+        rule_limits,
+        bytecodes: vec![],
+        build_manifest: None,
+        export_db: None,
+        taxonomy: options.taxonomy.clone(),
+        strict: false,
+        tui: false,
+        baseline: None,
+        advisories: options.advisories.clone(),
+        cfg,
+        fix: false,
+        dry_run: false,
+        timing_report: None,
+        max_file_size: None,
+        spill_ast: false,
+    };
+    match sast_command::run_with_type(&cmd, target.target_type.map(ProjectType::from)) {
+        Ok(states) => report.states = states,
+        Err(e) => {
+            report.error = Some(format!("SAST failed: {}", e));
+            return report;
+        }
+    }
+
+    if options.recap.unwrap_or(false) {
+        if project_type == ProjectType::Anchor {
+            match crate::recap::recap_markdown(path) {
+                Ok(recap) => report.recap = Some(recap),
+                Err(e) => report.error = Some(format!("recap failed: {}", e)),
+            }
+        } else {
+            report.error = Some("recap is only supported on Anchor projects".to_string());
+        }
+    }
+
+    report
+}
+
+/// Returns the results having at least one match, with the file they were found in.
+fn results_with_matches(states: &[SastState]) -> Vec<(&String, &SynAstResult)> {
+    states
+        .iter()
+        .flat_map(|state| state.syn_ast_map.iter())
+        .flat_map(|(filename, ast)| {
+            ast.results
+                .iter()
+                .filter(|result| !result.matches.is_empty())
+                .map(move |result| (filename, result))
+        })
+        .collect()
+}
+
+/// Orders the severities, the most severe first.
+pub(crate) fn severity_rank(severity: &Severity) -> u8 {
+    match severity {
+        Severity::Critical => 0,
+        Severity::High => 1,
+        Severity::Medium => 2,
+        Severity::Low => 3,
+        Severity::Unknown => 4,
+    }
+}
+
+/// Counts the matches per severity, in the order Critical, High, Medium, Low, Unknown.
+fn severity_counts(states: &[SastState]) -> [usize; 5] {
+    let mut counts = [0; 5];
+    for (_, result) in results_with_matches(states) {
+        counts[severity_rank(&result.rule_metadata.severity) as usize] += result.matches.len();
+    }
+    counts
+}
+
+/// Shifts the markdown headings of `markdown` by `levels`.
+fn demote_headings(markdown: &str, levels: usize) -> String {
+    markdown
+        .lines()
+        .map(|line| {
+            if line.starts_with('#') {
+                format!("{}{}", "#".repeat(levels), line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Renders the aggregated markdown report of all the targets.
+fn render_report(config_path: &Path, reports: &[TargetReport]) -> String {
+    let mut out = format!(
+        "# sol-azy scan report\n\n_Workspace: {}_\n\n",
+        config_path.display()
+    );
+
+    out.push_str("| Target | Type | Files | Critical | High | Medium | Low | Unknown | Status |\n");
+    out.push_str("|---|---|---|---|---|---|---|---|---|\n");
+    for report in reports {
+        let files: usize = report
+            .states
+            .iter()
+            .map(|state| state.syn_ast_map.count_files())
+            .sum();
+        let counts = severity_counts(&report.states);
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {} | {} | {} |\n",
+            report.name,
+            report.project_type,
+            files,
+            counts[0],
+            counts[1],
+            counts[2],
+            counts[3],
+            counts[4],
+            report.error.as_deref().unwrap_or("ok"),
+        ));
+    }
+
+    for report in reports {
+        out.push_str(&format!(
+            "\n## Target `{}`\n\n_Path: {}_\n\n",
+            report.name,
+            report.path.display()
+        ));
+        if let Some(error) = &report.error {
+            out.push_str(&format!("**Error:** {}\n\n", error));
+        }
+
+        let mut by_rule: BTreeMap<(u8, String), Vec<(&String, &SynAstResult)>> = BTreeMap::new();
+        for (filename, result) in results_with_matches(&report.states) {
+            by_rule
+                .entry((
+                    severity_rank(&result.rule_metadata.severity),
+                    result.rule_metadata.name.clone(),
+                ))
+                .or_default()
+                .push((filename, result));
+        }
+
+        if by_rule.is_empty() && !report.states.is_empty() {
+            out.push_str("No vulnerabilities detected.\n");
+        }
+        for ((_, rule_name), results) in &by_rule {
+            let metadata = &results[0].1.rule_metadata;
+            let classification = &results[0].1.classification;
+            let total: usize = results.iter().map(|(_, result)| result.matches.len()).sum();
+            let rating = if classification.is_default() {
+                format!(
+                    "{:?}, certainty {:?}",
+                    metadata.severity, metadata.certainty
+                )
+            } else {
+                classification.describe()
+            };
+            out.push_str(&format!(
+                "### {} ({})\n\n{}\n\nMatches found: {}\n\n",
+                rule_name, rating, metadata.description, total
+            ));
+            for (filename, result) in results {
+                for match_result in &result.matches {
+                    let location = match match_result.get_location_metadata() {
+                        Ok(pos) => pos.get_pretty_string(),
+                        Err(_) => format!("{}: {}", filename, match_result.access_path),
+                    };
+                    match result.render_description(match_result) {
+                        Some(description) => {
+                            out.push_str(&format!("- `{}` — {}\n", location, description))
+                        }
+                        None => out.push_str(&format!("- `{}`\n", location)),
+                    }
+                }
+            }
+            out.push('\n');
+        }
+
+        if let Some(recap) = &report.recap {
+            out.push_str("\n### Recap\n\n");
+            out.push_str(&demote_headings(recap, 3));
+            out.push('\n');
+        }
+    }
+
+    out
+}
+
+/// Scans every target of the workspace file and writes the aggregated report.
+///
+/// A failing target doesn't stop the scan: the error is logged and recorded in the report.
+///
+/// # Arguments
+///
+/// * `cmd` - A reference to the `ScanAllCmd` struct, containing command-line arguments.
+///
+/// # Returns
+///
+/// The `SastState`s of all the targets, or an error if the workspace file is invalid or the
+/// report cannot be written.
+pub fn run(cmd: &ScanAllCmd) -> Result<Vec<SastState>> {
+    let config_path = Path::new(&cmd.config);
+    let config = WorkspaceConfig::load(config_path)?;
+    let base_dir = config_path.parent().unwrap_or(Path::new("."));
+    debug!(
+        "Scanning {} targets from {}",
+        config.targets.len(),
+        config_path.display()
+    );
+
+    let mut reports = vec![];
+    for target in &config.targets {
+        let mut options = target.options.or(&config.defaults);
+        options.rules_dir = options
+            .rules_dir
+            .map(|dir| resolve(base_dir, &dir).to_string_lossy().to_string());
+        options.taxonomy = options
+            .taxonomy
+            .map(|file| resolve(base_dir, &file).to_string_lossy().to_string());
+        options.advisories = options
+            .advisories
+            .map(|file| resolve(base_dir, &file).to_string_lossy().to_string());
+        let path = resolve(base_dir, &target.path);
+
+        info!(
+            "Scanning target {} ({})",
+            target.display_name(),
+            path.display()
+        );
+        let report = if path.exists() {
+            scan_target(target, &options, &path)
+        } else {
+            TargetReport {
+                name: target.display_name(),
+                path: path.clone(),
+                project_type: ProjectType::Unknown,
+                states: vec![],
+                recap: None,
+                error: Some("path doesn't exist".to_string()),
+            }
+        };
+        if let Some(e) = &report.error {
+            error!("Target {}: {}", report.name, e);
+        }
+        reports.push(report);
+    }
+
+    let out_path = match &cmd.out {
+        Some(out) => PathBuf::from(out),
+        None => std::env::var_os("PWD")
+            .map(PathBuf::from)
+            .unwrap_or_default()
+            .join(REPORT_FILE),
+    };
+    std::fs::write(&out_path, render_report(config_path, &reports))
+        .with_context(|| format!("Cannot write the scan report to {}", out_path.display()))?;
+    info!("Scan report written to {}", out_path.display());
+    crate::helpers::events::artifact_written(&out_path);
+
+    Ok(reports
+        .into_iter()
+        .flat_map(|report| report.states)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_workspace_config() {
+        let config = WorkspaceConfig::parse(
+            r#"
+            [defaults]
+            rules_dir = "rules"
+            recap = true
+
+            [[targets]]
+            name = "vault"
+            path = "programs/vault"
+            type = "anchor"
+
+            [[targets]]
+            path = "native/escrow/"
+            recap = false
+            disable_rules = ["anchor"]
+            "#,
+        )
+        .unwrap();
+
+        let vault = &config.targets[0];
+        assert_eq!(vault.display_name(), "vault");
+        assert_eq!(vault.target_type, Some(TargetType::Anchor));
+        let options = vault.options.or(&config.defaults);
+        assert_eq!(options.rules_dir.as_deref(), Some("rules"));
+        assert_eq!(options.recap, Some(true));
+
+        let escrow = &config.targets[1];
+        assert_eq!(escrow.display_name(), "escrow");
+        let options = escrow.options.or(&config.defaults);
+        assert_eq!(options.recap, Some(false));
+        assert_eq!(options.disable_rules, Some(vec!["anchor".to_string()]));
+
+        assert!(WorkspaceConfig::parse("[defaults]\nrecap = true\n").is_err());
+        assert!(WorkspaceConfig::parse("[defaults]\nrulesdir = \"rules\"\n").is_err());
+        assert!(WorkspaceConfig::parse("[[targets]]\npath = \".\"\nrecpa = true\n").is_err());
+    }
+
+    #[test]
+    fn test_demote_headings() {
+        assert_eq!(
+            demote_headings("# Program `p`\n| a |", 3),
+            "#### Program `p`\n| a |"
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! `serve` command: exposes `sast`, `reverse` and `fetcher` over an HTTP+JSON API.
+//!
+//! Clients submit jobs with the command line arguments of these commands, poll their status
+//! and download their artifacts. See [`crate::server`] for the endpoints.
+
+use crate::helpers::events;
+use crate::server::{handle, ServerState};
+use crate::Commands;
+use anyhow::{Context, Result};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::Server;
+use log::{info, warn};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+pub struct ServeCmd {
+    pub listen: String,
+    pub work_dir: String,
+    pub max_jobs: usize,
+    /// Largest uploaded file, in MiB.
+    pub max_upload_mb: u64,
+    /// Bearer token required by every request, if any.
+    pub token: Option<String>,
+}
+
+impl ServeCmd {
+    pub fn new_from_clap(cmd: &Commands) -> Self {
+        match cmd {
+            Commands::Serve {
+                listen,
+                work_dir,
+                max_jobs,
+                max_upload_mb,
+                token,
+            } => Self {
+                listen: listen.clone(),
+                work_dir: work_dir.clone(),
+                max_jobs: *max_jobs,
+                max_upload_mb: *max_upload_mb,
+                token: token.clone(),
+            },
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Serves the API until the process is interrupted.
+///
+/// # Errors
+///
+/// Returns an error if the listen address is invalid, the work directory can't be created or
+/// the server fails.
+pub async fn run(cmd: &ServeCmd) -> Result<()> {
+    let address: SocketAddr = cmd
+        .listen
+        .parse()
+        .with_context(|| format!("Invalid listen address '{}'", cmd.listen))?;
+    let work_dir = PathBuf::from(&cmd.work_dir);
+    std::fs::create_dir_all(&work_dir)
+        .with_context(|| format!("Creating {}", work_dir.display()))?;
+    if cmd.token.is_none() && !address.ip().is_loopback() {
+        warn!(
+            "Listening on {} without --token: anyone reaching it can run jobs",
+            address
+        );
+    }
+
+    let state = Arc::new(ServerState::new(
+        work_dir,
+        cmd.token.clone(),
+        cmd.max_jobs,
+        cmd.max_upload_mb,
+    ));
+    let service = make_service_fn(move |_| {
+        let state = state.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let state = state.clone();
+                async move { Ok::<_, Infallible>(handle(state, request).await) }
+            }))
+        }
+    });
+    let server = Server::try_bind(&address)
+        .with_context(|| format!("Binding {}", address))?
+        .serve(service);
+    info!("Serving the sol-azy API on http://{}", address);
+    if events::human_output() {
+        println!("Serving the sol-azy API on http://{}", address);
+    }
+    server.await.context("Server error")
+}
@@ -0,0 +1,122 @@
+//! `has_one = <target>` constraints whose target account is not validated itself.
+//!
+//! `has_one` only checks that the key stored in the account equals the key of the target
+//! account. If nothing checks the target (its signature, its address, its owner...), any
+//! caller can pass the stored key: e.g. the `authority` of a vault, as an `UncheckedAccount`,
+//! without being the authority.
+
+use serde::Serialize;
+use std::collections::BTreeSet;
+use syn::visit::Visit;
+use syn::{Expr, Ident};
+
+use crate::parsers::anchor_accounts::{AccountsField, AccountsStruct};
+use crate::parsers::syn_ast::SourcePosition;
+
+/// Account types that Anchor doesn't check when loading them, unlike `Signer`, `Account`,
+/// `Program`...
+const UNCHECKED_TYPES: &[&str] = &["UncheckedAccount", "AccountInfo"];
+
+/// `#[account(...)]` arguments validating the account they are set on.
+const VALIDATING_ARGS: &[&str] = &[
+    "signer",
+    "address",
+    "owner",
+    "seeds",
+    "constraint",
+    "has_one",
+    "executable",
+    "init",
+    "init_if_needed",
+    "zero",
+];
+
+/// A `has_one` constraint whose target is not validated.
+#[derive(Debug, Clone, Serialize)]
+pub struct UnvalidatedHasOne {
+    pub accounts_struct: String,
+    /// The field holding the `has_one`.
+    pub field: String,
+    /// The target field, with its type.
+    pub target: String,
+    pub target_type: String,
+    /// Position of the field holding the `has_one`.
+    pub position: SourcePosition,
+}
+
+/// Collects the identifiers of an expression.
+#[derive(Default)]
+struct Idents(BTreeSet<String>);
+
+impl<'ast> Visit<'ast> for Idents {
+    fn visit_ident(&mut self, ident: &'ast Ident) {
+        self.0.insert(ident.to_string());
+    }
+}
+
+/// Whether the field is checked by its type or by one of its own constraints.
+fn is_validated(field: &AccountsField) -> bool {
+    // an attribute that couldn't be read may validate the account
+    if !UNCHECKED_TYPES.contains(&field.account_type().as_str()) || field.unparsed {
+        return true;
+    }
+    field.args.iter().any(|arg| {
+        VALIDATING_ARGS.contains(&arg.key.as_str())
+            || ["token::", "mint::", "associated_token::"]
+                .iter()
+                .any(|namespace| arg.key.starts_with(namespace))
+    })
+}
+
+/// Returns the name of the target of `has_one = <target>`.
+fn has_one_target(value: &Expr) -> Option<String> {
+    match value {
+        Expr::Path(path) => path.path.get_ident().map(Ident::to_string),
+        _ => None,
+    }
+}
+
+/// Finds the `has_one` constraints whose target field is neither validated by its type or its
+/// constraints, nor referred to by the `constraint = ...` of another field.
+///
+/// The targets that are not fields of the struct (Anchor rejects them) are ignored.
+pub fn unvalidated_has_one(structs: &[AccountsStruct]) -> Vec<UnvalidatedHasOne> {
+    let mut findings = vec![];
+    for accounts_struct in structs {
+        let mut constrained = Idents::default();
+        for arg in accounts_struct
+            .fields
+            .iter()
+            .flat_map(|field| &field.args)
+            .filter(|arg| arg.key == "constraint")
+        {
+            if let Some(value) = &arg.value {
+                constrained.visit_expr(value);
+            }
+        }
+
+        for field in &accounts_struct.fields {
+            for target in field
+                .args
+                .iter()
+                .filter(|arg| arg.key == "has_one")
+                .filter_map(|arg| has_one_target(arg.value.as_ref()?))
+            {
+                let Some(target_field) = accounts_struct.field(&target) else {
+                    continue;
+                };
+                if is_validated(target_field) || constrained.0.contains(&target) {
+                    continue;
+                }
+                findings.push(UnvalidatedHasOne {
+                    accounts_struct: accounts_struct.name.clone(),
+                    field: field.name.clone(),
+                    target,
+                    target_type: target_field.account_type(),
+                    position: field.position.clone(),
+                });
+            }
+        }
+    }
+    findings
+}
@@ -0,0 +1,327 @@
+//! Consistency of the Anchor account constraints across a project.
+//!
+//! The `#[derive(Accounts)]` structs of all the files are read with
+//! [`crate::parsers::anchor_accounts`], then checked together:
+//!
+//! - [`seeds`] — Accounts of different types whose seeds could derive the same address.
+//! - [`has_one`] — `has_one` targets that are not validated themselves.
+//!
+//! The report is part of the SAST findings, as two built-in rules, and of the recap.
+
+pub mod has_one;
+pub mod seeds;
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use crate::parsers::anchor_accounts::{accounts_structs, byte_constants};
+use crate::parsers::syn_ast::SourcePosition;
+use crate::state::sast_state::{
+    Certainty, FindingTemplate, Severity, SynAstResult, SynMatchResult, SynRuleMetadata,
+};
+use crate::state::taxonomy::Taxonomy;
+use has_one::{unvalidated_has_one, UnvalidatedHasOne};
+use seeds::{seed_collisions, seeds_to_string, SeedCollision};
+
+/// Rule of the seed collisions in the SAST findings, also its ID for `--enable-rule` /
+/// `--disable-rule`.
+pub const SEED_COLLISION_RULE: &str = "constraints/seed_collision";
+/// Rule of the unvalidated `has_one` targets in the SAST findings.
+pub const UNVALIDATED_HAS_ONE_RULE: &str = "constraints/unvalidated_has_one";
+/// The built-in rules of the constraint checks.
+pub const CONSTRAINT_RULES: &[&str] = &[SEED_COLLISION_RULE, UNVALIDATED_HAS_ONE_RULE];
+
+/// Metadata of the seed collision rule, in the same form as the SAST rules.
+pub fn seed_collision_rule_metadata() -> SynRuleMetadata {
+    SynRuleMetadata {
+        version: "0.1.0".to_string(),
+        author: "sol-azy".to_string(),
+        name: "PDA seeds collision".to_string(),
+        severity: Severity::Medium,
+        certainty: Certainty::Low,
+        description: "Accounts of different types are derived from seeds with the same \
+            literals and the same kinds of values: the same address may be passed for both, \
+            one account being read as the other."
+            .to_string(),
+        ..SynRuleMetadata::default()
+    }
+}
+
+/// Metadata of the unvalidated `has_one` rule, in the same form as the SAST rules.
+pub fn unvalidated_has_one_rule_metadata() -> SynRuleMetadata {
+    SynRuleMetadata {
+        version: "0.1.0".to_string(),
+        author: "sol-azy".to_string(),
+        name: "Unvalidated has_one target".to_string(),
+        severity: Severity::Medium,
+        certainty: Certainty::Medium,
+        description: "The target of a has_one constraint is neither a signer nor checked by \
+            another constraint: any caller can pass the stored key."
+            .to_string(),
+        ..SynRuleMetadata::default()
+    }
+}
+
+/// The constraint checks of a project.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConstraintReport {
+    pub seed_collisions: Vec<SeedCollision>,
+    pub unvalidated_has_one: Vec<UnvalidatedHasOne>,
+}
+
+/// Returns the root of the crate of a source file, the parent of its `src` directory.
+pub(crate) fn crate_root(path: &str) -> &Path {
+    Path::new(path)
+        .ancestors()
+        .find(|dir| dir.file_name().is_some_and(|name| name == "src"))
+        .and_then(Path::parent)
+        .unwrap_or(Path::new(""))
+}
+
+impl ConstraintReport {
+    /// Checks the accounts structs of the files of a project.
+    ///
+    /// The seeds are compared within each crate (the files under the same `src` directory),
+    /// the PDAs of two programs being derived from different program IDs.
+    ///
+    /// # Arguments
+    ///
+    /// * `files` - The path and the syntax tree of each file.
+    pub fn analyze<'a>(files: impl IntoIterator<Item = (&'a str, &'a syn::File)>) -> Self {
+        let mut crates: BTreeMap<&Path, Vec<(&str, &syn::File)>> = BTreeMap::new();
+        for (path, file) in files {
+            crates
+                .entry(crate_root(path))
+                .or_default()
+                .push((path, file));
+        }
+
+        let mut report = Self::default();
+        for files in crates.values() {
+            let mut structs = vec![];
+            let mut constants = HashMap::new();
+            for (path, file) in files {
+                structs.extend(accounts_structs(file, path));
+                constants.extend(byte_constants(file));
+            }
+            report
+                .seed_collisions
+                .extend(seed_collisions(&structs, &constants));
+            report
+                .unvalidated_has_one
+                .extend(unvalidated_has_one(&structs));
+        }
+        report
+    }
+
+    #[cfg(feature = "cli")]
+    pub fn is_empty(&self) -> bool {
+        self.seed_collisions.is_empty() && self.unvalidated_has_one.is_empty()
+    }
+
+    /// Converts the report to SAST results, one per file and rule, the match of each field
+    /// being described by its `description` metadata.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether a rule (one of [`CONSTRAINT_RULES`]) is enabled.
+    ///
+    /// # Returns
+    ///
+    /// The results, by file.
+    pub fn sast_results(
+        &self,
+        enabled: impl Fn(&str) -> bool,
+    ) -> BTreeMap<String, Vec<SynAstResult>> {
+        let mut matches: Vec<(&str, SynMatchResult)> = vec![];
+        if enabled(SEED_COLLISION_RULE) {
+            for collision in &self.seed_collisions {
+                for account in &collision.accounts {
+                    let others: Vec<String> = collision
+                        .accounts
+                        .iter()
+                        .filter(|other| other.account != account.account)
+                        .map(|other| {
+                            format!(
+                                "`{}` ({}.{})",
+                                other.account, other.accounts_struct, other.field
+                            )
+                        })
+                        .collect();
+                    let description = format!(
+                        "the seeds {} of `{}` may also derive {}",
+                        seeds_to_string(&collision.seeds),
+                        account.account,
+                        others.join(", ")
+                    );
+                    matches.push((
+                        SEED_COLLISION_RULE,
+                        field_match(
+                            &account.accounts_struct,
+                            &account.field,
+                            &account.position,
+                            description,
+                        ),
+                    ));
+                }
+            }
+        }
+        if enabled(UNVALIDATED_HAS_ONE_RULE) {
+            for finding in &self.unvalidated_has_one {
+                let description = format!(
+                    "`has_one = {}`, but `{}` is an unvalidated `{}`",
+                    finding.target, finding.target, finding.target_type
+                );
+                matches.push((
+                    UNVALIDATED_HAS_ONE_RULE,
+                    field_match(
+                        &finding.accounts_struct,
+                        &finding.field,
+                        &finding.position,
+                        description,
+                    ),
+                ));
+            }
+        }
+
+        let mut by_file: BTreeMap<(String, &str), Vec<SynMatchResult>> = BTreeMap::new();
+        for (rule, match_result) in matches {
+            let file = match_result
+                .get_location_metadata()
+                .map(|position| position.source_file)
+                .unwrap_or_default();
+            by_file.entry((file, rule)).or_default().push(match_result);
+        }
+        let mut results: BTreeMap<String, Vec<SynAstResult>> = BTreeMap::new();
+        for ((file, rule), matches) in by_file {
+            let rule_metadata = if rule == SEED_COLLISION_RULE {
+                seed_collision_rule_metadata()
+            } else {
+                unvalidated_has_one_rule_metadata()
+            };
+            results.entry(file).or_default().push(SynAstResult {
+                rule_filename: rule.to_string(),
+                result: serde_json::json!({ "matches": matches }).to_string(),
+                matches,
+                classification: Taxonomy::default().classify(&rule_metadata),
+                rule_metadata,
+                finding_template: Some(FindingTemplate {
+                    description: "{description}".to_string(),
+                    params: HashMap::new(),
+                }),
+            });
+        }
+        results
+    }
+}
+
+/// Builds the match of a field of an accounts struct.
+fn field_match(
+    accounts_struct: &str,
+    field: &str,
+    position: &SourcePosition,
+    description: String,
+) -> SynMatchResult {
+    SynMatchResult {
+        children: vec![],
+        access_path: format!("{}.{}", accounts_struct, field),
+        metadata: HashMap::from([
+            (
+                "position".to_string(),
+                serde_json::to_value(position).unwrap_or_default(),
+            ),
+            ("description".to_string(), description.into()),
+        ]),
+        ident: field.to_string(),
+        parent: accounts_struct.to_string(),
+        fix: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+        pub const VAULT_SEED: &[u8] = b"vault";
+
+        #[derive(Accounts)]
+        #[instruction(owner: Pubkey)]
+        pub struct Init<'info> {
+            #[account(init, payer = payer, space = 64, seeds = [b"vault", owner.as_ref()], bump)]
+            pub config: Account<'info, Config>,
+            #[account(mut)]
+            pub payer: Signer<'info>,
+        }
+
+        #[derive(Accounts)]
+        pub struct Withdraw<'info> {
+            #[account(mut, seeds = [VAULT_SEED, authority.key().as_ref()], bump, has_one = authority)]
+            pub vault: Account<'info, Vault>,
+            /// CHECK: only compared with the vault
+            pub authority: UncheckedAccount<'info>,
+        }
+
+        #[derive(Accounts)]
+        pub struct Deposit<'info> {
+            #[account(mut, seeds = [b"vault", user.key().as_ref()], bump, has_one = user)]
+            pub vault: Account<'info, Vault>,
+            /// CHECK: checked below
+            #[account(constraint = user.key() != Pubkey::default())]
+            pub user: AccountInfo<'info>,
+            #[account(seeds = [b"vault", user.key().as_ref(), &[1]], bump)]
+            pub other: Account<'info, Other>,
+        }
+    "#;
+
+    #[test]
+    fn test_constraint_report() {
+        let file = syn::parse_file(SOURCE).unwrap();
+        let report = ConstraintReport::analyze([("programs/p/src/lib.rs", &file)]);
+
+        assert_eq!(report.seed_collisions.len(), 1);
+        let collision = &report.seed_collisions[0];
+        assert_eq!(seeds_to_string(&collision.seeds), "[b\"vault\", <pubkey>]");
+        let fields: Vec<String> = collision
+            .accounts
+            .iter()
+            .map(|account| format!("{}.{}", account.accounts_struct, account.field))
+            .collect();
+        assert_eq!(
+            fields,
+            vec!["Init.config", "Deposit.vault", "Withdraw.vault"]
+        );
+
+        assert_eq!(report.unvalidated_has_one.len(), 1);
+        let has_one = &report.unvalidated_has_one[0];
+        assert_eq!(
+            (has_one.accounts_struct.as_str(), has_one.target.as_str()),
+            ("Withdraw", "authority")
+        );
+
+        // the seeds of another crate don't collide
+        let other = syn::parse_file(
+            "#[derive(Accounts)] pub struct A<'info> { \
+            #[account(seeds = [b\"vault\", a.key().as_ref()], bump)] pub a: Account<'info, A> }",
+        )
+        .unwrap();
+        let report = ConstraintReport::analyze([
+            ("programs/p/src/lib.rs", &file),
+            ("programs/q/src/lib.rs", &other),
+        ]);
+        assert_eq!(report.seed_collisions[0].accounts.len(), 3);
+
+        let results = report.sast_results(|rule| rule != UNVALIDATED_HAS_ONE_RULE);
+        let results = &results["programs/p/src/lib.rs"];
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].rule_filename, SEED_COLLISION_RULE);
+        let config = &results[0].matches[0];
+        assert_eq!(config.access_path, "Init.config");
+        assert_eq!(
+            results[0].render_description(config).unwrap(),
+            "the seeds [b\"vault\", <pubkey>] of `Config` may also derive \
+            `Vault` (Deposit.vault), `Vault` (Withdraw.vault)"
+        );
+    }
+}
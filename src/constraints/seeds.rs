@@ -0,0 +1,220 @@
+//! Normalization of the `seeds = [...]` of the seeded accounts, to find the accounts of
+//! different types whose seeds could derive the same address.
+//!
+//! Each seed is reduced to its value when it is known (a byte string, a string, or a constant
+//! holding one), else to the kind of the runtime value (`pubkey`, `u8`, ...). Two seeds lists
+//! could collide when they have the same count, the same literals (so the same literal prefix)
+//! and the same kinds of runtime values: the callers may then pass the same values for both.
+
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use syn::{Expr, Type};
+
+use crate::parsers::anchor_accounts::{literal_bytes, AccountsStruct};
+use crate::parsers::syn_ast::SourcePosition;
+
+/// A normalized seed.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum Seed {
+    /// A value known statically.
+    Literal(Vec<u8>),
+    /// A runtime value, by kind: `pubkey`, `u8` (a bump), `le_bytes` / `be_bytes` (an integer,
+    /// whose width is unknown) or `bytes`.
+    Dynamic(String),
+}
+
+impl fmt::Display for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Seed::Literal(bytes) => write!(f, "b\"{}\"", bytes.escape_ascii()),
+            Seed::Dynamic(kind) => write!(f, "<{}>", kind),
+        }
+    }
+}
+
+/// Renders a seeds list, e.g. `[b"vault", <pubkey>]`.
+pub fn seeds_to_string(seeds: &[Seed]) -> String {
+    format!(
+        "[{}]",
+        seeds
+            .iter()
+            .map(Seed::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+/// Removes the conversions that don't change the bytes of a seed, e.g. `&x.as_ref()` -> `x`.
+fn strip_conversions(mut expr: &Expr) -> &Expr {
+    loop {
+        expr = match expr {
+            Expr::Reference(reference) => &reference.expr,
+            Expr::Paren(paren) => &paren.expr,
+            Expr::Group(group) => &group.expr,
+            Expr::MethodCall(call)
+                if call.args.is_empty()
+                    && matches!(
+                        call.method.to_string().as_str(),
+                        "as_ref" | "as_bytes" | "as_slice" | "to_vec" | "to_bytes"
+                    ) =>
+            {
+                &call.receiver
+            }
+            _ => return expr,
+        }
+    }
+}
+
+/// Returns the kind of the value of an `#[instruction(...)]` argument of type `ty`.
+fn argument_kind(ty: &Type) -> &'static str {
+    let Type::Path(path) = ty else {
+        return "bytes";
+    };
+    match path
+        .path
+        .segments
+        .last()
+        .map(|s| s.ident.to_string())
+        .as_deref()
+    {
+        Some("Pubkey") => "pubkey",
+        Some("u8") => "u8",
+        _ => "bytes",
+    }
+}
+
+/// Normalizes a seed.
+///
+/// # Arguments
+///
+/// * `expr` - The seed, an element of `seeds = [...]`.
+/// * `constants` - The byte string constants of the crate, by name and `Type::NAME`.
+/// * `instruction_args` - The `#[instruction(...)]` arguments of the accounts struct.
+pub fn normalize_seed(
+    expr: &Expr,
+    constants: &HashMap<String, Vec<u8>>,
+    instruction_args: &[(String, Type)],
+) -> Seed {
+    let expr = strip_conversions(expr);
+    if let Some(bytes) = literal_bytes(expr) {
+        return Seed::Literal(bytes);
+    }
+    let dynamic = |kind: &str| Seed::Dynamic(kind.to_string());
+    match expr {
+        Expr::Path(path) => {
+            let segments: Vec<String> = path
+                .path
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect();
+            // `crate::state::VAULT_SEED` or `Vault::SEED`
+            let constant = (1..=segments.len().min(2))
+                .rev()
+                .map(|count| segments[segments.len() - count..].join("::"))
+                .find_map(|name| constants.get(&name));
+            if let Some(bytes) = constant {
+                return Seed::Literal(bytes.clone());
+            }
+            match instruction_args
+                .iter()
+                .find(|(name, _)| path.path.is_ident(name))
+            {
+                Some((_, ty)) => dynamic(argument_kind(ty)),
+                None => dynamic("bytes"),
+            }
+        }
+        Expr::MethodCall(call) => match call.method.to_string().as_str() {
+            "key" => dynamic("pubkey"),
+            "to_le_bytes" => dynamic("le_bytes"),
+            "to_be_bytes" => dynamic("be_bytes"),
+            _ => dynamic("bytes"),
+        },
+        Expr::Field(field) if matches!(&field.member, syn::Member::Named(name) if name == "key") => {
+            dynamic("pubkey")
+        }
+        // `&[bump]`
+        Expr::Array(array) if array.elems.len() == 1 => dynamic("u8"),
+        _ => dynamic("bytes"),
+    }
+}
+
+/// A seeded account of an accounts struct.
+#[derive(Debug, Clone, Serialize)]
+pub struct SeededAccount {
+    pub accounts_struct: String,
+    pub field: String,
+    /// The state account type of the field, e.g. `Vault`.
+    pub account: String,
+    pub position: SourcePosition,
+}
+
+/// Accounts of different types whose seeds could derive the same address.
+#[derive(Debug, Clone, Serialize)]
+pub struct SeedCollision {
+    pub seeds: Vec<Seed>,
+    /// The fields holding the accounts, sorted by account type then struct.
+    pub accounts: Vec<SeededAccount>,
+}
+
+/// Finds the seed collisions among the accounts structs of a crate.
+///
+/// Only the state accounts (`Account`, `AccountLoader`, `InterfaceAccount`) are compared: an
+/// `UncheckedAccount` or `AccountInfo` with the same seeds may be the same PDA seen without
+/// its type. The accounts derived from another program (`seeds::program`) are skipped, as well
+/// as the seeds that are not a list (e.g. `seeds = Vault::seeds(...)`).
+///
+/// # Arguments
+///
+/// * `structs` - The accounts structs of the crate.
+/// * `constants` - The byte string constants of the crate.
+pub fn seed_collisions(
+    structs: &[AccountsStruct],
+    constants: &HashMap<String, Vec<u8>>,
+) -> Vec<SeedCollision> {
+    let mut by_seeds: BTreeMap<Vec<Seed>, Vec<SeededAccount>> = BTreeMap::new();
+    for accounts_struct in structs {
+        for field in &accounts_struct.fields {
+            if field.has_arg("seeds::program") {
+                continue;
+            }
+            let Some(Expr::Array(seeds)) = field.arg("seeds").and_then(|arg| arg.value.as_ref())
+            else {
+                continue;
+            };
+            let Some(account) = field.state_type() else {
+                continue;
+            };
+            let seeds = seeds
+                .elems
+                .iter()
+                .map(|seed| normalize_seed(seed, constants, &accounts_struct.instruction_args))
+                .collect();
+            by_seeds.entry(seeds).or_default().push(SeededAccount {
+                accounts_struct: accounts_struct.name.clone(),
+                field: field.name.clone(),
+                account,
+                position: field.position.clone(),
+            });
+        }
+    }
+
+    by_seeds
+        .into_iter()
+        .filter(|(_, accounts)| {
+            accounts
+                .iter()
+                .map(|account| &account.account)
+                .collect::<BTreeSet<_>>()
+                .len()
+                > 1
+        })
+        .map(|(seeds, mut accounts)| {
+            accounts.sort_by(|a, b| {
+                (&a.account, &a.accounts_struct).cmp(&(&b.account, &b.accounts_struct))
+            });
+            SeedCollision { seeds, accounts }
+        })
+        .collect()
+}
@@ -0,0 +1,408 @@
+//! Audit of the `Cargo.lock` of a project against advisories on the crates used by the Solana
+//! programs (`borsh`, the `dalek` curves, `anchor-lang`...).
+//!
+//! The advisories are shipped in `src/static/advisories/solana.toml`, and more recent or
+//! additional ones can be given with `sast --advisories <FILE>`, in the same format:
+//!
+//! ```toml
+//! [[advisory]]
+//! id = "RUSTSEC-2023-0033"
+//! package = "borsh"
+//! title = "Parsing borsh messages with ZST which are not-copy/clone is unsound"
+//! severity = "Medium"
+//! vulnerable = ["< 0.10.3"]
+//! url = "https://rustsec.org/advisories/RUSTSEC-2023-0033"
+//! ```
+//!
+//! The affected packages are part of the SAST findings, one rule `deps/<id>` per advisory,
+//! located on their entry of the `Cargo.lock`.
+
+#[cfg(feature = "cli")]
+pub mod version;
+
+#[cfg(feature = "cli")]
+use anyhow::{anyhow, Context, Result};
+#[cfg(feature = "cli")]
+use serde::Deserialize;
+#[cfg(feature = "cli")]
+use std::collections::HashMap;
+#[cfg(feature = "cli")]
+use std::path::Path;
+
+#[cfg(feature = "cli")]
+use crate::helpers::static_dir;
+#[cfg(feature = "cli")]
+use crate::parsers::syn_ast::{ColumnEncoding, SourcePosition};
+#[cfg(feature = "cli")]
+use crate::state::sast_state::{
+    Certainty, FindingTemplate, Severity, SynAstResult, SynMatchResult, SynRuleMetadata,
+};
+#[cfg(feature = "cli")]
+use crate::state::taxonomy::Taxonomy;
+#[cfg(feature = "cli")]
+use version::{Version, VersionRange};
+
+/// Prefix of the rules of the advisories, e.g. `deps/RUSTSEC-2023-0033`. `--disable-rule deps`
+/// disables the audit.
+#[cfg(any(feature = "cli", feature = "wasm"))]
+pub const DEPENDENCY_RULES: &str = "deps";
+
+/// Advisories shipped with sol-azy.
+#[cfg(feature = "cli")]
+const BUNDLED_ADVISORIES: &str = "advisories/solana.toml";
+
+/// An advisory on some versions of a crate.
+#[cfg(feature = "cli")]
+#[derive(Debug, Clone, Deserialize)]
+pub struct Advisory {
+    pub id: String,
+    pub package: String,
+    pub title: String,
+    pub severity: Severity,
+    /// The affected versions, as ranges (`>= 0.10.0, < 0.10.3`): a version is affected if it
+    /// is in one of them.
+    pub vulnerable: Vec<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+#[cfg(feature = "cli")]
+impl Advisory {
+    /// Returns the rule of the advisory, e.g. `deps/RUSTSEC-2023-0033`.
+    pub fn rule_id(&self) -> String {
+        format!("{}/{}", DEPENDENCY_RULES, self.id)
+    }
+
+    fn rule_metadata(&self) -> SynRuleMetadata {
+        let mut description = self.description.clone().unwrap_or(self.title.clone());
+        if let Some(url) = &self.url {
+            description.push_str(&format!(" ({})", url));
+        }
+        SynRuleMetadata {
+            version: "0.1.0".to_string(),
+            author: "sol-azy".to_string(),
+            name: self.title.clone(),
+            severity: self.severity.clone(),
+            certainty: Certainty::High,
+            description,
+            ..SynRuleMetadata::default()
+        }
+    }
+}
+
+#[cfg(feature = "cli")]
+#[derive(Deserialize)]
+struct AdvisoryFile {
+    #[serde(default)]
+    advisory: Vec<Advisory>,
+}
+
+/// A list of advisories, each with its parsed version ranges.
+#[cfg(feature = "cli")]
+#[derive(Debug, Default)]
+pub struct AdvisoryDb {
+    advisories: Vec<(Advisory, Vec<VersionRange>)>,
+}
+
+#[cfg(feature = "cli")]
+impl AdvisoryDb {
+    /// Parses a list of advisories, `source` naming it in the errors.
+    pub fn parse(content: &str, source: &str) -> Result<Self> {
+        let file: AdvisoryFile =
+            toml::from_str(content).map_err(|e| anyhow!("Invalid advisories {}: {}", source, e))?;
+        let advisories = file
+            .advisory
+            .into_iter()
+            .map(|advisory| {
+                let ranges = advisory
+                    .vulnerable
+                    .iter()
+                    .map(|range| VersionRange::parse(range))
+                    .collect::<Result<_>>()
+                    .with_context(|| format!("In advisory {} of {}", advisory.id, source))?;
+                Ok((advisory, ranges))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { advisories })
+    }
+
+    /// Loads the advisories shipped with sol-azy.
+    pub fn bundled() -> Result<Self> {
+        Self::parse(
+            &static_dir::read_file(BUNDLED_ADVISORIES)?,
+            BUNDLED_ADVISORIES,
+        )
+    }
+
+    /// Loads the advisories shipped with sol-azy, updated with the ones of `path`, which
+    /// replace the shipped advisories of the same ID.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let mut db = Self::bundled()?;
+        if let Some(path) = path {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Reading advisories {}", path.display()))?;
+            db.update(Self::parse(&content, &path.display().to_string())?);
+        }
+        Ok(db)
+    }
+
+    /// Adds the advisories of `update`, replacing the ones of the same ID.
+    pub fn update(&mut self, update: AdvisoryDb) {
+        self.advisories.retain(|(advisory, _)| {
+            !update
+                .advisories
+                .iter()
+                .any(|(updated, _)| updated.id == advisory.id)
+        });
+        self.advisories.extend(update.advisories);
+    }
+
+    pub fn advisories(&self) -> impl Iterator<Item = &Advisory> {
+        self.advisories.iter().map(|(advisory, _)| advisory)
+    }
+
+    /// Returns the advisories affecting a version of a package.
+    pub fn affecting<'a>(
+        &'a self,
+        package: &'a str,
+        version: &'a Version,
+    ) -> impl Iterator<Item = &'a Advisory> {
+        self.advisories
+            .iter()
+            .filter(move |(advisory, ranges)| {
+                advisory.package == package && ranges.iter().any(|range| range.contains(version))
+            })
+            .map(|(advisory, _)| advisory)
+    }
+}
+
+/// A package of a `Cargo.lock`.
+#[cfg(feature = "cli")]
+#[derive(Debug, Clone)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: Version,
+    /// Line of its `name = ...` in the lock file.
+    pub line: u32,
+}
+
+#[cfg(feature = "cli")]
+#[derive(Deserialize)]
+struct LockFile {
+    #[serde(default)]
+    package: Vec<LockEntry>,
+}
+
+#[cfg(feature = "cli")]
+#[derive(Deserialize)]
+struct LockEntry {
+    name: toml::Spanned<String>,
+    version: String,
+    /// `None` for the packages of the workspace.
+    #[serde(default)]
+    source: Option<String>,
+}
+
+/// Parses the packages of a `Cargo.lock`, without the ones of the workspace (no `source`).
+#[cfg(feature = "cli")]
+pub fn locked_packages(content: &str) -> Result<Vec<LockedPackage>> {
+    let lock: LockFile =
+        toml::from_str(content).map_err(|e| anyhow!("Invalid Cargo.lock: {}", e))?;
+    let line_of = |offset: usize| content[..offset].matches('\n').count() as u32 + 1;
+    lock.package
+        .into_iter()
+        .filter(|entry| entry.source.is_some())
+        .map(|entry| {
+            Ok(LockedPackage {
+                line: line_of(entry.name.span().start),
+                version: Version::parse(&entry.version)?,
+                name: entry.name.into_inner(),
+            })
+        })
+        .collect()
+}
+
+/// A package of the lock file affected by an advisory.
+#[cfg(feature = "cli")]
+#[derive(Debug, Clone)]
+pub struct AffectedPackage {
+    pub package: LockedPackage,
+    pub advisory: Advisory,
+}
+
+/// Checks the packages of a `Cargo.lock` against the advisories.
+///
+/// # Arguments
+///
+/// * `content` - The content of the lock file.
+/// * `db` - The advisories.
+/// * `enabled` - Whether the rule of an advisory (`deps/<id>`) is enabled.
+#[cfg(feature = "cli")]
+pub fn audit_lock(
+    content: &str,
+    db: &AdvisoryDb,
+    enabled: impl Fn(&str) -> bool,
+) -> Result<Vec<AffectedPackage>> {
+    let mut affected = vec![];
+    for package in locked_packages(content)? {
+        for advisory in db.affecting(&package.name, &package.version) {
+            if enabled(&advisory.rule_id()) {
+                affected.push(AffectedPackage {
+                    package: package.clone(),
+                    advisory: advisory.clone(),
+                });
+            }
+        }
+    }
+    Ok(affected)
+}
+
+/// Converts the affected packages of the lock file `lock_file` to SAST results, one per
+/// advisory, the match of each package being described by its `description` metadata.
+#[cfg(feature = "cli")]
+pub fn sast_results(affected: &[AffectedPackage], lock_file: &str) -> Vec<SynAstResult> {
+    let mut by_advisory: Vec<(&Advisory, Vec<SynMatchResult>)> = vec![];
+    for affected in affected {
+        let package = &affected.package;
+        let position = SourcePosition {
+            start_line: package.line,
+            start_column: 0,
+            end_line: package.line,
+            end_column: 0,
+            source_file: lock_file.to_string(),
+            column_encoding: ColumnEncoding::default(),
+        };
+        let description = format!(
+            "`{}` {} is affected by {}: {}",
+            package.name, package.version, affected.advisory.id, affected.advisory.title
+        );
+        let match_result = SynMatchResult {
+            children: vec![],
+            access_path: format!("{}@{}", package.name, package.version),
+            metadata: HashMap::from([
+                (
+                    "position".to_string(),
+                    serde_json::to_value(&position).unwrap_or_default(),
+                ),
+                ("description".to_string(), description.into()),
+            ]),
+            ident: package.name.clone(),
+            parent: "Cargo.lock".to_string(),
+            fix: None,
+        };
+        match by_advisory
+            .iter_mut()
+            .find(|(advisory, _)| advisory.id == affected.advisory.id)
+        {
+            Some((_, matches)) => matches.push(match_result),
+            None => by_advisory.push((&affected.advisory, vec![match_result])),
+        }
+    }
+
+    by_advisory
+        .into_iter()
+        .map(|(advisory, matches)| {
+            let rule_metadata = advisory.rule_metadata();
+            SynAstResult {
+                rule_filename: advisory.rule_id(),
+                result: serde_json::json!({ "matches": matches }).to_string(),
+                matches,
+                classification: Taxonomy::default().classify(&rule_metadata),
+                rule_metadata,
+                finding_template: Some(FindingTemplate {
+                    description: "{description}".to_string(),
+                    params: HashMap::new(),
+                }),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCK: &str = r#"
+version = 3
+
+[[package]]
+name = "borsh"
+version = "0.9.3"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "borsh"
+version = "0.10.3"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "ed25519-dalek"
+version = "1.0.1"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "vault"
+version = "0.1.0"
+"#;
+
+    #[test]
+    fn test_audit_lock() {
+        let range = VersionRange::parse(">= 0.10.0, < 0.10.3").unwrap();
+        assert!(range.contains(&Version::parse("0.10.2").unwrap()));
+        assert!(!range.contains(&Version::parse("0.10.3").unwrap()));
+        assert!(range.contains(&Version::parse("0.10.3-alpha.1").unwrap()));
+        assert!(!range.contains(&Version::parse("0.9.3").unwrap()));
+
+        let db = AdvisoryDb::bundled().unwrap();
+        let affected = audit_lock(LOCK, &db, |_| true).unwrap();
+        let found: Vec<(String, &str, u32)> = affected
+            .iter()
+            .map(|a| {
+                (
+                    format!("{}@{}", a.package.name, a.package.version),
+                    a.advisory.id.as_str(),
+                    a.package.line,
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("borsh@0.9.3".to_string(), "RUSTSEC-2023-0033", 5),
+                ("ed25519-dalek@1.0.1".to_string(), "RUSTSEC-2022-0093", 15),
+            ]
+        );
+
+        // an updated advisory replaces the shipped one
+        let update = AdvisoryDb::parse(
+            "[[advisory]]\nid = \"RUSTSEC-2023-0033\"\npackage = \"borsh\"\ntitle = \"t\"\n\
+            severity = \"High\"\nvulnerable = [\"< 0.11.0\"]\n",
+            "update.toml",
+        )
+        .unwrap();
+        let mut updated = AdvisoryDb::bundled().unwrap();
+        updated.update(update);
+        assert_eq!(updated.advisories().count(), db.advisories().count());
+        let affected_borsh: Vec<String> = audit_lock(LOCK, &updated, |rule| rule.contains("0033"))
+            .unwrap()
+            .iter()
+            .map(|a| a.package.version.to_string())
+            .collect();
+        assert_eq!(affected_borsh, vec!["0.9.3", "0.10.3"]);
+
+        let disabled = audit_lock(LOCK, &db, |rule| rule != "deps/RUSTSEC-2022-0093").unwrap();
+        assert_eq!(disabled.len(), 1);
+
+        let results = sast_results(&affected, "Cargo.lock");
+        assert_eq!(results[0].rule_filename, "deps/RUSTSEC-2023-0033");
+        assert_eq!(
+            results[0]
+                .render_description(&results[0].matches[0])
+                .unwrap(),
+            "`borsh` 0.9.3 is affected by RUSTSEC-2023-0033: Parsing borsh messages with ZST \
+            which are not-copy/clone is unsound"
+        );
+    }
+}
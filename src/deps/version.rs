@@ -0,0 +1,107 @@
+//! Versions of the packages of a `Cargo.lock`, and the version ranges of the advisories.
+
+use anyhow::{anyhow, Result};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// A semantic version, e.g. `0.10.3` or `1.0.0-alpha.1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    numbers: [u64; 3],
+    /// The pre-release, empty for a release.
+    pre: String,
+}
+
+impl Version {
+    /// Parses a version. The missing numbers are zeros (`1.2` is `1.2.0`), the build metadata
+    /// (`+...`) is ignored.
+    pub fn parse(version: &str) -> Result<Self> {
+        let version = version.trim();
+        let version = version.split('+').next().unwrap_or_default();
+        let (numbers, pre) = version.split_once('-').unwrap_or((version, ""));
+        let mut parsed = [0; 3];
+        let parts: Vec<&str> = numbers.split('.').collect();
+        if parts.is_empty() || parts.len() > 3 {
+            return Err(anyhow!("Invalid version '{}'", version));
+        }
+        for (number, part) in parsed.iter_mut().zip(parts) {
+            *number = part
+                .parse()
+                .map_err(|_| anyhow!("Invalid version '{}'", version))?;
+        }
+        Ok(Self {
+            numbers: parsed,
+            pre: pre.to_string(),
+        })
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // a pre-release precedes its release
+        self.numbers.cmp(&other.numbers).then_with(|| {
+            match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => self.pre.cmp(&other.pre),
+            }
+        })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let [major, minor, patch] = self.numbers;
+        write!(f, "{}.{}.{}", major, minor, patch)?;
+        if !self.pre.is_empty() {
+            write!(f, "-{}", self.pre)?;
+        }
+        Ok(())
+    }
+}
+
+/// A version range: comparisons that must all hold, e.g. `>= 0.10.0, < 0.10.3`.
+#[derive(Debug, Clone)]
+pub struct VersionRange(Vec<(Ordering, bool, Version)>);
+
+impl VersionRange {
+    /// Parses a range of comparisons separated by commas, each one of `<`, `<=`, `>`, `>=`
+    /// and `=` followed by a version.
+    pub fn parse(range: &str) -> Result<Self> {
+        range
+            .split(',')
+            .map(|comparison| {
+                let comparison = comparison.trim();
+                let (operator, version) = comparison
+                    .find(|c: char| c.is_ascii_digit())
+                    .map(|index| comparison.split_at(index))
+                    .ok_or_else(|| anyhow!("Invalid version range '{}'", range))?;
+                // the ordering of the version relative to the bound, and whether equal holds
+                let (ordering, or_equal) = match operator.trim() {
+                    "<" => (Ordering::Less, false),
+                    "<=" => (Ordering::Less, true),
+                    ">" => (Ordering::Greater, false),
+                    ">=" => (Ordering::Greater, true),
+                    "=" => (Ordering::Equal, true),
+                    _ => return Err(anyhow!("Invalid version range '{}'", range)),
+                };
+                Ok((ordering, or_equal, Version::parse(version)?))
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+
+    pub fn contains(&self, version: &Version) -> bool {
+        self.0.iter().all(|(ordering, or_equal, bound)| {
+            let actual = version.cmp(bound);
+            actual == *ordering || (*or_equal && actual == Ordering::Equal)
+        })
+    }
+}
@@ -1,16 +1,18 @@
-use indicatif::{ProgressIterator};
-use log::debug;
+use log::{debug, warn};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     fs,
-    path::Path,
+    path::{Path, PathBuf},
 };
 use crate::helpers;
+use crate::reverse::cfg_graph::{CfgEdge, CfgGraph};
 
 #[derive(Debug, Deserialize)]
 struct Config {
+    /// May be empty with `--depth`, to start from the entrypoint.
+    #[serde(default)]
     functions: Vec<String>,
 }
 
@@ -86,6 +88,226 @@ fn extract_cleaned_edge<'a>(
     None
 }
 
+/// Returns `true` if `path` is a JSON graph (`cfg.json`) rather than a `.dot` file.
+fn is_json_graph(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "json")
+}
+
+/// Parses a function of the config, either `"42"` or `"cluster_42"`.
+fn parse_cluster_id(function: &str) -> Option<usize> {
+    let function = function.trim();
+    function
+        .strip_prefix("cluster_")
+        .unwrap_or(function)
+        .parse()
+        .ok()
+}
+
+/// Lists `seeds` and the functions reachable from them within `depth` calls, breadth first.
+pub fn reachable_functions(
+    call_graph: &BTreeMap<usize, Vec<usize>>,
+    seeds: &[usize],
+    depth: usize,
+) -> Vec<usize> {
+    let mut functions = Vec::new();
+    let mut visited = HashSet::new();
+    let mut queue: VecDeque<(usize, usize)> = seeds.iter().map(|seed| (*seed, 0)).collect();
+    while let Some((function, distance)) = queue.pop_front() {
+        if !visited.insert(function) {
+            continue;
+        }
+        functions.push(function);
+        if distance == depth {
+            continue;
+        }
+        for callee in call_graph.get(&function).into_iter().flatten() {
+            queue.push_back((*callee, distance + 1));
+        }
+    }
+    functions
+}
+
+/// Applies `--depth` to the functions of the config: starting from them, or from the entrypoint
+/// when the config lists none, returns every function reachable within `depth` calls.
+fn expand_functions(
+    functions: &[String],
+    depth: usize,
+    call_graph: &BTreeMap<usize, Vec<usize>>,
+    entrypoint: Option<usize>,
+) -> Vec<String> {
+    let mut seeds: Vec<usize> = functions
+        .iter()
+        .filter_map(|function| parse_cluster_id(function))
+        .collect();
+    if functions.is_empty() {
+        match entrypoint {
+            Some(entrypoint) => seeds.push(entrypoint),
+            None => warn!("No function in the config and no entrypoint in the full graph"),
+        }
+    }
+    let expanded = reachable_functions(call_graph, &seeds, depth);
+    debug!(
+        "{} function(s) within {} call(s) of {:?}",
+        expanded.len(),
+        depth,
+        seeds
+    );
+    expanded
+        .iter()
+        .map(|function| function.to_string())
+        .collect()
+}
+
+/// Builds the call graph of a legacy `.dot` from its clusters, using the `call function_<id>`
+/// rows. Calls to functions named from the symbols can't be resolved this way.
+fn dot_call_graph(cluster_cache: &ClusterCache) -> BTreeMap<usize, Vec<usize>> {
+    let call_re = Regex::new(r#">call</td><td align="left">function_(\d+)<"#).unwrap();
+    cluster_cache
+        .clusters
+        .iter()
+        .filter_map(|(cluster_id, block)| {
+            let callees: BTreeSet<usize> = call_re
+                .captures_iter(block)
+                .filter_map(|cap| cap[1].parse().ok())
+                .collect();
+            Some((cluster_id.parse().ok()?, callees.into_iter().collect()))
+        })
+        .collect()
+}
+
+/// Returns the id of the `entrypoint` cluster of a legacy `.dot`.
+fn dot_entrypoint(cluster_cache: &ClusterCache) -> Option<usize> {
+    let label_re = Regex::new(r#"^subgraph cluster_\d+\s*\{\s*label="entrypoint";"#).unwrap();
+    cluster_cache
+        .clusters
+        .iter()
+        .find(|(_, block)| label_re.is_match(block))
+        .and_then(|(cluster_id, _)| cluster_id.parse().ok())
+}
+
+/// Adds the requested function clusters of `full` to `reduced`, along with the control flow edges
+/// of `full` whose source and destinations are then present.
+///
+/// The destinations of an edge already in `reduced` are merged into it, so each block keeps a
+/// single outgoing edge. Dominator edges are not added.
+pub fn add_functions(reduced: &CfgGraph, full: &CfgGraph, functions: &[String]) -> CfgGraph {
+    let mut updated = reduced.clone();
+
+    for function in functions {
+        let Some(start) = parse_cluster_id(function) else {
+            warn!(
+                "Invalid function '{}' in the config, expected a cluster id",
+                function
+            );
+            continue;
+        };
+        if updated
+            .clusters
+            .iter()
+            .any(|cluster| cluster.start == start)
+        {
+            continue;
+        }
+        match full.clusters.iter().find(|cluster| cluster.start == start) {
+            Some(cluster) => updated.clusters.push(cluster.clone()),
+            None => warn!("No cluster_{} in the full graph", start),
+        }
+    }
+
+    let present_blocks: HashSet<usize> = updated
+        .clusters
+        .iter()
+        .flat_map(|cluster| cluster.blocks.iter().map(|block| block.start))
+        .collect();
+
+    for edge in full.edges.iter().filter(|edge| !edge.dominator) {
+        if !present_blocks.contains(&edge.from) {
+            continue;
+        }
+        let destinations: Vec<usize> = edge
+            .to
+            .iter()
+            .copied()
+            .filter(|to| present_blocks.contains(to))
+            .collect();
+        if destinations.is_empty() {
+            continue;
+        }
+        match updated
+            .edges
+            .iter_mut()
+            .find(|existing| !existing.dominator && existing.from == edge.from)
+        {
+            Some(existing) => {
+                let merged: BTreeSet<usize> =
+                    existing.to.iter().copied().chain(destinations).collect();
+                existing.to = merged.into_iter().collect();
+            }
+            None => updated.edges.push(CfgEdge {
+                from: edge.from,
+                to: destinations,
+                dominator: false,
+            }),
+        }
+    }
+
+    updated
+}
+
+/// Returns the path of the updated graph, `updated_<stem>.<extension>` next to `reduced_path`.
+fn updated_path(reduced_path: &Path, extension: &str) -> PathBuf {
+    let parent = reduced_path.parent().unwrap_or_else(|| Path::new("."));
+    let stem = reduced_path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy();
+    parent.join(format!("updated_{}.{}", stem, extension))
+}
+
+/// JSON variant of [`editor_add_functions`]: merges the graphs with [`add_functions`], then
+/// writes `updated_<name>.json` and renders it to `updated_<name>.dot`.
+fn editor_add_functions_json(
+    functions: &[String],
+    depth: Option<usize>,
+    reduced_path: &Path,
+    full_path: &Path,
+) -> std::io::Result<()> {
+    let reduced = CfgGraph::load(reduced_path)?;
+    let full = CfgGraph::load(full_path)?;
+
+    // stable ids (`--stable-ids`) are resolved to the clusters of this build
+    let functions: Vec<String> = functions
+        .iter()
+        .map(|function| match full.cluster_start(function) {
+            Some(start) => start.to_string(),
+            None => function.clone(),
+        })
+        .collect();
+    let functions = match depth {
+        Some(depth) => {
+            let entrypoint = full
+                .clusters
+                .iter()
+                .find(|cluster| cluster.label == "entrypoint")
+                .map(|cluster| cluster.start);
+            expand_functions(&functions, depth, &full.call_graph(), entrypoint)
+        }
+        None => functions,
+    };
+    let updated = add_functions(&reduced, &full, &functions);
+
+    let json_path = updated_path(reduced_path, "json");
+    updated.save(&json_path)?;
+    helpers::events::artifact_written(&json_path);
+    let dot_path = updated_path(reduced_path, "dot");
+    std::fs::write(&dot_path, updated.to_dot())?;
+    helpers::events::artifact_written(&dot_path);
+    debug!("Updated graph saved to {:?} and {:?}", json_path, dot_path);
+
+    Ok(())
+}
+
 /// Modifies a reduced `.dot` control flow graph by adding specific function subgraphs
 /// and corresponding intra-graph edges from a full `.dot` file.
 ///
@@ -95,8 +317,10 @@ fn extract_cleaned_edge<'a>(
 /// # Arguments
 ///
 /// * `json_path` - Path to the JSON configuration file specifying function cluster IDs to restore.
-/// * `reduced_path` - Path to the reduced `.dot` file generated by `--reduced` (or `--only-entrypoint`).
-/// * `full_path` - Path to the full `.dot` file used as a reference for missing subgraphs and edges.
+/// * `reduced_path` - Path to the reduced `cfg.json` (or legacy `.dot`) generated by `--reduced` (or `--only-entrypoint`).
+/// * `full_path` - Path to the full `cfg.json` (or legacy `.dot`) used as a reference for missing subgraphs and edges.
+/// * `depth` - If set, also adds the functions reachable within `depth` calls of the requested
+///   ones (or of the entrypoint, if the config lists none), using the call graph of the full graph.
 ///
 /// # Returns
 ///
@@ -114,21 +338,42 @@ fn extract_cleaned_edge<'a>(
 ///
 /// # Behavior
 ///
+/// - With `cfg.json` graphs, the graphs are merged as JSON (see [`add_functions`]) and the result
+///   is saved as `updated_cfg.json` and rendered to `updated_cfg.dot`. The regex-based edition
+///   below is only used for `.dot` inputs.
 /// - Clusters (subgraphs) from the full DOT are added if they are not already in the reduced version.
 /// - Edges are only reinserted if both their source and all destination nodes are already present.
 /// - The result is saved to `updated_<reduced_path>`.
 ///
 /// # Errors
 ///
-/// Returns an `std::io::Error` if any file operations fail, or if JSON is malformed.
+/// Returns an `std::io::Error` if any file operations fail, if JSON is malformed, or if a JSON graph
+/// is mixed with a `.dot` file.
 pub fn editor_add_functions<P: AsRef<Path> + ToString>(
     json_path: P,    // path to config file (.json)
-    reduced_path: P, // path to reduced .dot
-    full_path: P,    // path to full .dot
+    reduced_path: P, // path to reduced cfg.json or .dot
+    full_path: P,    // path to full cfg.json or .dot
+    depth: Option<usize>,
 ) -> std::io::Result<()> {
     let json_content = std::fs::read_to_string(&json_path)?;
     let config: Config = serde_json::from_str(&json_content)?;
-    let requested_clusters: HashSet<String> = config.functions.iter().cloned().collect();
+
+    let (reduced_graph, full_graph) = (
+        Path::new(reduced_path.as_ref()),
+        Path::new(full_path.as_ref()),
+    );
+    match (is_json_graph(reduced_graph), is_json_graph(full_graph)) {
+        (true, true) => {
+            return editor_add_functions_json(&config.functions, depth, reduced_graph, full_graph)
+        }
+        (false, false) => debug!("Legacy .dot inputs, editing the DOT text"),
+        _ => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "The reduced and full graphs must both be cfg.json or both be .dot files",
+            ))
+        }
+    }
 
     let mut reduced_dot = std::fs::read_to_string(&reduced_path)?;
     let full_dot = std::fs::read_to_string(&full_path)?;
@@ -136,8 +381,19 @@ pub fn editor_add_functions<P: AsRef<Path> + ToString>(
     debug!("Adding requested subgraphs...");
     let cluster_cache = load_or_build_cluster_cache(&full_dot)?;
 
+    let functions = match depth {
+        Some(depth) => expand_functions(
+            &config.functions,
+            depth,
+            &dot_call_graph(&cluster_cache),
+            dot_entrypoint(&cluster_cache),
+        ),
+        None => config.functions,
+    };
+    let requested_clusters: HashSet<String> = functions.into_iter().collect();
+
     // Add requested subgraphs if not already in reduced
-    for cluster_id in requested_clusters.iter().progress() {
+    for cluster_id in helpers::spinner::progress_iter(requested_clusters.iter(), "Adding subgraphs") {
         if let Some(block) = cluster_cache.clusters.get(cluster_id) {
             if !reduced_dot.contains(&format!("cluster_{}", cluster_id)) {
                 if let Some(pos) = reduced_dot.rfind('}') {
@@ -160,7 +416,10 @@ pub fn editor_add_functions<P: AsRef<Path> + ToString>(
 
     // Process full dot line-by-line to find new edges
     debug!("Processing for new edges...");
-    for line in full_dot.lines().collect::<Vec<_>>().iter().progress() {
+    for line in helpers::spinner::progress_iter(
+        full_dot.lines().collect::<Vec<_>>().iter(),
+        "Processing edges",
+    ) {
         if reduced_lines.contains(*line) {
             continue;
         }
@@ -185,7 +444,114 @@ pub fn editor_add_functions<P: AsRef<Path> + ToString>(
     let out_path = parent.join(updated_filename);
 
     std::fs::write(&out_path, reduced_dot)?;
+    helpers::events::artifact_written(out_path.as_ref());
     debug!("Updated file saved to {:?}", out_path);
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reverse::cfg_graph::{CfgBlock, CfgCluster};
+
+    fn cluster(start: usize, blocks: &[usize], calls: &[usize]) -> CfgCluster {
+        CfgCluster {
+            start,
+            id: None,
+            hash: None,
+            label: format!("function_{}", start),
+            blocks: blocks
+                .iter()
+                .map(|block| CfgBlock {
+                    start: *block,
+                    id: None,
+                    name: None,
+                    instructions: vec!["exit".to_string()],
+                    comments: BTreeMap::new(),
+                    bookmarks: vec![],
+                    strings: vec![],
+                    syscalls: vec![],
+                })
+                .collect(),
+            calls: calls.to_vec(),
+        }
+    }
+
+    fn edge(from: usize, to: &[usize], dominator: bool) -> CfgEdge {
+        CfgEdge {
+            from,
+            to: to.to_vec(),
+            dominator,
+        }
+    }
+
+    #[test]
+    fn test_add_functions() {
+        let reduced = CfgGraph {
+            clusters: vec![cluster(10, &[10, 12], &[20])],
+            edges: vec![edge(10, &[12], false), edge(12, &[10], true)],
+        };
+        let full = CfgGraph {
+            clusters: vec![
+                cluster(10, &[10, 12], &[20]),
+                cluster(20, &[20, 21], &[30]),
+                cluster(30, &[30], &[]),
+            ],
+            edges: vec![
+                edge(10, &[12, 20], false),
+                edge(20, &[21, 30], false),
+                edge(21, &[20], true),
+            ],
+        };
+
+        let functions = ["cluster_20".to_string(), "20".to_string(), "99".to_string()];
+        let updated = add_functions(&reduced, &full, &functions);
+        let starts: Vec<usize> = updated.clusters.iter().map(|c| c.start).collect();
+        assert_eq!(starts, vec![10, 20]);
+        assert_eq!(
+            updated.edges,
+            vec![
+                edge(10, &[12, 20], false),
+                edge(12, &[10], true),
+                edge(20, &[21], false),
+            ]
+        );
+
+        let dot = updated.to_dot();
+        assert!(dot.contains("  subgraph cluster_20 {"));
+        assert!(dot.contains("  lbb_10 -> {lbb_12 lbb_20};"));
+        assert!(dot.contains("  lbb_12 -> lbb_10 [style=dotted; arrowhead=none];"));
+        assert_eq!(
+            updated_path(Path::new("out/cfg.json"), "dot"),
+            Path::new("out/updated_cfg.dot")
+        );
+    }
+
+    #[test]
+    fn test_reachable_functions() {
+        let call_graph = BTreeMap::from([(1, vec![2, 3]), (2, vec![4]), (4, vec![1, 5])]);
+        assert_eq!(reachable_functions(&call_graph, &[1], 0), vec![1]);
+        assert_eq!(reachable_functions(&call_graph, &[1], 1), vec![1, 2, 3]);
+        assert_eq!(
+            reachable_functions(&call_graph, &[1], 3),
+            vec![1, 2, 3, 4, 5]
+        );
+        assert_eq!(
+            expand_functions(&[], 1, &call_graph, Some(4)),
+            vec!["4", "1", "5"]
+        );
+
+        let cluster_cache = ClusterCache {
+            clusters: HashMap::from([(
+                "7".to_string(),
+                "subgraph cluster_7 {\n    label=\"entrypoint\";\n    lbb_7 [label=<<table><tr><td align=\"left\">call</td><td align=\"left\">function_42</td></tr></table>>];\n  }".to_string(),
+            )]),
+        };
+        assert_eq!(dot_entrypoint(&cluster_cache), Some(7));
+        assert_eq!(
+            dot_call_graph(&cluster_cache),
+            BTreeMap::from([(7, vec![42])])
+        );
+    }
+}
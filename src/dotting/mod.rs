@@ -1,4 +1,4 @@
-//! Tools for manipulating `.dot` control flow graph (CFG) files after generation.
+//! Tools for manipulating control flow graph (CFG) files after generation.
 //!
 //! This module contains utilities to post-process and refine CFGs,
 //! especially useful when dealing with reduced graphs where only a subset
@@ -10,7 +10,8 @@
 //! ## Components
 //!
 //! - [`editor`] – Logic to add user-specified function clusters and associated edges
-//!   from the full `.dot` graph into a reduced one.
+//!   from the full graph into a reduced one. The graphs are edited as JSON (`cfg.json`) and
+//!   rendered to DOT afterwards; `.dot` inputs are still edited as text for older outputs.
 //!
 //! ## Example Use Case
 //!
@@ -20,8 +21,8 @@
 //!
 //! 1. Creating a `functions.json` file with the cluster IDs you want to restore.
 //! 2. Running the `dotting` command with paths to:
-//!    - the reduced `.dot` file,
-//!    - the full `.dot` file,
+//!    - the reduced `cfg.json` (or `.dot`) file,
+//!    - the full `cfg.json` (or `.dot`) file,
 //!    - and the JSON config.
 
 pub mod editor;
@@ -3,8 +3,13 @@
 //! This module currently includes:
 //!
 //! - [`starlark_engine`] — An engine for evaluating Starlark-based security rules against parsed Rust ASTs.
+//! - [`rules_git`] — Fetching of rule packs from Git repositories into a local cache.
+//! - [`source_text`] — The `source_text(position)` Starlark global and its file cache.
 //!
 //! Engines in this module are responsible for interpreting rule files, integrating with
 //! the syntax analysis layer, and returning structured results (e.g., matches, metadata).
 
+#[cfg(feature = "cli")]
+pub mod rules_git;
+pub mod source_text;
 pub mod starlark_engine;
@@ -0,0 +1,252 @@
+//! Fetching of Starlark rule packs hosted in Git repositories (`sast --rules-git`).
+//!
+//! A repository is cloned once into `.solazy_cache/rules/` and updated on the next runs, then
+//! loaded like a `--rules-dir`. A full commit hash as revision pins the rules: the checkout is
+//! verified against it, and the cached clone is reused without fetching.
+
+use crate::helpers::{check_binary_installed, run_command};
+use anyhow::{anyhow, Result};
+use log::{debug, info, warn};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Directory (relative to the working directory) where the rule repositories are cloned.
+pub const RULES_CACHE_DIR: &str = ".solazy_cache/rules";
+
+/// A Git repository of rules, as given to `--rules-git <url>[@rev]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RulesGitSource {
+    pub url: String,
+    /// Branch, tag or commit hash to check out; the remote `HEAD` if `None`.
+    pub rev: Option<String>,
+}
+
+impl RulesGitSource {
+    /// Parses `<url>[@rev]`.
+    ///
+    /// The `@` of SSH URLs (`git@github.com:org/rules.git`) is not taken as a revision
+    /// separator, since a revision contains neither `/` nor `:`.
+    ///
+    /// A URL or revision starting with `-` is refused, since git would take it as an option
+    /// (e.g. `--upload-pack=<command>`).
+    pub fn parse(spec: &str) -> Result<Self> {
+        let spec = spec.trim();
+        let (url, rev) = match spec.rsplit_once('@') {
+            Some((url, rev)) if !url.is_empty() && !rev.is_empty() && !rev.contains(['/', ':']) => {
+                (url, Some(rev.to_string()))
+            }
+            _ => (spec, None),
+        };
+        if url.is_empty() {
+            return Err(anyhow!("Empty rules repository URL"));
+        }
+        if let Some(option) = [Some(url), rev.as_deref()]
+            .into_iter()
+            .flatten()
+            .find(|part| part.starts_with('-'))
+        {
+            return Err(anyhow!(
+                "Invalid rules repository URL or revision '{}': it must not start with '-'",
+                option
+            ));
+        }
+        Ok(Self {
+            url: url.to_string(),
+            rev,
+        })
+    }
+
+    /// Returns `true` if the revision is a full commit hash.
+    pub fn is_pinned(&self) -> bool {
+        self.rev
+            .as_deref()
+            .is_some_and(|rev| rev.len() == 40 && rev.chars().all(|c| c.is_ascii_hexdigit()))
+    }
+
+    /// Name of the clone in the cache: the repository name and a hash of its URL.
+    fn cache_name(&self) -> String {
+        let name = self
+            .url
+            .trim_end_matches('/')
+            .trim_end_matches(".git")
+            .rsplit(['/', ':'])
+            .next()
+            .filter(|name| !name.is_empty())
+            .unwrap_or("rules");
+        let digest = hex::encode(Sha256::digest(self.url.as_bytes()));
+        format!("{}-{}", name, &digest[..12])
+    }
+}
+
+/// Runs `git -C <dir> <args>` and returns its trimmed output.
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let dir = dir.to_string_lossy();
+    let mut git_args = vec!["-C", dir.as_ref()];
+    git_args.extend_from_slice(args);
+    Ok(run_command("git", &git_args, vec![])?.trim().to_string())
+}
+
+/// Resolves `rev` to a commit of the clone, preferring the remote branches over stale local ones.
+fn resolve_commit(dir: &Path, rev: &str) -> Option<String> {
+    [format!("origin/{}", rev), rev.to_string()]
+        .iter()
+        .find_map(|candidate| {
+            let output = Command::new("git")
+                .arg("-C")
+                .arg(dir)
+                .args(["rev-parse", "--verify", "--quiet"])
+                .arg(format!("{}^{{commit}}", candidate))
+                .stderr(Stdio::null())
+                .output()
+                .ok()?;
+            output
+                .status
+                .success()
+                .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        })
+}
+
+/// Clones or updates the rules repository in `cache_root` and checks out the requested revision.
+///
+/// # Arguments
+///
+/// * `source` - The repository and revision.
+/// * `cache_root` - Directory containing the clones (usually [`RULES_CACHE_DIR`]).
+///
+/// # Returns
+///
+/// The path of the checked out rules, or an error if Git fails or if the checked out commit
+/// doesn't match a pinned revision.
+pub fn fetch_rules(source: &RulesGitSource, cache_root: &Path) -> Result<PathBuf> {
+    if !check_binary_installed(&"git".to_string()) {
+        return Err(anyhow!("git is required to fetch rules with --rules-git"));
+    }
+
+    let dir = cache_root.join(source.cache_name());
+    let cached_commit = dir
+        .join(".git")
+        .exists()
+        .then(|| git(&dir, &["rev-parse", "HEAD"]).ok())
+        .flatten();
+
+    let expected = source.rev.as_deref().filter(|_| source.is_pinned());
+    if let (Some(cached), Some(expected)) = (&cached_commit, expected) {
+        if cached.eq_ignore_ascii_case(expected) {
+            info!("Using cached rules {} at {}", source.url, dir.display());
+            return Ok(dir);
+        }
+    }
+
+    if cached_commit.is_some() {
+        info!("Updating rules {} in {}", source.url, dir.display());
+        git(&dir, &["fetch", "--quiet", "--tags", "--force", "origin"])?;
+    } else {
+        info!("Cloning rules {} into {}", source.url, dir.display());
+        std::fs::create_dir_all(cache_root)?;
+        let _ = std::fs::remove_dir_all(&dir);
+        let dir_arg = dir.to_string_lossy();
+        run_command(
+            "git",
+            &[
+                "clone",
+                "--quiet",
+                "--",
+                source.url.as_str(),
+                dir_arg.as_ref(),
+            ],
+            vec![],
+        )?;
+    }
+
+    let rev = source.rev.as_deref().unwrap_or("HEAD");
+    let commit = resolve_commit(&dir, rev)
+        .ok_or_else(|| anyhow!("Revision '{}' not found in {}", rev, source.url))?;
+    git(&dir, &["checkout", "--quiet", "--detach", commit.as_str()])?;
+
+    let head = git(&dir, &["rev-parse", "HEAD"])?;
+    if let Some(expected) = expected {
+        if !head.eq_ignore_ascii_case(expected) {
+            return Err(anyhow!(
+                "Rules {} checked out at {} instead of the pinned commit {}",
+                source.url,
+                head,
+                expected
+            ));
+        }
+    } else {
+        warn!(
+            "Rules {} are not pinned, checked out {} at commit {} (use <url>@{} to pin them)",
+            source.url, rev, head, head
+        );
+    }
+    debug!("Rules {} checked out at {}", source.url, head);
+
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rules_git_source() {
+        let source = RulesGitSource::parse("https://github.com/org/rules.git@v1.2").unwrap();
+        assert_eq!(source.url, "https://github.com/org/rules.git");
+        assert_eq!(source.rev.as_deref(), Some("v1.2"));
+        assert!(!source.is_pinned());
+
+        let source = RulesGitSource::parse("git@github.com:org/rules.git").unwrap();
+        assert_eq!(source.url, "git@github.com:org/rules.git");
+        assert_eq!(source.rev, None);
+
+        let commit = "0123456789abcdef0123456789abcdef01234567";
+        let source =
+            RulesGitSource::parse(&format!("git@github.com:org/rules.git@{}", commit)).unwrap();
+        assert_eq!(source.url, "git@github.com:org/rules.git");
+        assert!(source.is_pinned());
+        assert!(source.cache_name().starts_with("rules-"));
+
+        assert!(RulesGitSource::parse(" ").is_err());
+        assert!(RulesGitSource::parse("--upload-pack=touch /tmp/x").is_err());
+        assert!(RulesGitSource::parse("https://github.com/org/rules.git@-v1").is_err());
+    }
+
+    #[test]
+    fn test_fetch_local_rules() {
+        let root = std::env::temp_dir().join(format!("sol-azy-rules-git-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let repo = root.join("pack");
+        std::fs::create_dir_all(repo.join("anchor")).unwrap();
+        std::fs::write(repo.join("anchor/rule.star"), "").unwrap();
+        for args in [
+            vec!["init", "--quiet"],
+            vec!["add", "-A"],
+            vec![
+                "-c",
+                "user.name=t",
+                "-c",
+                "user.email=t@t",
+                "commit",
+                "--quiet",
+                "-m",
+                "init",
+            ],
+        ] {
+            git(&repo, &args).unwrap();
+        }
+        let commit = git(&repo, &["rev-parse", "HEAD"]).unwrap();
+        let url = repo.to_string_lossy().to_string();
+        let cache = root.join("cache");
+
+        let dir = fetch_rules(&RulesGitSource::parse(&url).unwrap(), &cache).unwrap();
+        assert!(dir.join("anchor/rule.star").is_file());
+
+        let pinned = RulesGitSource::parse(&format!("{}@{}", url, commit)).unwrap();
+        assert_eq!(fetch_rules(&pinned, &cache).unwrap(), dir);
+        let wrong = RulesGitSource::parse(&format!("{}@{}", url, "0".repeat(40))).unwrap();
+        assert!(fetch_rules(&wrong, &cache).is_err());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
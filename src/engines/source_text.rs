@@ -0,0 +1,136 @@
+//! Access to the source text of the matched spans from Starlark rules.
+//!
+//! The `source_text(position)` global returns the exact text covered by the `position`
+//! metadata of a node, so that rules can do string checks that are awkward against the
+//! JSON AST (e.g. an `unwrap()` inside a given macro body). The files are read once per
+//! engine and kept in a [`SourceCache`], which only serves the scanned files: a position
+//! naming any other file (e.g. `~/.ssh/id_ed25519` in an external rule) gets `None`.
+
+use crate::parsers::syn_ast::SourcePosition;
+use log::debug;
+use starlark::any::ProvidesStaticType;
+use starlark::environment::GlobalsBuilder;
+use starlark::eval::Evaluator;
+use starlark::starlark_module;
+use starlark::values::none::NoneOr;
+use starlark::values::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+/// Content of the source files read by `source_text`, keyed by path.
+///
+/// Given to the evaluator through `Evaluator::extra`.
+#[derive(Debug, Default, ProvidesStaticType)]
+pub struct SourceCache {
+    /// Paths of the scanned files (the keys of the `SynAstMap`), the only ones served.
+    scanned: HashSet<String>,
+    files: Mutex<HashMap<String, Option<Arc<str>>>>,
+}
+
+impl SourceCache {
+    /// Returns a cache serving the scanned files `paths`.
+    #[cfg(any(feature = "cli", feature = "wasm"))]
+    pub fn new(paths: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            scanned: paths.into_iter().collect(),
+            files: Mutex::default(),
+        }
+    }
+
+    /// Returns the content of `path`, read on the first call.
+    ///
+    /// # Returns
+    ///
+    /// The content, or `None` if `path` isn't a scanned file or cannot be read (which is
+    /// cached as well).
+    pub fn get(&self, path: &str) -> Option<Arc<str>> {
+        if !self.scanned.contains(path) {
+            debug!("source_text: {} is not a scanned file", path);
+            return None;
+        }
+        let mut files = self.files.lock().unwrap_or_else(|e| e.into_inner());
+        files
+            .entry(path.to_string())
+            .or_insert_with(|| match std::fs::read_to_string(path) {
+                Ok(content) => Some(content.into()),
+                Err(e) => {
+                    debug!("source_text: cannot read {}: {}", path, e);
+                    None
+                }
+            })
+            .clone()
+    }
+
+    /// Returns the text covered by `position`, if its file can be read.
+    pub fn text(&self, position: &SourcePosition) -> Option<String> {
+        position.slice(&self.get(&position.source_file)?)
+    }
+}
+
+#[starlark_module]
+pub fn source_text_globals(builder: &mut GlobalsBuilder) {
+    /// Returns the source text covered by a position (`node["metadata"]["position"]`), or
+    /// `None` if the position is invalid or its file isn't a scanned file or cannot be read.
+    fn source_text<'v>(
+        position: Value<'v>,
+        eval: &mut Evaluator<'v, '_, '_>,
+    ) -> anyhow::Result<NoneOr<String>> {
+        let Some(cache) = eval
+            .extra
+            .and_then(|extra| extra.downcast_ref::<SourceCache>())
+        else {
+            return Ok(NoneOr::None);
+        };
+        let Ok(position) = serde_json::from_str::<SourcePosition>(&position.to_json()?) else {
+            return Ok(NoneOr::None);
+        };
+        Ok(NoneOr::from_option(cache.text(&position)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starlark::environment::Module;
+    use starlark::syntax::{AstModule, Dialect};
+
+    #[test]
+    fn test_source_text() {
+        let path =
+            std::env::temp_dir().join(format!("sol-azy-source-text-{}.rs", std::process::id()));
+        std::fs::write(&path, "fn f() {\n    msg!(\"{}\", x.unwrap());\n}\n").unwrap();
+        let file = path.to_string_lossy().replace('\\', "\\\\");
+        let unscanned = std::env::temp_dir().join(format!(
+            "sol-azy-source-text-unscanned-{}.rs",
+            std::process::id()
+        ));
+        std::fs::write(&unscanned, "secret").unwrap();
+        let unscanned_file = unscanned.to_string_lossy().replace('\\', "\\\\");
+
+        let code = format!(
+            r#"
+position = {{"start_line": 2, "start_column": 4, "end_line": 2, "end_column": 26, "source_file": "{file}"}}
+missing = dict(position, source_file = "{file}.missing")
+unscanned = {{"start_line": 1, "start_column": 0, "end_line": 1, "end_column": 6, "source_file": "{unscanned_file}"}}
+[source_text(position), source_text(missing), source_text(unscanned), source_text({{}})]
+"#
+        );
+        let ast = AstModule::parse("test.star", code, &Dialect::Standard).unwrap();
+        let globals = GlobalsBuilder::standard().with(source_text_globals).build();
+        let module = Module::new();
+        let cache = SourceCache::new([
+            path.to_string_lossy().to_string(),
+            format!("{}.missing", path.to_string_lossy()),
+        ]);
+        let mut eval = Evaluator::new(&module);
+        eval.extra = Some(&cache);
+        let result = eval.eval_module(ast, &globals).unwrap();
+        assert_eq!(
+            result.to_json().unwrap(),
+            r#"["msg!(\"{}\", x.unwrap())",null,null,null]"#
+        );
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&unscanned).unwrap();
+    }
+}
@@ -1,11 +1,30 @@
-use crate::helpers::static_dir;
+#[cfg(any(feature = "cli", feature = "wasm"))]
+use crate::constraints::CONSTRAINT_RULES;
+#[cfg(any(feature = "cli", feature = "wasm"))]
+use crate::deps::DEPENDENCY_RULES;
+#[cfg(any(feature = "cli", feature = "wasm"))]
+use crate::engines::source_text::source_text_globals;
+use crate::engines::source_text::SourceCache;
+use crate::helpers::{static_dir, timing};
+#[cfg(any(feature = "cli", feature = "wasm"))]
+use crate::pubkeys::PUBKEY_RULES;
 use crate::state::sast_state::SynAst;
-use log::{error, info};
+use log::error;
+#[cfg(any(feature = "cli", feature = "wasm"))]
+use log::{info, warn};
 use serde::{Deserialize, Serialize};
-use starlark::environment::{FrozenModule, Globals, GlobalsBuilder, LibraryExtension, Module};
-use starlark::eval::{Evaluator, ReturnFileLoader};
-use starlark::syntax::{AstModule, Dialect, DialectTypes};
+use starlark::codemap::FileSpanRef;
+use starlark::environment::{FrozenModule, Globals, Module};
+#[cfg(any(feature = "cli", feature = "wasm"))]
+use starlark::environment::{GlobalsBuilder, LibraryExtension};
+use starlark::eval::{BeforeStmtFuncDyn, Evaluator, ReturnFileLoader};
+#[cfg(any(feature = "cli", feature = "wasm"))]
+use starlark::syntax::DialectTypes;
+use starlark::syntax::{AstModule, Dialect};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Represents the type of input a Starlark rule operates on.
 ///
@@ -13,6 +32,7 @@ use std::collections::HashMap;
 /// - `Syn`: Abstract Syntax Tree (AST) WIP
 /// - `Mir`: Mid-level Intermediate Representation (MIR) Not yet implemented
 /// - `LlvmIr`: LLVM Intermediate Representation (LLVM IR) Not yet implemented
+#[cfg(any(feature = "cli", feature = "wasm"))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum StarlarkRuleType {
     Syn,
@@ -22,18 +42,315 @@ pub enum StarlarkRuleType {
 
 /// A representation of a single loaded Starlark rule file.
 ///
-/// This struct holds the filename, file content, and the type of the rule.
+/// This struct holds the rule ID, filename, file content, and the type of the rule.
+#[cfg(any(feature = "cli", feature = "wasm"))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StarlarkRule {
+    /// Path of the rule relative to its rules directory, without the `.star` extension
+    /// (e.g. `anchor/missing_signer_check`), used to enable or disable it.
+    pub id: String,
+    /// Path of the rule file relative to its rules directory.
     pub filename: String,
     pub content: String,
     pub rule_type: StarlarkRuleType,
+    /// `true` for the rules loaded from a rules directory, `false` for the internal ones.
+    #[serde(default)]
+    pub external: bool,
+    /// Rules directory the rule was loaded from, where its `load()` statements are resolved
+    /// (`None` for the internal rules).
+    #[serde(skip)]
+    pub rules_dir: Option<PathBuf>,
 }
 
 /// A collection of Starlark rules loaded from a directory.
+#[cfg(any(feature = "cli", feature = "wasm"))]
 pub type StarlarkRulesDir = Vec<StarlarkRule>;
 
+/// Suffix of user-supplied template files living alongside the rules.
+///
+/// These files are not evaluated as rules: they are appended to `template_manager.star`
+/// so that their `TEMPLATES[...]` and `FINDING_TEMPLATES[...]` entries are available to every rule.
+#[cfg(any(feature = "cli", feature = "wasm"))]
+pub const TEMPLATE_FILE_SUFFIX: &str = ".tmpl.star";
+
+/// Suffix of the library files of an external rules directory.
+///
+/// These files are not evaluated as rules: they hold helpers shared by the rules of a pack,
+/// which `load()` them by path relative to their own file.
+#[cfg(any(feature = "cli", feature = "wasm"))]
+pub const LIBRARY_FILE_SUFFIX: &str = ".lib.star";
+
+/// Name of the optional configuration file at the root of an external rules directory.
+#[cfg(any(feature = "cli", feature = "wasm"))]
+pub const RULES_CONFIG_FILE: &str = "solazy-rules.toml";
+
+/// Selection of the rules to evaluate, by rule ID.
+///
+/// A pattern matches a rule ID exactly, or every rule below a directory: `anchor` matches
+/// `anchor/missing_signer_check`. When `enable` is not empty, only the matching rules are kept;
+/// rules matching `disable` are then removed.
+#[cfg(any(feature = "cli", feature = "wasm"))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RuleFilter {
+    pub enable: Vec<String>,
+    pub disable: Vec<String>,
+}
+
+#[cfg(any(feature = "cli", feature = "wasm"))]
+impl RuleFilter {
+    /// Reads the `enable` / `disable` lists of the `solazy-rules.toml` file of a rules
+    /// directory, if it has one.
+    pub fn load_config(rules_dir: &Path) -> anyhow::Result<Self> {
+        let config_path = rules_dir.join(RULES_CONFIG_FILE);
+        if !config_path.is_file() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&config_path)?;
+        let filter = toml::from_str(&content).map_err(|e| {
+            anyhow::anyhow!("Invalid rules configuration {}: {}", config_path.display(), e)
+        })?;
+        info!("Loaded rules configuration {}", config_path.display());
+        Ok(filter)
+    }
+
+    /// Adds the patterns of `other` to this filter.
+    pub fn merge(mut self, other: &RuleFilter) -> Self {
+        self.enable.extend(other.enable.iter().cloned());
+        self.disable.extend(other.disable.iter().cloned());
+        self
+    }
+
+    pub(crate) fn matches(pattern: &str, id: &str) -> bool {
+        let pattern = pattern.trim_end_matches(".star").trim_end_matches('/');
+        id == pattern || id.strip_prefix(pattern).is_some_and(|rest| rest.starts_with('/'))
+    }
+
+    /// Returns `true` if the rule `id` is selected by this filter.
+    pub fn is_enabled(&self, id: &str) -> bool {
+        (self.enable.is_empty() || self.enable.iter().any(|p| Self::matches(p, id)))
+            && !self.disable.iter().any(|p| Self::matches(p, id))
+    }
+
+    /// Keeps the selected rules, warning about the patterns matching no rule.
+    pub fn apply(&self, rules: StarlarkRulesDir) -> StarlarkRulesDir {
+        for pattern in self.enable.iter().chain(&self.disable) {
+            // the built-in constraint checks, key inventory and advisories are selected with
+            // the same patterns
+            if !rules.iter().any(|rule| Self::matches(pattern, &rule.id))
+                && !CONSTRAINT_RULES
+                    .iter()
+                    .chain(PUBKEY_RULES)
+                    .any(|id| Self::matches(pattern, id))
+                && !Self::matches(DEPENDENCY_RULES, pattern)
+            {
+                warn!("Rule pattern '{}' doesn't match any loaded rule", pattern);
+            }
+        }
+        rules
+            .into_iter()
+            .filter(|rule| {
+                let enabled = self.is_enabled(&rule.id);
+                if !enabled {
+                    info!("Rule {} is disabled", rule.id);
+                }
+                enabled
+            })
+            .collect()
+    }
+}
+
+/// Trust policy for the external rules.
+///
+/// With `trusted_only` (`--trusted-rules-only`), the rules and templates of a rules directory
+/// are refused, unless their ID matches one of the `allowed` patterns (same syntax as
+/// [`RuleFilter`]). The internal rules are always trusted.
+#[cfg(any(feature = "cli", feature = "wasm"))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RuleTrust {
+    pub trusted_only: bool,
+    pub allowed: Vec<String>,
+}
+
+#[cfg(any(feature = "cli", feature = "wasm"))]
+impl RuleTrust {
+    fn is_allowed(&self, id: &str) -> bool {
+        !self.trusted_only || self.allowed.iter().any(|p| RuleFilter::matches(p, id))
+    }
+
+    /// Checks that every external rule and template is allowed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error listing the refused rules and templates.
+    pub fn check(
+        &self,
+        rules: &[StarlarkRule],
+        templates: &[StarlarkTemplateFile],
+    ) -> anyhow::Result<()> {
+        let refused: Vec<&str> = rules
+            .iter()
+            .filter(|rule| rule.external)
+            .map(|rule| rule.id.as_str())
+            .chain(
+                templates
+                    .iter()
+                    .map(|template| template.filename.trim_end_matches(TEMPLATE_FILE_SUFFIX)),
+            )
+            .filter(|id| !self.is_allowed(id))
+            .collect();
+        if refused.is_empty() {
+            return Ok(());
+        }
+        Err(anyhow::anyhow!(
+            "Untrusted external rules refused by --trusted-rules-only: {} (allow them with --trust-rule)",
+            refused.join(", ")
+        ))
+    }
+}
+
+/// Execution limits of a rule, so that a misbehaving rule fails instead of hanging the scan.
+///
+/// The limits are checked before each Starlark statement executed for the rule, including the
+/// ones of the library functions it calls (`syn_ast.star`, ...), but not within a builtin call:
+/// a single statement allocating past the heap limit (e.g. `[0] * 10**10`) is only stopped by
+/// the memory of the machine. `None` disables a limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleLimits {
+    /// Maximum number of statements executed.
+    pub max_steps: Option<u64>,
+    /// Maximum wall-clock duration of the evaluation.
+    pub timeout: Option<Duration>,
+    /// Maximum size of the Starlark heap of the rule, in bytes.
+    pub max_heap_bytes: Option<usize>,
+}
+
+/// Default statement budget of a rule.
+pub const DEFAULT_RULE_MAX_STEPS: u64 = 100_000_000;
+/// Default wall-clock timeout of a rule, in seconds.
+pub const DEFAULT_RULE_TIMEOUT_SECS: u64 = 120;
+/// Default maximum heap of a rule, in MiB.
+pub const DEFAULT_RULE_MAX_HEAP_MB: usize = 4096;
+
+impl Default for RuleLimits {
+    fn default() -> Self {
+        Self {
+            max_steps: Some(DEFAULT_RULE_MAX_STEPS),
+            timeout: Some(Duration::from_secs(DEFAULT_RULE_TIMEOUT_SECS)),
+            max_heap_bytes: Some(DEFAULT_RULE_MAX_HEAP_MB.saturating_mul(1024 * 1024)),
+        }
+    }
+}
+
+impl RuleLimits {
+    /// No limit at all.
+    pub fn unlimited() -> Self {
+        Self {
+            max_steps: None,
+            timeout: None,
+            max_heap_bytes: None,
+        }
+    }
+
+    /// Builds the limits from command line values, `0` disabling a limit.
+    ///
+    /// Fails if the heap limit doesn't fit in a `usize` once converted to bytes.
+    #[cfg(feature = "cli")]
+    pub fn from_options(
+        max_steps: u64,
+        timeout_secs: u64,
+        max_heap_mb: usize,
+    ) -> anyhow::Result<Self> {
+        let max_heap_bytes = max_heap_mb
+            .checked_mul(1024 * 1024)
+            .ok_or_else(|| anyhow::anyhow!("Rule heap limit too large: {} MiB", max_heap_mb))?;
+        Ok(Self {
+            max_steps: (max_steps > 0).then_some(max_steps),
+            timeout: (timeout_secs > 0).then(|| Duration::from_secs(timeout_secs)),
+            max_heap_bytes: (max_heap_bytes > 0).then_some(max_heap_bytes),
+        })
+    }
+
+    fn is_unlimited(&self) -> bool {
+        *self == Self::unlimited()
+    }
+}
+
+/// Parses a `--rule-max-heap-mb` value, refusing the ones that overflow once converted to bytes.
+#[cfg(feature = "cli")]
+pub fn parse_max_heap_mb(value: &str) -> anyhow::Result<usize> {
+    let max_heap_mb = value
+        .trim()
+        .parse::<usize>()
+        .map_err(|e| anyhow::anyhow!("Invalid heap limit {}: {}", value, e))?;
+    RuleLimits::from_options(0, 0, max_heap_mb)?;
+    Ok(max_heap_mb)
+}
+
+/// Enforces the `RuleLimits` of an evaluation, called by the evaluator before each statement.
+struct LimitGuard {
+    rule: String,
+    limits: RuleLimits,
+    started: Instant,
+    steps: u64,
+}
+
+impl LimitGuard {
+    fn new(rule: &str, limits: RuleLimits) -> Self {
+        Self {
+            rule: rule.to_string(),
+            limits,
+            started: Instant::now(),
+            steps: 0,
+        }
+    }
+}
+
+impl<'a, 'e: 'a> BeforeStmtFuncDyn<'a, 'e> for LimitGuard {
+    fn call<'v>(
+        &mut self,
+        _span: FileSpanRef,
+        eval: &mut Evaluator<'v, 'a, 'e>,
+    ) -> starlark::Result<()> {
+        self.steps += 1;
+        let exceeded = if self.limits.max_steps.is_some_and(|max| self.steps > max) {
+            Some(format!("exceeded {} steps", self.steps - 1))
+        } else if self
+            .limits
+            .timeout
+            .is_some_and(|timeout| self.started.elapsed() > timeout)
+        {
+            Some(format!(
+                "timed out after {:.1}s",
+                self.started.elapsed().as_secs_f64()
+            ))
+        } else {
+            let allocated = eval.heap().allocated_bytes();
+            self.limits
+                .max_heap_bytes
+                .filter(|max| allocated > *max)
+                .map(|max| format!("heap of {} bytes exceeds {} bytes", allocated, max))
+        };
+        match exceeded {
+            Some(reason) => Err(starlark::Error::new_other(anyhow::anyhow!(
+                "Rule {} {}",
+                self.rule,
+                reason
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A user-supplied template file loaded from the rules directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StarlarkTemplateFile {
+    pub filename: String,
+    pub content: String,
+}
+
 /// A trait for loading Starlark rule files from a directory.
+#[cfg(any(feature = "cli", feature = "wasm"))]
 pub trait StarlarkRuleDirExt
 where
     Self: Sized,
@@ -44,24 +361,37 @@ where
     ///
     /// * `rules_dir` - The path to the directory containing the rule files.
     /// * `use_internal_rules` - A boolean indicating whether to include built-in rules.
-    fn new_from_dir(rules_dir: Option<String>, use_internal_rules: bool) -> anyhow::Result<Self>;
+    /// * `filter` - The rules to enable or disable, on top of the directory configuration.
+    fn new_from_dir(
+        rules_dir: Option<String>,
+        use_internal_rules: bool,
+        filter: &RuleFilter,
+    ) -> anyhow::Result<Self>;
 }
 
+#[cfg(any(feature = "cli", feature = "wasm"))]
 impl StarlarkRuleDirExt for StarlarkRulesDir {
-    /// Loads all `.star` files from the specified directory and, if requested,
-    /// includes the internal (built-in) rules.
+    /// Loads all `.star` files from the specified directory and its subdirectories and, if
+    /// requested, includes the internal (built-in) rules, then keeps the enabled ones.
     ///
     /// # Arguments
     ///
     /// * `rules_dir` - Path to the directory containing external Starlark `.star` rule files.
     /// * `use_internal_rules` - If `true`, loads the bundled internal rules.
+    /// * `filter` - Rules enabled or disabled from the command line, merged with the
+    ///   `solazy-rules.toml` file of the rules directory.
     ///
     /// # Returns
     ///
     /// A `Result` containing a vector of `StarlarkRule` objects on success, or an error
     /// if the directory is invalid or contains faulty files.
-    fn new_from_dir(rules_dir: Option<String>, use_internal_rules: bool) -> anyhow::Result<Self> {
+    fn new_from_dir(
+        rules_dir: Option<String>,
+        use_internal_rules: bool,
+        filter: &RuleFilter,
+    ) -> anyhow::Result<Self> {
         let mut rules = Vec::new();
+        let mut filter = filter.clone();
 
         if use_internal_rules {
             let internal_rules = load_internal_rules()?;
@@ -73,9 +403,10 @@ impl StarlarkRuleDirExt for StarlarkRulesDir {
             validate_rules_directory(path, &dir_path)?;
             let external_rules = load_external_rules(path, &dir_path)?;
             rules.extend(external_rules);
+            filter = RuleFilter::load_config(path)?.merge(&filter);
         }
 
-        Ok(rules)
+        Ok(filter.apply(rules))
     }
 }
 
@@ -89,6 +420,7 @@ impl StarlarkRuleDirExt for StarlarkRulesDir {
 /// # Returns
 ///
 /// An empty `Result` on success, or an error if validation fails.
+#[cfg(any(feature = "cli", feature = "wasm"))]
 fn validate_rules_directory(path: &std::path::Path, rules_dir: &String) -> anyhow::Result<()> {
     if !path.exists() {
         error!("Rules directory does not exist: {}", rules_dir);
@@ -111,6 +443,7 @@ fn validate_rules_directory(path: &std::path::Path, rules_dir: &String) -> anyho
 /// # Returns
 ///
 /// A `Result` containing a vector of `StarlarkRule` objects, or an I/O error.
+#[cfg(any(feature = "cli", feature = "wasm"))]
 fn load_internal_rules() -> anyhow::Result<Vec<StarlarkRule>> {
     static_dir::read_all_files_in_dir("starlark_rules/syn_ast")?
         .into_iter()
@@ -125,15 +458,74 @@ fn load_internal_rules() -> anyhow::Result<Vec<StarlarkRule>> {
             info!("Loaded internal rule {}", filename);
 
             Ok(StarlarkRule {
+                id: filename.trim_end_matches(".star").to_string(),
                 filename,
                 content,
                 rule_type: StarlarkRuleType::Syn,
+                external: false,
+                rules_dir: None,
             })
         })
         .collect()
 }
 
-/// Loads external Starlark rules from a specified filesystem directory.
+/// Lists the `.star` files of a directory and its subdirectories, sorted by path.
+///
+/// Hidden directories (e.g. `.git`) are skipped, and so are the symlinks to a directory being
+/// listed, which would make the walk endless.
+#[cfg(any(feature = "cli", feature = "wasm"))]
+fn find_star_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    collect_star_files(dir, &mut Vec::new())
+}
+
+/// Walk of [`find_star_files`], `ancestors` holding the canonical paths of the directories
+/// being listed.
+#[cfg(any(feature = "cli", feature = "wasm"))]
+fn collect_star_files(dir: &Path, ancestors: &mut Vec<PathBuf>) -> std::io::Result<Vec<PathBuf>> {
+    let canonical = std::fs::canonicalize(dir)?;
+    if ancestors.contains(&canonical) {
+        warn!(
+            "Skipping {}, a symlink to one of its parent directories",
+            dir.display()
+        );
+        return Ok(Vec::new());
+    }
+    ancestors.push(canonical);
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            let hidden = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with('.'));
+            if !hidden {
+                files.extend(collect_star_files(&path, ancestors)?);
+            }
+        } else if path.extension().and_then(|ext| ext.to_str()) == Some("star") {
+            files.push(path);
+        }
+    }
+    ancestors.pop();
+    files.sort();
+    Ok(files)
+}
+
+/// Returns the path of `path` relative to `root`, with `/` separators.
+fn relative_path(path: &Path, root: &Path) -> anyhow::Result<String> {
+    let relative = path
+        .strip_prefix(root)
+        .map_err(|_| anyhow::anyhow!("Invalid filename"))?;
+    Ok(relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/"))
+}
+
+/// Loads external Starlark rules from a specified filesystem directory, recursively.
+///
+/// Each rule is identified by its path relative to the directory, without extension.
 ///
 /// # Arguments
 ///
@@ -143,24 +535,18 @@ fn load_internal_rules() -> anyhow::Result<Vec<StarlarkRule>> {
 /// # Returns
 ///
 /// A `Result` containing a vector of `StarlarkRule` objects, or an I/O error.
+#[cfg(any(feature = "cli", feature = "wasm"))]
 fn load_external_rules(
     path: &std::path::Path,
     rules_dir: &String,
 ) -> anyhow::Result<Vec<StarlarkRule>> {
-    std::fs::read_dir(path)?
-        .filter_map(Result::ok)
-        .map(|entry| entry.path())
-        .filter(|path| {
-            path.is_file() && path.extension().and_then(|ext| ext.to_str()) == Some("star")
-        })
-        .map(|path| {
-            let filename = path
-                .file_name()
-                .and_then(|name| name.to_str())
-                .ok_or_else(|| anyhow::anyhow!("Invalid filename"))?
-                .to_string();
+    find_star_files(path)?
+        .into_iter()
+        .filter(|rule_path| !is_template_file(rule_path) && !is_library_file(rule_path))
+        .map(|rule_path| {
+            let filename = relative_path(&rule_path, path)?;
 
-            let content = std::fs::read_to_string(&path)?;
+            let content = std::fs::read_to_string(&rule_path)?;
 
             // TODO: get rule_type
             let rule_type = StarlarkRuleType::Syn;
@@ -168,14 +554,138 @@ fn load_external_rules(
             info!("Loaded rule {} from directory {}", filename, rules_dir);
 
             Ok(StarlarkRule {
+                id: filename.trim_end_matches(".star").to_string(),
                 filename,
                 content,
                 rule_type,
+                external: true,
+                rules_dir: Some(path.to_path_buf()),
             })
         })
         .collect()
 }
 
+/// Returns `true` if `path` is a user template file (`*.tmpl.star`) rather than a rule.
+#[cfg(any(feature = "cli", feature = "wasm"))]
+fn is_template_file(path: &std::path::Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(TEMPLATE_FILE_SUFFIX))
+}
+
+/// Returns `true` if `path` is a library file (`*.lib.star`) rather than a rule.
+#[cfg(any(feature = "cli", feature = "wasm"))]
+fn is_library_file(path: &std::path::Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(LIBRARY_FILE_SUFFIX))
+}
+
+/// Resolution of the `load()` statements of an external rule and of the files it loads.
+///
+/// A module is first looked up relative to the directory of the file loading it, then in the
+/// embedded `starlark_libs/`. Loads can't leave the rules directory, nor load a file that is
+/// already being loaded.
+#[derive(Debug, Clone)]
+struct LocalLoads {
+    /// Canonical path of the rules directory.
+    root: PathBuf,
+    /// Canonical paths of the files being loaded, from the rule to the current one.
+    chain: Vec<PathBuf>,
+}
+
+impl LocalLoads {
+    #[cfg(any(feature = "cli", feature = "wasm"))]
+    fn new(rules_dir: &Path, rule_filename: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            root: rules_dir.canonicalize()?,
+            chain: vec![rules_dir.join(rule_filename).canonicalize()?],
+        })
+    }
+
+    /// Path of a file of the chain relative to the rules directory, for messages.
+    fn display(&self, path: &Path) -> String {
+        relative_path(path, &self.root).unwrap_or_else(|_| path.display().to_string())
+    }
+
+    /// Returns the file loaded by `load(module_id)` from the current file, or `None` if there
+    /// is no such file next to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is outside of the rules directory or loads itself back.
+    fn resolve(&self, module_id: &str) -> anyhow::Result<Option<PathBuf>> {
+        let current = self.chain.last().unwrap_or(&self.root);
+        let path = current.parent().unwrap_or(&self.root).join(module_id);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let path = path.canonicalize()?;
+        if !path.starts_with(&self.root) {
+            return Err(anyhow::anyhow!(
+                "load(\"{}\") of {} is outside of the rules directory",
+                module_id,
+                self.display(current)
+            ));
+        }
+        if let Some(start) = self.chain.iter().position(|loaded| *loaded == path) {
+            let cycle: Vec<String> = self.chain[start..]
+                .iter()
+                .chain(std::iter::once(&path))
+                .map(|loaded| self.display(loaded))
+                .collect();
+            return Err(anyhow::anyhow!("Cyclic load(): {}", cycle.join(" -> ")));
+        }
+        Ok(Some(path))
+    }
+
+    /// Returns the resolution of the loads of `path`, loaded from the current file.
+    fn enter(&self, path: PathBuf) -> Self {
+        let mut chain = self.chain.clone();
+        chain.push(path);
+        Self {
+            root: self.root.clone(),
+            chain,
+        }
+    }
+}
+
+/// Loads the user template files (`*.tmpl.star`) from the rules directory and its
+/// subdirectories, if any.
+///
+/// Files are sorted by path so that the order in which they override each other is stable.
+///
+/// # Arguments
+///
+/// * `rules_dir` - Optional path to the directory containing the external rules.
+///
+/// # Returns
+///
+/// A `Result` containing the template files, or an I/O error.
+#[cfg(any(feature = "cli", feature = "wasm"))]
+pub fn load_user_templates(rules_dir: Option<&str>) -> anyhow::Result<Vec<StarlarkTemplateFile>> {
+    let Some(dir_path) = rules_dir else {
+        return Ok(Vec::new());
+    };
+    let path = std::path::Path::new(dir_path);
+    if !path.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    find_star_files(path)?
+        .into_iter()
+        .filter(|template_path| is_template_file(template_path))
+        .map(|template_path| {
+            let filename = relative_path(&template_path, path)?;
+            let content = std::fs::read_to_string(&template_path)?;
+
+            info!("Loaded template file {} from directory {}", filename, dir_path);
+
+            Ok(StarlarkTemplateFile { filename, content })
+        })
+        .collect()
+}
+
 /// Provides an environment to evaluate Starlark rule files against parsed Rust ASTs.
 ///
 /// The engine is configured with a dialect that supports f-strings and type annotations.
@@ -185,6 +695,11 @@ fn load_external_rules(
 pub struct StarlarkEngine {
     pub dialect: Dialect,
     pub globals: Globals,
+    pub user_templates: Vec<StarlarkTemplateFile>,
+    /// Source files read by the `source_text` global, shared by the clones of the engine.
+    pub source_cache: Arc<SourceCache>,
+    /// Execution limits applied to each rule.
+    pub limits: RuleLimits,
 }
 
 // TODO: Script header/footer
@@ -197,6 +712,9 @@ impl StarlarkEngine {
     /// - `Typing`: For type annotation and checking.
     /// - `StructType`: For creating structured data.
     /// - `Print`: For debugging.
+    ///
+    /// It also registers `source_text(position)`, returning the source text of a node's span.
+    #[cfg(any(feature = "cli", feature = "wasm"))]
     pub fn new() -> Self {
         Self {
             dialect: Dialect {
@@ -214,14 +732,40 @@ impl StarlarkEngine {
                 LibraryExtension::Print, // ? Access to `print`
                 LibraryExtension::SetType, // ? Access to `set`
             ])
+            .with(source_text_globals) // ? `source_text(position)`, backed by `source_cache`
             .build(),
+            user_templates: Vec::new(),
+            source_cache: Arc::new(SourceCache::default()),
+            limits: RuleLimits::default(),
         }
     }
 
+    /// Sets the execution limits applied to each rule.
+    #[cfg(any(feature = "cli", feature = "wasm"))]
+    pub fn with_limits(mut self, limits: RuleLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Sets the files `source_text` may read: the scanned files `paths`.
+    #[cfg(any(feature = "cli", feature = "wasm"))]
+    pub fn with_source_files(mut self, paths: impl IntoIterator<Item = String>) -> Self {
+        self.source_cache = Arc::new(SourceCache::new(paths));
+        self
+    }
+
+    /// Registers user template files, appended to `template_manager.star` when it is loaded.
+    #[cfg(any(feature = "cli", feature = "wasm"))]
+    pub fn with_user_templates(mut self, user_templates: Vec<StarlarkTemplateFile>) -> Self {
+        self.user_templates = user_templates;
+        self
+    }
+
     /// Wraps Starlark rule source code with a standard entry point.
     ///
     /// This function adds boilerplate to import necessary modules (`syn_ast`, `template_manager`)
-    /// and defines a `syn_rule_loader` function that the engine calls to execute the rule.
+    /// and defines the functions the engine calls to execute the rule: `syn_rule_prepare`, which
+    /// decodes and prepares the syntax tree, then `syn_rule_loader`, which runs the rule on it.
     ///
     /// # Arguments
     ///
@@ -240,13 +784,17 @@ load("template_manager.star", "template_manager")
 {}
 
 # ! GENERATED
-def syn_rule_loader(ast: str) -> dict:
+def syn_rule_prepare(ast: str) -> dict:
+    decoded = json.decode(ast)
+    return syn_ast.prepare_ast(decoded["items"], decoded.get("framework"))
+
+
+# ! GENERATED
+def syn_rule_loader(prepared_ast: dict) -> dict:
     return {{
-        "matches": syn_ast.filter_result(syn_ast_rule(
-            syn_ast.prepare_ast(json.decode(ast)["items"]),
-            # json.decode(ast),
-        )),
+        "matches": syn_ast.filter_result(syn_ast_rule(prepared_ast)),
         "metadata": RULE_METADATA,
+        "finding_template": template_manager.FINDING_TEMPLATES.get(RULE_METADATA.get("template", "")),
     }}
 
 
@@ -257,6 +805,7 @@ syn_rule_loader
         )
     }
     
+    #[cfg(feature = "cli")]
     fn wrap_get_prepared_ast(code: String) -> String {
         format!(
             r#"# ! GENERATED
@@ -268,7 +817,8 @@ load("template_manager.star", "template_manager")
 
 # ! GENERATED
 def get_prepared_ast(ast: str) -> dict:
-    return syn_ast.prepare_ast(json.decode(ast)["items"])
+    decoded = json.decode(ast)
+    return syn_ast.prepare_ast(decoded["items"], decoded.get("framework"))
 
 
 get_prepared_ast
@@ -280,8 +830,8 @@ get_prepared_ast
 
     /// Evaluates a Starlark rule script against a `SynAst` structure.
     ///
-    /// This method parses the rule, loads its dependencies, sets up an evaluator, and
-    /// invokes the rule with the provided syntax tree.
+    /// This method parses the rule, loads its dependencies from the embedded libraries, sets
+    /// up an evaluator, and invokes the rule with the provided syntax tree.
     ///
     /// # Arguments
     ///
@@ -292,17 +842,58 @@ get_prepared_ast
     /// # Returns
     ///
     /// A `Result` containing a JSON string with the analysis results, or an error if evaluation fails.
+    #[allow(dead_code)]
     pub fn eval_syn_rule(
         &self,
         filename: &str,
         code: String,
         syn_ast: &SynAst,
     ) -> anyhow::Result<String> {
+        self.eval_syn_rule_with_loads(filename, code, &syn_ast.ast_json_string()?, None)
+    }
+
+    /// Evaluates a loaded rule against a `SynAst` structure.
+    ///
+    /// Unlike [`Self::eval_syn_rule`], the `load()` statements of an external rule are first
+    /// resolved relative to its file, so rule packs can share library files.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a JSON string with the analysis results, or an error if evaluation fails.
+    #[cfg(feature = "cli")]
+    pub fn eval_rule(&self, rule: &StarlarkRule, syn_ast: &SynAst) -> anyhow::Result<String> {
+        self.eval_rule_on_json(rule, &syn_ast.ast_json_string()?)
+    }
+
+    /// Same as [`Self::eval_rule`], on the serialized JSON syntax tree of a file
+    /// ([`SynAst::ast_json_string`]), so that it is serialized once for all the rules.
+    #[cfg(any(feature = "cli", feature = "wasm"))]
+    pub fn eval_rule_on_json(&self, rule: &StarlarkRule, ast_json: &str) -> anyhow::Result<String> {
+        let local_loads = match &rule.rules_dir {
+            Some(rules_dir) => Some(LocalLoads::new(rules_dir, &rule.filename)?),
+            None => None,
+        };
+        self.eval_syn_rule_with_loads(
+            &rule.filename,
+            rule.content.clone(),
+            ast_json,
+            local_loads.as_ref(),
+        )
+    }
+
+    fn eval_syn_rule_with_loads(
+        &self,
+        filename: &str,
+        code: String,
+        ast_json: &str,
+        local_loads: Option<&LocalLoads>,
+    ) -> anyhow::Result<String> {
+        let load_span = timing::span("load");
         let starlark_ast = AstModule::parse(filename, Self::wrap_syn_rule(code), &self.dialect)
             .map_err(|e| e.into_anyhow())?;
 
         let binding = starlark_ast.clone();
-        let modules_owned = self.load_modules(&binding)?;
+        let modules_owned = self.load_modules(&binding, local_loads)?;
 
         let modules_ref: HashMap<&str, &FrozenModule> =
             modules_owned.iter().map(|(k, v)| (*k, v)).collect();
@@ -314,22 +905,31 @@ get_prepared_ast
         let module = Module::new();
         let mut eval = Evaluator::new(&module);
         eval.set_loader(&loader);
+        eval.extra = Some(self.source_cache.as_ref());
+        if !self.limits.is_unlimited() {
+            let guard: Box<dyn BeforeStmtFuncDyn> =
+                Box::new(LimitGuard::new(filename, self.limits));
+            eval.before_stmt_for_dap(guard.into());
+        }
 
         let syn_rule = eval
             .eval_module(starlark_ast, &self.globals)
             .map_err(|e| e.into_anyhow())?;
+        let prepare = module
+            .get("syn_rule_prepare")
+            .ok_or_else(|| anyhow::anyhow!("{}: syn_rule_prepare is not defined", filename))?;
+        drop(load_span);
 
         let heap = eval.heap();
-        eval.eval_function(
-            syn_rule,
-            &[heap.alloc(serde_json::to_string(&syn_ast.ast_json).unwrap_or(String::new()))],
-            // &[heap.alloc(serde_json::to_string(
-            //     &starlark_syn_ast::prepare_ast(&syn_ast.ast_json)
-            // ).unwrap_or(String::new()))],
-            &[],
-        )
-        .map(|v| v.to_json())
-        .map_err(|e| e.into_anyhow())?
+        let prepared_ast = timing::time("prepare", || {
+            eval.eval_function(prepare, &[heap.alloc(ast_json)], &[])
+        })
+        .map_err(|e| e.into_anyhow())?;
+
+        let _span = timing::span("eval");
+        eval.eval_function(syn_rule, &[prepared_ast], &[])
+            .map(|v| v.to_json())
+            .map_err(|e| e.into_anyhow())?
     }
 
     /// Evaluates a Starlark script to get the prepared AST structure.
@@ -346,6 +946,7 @@ get_prepared_ast
     /// # Returns
     ///
     /// A `Result` containing a JSON string with the prepared AST, or an error if evaluation fails.
+    #[cfg(feature = "cli")]
     pub fn eval_get_prepared_ast(
         &self,
         filename: &str,
@@ -356,7 +957,7 @@ get_prepared_ast
             .map_err(|e| e.into_anyhow())?;
 
         let binding = starlark_ast.clone();
-        let modules_owned = self.load_modules(&binding)?;
+        let modules_owned = self.load_modules(&binding, None)?;
 
         let modules_ref: HashMap<&str, &FrozenModule> =
             modules_owned.iter().map(|(k, v)| (*k, v)).collect();
@@ -376,7 +977,7 @@ get_prepared_ast
         let heap = eval.heap();
         eval.eval_function(
             get_prepared_ast_fn,
-            &[heap.alloc(serde_json::to_string(&syn_ast.ast_json).unwrap_or(String::new()))],
+            &[heap.alloc(syn_ast.ast_json_string()?)],
             &[],
         )
             .map(|v| v.to_json())
@@ -397,12 +998,40 @@ get_prepared_ast
     /// A `Result` containing the `FrozenModule`, or an error if loading or freezing fails.
     fn load_frozen_module(&self, filename: &str) -> anyhow::Result<FrozenModule> {
         let code = match static_dir::read_file(filename) {
+            Ok(code) if filename == "starlark_libs/template_manager.star" => {
+                self.append_user_templates(code)
+            }
             Ok(code) => code,
             Err(e) => {
                 error!("Failed to read Starlark module {}: {}", filename, e);
                 return Err(e.into());
             }
         };
+        self.freeze_module(filename, code, None)
+    }
+
+    /// Loads a library file of an external rules directory and freezes it.
+    ///
+    /// # Arguments
+    ///
+    /// * `local_loads` - Resolution of the loads of the library, whose chain ends with its path.
+    fn load_local_module(&self, local_loads: &LocalLoads) -> anyhow::Result<FrozenModule> {
+        let path = local_loads.chain.last().unwrap_or(&local_loads.root);
+        let filename = local_loads.display(path);
+        let code = std::fs::read_to_string(path).map_err(|e| {
+            error!("Failed to read Starlark module {}: {}", filename, e);
+            e
+        })?;
+        self.freeze_module(&filename, code, Some(local_loads))
+    }
+
+    /// Evaluates the source of a module, after loading its own dependencies, and freezes it.
+    fn freeze_module(
+        &self,
+        filename: &str,
+        code: String,
+        local_loads: Option<&LocalLoads>,
+    ) -> anyhow::Result<FrozenModule> {
         let starlark_ast =
             match AstModule::parse(filename, code, &self.dialect).map_err(|e| e.into_anyhow()) {
                 Ok(ast) => ast,
@@ -413,7 +1042,7 @@ get_prepared_ast
             };
 
         let binding = starlark_ast.clone();
-        let modules_owned = self.load_modules(&binding)?;
+        let modules_owned = self.load_modules(&binding, local_loads)?;
 
         let modules_ref: HashMap<&str, &FrozenModule> =
             modules_owned.iter().map(|(k, v)| (*k, v)).collect();
@@ -439,11 +1068,27 @@ get_prepared_ast
         module.freeze().map_err(|e| e.into())
     }
 
+    /// Appends the user template files to the `template_manager.star` source.
+    ///
+    /// Each file is evaluated in the module scope, so it can add entries to `TEMPLATES`
+    /// and `FINDING_TEMPLATES` or use the helpers (`generate_call_fn_template`, ...).
+    fn append_user_templates(&self, mut code: String) -> String {
+        for template in &self.user_templates {
+            code.push_str(&format!(
+                "\n# ! USER TEMPLATE: {}\n{}\n",
+                template.filename, template.content
+            ));
+        }
+        code
+    }
+
     /// Loads all module dependencies specified in `load()` statements within a Starlark file.
     ///
     /// # Arguments
     ///
     /// * `starlark_ast` - A reference to the parsed AST of a Starlark script.
+    /// * `local_loads` - For the files of an external rules directory, resolution of the
+    ///   modules relative to the file, tried before the embedded `starlark_libs/`.
     ///
     /// # Returns
     ///
@@ -452,21 +1097,25 @@ get_prepared_ast
     fn load_modules<'a>(
         &self,
         starlark_ast: &'a AstModule,
+        local_loads: Option<&LocalLoads>,
     ) -> anyhow::Result<HashMap<&'a str, FrozenModule>> {
-        let modules = starlark_ast
-            .loads()
-            .iter()
-            .filter_map(|load| {
-                let module_id = load.module_id;
-                match self.load_frozen_module(&format!("starlark_libs/{}", module_id)) {
-                    Ok(module) => Some((module_id, module)),
-                    Err(e) => {
-                        error!("Failed to load module {}: {}", load.module_id, e);
-                        None
-                    }
+        let mut modules = HashMap::new();
+        for load in starlark_ast.loads() {
+            let module_id = load.module_id;
+            if let Some(local_loads) = local_loads {
+                if let Some(path) = local_loads.resolve(module_id)? {
+                    let module = self.load_local_module(&local_loads.enter(path))?;
+                    modules.insert(module_id, module);
+                    continue;
                 }
-            })
-            .collect();
+            }
+            match self.load_frozen_module(&format!("starlark_libs/{}", module_id)) {
+                Ok(module) => {
+                    modules.insert(module_id, module);
+                }
+                Err(e) => error!("Failed to load module {}: {}", load.module_id, e),
+            }
+        }
         Ok(modules)
     }
 }
@@ -474,7 +1123,7 @@ get_prepared_ast
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parsers::syn_ast::parse_rust_file;
+    use crate::parsers::syn_ast::{parse_rust_file, ColumnEncoding};
     use std::collections::HashMap;
     use std::path::Path;
 
@@ -491,7 +1140,7 @@ mod tests {
 
         let mut ast_map = HashMap::new();
         let program_path = "test_cases/base_anchor/programs/base_anchor/src/lib.rs";
-        parse_rust_file(&Path::new(program_path), &mut ast_map).unwrap();
+        parse_rust_file(&Path::new(program_path), &mut ast_map, ColumnEncoding::Chars).unwrap();
 
         let engine = StarlarkEngine::new();
 
@@ -506,4 +1155,591 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_find_by_type() {
+        let ty = |ident: &str| serde_json::json!({ "path": { "segments": [{ "ident": ident }] } });
+        let syn_ast = SynAst {
+            ast: syn::parse_file("").unwrap(),
+            ast_positions: crate::parsers::syn_ast::AstPositions::new(),
+            ast_json: serde_json::json!({ "items": [{ "struct": {
+                "ident": "Withdraw",
+                "fields": { "named": [
+                    { "ident": "vault", "ty": ty("AccountInfo"), "normalized_type": "AccountInfo" },
+                    { "ident": "owner", "ty": ty("Signer"), "normalized_type": "Signer" },
+                ] },
+            } }] }),
+            ast_json_file: None,
+            results: vec![],
+        };
+        let rule = r#"
+RULE_METADATA = {"version": "0.1.0", "author": "test", "name": "AccountInfo fields", "severity": "Low", "certainty": "Low", "description": ""}
+
+def syn_ast_rule(root: dict) -> list[dict]:
+    return [syn_ast.to_result(node) for node in syn_ast.find_by_type(root, "AccountInfo")]
+"#;
+        let result = StarlarkEngine::new()
+            .eval_syn_rule("find_by_type.star", rule.to_string(), &syn_ast)
+            .unwrap();
+        let result: serde_json::Value = serde_json::from_str(&result).unwrap();
+        let matches = result["matches"].as_array().unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0]["ident"], "vault");
+    }
+
+    /// Evaluates a built-in rule on a fixture file and returns its matches.
+    fn eval_internal_rule_on_file(rule: &str, program_path: &str) -> Vec<serde_json::Value> {
+        let mut ast_map = HashMap::new();
+        parse_rust_file(Path::new(program_path), &mut ast_map, ColumnEncoding::Chars).unwrap();
+        let rule_path = format!("src/static/starlark_rules/syn_ast/{}.star", rule);
+        let code = std::fs::read_to_string(&rule_path).unwrap();
+        let result = StarlarkEngine::new()
+            .with_source_files(ast_map.keys().cloned())
+            .eval_syn_rule(&rule_path, code, ast_map.values().next().unwrap())
+            .unwrap();
+        let result: serde_json::Value = serde_json::from_str(&result).unwrap();
+        result["matches"].as_array().unwrap().clone()
+    }
+
+    #[test]
+    fn test_unsigned_privileged_instruction_star() {
+        let matches = eval_internal_rule_on_file(
+            "unsigned_privileged_instruction",
+            "test_cases/signer_checks/programs/signer_checks/src/lib.rs",
+        );
+        let handlers: Vec<_> = matches.iter().map(|m| m["ident"].clone()).collect();
+        assert_eq!(
+            handlers,
+            ["vulnerable_withdraw", "vulnerable_named_signer"],
+            "{:#?}",
+            matches
+        );
+    }
+
+    #[test]
+    fn test_unchecked_cpi_program_star() {
+        let matches = eval_internal_rule_on_file(
+            "unchecked_cpi_program",
+            "test_cases/cpi_programs/programs/cpi_programs/src/lib.rs",
+        );
+        let handlers: Vec<_> = matches.iter().map(|m| m["ident"].clone()).collect();
+        assert_eq!(
+            handlers,
+            [
+                "vulnerable_transfer",
+                "vulnerable_invoke",
+                "vulnerable_constrained_invoke"
+            ],
+            "{:#?}",
+            matches
+        );
+    }
+
+    #[test]
+    fn test_constraints_of() {
+        let source = r#"
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut, seeds = [b"vault", owner.key().as_ref()], bump, has_one = owner)]
+    pub vault: Account<'info, Vault>,
+    pub owner: Signer<'info>,
+}
+"#;
+        let mut ast_map = HashMap::new();
+        crate::parsers::syn_ast::parse_rust_source(
+            "lib.rs",
+            source,
+            &mut ast_map,
+            ColumnEncoding::Chars,
+        )
+        .unwrap();
+        let rule = r#"
+RULE_METADATA = {"version": "0.1.0", "author": "test", "name": "Constraints", "severity": "Low", "certainty": "Low", "description": ""}
+
+def syn_ast_rule(root: dict) -> list[dict]:
+    fields = syn_ast.get_struct_fields(syn_ast.find_structs_by_names(root, "Deposit")[0])
+    return [{
+        field["ident"]: {
+            "seeds": [c["value"] for c in syn_ast.constraints_of(field) if c["key"] == "seeds"],
+            "has_one": syn_ast.get_account_constraint_values(field, "has_one"),
+            "bump": syn_ast.has_constraint(field, "bump"),
+        }
+        for field in fields
+    }]
+"#;
+        let result = StarlarkEngine::new()
+            .eval_syn_rule("constraints.star", rule.to_string(), &ast_map["lib.rs"])
+            .unwrap();
+        let result: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(
+            result["matches"],
+            serde_json::json!([{
+                "vault": {
+                    "seeds": ["[b\"vault\", owner.key().as_ref()]"],
+                    "has_one": [["owner"]],
+                    "bump": true,
+                },
+                "owner": { "seeds": [], "has_one": [], "bump": false },
+            }])
+        );
+    }
+
+    #[test]
+    fn test_unsafe_account_close_star() {
+        let matches = eval_internal_rule_on_file(
+            "unsafe_account_close",
+            "test_cases/close_accounts/programs/close_accounts/src/lib.rs",
+        );
+        let issue = |handler: &str| {
+            matches
+                .iter()
+                .find(|m| m["ident"] == handler)
+                .and_then(|m| m["metadata"]["issue"].as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        assert_eq!(matches.len(), 5, "{:#?}", matches);
+        assert!(issue("vulnerable_manual_close").contains("lamports of `vault`"));
+        assert!(issue("vulnerable_close_to_anyone").contains("`receiver`"));
+        assert!(issue("vulnerable_reopen").contains("init_if_needed"));
+        // the data of another account is wiped
+        assert!(issue("vulnerable_wipe_other").contains("lamports of `vault`"));
+        // the constraint of the destination doesn't involve it
+        assert!(issue("vulnerable_close_to_unrelated_constraint").contains("`receiver`"));
+    }
+
+    #[test]
+    fn test_direct_lamport_manipulation_star() {
+        let matches = eval_internal_rule_on_file(
+            "direct_lamport_manipulation",
+            "test_cases/close_accounts/programs/close_accounts/src/lib.rs",
+        );
+        let found: Vec<(u64, &str, &str, &str)> = matches
+            .iter()
+            .map(|m| {
+                (
+                    m["metadata"]["position"]["start_line"]
+                        .as_u64()
+                        .unwrap_or_default(),
+                    m["metadata"]["function"].as_str().unwrap_or_default(),
+                    m["ident"].as_str().unwrap_or_default(),
+                    m["metadata"]["operation"].as_str().unwrap_or_default(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    21,
+                    "vulnerable_manual_close",
+                    "destination",
+                    "increases (`+=`)"
+                ),
+                (22, "vulnerable_manual_close", "vault", "sets (`=`)"),
+                (30, "manual_close", "destination", "increases (`+=`)"),
+                (31, "manual_close", "vault", "sets (`=`)"),
+                (56, "vulnerable_wipe_other", "authority", "increases (`+=`)"),
+                (57, "vulnerable_wipe_other", "vault", "sets (`=`)"),
+            ]
+        );
+        // the position covers the whole expression
+        let position = &matches[1]["metadata"]["position"];
+        let columns = (
+            position["start_column"].as_u64(),
+            position["end_column"].as_u64(),
+        );
+        assert_eq!(columns, (Some(8), Some(72)));
+    }
+
+    #[test]
+    fn test_native_framework_rules() {
+        let found = |rule: &str, program: &str| -> Vec<(String, String, u64)> {
+            eval_internal_rule_on_file(rule, program)
+                .iter()
+                .map(|m| {
+                    (
+                        m["parent"].as_str().unwrap_or_default().to_string(),
+                        m["ident"].as_str().unwrap_or_default().to_string(),
+                        m["metadata"]["position"]["start_line"]
+                            .as_u64()
+                            .unwrap_or_default(),
+                    )
+                })
+                .collect()
+        };
+        let pinocchio = "test_cases/pinocchio_vault/src/lib.rs";
+        let steel = "test_cases/steel_counter/src/lib.rs";
+        let expected = |function: &str, account: &str, line| {
+            vec![(function.to_string(), account.to_string(), line)]
+        };
+
+        assert_eq!(
+            found("native_missing_signer_check", pinocchio),
+            expected("vulnerable_withdraw", "authority", 101)
+        );
+        assert_eq!(
+            found("native_missing_owner_check", pinocchio),
+            expected("vulnerable_withdraw", "vault", 101)
+        );
+        assert_eq!(
+            found("unchecked_account_borrow", pinocchio),
+            expected("load", "account", 24)
+        );
+
+        assert_eq!(
+            found("native_missing_signer_check", steel),
+            expected("process_reset", "authority_info", 87)
+        );
+        assert_eq!(
+            found("native_missing_owner_check", steel),
+            expected("process_reset", "counter_info", 87)
+        );
+        assert!(found("unchecked_account_borrow", steel).is_empty());
+
+        // the Anchor programs are left to the Anchor rules
+        let anchor = "test_cases/close_accounts/programs/close_accounts/src/lib.rs";
+        assert!(found("native_missing_owner_check", anchor).is_empty());
+    }
+
+    #[test]
+    fn test_narrowing_amount_cast_star() {
+        let matches = eval_internal_rule_on_file(
+            "narrowing_amount_cast",
+            "test_cases/amount_casts/programs/amount_casts/src/lib.rs",
+        );
+        let found: Vec<(u64, &str, &str)> = matches
+            .iter()
+            .map(|m| {
+                (
+                    m["metadata"]["position"]["start_line"]
+                        .as_u64()
+                        .unwrap_or_default(),
+                    m["ident"].as_str().unwrap_or_default(),
+                    m["metadata"]["operation"].as_str().unwrap_or_default(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (14, "amount", "a `u64 as u32` cast"),
+                (
+                    29,
+                    "amount",
+                    "`u128` arithmetic truncated back to `u64` with `as`"
+                ),
+                (47, "fee_total", "a `u128 as u64` cast"),
+            ]
+        );
+        let fixes: Vec<&str> = matches
+            .iter()
+            .map(|m| {
+                m["fix"]["edits"][0]["replacement"]
+                    .as_str()
+                    .unwrap_or_default()
+            })
+            .collect();
+        assert_eq!(
+            fixes,
+            vec![
+                "u32::try_from(amount).map_err(|_| ProgramError::ArithmeticOverflow)?",
+                "u64::try_from(amount as u128 * pool.shares as u128 / pool.total as u128).map_err(|_| ProgramError::ArithmeticOverflow)?",
+                "u64::try_from(fee_total).map_err(|_| ProgramError::ArithmeticOverflow)?",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unchecked_remaining_accounts_star() {
+        let matches = eval_internal_rule_on_file(
+            "unchecked_remaining_accounts",
+            "test_cases/remaining_accounts/programs/remaining_accounts/src/lib.rs",
+        );
+        let found: Vec<(&str, &str, &str)> = matches
+            .iter()
+            .map(|m| {
+                (
+                    m["ident"].as_str().unwrap_or_default(),
+                    m["metadata"]["callee"].as_str().unwrap_or_default(),
+                    m["metadata"]["what"].as_str().unwrap_or_default(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "vulnerable_read_vault",
+                    "`Vault::try_deserialize` on `remaining_accounts`",
+                    "the owner or key of `account`"
+                ),
+                (
+                    "vulnerable_pay_all",
+                    "a CPI on `remaining_accounts`",
+                    "the owner or key of `recipient`"
+                ),
+                (
+                    "vulnerable_mint_supply",
+                    "`Mint::unpack` on `remaining_accounts`",
+                    "the owner or key of `accounts`"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_account_data_reallocation_star() {
+        let matches = eval_internal_rule_on_file(
+            "account_data_reallocation",
+            "test_cases/reallocs/programs/reallocs/src/lib.rs",
+        );
+        let found: Vec<(&str, Vec<&str>)> = matches
+            .iter()
+            .map(|m| {
+                (
+                    m["ident"].as_str().unwrap_or_default(),
+                    m["metadata"]["instructions"]
+                        .as_array()
+                        .map(|names| names.iter().filter_map(|name| name.as_str()).collect())
+                        .unwrap_or_default(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("grow", vec!["vulnerable_grow"]),
+                ("grow_raw", vec!["vulnerable_grow_nested"]),
+            ],
+            "{:#?}",
+            matches
+        );
+    }
+
+    #[test]
+    fn test_sysvar_rules_star() {
+        let program = "test_cases/sysvars/programs/sysvars/src/lib.rs";
+        let idents = |rule: &str| -> Vec<String> {
+            eval_internal_rule_on_file(rule, program)
+                .iter()
+                .map(|m| m["ident"].as_str().unwrap_or_default().to_string())
+                .collect()
+        };
+        assert_eq!(
+            idents("clock_timestamp_comparison"),
+            ["vulnerable_unlock", "vulnerable_unlock_require"]
+        );
+        assert_eq!(idents("deprecated_rent_sysvar"), ["vulnerable_rent"]);
+        assert_eq!(
+            idents("recent_blockhashes_usage"),
+            ["vulnerable_draw", "recent_blockhashes"]
+        );
+    }
+
+    #[test]
+    fn test_load_nested_rules_with_filter() {
+        let dir = std::env::temp_dir().join(format!("sol-azy-rules-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        for file in [
+            "top.star",
+            "anchor/signer.star",
+            "anchor/owner.star",
+            "anchor/deep/cpi.star",
+            "anchor/pack.tmpl.star",
+            ".git/hooks.star",
+        ] {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+        std::fs::write(dir.join(RULES_CONFIG_FILE), "disable = [\"anchor/owner\"]\n").unwrap();
+        // a symlink cycle isn't followed
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("..", dir.join("anchor/deep/loop")).unwrap();
+        let dir_path = dir.to_string_lossy().to_string();
+
+        let ids = |filter: &RuleFilter| {
+            StarlarkRulesDir::new_from_dir(Some(dir_path.clone()), false, filter)
+                .unwrap()
+                .into_iter()
+                .map(|rule| rule.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ids(&RuleFilter::default()),
+            vec!["anchor/deep/cpi", "anchor/signer", "top"]
+        );
+        let filter = RuleFilter {
+            enable: vec!["anchor".to_string()],
+            disable: vec!["anchor/deep/cpi.star".to_string()],
+        };
+        assert_eq!(ids(&filter), vec!["anchor/signer"]);
+
+        let templates = load_user_templates(Some(&dir_path)).unwrap();
+        assert_eq!(templates.len(), 1);
+        assert_eq!(templates[0].filename, "anchor/pack.tmpl.star");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rule_limits() {
+        let syn_ast = SynAst {
+            ast: syn::parse_file("").unwrap(),
+            ast_positions: crate::parsers::syn_ast::AstPositions::new(),
+            ast_json: serde_json::json!({ "items": [] }),
+            ast_json_file: None,
+            results: vec![],
+        };
+        let rule = r#"
+RULE_METADATA = {"version": "0.1.0", "author": "test", "name": "Endless", "severity": "Low", "certainty": "Low", "description": ""}
+
+def syn_ast_rule(root: dict) -> list[dict]:
+    found = []
+    for i in range(1000000000):
+        found.append(i)
+    return []
+"#;
+        let eval = |limits: RuleLimits| {
+            StarlarkEngine::new().with_limits(limits).eval_syn_rule(
+                "endless.star",
+                rule.to_string(),
+                &syn_ast,
+            )
+        };
+
+        let steps = RuleLimits {
+            max_steps: Some(1000),
+            ..RuleLimits::unlimited()
+        };
+        let err = eval(steps).unwrap_err().to_string();
+        assert!(err.contains("exceeded 1000 steps"), "{}", err);
+
+        let timeout = RuleLimits {
+            timeout: Some(Duration::from_millis(100)),
+            ..RuleLimits::unlimited()
+        };
+        let started = Instant::now();
+        let err = eval(timeout).unwrap_err().to_string();
+        assert!(err.contains("timed out"), "{}", err);
+        assert!(started.elapsed() < Duration::from_secs(30));
+
+        let rule = rule.replace("found.append(i)", "found.append(\"x\" * 1000 + str(i))");
+        let heap = RuleLimits {
+            max_heap_bytes: Some(1024 * 1024),
+            ..RuleLimits::unlimited()
+        };
+        let err = StarlarkEngine::new()
+            .with_limits(heap)
+            .eval_syn_rule("endless.star", rule, &syn_ast)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("exceeds 1048576 bytes"), "{}", err);
+
+        assert_eq!(parse_max_heap_mb("0").unwrap(), 0);
+        assert_eq!(
+            RuleLimits::from_options(0, 0, 0).unwrap(),
+            RuleLimits::unlimited()
+        );
+        assert!(parse_max_heap_mb(&usize::MAX.to_string()).is_err());
+        assert!(RuleLimits::from_options(0, 0, usize::MAX).is_err());
+    }
+
+    #[test]
+    fn test_rule_trust() {
+        let rule = |id: &str, external: bool| StarlarkRule {
+            id: id.to_string(),
+            filename: format!("{}.star", id),
+            content: String::new(),
+            rule_type: StarlarkRuleType::Syn,
+            external,
+            rules_dir: None,
+        };
+        let rules = vec![rule("arbitrary_cpi", false), rule("anchor/signer", true)];
+        let templates = vec![StarlarkTemplateFile {
+            filename: "project.tmpl.star".to_string(),
+            content: String::new(),
+        }];
+
+        assert!(RuleTrust::default().check(&rules, &templates).is_ok());
+        let mut trust = RuleTrust {
+            trusted_only: true,
+            allowed: vec![],
+        };
+        let err = trust.check(&rules, &templates).unwrap_err().to_string();
+        assert!(err.contains("anchor/signer, project"), "{}", err);
+        assert!(!err.contains("arbitrary_cpi"), "{}", err);
+
+        trust.allowed = vec!["anchor".to_string(), "project".to_string()];
+        assert!(trust.check(&rules, &templates).is_ok());
+    }
+
+    #[test]
+    fn test_local_loads() {
+        let dir = std::env::temp_dir().join(format!("sol-azy-loads-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let files = [
+            (
+                "anchor/structs.star",
+                r#"load("../common/helpers.lib.star", "struct_nodes")
+
+RULE_METADATA = {"version": "0.1.0", "author": "test", "name": "Structs", "severity": "Low", "certainty": "Low", "description": ""}
+
+def syn_ast_rule(root: dict) -> list[dict]:
+    return [syn_ast.to_result(node) for node in struct_nodes(root)]
+"#,
+            ),
+            (
+                "common/helpers.lib.star",
+                "load(\"syn_ast.star\", \"syn_ast\")\nload(\"names.lib.star\", \"STRUCT\")\n\ndef struct_nodes(root):\n    return syn_ast.find_by_names(root, STRUCT)\n",
+            ),
+            ("common/names.lib.star", "STRUCT = \"Initialize\"\n"),
+            (
+                "cycle/a.lib.star",
+                "load(\"b.lib.star\", \"B\")\nA = B\n",
+            ),
+            (
+                "cycle/b.lib.star",
+                "load(\"a.lib.star\", \"A\")\nB = A\n",
+            ),
+            ("cycle/rule.star", "load(\"a.lib.star\", \"A\")\n"),
+        ];
+        for (file, content) in files {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        let rules = StarlarkRulesDir::new_from_dir(
+            Some(dir.to_string_lossy().to_string()),
+            false,
+            &RuleFilter::default(),
+        )
+        .unwrap();
+        let ids: Vec<&str> = rules.iter().map(|rule| rule.id.as_str()).collect();
+        assert_eq!(ids, vec!["anchor/structs", "cycle/rule"]);
+
+        let mut ast_map = HashMap::new();
+        let program_path = "test_cases/base_anchor/programs/base_anchor/src/lib.rs";
+        parse_rust_file(Path::new(program_path), &mut ast_map, ColumnEncoding::Chars).unwrap();
+        let syn_ast = ast_map.values().next().unwrap();
+        let engine = StarlarkEngine::new();
+
+        let result = engine.eval_rule(&rules[0], syn_ast).unwrap();
+        let result: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert!(!result["matches"].as_array().unwrap().is_empty());
+
+        let err = engine
+            .eval_rule(&rules[1], syn_ast)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("cycle/a.lib.star -> cycle/b.lib.star -> cycle/a.lib.star"),
+            "{}",
+            err
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,222 @@
+//! Cache of the fetched programs, so that an unchanged program is not downloaded again.
+//!
+//! A deployment is identified without downloading its bytecode: an upgradeable program by the
+//! slot of its last deployment, stored in the header of its `ProgramData` account (read alone
+//! with a `dataSlice`), a program of the older loaders being immutable. The ELF of each
+//! deployment is kept in `.solazy_cache/programs/<program id>/<deployment>.so`, with its
+//! metadata (and sha256, verified before reuse) in `<deployment>.json`.
+
+use anyhow::{anyhow, Context, Result};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use solana_sdk::pubkey::Pubkey;
+use std::path::{Path, PathBuf};
+
+use super::rpc::RpcClient;
+
+/// Directory (relative to the working directory) where the fetched programs are cached.
+pub const PROGRAMS_CACHE_DIR: &str = ".solazy_cache/programs";
+
+/// Name of the metadata file written next to the fetched program.
+pub const FETCH_METADATA_FILE: &str = "fetched_program.json";
+
+const UPGRADEABLE_LOADER: &str = "BPFLoaderUpgradeab1e11111111111111111111111";
+/// Loaders whose programs can't be modified once deployed.
+const IMMUTABLE_LOADERS: &[&str] = &[
+    "BPFLoader1111111111111111111111111111111111",
+    "BPFLoader2111111111111111111111111111111111",
+];
+
+/// Size of the `ProgramData` header: state tag (4), slot (8), optional authority (1 + 32).
+const PROGRAMDATA_HEADER_SIZE: usize = 45;
+
+/// The deployment of a program, as known before fetching its bytecode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deployment {
+    /// `ProgramData` account of an upgradeable program.
+    pub programdata: Option<String>,
+    /// Slot of the last deployment of an upgradeable program.
+    pub deploy_slot: Option<u64>,
+    /// Whether the program can't change (older loaders), and is cached once for all.
+    pub immutable: bool,
+}
+
+impl Deployment {
+    /// Name of the deployment in the cache, `None` if it can't be identified (e.g. loader v4).
+    pub fn cache_key(&self) -> Option<String> {
+        match (self.deploy_slot, self.immutable) {
+            (Some(slot), _) => Some(format!("slot_{}", slot)),
+            (None, true) => Some("immutable".to_string()),
+            (None, false) => None,
+        }
+    }
+}
+
+/// Metadata of a fetched program, written to `fetched_program.json` and to the cache.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FetchMetadata {
+    pub program_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub programdata: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deploy_slot: Option<u64>,
+    /// Slot given to `--at-slot`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub at_slot: Option<u64>,
+    /// sha256 of the ELF, as written.
+    pub sha256: String,
+    pub size: usize,
+    /// When the bytecode was downloaded from the RPC.
+    pub fetched_at: String,
+    /// Whether the bytecode was reused from the cache rather than downloaded by this run.
+    #[serde(default)]
+    pub from_cache: bool,
+}
+
+impl FetchMetadata {
+    pub fn new(program_id: &str, deployment: Option<&Deployment>, elf: &[u8]) -> Self {
+        Self {
+            program_id: program_id.to_string(),
+            programdata: deployment.and_then(|d| d.programdata.clone()),
+            deploy_slot: deployment.and_then(|d| d.deploy_slot),
+            at_slot: None,
+            sha256: hex::encode(Sha256::digest(elf)),
+            size: elf.len(),
+            fetched_at: crate::helpers::run_layout::now_utc(),
+            from_cache: false,
+        }
+    }
+
+    /// Writes the metadata as JSON at `path`.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)? + "\n")
+            .with_context(|| format!("Writing {}", path.display()))
+    }
+}
+
+/// Reads the slot of the last deployment in the header of a `ProgramData` account.
+fn deploy_slot_from_header(header: &[u8]) -> Option<u64> {
+    // UpgradeableLoaderState::ProgramData is the variant 3
+    if header.len() < 12 || header[..4] != [3, 0, 0, 0] {
+        return None;
+    }
+    Some(u64::from_le_bytes(header[4..12].try_into().ok()?))
+}
+
+/// Reads an account (or the `length` first bytes of its data) with its owner.
+async fn get_account_slice(
+    rpc: &RpcClient,
+    account: &str,
+    length: Option<usize>,
+) -> Result<(Vec<u8>, String)> {
+    let account_info = rpc
+        .get_account(account, length)
+        .await?
+        .ok_or_else(|| anyhow!("Account '{}' not found", account))?;
+    Ok((account_info.data, account_info.owner))
+}
+
+/// Identifies the current deployment of `program_id` by reading its program account and the
+/// header of its `ProgramData` account, without downloading the bytecode.
+pub async fn current_deployment(rpc: &RpcClient, program_id: &str) -> Result<Deployment> {
+    let (program, owner) = get_account_slice(rpc, program_id, None).await?;
+    if owner != UPGRADEABLE_LOADER {
+        return Ok(Deployment {
+            programdata: None,
+            deploy_slot: None,
+            immutable: IMMUTABLE_LOADERS.contains(&owner.as_str()),
+        });
+    }
+    if program.len() < 36 {
+        return Err(anyhow!("Upgradeable program account too small"));
+    }
+    let programdata = Pubkey::new_from_array(program[4..36].try_into()?).to_string();
+    let (header, _) = get_account_slice(rpc, &programdata, Some(PROGRAMDATA_HEADER_SIZE)).await?;
+    let deploy_slot = deploy_slot_from_header(&header)
+        .ok_or_else(|| anyhow!("Invalid ProgramData account '{}'", programdata))?;
+    Ok(Deployment {
+        programdata: Some(programdata),
+        deploy_slot: Some(deploy_slot),
+        immutable: false,
+    })
+}
+
+/// Paths of the cached ELF and metadata of a deployment.
+fn cache_paths(cache_dir: &Path, program_id: &str, key: &str) -> (PathBuf, PathBuf) {
+    let dir = cache_dir.join(program_id);
+    (
+        dir.join(format!("{}.so", key)),
+        dir.join(format!("{}.json", key)),
+    )
+}
+
+/// Returns the cached ELF of the deployment `key` of `program_id`, with its metadata.
+///
+/// An entry whose ELF doesn't match its recorded sha256 is ignored (and overwritten by the next
+/// fetch).
+pub fn lookup(cache_dir: &Path, program_id: &str, key: &str) -> Option<(Vec<u8>, FetchMetadata)> {
+    let (elf_path, metadata_path) = cache_paths(cache_dir, program_id, key);
+    let metadata: FetchMetadata =
+        serde_json::from_str(&std::fs::read_to_string(&metadata_path).ok()?).ok()?;
+    let elf = std::fs::read(&elf_path).ok()?;
+    if hex::encode(Sha256::digest(&elf)) != metadata.sha256 {
+        warn!(
+            "Cached program {} doesn't match its sha256, fetching it again",
+            elf_path.display()
+        );
+        return None;
+    }
+    debug!("Program found in cache: {}", elf_path.display());
+    Some((elf, metadata))
+}
+
+/// Stores the ELF of the deployment `key` of `program_id`, with its metadata.
+pub fn store(cache_dir: &Path, key: &str, elf: &[u8], metadata: &FetchMetadata) -> Result<()> {
+    let (elf_path, metadata_path) = cache_paths(cache_dir, &metadata.program_id, key);
+    if let Some(dir) = elf_path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Creating cache directory {}", dir.display()))?;
+    }
+    std::fs::write(&elf_path, elf).with_context(|| format!("Writing {}", elf_path.display()))?;
+    metadata.save(&metadata_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_program_cache() {
+        let mut header = vec![3, 0, 0, 0];
+        header.extend(250_000_000u64.to_le_bytes());
+        header.push(0);
+        assert_eq!(deploy_slot_from_header(&header), Some(250_000_000));
+        assert_eq!(deploy_slot_from_header(&[2, 0, 0, 0]), None);
+
+        let deployment = Deployment {
+            programdata: Some("Data111111111111111111111111111111111111111".to_string()),
+            deploy_slot: Some(250_000_000),
+            immutable: false,
+        };
+        let key = deployment.cache_key().unwrap();
+        assert_eq!(key, "slot_250000000");
+
+        let cache_dir = Path::new("temp_test_dir_program_cache");
+        let program_id = "Prog111111111111111111111111111111111111111";
+        let elf = b"\x7fELF program".to_vec();
+        let metadata = FetchMetadata::new(program_id, Some(&deployment), &elf);
+        assert!(lookup(cache_dir, program_id, &key).is_none());
+        store(cache_dir, &key, &elf, &metadata).unwrap();
+        assert_eq!(lookup(cache_dir, program_id, &key), Some((elf, metadata)));
+
+        // a modified entry is not reused
+        std::fs::write(
+            cache_dir.join(program_id).join("slot_250000000.so"),
+            b"other",
+        )
+        .unwrap();
+        assert!(lookup(cache_dir, program_id, &key).is_none());
+        std::fs::remove_dir_all(cache_dir).unwrap();
+    }
+}
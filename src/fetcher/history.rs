@@ -0,0 +1,323 @@
+//! Retrieval of a previous deployment of an upgradeable program.
+//!
+//! `getAccountInfo` only serves the latest state, so older bytecode is rebuilt from the
+//! transaction history (which requires an archive / Bigtable-backed RPC):
+//! 1. the `Upgrade` (or initial `DeployWithMaxDataLen`) transaction that was live at the
+//!    requested slot is located among the `ProgramData` account signatures,
+//! 2. the buffer account it consumed is rebuilt by replaying its `Write` instructions.
+
+use anyhow::{anyhow, Result};
+use log::{debug, info};
+use serde_json::{json, Value};
+use solana_sdk::{
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    loader_upgradeable_instruction::UpgradeableLoaderInstruction,
+    program_utils::limited_deserialize,
+    pubkey::Pubkey,
+};
+use std::{fs, path::Path, path::PathBuf};
+
+use super::rpc::RpcClient;
+use super::slice_from_elf_header;
+
+/// Page size used with `getSignaturesForAddress` (maximum allowed by the RPC).
+const SIGNATURES_PAGE_SIZE: u64 = 1000;
+
+/// Name of the file written for a program fetched at a given slot.
+pub fn program_filename_at_slot(slot: u64) -> String {
+    format!("fetched_program_slot_{}.so", slot)
+}
+
+/// Fetches the raw data of an account along with its owner.
+async fn get_account(rpc: &RpcClient, account: &str) -> Result<(Vec<u8>, String)> {
+    let account_info = rpc
+        .get_account(account, None)
+        .await?
+        .ok_or_else(|| anyhow!("Account '{}' not found", account))?;
+    Ok((account_info.data, account_info.owner))
+}
+
+/// Lists the successful transaction signatures (with their slot) touching `address`,
+/// newest first, starting right before `before` when provided.
+///
+/// Also returns the last signature of the page (failed ones included) to continue paging,
+/// or `None` once the history is exhausted.
+async fn get_signatures(
+    rpc: &RpcClient,
+    address: &str,
+    before: Option<&str>,
+) -> Result<(Vec<(String, u64)>, Option<String>)> {
+    let mut config = json!({ "limit": SIGNATURES_PAGE_SIZE });
+    if let Some(before) = before {
+        config["before"] = json!(before);
+    }
+    let result = rpc
+        .call("getSignaturesForAddress", json!([address, config]))
+        .await?;
+    let entries = result.as_array().cloned().unwrap_or_default();
+    let cursor = entries
+        .last()
+        .and_then(|entry| entry["signature"].as_str())
+        .map(str::to_string);
+    let signatures = entries
+        .iter()
+        .filter(|entry| entry["err"].is_null())
+        .filter_map(|entry| {
+            Some((
+                entry["signature"].as_str()?.to_string(),
+                entry["slot"].as_u64()?,
+            ))
+        })
+        .collect();
+    Ok((signatures, cursor))
+}
+
+/// A top-level instruction of the upgradeable loader, with its account keys resolved.
+struct LoaderInstruction {
+    instruction: UpgradeableLoaderInstruction,
+    accounts: Vec<String>,
+}
+
+/// Fetches a transaction and decodes its top-level upgradeable loader instructions.
+async fn get_loader_instructions(
+    rpc: &RpcClient,
+    signature: &str,
+) -> Result<Vec<LoaderInstruction>> {
+    let result = rpc
+        .call(
+            "getTransaction",
+            json!([signature, { "encoding": "json", "maxSupportedTransactionVersion": 0 }]),
+        )
+        .await?;
+    if result.is_null() {
+        return Err(anyhow!(
+            "Transaction '{}' is not available on this RPC (an archive RPC is required)",
+            signature
+        ));
+    }
+
+    // static keys first, then the ones loaded from address lookup tables (v0 transactions)
+    let as_strings = |value: &Value| -> Vec<String> {
+        value
+            .as_array()
+            .map(|keys| {
+                keys.iter()
+                    .filter_map(|k| k.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let mut account_keys = as_strings(&result["transaction"]["message"]["accountKeys"]);
+    account_keys.extend(as_strings(&result["meta"]["loadedAddresses"]["writable"]));
+    account_keys.extend(as_strings(&result["meta"]["loadedAddresses"]["readonly"]));
+
+    let loader_id = bpf_loader_upgradeable::id().to_string();
+    let mut instructions = Vec::new();
+    for ix in result["transaction"]["message"]["instructions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+    {
+        let program_index = ix["programIdIndex"].as_u64().unwrap_or(u64::MAX) as usize;
+        if account_keys.get(program_index) != Some(&loader_id) {
+            continue;
+        }
+        let data = ix["data"]
+            .as_str()
+            .and_then(|data| solana_sdk::bs58::decode(data).into_vec().ok())
+            .unwrap_or_default();
+        let Ok(instruction) = limited_deserialize(&data) else {
+            continue;
+        };
+        let accounts = ix["accounts"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|idx| account_keys.get(idx.as_u64()? as usize).cloned())
+            .collect();
+        instructions.push(LoaderInstruction {
+            instruction,
+            accounts,
+        });
+    }
+    Ok(instructions)
+}
+
+/// Finds the deployment live at `at_slot`: the most recent `Upgrade`/`DeployWithMaxDataLen`
+/// targeting `programdata` landed at or before that slot.
+///
+/// Returns the deployment signature and the buffer account it consumed.
+async fn find_deployment_at_slot(
+    rpc: &RpcClient,
+    programdata: &str,
+    at_slot: u64,
+) -> Result<(String, String)> {
+    let mut before: Option<String> = None;
+    loop {
+        let (page, cursor) = get_signatures(rpc, programdata, before.as_deref()).await?;
+        if cursor.is_none() {
+            return Err(anyhow!(
+                "No deployment of ProgramData '{}' found at or before slot {}",
+                programdata,
+                at_slot
+            ));
+        }
+        before = cursor;
+
+        for (signature, slot) in page.iter().filter(|(_, slot)| *slot <= at_slot) {
+            for ix in get_loader_instructions(rpc, signature).await? {
+                // Upgrade: [programdata, program, buffer, ...]
+                // DeployWithMaxDataLen: [payer, programdata, program, buffer, ...]
+                let (programdata_idx, buffer_idx) = match ix.instruction {
+                    UpgradeableLoaderInstruction::Upgrade => (0, 2),
+                    UpgradeableLoaderInstruction::DeployWithMaxDataLen { .. } => (1, 3),
+                    _ => continue,
+                };
+                if ix.accounts.get(programdata_idx).map(String::as_str) != Some(programdata) {
+                    continue;
+                }
+                if let Some(buffer) = ix.accounts.get(buffer_idx) {
+                    info!(
+                        "Deployment live at slot {} found in transaction {} (slot {})",
+                        at_slot, signature, slot
+                    );
+                    return Ok((signature.clone(), buffer.clone()));
+                }
+            }
+        }
+    }
+}
+
+/// Rebuilds the content of a buffer account, as it was when `deployment_signature` consumed it,
+/// by replaying its `Write` instructions back to its `InitializeBuffer`.
+async fn rebuild_buffer(
+    rpc: &RpcClient,
+    buffer: &str,
+    deployment_signature: &str,
+) -> Result<Vec<u8>> {
+    let mut writes: Vec<(u32, Vec<u8>)> = Vec::new();
+    let mut before = Some(deployment_signature.to_string());
+
+    'pages: loop {
+        let (page, cursor) = get_signatures(rpc, buffer, before.as_deref()).await?;
+        if cursor.is_none() {
+            break;
+        }
+        before = cursor;
+
+        for (signature, _) in &page {
+            let mut reached_initialization = false;
+            for ix in get_loader_instructions(rpc, signature).await? {
+                if ix.accounts.first().map(String::as_str) != Some(buffer) {
+                    continue;
+                }
+                match ix.instruction {
+                    UpgradeableLoaderInstruction::Write { offset, bytes } => {
+                        writes.push((offset, bytes))
+                    }
+                    UpgradeableLoaderInstruction::InitializeBuffer => reached_initialization = true,
+                    _ => {}
+                }
+            }
+            if reached_initialization {
+                break 'pages;
+            }
+        }
+    }
+
+    if writes.is_empty() {
+        return Err(anyhow!("No Write instruction found for buffer '{}'", buffer));
+    }
+    debug!("Replaying {} writes into buffer '{}'", writes.len(), buffer);
+
+    let len = writes
+        .iter()
+        .map(|(offset, bytes)| *offset as usize + bytes.len())
+        .max()
+        .unwrap_or_default();
+    let mut data = vec![0u8; len];
+    // signatures come newest first: replay oldest first so that rewrites win
+    for (offset, bytes) in writes.iter().rev() {
+        let offset = *offset as usize;
+        data[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+    Ok(data)
+}
+
+/// Fetches the bytecode of an upgradeable program as it was deployed at `at_slot`
+/// and writes it to `<out_dir>/fetched_program_slot_<at_slot>.so`.
+///
+/// If the current deployment was already live at that slot (or if the program is not
+/// upgradeable), the current bytecode is written. Otherwise the deployment is rebuilt from the
+/// transaction history, which requires an archive RPC (e.g. Bigtable-backed).
+///
+/// # Arguments
+///
+/// * `out_dir` - Path to the output directory.
+/// * `rpc` - Client of the Solana RPC endpoint.
+/// * `program_id` - The program ID to fetch.
+/// * `at_slot` - Slot at which the wanted deployment was live.
+///
+/// # Returns
+///
+/// The path of the written file.
+pub async fn fetch_program_at_slot_to<P: AsRef<Path>>(
+    out_dir: P,
+    rpc: &RpcClient,
+    program_id: &str,
+    at_slot: u64,
+) -> Result<PathBuf> {
+    let out_path = out_dir.as_ref().join(program_filename_at_slot(at_slot));
+
+    let (program_data, owner) = get_account(rpc, program_id).await?;
+
+    let elf = if owner != bpf_loader_upgradeable::id().to_string() {
+        // non-upgradeable programs never change once deployed
+        info!("Program '{}' is not upgradeable, fetching its only version", program_id);
+        program_data
+    } else {
+        if program_data.len() < 36 {
+            return Err(anyhow!("Upgradeable program account too small"));
+        }
+        let programdata_address =
+            Pubkey::new_from_array(program_data[4..36].try_into().unwrap()).to_string();
+        let (programdata, _) = get_account(rpc, &programdata_address).await?;
+        if programdata.len() < UpgradeableLoaderState::size_of_programdata_metadata() {
+            return Err(anyhow!("ProgramData account too small"));
+        }
+        let last_deploy_slot = u64::from_le_bytes(programdata[4..12].try_into().unwrap());
+
+        if last_deploy_slot <= at_slot {
+            info!(
+                "Current deployment (slot {}) was already live at slot {}",
+                last_deploy_slot, at_slot
+            );
+            programdata
+        } else {
+            info!(
+                "Program was upgraded at slot {} (after {}), rebuilding the previous deployment",
+                last_deploy_slot, at_slot
+            );
+            let (signature, buffer) =
+                find_deployment_at_slot(rpc, &programdata_address, at_slot).await?;
+            rebuild_buffer(rpc, &buffer, &signature).await?
+        }
+    };
+
+    let elf = slice_from_elf_header(&elf).ok_or_else(|| anyhow!("Missing ELF header"))?;
+    fs::write(&out_path, elf)?;
+    Ok(out_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_program_filename_encodes_slot() {
+        assert_eq!(
+            program_filename_at_slot(123_456_789),
+            "fetched_program_slot_123456789.so"
+        );
+    }
+}
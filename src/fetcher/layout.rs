@@ -0,0 +1,445 @@
+//! Memory map of the data of a non-executable account (`account_layout.txt`).
+//!
+//! The data is cut into segments, either by decoding it (Borsh) with an account type of an
+//! Anchor IDL, or by heuristics when no IDL is given: the 8-byte discriminator (named when
+//! its hash is known, see [`crate::reverse::account_names`]), then pubkey-looking 32-byte
+//! fields, `u64` values and runs of zeros. Each segment is printed with its offset, its label
+//! and value, followed by its hexdump.
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
+use std::fmt::Write;
+
+use crate::recap::idl::Idl;
+use crate::reverse::account_names::{account_discriminator, AccountNames};
+use crate::reverse::entropy::{shannon_entropy, PUBKEY_MIN_ENTROPY};
+
+/// Maximum number of hexdump lines printed for a segment.
+const MAX_HEXDUMP_LINES: usize = 8;
+
+/// A field of the account data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutSegment {
+    pub offset: usize,
+    pub size: usize,
+    /// Field path (`config.fee`, `items[2]`) or heuristic kind (`pubkey?`).
+    pub label: String,
+    /// Decoded value, if any.
+    pub value: Option<String>,
+}
+
+/// Borsh decoder of the account data against the types of an IDL.
+struct Decoder<'a> {
+    data: &'a [u8],
+    offset: usize,
+    idl: &'a Idl,
+    segments: Vec<LayoutSegment>,
+}
+
+impl Decoder<'_> {
+    fn take(&mut self, size: usize, label: &str) -> Result<&[u8]> {
+        let end = self
+            .offset
+            .checked_add(size)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| {
+                anyhow!(
+                    "data ends at 0x{:x} while reading `{}` ({} bytes)",
+                    self.data.len(),
+                    label,
+                    size
+                )
+            })?;
+        let bytes = &self.data[self.offset..end];
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    fn push(&mut self, offset: usize, label: &str, value: String) {
+        self.segments.push(LayoutSegment {
+            offset,
+            size: self.offset - offset,
+            label: label.to_string(),
+            value: Some(value),
+        });
+    }
+
+    fn read_len(&mut self, label: &str) -> Result<usize> {
+        let offset = self.offset;
+        let len = u32::from_le_bytes(self.take(4, label)?.try_into().unwrap()) as usize;
+        self.push(offset, &format!("{}.len", label), len.to_string());
+        Ok(len)
+    }
+
+    /// Finds a type definition by name, in the `types` then the `accounts` (IDLs < 0.30).
+    fn type_definition(&self, name: &str) -> Option<&Value> {
+        self.idl
+            .types
+            .iter()
+            .find(|def| def.name == name)
+            .map(|def| &def.r#type)
+            .or_else(|| {
+                self.idl
+                    .accounts
+                    .iter()
+                    .find(|account| account.name == name && !account.r#type.is_null())
+                    .map(|account| &account.r#type)
+            })
+    }
+
+    /// Decodes a type definition (`{"kind": "struct" | "enum" | "type", ...}`).
+    fn decode_definition(&mut self, definition: &Value, label: &str) -> Result<()> {
+        match definition["kind"].as_str() {
+            Some("struct") => self.decode_fields(&definition["fields"], label),
+            Some("enum") => {
+                let offset = self.offset;
+                let index = self.take(1, label)?[0] as usize;
+                let variant = definition["variants"]
+                    .get(index)
+                    .ok_or_else(|| anyhow!("`{}`: no variant {}", label, index))?;
+                let name = variant["name"].as_str().unwrap_or("?");
+                self.push(offset, label, format!("{} (variant {})", name, index));
+                self.decode_fields(&variant["fields"], &format!("{}.{}", label, name))
+            }
+            Some("type") => self.decode(&definition["alias"], label),
+            kind => Err(anyhow!("`{}`: unsupported type kind {:?}", label, kind)),
+        }
+    }
+
+    /// Decodes named (`[{"name", "type"}]`) or tuple (`[type, ...]`) fields.
+    fn decode_fields(&mut self, fields: &Value, label: &str) -> Result<()> {
+        for (index, field) in fields.as_array().into_iter().flatten().enumerate() {
+            match field["name"].as_str() {
+                Some(name) => self.decode(&field["type"], &format!("{}.{}", label, name))?,
+                None => self.decode(field, &format!("{}.{}", label, index))?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Decodes a value of an IDL type, pushing a segment per leaf field.
+    fn decode(&mut self, ty: &Value, label: &str) -> Result<()> {
+        let offset = self.offset;
+        if let Some(name) = ty.as_str() {
+            let value = match name {
+                "bool" => (self.take(1, label)?[0] != 0).to_string(),
+                "u8" => self.take(1, label)?[0].to_string(),
+                "i8" => (self.take(1, label)?[0] as i8).to_string(),
+                "u16" => u16::from_le_bytes(self.take(2, label)?.try_into()?).to_string(),
+                "i16" => i16::from_le_bytes(self.take(2, label)?.try_into()?).to_string(),
+                "u32" => u32::from_le_bytes(self.take(4, label)?.try_into()?).to_string(),
+                "i32" => i32::from_le_bytes(self.take(4, label)?.try_into()?).to_string(),
+                "f32" => f32::from_le_bytes(self.take(4, label)?.try_into()?).to_string(),
+                "u64" => u64::from_le_bytes(self.take(8, label)?.try_into()?).to_string(),
+                "i64" => i64::from_le_bytes(self.take(8, label)?.try_into()?).to_string(),
+                "f64" => f64::from_le_bytes(self.take(8, label)?.try_into()?).to_string(),
+                "u128" => u128::from_le_bytes(self.take(16, label)?.try_into()?).to_string(),
+                "i128" => i128::from_le_bytes(self.take(16, label)?.try_into()?).to_string(),
+                "pubkey" | "publicKey" => {
+                    Pubkey::new_from_array(self.take(32, label)?.try_into()?).to_string()
+                }
+                "string" | "bytes" => {
+                    let len = self.read_len(label)?;
+                    let offset = self.offset;
+                    let bytes = self.take(len, label)?;
+                    let value = if name == "string" {
+                        format!("{:?}", String::from_utf8_lossy(bytes))
+                    } else {
+                        format!("{} bytes", len)
+                    };
+                    self.push(offset, label, value);
+                    return Ok(());
+                }
+                _ => return Err(anyhow!("`{}`: unsupported type `{}`", label, name)),
+            };
+            self.push(offset, label, value);
+            return Ok(());
+        }
+
+        if let Some(inner) = ty.get("vec") {
+            let len = self.read_len(label)?;
+            for index in 0..len {
+                self.decode(inner, &format!("{}[{}]", label, index))?;
+            }
+        } else if let Some(inner) = ty.get("option").or_else(|| ty.get("coption")) {
+            let tag_size = if ty.get("option").is_some() { 1 } else { 4 };
+            let present = self.take(tag_size, label)?.iter().any(|b| *b != 0);
+            self.push(
+                offset,
+                &format!("{}.tag", label),
+                if present { "Some" } else { "None" }.into(),
+            );
+            if present {
+                self.decode(inner, label)?;
+            }
+        } else if let Some(array) = ty.get("array").and_then(Value::as_array) {
+            let len = array.get(1).and_then(Value::as_u64).unwrap_or(0);
+            for index in 0..len {
+                self.decode(&array[0], &format!("{}[{}]", label, index))?;
+            }
+        } else if let Some(defined) = ty.get("defined") {
+            // `{"defined": "Name"}` before Anchor 0.30, `{"defined": {"name": "Name"}}` after
+            let name = defined
+                .as_str()
+                .or_else(|| defined["name"].as_str())
+                .ok_or_else(|| anyhow!("`{}`: malformed defined type", label))?;
+            let definition = self
+                .type_definition(name)
+                .ok_or_else(|| anyhow!("`{}`: type `{}` not in the IDL", label, name))?
+                .clone();
+            self.decode_definition(&definition, label)?;
+        } else {
+            return Err(anyhow!("`{}`: unsupported type {}", label, ty));
+        }
+        Ok(())
+    }
+}
+
+/// Cuts the account data along an account type of the IDL: the one named `account_type`, or
+/// the one whose discriminator matches the first 8 bytes.
+///
+/// # Returns
+///
+/// The segments. Bytes left after the account, or after a decoding error (reported as a
+/// warning), are a final `unparsed` segment.
+pub fn idl_layout(
+    data: &[u8],
+    idl: &Idl,
+    account_type: Option<&str>,
+) -> Result<Vec<LayoutSegment>> {
+    let account = match account_type {
+        Some(name) => idl
+            .accounts
+            .iter()
+            .find(|account| account.name == name)
+            .ok_or_else(|| anyhow!("No account `{}` in the IDL", name))?,
+        None => idl
+            .accounts
+            .iter()
+            .find(|account| {
+                let discriminator = match account.discriminator.as_deref() {
+                    Some(bytes) => bytes.to_vec(),
+                    None => account_discriminator(&account.name).to_vec(),
+                };
+                data.starts_with(&discriminator)
+            })
+            .ok_or_else(|| {
+                anyhow!("No account of the IDL matches the discriminator, use --account-type")
+            })?,
+    };
+
+    let mut decoder = Decoder {
+        data,
+        offset: 0,
+        idl,
+        segments: vec![],
+    };
+    let discriminator_size = account.discriminator.as_ref().map_or(8, Vec::len);
+    decoder.take(discriminator_size, "discriminator")?;
+    decoder.push(0, "discriminator", account.name.clone());
+    let definition = decoder
+        .type_definition(&account.name)
+        .ok_or_else(|| anyhow!("No definition of the account `{}` in the IDL", account.name))?
+        .clone();
+    if let Err(e) = decoder.decode_definition(&definition, &account.name) {
+        log::warn!("Layout of `{}` incomplete: {}", account.name, e);
+    }
+
+    let mut segments = decoder.segments;
+    if decoder.offset < data.len() {
+        segments.push(LayoutSegment {
+            offset: decoder.offset,
+            size: data.len() - decoder.offset,
+            label: "unparsed".to_string(),
+            value: None,
+        });
+    }
+    Ok(segments)
+}
+
+/// Cuts the account data by heuristics: the discriminator, named when `names` knows it, then
+/// pubkey-looking 32-byte fields, non-null `u64` below 2^48 (amounts, timestamps, ...), runs
+/// of zeros, and the remaining bytes.
+pub fn heuristic_layout(data: &[u8], names: &AccountNames) -> Vec<LayoutSegment> {
+    let mut segments: Vec<LayoutSegment> = vec![];
+    let mut push = |offset: usize, size: usize, label: &str, value: Option<String>| {
+        // adjacent unknown bytes are merged
+        if let Some(last) = segments.last_mut() {
+            if label == "bytes" && last.label == "bytes" && last.offset + last.size == offset {
+                last.size += size;
+                return;
+            }
+        }
+        segments.push(LayoutSegment {
+            offset,
+            size,
+            label: label.to_string(),
+            value,
+        });
+    };
+
+    let mut offset = 0;
+    if data.len() >= 8 {
+        match names.identify(data) {
+            Some(name) => push(
+                0,
+                8,
+                "discriminator",
+                Some(format!("{} ({})", name, hex::encode(&data[..8]))),
+            ),
+            None => push(0, 8, "discriminator?", Some(hex::encode(&data[..8]))),
+        }
+        offset = 8;
+    }
+    while offset < data.len() {
+        let rest = &data[offset..];
+        let zeros = rest.iter().take_while(|b| **b == 0).count();
+        if zeros >= 8 {
+            push(offset, zeros, "zeros", None);
+            offset += zeros;
+        } else if rest.len() >= 32 && shannon_entropy(&rest[..32]) >= PUBKEY_MIN_ENTROPY {
+            let pubkey = Pubkey::new_from_array(rest[..32].try_into().unwrap());
+            push(offset, 32, "pubkey?", Some(pubkey.to_string()));
+            offset += 32;
+        } else if rest.len() >= 8
+            && (1..1u64 << 48).contains(&u64::from_le_bytes(rest[..8].try_into().unwrap()))
+        {
+            let value = u64::from_le_bytes(rest[..8].try_into().unwrap());
+            push(offset, 8, "u64?", Some(value.to_string()));
+            offset += 8;
+        } else {
+            push(offset, 1, "bytes", None);
+            offset += 1;
+        }
+    }
+    segments
+}
+
+/// Renders the segments as an annotated hexdump, long segments being truncated.
+pub fn render_layout(data: &[u8], segments: &[LayoutSegment]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# {} bytes, {} segments", data.len(), segments.len());
+    for segment in segments {
+        let _ = write!(
+            out,
+            "\n0x{:04x}  +{:<4}  {}",
+            segment.offset, segment.size, segment.label
+        );
+        if let Some(value) = &segment.value {
+            let _ = write!(out, " = {}", value);
+        }
+        out.push('\n');
+
+        let bytes = &data[segment.offset..segment.offset + segment.size];
+        for (line, chunk) in bytes.chunks(16).enumerate() {
+            if line == MAX_HEXDUMP_LINES {
+                let _ = writeln!(out, "        ... {} more bytes", bytes.len() - line * 16);
+                break;
+            }
+            let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|b| {
+                    if b.is_ascii_graphic() {
+                        *b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            let _ = writeln!(
+                out,
+                "        {:04x}: {:<47}  |{}|",
+                segment.offset + line * 16,
+                hex.join(" "),
+                ascii
+            );
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::{Digest, Sha256};
+
+    fn idl() -> Idl {
+        serde_json::from_value(serde_json::json!({
+            "accounts": [{ "name": "Pool" }],
+            "types": [
+                { "name": "Pool", "type": { "kind": "struct", "fields": [
+                    { "name": "authority", "type": "pubkey" },
+                    { "name": "fees", "type": { "vec": "u16" } },
+                    { "name": "state", "type": { "defined": { "name": "State" } } },
+                    { "name": "label", "type": { "option": "string" } }
+                ]}},
+                { "name": "State", "type": { "kind": "enum", "variants": [
+                    { "name": "Paused" }, { "name": "Active" }
+                ]}}
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn decodes_with_the_idl() {
+        let authority = Pubkey::new_from_array(Sha256::digest(b"authority").into());
+        let mut data = account_discriminator("Pool").to_vec();
+        data.extend(authority.to_bytes());
+        data.extend([2, 0, 0, 0, 5, 0, 7, 0]);
+        data.extend([1, 1, 2, 0, 0, 0, b'o', b'k']);
+        data.extend([0xff; 3]);
+
+        let segments = idl_layout(&data, &idl(), None).unwrap();
+        let labelled = |label: &str| {
+            segments
+                .iter()
+                .find(|segment| segment.label == label)
+                .and_then(|segment| segment.value.clone())
+        };
+        assert_eq!(labelled("discriminator").as_deref(), Some("Pool"));
+        assert_eq!(labelled("Pool.authority"), Some(authority.to_string()));
+        assert_eq!(labelled("Pool.fees[1]").as_deref(), Some("7"));
+        assert_eq!(
+            labelled("Pool.state").as_deref(),
+            Some("Active (variant 1)")
+        );
+        assert_eq!(labelled("Pool.label").as_deref(), Some("\"ok\""));
+        let last = segments.last().unwrap();
+        assert_eq!((last.label.as_str(), last.size), ("unparsed", 3));
+
+        assert!(idl_layout(&[0; 16], &idl(), None).is_err());
+        let truncated = idl_layout(&data[..20], &idl(), Some("Pool")).unwrap();
+        assert_eq!(truncated.last().unwrap().label, "unparsed");
+    }
+
+    #[test]
+    fn cuts_by_heuristics() {
+        let key = Pubkey::new_from_array(Sha256::digest(b"key").into());
+        let mut data = vec![0xaa; 8];
+        data.extend(key.to_bytes());
+        data.extend(1_000_000u64.to_le_bytes());
+        data.extend([0; 16]);
+        data.extend([0xff, 0xfe]);
+
+        let names = AccountNames::common().unwrap();
+        let segments = heuristic_layout(&data, &names);
+        let labels: Vec<&str> = segments.iter().map(|s| s.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            vec!["discriminator?", "pubkey?", "u64?", "zeros", "bytes"]
+        );
+        assert_eq!(segments[1].value, Some(key.to_string()));
+        assert_eq!(segments[4].size, 2);
+
+        let rendered = render_layout(&data, &segments);
+        assert!(rendered.contains("0x0028  +8     u64? = 1000000"));
+
+        data[..8].copy_from_slice(&account_discriminator("Vault"));
+        let segments = heuristic_layout(&data, &names);
+        assert_eq!(segments[0].label, "discriminator");
+        assert!(segments[0].value.as_ref().unwrap().starts_with("Vault ("));
+    }
+}
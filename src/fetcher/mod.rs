@@ -1,10 +1,16 @@
 use anyhow::Result;
-use base64::{engine::general_purpose, Engine};
-use reqwest::Client;
-use serde_json::json;
 use solana_sdk::pubkey::Pubkey;
 use std::{fs, path::Path};
 
+use crate::reverse::account_names::AccountNames;
+use rpc::RpcClient;
+
+pub mod cache;
+pub mod history;
+pub mod layout;
+pub mod prefetch;
+pub mod rpc;
+
 /// Default RPC endpoint (mainnet‑beta).
 pub const MAINNET_RPC: &str = "https://api.mainnet-beta.solana.com";
 
@@ -25,7 +31,8 @@ fn slice_from_elf_header(bytecode: &[u8]) -> Option<&[u8]> {
         .map(|idx| &bytecode[idx..])
 }
 
-/// If the buffer *might* be an Anchor account, print its potential discriminator.
+/// If the buffer *might* be an Anchor account, print its potential discriminator, with the
+/// account name it is the hash of when it is in the common names (see [`AccountNames`]).
 /// There is no fool‑proof way without the IDL, but dumping the first 8 bytes is handy.
 /// https://www.anchor-lang.com/docs/basics/program-structure#account-discriminator
 /// https://github.com/solana-foundation/anchor/blob/0e5285aecdf410fa0779b7cd09a47f235882c156/lang/attribute/account/src/lib.rs#L30-L34
@@ -33,9 +40,15 @@ fn slice_from_elf_header(bytecode: &[u8]) -> Option<&[u8]> {
 fn report_anchor_discriminator(data: &[u8]) -> &[u8] {
     if data.len() >= 8 {
         let disc = &data[..8];
+        let name = AccountNames::common().ok().and_then(|names| {
+            names
+                .identify(disc)
+                .map(|name| format!(" (account `{}`)", name))
+        });
         eprintln!(
-            "[fetcher] First 8 bytes (possible Anchor discriminator): {}",
-            hex::encode(disc)
+            "[fetcher] First 8 bytes (possible Anchor discriminator): {}{}",
+            hex::encode(disc),
+            name.unwrap_or_default()
         );
         return disc;
     }
@@ -48,38 +61,17 @@ fn report_anchor_discriminator(data: &[u8]) -> &[u8] {
 /// * If the account is executable, the function resolves potential `ProgramData` indirection
 ///   and returns a `Vec<u8>` starting exactly at the ELF header.
 /// * Otherwise, the raw account data is returned unmodified.
-async fn fetch_account_contents(rpc_url: &str, account: &str) -> Result<AccountFetch> {
-    let client = Client::new();
-
-    // Single round‑trip: getAccountInfo
-    let request_body = json!({
-        "jsonrpc": "2.0",
-        "id": 1,
-        "method": "getAccountInfo",
-        "params": [
-            account,
-            { "encoding": "base64" }
-        ]
-    });
-
-    let res = client.post(rpc_url).json(&request_body).send().await?;
-    let res_json: serde_json::Value = res.json().await?;
-    let value = &res_json["result"]["value"];
-
-    if value.is_null() {
-        return Err(anyhow::anyhow!("Account not found: can't fetch any value using this pubkey, probably invalid pubkey"));
-    }
-
-    let executable = value["executable"].as_bool()
-        .ok_or_else(|| anyhow::anyhow!("Missing `executable` flag"))?;
-
-    let data_base64 = value["data"][0]
-        .as_str()
-        .ok_or_else(|| anyhow::anyhow!("No data in account response"))?;
-    let mut decoded_data = general_purpose::STANDARD.decode(data_base64)?;
+///
+/// The requests go through `rpc`, and so under its rate.
+pub(crate) async fn fetch_account_contents(rpc: &RpcClient, account: &str) -> Result<AccountFetch> {
+    let fetched = rpc.get_account(account, None).await?.ok_or_else(|| {
+        anyhow::anyhow!("Account not found: can't fetch any value using this pubkey, probably invalid pubkey")
+    })?;
+    let executable = fetched.executable;
+    let mut decoded_data = fetched.data;
 
     // Upgradeable loader indirection (program -> ProgramData)
-    if executable && value["owner"] == "BPFLoaderUpgradeab1e11111111111111111111111" {
+    if executable && fetched.owner == "BPFLoaderUpgradeab1e11111111111111111111111" {
         if decoded_data.len() < 36 {
             return Err(anyhow::anyhow!("Upgradeable program account too small"));
         }
@@ -87,23 +79,11 @@ async fn fetch_account_contents(rpc_url: &str, account: &str) -> Result<AccountF
         // Bytes [4..36] hold the ProgramData pubkey
         let programdata_pubkey = Pubkey::new_from_array(decoded_data[4..36].try_into().unwrap()); // will not crash since len >= 36 and it is sliced for 32 bytes
 
-        let request_body = json!({
-            "jsonrpc": "2.0",
-            "id": 2,
-            "method": "getAccountInfo",
-            "params": [
-                programdata_pubkey.to_string(),
-                { "encoding": "base64" }
-            ]
-        });
-
-        let res = client.post(rpc_url).json(&request_body).send().await?;
-        let res_json: serde_json::Value = res.json().await?;
-        let value = &res_json["result"]["value"];
-        let data_base64 = value["data"][0]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("No data in ProgramData response"))?;
-        decoded_data = general_purpose::STANDARD.decode(data_base64)?;
+        decoded_data = rpc
+            .get_account(&programdata_pubkey.to_string(), None)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("ProgramData account not found"))?
+            .data;
     }
 
     if executable {
@@ -120,9 +100,8 @@ async fn fetch_account_contents(rpc_url: &str, account: &str) -> Result<AccountF
 ///
 /// * Executable account -> `fetched_program.so`
 /// * Non‑executable account -> `fetched_account.bin`
-pub async fn fetch_to<P: AsRef<Path>>(out_dir: P, rpc_url: Option<String>, account: &str) -> Result<()> {
-    let rpc_url = rpc_url.unwrap_or_else(|| MAINNET_RPC.to_string());
-    let fetched = fetch_account_contents(&rpc_url, account).await?;
+pub async fn fetch_to<P: AsRef<Path>>(out_dir: P, rpc: &RpcClient, account: &str) -> Result<()> {
+    let fetched = fetch_account_contents(rpc, account).await?;
 
     let filename = if fetched.executable { "fetched_program.so" } else { "fetched_account.bin" };
     fs::write(out_dir.as_ref().join(filename), fetched.data)?;
@@ -137,7 +116,7 @@ pub async fn fetch_to<P: AsRef<Path>>(out_dir: P, rpc_url: Option<String>, accou
 /// # Arguments
 ///
 /// * `out_dir` - Path to the output directory where the bytecode file will be saved.
-/// * `rpc_url` - Optional Solana RPC endpoint; defaults to `https://api.mainnet-beta.solana.com` if `None`.
+/// * `rpc` - Client of the Solana RPC endpoint (e.g. `RpcClient::new(MAINNET_RPC)`).
 /// * `program_id` - The program ID on Solana to fetch the bytecode from.
 ///
 /// # Returns
@@ -160,8 +139,8 @@ pub async fn fetch_to<P: AsRef<Path>>(out_dir: P, rpc_url: Option<String>, accou
 /// # Requirements
 ///
 /// This function is asynchronous and should be `.await`ed within an async context.
-pub async fn fetch_bytecode_to<P: AsRef<Path>>(out_dir: P, rpc_url: Option<String>, program_id: &str) -> Result<()> {
-    fetch_to(out_dir, rpc_url, program_id).await
+pub async fn fetch_bytecode_to<P: AsRef<Path>>(out_dir: P, rpc: &RpcClient, program_id: &str) -> Result<()> {
+    fetch_to(out_dir, rpc, program_id).await
 }
 
 #[cfg(test)]
@@ -177,7 +156,7 @@ mod tests {
     /// Ensure we can fetch an immutable BPF program and obtain a valid ELF
     #[tokio::test]
     async fn test_fetch_executable() {
-        let res = fetch_account_contents(MAINNET_RPC, TEST_EXECUTABLE_PROG)
+        let res = fetch_account_contents(&RpcClient::new(MAINNET_RPC), TEST_EXECUTABLE_PROG)
             .await
             .expect("Fetch executable program");
         assert!(res.executable, "Account must be flagged executable");
@@ -195,7 +174,7 @@ mod tests {
     /// Ensure we can follow Program -> ProgramData indirection and still retrieve a valid ELF
     #[tokio::test]
     async fn test_fetch_upgradeable() {
-        let res = fetch_account_contents(MAINNET_RPC, TEST_UPGRADEABLE_PROG)
+        let res = fetch_account_contents(&RpcClient::new(MAINNET_RPC), TEST_UPGRADEABLE_PROG)
             .await
             .expect("Fetch upgradeable program");
         assert!(res.executable, "Account must be executable");
@@ -205,7 +184,7 @@ mod tests {
     /// Validate behaviour on a standard Sysvar (non‑executable). Expected size is 17 bytes
     #[tokio::test]
     async fn test_fetch_non_executable_sysvar() {
-        let res = fetch_account_contents(MAINNET_RPC, TEST_SYSVAR_RENT)
+        let res = fetch_account_contents(&RpcClient::new(MAINNET_RPC), TEST_SYSVAR_RENT)
             .await
             .expect("Fetch Sysvar Rent");
         assert!(!res.executable, "Sysvar Rent should not be executable");
@@ -229,7 +208,7 @@ mod tests {
     /// Ensure the function returns a readable error on an invalid pubkey
     #[tokio::test]
     async fn test_invalid_pubkey_error() {
-        let _err = fetch_account_contents(MAINNET_RPC, TEST_INVALID_PUBKEY)
+        let _err = fetch_account_contents(&RpcClient::new(MAINNET_RPC), TEST_INVALID_PUBKEY)
             .await
             .expect_err("Account not found: can't fetch any value using this pubkey, probably invalid pubkey");
     }
@@ -247,7 +226,7 @@ mod tests {
         let hash_result = hasher.finalize();
         let wanted_discriminator: [u8; 8] = hash_result[0..8].try_into().unwrap();
 
-        let res = fetch_account_contents(MAINNET_RPC, TEST_MARINADE_STATE_ACCOUNT)
+        let res = fetch_account_contents(&RpcClient::new(MAINNET_RPC), TEST_MARINADE_STATE_ACCOUNT)
             .await
             .expect("Fetch marinade state account");
         assert!(!res.executable, "Marinade state account should not be executable");
@@ -0,0 +1,223 @@
+//! Resolution of the public keys embedded in a fetched program against the chain
+//! (`rodata_pubkeys.out`).
+//!
+//! The 32-byte windows of the data sections that may be keys (see
+//! [`find_pubkey_candidates`](crate::reverse::entropy::find_pubkey_candidates)) are read with
+//! batched `getMultipleAccounts` requests. The windows that are not keys, e.g. a key read one
+//! byte off, don't exist on-chain and are left out, and the others are classified as programs,
+//! token mints, token accounts or other accounts, with their owner.
+
+use anyhow::Result;
+use solana_sdk::pubkey::Pubkey;
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::rpc::{RpcAccount, RpcClient};
+use crate::reverse::entropy::PubkeyCandidate;
+
+/// Name of the file listing the resolved keys, next to the fetched program.
+pub const RESOLVED_PUBKEYS_FILE: &str = "rodata_pubkeys.out";
+
+/// Maximum number of candidates resolved, 100 `getMultipleAccounts` requests.
+pub const MAX_RESOLVED_CANDIDATES: usize = 10_000;
+
+/// Bytes of data read for each account: enough to tell a token mint (82 bytes) from a token
+/// account (165 bytes), and their Token-2022 versions by the account type at byte 165.
+const DATA_SLICE_LENGTH: usize = 166;
+
+const TOKEN_PROGRAMS: &[&str] = &[
+    "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+    "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb",
+];
+
+/// Size of an SPL token mint.
+const MINT_SIZE: usize = 82;
+/// Size of an SPL token account.
+const TOKEN_ACCOUNT_SIZE: usize = 165;
+
+/// What a key is on-chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountKind {
+    /// An executable account.
+    Program,
+    Mint,
+    TokenAccount,
+    /// Any other account.
+    Account,
+}
+
+impl fmt::Display for AccountKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AccountKind::Program => "program",
+            AccountKind::Mint => "mint",
+            AccountKind::TokenAccount => "token account",
+            AccountKind::Account => "account",
+        })
+    }
+}
+
+/// A key of the program that exists on-chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedKey {
+    pub candidate: PubkeyCandidate,
+    pub kind: AccountKind,
+    pub owner: String,
+    pub lamports: u64,
+    /// Decimals and supply of a mint, mint and owner of a token account.
+    pub details: Option<String>,
+}
+
+/// Reads the pubkey at `offset` of the data, if it is long enough.
+fn pubkey_at(data: &[u8], offset: usize) -> Option<Pubkey> {
+    Some(Pubkey::new_from_array(
+        data.get(offset..offset + 32)?.try_into().ok()?,
+    ))
+}
+
+/// Classifies an account read with a slice of [`DATA_SLICE_LENGTH`] bytes, with the details of
+/// the token accounts.
+fn classify(account: &RpcAccount) -> (AccountKind, Option<String>) {
+    if account.executable {
+        return (AccountKind::Program, None);
+    }
+    if !TOKEN_PROGRAMS.contains(&account.owner.as_str()) {
+        return (AccountKind::Account, None);
+    }
+    let data = &account.data;
+    // Token-2022 accounts with extensions hold their type after the token account layout
+    let kind = match (data.len(), data.get(TOKEN_ACCOUNT_SIZE)) {
+        (MINT_SIZE, _) | (_, Some(1)) => AccountKind::Mint,
+        (TOKEN_ACCOUNT_SIZE, _) | (_, Some(2)) => AccountKind::TokenAccount,
+        _ => return (AccountKind::Account, None),
+    };
+    let details = match kind {
+        AccountKind::Mint => {
+            let supply = u64::from_le_bytes(data[36..44].try_into().unwrap());
+            Some(format!("decimals {}, supply {}", data[44], supply))
+        }
+        _ => match (pubkey_at(data, 0), pubkey_at(data, 32)) {
+            (Some(mint), Some(owner)) => Some(format!("mint {}, owner {}", mint, owner)),
+            _ => None,
+        },
+    };
+    (kind, details)
+}
+
+/// Reads the candidates on-chain (at most [`MAX_RESOLVED_CANDIDATES`]).
+///
+/// # Returns
+///
+/// The candidates that exist on-chain, in the order of `candidates`.
+pub async fn resolve_pubkeys(
+    rpc: &RpcClient,
+    candidates: &[PubkeyCandidate],
+) -> Result<Vec<ResolvedKey>> {
+    let candidates = &candidates[..candidates.len().min(MAX_RESOLVED_CANDIDATES)];
+    let keys: Vec<String> = candidates
+        .iter()
+        .map(|candidate| candidate.pubkey.to_string())
+        .collect();
+    let accounts = rpc
+        .get_multiple_accounts(&keys, Some(DATA_SLICE_LENGTH))
+        .await?;
+    Ok(candidates
+        .iter()
+        .zip(accounts)
+        .filter_map(|(candidate, account)| {
+            let account = account?;
+            let (kind, details) = classify(&account);
+            Some(ResolvedKey {
+                candidate: candidate.clone(),
+                kind,
+                owner: account.owner,
+                lamports: account.lamports,
+                details,
+            })
+        })
+        .collect())
+}
+
+/// Writes the resolved keys to `rodata_pubkeys.out` in `out_dir`.
+///
+/// # Returns
+///
+/// The path of the written file.
+pub fn export_resolved_pubkeys(
+    resolved: &[ResolvedKey],
+    candidates: usize,
+    out_dir: &Path,
+) -> std::io::Result<PathBuf> {
+    let path = out_dir.join(RESOLVED_PUBKEYS_FILE);
+    let mut output = File::create(&path)?;
+    writeln!(
+        output,
+        "Keys of the data sections found on-chain ({} of {} candidates):",
+        resolved.len(),
+        candidates
+    )?;
+    for key in resolved {
+        writeln!(
+            output,
+            "\n    {} ({} {:#x})",
+            key.candidate.pubkey, key.candidate.section, key.candidate.offset
+        )?;
+        writeln!(output, "        kind:     {}", key.kind)?;
+        writeln!(output, "        owner:    {}", key.owner)?;
+        writeln!(output, "        lamports: {}", key.lamports)?;
+        if let Some(details) = &key.details {
+            writeln!(output, "        details:  {}", details)?;
+        }
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let account = |owner: &str, executable, data: Vec<u8>| RpcAccount {
+            owner: owner.to_string(),
+            lamports: 1,
+            executable,
+            space: None,
+            data,
+        };
+        let loader = "BPFLoaderUpgradeab1e11111111111111111111111";
+        assert_eq!(
+            classify(&account(loader, true, vec![2; 36])),
+            (AccountKind::Program, None)
+        );
+
+        let mut mint = vec![0; MINT_SIZE];
+        mint[36..44].copy_from_slice(&1_000u64.to_le_bytes());
+        mint[44] = 6;
+        assert_eq!(
+            classify(&account(TOKEN_PROGRAMS[0], false, mint)),
+            (
+                AccountKind::Mint,
+                Some("decimals 6, supply 1000".to_string())
+            )
+        );
+
+        // Token-2022 account with extensions
+        let mut token_account = vec![0; DATA_SLICE_LENGTH];
+        token_account[TOKEN_ACCOUNT_SIZE] = 2;
+        let (kind, details) = classify(&account(TOKEN_PROGRAMS[1], false, token_account));
+        assert_eq!(kind, AccountKind::TokenAccount);
+        assert!(details.unwrap().starts_with("mint 1111"));
+
+        assert_eq!(
+            classify(&account(TOKEN_PROGRAMS[0], false, vec![0; 10])),
+            (AccountKind::Account, None)
+        );
+        assert_eq!(
+            classify(&account(loader, false, vec![0; MINT_SIZE])),
+            (AccountKind::Account, None)
+        );
+    }
+}
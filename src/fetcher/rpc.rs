@@ -0,0 +1,217 @@
+//! JSON-RPC client shared by the fetcher modules.
+//!
+//! Public endpoints answer `429 Too Many Requests` beyond a few requests per second, which
+//! the history replay (one `getTransaction` per signature) and the resolution of the keys of
+//! a program easily exceed. An [`RpcClient`] bounds the requests in flight and spaces them to
+//! a given rate, retrying the rate-limited ones with a backoff. Its clones share these
+//! limits, so that concurrent tasks stay under the rate together.
+
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose, Engine};
+use log::debug;
+use reqwest::{Client, StatusCode};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, Semaphore};
+use tokio::time::Instant;
+
+/// Requests per second sent by default, the rate limit of the public mainnet endpoint.
+pub const DEFAULT_REQUESTS_PER_SECOND: u32 = 10;
+
+/// Requests in flight at once.
+const MAX_CONCURRENT_REQUESTS: usize = 4;
+
+/// Retries of a rate-limited request, waiting 1 s, 2 s, then 4 s.
+const MAX_RETRIES: u32 = 3;
+
+/// Maximum number of accounts of a `getMultipleAccounts` request.
+pub const MAX_ACCOUNTS_PER_REQUEST: usize = 100;
+
+/// Limits shared by the clones of a client.
+struct Limits {
+    in_flight: Semaphore,
+    interval: Duration,
+    next_request: Mutex<Instant>,
+}
+
+/// Rate-limited JSON-RPC client of a Solana endpoint.
+#[derive(Clone)]
+pub struct RpcClient {
+    client: Client,
+    url: String,
+    limits: Arc<Limits>,
+}
+
+/// An account read with `getMultipleAccounts` or `getAccountInfo`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcAccount {
+    pub owner: String,
+    pub lamports: u64,
+    pub executable: bool,
+    /// Size of the data, `None` if the RPC doesn't report it along with a data slice.
+    pub space: Option<u64>,
+    /// The data, or the requested slice of it.
+    pub data: Vec<u8>,
+}
+
+impl RpcAccount {
+    fn from_value(value: &Value) -> Result<Self> {
+        let data_base64 = value["data"][0]
+            .as_str()
+            .ok_or_else(|| anyhow!("No data in account response"))?;
+        Ok(Self {
+            owner: value["owner"].as_str().unwrap_or_default().to_string(),
+            lamports: value["lamports"].as_u64().unwrap_or_default(),
+            executable: value["executable"].as_bool().unwrap_or_default(),
+            space: value["space"].as_u64(),
+            data: general_purpose::STANDARD.decode(data_base64)?,
+        })
+    }
+}
+
+impl RpcClient {
+    /// Client of `url` sending at most [`DEFAULT_REQUESTS_PER_SECOND`] requests per second.
+    #[cfg(any(test, feature = "python"))]
+    pub fn new(url: &str) -> Self {
+        Self::with_rate(url, DEFAULT_REQUESTS_PER_SECOND)
+    }
+
+    /// Client of `url` sending at most `requests_per_second` requests per second.
+    pub fn with_rate(url: &str, requests_per_second: u32) -> Self {
+        Self {
+            client: Client::new(),
+            url: url.to_string(),
+            limits: Arc::new(Limits {
+                in_flight: Semaphore::new(MAX_CONCURRENT_REQUESTS),
+                interval: Duration::from_secs(1) / requests_per_second.max(1),
+                next_request: Mutex::new(Instant::now()),
+            }),
+        }
+    }
+
+    /// Waits until the next request can be sent under the rate.
+    async fn wait_turn(&self) {
+        let mut next_request = self.limits.next_request.lock().await;
+        let now = Instant::now();
+        if *next_request > now {
+            tokio::time::sleep_until(*next_request).await;
+        }
+        *next_request = (*next_request).max(now) + self.limits.interval;
+    }
+
+    /// Sends a JSON-RPC request and returns its `result` field.
+    pub async fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let _permit = self.limits.in_flight.acquire().await?;
+        let request_body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params
+        });
+        let mut retries = 0;
+        loop {
+            self.wait_turn().await;
+            let response = self
+                .client
+                .post(&self.url)
+                .json(&request_body)
+                .send()
+                .await?;
+            if response.status() == StatusCode::TOO_MANY_REQUESTS && retries < MAX_RETRIES {
+                let backoff = Duration::from_secs(1 << retries);
+                retries += 1;
+                debug!("{} rate-limited, retrying in {:?}", method, backoff);
+                tokio::time::sleep(backoff).await;
+                continue;
+            }
+            let res_json: Value = response.json().await?;
+            if let Some(err) = res_json.get("error") {
+                return Err(anyhow!("RPC error on {}: {}", method, err));
+            }
+            return Ok(res_json["result"].clone());
+        }
+    }
+
+    /// Reads an account (or the `length` first bytes of its data), `None` if it doesn't exist.
+    pub async fn get_account(
+        &self,
+        account: &str,
+        length: Option<usize>,
+    ) -> Result<Option<RpcAccount>> {
+        let result = self
+            .call("getAccountInfo", json!([account, account_config(length)]))
+            .await?;
+        match &result["value"] {
+            Value::Null => Ok(None),
+            value => RpcAccount::from_value(value).map(Some),
+        }
+    }
+
+    /// Reads accounts (or the `length` first bytes of their data), in batches of
+    /// [`MAX_ACCOUNTS_PER_REQUEST`] sent concurrently.
+    ///
+    /// # Returns
+    ///
+    /// The accounts in the order of `accounts`, `None` for those that don't exist.
+    pub async fn get_multiple_accounts(
+        &self,
+        accounts: &[String],
+        length: Option<usize>,
+    ) -> Result<Vec<Option<RpcAccount>>> {
+        let mut batches = tokio::task::JoinSet::new();
+        for (index, batch) in accounts.chunks(MAX_ACCOUNTS_PER_REQUEST).enumerate() {
+            let rpc = self.clone();
+            let params = json!([batch, account_config(length)]);
+            batches.spawn(async move {
+                let result = rpc.call("getMultipleAccounts", params).await?;
+                let accounts = result["value"]
+                    .as_array()
+                    .ok_or_else(|| anyhow!("No value in getMultipleAccounts response"))?
+                    .iter()
+                    .map(|value| match value {
+                        Value::Null => Ok(None),
+                        value => RpcAccount::from_value(value).map(Some),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok::<_, anyhow::Error>((index, accounts))
+            });
+        }
+        let mut results = vec![vec![]; batches.len()];
+        while let Some(batch) = batches.join_next().await {
+            let (index, accounts) = batch??;
+            results[index] = accounts;
+        }
+        Ok(results.into_iter().flatten().collect())
+    }
+}
+
+/// Configuration of the account requests: base64 data, sliced to `length` bytes.
+fn account_config(length: Option<usize>) -> Value {
+    let mut config = json!({ "encoding": "base64" });
+    if let Some(length) = length {
+        config["dataSlice"] = json!({ "offset": 0, "length": length });
+    }
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn spaces_the_requests() {
+        let rpc = RpcClient::with_rate("http://localhost", 50);
+        let start = Instant::now();
+        for _ in 0..5 {
+            rpc.wait_turn().await;
+        }
+        // the first request goes right away, the next ones every 20 ms
+        assert!(start.elapsed() >= Duration::from_millis(80));
+
+        // the clones share the rate
+        let clone = rpc.clone();
+        clone.wait_turn().await;
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,392 @@
+//! Application of the fixes suggested by the rules (`sast --fix`).
+//!
+//! A rule attaches to a match a [`SynFix`]: edits replacing the text of source spans. The
+//! fixes of each file are resolved against its current content, the ones whose spans can't be
+//! found or overlap an earlier fix are skipped, and the others are either written back to the
+//! file or printed as a unified diff (`--dry-run`).
+
+use crate::parsers::syn_ast::SourcePosition;
+use crate::state::sast_state::{SastState, SynFix, SynFixEdit};
+use anyhow::{Context, Result};
+use log::warn;
+use std::collections::BTreeMap;
+use std::fs;
+use std::ops::Range;
+
+/// Lines of context around the changes of a diff hunk.
+const DIFF_CONTEXT: usize = 3;
+
+/// A replacement of the bytes of a range of the content.
+type Edit = (Range<usize>, String);
+
+/// The fixes of a file, resolved against its content.
+#[derive(Debug)]
+pub struct FileFixes {
+    pub file: String,
+    pub content: String,
+    /// The replacements, sorted and not overlapping.
+    pub edits: Vec<Edit>,
+    /// The descriptions of the applied fixes.
+    pub applied: Vec<String>,
+    /// The number of fixes skipped, their spans not being found or overlapping another fix.
+    pub skipped: usize,
+}
+
+impl FileFixes {
+    /// Returns the content of the file with the fixes applied.
+    pub fn fixed(&self) -> String {
+        let mut fixed = String::with_capacity(self.content.len());
+        let mut last = 0;
+        for (range, replacement) in &self.edits {
+            fixed.push_str(&self.content[last..range.start]);
+            fixed.push_str(replacement);
+            last = range.end;
+        }
+        fixed.push_str(&self.content[last..]);
+        fixed
+    }
+
+    /// Returns the fixes as a unified diff, with the path of the file on both sides.
+    pub fn diff(&self) -> String {
+        let lines: Vec<&str> = self.content.split_inclusive('\n').collect();
+        let starts: Vec<usize> = line_starts(&self.content);
+        let line_of = |offset: usize| starts.partition_point(|start| *start <= offset).max(1) - 1;
+        let line_end = |line: usize| starts.get(line + 1).copied().unwrap_or(self.content.len());
+
+        // the edits whose contexts overlap are shown in the same hunk
+        let mut hunks: Vec<(usize, usize, Vec<&Edit>)> = vec![];
+        for edit in &self.edits {
+            let (range, _) = edit;
+            let first = line_of(range.start);
+            let last = line_of(range.end.max(range.start + 1) - 1).max(first);
+            match hunks.last_mut() {
+                Some((_, hunk_last, edits)) if first <= *hunk_last + 2 * DIFF_CONTEXT + 1 => {
+                    *hunk_last = last.max(*hunk_last);
+                    edits.push(edit);
+                }
+                _ => hunks.push((first, last, vec![edit])),
+            }
+        }
+
+        let mut diff = format!("--- {}\n+++ {}\n", self.file, self.file);
+        let mut offset: isize = 0;
+        for (first, last, edits) in hunks {
+            let last = last.min(lines.len().saturating_sub(1));
+            let segment_start = starts.get(first).copied().unwrap_or(self.content.len());
+            let mut changed = String::new();
+            let mut cursor = segment_start;
+            for (range, replacement) in edits {
+                changed.push_str(&self.content[cursor..range.start]);
+                changed.push_str(replacement);
+                cursor = range.end;
+            }
+            changed.push_str(&self.content[cursor..line_end(last).max(cursor)]);
+            let new_lines: Vec<&str> = changed.split_inclusive('\n').collect();
+
+            let context_start = first.saturating_sub(DIFF_CONTEXT);
+            let context_end = (last + DIFF_CONTEXT).min(lines.len().saturating_sub(1));
+            let old_count = (context_end + 1).saturating_sub(context_start);
+            let new_count = old_count - (last + 1 - first) + new_lines.len();
+            let old_start = context_start + 1;
+            let new_start = (old_start as isize + offset) as usize;
+            offset += new_count as isize - old_count as isize;
+            diff.push_str(&format!(
+                "@@ -{} +{} @@\n",
+                hunk_range(old_start, old_count),
+                hunk_range(new_start, new_count)
+            ));
+            for line in lines.get(context_start..first).unwrap_or_default() {
+                push_diff_line(&mut diff, ' ', line);
+            }
+            for line in lines.get(first..=last).unwrap_or_default() {
+                push_diff_line(&mut diff, '-', line);
+            }
+            for line in &new_lines {
+                push_diff_line(&mut diff, '+', line);
+            }
+            for line in lines.get(last + 1..=context_end).unwrap_or_default() {
+                push_diff_line(&mut diff, ' ', line);
+            }
+        }
+        diff
+    }
+}
+
+/// Formats the range of a hunk header, `start,count` (`start` being the line before the hunk
+/// when it is empty).
+fn hunk_range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{},0", start - 1),
+        _ => format!("{},{}", start, count),
+    }
+}
+
+/// Adds a line of a hunk, noting when it is the last line of a file without a newline.
+fn push_diff_line(diff: &mut String, prefix: char, line: &str) {
+    diff.push(prefix);
+    diff.push_str(line);
+    if !line.ends_with('\n') {
+        diff.push_str("\n\\ No newline at end of file\n");
+    }
+}
+
+/// Returns the byte offsets at which the lines of `content` start.
+fn line_starts(content: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(index, _)| index + 1))
+        .filter(|start| *start < content.len() || *start == 0)
+        .collect()
+}
+
+/// Returns the bytes of `content` covered by `position`.
+fn byte_range(content: &str, starts: &[usize], position: &SourcePosition) -> Option<Range<usize>> {
+    let offset = |line: u32, column: u32| -> Option<usize> {
+        let start = *starts.get((line as usize).checked_sub(1)?)?;
+        let text = content[start..].split('\n').next().unwrap_or_default();
+        Some(start + position.column_encoding.byte_offset(text, column as usize))
+    };
+    let start = offset(position.start_line, position.start_column)?;
+    let end = offset(position.end_line, position.end_column)?;
+    (start <= end).then_some(start..end)
+}
+
+/// Resolves the span of an edit: the bytes of its position, or, when it has an `original`
+/// text which isn't the one of its position, the only occurrence of this text on its lines.
+fn resolve_edit(content: &str, starts: &[usize], edit: &SynFixEdit) -> Option<Range<usize>> {
+    let range = byte_range(content, starts, &edit.position)?;
+    let Some(original) = &edit.original else {
+        return Some(range);
+    };
+    if content.get(range.clone()) == Some(original.as_str()) {
+        return Some(range);
+    }
+    let lines_start = starts[edit.position.start_line as usize - 1];
+    let lines_end = starts
+        .get(edit.position.end_line as usize)
+        .copied()
+        .unwrap_or(content.len());
+    let mut occurrences = content[lines_start..lines_end].match_indices(original.as_str());
+    match (occurrences.next(), occurrences.next()) {
+        (Some((index, _)), None) => Some(lines_start + index..lines_start + index + original.len()),
+        _ => None,
+    }
+}
+
+/// Resolves the fixes of the file `file` against its content `content`.
+///
+/// The fixes are taken in order: a fix is skipped when one of its spans can't be resolved or
+/// overlaps an edit of a previous fix, an edit identical to a previous one being applied once.
+pub fn resolve_fixes(file: &str, content: String, fixes: &[&SynFix]) -> FileFixes {
+    let starts = line_starts(&content);
+    let mut file_fixes = FileFixes {
+        file: file.to_string(),
+        content: String::new(),
+        edits: vec![],
+        applied: vec![],
+        skipped: 0,
+    };
+    for fix in fixes {
+        let resolved: Option<Vec<Edit>> = fix
+            .edits
+            .iter()
+            .map(|edit| {
+                Some((
+                    resolve_edit(&content, &starts, edit)?,
+                    edit.replacement.clone(),
+                ))
+            })
+            .collect();
+        let Some(resolved) = resolved else {
+            warn!(
+                "Fix '{}' skipped in {}: its text is not at the reported position",
+                fix.description, file
+            );
+            file_fixes.skipped += 1;
+            continue;
+        };
+        let new_edits: Vec<_> = resolved
+            .into_iter()
+            .filter(|edit| !file_fixes.edits.contains(edit))
+            .collect();
+        if new_edits.is_empty() {
+            continue;
+        }
+        let overlaps = new_edits.iter().enumerate().any(|(index, (range, _))| {
+            file_fixes
+                .edits
+                .iter()
+                .chain(&new_edits[index + 1..])
+                .any(|(other, _)| {
+                    range.start < other.end && other.start < range.end || range.start == other.start
+                })
+        });
+        if overlaps {
+            warn!(
+                "Fix '{}' skipped in {}: it overlaps another fix",
+                fix.description, file
+            );
+            file_fixes.skipped += 1;
+            continue;
+        }
+        file_fixes.edits.extend(new_edits);
+        file_fixes.applied.push(fix.description.clone());
+    }
+    file_fixes
+        .edits
+        .sort_by_key(|(range, _)| (range.start, range.end));
+    file_fixes.content = content;
+    file_fixes
+}
+
+/// Collects the fixes of the matches of the SAST states, by file, and resolves them against
+/// the files.
+///
+/// # Returns
+///
+/// The fixes of each file having some, or an error if a file can't be read.
+pub fn collect_fixes(states: &[SastState]) -> Result<Vec<FileFixes>> {
+    let mut by_file: BTreeMap<String, Vec<&SynFix>> = BTreeMap::new();
+    for state in states {
+        let mut files: Vec<_> = state.syn_ast_map.iter().collect();
+        files.sort_by_key(|(path, _)| *path);
+        for (_, syn_ast) in files {
+            for fix in syn_ast
+                .results
+                .iter()
+                .flat_map(|result| &result.matches)
+                .filter_map(|match_result| match_result.fix.as_ref())
+            {
+                let Some(file) = fix.edits.first().map(|edit| &edit.position.source_file) else {
+                    continue;
+                };
+                if fix
+                    .edits
+                    .iter()
+                    .any(|edit| &edit.position.source_file != file)
+                {
+                    warn!("Fix '{}' skipped: it edits several files", fix.description);
+                    continue;
+                }
+                let path = state.source_path(file).to_string_lossy().to_string();
+                by_file.entry(path).or_default().push(fix);
+            }
+        }
+    }
+
+    by_file
+        .into_iter()
+        .map(|(file, fixes)| {
+            let content = fs::read_to_string(&file).with_context(|| format!("Reading {}", file))?;
+            Ok(resolve_fixes(&file, content, &fixes))
+        })
+        .collect()
+}
+
+/// Writes the fixed content of the files.
+pub fn apply_fixes(fixes: &[FileFixes]) -> Result<()> {
+    for file_fixes in fixes
+        .iter()
+        .filter(|file_fixes| !file_fixes.edits.is_empty())
+    {
+        fs::write(&file_fixes.file, file_fixes.fixed())
+            .with_context(|| format!("Writing {}", file_fixes.file))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::syn_ast::ColumnEncoding;
+
+    fn edit(
+        line: u32,
+        columns: Range<u32>,
+        replacement: &str,
+        original: Option<&str>,
+    ) -> SynFixEdit {
+        SynFixEdit {
+            position: SourcePosition {
+                start_line: line,
+                start_column: columns.start,
+                end_line: line,
+                end_column: columns.end,
+                source_file: "src/lib.rs".to_string(),
+                column_encoding: ColumnEncoding::Chars,
+            },
+            replacement: replacement.to_string(),
+            original: original.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_resolve_fixes() {
+        let content = "fn f(vault: &mut Vault, amount: u64) -> Result<()> {\n\
+                       \x20   let fee = (amount as u128 * 3 / 100) as u32;\n\
+                       \x20   let total = amount.checked_add(1).unwrap();\n\
+                       \x20   vault.fee = fee;\n\
+                       \x20   Ok(())\n\
+                       }"
+        .to_string();
+        let cast = SynFix {
+            description: "use `u32::try_from`".to_string(),
+            edits: vec![edit(
+                2,
+                14..47,
+                "u32::try_from(amount as u128 * 3 / 100)?",
+                None,
+            )],
+        };
+        // the position of `total`, the `unwrap()` being looked up on its line
+        let unwrap = SynFix {
+            description: "return an error".to_string(),
+            edits: vec![edit(
+                3,
+                8..13,
+                "ok_or(ErrorCode::Overflow)?",
+                Some("unwrap()"),
+            )],
+        };
+        let stale = SynFix {
+            description: "stale".to_string(),
+            edits: vec![edit(4, 4..9, "", Some("unwrap()"))],
+        };
+        let overlapping = SynFix {
+            description: "overlapping".to_string(),
+            edits: vec![edit(2, 15..21, "fee", None)],
+        };
+
+        let fixes = resolve_fixes(
+            "src/lib.rs",
+            content,
+            &[&cast, &unwrap, &stale, &overlapping, &cast],
+        );
+        // the second `cast` fix being the same, it is applied once
+        assert_eq!(fixes.applied, ["use `u32::try_from`", "return an error"]);
+        assert_eq!(fixes.skipped, 2);
+        assert_eq!(
+            fixes.fixed(),
+            "fn f(vault: &mut Vault, amount: u64) -> Result<()> {\n\
+             \x20   let fee = u32::try_from(amount as u128 * 3 / 100)?;\n\
+             \x20   let total = amount.checked_add(1).ok_or(ErrorCode::Overflow)?;\n\
+             \x20   vault.fee = fee;\n\
+             \x20   Ok(())\n\
+             }"
+        );
+        assert_eq!(
+            fixes.diff(),
+            "--- src/lib.rs\n\
+             +++ src/lib.rs\n\
+             @@ -1,6 +1,6 @@\n\
+             \x20fn f(vault: &mut Vault, amount: u64) -> Result<()> {\n\
+             -    let fee = (amount as u128 * 3 / 100) as u32;\n\
+             -    let total = amount.checked_add(1).unwrap();\n\
+             +    let fee = u32::try_from(amount as u128 * 3 / 100)?;\n\
+             +    let total = amount.checked_add(1).ok_or(ErrorCode::Overflow)?;\n\
+             \x20    vault.fee = fee;\n\
+             \x20    Ok(())\n\
+             \x20}\n\
+             \\ No newline at end of file\n"
+        );
+    }
+}
@@ -0,0 +1,636 @@
+//! Harness spec of the `fuzz` command: the accounts given to each instruction of the program.
+//!
+//! An Anchor instruction fails in its account deserialization unless it gets accounts with the
+//! expected owner, size and addresses, so random accounts never reach the handler. The spec
+//! (TOML or JSON) describes, for each instruction, its discriminator, its arguments and its
+//! accounts: fixed pubkey or seed templates of a PDA, owner, lamports, data length and flags.
+//! A skeleton is generated from an Anchor IDL with [`HarnessSpec::from_idl`].
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_sdk::{pubkey::Pubkey, rent::Rent};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::recap::idl::{Idl, IdlAccountItem, IdlSeed};
+use crate::reverse::dispatch::anchor_instruction_discriminator;
+
+/// Lamports given to the signers of the skeleton (10 SOL), to pay for the accounts they create.
+const SIGNER_LAMPORTS: u64 = 10_000_000_000;
+
+/// The runtime limits (`MAX_SEEDS`, `MAX_SEED_LEN`).
+const MAX_SEEDS: usize = 16;
+const MAX_SEED_LEN: usize = 32;
+
+/// Programs and sysvars referenced by name in the IDLs older than Anchor 0.30, which don't give
+/// their address.
+const WELL_KNOWN_ACCOUNTS: &[(&str, &str)] = &[
+    ("system_program", "11111111111111111111111111111111"),
+    (
+        "token_program",
+        "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+    ),
+    (
+        "token_2022_program",
+        "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb",
+    ),
+    (
+        "associated_token_program",
+        "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL",
+    ),
+    ("rent", "SysvarRent111111111111111111111111111111111"),
+    ("clock", "SysvarC1ock11111111111111111111111111111111"),
+    (
+        "instructions",
+        "Sysvar1nstructions1111111111111111111111111",
+    ),
+];
+
+/// The harness spec of a program.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HarnessSpec {
+    /// Program id, owner of the `owner = "program"` accounts and of the PDAs.
+    pub program_id: String,
+    /// Compiled program (`.so`) to fuzz.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub program: Option<String>,
+    #[serde(default)]
+    pub instructions: Vec<InstructionSpec>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstructionSpec {
+    pub name: String,
+    /// Bytes starting the instruction data, before the arguments.
+    #[serde(default)]
+    pub discriminator: Vec<u8>,
+    #[serde(default)]
+    pub args: Vec<ArgSpec>,
+    #[serde(default)]
+    pub accounts: Vec<AccountSpec>,
+}
+
+/// An argument of an instruction, with its IDL type (`u64`, `vec<u8>`, `[u8; 32]`, `Config`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArgSpec {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+/// An account given to an instruction, in the order of the instruction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountSpec {
+    pub name: String,
+    /// Fixed address. Without it nor `seeds`, a new address is generated for each input.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pubkey: Option<String>,
+    /// Seed templates of a PDA of the program: `const:<text>`, `hex:<bytes>`,
+    /// `account:<name>` (an account of the instruction) or `arg:<name>` (an argument).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub seeds: Vec<String>,
+    /// `program`, `system` or a pubkey. Unset for the accounts provided by the runtime
+    /// (programs, sysvars).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub lamports: u64,
+    #[serde(default)]
+    pub data_len: usize,
+    #[serde(default)]
+    pub signer: bool,
+    #[serde(default)]
+    pub writable: bool,
+    #[serde(default)]
+    pub executable: bool,
+}
+
+/// A parsed seed template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeedTemplate {
+    Const(Vec<u8>),
+    Account(String),
+    Arg(String),
+}
+
+impl FromStr for SeedTemplate {
+    type Err = anyhow::Error;
+
+    fn from_str(template: &str) -> Result<Self> {
+        let (kind, value) = template.split_once(':').ok_or_else(|| {
+            anyhow!(
+                "Seed '{}' has no kind (const:, hex:, account:, arg:)",
+                template
+            )
+        })?;
+        match kind {
+            "const" => Ok(SeedTemplate::Const(value.as_bytes().to_vec())),
+            "hex" => Ok(SeedTemplate::Const(
+                hex::decode(value).with_context(|| format!("Seed '{}'", template))?,
+            )),
+            "account" => Ok(SeedTemplate::Account(value.to_string())),
+            "arg" => Ok(SeedTemplate::Arg(value.to_string())),
+            _ => Err(anyhow!("Unknown seed kind '{}' in '{}'", kind, template)),
+        }
+    }
+}
+
+impl SeedTemplate {
+    /// The template of a PDA seed of an IDL.
+    fn from_idl(seed: &IdlSeed) -> Option<Self> {
+        // `path` is `account` or `account.field`, only the account is used
+        let path = || {
+            seed.path
+                .as_deref()
+                .map(|path| path.split('.').next().unwrap_or(path))
+        };
+        match seed.kind.as_str() {
+            "const" => Some(SeedTemplate::Const(
+                seed.value
+                    .as_array()?
+                    .iter()
+                    .map(|byte| byte.as_u64().map(|byte| byte as u8))
+                    .collect::<Option<_>>()?,
+            )),
+            "account" => Some(SeedTemplate::Account(path()?.to_string())),
+            "arg" => Some(SeedTemplate::Arg(path()?.to_string())),
+            _ => None,
+        }
+    }
+
+    fn to_template(&self) -> String {
+        match self {
+            SeedTemplate::Const(bytes) => match std::str::from_utf8(bytes) {
+                Ok(text) if !text.is_empty() && text.chars().all(|c| c.is_ascii_graphic()) => {
+                    format!("const:{}", text)
+                }
+                _ => format!("hex:{}", hex::encode(bytes)),
+            },
+            SeedTemplate::Account(name) => format!("account:{}", name),
+            SeedTemplate::Arg(name) => format!("arg:{}", name),
+        }
+    }
+}
+
+impl HarnessSpec {
+    /// Reads a spec, as JSON for a `.json` file and TOML otherwise, and validates it.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Reading harness spec {}", path.display()))?;
+        let spec: Self = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&content)
+                .with_context(|| format!("Parsing harness spec {}", path.display()))?
+        } else {
+            toml::from_str(&content)
+                .with_context(|| format!("Parsing harness spec {}", path.display()))?
+        };
+        spec.validate()
+            .with_context(|| format!("Invalid harness spec {}", path.display()))?;
+        Ok(spec)
+    }
+
+    /// Writes the spec, as JSON for a `.json` path and TOML otherwise.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::to_string_pretty(self)? + "\n"
+        } else {
+            toml::to_string(self)?
+        };
+        std::fs::write(path, content).with_context(|| format!("Writing {}", path.display()))
+    }
+
+    /// Checks the addresses, the owners and the seed templates of the accounts.
+    pub fn validate(&self) -> Result<()> {
+        parse_pubkey(&self.program_id, "program_id")?;
+        let mut names = HashSet::new();
+        for instruction in &self.instructions {
+            if !names.insert(&instruction.name) {
+                return Err(anyhow!(
+                    "Instruction '{}' is defined twice",
+                    instruction.name
+                ));
+            }
+            instruction
+                .validate()
+                .with_context(|| format!("Instruction '{}'", instruction.name))?;
+        }
+        Ok(())
+    }
+
+    /// Generates the skeleton spec of an Anchor IDL: the discriminator and the arguments of each
+    /// instruction, and its accounts with the addresses, owners and data lengths the IDL tells
+    /// (signers funded, PDAs derived from their seeds, data length of the account type of the
+    /// same name).
+    pub fn from_idl(idl: &Idl) -> Self {
+        let program_id = idl
+            .address
+            .clone()
+            .or_else(|| idl.metadata.as_ref().and_then(|m| m.address.clone()))
+            .unwrap_or_default();
+        let instructions = idl
+            .instructions
+            .iter()
+            .map(|instruction| {
+                let mut accounts = vec![];
+                flatten_idl_accounts(idl, &instruction.accounts, &mut accounts);
+                InstructionSpec {
+                    name: instruction.name.clone(),
+                    discriminator: instruction.discriminator.clone().unwrap_or_else(|| {
+                        anchor_instruction_discriminator(&instruction.name)
+                            .to_le_bytes()
+                            .to_vec()
+                    }),
+                    args: instruction
+                        .args
+                        .iter()
+                        .map(|arg| ArgSpec {
+                            name: arg.name.clone(),
+                            ty: type_name(&arg.r#type),
+                        })
+                        .collect(),
+                    accounts,
+                }
+            })
+            .collect();
+        Self {
+            program_id,
+            program: None,
+            instructions,
+        }
+    }
+}
+
+impl InstructionSpec {
+    fn validate(&self) -> Result<()> {
+        let names: HashSet<&str> = self.accounts.iter().map(|a| a.name.as_str()).collect();
+        if names.len() != self.accounts.len() {
+            return Err(anyhow!("An account name is used twice"));
+        }
+        for account in &self.accounts {
+            let context = || format!("Account '{}'", account.name);
+            if let Some(pubkey) = &account.pubkey {
+                parse_pubkey(pubkey, "pubkey").with_context(context)?;
+                if !account.seeds.is_empty() {
+                    return Err(anyhow!("Both a pubkey and seeds are given")).with_context(context);
+                }
+            }
+            if let Some(owner) = account.owner.as_deref() {
+                if !matches!(owner, "program" | "system") {
+                    parse_pubkey(owner, "owner").with_context(context)?;
+                }
+            }
+            if account.seeds.len() > MAX_SEEDS {
+                return Err(anyhow!("More than {} seeds", MAX_SEEDS)).with_context(context);
+            }
+            for seed in &account.seeds {
+                match seed.parse::<SeedTemplate>().with_context(context)? {
+                    SeedTemplate::Const(bytes) if bytes.len() > MAX_SEED_LEN => {
+                        return Err(anyhow!(
+                            "Seed '{}' is longer than {} bytes",
+                            seed,
+                            MAX_SEED_LEN
+                        ))
+                        .with_context(context);
+                    }
+                    SeedTemplate::Account(name) if !names.contains(name.as_str()) => {
+                        return Err(anyhow!("Seed '{}': no such account", seed))
+                            .with_context(context);
+                    }
+                    SeedTemplate::Arg(name) if !self.args.iter().any(|arg| arg.name == name) => {
+                        return Err(anyhow!("Seed '{}': no such argument", seed))
+                            .with_context(context);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolves the addresses of the fixed accounts and of the PDAs, given the encoded value of
+    /// the arguments used in seeds.
+    ///
+    /// # Returns
+    ///
+    /// The address of each account, `None` for the accounts whose address is generated, and the
+    /// PDAs depending on them or on a missing argument.
+    pub fn resolve_addresses(
+        &self,
+        program_id: &Pubkey,
+        args: &HashMap<String, Vec<u8>>,
+    ) -> Result<Vec<Option<Pubkey>>> {
+        let mut addresses: Vec<Option<Pubkey>> = self
+            .accounts
+            .iter()
+            .map(|account| {
+                account
+                    .pubkey
+                    .as_deref()
+                    .map(|pubkey| parse_pubkey(pubkey, "pubkey"))
+                    .transpose()
+            })
+            .collect::<Result<_>>()?;
+        // a PDA may depend on a PDA listed after it
+        for _ in 0..self.accounts.len() {
+            let mut changed = false;
+            for (index, account) in self.accounts.iter().enumerate() {
+                if addresses[index].is_some() || account.seeds.is_empty() {
+                    continue;
+                }
+                let mut seeds = vec![];
+                for seed in &account.seeds {
+                    seeds.push(match seed.parse::<SeedTemplate>()? {
+                        SeedTemplate::Const(bytes) => Some(bytes),
+                        SeedTemplate::Account(name) => self
+                            .accounts
+                            .iter()
+                            .position(|account| account.name == name)
+                            .and_then(|position| addresses[position])
+                            .map(|address| address.to_bytes().to_vec()),
+                        SeedTemplate::Arg(name) => args.get(&name).cloned(),
+                    });
+                }
+                if let Some(seeds) = seeds.into_iter().collect::<Option<Vec<_>>>() {
+                    let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+                    addresses[index] = Some(Pubkey::find_program_address(&seeds, program_id).0);
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        Ok(addresses)
+    }
+}
+
+fn parse_pubkey(value: &str, field: &str) -> Result<Pubkey> {
+    if value.is_empty() {
+        return Err(anyhow!("{} is not set", field));
+    }
+    Pubkey::from_str(value).map_err(|e| anyhow!("Invalid {} '{}': {}", field, value, e))
+}
+
+/// Flattens the (possibly nested) accounts of an IDL instruction into account specs.
+fn flatten_idl_accounts(idl: &Idl, items: &[IdlAccountItem], out: &mut Vec<AccountSpec>) {
+    for item in items {
+        if !item.accounts.is_empty() {
+            flatten_idl_accounts(idl, &item.accounts, out);
+            continue;
+        }
+        let signer = item
+            .signer
+            .unwrap_or_else(|| item.isSigner.unwrap_or(false));
+        let writable = item.writable.unwrap_or_else(|| item.isMut.unwrap_or(false));
+        let pubkey = item.address.clone().or_else(|| {
+            WELL_KNOWN_ACCOUNTS
+                .iter()
+                .find(|(name, _)| *name == item.name || snake_to_camel(name) == item.name)
+                .map(|(_, address)| address.to_string())
+        });
+        let seeds: Vec<String> = item
+            .pda
+            .iter()
+            .flat_map(|pda| &pda.seeds)
+            .filter_map(SeedTemplate::from_idl)
+            .map(|seed| seed.to_template())
+            .collect();
+        let data_len = account_data_len(idl, &item.name).unwrap_or(0);
+        let (owner, lamports) = if pubkey.is_some() {
+            (None, 0)
+        } else if signer {
+            (Some("system"), SIGNER_LAMPORTS)
+        } else if !seeds.is_empty() || data_len > 0 {
+            (Some("program"), Rent::default().minimum_balance(data_len))
+        } else {
+            (Some("system"), Rent::default().minimum_balance(0))
+        };
+        out.push(AccountSpec {
+            name: item.name.clone(),
+            executable: pubkey.is_some() && item.name.to_lowercase().ends_with("program"),
+            pubkey,
+            seeds,
+            owner: owner.map(str::to_string),
+            lamports,
+            data_len,
+            signer,
+            writable,
+        });
+    }
+}
+
+fn snake_to_camel(name: &str) -> String {
+    let pascal = snake_to_pascal(name);
+    let mut chars = pascal.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+fn snake_to_pascal(name: &str) -> String {
+    name.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Size of the data of the account `name` (`user_state`, `userState`): the 8-byte
+/// discriminator and the smallest encoding of the account type of the same name (`UserState`),
+/// if the IDL has it.
+fn account_data_len(idl: &Idl, name: &str) -> Option<usize> {
+    let type_name = snake_to_pascal(name);
+    idl.accounts
+        .iter()
+        .find(|account| account.name == type_name)?;
+    Some(8 + definition_size(idl, &type_name, 0)?)
+}
+
+/// Finds a type definition by name, in the `types` then the `accounts` (IDLs < 0.30).
+fn type_definition<'a>(idl: &'a Idl, name: &str) -> Option<&'a Value> {
+    idl.types
+        .iter()
+        .find(|def| def.name == name)
+        .map(|def| &def.r#type)
+        .or_else(|| {
+            idl.accounts
+                .iter()
+                .find(|account| account.name == name && !account.r#type.is_null())
+                .map(|account| &account.r#type)
+        })
+}
+
+fn definition_size(idl: &Idl, name: &str, depth: usize) -> Option<usize> {
+    // recursive types have no fixed size
+    if depth > 16 {
+        return None;
+    }
+    let definition = type_definition(idl, name)?;
+    let fields_size = |fields: &Value| -> Option<usize> {
+        fields
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|field| type_size(idl, field.get("type").unwrap_or(field), depth + 1))
+            .sum()
+    };
+    match definition["kind"].as_str()? {
+        "struct" => fields_size(&definition["fields"]),
+        "enum" => definition["variants"]
+            .as_array()?
+            .iter()
+            .map(|variant| fields_size(&variant["fields"]))
+            .collect::<Option<Vec<_>>>()
+            .map(|sizes| 1 + sizes.into_iter().max().unwrap_or(0)),
+        "type" => type_size(idl, &definition["alias"], depth + 1),
+        _ => None,
+    }
+}
+
+/// Smallest Borsh encoding of a value of an IDL type: empty vectors and strings, `None`
+/// options (sized as `Some` for the account to have room for it).
+fn type_size(idl: &Idl, ty: &Value, depth: usize) -> Option<usize> {
+    if let Some(name) = ty.as_str() {
+        return match name {
+            "bool" | "u8" | "i8" => Some(1),
+            "u16" | "i16" => Some(2),
+            "u32" | "i32" | "f32" | "string" | "bytes" => Some(4),
+            "u64" | "i64" | "f64" => Some(8),
+            "u128" | "i128" => Some(16),
+            "pubkey" | "publicKey" => Some(32),
+            _ => None,
+        };
+    }
+    if ty.get("vec").is_some() {
+        Some(4)
+    } else if let Some(inner) = ty.get("option") {
+        Some(1 + type_size(idl, inner, depth)?)
+    } else if let Some(inner) = ty.get("coption") {
+        Some(4 + type_size(idl, inner, depth)?)
+    } else if let Some(array) = ty.get("array").and_then(Value::as_array) {
+        let len = array.get(1).and_then(Value::as_u64)? as usize;
+        Some(len * type_size(idl, array.first()?, depth)?)
+    } else {
+        let defined = ty.get("defined")?;
+        definition_size(
+            idl,
+            defined.as_str().or_else(|| defined["name"].as_str())?,
+            depth,
+        )
+    }
+}
+
+/// Name of an IDL type, as written in the spec (`u64`, `vec<u8>`, `option<pubkey>`,
+/// `[u8; 32]`, `Config`).
+fn type_name(ty: &Value) -> String {
+    if let Some(name) = ty.as_str() {
+        return name.to_string();
+    }
+    if let Some(inner) = ty.get("vec") {
+        format!("vec<{}>", type_name(inner))
+    } else if let Some(inner) = ty.get("option") {
+        format!("option<{}>", type_name(inner))
+    } else if let Some(inner) = ty.get("coption") {
+        format!("coption<{}>", type_name(inner))
+    } else if let Some(array) = ty.get("array").and_then(Value::as_array) {
+        format!(
+            "[{}; {}]",
+            array.first().map_or("?".to_string(), type_name),
+            array.get(1).map_or("?".to_string(), Value::to_string)
+        )
+    } else if let Some(defined) = ty.get("defined") {
+        defined
+            .as_str()
+            .or_else(|| defined["name"].as_str())
+            .unwrap_or("?")
+            .to_string()
+    } else {
+        ty.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_harness_spec_from_idl() {
+        let idl: Idl = serde_json::from_value(serde_json::json!({
+            "address": "Recap11111111111111111111111111111111111111",
+            "instructions": [{
+                "name": "deposit",
+                "discriminator": [0, 0, 0, 0, 0, 0, 0, 2],
+                "accounts": [
+                    { "name": "user_state", "writable": true, "pda": { "seeds": [
+                        { "kind": "const", "value": [117, 115, 101, 114] },
+                        { "kind": "account", "path": "user" }
+                    ]}},
+                    { "name": "vault", "writable": true, "pda": { "seeds": [
+                        { "kind": "arg", "path": "vault_id" }
+                    ]}},
+                    { "name": "user", "writable": true, "signer": true },
+                    { "name": "system_program" }
+                ],
+                "args": [
+                    { "name": "vault_id", "type": "u64" },
+                    { "name": "memo", "type": { "option": "string" } }
+                ]
+            }],
+            "accounts": [{ "name": "UserState", "discriminator": [1, 2, 3, 4, 5, 6, 7, 8] }],
+            "types": [{ "name": "UserState", "type": { "kind": "struct", "fields": [
+                { "name": "owner", "type": "pubkey" },
+                { "name": "amounts", "type": { "array": ["u64", 4] } },
+                { "name": "label", "type": "string" }
+            ]}}]
+        }))
+        .unwrap();
+        let spec = HarnessSpec::from_idl(&idl);
+        spec.validate().unwrap();
+
+        let deposit = &spec.instructions[0];
+        assert_eq!(deposit.args[1].ty, "option<string>");
+        let user_state = &deposit.accounts[0];
+        assert_eq!(user_state.seeds, ["const:user", "account:user"]);
+        assert_eq!(user_state.owner.as_deref(), Some("program"));
+        assert_eq!(user_state.data_len, 8 + 32 + 4 * 8 + 4);
+        assert_eq!(deposit.accounts[2].lamports, SIGNER_LAMPORTS);
+        let system_program = &deposit.accounts[3];
+        assert_eq!(
+            system_program.pubkey.as_deref(),
+            Some("11111111111111111111111111111111")
+        );
+        assert!(system_program.executable && system_program.owner.is_none());
+
+        // the TOML round trip keeps the spec
+        let toml = toml::to_string_pretty(&spec).unwrap();
+        assert_eq!(toml::from_str::<HarnessSpec>(&toml).unwrap(), spec);
+
+        // user_state is derived once the user is known, vault with its argument
+        let program_id = Pubkey::from_str(&spec.program_id).unwrap();
+        let mut with_user = deposit.clone();
+        let user = Pubkey::new_unique();
+        with_user.accounts[2].pubkey = Some(user.to_string());
+        let args = HashMap::from([("vault_id".to_string(), 7u64.to_le_bytes().to_vec())]);
+        let addresses = with_user.resolve_addresses(&program_id, &args).unwrap();
+        assert_eq!(
+            addresses[0],
+            Some(Pubkey::find_program_address(&[b"user", user.as_ref()], &program_id).0)
+        );
+        assert!(addresses[1].is_some());
+        assert!(deposit
+            .resolve_addresses(&program_id, &HashMap::new())
+            .unwrap()[0]
+            .is_none());
+
+        let mut invalid = deposit.clone();
+        invalid.accounts[0].seeds.push("account:nobody".to_string());
+        assert!(invalid.validate().is_err());
+    }
+}
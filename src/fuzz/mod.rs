@@ -0,0 +1,12 @@
+//! Fuzzing support.
+//!
+//! The fuzzing engine of the `fuzz` command is not implemented yet. This module holds what it
+//! is configured with:
+//!
+//! - [`harness`] — Harness spec: the accounts given to each instruction, with a skeleton
+//!   generated from an Anchor IDL.
+//!
+//! The initial corpus is generated by the `fuzz-corpus` pass of the reverse command (see
+//! [`crate::reverse::fuzz_corpus`]).
+
+pub mod harness;
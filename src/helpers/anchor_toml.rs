@@ -0,0 +1,398 @@
+//! Discovery of the program crates of an Anchor workspace from its `Anchor.toml`.
+//!
+//! The crates are taken from the `[workspace]` members when they are listed, otherwise from
+//! the programs declared in the `[programs.<cluster>]` tables, matched against the package
+//! (or lib) names of the crates of the workspace. The crates under another `Anchor.toml` belong
+//! to that workspace, not to the enclosing one.
+//!
+//! The library crates the programs depend on by path (`libs/common`, `../shared`, ...) are
+//! scanned with them, once, whichever programs use them.
+
+use log::{debug, warn};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Component, Path, PathBuf};
+
+#[derive(Debug, Default, Deserialize)]
+struct AnchorToml {
+    #[serde(default)]
+    workspace: AnchorWorkspace,
+    /// `cluster -> program name -> address`
+    #[serde(default)]
+    programs: BTreeMap<String, BTreeMap<String, toml::Value>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct AnchorWorkspace {
+    #[serde(default)]
+    members: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+/// Returns `true` if `name` matches `pattern`, where `*` matches any sequence of characters.
+fn matches_wildcard(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => {
+            let Some(name) = name.strip_prefix(prefix) else {
+                return false;
+            };
+            (0..=name.len())
+                .filter(|&i| name.is_char_boundary(i))
+                .any(|i| matches_wildcard(rest, &name[i..]))
+        }
+    }
+}
+
+/// Expands a workspace member (`programs/*`, `programs/vault`, ...) relative to `root`.
+///
+/// Wildcards are only supported in the last component.
+fn expand_member(root: &Path, member: &str) -> Vec<PathBuf> {
+    let path = root.join(member.trim_end_matches('/'));
+    let Some(last) = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+    else {
+        return vec![];
+    };
+    if !last.contains('*') {
+        return vec![path].into_iter().filter(|p| p.is_dir()).collect();
+    }
+    let parent = path.parent().unwrap_or(root);
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(parent)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|p| p.is_dir())
+                .filter(|p| {
+                    p.file_name()
+                        .is_some_and(|name| matches_wildcard(&last, &name.to_string_lossy()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    dirs.sort();
+    dirs
+}
+
+/// Returns the package and lib names of a crate, with `-` replaced by `_`.
+pub(crate) fn crate_names(crate_dir: &Path) -> Vec<String> {
+    let Ok(content) = std::fs::read_to_string(crate_dir.join("Cargo.toml")) else {
+        return vec![];
+    };
+    let Ok(manifest) = content.parse::<toml::Value>() else {
+        return vec![];
+    };
+    ["package", "lib"]
+        .iter()
+        .filter_map(|table| manifest.get(table)?.get("name")?.as_str())
+        .map(|name| name.replace('-', "_"))
+        .collect()
+}
+
+/// Recursively lists the directories containing a `Cargo.toml`, skipping `target`,
+/// `node_modules` and hidden directories, and the nested Anchor workspaces (directories with
+/// their own `Anchor.toml`).
+fn find_crate_dirs(dir: &Path, crate_dirs: &mut Vec<PathBuf>) {
+    if dir.join("Cargo.toml").is_file() {
+        crate_dirs.push(dir.to_path_buf());
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut sub_dirs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|p| p.is_dir())
+        .filter(|p| {
+            let name = p.file_name().unwrap_or_default().to_string_lossy();
+            !name.starts_with('.') && name != "target" && name != "node_modules"
+        })
+        .filter(|p| !p.join("Anchor.toml").is_file())
+        .collect();
+    sub_dirs.sort();
+    for sub_dir in sub_dirs {
+        find_crate_dirs(&sub_dir, crate_dirs);
+    }
+}
+
+/// Returns the directories of the program crates declared by the `Anchor.toml` of `root`.
+///
+/// # Arguments
+///
+/// * `root` - The root of the Anchor workspace.
+///
+/// # Returns
+///
+/// The crate directories, or `None` if `Anchor.toml` is missing or invalid, or declares no
+/// program that could be located (the caller then falls back on `programs/`).
+pub fn anchor_program_dirs(root: &Path) -> Option<Vec<PathBuf>> {
+    let content = std::fs::read_to_string(root.join("Anchor.toml")).ok()?;
+    let anchor_toml: AnchorToml = match toml::from_str(&content) {
+        Ok(anchor_toml) => anchor_toml,
+        Err(e) => {
+            warn!("Cannot parse {}/Anchor.toml: {}", root.display(), e);
+            return None;
+        }
+    };
+
+    let dirs: Vec<PathBuf> = if !anchor_toml.workspace.members.is_empty() {
+        let excluded: BTreeSet<PathBuf> = anchor_toml
+            .workspace
+            .exclude
+            .iter()
+            .flat_map(|member| expand_member(root, member))
+            .collect();
+        anchor_toml
+            .workspace
+            .members
+            .iter()
+            .flat_map(|member| expand_member(root, member))
+            .filter(|dir| !excluded.contains(dir))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    } else {
+        let programs: BTreeSet<String> = anchor_toml
+            .programs
+            .values()
+            .flat_map(|cluster| cluster.keys())
+            .map(|name| name.replace('-', "_"))
+            .collect();
+        let mut crate_dirs = vec![];
+        if !programs.is_empty() {
+            find_crate_dirs(root, &mut crate_dirs);
+        }
+        crate_dirs
+            .into_iter()
+            .filter(|dir| crate_names(dir).iter().any(|name| programs.contains(name)))
+            .collect()
+    };
+
+    debug!(
+        "Programs of {} from Anchor.toml: {:?}",
+        root.display(),
+        dirs
+    );
+    (!dirs.is_empty()).then_some(dirs)
+}
+
+/// Removes the `..` components of a path without resolving the symbolic links, so that a
+/// dependency (`programs/vault/../../libs/math`) keeps the prefix it was found from.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        let last = normalized.components().next_back();
+        match component {
+            Component::ParentDir if matches!(last, Some(Component::Normal(_))) => {
+                normalized.pop();
+            }
+            component => normalized.push(component.as_os_str()),
+        }
+    }
+    normalized
+}
+
+/// Returns the directories of the crates `crate_dir` depends on by path, in its
+/// `[dependencies]` and `[target.'cfg(...)'.dependencies]` tables.
+///
+/// The `workspace = true` dependencies are looked up in the `[workspace.dependencies]` of the
+/// `Cargo.toml` of `workspace_root`.
+fn path_dependency_dirs(crate_dir: &Path, workspace_root: &Path) -> Vec<PathBuf> {
+    let read_manifest = |dir: &Path| {
+        std::fs::read_to_string(dir.join("Cargo.toml"))
+            .ok()
+            .and_then(|content| content.parse::<toml::Value>().ok())
+    };
+    let Some(manifest) = read_manifest(crate_dir) else {
+        return vec![];
+    };
+    let workspace_manifest = read_manifest(workspace_root);
+    let workspace_dependencies = workspace_manifest
+        .as_ref()
+        .and_then(|manifest| manifest.get("workspace")?.get("dependencies"));
+
+    let targets = manifest
+        .get("target")
+        .and_then(|targets| targets.as_table())
+        .into_iter()
+        .flat_map(|targets| targets.values());
+    let mut dirs = vec![];
+    for dependencies in std::iter::once(&manifest)
+        .chain(targets)
+        .filter_map(|table| table.get("dependencies")?.as_table())
+    {
+        for (name, dependency) in dependencies {
+            let dir = match dependency.get("path").and_then(|path| path.as_str()) {
+                Some(path) => crate_dir.join(path),
+                None if dependency.get("workspace").and_then(|w| w.as_bool()) == Some(true) => {
+                    match workspace_dependencies
+                        .and_then(|dependencies| dependencies.get(name)?.get("path")?.as_str())
+                    {
+                        Some(path) => workspace_root.join(path),
+                        None => continue,
+                    }
+                }
+                None => continue,
+            };
+            dirs.push(normalize_path(&dir));
+        }
+    }
+    dirs
+}
+
+/// Returns the library crates of an Anchor workspace: the crates its programs depend on by
+/// path, directly or through another library, wherever they are.
+///
+/// # Arguments
+///
+/// * `root` - The root of the Anchor workspace, whose `Cargo.toml` holds the
+///   `[workspace.dependencies]`.
+/// * `program_dirs` - The directories of its program crates (see [`anchor_program_dirs`]).
+///
+/// # Returns
+///
+/// The directory of each library crate, with the program crates depending on it.
+pub fn anchor_library_dirs(
+    root: &Path,
+    program_dirs: &[PathBuf],
+) -> BTreeMap<PathBuf, BTreeSet<PathBuf>> {
+    let programs: BTreeSet<PathBuf> = program_dirs.iter().map(|dir| normalize_path(dir)).collect();
+    let mut libraries: BTreeMap<PathBuf, BTreeSet<PathBuf>> = BTreeMap::new();
+    for program_dir in program_dirs {
+        let mut visited = BTreeSet::new();
+        let mut stack = path_dependency_dirs(program_dir, root);
+        while let Some(dir) = stack.pop() {
+            if programs.contains(&dir)
+                || !dir.join("Cargo.toml").is_file()
+                || !visited.insert(dir.clone())
+            {
+                continue;
+            }
+            stack.extend(path_dependency_dirs(&dir, root));
+            libraries
+                .entry(dir)
+                .or_default()
+                .insert(program_dir.clone());
+        }
+    }
+    debug!(
+        "Libraries of {} from the program manifests: {:?}",
+        root.display(),
+        libraries.keys()
+    );
+    libraries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_files(root: &Path, files: &[(&str, &str)]) {
+        let _ = std::fs::remove_dir_all(root);
+        for (file, content) in files {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_anchor_program_dirs() {
+        let root = std::env::temp_dir().join(format!("sol-azy-anchor-toml-{}", std::process::id()));
+
+        // programs declared per cluster, kept outside of programs/
+        write_files(
+            &root,
+            &[
+                ("Anchor.toml", "[programs.localnet]\nmy_vault = \"Vau1t11111111111111111111111111111111111111\"\n"),
+                ("Cargo.toml", "[workspace]\nmembers = [\"onchain/*\"]\n"),
+                ("onchain/vault/Cargo.toml", "[package]\nname = \"my-vault\"\n"),
+                ("onchain/client/Cargo.toml", "[package]\nname = \"client\"\n"),
+            ],
+        );
+        assert_eq!(
+            anchor_program_dirs(&root),
+            Some(vec![root.join("onchain/vault")])
+        );
+
+        // workspace members take precedence
+        write_files(
+            &root,
+            &[
+                (
+                    "Anchor.toml",
+                    "[workspace]\nmembers = [\"programs/*\", \"extra/one\"]\nexclude = [\"programs/legacy\"]\n",
+                ),
+                ("programs/a/Cargo.toml", ""),
+                ("programs/legacy/Cargo.toml", ""),
+                ("extra/one/Cargo.toml", ""),
+            ],
+        );
+        assert_eq!(
+            anchor_program_dirs(&root),
+            Some(vec![root.join("extra/one"), root.join("programs/a")])
+        );
+
+        // nothing usable: fallback
+        write_files(
+            &root,
+            &[("Anchor.toml", "[provider]\ncluster = \"localnet\"\n")],
+        );
+        assert_eq!(anchor_program_dirs(&root), None);
+
+        assert!(matches_wildcard("prog*-v*", "program-v2"));
+        assert!(!matches_wildcard("prog*", "client"));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_anchor_library_dirs() {
+        let root = std::env::temp_dir().join(format!("sol-azy-anchor-libs-{}", std::process::id()));
+        write_files(
+            &root,
+            &[
+                (
+                    "Anchor.toml",
+                    "[programs.localnet]\nvault = \"\"\nstaking = \"\"\n",
+                ),
+                (
+                    "Cargo.toml",
+                    "[workspace.dependencies]\nmath = { path = \"shared/math\" }\n",
+                ),
+                (
+                    "programs/vault/Cargo.toml",
+                    "[package]\nname = \"vault\"\n[dependencies]\ncommon = { path = \"../../libs/common\" }\nanchor-lang = \"0.30\"\n",
+                ),
+                (
+                    "programs/staking/Cargo.toml",
+                    "[package]\nname = \"staking\"\n[dependencies]\nmath = { workspace = true }\nvault = { path = \"../vault\" }\n",
+                ),
+                (
+                    "libs/common/Cargo.toml",
+                    "[target.'cfg(target_os = \"solana\")'.dependencies]\nmath = { path = \"../../shared/math\" }\n",
+                ),
+                ("shared/math/Cargo.toml", ""),
+                // a crate of another workspace isn't a program of this one
+                ("nested/Anchor.toml", "[programs.localnet]\nvault = \"\"\n"),
+                ("nested/programs/vault/Cargo.toml", "[package]\nname = \"vault\"\n"),
+            ],
+        );
+        let programs = anchor_program_dirs(&root).unwrap();
+        let (staking, vault) = (root.join("programs/staking"), root.join("programs/vault"));
+        assert_eq!(programs, vec![staking.clone(), vault.clone()]);
+
+        assert_eq!(
+            anchor_library_dirs(&root, &programs),
+            BTreeMap::from([
+                (root.join("libs/common"), BTreeSet::from([vault.clone()])),
+                (root.join("shared/math"), BTreeSet::from([staking, vault])),
+            ])
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
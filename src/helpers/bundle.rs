@@ -0,0 +1,486 @@
+//! Artifact bundles (`--bundle`): zip archives and npm tarballs holding a compiled program
+//! with its IDL and metadata, as shipped for verifiable builds.
+//!
+//! A bundle is extracted to a new temporary directory with a random name, removed when the
+//! [`Bundle`] is dropped, with `unzip` or `tar`. Its files are then found by their content
+//! rather than by their place in the archive: the programs are the `.so` ELF files, the IDLs
+//! the JSON files with a list of `instructions`, and the Anchor workspaces the directories
+//! holding an `Anchor.toml`. The symbolic links of the archive are not followed.
+
+use anyhow::{anyhow, Context, Result};
+use log::{debug, warn};
+use serde_json::Value;
+use std::collections::hash_map::RandomState;
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{ErrorKind, Read};
+use std::path::{Path, PathBuf};
+
+use crate::helpers::{check_binary_installed, run_command};
+
+/// Directories of the bundle that are not looked into.
+const SKIPPED_DIRS: &[&str] = &["node_modules", ".git"];
+
+/// Attempts at creating an extraction directory whose random name isn't taken.
+const MAX_DIR_ATTEMPTS: usize = 16;
+
+/// Archive format of a bundle, detected from its first bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BundleFormat {
+    Zip,
+    /// A tarball, compressed (npm's `.tgz`) or not.
+    Tar,
+}
+
+impl BundleFormat {
+    /// Detects the format of the archive at `path`.
+    pub fn detect(path: &Path) -> Result<Self> {
+        let mut header = [0u8; 262];
+        let mut file =
+            fs::File::open(path).with_context(|| format!("Opening bundle {}", path.display()))?;
+        let read = file.read(&mut header)?;
+        let header = &header[..read];
+        if header.starts_with(b"PK\x03\x04") || header.starts_with(b"PK\x05\x06") {
+            Ok(Self::Zip)
+        } else if header.starts_with(&[0x1f, 0x8b]) || header.get(257..262) == Some(b"ustar") {
+            Ok(Self::Tar)
+        } else {
+            Err(anyhow!(
+                "Bundle {} is neither a zip archive nor a tarball",
+                path.display()
+            ))
+        }
+    }
+}
+
+/// An IDL found in a bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleIdl {
+    pub path: PathBuf,
+    /// Name of the program (`name`, or `metadata.name` for the Anchor 0.30 IDLs).
+    pub name: Option<String>,
+}
+
+/// The files of an extracted bundle.
+#[derive(Debug)]
+pub struct Bundle {
+    /// Directory the bundle was extracted to.
+    pub dir: PathBuf,
+    /// The compiled programs, sorted.
+    pub programs: Vec<PathBuf>,
+    pub idls: Vec<BundleIdl>,
+    /// The directories holding an `Anchor.toml`, the shallowest first.
+    pub anchor_roots: Vec<PathBuf>,
+    /// Whether `dir` is removed when the bundle is dropped.
+    temporary: bool,
+}
+
+/// Returns the files under `dir`, sorted, without the [`SKIPPED_DIRS`] and the symbolic links,
+/// which could lead out of the bundle or back to one of its parents.
+fn walk(dir: &Path) -> Vec<PathBuf> {
+    let mut files = vec![];
+    let Ok(entries) = fs::read_dir(dir) else {
+        return files;
+    };
+    let mut entries: Vec<(PathBuf, fs::FileType)> = entries
+        .flatten()
+        .filter_map(|entry| Some((entry.path(), entry.file_type().ok()?)))
+        .collect();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (path, file_type) in entries {
+        if file_type.is_symlink() {
+            debug!("Skipping the symbolic link {}", path.display());
+        } else if file_type.is_dir() {
+            if !path
+                .file_name()
+                .is_some_and(|name| SKIPPED_DIRS.iter().any(|skipped| name == *skipped))
+            {
+                files.extend(walk(&path));
+            }
+        } else {
+            files.push(path);
+        }
+    }
+    files
+}
+
+/// Returns `true` if the file at `path` is an ELF.
+fn is_elf(path: &Path) -> bool {
+    let mut magic = [0u8; 4];
+    fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .is_ok()
+        && magic == *b"\x7fELF"
+}
+
+/// Reads the JSON file at `path` as an IDL, if it has a list of `instructions`.
+fn read_idl(path: &Path) -> Option<BundleIdl> {
+    let content = fs::read_to_string(path).ok()?;
+    let json: Value = serde_json::from_str(&content).ok()?;
+    json.get("instructions")?.as_array()?;
+    let name = json
+        .get("name")
+        .or_else(|| json.pointer("/metadata/name"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    Some(BundleIdl {
+        path: path.to_path_buf(),
+        name,
+    })
+}
+
+/// Extracts `archive` to `dir`, with `unzip` or `tar`.
+pub(crate) fn extract_to(archive: &Path, format: BundleFormat, dir: &Path) -> Result<()> {
+    let archive = archive.to_string_lossy();
+    let dir = dir.to_string_lossy();
+    match format {
+        BundleFormat::Zip if check_binary_installed(&"unzip".to_string()) => run_command(
+            "unzip",
+            &["-q", "-o", archive.as_ref(), "-d", dir.as_ref()],
+            vec![],
+        ),
+        // bsdtar, the `tar` of macOS and Windows, also reads zip archives
+        _ if check_binary_installed(&"tar".to_string()) => run_command(
+            "tar",
+            &["-xf", archive.as_ref(), "-C", dir.as_ref()],
+            vec![],
+        ),
+        _ => Err(anyhow!(
+            "`unzip` or `tar` is required to extract the bundle"
+        )),
+    }
+    .map(|_| ())
+}
+
+/// Creates a new directory to extract the bundle `stem` to, with a random suffix, so that
+/// its name can't be guessed and taken beforehand.
+fn create_extraction_dir(stem: &str) -> Result<PathBuf> {
+    for _ in 0..MAX_DIR_ATTEMPTS {
+        let suffix = RandomState::new().build_hasher().finish();
+        let dir = std::env::temp_dir().join(format!("sol-azy-bundle-{}-{:016x}", stem, suffix));
+        match fs::create_dir(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Creating the bundle directory {}", dir.display()))
+            }
+        }
+    }
+    Err(anyhow!(
+        "Failed to create a bundle directory in {}",
+        std::env::temp_dir().display()
+    ))
+}
+
+/// Returns `true` if `path` is a symbolic link.
+fn is_symlink(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_symlink())
+}
+
+/// Name of a program as written in the file names (`my-program` and `my_program` are the same).
+fn normalize_name(name: &str) -> String {
+    name.replace('-', "_").to_lowercase()
+}
+
+impl Bundle {
+    /// Extracts the bundle `archive` to a temporary directory and finds its files.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archive is not a zip archive or a tarball, if `unzip` or `tar`
+    /// is missing, or if the extraction fails.
+    pub fn extract(archive: &Path) -> Result<Self> {
+        let format = BundleFormat::detect(archive)?;
+        let stem = archive
+            .file_stem()
+            .map_or("bundle".into(), |stem| stem.to_string_lossy());
+        let dir = create_extraction_dir(&stem)?;
+        if let Err(e) = extract_to(archive, format, &dir) {
+            let _ = fs::remove_dir_all(&dir);
+            return Err(e.context(format!("Extracting bundle {}", archive.display())));
+        }
+
+        let mut bundle = Self::open(dir);
+        bundle.temporary = true;
+        debug!(
+            "Bundle {} extracted to {}: {} program(s), {} IDL(s), {} Anchor workspace(s)",
+            archive.display(),
+            bundle.dir.display(),
+            bundle.programs.len(),
+            bundle.idls.len(),
+            bundle.anchor_roots.len()
+        );
+        Ok(bundle)
+    }
+
+    /// Finds the files of a bundle already extracted to `dir`, which is kept when dropped.
+    pub fn open(dir: PathBuf) -> Self {
+        let files = walk(&dir);
+        let programs = files
+            .iter()
+            .filter(|path| path.extension().is_some_and(|ext| ext == "so") && is_elf(path))
+            .cloned()
+            .collect();
+        let idls = files
+            .iter()
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| read_idl(path))
+            .collect();
+        let mut anchor_roots: Vec<PathBuf> = files
+            .iter()
+            .filter(|path| path.file_name().is_some_and(|name| name == "Anchor.toml"))
+            .filter_map(|path| path.parent().map(Path::to_path_buf))
+            .collect();
+        anchor_roots.sort_by_key(|root| root.components().count());
+        Self {
+            dir,
+            programs,
+            idls,
+            anchor_roots,
+            temporary: false,
+        }
+    }
+
+    /// Returns `true` if `path` is a file of the bundle.
+    pub fn contains(&self, path: &Path) -> bool {
+        path.starts_with(&self.dir)
+    }
+
+    /// Returns the program to analyze: the one named `name` (its file name, with or without
+    /// `.so`) if given, the only program of the bundle, or the only one named after an IDL.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no program matches, or if several do, listing them.
+    pub fn program(&self, name: Option<&str>) -> Result<&Path> {
+        let names = || {
+            self.programs
+                .iter()
+                .map(|path| {
+                    path.strip_prefix(&self.dir)
+                        .unwrap_or(path)
+                        .display()
+                        .to_string()
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let stem = |path: &Path| {
+            path.file_stem()
+                .map(|stem| normalize_name(&stem.to_string_lossy()))
+                .unwrap_or_default()
+        };
+        let candidates: Vec<&PathBuf> = match name {
+            Some(name) => {
+                let name = normalize_name(name.trim_end_matches(".so"));
+                self.programs
+                    .iter()
+                    .filter(|path| stem(path) == name)
+                    .collect()
+            }
+            None if self.programs.len() > 1 => self
+                .programs
+                .iter()
+                .filter(|path| {
+                    self.idls.iter().any(|idl| {
+                        idl.name
+                            .as_deref()
+                            .is_some_and(|name| normalize_name(name) == stem(path))
+                    })
+                })
+                .collect(),
+            None => self.programs.iter().collect(),
+        };
+        match candidates.as_slice() {
+            [program] => Ok(program.as_path()),
+            [] if self.programs.is_empty() => {
+                Err(anyhow!("No compiled program (.so) in the bundle"))
+            }
+            [] => Err(anyhow!(
+                "No program{} in the bundle, which holds {}",
+                name.map_or(String::new(), |name| format!(" named '{}'", name)),
+                names()
+            )),
+            _ => Err(anyhow!(
+                "Several programs in the bundle ({}), select one with --bytecodes-file <name>",
+                names()
+            )),
+        }
+    }
+
+    /// Returns the IDL of `program`: the one named after it, or the only IDL of the bundle.
+    pub fn idl_of(&self, program: &Path) -> Option<&Path> {
+        let stem = program
+            .file_stem()
+            .map(|stem| normalize_name(&stem.to_string_lossy()))?;
+        self.idls
+            .iter()
+            .find(|idl| idl.name.as_deref().map(normalize_name).as_ref() == Some(&stem))
+            .or(match self.idls.as_slice() {
+                [idl] => Some(idl),
+                _ => None,
+            })
+            .map(|idl| idl.path.as_path())
+    }
+
+    /// Returns the Anchor workspace of the bundle, the shallowest one if there are several.
+    ///
+    /// The IDLs of the bundle are copied to its `target/idl/` when it has none, so that the
+    /// recap uses them instead of synthesizing them from the sources. They are not if
+    /// `target/` or `target/idl/` is a symbolic link, which could lead out of the bundle.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bundle has no `Anchor.toml`.
+    pub fn anchor_root(&self) -> Result<&Path> {
+        let root = self.anchor_roots.first().ok_or_else(|| {
+            anyhow!("No Anchor.toml in the bundle, the sources of the workspace are required")
+        })?;
+        if self.anchor_roots.len() > 1 {
+            warn!(
+                "{} Anchor workspaces in the bundle, using {}",
+                self.anchor_roots.len(),
+                root.strip_prefix(&self.dir).unwrap_or(root).display()
+            );
+        }
+        let target_dir = root.join("target");
+        let idl_dir = target_dir.join("idl");
+        if is_symlink(&target_dir) || is_symlink(&idl_dir) {
+            warn!(
+                "{} is a symbolic link, the IDLs of the bundle are not copied to it",
+                idl_dir.display()
+            );
+        } else if !idl_dir.exists() && !self.idls.is_empty() {
+            fs::create_dir_all(&idl_dir)?;
+            for idl in &self.idls {
+                let file_name = match &idl.name {
+                    Some(name) => format!("{}.json", normalize_name(name)),
+                    None => idl
+                        .path
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .into(),
+                };
+                // the name comes from the IDL, only its last component is kept
+                let file_name = Path::new(&file_name).file_name().unwrap_or_default();
+                fs::copy(&idl.path, idl_dir.join(file_name))
+                    .with_context(|| format!("Copying IDL {}", idl.path.display()))?;
+            }
+        }
+        Ok(root)
+    }
+}
+
+impl Drop for Bundle {
+    fn drop(&mut self) {
+        if self.temporary && self.dir.exists() {
+            if let Err(e) = fs::remove_dir_all(&self.dir) {
+                warn!(
+                    "Failed to remove the extracted bundle {}: {}",
+                    self.dir.display(),
+                    e
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundle() {
+        let source =
+            Path::new("test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so");
+        let staging =
+            std::env::temp_dir().join(format!("sol-azy-bundle-test-{}", std::process::id()));
+        let package = staging.join("package");
+        fs::create_dir_all(package.join("target/deploy")).unwrap();
+        fs::create_dir_all(package.join("idl")).unwrap();
+        fs::copy(source, package.join("target/deploy/addition_checker.so")).unwrap();
+        fs::write(package.join("target/deploy/notes.so"), "not an ELF").unwrap();
+        fs::write(
+            package.join("idl/addition_checker.json"),
+            r#"{"metadata": {"name": "addition_checker"}, "instructions": []}"#,
+        )
+        .unwrap();
+        fs::write(package.join("package.json"), r#"{"name": "checker"}"#).unwrap();
+        fs::write(package.join("Anchor.toml"), "[programs.localnet]\n").unwrap();
+
+        // an npm tarball, holding a `package/` directory
+        let archive = staging.join("checker-1.0.0.tgz");
+        run_command(
+            "tar",
+            &[
+                "-czf",
+                &archive.to_string_lossy(),
+                "-C",
+                &staging.to_string_lossy(),
+                "package",
+            ],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(BundleFormat::detect(&archive).unwrap(), BundleFormat::Tar);
+        assert!(BundleFormat::detect(source).is_err());
+
+        let bundle = Bundle::extract(&archive).unwrap();
+        let dir = bundle.dir.clone();
+        let program = bundle.program(None).unwrap().to_path_buf();
+        assert!(program.ends_with("package/target/deploy/addition_checker.so"));
+        assert_eq!(
+            bundle.program(Some("addition-checker.so")).unwrap(),
+            program
+        );
+        assert!(bundle.program(Some("other")).is_err());
+        assert!(bundle
+            .idl_of(&program)
+            .unwrap()
+            .ends_with("idl/addition_checker.json"));
+
+        let root = bundle.anchor_root().unwrap();
+        assert!(root.ends_with("package"));
+        assert!(root.join("target/idl/addition_checker.json").is_file());
+
+        drop(bundle);
+        assert!(!dir.exists());
+        fs::remove_dir_all(staging).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_bundle_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let staging = std::env::temp_dir().join(format!(
+            "sol-azy-bundle-symlinks-test-{}",
+            std::process::id()
+        ));
+        let dir = staging.join("bundle");
+        let outside = staging.join("outside");
+        fs::create_dir_all(dir.join("ws")).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(dir.join("ws/Anchor.toml"), "[programs.localnet]\n").unwrap();
+        fs::write(
+            dir.join("checker.json"),
+            r#"{"name": "../../checker", "instructions": []}"#,
+        )
+        .unwrap();
+        symlink("..", dir.join("ws/parent")).unwrap();
+        symlink(&outside, dir.join("ws/target")).unwrap();
+
+        let bundle = Bundle::open(dir.clone());
+        assert_eq!(bundle.idls.len(), 1);
+        assert_eq!(bundle.anchor_root().unwrap(), dir.join("ws"));
+        assert_eq!(fs::read_dir(&outside).unwrap().count(), 0);
+
+        // the IDL named after a path is copied into `target/idl/`
+        fs::remove_file(dir.join("ws/target")).unwrap();
+        bundle.anchor_root().unwrap();
+        assert!(dir.join("ws/target/idl/checker.json").is_file());
+        fs::remove_dir_all(staging).unwrap();
+    }
+}
@@ -0,0 +1,207 @@
+//! Machine-readable events (`--events-json`), for the tools driving sol-azy (GUIs, CI).
+//!
+//! When enabled, each command emits line-delimited JSON events (`started`, `progress`,
+//! `artifact_written`, `finding`, `finished`) to stdout or to a socket, and the spinners and
+//! progress bars are replaced by `progress` events. When the events go to stdout, the logs are
+//! written to stderr and the human-readable reports are not printed, so that stdout only
+//! contains events.
+
+use anyhow::{anyhow, Context, Result};
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::net::TcpStream;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Where the events are written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventTarget {
+    Stdout,
+    /// `tcp://<host>:<port>`
+    Tcp(String),
+    /// `unix://<path>`
+    #[cfg(unix)]
+    Unix(String),
+}
+
+impl EventTarget {
+    /// Parses `stdout` (or `-`), `tcp://<host>:<port>` or `unix://<path>`.
+    pub fn parse(target: &str) -> Result<Self> {
+        let target = target.trim();
+        if target.is_empty() || target == "stdout" || target == "-" {
+            return Ok(EventTarget::Stdout);
+        }
+        if let Some(address) = target.strip_prefix("tcp://") {
+            return Ok(EventTarget::Tcp(address.to_string()));
+        }
+        #[cfg(unix)]
+        if let Some(path) = target.strip_prefix("unix://") {
+            return Ok(EventTarget::Unix(path.to_string()));
+        }
+        Err(anyhow!(
+            "Unknown events target '{}' (expected stdout, tcp://<host>:<port> or unix://<path>)",
+            target
+        ))
+    }
+}
+
+/// An event of a command.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    #[cfg(feature = "cli")]
+    Started {
+        command: String,
+        arguments: Vec<String>,
+    },
+    Progress {
+        message: String,
+        /// Only set when the amount of work is known.
+        percent: Option<u8>,
+    },
+    #[cfg(feature = "cli")]
+    ArtifactWritten {
+        path: String,
+        sha256: Option<String>,
+    },
+    #[cfg(feature = "cli")]
+    /// The SAST scan of a project, before its findings, whose locations are relative to `root`.
+    Scanned { root: String, files: usize },
+    Finding {
+        rule: String,
+        severity: String,
+        certainty: String,
+        /// Levels by axis, when the findings are rated in a configured taxonomy.
+        ratings: Option<BTreeMap<String, String>>,
+        location: String,
+        description: Option<String>,
+    },
+    #[cfg(feature = "cli")]
+    Finished {
+        command: String,
+        success: bool,
+        error: Option<String>,
+        duration_ms: u64,
+    },
+}
+
+#[derive(Serialize)]
+struct EventLine<'a> {
+    /// Milliseconds since the UNIX epoch.
+    timestamp: u64,
+    #[serde(flatten)]
+    event: &'a Event,
+}
+
+struct EventSink {
+    writer: Mutex<Box<dyn Write + Send>>,
+    to_stdout: bool,
+}
+
+static SINK: OnceCell<EventSink> = OnceCell::new();
+
+/// Enables the events, written to `target`.
+///
+/// # Errors
+///
+/// Returns an error if the socket can't be connected, or if the events are already enabled.
+pub fn init(target: &EventTarget) -> Result<()> {
+    let writer: Box<dyn Write + Send> = match target {
+        EventTarget::Stdout => Box::new(std::io::stdout()),
+        EventTarget::Tcp(address) => Box::new(
+            TcpStream::connect(address)
+                .with_context(|| format!("Connecting to the events socket {}", address))?,
+        ),
+        #[cfg(unix)]
+        EventTarget::Unix(path) => Box::new(
+            std::os::unix::net::UnixStream::connect(path)
+                .with_context(|| format!("Connecting to the events socket {}", path))?,
+        ),
+    };
+    SINK.set(EventSink {
+        writer: Mutex::new(writer),
+        to_stdout: *target == EventTarget::Stdout,
+    })
+    .map_err(|_| anyhow!("Events already enabled"))
+}
+
+/// Returns `true` if `--events-json` is active.
+pub fn enabled() -> bool {
+    SINK.get().is_some()
+}
+
+/// Returns `true` if the human-readable output (reports, tables) can be printed on stdout, i.e.
+/// unless the events are written there.
+pub fn human_output() -> bool {
+    !SINK.get().is_some_and(|sink| sink.to_stdout)
+}
+
+/// Writes an event, if the events are enabled.
+pub fn emit(event: Event) {
+    let Some(sink) = SINK.get() else {
+        return;
+    };
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default();
+    let Ok(line) = serde_json::to_string(&EventLine {
+        timestamp,
+        event: &event,
+    }) else {
+        return;
+    };
+    if let Ok(mut writer) = sink.writer.lock() {
+        // a GUI that went away must not make the command fail
+        let _ = writeln!(writer, "{}", line).and_then(|_| writer.flush());
+    }
+}
+
+/// Emits an `artifact_written` event for a file, with its SHA-256.
+#[cfg(feature = "cli")]
+pub fn artifact_written(path: &std::path::Path) {
+    if !enabled() {
+        return;
+    }
+    emit(Event::ArtifactWritten {
+        path: path.to_string_lossy().to_string(),
+        sha256: crate::helpers::run_layout::sha256_file(path).ok(),
+    });
+}
+
+/// Emits a `progress` event without percentage.
+pub fn progress(message: impl Into<String>) {
+    emit(Event::Progress {
+        message: message.into(),
+        percent: None,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events() {
+        assert_eq!(EventTarget::parse("stdout").unwrap(), EventTarget::Stdout);
+        assert_eq!(
+            EventTarget::parse("tcp://127.0.0.1:4000").unwrap(),
+            EventTarget::Tcp("127.0.0.1:4000".to_string())
+        );
+        assert!(EventTarget::parse("http://localhost").is_err());
+
+        let line = serde_json::to_value(EventLine {
+            timestamp: 1,
+            event: &Event::Progress {
+                message: "Disassembling".to_string(),
+                percent: Some(42),
+            },
+        })
+        .unwrap();
+        assert_eq!(line["event"], "progress");
+        assert_eq!(line["percent"], 42);
+        assert_eq!(line["timestamp"], 1);
+    }
+}
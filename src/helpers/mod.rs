@@ -3,20 +3,115 @@
 //! This module provides utility functionality for:
 //! - Checking the presence of required binaries (`check_binary_installed`)
 //! - Creating directories (`create_dir_if_not_exists`)
+//! - Extracting the artifact bundles given with `--bundle` (`bundle`)
 //! - Detecting project type (Anchor vs SBF)
+//! - Locating the program crates of an Anchor workspace (`anchor_toml`)
+//! - Installing the Anchor CLI versions used by `build --version-switch` (`toolchains`)
+//! - Emitting the machine-readable events of `--events-json` (`events`)
+//! - Laying out the output directories of the runs and their `run.json` manifests (`run_layout`)
+//! - Timing the stages of the pipeline for `--timing-report` (`timing`)
 //! - Running shell commands with optional environment variables (`run_command`)
 //!
 //! It also defines helper types like `ProjectType` and `BeforeCheck` used in build and analysis workflows.
 
+#[cfg(feature = "cli")]
+pub mod anchor_toml;
+#[cfg(feature = "cli")]
+pub mod bundle;
+pub mod events;
+#[cfg(feature = "cli")]
+pub mod run_layout;
 pub mod static_dir;
 pub mod spinner;
+pub mod timing;
+#[cfg(feature = "cli")]
+pub mod toolchains;
 
+#[cfg(feature = "cli")]
 use log::{debug, error};
+#[cfg(feature = "cli")]
 use std::fmt::Formatter;
+#[cfg(feature = "cli")]
 use std::process::Stdio;
+#[cfg(feature = "cli")]
+use std::collections::VecDeque;
+#[cfg(feature = "cli")]
+use std::io::{BufRead, BufReader, Write};
+#[cfg(feature = "cli")]
+use std::path::PathBuf;
+#[cfg(feature = "cli")]
+use std::sync::Mutex;
+#[cfg(feature = "cli")]
 use std::{fmt, fs, path::Path, process::Command};
+#[cfg(feature = "cli")]
 use toml::Value;
 
+/// Returns the executable file extensions to try when resolving a binary.
+///
+/// On Windows, these are the extensions of `%PATHEXT%` (`.exe`, `.cmd`, ...), since tools like
+/// `anchor` may be installed as `.cmd` shims. Elsewhere, only the bare name is tried.
+#[cfg(feature = "cli")]
+fn executable_extensions() -> Vec<String> {
+    if cfg!(windows) {
+        let pathext = std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+        std::iter::once(String::new())
+            .chain(
+                pathext
+                    .split(';')
+                    .filter(|ext| !ext.is_empty())
+                    .map(|ext| ext.to_lowercase()),
+            )
+            .collect()
+    } else {
+        vec![String::new()]
+    }
+}
+
+/// Returns `true` if `path` is a file that can be executed.
+#[cfg(feature = "cli")]
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        path.metadata()
+            .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+            .unwrap_or(false)
+    }
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
+/// Resolves a binary by walking the directories of a `PATH`-like variable.
+///
+/// # Arguments
+///
+/// * `bin_name` - Name of the binary, without extension.
+/// * `paths` - Value of the `PATH` variable.
+///
+/// # Returns
+///
+/// The path of the first matching executable, if any.
+#[cfg(feature = "cli")]
+pub fn find_binary_in(bin_name: &str, paths: &std::ffi::OsStr) -> Option<PathBuf> {
+    let extensions = executable_extensions();
+    std::env::split_paths(paths)
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .flat_map(|dir| {
+            extensions
+                .iter()
+                .map(move |ext| dir.join(format!("{}{}", bin_name, ext)))
+        })
+        .find(|candidate| is_executable(candidate))
+}
+
+/// Resolves a binary from the system's `PATH`, like `which` (or `where` on Windows).
+#[cfg(feature = "cli")]
+pub fn find_binary(bin_name: &str) -> Option<PathBuf> {
+    find_binary_in(bin_name, &std::env::var_os("PATH")?)
+}
+
 /// Checks if a binary is available in the system's `$PATH`.
 ///
 /// # Arguments
@@ -26,11 +121,9 @@ use toml::Value;
 /// # Returns
 ///
 /// `true` if the binary is found, otherwise `false`.
+#[cfg(feature = "cli")]
 pub fn check_binary_installed(bin_name: &String) -> bool {
-    Command::new("which")
-        .arg(bin_name)
-        .output()
-        .map_or(false, |output| output.status.success())
+    find_binary(bin_name).is_some()
 }
 
 /// Ensures a directory exists, creating it if necessary.
@@ -42,6 +135,7 @@ pub fn check_binary_installed(bin_name: &String) -> bool {
 /// # Returns
 ///
 /// `true` if the directory exists or was created successfully, otherwise `false`.
+#[cfg(feature = "cli")]
 pub fn create_dir_if_not_exists(dir: &String) -> bool {
     let path = Path::new(dir);
     if path.exists() {
@@ -54,17 +148,32 @@ pub fn create_dir_if_not_exists(dir: &String) -> bool {
 ///
 /// - `Anchor`: Project contains an `Anchor.toml` file.
 /// - `Sbf`: Project is identified as a native Solana SBF crate.
+/// - `Pinocchio`: Native crate written with the `pinocchio` framework.
+/// - `Steel`: Native crate written with the `steel` framework.
 /// - `Unknown`: Type could not be determined.
+#[cfg(feature = "cli")]
 #[derive(PartialEq, Debug, Clone, Copy, Eq)]
 pub enum ProjectType {
     Anchor,
     Sbf,
+    Pinocchio,
+    Steel,
     Unknown,
 }
 
+/// Dependencies of the `Cargo.toml` telling the type of a native project, by priority: a Steel
+/// program also depends on `solana-program`, and a Pinocchio one may.
+#[cfg(feature = "cli")]
+const NATIVE_DEPENDENCIES: &[(&str, ProjectType)] = &[
+    ("steel", ProjectType::Steel),
+    ("pinocchio", ProjectType::Pinocchio),
+    ("solana-program", ProjectType::Sbf),
+];
+
 /// Attempts to determine the type of Solana project based on its configuration files.
 ///
-/// Checks for presence of `Anchor.toml` or a `Cargo.toml` containing a `solana-program` dependency.
+/// Checks for presence of `Anchor.toml`, then for a `Cargo.toml` depending on `steel`,
+/// `pinocchio` or `solana-program`.
 ///
 /// # Arguments
 ///
@@ -72,17 +181,21 @@ pub enum ProjectType {
 ///
 /// # Returns
 ///
-/// A `ProjectType` variant (`Anchor`, `Sbf`, or `Unknown`).
+/// A `ProjectType` variant (`Anchor`, `Steel`, `Pinocchio`, `Sbf`, or `Unknown`).
+#[cfg(feature = "cli")]
 impl fmt::Display for ProjectType {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             ProjectType::Anchor => write!(f, "Anchor"),
             ProjectType::Sbf => write!(f, "Solana BPF"),
+            ProjectType::Pinocchio => write!(f, "Pinocchio"),
+            ProjectType::Steel => write!(f, "Steel"),
             ProjectType::Unknown => write!(f, "Unknown"),
         }
     }
 }
 
+#[cfg(feature = "cli")]
 pub fn get_project_type(project_dir: &String) -> ProjectType {
     let anchor_toml = Path::new(project_dir).join("Anchor.toml");
     if anchor_toml.exists() {
@@ -90,16 +203,22 @@ pub fn get_project_type(project_dir: &String) -> ProjectType {
     }
 
     let cargo_toml = Path::new(project_dir).join("Cargo.toml");
-    fs::read_to_string(cargo_toml)
+    let Some(dependencies) = fs::read_to_string(cargo_toml)
         .ok()
         .and_then(|content| content.parse::<Value>().ok())
         .and_then(|parsed| parsed.get("dependencies").cloned())
-        .and_then(|dependencies| dependencies.get("solana-program").cloned())
-        .map_or(ProjectType::Unknown, |_| ProjectType::Sbf)
+    else {
+        return ProjectType::Unknown;
+    };
+    NATIVE_DEPENDENCIES
+        .iter()
+        .find(|(dependency, _)| dependencies.get(dependency).is_some())
+        .map_or(ProjectType::Unknown, |(_, project_type)| *project_type)
 }
 
 /// Represents a single pre-check step before a build or analysis,
 /// consisting of an error message and a success result.
+#[cfg(feature = "cli")]
 pub struct BeforeCheck {
     pub error_msg: String,
     pub result: bool,
@@ -118,27 +237,169 @@ pub struct BeforeCheck {
 /// # Returns
 ///
 /// A `Result<String>` containing the command's stdout if successful, or an error.
+#[cfg(feature = "cli")]
 pub fn run_command(
     command_name: &str,
     args: &[&str],
     env_vars: Vec<(&str, &str)>,
 ) -> Result<String, anyhow::Error> {
-    let mut bind = Command::new(command_name);
+    run_command_in(command_name, args, env_vars, None)
+}
+
+/// Same as [`run_command`], but runs the command in `current_dir` (if given) instead of the
+/// working directory of the process, which is left untouched.
+///
+/// The command is resolved with [`find_binary`] first, so that `.cmd`/`.bat` shims are found
+/// on Windows.
+#[cfg(feature = "cli")]
+pub fn run_command_in(
+    command_name: &str,
+    args: &[&str],
+    env_vars: Vec<(&str, &str)>,
+    current_dir: Option<&Path>,
+) -> Result<String, anyhow::Error> {
+    run_command_streamed(
+        command_name,
+        args,
+        env_vars,
+        current_dir,
+        &OutputSink::default(),
+    )
+}
+
+/// Which stream of a command an output line comes from.
+#[cfg(feature = "cli")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// Callback receiving the output lines of a command.
+#[cfg(feature = "cli")]
+pub type LineHandler<'a> = dyn Fn(OutputStream, &str) + Sync + 'a;
+
+/// Where the output lines of a command run by [`run_command_streamed`] are sent, as they
+/// are produced.
+#[cfg(feature = "cli")]
+#[derive(Default)]
+pub struct OutputSink<'a> {
+    /// File receiving every line of both streams (created if needed, and appended to).
+    pub log_file: Option<&'a Path>,
+    /// Called for every line, e.g. to update a spinner or echo the output.
+    pub on_line: Option<&'a LineHandler<'a>>,
+}
+
+/// Number of stderr lines kept to report a failure.
+#[cfg(feature = "cli")]
+const STDERR_TAIL_LINES: usize = 200;
+
+/// Reads `reader` line by line, handing each line to `handle`.
+#[cfg(feature = "cli")]
+fn read_lines<R: std::io::Read>(reader: R, mut handle: impl FnMut(&str)) {
+    let mut reader = BufReader::new(reader);
+    let mut buffer = Vec::new();
+    loop {
+        buffer.clear();
+        match reader.read_until(b'\n', &mut buffer) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {
+                let line = String::from_utf8_lossy(&buffer);
+                handle(line.trim_end_matches(['\n', '\r']));
+            }
+        }
+    }
+}
+
+/// Same as [`run_command_in`], but streams the output of the command line by line to `sink`
+/// while it runs, instead of buffering it until the command exits.
+///
+/// Only the stdout (returned on success) and the last lines of stderr (logged on failure) are
+/// kept in memory, so very verbose commands like `anchor build` can be followed live.
+#[cfg(feature = "cli")]
+pub fn run_command_streamed(
+    command_name: &str,
+    args: &[&str],
+    env_vars: Vec<(&str, &str)>,
+    current_dir: Option<&Path>,
+    sink: &OutputSink,
+) -> Result<String, anyhow::Error> {
+    // a path (e.g. a sol-azy managed toolchain) is run as is
+    let program = if Path::new(command_name).components().count() > 1 {
+        PathBuf::from(command_name)
+    } else {
+        find_binary(command_name).unwrap_or_else(|| PathBuf::from(command_name))
+    };
+    let mut bind = Command::new(program);
     let command = bind
         .args(args)
+        .stdin(Stdio::null())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
+    if let Some(dir) = current_dir {
+        command.current_dir(dir);
+    }
     for (key, value) in env_vars {
         command.env(key, value);
     }
 
-    let output = command
-        .output()
+    let log_file = match sink.log_file {
+        Some(path) => Some(Mutex::new(
+            fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| anyhow::anyhow!("Failed to open log file {}: {}", path.display(), e))?,
+        )),
+        None => None,
+    };
+    let mut child = command
+        .spawn()
         .map_err(|e| anyhow::anyhow!("Failed to run `{}`: {}", command_name, e))?;
+    let child_stdout = child.stdout.take();
+    let child_stderr = child.stderr.take();
+
+    let handle_line = |stream: OutputStream, line: &str| {
+        if let Some(file) = &log_file {
+            let mut file = file.lock().unwrap_or_else(|e| e.into_inner());
+            let _ = writeln!(file, "{}", line);
+        }
+        if let Some(on_line) = sink.on_line {
+            on_line(stream, line);
+        }
+    };
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+    let mut stdout = String::new();
+    let mut stderr_tail: VecDeque<String> = VecDeque::new();
+    std::thread::scope(|scope| {
+        if let Some(child_stdout) = child_stdout {
+            let stdout = &mut stdout;
+            scope.spawn(|| {
+                read_lines(child_stdout, |line| {
+                    handle_line(OutputStream::Stdout, line);
+                    stdout.push_str(line);
+                    stdout.push('\n');
+                })
+            });
+        }
+        if let Some(child_stderr) = child_stderr {
+            read_lines(child_stderr, |line| {
+                handle_line(OutputStream::Stderr, line);
+                if stderr_tail.len() == STDERR_TAIL_LINES {
+                    stderr_tail.pop_front();
+                }
+                stderr_tail.push_back(line.to_string());
+            });
+        }
+    });
+
+    let status = child
+        .wait()
+        .map_err(|e| anyhow::anyhow!("Failed to wait for `{}`: {}", command_name, e))?;
+
+    if !status.success() {
+        let stderr = Vec::from(stderr_tail).join("\n");
         error!(
             "Error while running `{}`\nStderr:\n{}",
             command_name, stderr
@@ -149,45 +410,9 @@ pub fn run_command(
         ));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
     debug!("Command output:\n{}", stdout);
 
-    Ok(stdout.into())
-}
-
-/// Switches the Anchor CLI version by installing a specific version from the official repository.
-///
-/// This function executes `cargo install --git https://github.com/coral-xyz/anchor --tag vXXX anchor-cli --force`
-/// where XXX is the provided version parameter.
-///
-/// # Arguments
-///
-/// * `version` - The version string to install (e.g., "0.31.0"). The "v" prefix will be added automatically.
-///
-/// # Returns
-///
-/// Returns `Ok(String)` with the command output on success, or an `anyhow::Error` on failure.
-///
-/// # Example
-///
-/// ```rust
-/// // Install Anchor CLI version 0.31.0
-/// switch_anchor_version("0.31.0")?;
-/// ```
-pub fn switch_anchor_version(version: &str) -> Result<String, anyhow::Error> {
-    let command_name = "cargo";
-    let args = &[
-        "install",
-        "--git",
-        "https://github.com/coral-xyz/anchor",
-        "--tag",
-        &format!("v{}", version),
-        "anchor-cli",
-        "--force",
-    ];
-    let env_vars = vec![];
-
-    run_command(command_name, args, env_vars)
+    Ok(stdout)
 }
 
 /// Retrieves the Anchor version from an Anchor.toml file in the specified directory.
@@ -216,6 +441,7 @@ pub fn switch_anchor_version(version: &str) -> Result<String, anyhow::Error> {
 ///     None => println!("No anchor version specified in Anchor.toml"),
 /// }
 /// ```
+#[cfg(feature = "cli")]
 pub fn get_anchor_version(project_path: &Path) -> Result<Option<String>, anyhow::Error> {
     let anchor_toml_path = project_path.join("Anchor.toml");
 
@@ -236,4 +462,93 @@ pub fn get_anchor_version(project_path: &Path) -> Result<Option<String>, anyhow:
     }
 
     Ok(None)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_binary_in() {
+        let root = std::env::temp_dir().join(format!("sol-azy-find-binary-{}", std::process::id()));
+        let (empty, bin) = (root.join("empty"), root.join("bin"));
+        std::fs::create_dir_all(&empty).unwrap();
+        std::fs::create_dir_all(&bin).unwrap();
+        let tool = bin.join(if cfg!(windows) { "tool.cmd" } else { "tool" });
+        std::fs::write(&tool, "").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&tool, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let paths = std::env::join_paths([&empty, &bin]).unwrap();
+        assert_eq!(find_binary_in("tool", &paths), Some(tool));
+        assert_eq!(find_binary_in("missing", &paths), None);
+        assert!(check_binary_installed(&"cargo".to_string()));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_get_project_type() {
+        let root =
+            std::env::temp_dir().join(format!("sol-azy-project-type-{}", std::process::id()));
+        let project_type = |dependencies: &str| {
+            std::fs::create_dir_all(&root).unwrap();
+            std::fs::write(
+                root.join("Cargo.toml"),
+                format!(
+                    "[package]\nname = \"p\"\n\n[dependencies]\n{}",
+                    dependencies
+                ),
+            )
+            .unwrap();
+            get_project_type(&root.to_string_lossy().to_string())
+        };
+        assert_eq!(project_type("solana-program = \"2\"\n"), ProjectType::Sbf);
+        assert_eq!(
+            project_type("pinocchio = \"0.8\"\n"),
+            ProjectType::Pinocchio
+        );
+        assert_eq!(
+            project_type("solana-program = \"2\"\nsteel = { version = \"4\" }\n"),
+            ProjectType::Steel
+        );
+        assert_eq!(project_type("serde = \"1\"\n"), ProjectType::Unknown);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_command_streamed() {
+        let log = std::env::temp_dir().join(format!("sol-azy-streamed-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&log);
+        let lines = Mutex::new(Vec::new());
+        let on_line = |stream: OutputStream, line: &str| {
+            lines.lock().unwrap().push((stream, line.to_string()));
+        };
+        let sink = OutputSink {
+            log_file: Some(&log),
+            on_line: Some(&on_line),
+        };
+
+        let stdout = run_command_streamed(
+            "sh",
+            &["-c", "echo out; echo err >&2; echo $SOLAZY_TEST"],
+            vec![("SOLAZY_TEST", "env")],
+            None,
+            &sink,
+        )
+        .unwrap();
+        assert_eq!(stdout, "out\nenv\n");
+        let lines = lines.lock().unwrap().clone();
+        assert!(lines.contains(&(OutputStream::Stderr, "err".to_string())));
+        assert_eq!(lines.len(), 3);
+        assert_eq!(std::fs::read_to_string(&log).unwrap().lines().count(), 3);
+
+        assert!(run_command_streamed("sh", &["-c", "exit 3"], vec![], None, &sink).is_err());
+
+        std::fs::remove_file(&log).unwrap();
+    }
+}
@@ -0,0 +1,350 @@
+//! Layout of the files written by the commands, so that successive runs never overwrite each
+//! other.
+//!
+//! Each run writes into `<out-dir>/<command>/<label or timestamp>/`, next to a `run.json`
+//! manifest recording the command line, the inputs and options, the sol-azy version and the
+//! hashes of the inputs and of the written files. `<out-dir>/<command>/latest` points to the
+//! last run (a symlink, or a file holding the name of the run directory where symlinks are not
+//! available).
+
+use crate::helpers::events;
+use anyhow::{anyhow, Context, Result};
+use log::{debug, info, warn};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Name of the manifest written in each run directory.
+pub const RUN_MANIFEST_FILE: &str = "run.json";
+
+/// Name of the link to the last run of a command.
+pub const LATEST_LINK: &str = "latest";
+
+/// A file read by a run.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunInput {
+    pub path: String,
+    /// `None` for directories and missing files.
+    pub sha256: Option<String>,
+}
+
+/// A file written by a run, relative to the run directory.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunOutput {
+    pub path: String,
+    pub sha256: String,
+}
+
+/// Content of `run.json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunManifest {
+    pub command: String,
+    pub label: Option<String>,
+    pub tool_version: String,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub success: bool,
+    pub error: Option<String>,
+    /// The full command line.
+    pub arguments: Vec<String>,
+    pub options: BTreeMap<String, serde_json::Value>,
+    pub inputs: Vec<RunInput>,
+    pub outputs: Vec<RunOutput>,
+}
+
+/// A run directory being written.
+pub struct RunDir {
+    pub dir: PathBuf,
+    manifest: RunManifest,
+}
+
+/// Formats a UNIX timestamp as `YYYY-MM-DDTHH:MM:SSZ` (UTC).
+fn format_utc(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let time = secs % 86_400;
+    // days to civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
+
+pub(crate) fn now_utc() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    format_utc(secs)
+}
+
+/// Checks that a label can be used as a directory name.
+fn validate_label(label: &str) -> Result<&str> {
+    let label = label.trim();
+    let valid = !label.is_empty()
+        && label != LATEST_LINK
+        && !label.starts_with('.')
+        && label
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(anyhow!(
+            "Invalid label '{}' (letters, digits, '-', '_' and '.' only, and not '{}')",
+            label,
+            LATEST_LINK
+        ));
+    }
+    Ok(label)
+}
+
+/// Returns the SHA-256 of a file.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let bytes = std::fs::read(path).with_context(|| format!("Reading {}", path.display()))?;
+    Ok(hex::encode(Sha256::digest(&bytes)))
+}
+
+/// Lists the files under `dir`, relative to `root`, sorted. Symlinks (the `latest` links of
+/// the command directories) are skipped.
+pub(crate) fn list_files(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry
+            .file_type()
+            .is_ok_and(|file_type| file_type.is_symlink())
+        {
+            continue;
+        }
+        let path = entry.path();
+        if path.is_dir() {
+            list_files(root, &path, files);
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            files.push(relative.to_path_buf());
+        }
+    }
+    files.sort();
+}
+
+impl RunDir {
+    /// Creates the directory of a new run of `command` in `out_dir`.
+    ///
+    /// # Arguments
+    ///
+    /// * `out_dir` - The output directory given to the command.
+    /// * `command` - The command name (`reverse`, `fetcher`, ...).
+    /// * `label` - Optional name of the run directory; the start time is used otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the label is invalid or already used, or if the directory can't be
+    /// created.
+    pub fn create(out_dir: &Path, command: &str, label: Option<&str>) -> Result<Self> {
+        let started_at = now_utc();
+        let command_dir = out_dir.join(command);
+        std::fs::create_dir_all(&command_dir)
+            .with_context(|| format!("Creating {}", command_dir.display()))?;
+        // the run directory itself is created with `create_dir`, which fails if it exists, so
+        // that concurrent runs never share it
+        let create = |dir: &Path| std::fs::create_dir(dir).map(|_| dir.to_path_buf());
+        let dir = match label {
+            Some(label) => {
+                let dir = command_dir.join(validate_label(label)?);
+                match create(&dir) {
+                    Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                        return Err(anyhow!(
+                            "Run '{}' already exists in {}, use another --label",
+                            label,
+                            command_dir.display()
+                        ))
+                    }
+                    result => result.with_context(|| format!("Creating {}", dir.display()))?,
+                }
+            }
+            None => {
+                // runs started within the same second get a suffix
+                let name = started_at.replace(':', "-");
+                let mut suffix = 0;
+                loop {
+                    let dir = match suffix {
+                        0 => command_dir.join(&name),
+                        _ => command_dir.join(format!("{}-{}", name, suffix)),
+                    };
+                    match create(&dir) {
+                        Err(e) if e.kind() == ErrorKind::AlreadyExists => suffix += 1,
+                        result => {
+                            break result.with_context(|| format!("Creating {}", dir.display()))?
+                        }
+                    }
+                }
+            }
+        };
+        debug!("Run directory: {}", dir.display());
+
+        Ok(Self {
+            dir,
+            manifest: RunManifest {
+                command: command.to_string(),
+                label: label.map(|label| label.trim().to_string()),
+                tool_version: env!("CARGO_PKG_VERSION").to_string(),
+                started_at,
+                finished_at: None,
+                success: false,
+                error: None,
+                arguments: std::env::args().collect(),
+                options: BTreeMap::new(),
+                inputs: vec![],
+                outputs: vec![],
+            },
+        })
+    }
+
+    /// Returns the run directory as a string, to be used as the output directory of the command.
+    pub fn dir_string(&self) -> String {
+        self.dir.to_string_lossy().to_string()
+    }
+
+    /// Records an input file (hashed) or directory.
+    pub fn add_input(&mut self, path: &str) {
+        let sha256 = Path::new(path)
+            .is_file()
+            .then(|| sha256_file(Path::new(path)).ok())
+            .flatten();
+        self.manifest.inputs.push(RunInput {
+            path: path.to_string(),
+            sha256,
+        });
+    }
+
+    /// Records an option of the run.
+    pub fn set_option(&mut self, name: &str, value: impl Serialize) {
+        let value = serde_json::to_value(value).unwrap_or(serde_json::Value::Null);
+        self.manifest.options.insert(name.to_string(), value);
+    }
+
+    /// Completes the run: hashes the written files, writes `run.json` and points `latest` to
+    /// this run. An `artifact_written` event is emitted for each file with `--events-json`.
+    ///
+    /// # Returns
+    ///
+    /// The `result` of the command, or the error that occurred while writing the manifest.
+    pub fn finish<T>(mut self, result: Result<T>) -> Result<T> {
+        self.manifest.finished_at = Some(now_utc());
+        self.manifest.success = result.is_ok();
+        self.manifest.error = result.as_ref().err().map(|e| e.to_string());
+
+        let mut files = vec![];
+        list_files(&self.dir, &self.dir, &mut files);
+        self.manifest.outputs = files
+            .iter()
+            .filter(|file| file.as_path() != Path::new(RUN_MANIFEST_FILE))
+            .filter_map(|file| {
+                Some(RunOutput {
+                    path: file.to_string_lossy().replace('\\', "/"),
+                    sha256: sha256_file(&self.dir.join(file)).ok()?,
+                })
+            })
+            .collect();
+
+        let manifest_path = self.dir.join(RUN_MANIFEST_FILE);
+        std::fs::write(
+            &manifest_path,
+            serde_json::to_string_pretty(&self.manifest)?,
+        )
+        .with_context(|| format!("Writing {}", manifest_path.display()))?;
+        for output in &self.manifest.outputs {
+            events::artifact_written(&self.dir.join(&output.path));
+        }
+        events::artifact_written(&manifest_path);
+        if let Err(e) = self.update_latest() {
+            warn!("Failed to update the '{}' link: {}", LATEST_LINK, e);
+        }
+        info!("Run written to {}", self.dir.display());
+        result
+    }
+
+    /// Points `<out-dir>/<command>/latest` to this run.
+    fn update_latest(&self) -> Result<()> {
+        let (Some(command_dir), Some(name)) = (self.dir.parent(), self.dir.file_name()) else {
+            return Ok(());
+        };
+        let latest = command_dir.join(LATEST_LINK);
+        if latest.symlink_metadata().is_ok() {
+            std::fs::remove_file(&latest)?;
+        }
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(name, &latest)?;
+        #[cfg(not(unix))]
+        std::fs::write(&latest, name.to_string_lossy().as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_dir() {
+        assert_eq!(format_utc(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_utc(1_709_251_199), "2024-02-29T23:59:59Z");
+        assert!(validate_label("audit-v1.2").is_ok());
+        assert!(validate_label("../x").is_err());
+        assert!(validate_label(LATEST_LINK).is_err());
+
+        let out_dir = std::env::temp_dir().join(format!("sol-azy-run-layout-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&out_dir);
+
+        let mut run = RunDir::create(&out_dir, "reverse", Some("first")).unwrap();
+        assert_eq!(run.dir, out_dir.join("reverse").join("first"));
+        run.set_option("mode", "both");
+        std::fs::write(run.dir.join("disassembly.out"), "exit").unwrap();
+        run.finish(Ok(())).unwrap();
+
+        let manifest: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(out_dir.join("reverse/first").join(RUN_MANIFEST_FILE)).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(manifest["success"], true);
+        assert_eq!(manifest["options"]["mode"], "both");
+        assert_eq!(manifest["outputs"][0]["path"], "disassembly.out");
+        assert!(RunDir::create(&out_dir, "reverse", Some("first")).is_err());
+
+        let second = RunDir::create(&out_dir, "reverse", None).unwrap();
+        // runs started at the same time get their own directories
+        let concurrent: Vec<PathBuf> = std::thread::scope(|scope| {
+            let runs: Vec<_> = (0..8)
+                .map(|_| scope.spawn(|| RunDir::create(&out_dir, "fetcher", None).unwrap().dir))
+                .collect();
+            runs.into_iter().map(|run| run.join().unwrap()).collect()
+        });
+        let distinct: std::collections::BTreeSet<_> = concurrent.iter().collect();
+        assert_eq!(distinct.len(), concurrent.len());
+        #[cfg(unix)]
+        let second_dir = second.dir.clone();
+        assert!(second.finish::<()>(Err(anyhow!("failed"))).is_err());
+        #[cfg(unix)]
+        assert_eq!(
+            std::fs::canonicalize(out_dir.join("reverse").join(LATEST_LINK)).unwrap(),
+            std::fs::canonicalize(second_dir).unwrap()
+        );
+
+        std::fs::remove_dir_all(&out_dir).unwrap();
+    }
+}
@@ -1,7 +1,17 @@
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
 use std::time::Duration;
 use indicatif::{ProgressBar, ProgressStyle};
+#[cfg(feature = "cli")]
+use indicatif::ProgressIterator;
+use crate::helpers::events;
 
+/// Returns a spinner displaying `msg`, or a hidden one (after a `progress` event) when
+/// `--events-json` is active.
 pub fn get_new_spinner(msg: String) -> ProgressBar {
+    if events::enabled() {
+        events::progress(msg);
+        return ProgressBar::hidden();
+    }
     let spinner = ProgressBar::new_spinner();
     spinner.set_message(msg);
     spinner.set_style(
@@ -12,4 +22,80 @@ pub fn get_new_spinner(msg: String) -> ProgressBar {
     );
     spinner.enable_steady_tick(Duration::from_millis(50));
     spinner
-}
\ No newline at end of file
+}
+
+/// Wraps `iter` in a progress bar, or emits `progress` events with the percentage of `iter`
+/// consumed when `--events-json` is active.
+#[cfg(feature = "cli")]
+pub fn progress_iter<'a, I>(iter: I, msg: &str) -> Box<dyn Iterator<Item = I::Item> + 'a>
+where
+    I: ExactSizeIterator + 'a,
+{
+    if !events::enabled() {
+        return Box::new(iter.progress());
+    }
+    let total = iter.len().max(1);
+    let msg = msg.to_string();
+    let mut last_percent = None;
+    Box::new(iter.enumerate().map(move |(i, item)| {
+        let percent = ((i + 1) * 100 / total) as u8;
+        if last_percent != Some(percent) {
+            last_percent = Some(percent);
+            events::emit(events::Event::Progress {
+                message: msg.clone(),
+                percent: Some(percent),
+            });
+        }
+        item
+    }))
+}
+
+/// Progress of a task whose steps are done from several threads, see [`get_new_progress`].
+pub struct Progress {
+    bar: ProgressBar,
+    msg: String,
+    total: usize,
+    done: AtomicUsize,
+    last_percent: AtomicU8,
+}
+
+/// Returns a progress bar of `total` steps, or one emitting `progress` events with the
+/// percentage done when `--events-json` is active. Unlike [`progress_iter`], it is advanced with
+/// [`Progress::inc`], which can be called from any thread.
+pub fn get_new_progress(total: usize, msg: &str) -> Progress {
+    let bar = if events::enabled() {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new(total as u64)
+    };
+    Progress {
+        bar,
+        msg: msg.to_string(),
+        total: total.max(1),
+        done: AtomicUsize::new(0),
+        last_percent: AtomicU8::new(0),
+    }
+}
+
+impl Progress {
+    /// Marks `steps` more steps as done.
+    pub fn inc(&self, steps: usize) {
+        if !events::enabled() {
+            self.bar.inc(steps as u64);
+            return;
+        }
+        let done = self.done.fetch_add(steps, Ordering::Relaxed) + steps;
+        let percent = (done.min(self.total) * 100 / self.total) as u8;
+        // only the first thread reaching a percentage reports it, never going backwards
+        if self.last_percent.fetch_max(percent, Ordering::Relaxed) < percent {
+            events::emit(events::Event::Progress {
+                message: self.msg.clone(),
+                percent: Some(percent),
+            });
+        }
+    }
+
+    pub fn finish(&self) {
+        self.bar.finish();
+    }
+}
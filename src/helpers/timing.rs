@@ -0,0 +1,205 @@
+//! Timing of the stages of the pipeline, for `--timing-report`.
+//!
+//! The stages are measured with [`span`] guards, nested like the calls: a span opened while
+//! another one is open on the same thread is recorded under it, e.g. `sast;parse;syn`. Nothing
+//! is measured until [`enable`] is called.
+
+#[cfg(feature = "cli")]
+use anyhow::{Context, Result};
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+#[cfg(feature = "cli")]
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Separator of the stages of a stack, as in the folded stacks format.
+const STACK_SEPARATOR: char = ';';
+
+static TIMINGS: OnceCell<Mutex<BTreeMap<String, StageTiming>>> = OnceCell::new();
+
+thread_local! {
+    /// The stages currently open on the thread, outermost first.
+    static OPEN_STAGES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// What was measured for a stack of stages.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct StageTiming {
+    /// Number of times the stage was entered.
+    pub calls: u64,
+    /// Total time spent in the stage, its sub-stages included, in microseconds.
+    pub total_us: u64,
+}
+
+/// Enables the timing of the stages, until the end of the process.
+#[cfg(feature = "cli")]
+pub fn enable() {
+    let _ = TIMINGS.set(Mutex::new(BTreeMap::new()));
+}
+
+/// Returns `true` if `--timing-report` is active.
+pub fn enabled() -> bool {
+    TIMINGS.get().is_some()
+}
+
+/// A stage being measured, recorded when dropped.
+pub struct Span {
+    started: Option<Instant>,
+}
+
+/// Opens the stage `name`, nested in the stages open on the thread, until the returned guard
+/// is dropped.
+///
+/// `;` can't be used in `name`, it is replaced by `_`.
+pub fn span(name: &str) -> Span {
+    if !enabled() {
+        return Span { started: None };
+    }
+    OPEN_STAGES.with(|stages| stages.borrow_mut().push(name.replace(STACK_SEPARATOR, "_")));
+    Span {
+        started: Some(Instant::now()),
+    }
+}
+
+/// Runs `f` in the stage `name`, see [`span`].
+pub fn time<T>(name: &str, f: impl FnOnce() -> T) -> T {
+    let _span = span(name);
+    f()
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let Some(started) = self.started else {
+            return;
+        };
+        let elapsed = started.elapsed();
+        let stack = OPEN_STAGES.with(|stages| {
+            let mut stages = stages.borrow_mut();
+            let stack = stages.join(&STACK_SEPARATOR.to_string());
+            stages.pop();
+            stack
+        });
+        if let Some(timings) = TIMINGS.get() {
+            let mut timings = timings.lock().unwrap_or_else(|e| e.into_inner());
+            record(&mut timings, stack, elapsed);
+        }
+    }
+}
+
+fn record(timings: &mut BTreeMap<String, StageTiming>, stack: String, elapsed: Duration) {
+    let timing = timings.entry(stack).or_default();
+    timing.calls += 1;
+    timing.total_us += elapsed.as_micros() as u64;
+}
+
+/// The timings of a run, by stack of stages (`sast;rules;<rule>;eval`).
+#[cfg(feature = "cli")]
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TimingReport {
+    pub stages: BTreeMap<String, StageTiming>,
+}
+
+#[cfg(feature = "cli")]
+impl TimingReport {
+    /// Returns what was measured since [`enable`].
+    pub fn collect() -> Self {
+        Self {
+            stages: TIMINGS
+                .get()
+                .map(|timings| timings.lock().unwrap_or_else(|e| e.into_inner()).clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Returns the time spent in a stack outside of its sub-stages, in microseconds.
+    fn self_us(&self, stack: &str) -> u64 {
+        let prefix = format!("{}{}", stack, STACK_SEPARATOR);
+        let children: u64 = self
+            .stages
+            .iter()
+            .filter(|(other, _)| {
+                other
+                    .strip_prefix(&prefix)
+                    .is_some_and(|rest| !rest.contains(STACK_SEPARATOR))
+            })
+            .map(|(_, timing)| timing.total_us)
+            .sum();
+        self.stages[stack].total_us.saturating_sub(children)
+    }
+
+    /// Renders the timings as folded stacks (`sast;parse;syn 1234`), the input of
+    /// `flamegraph.pl` and `inferno-flamegraph`, with the self time of each stack in
+    /// microseconds.
+    pub fn to_folded(&self) -> String {
+        self.stages
+            .keys()
+            .map(|stack| (stack, self.self_us(stack)))
+            .filter(|(_, self_us)| *self_us > 0)
+            .map(|(stack, self_us)| format!("{} {}\n", stack, self_us))
+            .collect()
+    }
+
+    /// Writes the report to `path`, as JSON if it ends with `.json`, as folded stacks otherwise.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let content = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::to_string_pretty(self)?
+        } else {
+            self.to_folded()
+        };
+        std::fs::write(path, content)
+            .with_context(|| format!("Writing the timing report {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timing_report() {
+        let mut stages = BTreeMap::new();
+        record(&mut stages, "sast".to_string(), Duration::from_micros(1000));
+        record(
+            &mut stages,
+            "sast;parse".to_string(),
+            Duration::from_micros(300),
+        );
+        record(
+            &mut stages,
+            "sast;parse;syn".to_string(),
+            Duration::from_micros(100),
+        );
+        record(
+            &mut stages,
+            "sast;parse;syn".to_string(),
+            Duration::from_micros(100),
+        );
+        record(
+            &mut stages,
+            "sast;print".to_string(),
+            Duration::from_micros(700),
+        );
+        let report = TimingReport { stages };
+
+        assert_eq!(report.stages["sast;parse;syn"].calls, 2);
+        assert_eq!(
+            report.to_folded(),
+            "sast;parse 100\nsast;parse;syn 200\nsast;print 700\n"
+        );
+
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["stages"]["sast;parse"]["total_us"], 300);
+
+        enable();
+        {
+            let _outer = span("outer-stage");
+            let _inner = span("inner;stage");
+        }
+        let collected = TimingReport::collect();
+        assert_eq!(collected.stages["outer-stage;inner_stage"].calls, 1);
+        assert_eq!(collected.stages["outer-stage"].calls, 1);
+    }
+}
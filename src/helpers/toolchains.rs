@@ -0,0 +1,119 @@
+//! Toolchains installed and managed by sol-azy, outside of the user's global installs.
+//!
+//! The Anchor CLI versions requested by `build --version-switch` are installed with
+//! `cargo install --root ~/.solazy/toolchains/anchor-v<version>` and invoked from there, so the
+//! `anchor` of the user's `PATH` (or `avm`) is never replaced.
+
+use crate::helpers::run_command;
+use anyhow::{anyhow, Result};
+use log::{debug, info};
+use std::path::PathBuf;
+
+/// Environment variable overriding the sol-azy home directory (`~/.solazy` by default).
+pub const SOLAZY_HOME_ENV: &str = "SOLAZY_HOME";
+
+/// Returns the sol-azy home directory: `$SOLAZY_HOME`, or `.solazy` in the user's home.
+pub fn solazy_home() -> Result<PathBuf> {
+    if let Some(home) = std::env::var_os(SOLAZY_HOME_ENV).filter(|home| !home.is_empty()) {
+        return Ok(PathBuf::from(home));
+    }
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .filter(|home| !home.is_empty())
+        .map(|home| PathBuf::from(home).join(".solazy"))
+        .ok_or_else(|| anyhow!("Cannot locate the home directory, set {}", SOLAZY_HOME_ENV))
+}
+
+/// Checks that `version` is a plain version number (`0.30.1`), since it ends up in a path and
+/// a git tag.
+fn validate_version(version: &str) -> Result<&str> {
+    let version = version.trim().trim_start_matches('v');
+    let valid = !version.is_empty()
+        && version.split('.').all(|part| {
+            !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    if !valid {
+        return Err(anyhow!("Invalid Anchor version '{}'", version));
+    }
+    Ok(version)
+}
+
+/// Returns the directory of the managed Anchor CLI `version`.
+pub fn anchor_toolchain_dir(version: &str) -> Result<PathBuf> {
+    let version = validate_version(version)?;
+    Ok(solazy_home()?
+        .join("toolchains")
+        .join(format!("anchor-v{}", version)))
+}
+
+/// Returns the path of the `anchor` binary of the managed Anchor CLI `version`.
+pub fn anchor_toolchain_bin(version: &str) -> Result<PathBuf> {
+    Ok(anchor_toolchain_dir(version)?
+        .join("bin")
+        .join(format!("anchor{}", std::env::consts::EXE_SUFFIX)))
+}
+
+/// Installs the Anchor CLI `version` in its sol-azy toolchain directory, unless it is already
+/// installed.
+///
+/// This runs `cargo install --git https://github.com/coral-xyz/anchor --tag v<version> anchor-cli
+/// --locked --root <toolchain dir>`, and leaves the user's global `anchor` untouched.
+///
+/// # Returns
+///
+/// The path of the installed `anchor` binary.
+pub fn install_anchor_toolchain(version: &str) -> Result<PathBuf> {
+    let bin = anchor_toolchain_bin(version)?;
+    if bin.is_file() {
+        debug!("Anchor {} already installed at {}", version, bin.display());
+        return Ok(bin);
+    }
+
+    let dir = anchor_toolchain_dir(version)?;
+    let version = validate_version(version)?;
+    info!("Installing Anchor {} into {}", version, dir.display());
+    std::fs::create_dir_all(&dir)?;
+    let tag = format!("v{}", version);
+    let root = dir.to_string_lossy();
+    run_command(
+        "cargo",
+        &[
+            "install",
+            "--git",
+            "https://github.com/coral-xyz/anchor",
+            "--tag",
+            tag.as_str(),
+            "anchor-cli",
+            "--locked",
+            "--root",
+            root.as_ref(),
+        ],
+        vec![],
+    )?;
+
+    if !bin.is_file() {
+        return Err(anyhow!(
+            "Anchor {} was installed but {} is missing",
+            version,
+            bin.display()
+        ));
+    }
+    Ok(bin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anchor_toolchain_paths() {
+        let dir = anchor_toolchain_dir("v0.30.1").unwrap();
+        assert!(dir.ends_with("toolchains/anchor-v0.30.1"));
+        assert!(anchor_toolchain_bin("0.30.1")
+            .unwrap()
+            .starts_with(dir.join("bin")));
+        assert!(anchor_toolchain_dir("0.30.1-rc.1").is_ok());
+        assert!(anchor_toolchain_dir("../../bin").is_err());
+        assert!(anchor_toolchain_dir("").is_err());
+    }
+}
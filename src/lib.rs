@@ -0,0 +1,910 @@
+//! Library of the `sol-azy` CLI application.
+//!
+//! This crate provides commands for building Solana programs, running SAST (static analysis),
+//! and performing reverse engineering (disassembly and CFG generation) on compiled bytecode.
+//!
+//! Commands are parsed using `clap`, and executed through the central `AppState` dispatcher.
+//! The `sol-azy` binary (`main.rs`) only sets up the logging and the events before dispatching;
+//! the `python` feature builds the `solazy` Python bindings of the same code.
+//! The reverse analysis is also exposed, with its [`ReversePass`] extension point.
+//!
+//! Without the default `cli` feature, only the analysis core (disassembly, CFG, syn-AST SAST)
+//! is built, without the dependencies of the commands; the `wasm` feature builds its browser
+//! bindings on top of it.
+
+#[cfg(feature = "cli")]
+mod commands;
+#[cfg(any(feature = "cli", feature = "wasm"))]
+mod constraints;
+mod deps;
+#[cfg(feature = "cli")]
+mod dotting;
+mod engines;
+#[cfg(feature = "cli")]
+mod fetcher;
+#[cfg(feature = "cli")]
+mod fixer;
+#[cfg(feature = "cli")]
+mod fuzz;
+mod helpers;
+mod parsers;
+mod printers;
+#[cfg(any(feature = "cli", feature = "wasm"))]
+mod pubkeys;
+#[cfg(feature = "python")]
+mod python;
+mod recap;
+mod reverse;
+#[cfg(feature = "cli")]
+mod server;
+mod state;
+#[cfg(feature = "cli")]
+mod tui;
+#[cfg(feature = "wasm")]
+mod wasm;
+
+pub use crate::helpers::events::{init as init_events, EventTarget};
+pub use crate::reverse::passes::{
+    PassAnnotation, PassContext, PassOutput, PassRegistry, PassToggles, ReversePass,
+};
+pub use crate::reverse::{
+    analyze_in_memory, analyze_program, analyze_program_with_passes, InMemoryOutputs,
+    ReverseOptions, ReverseOutputMode,
+};
+#[cfg(feature = "cli")]
+pub use crate::state::app_state::AppState;
+#[cfg(feature = "cli")]
+use clap::{Parser, Subcommand};
+
+#[cfg(feature = "cli")]
+#[derive(Parser)]
+#[clap(
+    name = "sol-azy",
+    version = "0.1",
+    author = "FuzzingLabs",
+    arg_required_else_help = true
+)]
+pub struct Cli {
+    /// Only `None` with `--generate-man`.
+    #[clap(subcommand)]
+    pub command: Option<Commands>,
+
+    #[clap(
+        long = "generate-man",
+        hide = true,
+        exclusive = true,
+        value_name = "DIR",
+        help = "Write the man pages of sol-azy and of each command to DIR"
+    )]
+    pub generate_man: Option<String>,
+
+    #[clap(
+        long = "events-json",
+        global = true,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "stdout",
+        value_name = "TARGET",
+        help = "Emit line-delimited JSON events instead of spinners, to stdout (default) or to --events-json=tcp://<host>:<port> or unix://<path>"
+    )]
+    pub events_json: Option<String>,
+}
+
+#[cfg(feature = "cli")]
+#[derive(Subcommand)]
+pub enum Commands {
+    Build {
+        #[clap(
+            short = 'd',
+            long = "target-dir",
+            required = true,
+            help = "Project to build, can be repeated to build several projects"
+        )]
+        target_dir: Vec<String>,
+        #[clap(
+            short = 'j',
+            long = "jobs",
+            default_value_t = 1,
+            help = "Number of projects built at the same time"
+        )]
+        jobs: usize,
+        #[clap(short = 'r', long = "out-dir")]
+        out_dir: String,
+        #[clap(
+            long = "label",
+            help = "Name of the run directory (<out-dir>/build/<label>), the start time by default"
+        )]
+        label: Option<String>,
+        #[clap(
+            long = "version-switch",
+            alias = "unsafe-version-switch",
+            default_value_t = false,
+            help = "Build Anchor projects with the Anchor CLI version of their Anchor.toml, installed in ~/.solazy/toolchains (the global anchor is left untouched)"
+        )]
+        version_switch: bool,
+        #[clap(
+            long = "features",
+            value_delimiter = ',',
+            help = "Cargo features to enable in the built programs"
+        )]
+        features: Vec<String>,
+        #[clap(long = "release", action, conflicts_with = "debug", help = "Build with the release profile (default)")]
+        release: bool,
+        #[clap(long = "debug", action, help = "Build with the debug profile")]
+        debug: bool,
+        #[clap(long = "no-clean", action, help = "Skip the `cargo clean` step before building")]
+        no_clean: bool,
+        #[clap(
+            long = "emit",
+            value_delimiter = ',',
+            default_value = commands::build_command::DEFAULT_EMIT,
+            help = "Artifacts emitted by rustc (`--emit` list), or `none` to keep the rustc defaults"
+        )]
+        emit: Vec<String>,
+        #[clap(
+            long = "rustflags",
+            allow_hyphen_values = true,
+            help = "Extra RUSTFLAGS appended to the ones set by sol-azy"
+        )]
+        rustflags: Option<String>,
+        #[clap(
+            short = 'v',
+            long = "verbose",
+            action,
+            help = "Echo the output of the build commands (always written to <out-dir>/build-<project>-<hash>.log)"
+        )]
+        verbose: bool,
+    },
+    Sast {
+        #[clap(short = 'd', long = "target-dir")]
+        target_dir: String,
+        #[clap(short = 'r', long = "rules-dir")]
+        rules_dir: Option<String>,
+        #[clap(
+            long = "rules-git",
+            conflicts_with = "rules_dir",
+            help = "Git repository of rules to clone into .solazy_cache/rules and load like --rules-dir: <url>[@rev], pinned with a full commit hash as rev"
+        )]
+        rules_git: Option<String>,
+        #[clap(short = 's', long = "syn-scan-only", default_value_t = false)]
+        syn_scan_only: bool,
+        #[clap(long = "no-internal-rules", action = clap::ArgAction::SetFalse, default_value_t = true)]
+        use_internal_rules: bool,
+        #[clap(long = "recursive", default_value_t = true)]
+        recursive: bool,
+        #[clap(
+            long = "column-encoding",
+            value_parser = clap::builder::PossibleValuesParser::new(["chars", "bytes", "utf16"]),
+            default_value = "chars",
+            help = "Unit of the columns in the reported source positions"
+        )]
+        column_encoding: String,
+        #[clap(
+            long = "enable-rule",
+            value_delimiter = ',',
+            help = "Only evaluate these rules (ID relative to its rules directory, or a directory of rules)"
+        )]
+        enable_rules: Vec<String>,
+        #[clap(
+            long = "disable-rule",
+            value_delimiter = ',',
+            help = "Skip these rules (ID relative to its rules directory, or a directory of rules)"
+        )]
+        disable_rules: Vec<String>,
+        #[clap(
+            long = "trusted-rules-only",
+            action,
+            help = "Refuse the external rules and templates, except the ones allowed with --trust-rule"
+        )]
+        trusted_rules_only: bool,
+        #[clap(
+            long = "trust-rule",
+            value_delimiter = ',',
+            help = "External rules allowed with --trusted-rules-only (ID relative to its rules directory, or a directory of rules)"
+        )]
+        trust_rules: Vec<String>,
+        #[clap(
+            long = "rule-max-steps",
+            default_value_t = engines::starlark_engine::DEFAULT_RULE_MAX_STEPS,
+            help = "Maximum number of statements executed by a rule (0 for no limit)"
+        )]
+        rule_max_steps: u64,
+        #[clap(
+            long = "rule-timeout",
+            default_value_t = engines::starlark_engine::DEFAULT_RULE_TIMEOUT_SECS,
+            help = "Maximum duration of a rule on a file, in seconds (0 for no limit)"
+        )]
+        rule_timeout: u64,
+        #[clap(
+            long = "rule-max-heap-mb",
+            default_value_t = engines::starlark_engine::DEFAULT_RULE_MAX_HEAP_MB,
+            value_parser = engines::starlark_engine::parse_max_heap_mb,
+            help = "Maximum heap of a rule, in MiB (0 for no limit)"
+        )]
+        rule_max_heap_mb: usize,
+        #[clap(
+            long = "bytecode",
+            value_delimiter = ',',
+            help = "Compiled program (.so) of the project, to tell whether the code of each finding was compiled"
+        )]
+        bytecodes: Vec<String>,
+        #[clap(
+            long = "build-manifest",
+            help = "build-manifest.json of a `build` run, whose programs are correlated like --bytecode"
+        )]
+        build_manifest: Option<String>,
+        #[clap(
+            long = "export-db",
+            help = "Directory receiving the AST nodes, call graph and findings as CSV tables"
+        )]
+        export_db: Option<String>,
+        #[clap(
+            long = "taxonomy",
+            help = "TOML taxonomy in which the findings are rated (e.g. likelihood / impact) instead of severity / certainty"
+        )]
+        taxonomy: Option<String>,
+        #[clap(
+            long = "strict",
+            action,
+            help = "Fail when a rule can't be evaluated on a file, instead of reporting it with the other results"
+        )]
+        strict: bool,
+        #[clap(
+            long = "tui",
+            action,
+            help = "Browse the findings in an interactive terminal interface instead of printing them"
+        )]
+        tui: bool,
+        #[clap(
+            long = "baseline",
+            help = "Baseline of the findings triaged with --tui (default: <target-dir>/.solazy-baseline.json)"
+        )]
+        baseline: Option<String>,
+        #[clap(
+            long = "advisories",
+            help = "TOML advisories checked against the Cargo.lock of the project, replacing the shipped ones of the same id"
+        )]
+        advisories: Option<String>,
+        #[clap(
+            long = "features",
+            value_delimiter = ',',
+            help = "Features of the deployed program: the code whose #[cfg(...)] don't hold with them is not scanned ('' for none)"
+        )]
+        features: Vec<String>,
+        #[clap(
+            long = "cfg",
+            value_delimiter = ',',
+            help = "Options of the deployed program (name or name=\"value\"), besides the ones of the Solana target, for the #[cfg(...)] like --features"
+        )]
+        cfgs: Vec<String>,
+        #[clap(
+            long = "fix",
+            action,
+            help = "Apply the fixes suggested by the rules to the matched sources"
+        )]
+        fix: bool,
+        #[clap(
+            long = "dry-run",
+            action,
+            requires = "fix",
+            help = "With --fix, print the fixes as a unified diff instead of applying them"
+        )]
+        dry_run: bool,
+        #[clap(
+            long = "timing-report",
+            help = "File receiving the time spent in each stage of the scan, as folded stacks for a flamegraph, or as JSON if it ends with .json"
+        )]
+        timing_report: Option<String>,
+        #[clap(
+            long = "max-file-size",
+            value_parser = parsers::syn_ast::parse_size,
+            help = "Size above which a source file is skipped with a warning instead of scanned, in bytes or with a K, M or G suffix (e.g. 2M)"
+        )]
+        max_file_size: Option<u64>,
+        #[clap(
+            long = "spill-ast",
+            action,
+            help = "Write the JSON syntax trees of the files to a temporary directory instead of keeping them all in memory"
+        )]
+        spill_ast: bool,
+    },
+    // example: cargo run -- fuzz --idl target/idl/program.json --init-spec harness.toml
+    Fuzz {
+        #[clap(
+            long = "spec",
+            conflicts_with = "init_spec",
+            help = "Harness spec (TOML, or JSON for a .json file) describing the accounts given to each instruction"
+        )]
+        spec: Option<String>,
+
+        #[clap(
+            long = "idl",
+            requires = "init_spec",
+            help = "Anchor IDL from which the skeleton harness spec is generated"
+        )]
+        idl: Option<String>,
+
+        #[clap(
+            long = "init-spec",
+            requires = "idl",
+            help = "Write the skeleton harness spec generated from --idl to this path (.toml or .json)"
+        )]
+        init_spec: Option<String>,
+    },
+    Test {},
+    Clean {},
+    // example: cargo run -- completions bash > ~/.local/share/bash-completion/completions/sol-azy
+    Completions {
+        #[clap(value_enum, help = "Shell to generate the completion script for")]
+        shell: clap_complete::Shell,
+    },
+    // example: cargo run -- reverse --mode both --out-dir test_cases/base_sbf_addition_checker/out1/  --bytecodes-file ./test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so --labeling
+    Reverse {
+        #[clap(long = "mode", value_parser = clap::builder::PossibleValuesParser::new(["disass", "cfg", "both"]))]
+        mode: String,
+
+        #[clap(long = "out-dir")]
+        out_dir: String,
+
+        #[clap(
+            long = "label",
+            help = "Name of the run directory (<out-dir>/reverse/<label>), the start time by default"
+        )]
+        label: Option<String>,
+
+        #[clap(
+            long = "bytecodes-file",
+            required_unless_present = "bundle",
+            help = "Compiled program to analyze, or with --bundle the name of the program of the bundle"
+        )]
+        bytecodes_file: Option<String>,
+
+        #[clap(
+            long = "bundle",
+            help = "Zip archive or npm tarball holding the program, its IDL and metadata, extracted to a temporary directory"
+        )]
+        bundle: Option<String>,
+
+        #[clap(
+            long = "labeling",
+            action,
+            conflicts_with = "no_labeling",
+            help = "Force symbol and section labeling (detected from the ELF symbol table by default)"
+        )]
+        labeling: bool,
+
+        #[clap(
+            long = "no-labeling",
+            action,
+            help = "Disable symbol and section labeling, even if the ELF has a symbol table"
+        )]
+        no_labeling: bool,
+
+        #[clap(long = "reduced", action)]
+        reduced: bool,
+
+        #[clap(long = "only-entrypoint", action)]
+        only_entrypoint: bool,
+
+        #[clap(
+            long = "entry",
+            value_name = "ADDRESS|LABEL",
+            help = "Root of the reduced CFG instead of the entrypoint (implies --reduced)"
+        )]
+        entry: Option<String>,
+
+        #[clap(
+            long = "idl",
+            help = "Anchor IDL used to name the recognized dispatch targets, program error codes and account discriminators"
+        )]
+        idl: Option<String>,
+
+        #[clap(
+            long = "account-names",
+            help = "Wordlist of account names (one per line) hashed to name the account discriminators, on top of the shipped one"
+        )]
+        account_names: Option<String>,
+
+        #[clap(
+            long = "account-sources",
+            help = "Source tree whose struct names are hashed to name the account discriminators"
+        )]
+        account_sources: Option<String>,
+
+        #[clap(
+            long = "cfg-chunk",
+            help = "Split the CFG into .dot files of N functions each (with an index), rendered in parallel"
+        )]
+        cfg_chunk: Option<usize>,
+
+        #[clap(
+            long = "stats",
+            action,
+            help = "Export instruction statistics (opcode histogram, per-function counts) as JSON and CSV"
+        )]
+        stats: bool,
+
+        #[clap(
+            long = "signatures",
+            help = "Additional .sig file (or directory) used to identify library functions"
+        )]
+        signatures: Option<String>,
+
+        #[clap(
+            long = "export-signatures",
+            action,
+            help = "Export the signatures of the named functions to signatures.sig"
+        )]
+        export_signatures: bool,
+
+        #[clap(
+            long = "raw",
+            action,
+            help = "Read the input as raw instructions (memory dump, hex dump or base64) instead of an ELF"
+        )]
+        raw: bool,
+
+        #[clap(
+            long = "raw-format",
+            requires = "raw",
+            value_parser = clap::builder::PossibleValuesParser::new(["auto", "bin", "hex", "base64"]),
+            default_value = "auto",
+            help = "Encoding of the raw input"
+        )]
+        raw_format: String,
+
+        #[clap(
+            long = "text-offset",
+            requires = "raw",
+            default_value = "0",
+            help = "Offset of the first instruction in the decoded raw input (decimal or 0x hex)"
+        )]
+        text_offset: String,
+
+        #[clap(
+            long = "sbpf-version",
+            requires = "raw",
+            value_parser = clap::builder::PossibleValuesParser::new(["v0", "v1", "v2", "v3"]),
+            default_value = "v0",
+            help = "SBPF version of the raw instructions"
+        )]
+        sbpf_version: String,
+
+        #[clap(
+            long = "no-rusteq",
+            action,
+            conflicts_with = "rusteq_only",
+            help = "Omit the Rust equivalent column from the disassembly"
+        )]
+        no_rusteq: bool,
+
+        #[clap(
+            long = "rusteq-only",
+            action,
+            help = "Only write the Rust equivalent of the instructions in the disassembly, one line per pc"
+        )]
+        rusteq_only: bool,
+
+        #[clap(
+            long = "disass-format",
+            value_parser = clap::builder::PossibleValuesParser::new(["solazy", "objdump", "json"]),
+            default_value = "solazy",
+            help = "Format of the disassembly: annotated (solazy), objdump-like for diffing tools, or JSON (disassembly.json)"
+        )]
+        disass_format: String,
+
+        #[clap(
+            long = "show-bytes",
+            action,
+            help = "Write the bytes of each instruction before it in the disassembly, like objdump"
+        )]
+        show_bytes: bool,
+
+        #[clap(
+            long = "stable-ids",
+            action,
+            help = "Name the CFG clusters and blocks after the hash of their function instead of their address (stable across rebuilds), and write id_map.json"
+        )]
+        stable_ids: bool,
+
+        #[clap(
+            long = "only-function",
+            help = "Only output this function (name or address, e.g. 42, 0x2a or lbb_42), can be repeated"
+        )]
+        only_function: Vec<String>,
+
+        #[clap(
+            long = "skip-libs",
+            action,
+            help = "Leave out the library functions identified by signature"
+        )]
+        skip_libs: bool,
+
+        #[clap(
+            long = "range",
+            help = "Only output the functions starting in <start>-<end> (instruction pointers, decimal or 0x hex)"
+        )]
+        range: Option<String>,
+
+        #[clap(
+            long = "pass",
+            help = "Run this analysis pass on top of the default ones (dominators, pda-seeds, pointer-leaks, program-info; off by default: stats, fuzz-corpus), can be repeated"
+        )]
+        pass: Vec<String>,
+
+        #[clap(long = "no-pass", help = "Skip this analysis pass, can be repeated")]
+        no_pass: Vec<String>,
+
+        #[clap(
+            long = "annotations",
+            help = "Annotations file (renames, comments, bookmarks) merged into the outputs, annotations.json next to the program by default"
+        )]
+        annotations: Option<String>,
+
+        #[clap(
+            long = "export-names",
+            action,
+            help = "Export the names of the functions and blocks for Ghidra and Binary Ninja (names.csv, names.json)"
+        )]
+        export_names: bool,
+
+        #[clap(
+            long = "fuzz-corpus",
+            help = "Corpus directory of a fuzz workspace (e.g. fuzz/corpus/<target>) where the seeds of the fuzz-corpus pass are copied"
+        )]
+        fuzz_corpus: Option<String>,
+
+        #[clap(
+            long = "tui",
+            action,
+            help = "Browse the functions and their disassembly in the terminal once the outputs are written"
+        )]
+        tui: bool,
+    },
+    // example: cargo run -- dotting -c functions.json -f cfg.dot -r cfg_reduced.dot
+    Dotting {
+        #[clap(
+            short = 'c',
+            long = "config",
+            help = "Path to the JSON configuration file (e.g. to specify which functions to add)"
+        )]
+        config: String,
+
+        #[clap(
+            short = 'r',
+            long = "reduced-dot-path",
+            help = "Path to the reduced graph (cfg.json, or cfg.dot for legacy graphs)"
+        )]
+        reduced_dot_path: String,
+
+        #[clap(
+            short = 'f',
+            long = "full-dot-path",
+            help = "Path to the full graph (cfg.json, or cfg.dot for legacy graphs)"
+        )]
+        full_dot_path: String,
+
+        #[clap(
+            long = "depth",
+            help = "Also add the functions reachable within N calls of the configured ones (or of the entrypoint if the config lists none)"
+        )]
+        depth: Option<usize>,
+    },
+    // example: cargo run -- asm -i snippet.s --bytecodes-file program.so --pc 42 --format patch
+    Asm {
+        #[clap(
+            short = 'i',
+            long = "input",
+            help = "File with the instructions to assemble (disassembly syntax), stdin by default"
+        )]
+        input: Option<String>,
+
+        #[clap(
+            long = "bytecodes-file",
+            help = "Program whose labels and SBPF version are used to assemble the instructions"
+        )]
+        bytecodes_file: Option<String>,
+
+        #[clap(
+            long = "pc",
+            default_value = "0",
+            help = "Instruction pointer of the first instruction (decimal, 0x hex or lbb_<pc>)"
+        )]
+        pc: String,
+
+        #[clap(
+            long = "sbpf-version",
+            conflicts_with = "bytecodes_file",
+            value_parser = clap::builder::PossibleValuesParser::new(["v0", "v1", "v2", "v3"]),
+            default_value = "v0",
+            help = "SBPF version of the instructions, when no program is given"
+        )]
+        sbpf_version: String,
+
+        #[clap(
+            long = "format",
+            value_parser = clap::builder::PossibleValuesParser::new(["listing", "patch", "bin"]),
+            default_value = "listing",
+            help = "Output: listing (pc, hex, source), patch (JSON patch spec) or bin (raw bytes, needs --output)"
+        )]
+        format: String,
+
+        #[clap(short = 'o', long = "output", help = "Output file, stdout by default")]
+        output: Option<String>,
+    },
+    // example: cargo run -- patch --bytecodes-file program.so -s patches.json -o program.patched.so
+    Patch {
+        #[clap(
+            long = "bytecodes-file",
+            help = "Path to the compiled program (.so) to patch"
+        )]
+        bytecodes_file: String,
+
+        #[clap(
+            short = 's',
+            long = "spec",
+            help = "Path to the JSON patch spec (instructions to replace and nop ranges)"
+        )]
+        spec: String,
+
+        #[clap(
+            short = 'o',
+            long = "output",
+            help = "Path of the patched program, <input>.patched.so by default"
+        )]
+        output: Option<String>,
+    },
+    // example: cargo run -- import-names --bytecodes-file program.so -i ghidra_names.csv
+    ImportNames {
+        #[clap(
+            long = "bytecodes-file",
+            help = "Path to the compiled program (.so) the names belong to"
+        )]
+        bytecodes_file: String,
+
+        #[clap(
+            short = 'i',
+            long = "input",
+            help = "Names to import: CSV (address,name per line) or names.json"
+        )]
+        input: String,
+
+        #[clap(
+            long = "annotations",
+            help = "Annotations file to update, annotations.json next to the program by default"
+        )]
+        annotations: Option<String>,
+    },
+    // example: cargo run -- query -a ./out/reverse/latest --calls sol_invoke_signed --functions
+    Query {
+        #[clap(
+            short = 'a',
+            long = "artifacts",
+            help = "Run directory of reverse holding cfg.json or disassembly.json"
+        )]
+        artifacts: String,
+
+        #[clap(
+            long = "calls",
+            help = "Blocks calling a syscall or function whose name matches this regex"
+        )]
+        calls: Option<String>,
+
+        #[clap(
+            long = "strings",
+            help = "Blocks loading an immediate string matching this regex"
+        )]
+        strings: Option<String>,
+
+        #[clap(
+            long = "min-instructions",
+            help = "Blocks of at least this many instructions"
+        )]
+        min_instructions: Option<usize>,
+
+        #[clap(
+            long = "function",
+            help = "Blocks of the functions whose name matches this regex"
+        )]
+        function: Option<String>,
+
+        #[clap(
+            long = "functions",
+            action,
+            help = "List the functions holding the matching blocks instead of the blocks"
+        )]
+        functions: bool,
+    },
+    Fetcher {
+        #[clap(
+            short = 'p',
+            long = "program-id",
+            help = "Solana Program ID to fetch bytecode from"
+        )]
+        program_id: String,
+
+        #[clap(
+            short = 'o',
+            long = "out-dir",
+            help = "Path to write the program.so file"
+        )]
+        out_dir: String,
+
+        #[clap(
+            long = "label",
+            help = "Name of the run directory (<out-dir>/fetcher/<label>), the start time by default"
+        )]
+        label: Option<String>,
+
+        #[clap(
+            short = 'r',
+            long = "rpc-url",
+            help = "Optional Solana RPC endpoint (by default it will use https://api.mainnet-beta.solana.com)"
+        )]
+        rpc_url: Option<String>,
+
+        #[clap(
+            long = "at-slot",
+            help = "Fetch the deployment that was live at this slot (requires an archive RPC if the program was upgraded since)"
+        )]
+        at_slot: Option<u64>,
+
+        #[clap(
+            long = "layout",
+            action,
+            help = "Accept a non-executable account and write the annotated memory map of its data (account_layout.txt)"
+        )]
+        layout: bool,
+
+        #[clap(
+            long = "idl",
+            requires = "layout",
+            help = "Anchor IDL whose account types are used to segment the data with --layout"
+        )]
+        idl: Option<String>,
+
+        #[clap(
+            long = "account-type",
+            requires = "idl",
+            help = "Account type of the IDL to decode the data with (found by discriminator by default)"
+        )]
+        account_type: Option<String>,
+
+        #[clap(
+            long = "account-names",
+            requires = "layout",
+            help = "Wordlist of account names (one per line) hashed to identify the account type, on top of the shipped one"
+        )]
+        account_names: Option<String>,
+
+        #[clap(
+            long = "account-sources",
+            requires = "layout",
+            help = "Source tree whose struct names are hashed to identify the account type"
+        )]
+        account_sources: Option<String>,
+
+        #[clap(
+            long = "force",
+            action,
+            help = "Download the program even if its current deployment is in .solazy_cache/programs"
+        )]
+        force: bool,
+
+        #[clap(
+            long = "resolve-pubkeys",
+            action,
+            conflicts_with = "layout",
+            help = "Read the 32-byte keys of the data sections of the program on-chain, and write whether they are programs, mints or accounts with their owner (rodata_pubkeys.out)"
+        )]
+        resolve_pubkeys: bool,
+
+        #[clap(
+            long = "rpc-rate",
+            default_value_t = fetcher::rpc::DEFAULT_REQUESTS_PER_SECOND,
+            help = "Maximum number of requests per second sent to the RPC"
+        )]
+        rpc_rate: u32,
+    },
+    AstUtils {
+        #[clap(short = 'f', long = "file-path", help = "Path to the file to parse")]
+        file_path: String,
+        #[clap(short = 's', long = "starlark-syn-ast", default_value_t = false)]
+        starlark_syn_ast: bool,
+        #[clap(
+            long = "column-encoding",
+            value_parser = clap::builder::PossibleValuesParser::new(["chars", "bytes", "utf16"]),
+            default_value = "chars",
+            help = "Unit of the columns in the reported source positions"
+        )]
+        column_encoding: String,
+    },
+    Recap {
+        #[clap(
+            short = 'd',
+            long = "target-dir",
+            help = "Path to the root of an Anchor project (with an IDL)"
+        )]
+        anchor_path: Option<String>,
+        #[clap(
+            long = "bundle",
+            conflicts_with = "anchor_path",
+            help = "Zip archive or npm tarball holding the Anchor workspace and its IDLs, extracted to a temporary directory"
+        )]
+        bundle: Option<String>,
+        #[clap(
+            short = 'o',
+            long = "out-dir",
+            help = "Write the recap in <out-dir>/recap/<label or timestamp>/ instead of ./recap-solazy.md"
+        )]
+        out_dir: Option<String>,
+        #[clap(
+            long = "label",
+            requires = "out_dir",
+            help = "Name of the run directory (<out-dir>/recap/<label>), the start time by default"
+        )]
+        label: Option<String>,
+        #[clap(
+            long = "graphs",
+            action,
+            help = "Also write the ownership graph of each instruction to ownership/<program>/<instruction>.dot"
+        )]
+        graphs: bool,
+    },
+    Doctor {
+        #[clap(
+            short = 'd',
+            long = "target-dir",
+            help = "Project whose Anchor.toml [toolchain] pins the expected Anchor and Solana versions"
+        )]
+        target_dir: Option<String>,
+    },
+    ScanAll {
+        #[clap(
+            short = 'c',
+            long = "config",
+            default_value = commands::scan_all_command::WORKSPACE_FILE,
+            help = "Workspace file listing the projects to scan"
+        )]
+        config: String,
+        #[clap(
+            short = 'o',
+            long = "out",
+            help = "Path of the aggregated report (by default ./solazy-scan-report.md)"
+        )]
+        out: Option<String>,
+    },
+    Serve {
+        #[clap(
+            short = 'l',
+            long = "listen",
+            default_value = "127.0.0.1:8080",
+            help = "Address the HTTP API listens on"
+        )]
+        listen: String,
+        #[clap(
+            short = 'w',
+            long = "work-dir",
+            default_value = "solazy-jobs",
+            help = "Directory where each job writes its artifacts (<work-dir>/<job id>/)"
+        )]
+        work_dir: String,
+        #[clap(
+            long = "max-jobs",
+            default_value_t = 2,
+            help = "Number of jobs running at the same time, the others are queued"
+        )]
+        max_jobs: usize,
+        #[clap(
+            long = "max-upload-mb",
+            default_value_t = 256,
+            help = "Largest file uploaded with POST /uploads, in MiB"
+        )]
+        max_upload_mb: u64,
+        #[clap(
+            long = "token",
+            help = "Bearer token required in the Authorization header of every request"
+        )]
+        token: Option<String>,
+    },
+}
@@ -0,0 +1,512 @@
+//! Structured parsing of the Anchor accounts structs.
+//!
+//! The `#[derive(Accounts)]` structs of a syntax tree are read with their fields, the
+//! arguments of the `#[account(...)]` attributes of each field being parsed from the attribute
+//! token streams (`seeds = [...]` is a `syn::Expr`, not a slice of text), along with the
+//! `#[instruction(...)]` arguments of the struct and the byte string constants of the file,
+//! which the seeds often refer to.
+//!
+//! The arguments are also added to the JSON syntax tree given to the rules, as the
+//! `constraints` of the fields (see [`enrich_json_with_constraints`]).
+
+use proc_macro2::Span;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+use syn::{Attribute, Expr, Ident, Item, Token, Type};
+#[cfg(any(feature = "cli", feature = "wasm"))]
+use syn::{GenericArgument, Lit, PathArguments};
+
+use crate::parsers::syn_ast::{ColumnEncoding, SourcePosition};
+
+/// An argument of an `#[account(...)]` attribute, e.g. `mut`, `seeds = [...]`,
+/// `token::mint = mint` or `has_one = authority @ ErrorCode::Unauthorized`.
+#[derive(Clone)]
+pub struct AccountArg {
+    /// The key with its namespace, e.g. `token::mint` or `realloc::zero`.
+    pub key: String,
+    /// The value after `=`, without the custom error after `@`.
+    pub value: Option<Expr>,
+}
+
+impl Parse for AccountArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // `parse_any` accepts the keywords, such as `mut`
+        let mut key = Ident::parse_any(input)?.to_string();
+        while input.peek(Token![::]) {
+            input.parse::<Token![::]>()?;
+            key.push_str("::");
+            key.push_str(&Ident::parse_any(input)?.to_string());
+        }
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Some(input.parse::<Expr>()?)
+        } else {
+            None
+        };
+        if input.peek(Token![@]) {
+            input.parse::<Token![@]>()?;
+            input.parse::<Expr>()?;
+        }
+        Ok(Self { key, value })
+    }
+}
+
+impl AccountArg {
+    /// Returns the argument as an entry of the `constraints` of the JSON of a field, e.g.
+    /// `{"key": "has_one", "value": "authority", "idents": ["authority"]}`.
+    ///
+    /// The value is written as in the sources (empty if they aren't known), `null` for a flag
+    /// such as `mut`, and `idents` lists the identifiers it uses, in order.
+    pub fn to_json(&self) -> Value {
+        match &self.value {
+            Some(value) => {
+                let mut idents = IdentCollector::default();
+                idents.visit_expr(value);
+                json!({
+                    "key": self.key,
+                    "value": value.span().source_text().unwrap_or_default(),
+                    "idents": idents.0,
+                })
+            }
+            None => json!({ "key": self.key, "value": null, "idents": [] }),
+        }
+    }
+}
+
+/// Collects the identifiers of an expression, in order.
+#[derive(Default)]
+struct IdentCollector(Vec<String>);
+
+impl<'ast> Visit<'ast> for IdentCollector {
+    fn visit_ident(&mut self, ident: &'ast Ident) {
+        self.0.push(ident.to_string());
+    }
+}
+
+/// An argument of an `#[instruction(...)]` attribute, `name: Type`.
+struct InstructionArg {
+    name: String,
+    ty: Type,
+}
+
+impl Parse for InstructionArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<Option<Token![mut]>>()?;
+        let name = Ident::parse_any(input)?.to_string();
+        input.parse::<Token![:]>()?;
+        Ok(Self {
+            name,
+            ty: input.parse()?,
+        })
+    }
+}
+
+/// A field of an accounts struct.
+#[derive(Clone)]
+pub struct AccountsField {
+    pub name: String,
+    #[cfg_attr(not(any(feature = "cli", feature = "wasm")), allow(dead_code))]
+    pub ty: Type,
+    /// The arguments of all the `#[account(...)]` attributes of the field.
+    pub args: Vec<AccountArg>,
+    /// Whether an `#[account(...)]` attribute couldn't be parsed, its arguments being unknown.
+    pub unparsed: bool,
+    /// Position of the name of the field.
+    #[cfg_attr(not(any(feature = "cli", feature = "wasm")), allow(dead_code))]
+    pub position: SourcePosition,
+}
+
+impl AccountsField {
+    /// Returns the argument `key` (e.g. `seeds`), if the field has it.
+    #[cfg(any(feature = "cli", feature = "wasm"))]
+    pub fn arg(&self, key: &str) -> Option<&AccountArg> {
+        self.args.iter().find(|arg| arg.key == key)
+    }
+
+    #[cfg(any(feature = "cli", feature = "wasm"))]
+    pub fn has_arg(&self, key: &str) -> bool {
+        self.arg(key).is_some()
+    }
+
+    /// Returns the account type of the field, without `Box<...>` / `Option<...>` wrappers nor
+    /// path, e.g. `Signer` or `Account`.
+    #[cfg(any(feature = "cli", feature = "wasm"))]
+    pub fn account_type(&self) -> String {
+        let (name, _) = unwrapped_type(&self.ty);
+        name
+    }
+
+    /// Returns the state account held by an `Account`, `AccountLoader` or `InterfaceAccount`
+    /// field, without its path, e.g. `Vault` for `Box<Account<'info, crate::state::Vault>>`.
+    #[cfg(any(feature = "cli", feature = "wasm"))]
+    pub fn state_type(&self) -> Option<String> {
+        let (name, arguments) = unwrapped_type(&self.ty);
+        if !matches!(
+            name.as_str(),
+            "Account" | "AccountLoader" | "InterfaceAccount"
+        ) {
+            return None;
+        }
+        match arguments?.args.iter().last()? {
+            GenericArgument::Type(Type::Path(inner)) => inner
+                .path
+                .segments
+                .last()
+                .map(|segment| segment.ident.to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// A `#[derive(Accounts)]` struct.
+#[derive(Clone)]
+pub struct AccountsStruct {
+    pub name: String,
+    /// The `#[instruction(...)]` arguments, with their types.
+    #[cfg_attr(not(any(feature = "cli", feature = "wasm")), allow(dead_code))]
+    pub instruction_args: Vec<(String, Type)>,
+    pub fields: Vec<AccountsField>,
+}
+
+impl AccountsStruct {
+    pub fn field(&self, name: &str) -> Option<&AccountsField> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+}
+
+/// Returns the name of a type without `Box<...>` / `Option<...>` wrappers nor path, with its
+/// generic arguments.
+#[cfg(any(feature = "cli", feature = "wasm"))]
+fn unwrapped_type(ty: &Type) -> (String, Option<&syn::AngleBracketedGenericArguments>) {
+    let mut ty = ty;
+    loop {
+        let Type::Path(path) = ty else {
+            return (String::new(), None);
+        };
+        let Some(segment) = path.path.segments.last() else {
+            return (String::new(), None);
+        };
+        let arguments = match &segment.arguments {
+            PathArguments::AngleBracketed(arguments) => Some(arguments),
+            _ => None,
+        };
+        let inner = arguments.and_then(|arguments| {
+            arguments.args.iter().find_map(|argument| match argument {
+                GenericArgument::Type(inner) => Some(inner),
+                _ => None,
+            })
+        });
+        match (segment.ident.to_string().as_str(), inner) {
+            ("Box" | "Option", Some(inner)) => ty = inner,
+            (name, _) => return (name.to_string(), arguments),
+        }
+    }
+}
+
+/// Whether `attrs` hold a `#[derive(...)]` of `derive`.
+pub(crate) fn derives(attrs: &[Attribute], derive: &str) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<syn::Path, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .any(|path| path.segments.last().is_some_and(|s| s.ident == derive))
+}
+
+/// Lists the items of a file, going through the inline modules.
+pub(crate) fn items_of(items: &[Item]) -> Vec<&Item> {
+    let mut out = vec![];
+    for item in items {
+        out.push(item);
+        if let Item::Mod(module) = item {
+            if let Some((_, content)) = &module.content {
+                out.extend(items_of(content));
+            }
+        }
+    }
+    out
+}
+
+/// Parses the `#[derive(Accounts)]` structs of a file.
+///
+/// # Arguments
+///
+/// * `file` - The syntax tree of the file.
+/// * `source_file` - The path of the file, for the positions of the fields.
+///
+/// # Returns
+///
+/// The accounts structs, in the order of the file. The fields whose `#[account(...)]` can't be
+/// parsed are kept, flagged `unparsed`.
+pub fn accounts_structs(file: &syn::File, source_file: &str) -> Vec<AccountsStruct> {
+    let mut structs = vec![];
+    for item in items_of(&file.items) {
+        let Item::Struct(item) = item else {
+            continue;
+        };
+        if !derives(&item.attrs, "Accounts") {
+            continue;
+        }
+        let instruction_args = item
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("instruction"))
+            .filter_map(|attr| {
+                attr.parse_args_with(Punctuated::<InstructionArg, Token![,]>::parse_terminated)
+                    .ok()
+            })
+            .flatten()
+            .map(|arg| (arg.name, arg.ty))
+            .collect();
+
+        let mut fields = vec![];
+        for field in &item.fields {
+            let Some(ident) = &field.ident else {
+                continue;
+            };
+            let mut args = vec![];
+            let mut unparsed = false;
+            for attr in field
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("account"))
+            {
+                // a bare `#[account]` has no arguments
+                if matches!(attr.meta, syn::Meta::Path(_)) {
+                    continue;
+                }
+                match attr.parse_args_with(Punctuated::<AccountArg, Token![,]>::parse_terminated) {
+                    Ok(parsed) => args.extend(parsed),
+                    Err(_) => unparsed = true,
+                }
+            }
+            fields.push(AccountsField {
+                name: ident.to_string(),
+                ty: field.ty.clone(),
+                args,
+                unparsed,
+                position: field_position(ident.span(), source_file),
+            });
+        }
+        structs.push(AccountsStruct {
+            name: item.ident.to_string(),
+            instruction_args,
+            fields,
+        });
+    }
+    structs
+}
+
+/// Adds the arguments of the `#[account(...)]` attributes to the JSON of the fields of the
+/// `#[derive(Accounts)]` structs of a file, as a `constraints` list of
+/// [`AccountArg::to_json`] entries, so that the rules don't have to split the attribute tokens.
+///
+/// The fields whose attributes can't be parsed are left without `constraints`.
+pub fn enrich_json_with_constraints(ast_json: &mut Value, file: &syn::File) {
+    let structs: HashMap<String, AccountsStruct> = accounts_structs(file, "")
+        .into_iter()
+        .map(|accounts_struct| (accounts_struct.name.clone(), accounts_struct))
+        .collect();
+    if !structs.is_empty() {
+        add_constraints(ast_json, &structs);
+    }
+}
+
+fn add_constraints(node: &mut Value, structs: &HashMap<String, AccountsStruct>) {
+    match node {
+        Value::Object(map) => {
+            if let Some(item) = map.get_mut("struct") {
+                let accounts_struct = item
+                    .get("ident")
+                    .and_then(Value::as_str)
+                    .and_then(|name| structs.get(name));
+                let fields = item
+                    .pointer_mut("/fields/named")
+                    .and_then(Value::as_array_mut);
+                if let (Some(accounts_struct), Some(fields)) = (accounts_struct, fields) {
+                    for field in fields {
+                        let parsed = field
+                            .get("ident")
+                            .and_then(Value::as_str)
+                            .and_then(|name| accounts_struct.field(name))
+                            .filter(|parsed| !parsed.unparsed);
+                        if let Some(parsed) = parsed {
+                            field["constraints"] =
+                                parsed.args.iter().map(AccountArg::to_json).collect();
+                        }
+                    }
+                }
+            }
+            for value in map.values_mut() {
+                add_constraints(value, structs);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                add_constraints(item, structs);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Builds the position of a field name. The columns of the spans are counted in characters.
+fn field_position(span: Span, source_file: &str) -> SourcePosition {
+    SourcePosition::from_span(&span, source_file.to_string(), &[], ColumnEncoding::Chars)
+}
+
+/// Returns the value of a byte string or string literal, e.g. `b"vault"`.
+#[cfg(any(feature = "cli", feature = "wasm"))]
+pub fn literal_bytes(expr: &Expr) -> Option<Vec<u8>> {
+    match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::ByteStr(bytes) => Some(bytes.value()),
+            Lit::Str(string) => Some(string.value().into_bytes()),
+            _ => None,
+        },
+        Expr::Reference(reference) => literal_bytes(&reference.expr),
+        Expr::Paren(paren) => literal_bytes(&paren.expr),
+        _ => None,
+    }
+}
+
+/// Collects the constants of a file holding a byte string or a string, e.g.
+/// `pub const VAULT_SEED: &[u8] = b"vault";`, so that the seeds using them can be compared.
+///
+/// The associated constants are listed under `Type::NAME`.
+#[cfg(any(feature = "cli", feature = "wasm"))]
+pub fn byte_constants(file: &syn::File) -> HashMap<String, Vec<u8>> {
+    let mut constants = HashMap::new();
+    for item in items_of(&file.items) {
+        match item {
+            Item::Const(item) => {
+                if let Some(bytes) = literal_bytes(&item.expr) {
+                    constants.insert(item.ident.to_string(), bytes);
+                }
+            }
+            Item::Impl(item) => {
+                let Type::Path(self_ty) = &*item.self_ty else {
+                    continue;
+                };
+                let Some(type_name) = self_ty.path.segments.last() else {
+                    continue;
+                };
+                for impl_item in &item.items {
+                    let syn::ImplItem::Const(constant) = impl_item else {
+                        continue;
+                    };
+                    if let Some(bytes) = literal_bytes(&constant.expr) {
+                        constants.insert(format!("{}::{}", type_name.ident, constant.ident), bytes);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    constants
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accounts_structs() {
+        let file = syn::parse_file(
+            r#"
+            pub const VAULT_SEED: &[u8] = b"vault";
+
+            mod instructions {
+                #[derive(Accounts)]
+                #[instruction(index: u64)]
+                pub struct Withdraw<'info> {
+                    #[account(
+                        mut,
+                        seeds = [VAULT_SEED, owner.key().as_ref()],
+                        bump = vault.bump,
+                        has_one = owner @ ErrorCode::Unauthorized,
+                        constraint = vault.amount > 0 @ ErrorCode::Empty,
+                    )]
+                    pub vault: Box<Account<'info, crate::state::Vault>>,
+                    #[account(token::mint = mint, token::authority = owner)]
+                    pub tokens: Account<'info, TokenAccount>,
+                    pub owner: Signer<'info>,
+                    #[account(seeds = [b"a" b"b"], bump)]
+                    pub broken: UncheckedAccount<'info>,
+                }
+            }
+            "#,
+        )
+        .unwrap();
+
+        let structs = accounts_structs(&file, "lib.rs");
+        assert_eq!(structs.len(), 1);
+        let withdraw = &structs[0];
+        assert_eq!(withdraw.name, "Withdraw");
+        assert_eq!(withdraw.instruction_args[0].0, "index");
+
+        let vault = withdraw.field("vault").unwrap();
+        let keys: Vec<&str> = vault.args.iter().map(|arg| arg.key.as_str()).collect();
+        assert_eq!(keys, vec!["mut", "seeds", "bump", "has_one", "constraint"]);
+        assert!(matches!(
+            vault.arg("seeds").unwrap().value,
+            Some(Expr::Array(_))
+        ));
+        assert_eq!(vault.account_type(), "Account");
+        assert_eq!(vault.state_type().as_deref(), Some("Vault"));
+        assert_eq!(vault.position.start_line, 15);
+
+        let tokens = withdraw.field("tokens").unwrap();
+        assert!(tokens.has_arg("token::authority"));
+        assert_eq!(withdraw.field("owner").unwrap().account_type(), "Signer");
+        assert!(withdraw.field("broken").unwrap().unparsed);
+
+        assert_eq!(byte_constants(&file)["VAULT_SEED"], b"vault");
+    }
+
+    #[test]
+    fn test_json_constraints() {
+        let file = syn::parse_file(
+            r#"
+            #[derive(Accounts)]
+            pub struct Close<'info> {
+                #[account(mut, close = owner, constraint = vault.owner == owner.key() @ Error::Owner)]
+                pub vault: Account<'info, Vault>,
+                #[account(token::mint = mint)]
+                pub tokens: Account<'info, TokenAccount>,
+                pub owner: Signer<'info>,
+                #[account(seeds = [b"a" b"b"], bump)]
+                pub broken: UncheckedAccount<'info>,
+            }
+            "#,
+        )
+        .unwrap();
+        let mut ast_json: Value = serde_json::from_str(&syn_serde::json::to_string(&file)).unwrap();
+        enrich_json_with_constraints(&mut ast_json, &file);
+
+        let fields = &ast_json["items"][0]["struct"]["fields"]["named"];
+        assert_eq!(
+            fields[0]["constraints"],
+            json!([
+                { "key": "mut", "value": null, "idents": [] },
+                { "key": "close", "value": "owner", "idents": ["owner"] },
+                {
+                    "key": "constraint",
+                    "value": "vault.owner == owner.key()",
+                    "idents": ["vault", "owner", "owner", "key"],
+                },
+            ])
+        );
+        assert_eq!(fields[1]["constraints"][0]["key"], "token::mint");
+        assert_eq!(fields[2]["constraints"], json!([]));
+        assert!(fields[3].get("constraints").is_none());
+    }
+}
@@ -0,0 +1,619 @@
+//! Evaluation of the `#[cfg(...)]` attributes against the features and options of the deployed
+//! program.
+//!
+//! The syntax trees are parsed from the sources as written, with the code of every
+//! configuration. Given the enabled features and `--cfg` options, the items, fields, variants,
+//! statements and match arms whose `#[cfg(...)]` don't hold are removed, the
+//! `#[cfg_attr(...)]` are expanded, and the files of the disabled modules are dropped, so that
+//! the rules only see the code compiled into the program.
+
+use crate::parsers::syn_ast::{
+    ast_to_json_with_positions, enrich_ast_with_source_lines, ColumnEncoding,
+};
+use crate::state::sast_state::SynAstMap;
+use anyhow::{anyhow, Result};
+use log::{error, info};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use syn::parse::ParseStream;
+use syn::punctuated::Punctuated;
+use syn::visit_mut::{self, VisitMut};
+use syn::{Attribute, Expr, ExprLit, Item, Lit, Meta, Token};
+
+/// The options set when compiling for the Solana target, e.g. `target_os = "solana"`.
+pub const TARGET_CFG: [(&str, &str); 4] = [
+    ("target_os", "solana"),
+    ("target_arch", "sbf"),
+    ("target_endian", "little"),
+    ("target_pointer_width", "64"),
+];
+
+/// The configuration options of the deployed program: `feature = "..."` for the enabled
+/// features, the options of the Solana target, and the `--cfg` options.
+#[derive(Debug, Clone, Default)]
+pub struct CfgSet {
+    options: BTreeSet<(String, Option<String>)>,
+}
+
+impl CfgSet {
+    /// Builds the configuration from the `--features` and `--cfg` options, a `--cfg` being
+    /// `name` or `name="value"`.
+    ///
+    /// # Returns
+    ///
+    /// `None` if neither is given, the code of every configuration being scanned, or an error
+    /// if a `--cfg` is invalid.
+    pub fn from_options(features: &[String], cfgs: &[String]) -> Result<Option<Self>> {
+        if features.is_empty() && cfgs.is_empty() {
+            return Ok(None);
+        }
+        let mut options: BTreeSet<_> = TARGET_CFG
+            .iter()
+            .map(|(name, value)| (name.to_string(), Some(value.to_string())))
+            .collect();
+        // like cargo, the features may also be separated by spaces
+        options.extend(
+            features
+                .iter()
+                .flat_map(|features| features.split_whitespace())
+                .map(|feature| ("feature".to_string(), Some(feature.to_string()))),
+        );
+        for cfg in cfgs {
+            let (name, value) = match cfg.split_once('=') {
+                Some((name, value)) => {
+                    let value = value.trim();
+                    let value = value
+                        .strip_prefix('"')
+                        .and_then(|value| value.strip_suffix('"'))
+                        .unwrap_or(value);
+                    (name.trim(), Some(value.to_string()))
+                }
+                None => (cfg.trim(), None),
+            };
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(anyhow!(
+                    "Invalid cfg '{}', expected `name` or `name=\"value\"`",
+                    cfg
+                ));
+            }
+            options.insert((name.to_string(), value));
+        }
+        Ok(Some(Self { options }))
+    }
+
+    /// Evaluates a configuration predicate, e.g. `all(feature = "devnet", not(test))`.
+    ///
+    /// # Returns
+    ///
+    /// Whether the predicate holds, or `None` if it isn't a valid predicate.
+    pub fn evaluate(&self, predicate: &Meta) -> Option<bool> {
+        match predicate {
+            Meta::Path(path) => Some(
+                self.options
+                    .contains(&(path.get_ident()?.to_string(), None)),
+            ),
+            Meta::NameValue(name_value) => {
+                let Expr::Lit(ExprLit {
+                    lit: Lit::Str(value),
+                    ..
+                }) = &name_value.value
+                else {
+                    return None;
+                };
+                let name = name_value.path.get_ident()?.to_string();
+                Some(self.options.contains(&(name, Some(value.value()))))
+            }
+            Meta::List(list) => {
+                let operands = list
+                    .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                    .ok()?;
+                let values = operands
+                    .iter()
+                    .map(|operand| self.evaluate(operand))
+                    .collect::<Option<Vec<bool>>>()?;
+                match list.path.get_ident()?.to_string().as_str() {
+                    "all" => Some(values.iter().all(|value| *value)),
+                    "any" => Some(values.iter().any(|value| *value)),
+                    "not" if values.len() == 1 => Some(!values[0]),
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+/// Removes from the syntax trees the code disabled by the configuration `cfg`, along with the
+/// files of the disabled modules.
+///
+/// The positions and the JSON of the modified syntax trees are computed again, from their
+/// file.
+///
+/// # Returns
+///
+/// The number of files removed.
+pub fn filter_syn_ast_map(
+    ast_map: &mut SynAstMap,
+    cfg: &CfgSet,
+    column_encoding: ColumnEncoding,
+) -> usize {
+    let mut excluded_files = vec![];
+    let mut excluded_dirs = vec![];
+    for (filename, syn_ast) in ast_map.iter_mut() {
+        let path = Path::new(filename);
+        let mut stripper = CfgStripper {
+            cfg,
+            modules: vec![],
+            disabled_modules: vec![],
+            changed: false,
+        };
+        // `#![cfg(...)]` disables the whole file
+        if !stripper.keeps(&mut syn_ast.ast.attrs) {
+            excluded_files.push(path.to_path_buf());
+            excluded_dirs.push(child_modules_dir(path));
+            continue;
+        }
+        stripper.visit_file_mut(&mut syn_ast.ast);
+        for module in &stripper.disabled_modules {
+            let (file, dir) = module.locations(path);
+            excluded_files.push(file);
+            excluded_dirs.extend(dir);
+        }
+        if stripper.changed {
+            match fs::read_to_string(path) {
+                Ok(source) => {
+                    syn_ast.ast_positions =
+                        enrich_ast_with_source_lines(&syn_ast.ast, path, &source, column_encoding);
+                    let ast_json = ast_to_json_with_positions(&syn_ast.ast, &syn_ast.ast_positions);
+                    if let Err(e) = syn_ast.set_ast_json(ast_json) {
+                        error!("{:#}", e);
+                    }
+                }
+                Err(e) => error!("Failed to read Rust file {:?}: {}", path, e),
+            }
+        }
+    }
+
+    let files = ast_map.len();
+    ast_map.retain(|filename, _| {
+        let path = Path::new(filename);
+        !excluded_files.iter().any(|file| file == path)
+            && !excluded_dirs.iter().any(|dir| path.starts_with(dir))
+    });
+    let removed = files - ast_map.len();
+    if removed > 0 {
+        info!(
+            "{} file(s) of disabled modules excluded by the cfg options",
+            removed
+        );
+    }
+    removed
+}
+
+/// Returns the directory holding the files of the modules declared in the file `path`:
+/// its directory for a `mod.rs`, `lib.rs` or `main.rs`, `<dir>/<name>/` for `<dir>/<name>.rs`.
+fn child_modules_dir(path: &Path) -> PathBuf {
+    let dir = path.parent().unwrap_or(Path::new(""));
+    match path.file_stem().and_then(|stem| stem.to_str()) {
+        Some("mod" | "lib" | "main") | None => dir.to_path_buf(),
+        Some(stem) => dir.join(stem),
+    }
+}
+
+/// A module declared without body (`mod name;`) whose `#[cfg(...)]` doesn't hold.
+struct DisabledModule {
+    /// The inline modules enclosing the declaration.
+    parents: Vec<String>,
+    name: String,
+    /// The file of its `#[path = "..."]` attribute.
+    path: Option<String>,
+}
+
+impl DisabledModule {
+    /// Returns the file of the module declared in the file `declared_in`, and the directory of
+    /// its child modules, if any.
+    fn locations(&self, declared_in: &Path) -> (PathBuf, Option<PathBuf>) {
+        let mut dir = child_modules_dir(declared_in);
+        dir.extend(&self.parents);
+        match &self.path {
+            Some(path) => {
+                let file = match self.parents.is_empty() {
+                    true => declared_in.parent().unwrap_or(Path::new("")).join(path),
+                    false => dir.join(path),
+                };
+                let children = (file.file_name().and_then(|name| name.to_str()) == Some("mod.rs"))
+                    .then(|| file.parent().map(Path::to_path_buf))
+                    .flatten();
+                (file, children)
+            }
+            None => (
+                dir.join(format!("{}.rs", self.name)),
+                Some(dir.join(&self.name)),
+            ),
+        }
+    }
+}
+
+/// A `syn::visit_mut::VisitMut` implementation removing the nodes whose `#[cfg(...)]` don't
+/// hold, and expanding the `#[cfg_attr(...)]` of the nodes which may have a `#[cfg(...)]`.
+struct CfgStripper<'a> {
+    cfg: &'a CfgSet,
+    /// The inline modules enclosing the visited node.
+    modules: Vec<String>,
+    disabled_modules: Vec<DisabledModule>,
+    /// Whether the syntax tree was modified.
+    changed: bool,
+}
+
+impl CfgStripper<'_> {
+    /// Expands the `#[cfg_attr(...)]` of `attrs`, then returns whether their `#[cfg(...)]` hold.
+    ///
+    /// The invalid predicates are considered to hold, their node being kept.
+    fn keeps(&mut self, attrs: &mut Vec<Attribute>) -> bool {
+        if attrs.iter().any(|attr| attr.path().is_ident("cfg_attr")) {
+            *attrs = self.expand(std::mem::take(attrs));
+        }
+        attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("cfg"))
+            .all(|attr| {
+                attr.parse_args::<Meta>()
+                    .ok()
+                    .and_then(|predicate| self.cfg.evaluate(&predicate))
+                    .unwrap_or(true)
+            })
+    }
+
+    /// Replaces the `#[cfg_attr(predicate, attrs...)]` whose predicate holds by their
+    /// attributes, and removes the others.
+    fn expand(&mut self, attrs: Vec<Attribute>) -> Vec<Attribute> {
+        let mut expanded = vec![];
+        for attr in attrs {
+            if !attr.path().is_ident("cfg_attr") {
+                expanded.push(attr);
+                continue;
+            }
+            let parsed = attr.parse_args_with(|input: ParseStream| {
+                let predicate: Meta = input.parse()?;
+                input.parse::<Token![,]>()?;
+                let metas = Punctuated::<Meta, Token![,]>::parse_terminated(input)?;
+                Ok((predicate, metas))
+            });
+            let Some((enabled, metas)) = parsed
+                .ok()
+                .and_then(|(predicate, metas)| Some((self.cfg.evaluate(&predicate)?, metas)))
+            else {
+                expanded.push(attr);
+                continue;
+            };
+            self.changed = true;
+            if enabled {
+                let attrs = metas
+                    .into_iter()
+                    .map(|meta| Attribute {
+                        pound_token: attr.pound_token,
+                        style: attr.style,
+                        bracket_token: attr.bracket_token,
+                        meta,
+                    })
+                    .collect();
+                expanded.extend(self.expand(attrs));
+            }
+        }
+        expanded
+    }
+
+    /// Removes the nodes of `nodes` whose `#[cfg(...)]` don't hold.
+    fn retain<T: CfgNode>(&mut self, nodes: &mut Vec<T>) {
+        nodes.retain_mut(|node| {
+            let kept = node.attrs_mut().is_none_or(|attrs| self.keeps(attrs));
+            self.changed |= !kept;
+            kept
+        });
+    }
+
+    /// Removes the nodes of `nodes` whose `#[cfg(...)]` don't hold.
+    fn retain_punctuated<T: CfgNode, P: Default>(&mut self, nodes: &mut Punctuated<T, P>) {
+        let mut kept = Punctuated::new();
+        for mut node in std::mem::take(nodes) {
+            if node.attrs_mut().is_none_or(|attrs| self.keeps(attrs)) {
+                kept.push(node);
+            } else {
+                self.changed = true;
+            }
+        }
+        *nodes = kept;
+    }
+
+    /// Removes the items of `items` whose `#[cfg(...)]` don't hold, recording the disabled
+    /// modules declared without body.
+    fn retain_items(&mut self, items: &mut Vec<Item>) {
+        items.retain_mut(|item| {
+            if item.attrs_mut().is_none_or(|attrs| self.keeps(attrs)) {
+                return true;
+            }
+            self.changed = true;
+            if let Item::Mod(module) = item {
+                if module.content.is_none() {
+                    self.disabled_modules.push(DisabledModule {
+                        parents: self.modules.clone(),
+                        name: module.ident.to_string(),
+                        path: module.attrs.iter().find_map(|attr| match &attr.meta {
+                            Meta::NameValue(name_value) if name_value.path.is_ident("path") => {
+                                match &name_value.value {
+                                    Expr::Lit(ExprLit {
+                                        lit: Lit::Str(path),
+                                        ..
+                                    }) => Some(path.value()),
+                                    _ => None,
+                                }
+                            }
+                            _ => None,
+                        }),
+                    });
+                }
+            }
+            false
+        });
+    }
+}
+
+impl VisitMut for CfgStripper<'_> {
+    fn visit_file_mut(&mut self, node: &mut syn::File) {
+        self.retain_items(&mut node.items);
+        visit_mut::visit_file_mut(self, node);
+    }
+
+    fn visit_item_mod_mut(&mut self, node: &mut syn::ItemMod) {
+        let Some((_, items)) = &mut node.content else {
+            return;
+        };
+        self.retain_items(items);
+        self.modules.push(node.ident.to_string());
+        visit_mut::visit_item_mod_mut(self, node);
+        self.modules.pop();
+    }
+
+    fn visit_item_impl_mut(&mut self, node: &mut syn::ItemImpl) {
+        self.retain(&mut node.items);
+        visit_mut::visit_item_impl_mut(self, node);
+    }
+
+    fn visit_item_trait_mut(&mut self, node: &mut syn::ItemTrait) {
+        self.retain(&mut node.items);
+        visit_mut::visit_item_trait_mut(self, node);
+    }
+
+    fn visit_item_foreign_mod_mut(&mut self, node: &mut syn::ItemForeignMod) {
+        self.retain(&mut node.items);
+        visit_mut::visit_item_foreign_mod_mut(self, node);
+    }
+
+    fn visit_item_enum_mut(&mut self, node: &mut syn::ItemEnum) {
+        self.retain_punctuated(&mut node.variants);
+        visit_mut::visit_item_enum_mut(self, node);
+    }
+
+    fn visit_fields_named_mut(&mut self, node: &mut syn::FieldsNamed) {
+        self.retain_punctuated(&mut node.named);
+        visit_mut::visit_fields_named_mut(self, node);
+    }
+
+    fn visit_fields_unnamed_mut(&mut self, node: &mut syn::FieldsUnnamed) {
+        self.retain_punctuated(&mut node.unnamed);
+        visit_mut::visit_fields_unnamed_mut(self, node);
+    }
+
+    fn visit_block_mut(&mut self, node: &mut syn::Block) {
+        self.retain(&mut node.stmts);
+        visit_mut::visit_block_mut(self, node);
+    }
+
+    fn visit_expr_match_mut(&mut self, node: &mut syn::ExprMatch) {
+        self.retain(&mut node.arms);
+        visit_mut::visit_expr_match_mut(self, node);
+    }
+
+    fn visit_expr_struct_mut(&mut self, node: &mut syn::ExprStruct) {
+        self.retain_punctuated(&mut node.fields);
+        visit_mut::visit_expr_struct_mut(self, node);
+    }
+}
+
+/// A node which may be disabled by a `#[cfg(...)]`.
+trait CfgNode {
+    /// Returns the attributes of the node, if it has some.
+    fn attrs_mut(&mut self) -> Option<&mut Vec<Attribute>>;
+}
+
+impl CfgNode for Item {
+    fn attrs_mut(&mut self) -> Option<&mut Vec<Attribute>> {
+        match self {
+            Item::Const(item) => Some(&mut item.attrs),
+            Item::Enum(item) => Some(&mut item.attrs),
+            Item::ExternCrate(item) => Some(&mut item.attrs),
+            Item::Fn(item) => Some(&mut item.attrs),
+            Item::ForeignMod(item) => Some(&mut item.attrs),
+            Item::Impl(item) => Some(&mut item.attrs),
+            Item::Macro(item) => Some(&mut item.attrs),
+            Item::Mod(item) => Some(&mut item.attrs),
+            Item::Static(item) => Some(&mut item.attrs),
+            Item::Struct(item) => Some(&mut item.attrs),
+            Item::Trait(item) => Some(&mut item.attrs),
+            Item::TraitAlias(item) => Some(&mut item.attrs),
+            Item::Type(item) => Some(&mut item.attrs),
+            Item::Union(item) => Some(&mut item.attrs),
+            Item::Use(item) => Some(&mut item.attrs),
+            _ => None,
+        }
+    }
+}
+
+impl CfgNode for syn::ImplItem {
+    fn attrs_mut(&mut self) -> Option<&mut Vec<Attribute>> {
+        match self {
+            syn::ImplItem::Const(item) => Some(&mut item.attrs),
+            syn::ImplItem::Fn(item) => Some(&mut item.attrs),
+            syn::ImplItem::Type(item) => Some(&mut item.attrs),
+            syn::ImplItem::Macro(item) => Some(&mut item.attrs),
+            _ => None,
+        }
+    }
+}
+
+impl CfgNode for syn::TraitItem {
+    fn attrs_mut(&mut self) -> Option<&mut Vec<Attribute>> {
+        match self {
+            syn::TraitItem::Const(item) => Some(&mut item.attrs),
+            syn::TraitItem::Fn(item) => Some(&mut item.attrs),
+            syn::TraitItem::Type(item) => Some(&mut item.attrs),
+            syn::TraitItem::Macro(item) => Some(&mut item.attrs),
+            _ => None,
+        }
+    }
+}
+
+impl CfgNode for syn::ForeignItem {
+    fn attrs_mut(&mut self) -> Option<&mut Vec<Attribute>> {
+        match self {
+            syn::ForeignItem::Fn(item) => Some(&mut item.attrs),
+            syn::ForeignItem::Static(item) => Some(&mut item.attrs),
+            syn::ForeignItem::Type(item) => Some(&mut item.attrs),
+            syn::ForeignItem::Macro(item) => Some(&mut item.attrs),
+            _ => None,
+        }
+    }
+}
+
+impl CfgNode for syn::Stmt {
+    fn attrs_mut(&mut self) -> Option<&mut Vec<Attribute>> {
+        match self {
+            syn::Stmt::Local(local) => Some(&mut local.attrs),
+            syn::Stmt::Item(item) => item.attrs_mut(),
+            syn::Stmt::Macro(stmt) => Some(&mut stmt.attrs),
+            // the expressions which are usually statements
+            syn::Stmt::Expr(expr, _) => match expr {
+                Expr::Assign(expr) => Some(&mut expr.attrs),
+                Expr::Block(expr) => Some(&mut expr.attrs),
+                Expr::Call(expr) => Some(&mut expr.attrs),
+                Expr::ForLoop(expr) => Some(&mut expr.attrs),
+                Expr::If(expr) => Some(&mut expr.attrs),
+                Expr::Loop(expr) => Some(&mut expr.attrs),
+                Expr::Macro(expr) => Some(&mut expr.attrs),
+                Expr::Match(expr) => Some(&mut expr.attrs),
+                Expr::MethodCall(expr) => Some(&mut expr.attrs),
+                Expr::Unsafe(expr) => Some(&mut expr.attrs),
+                Expr::While(expr) => Some(&mut expr.attrs),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl CfgNode for syn::Arm {
+    fn attrs_mut(&mut self) -> Option<&mut Vec<Attribute>> {
+        Some(&mut self.attrs)
+    }
+}
+
+impl CfgNode for syn::Field {
+    fn attrs_mut(&mut self) -> Option<&mut Vec<Attribute>> {
+        Some(&mut self.attrs)
+    }
+}
+
+impl CfgNode for syn::Variant {
+    fn attrs_mut(&mut self) -> Option<&mut Vec<Attribute>> {
+        Some(&mut self.attrs)
+    }
+}
+
+impl CfgNode for syn::FieldValue {
+    fn attrs_mut(&mut self) -> Option<&mut Vec<Attribute>> {
+        Some(&mut self.attrs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::syn_ast::get_syn_ast_recursive;
+
+    #[test]
+    fn test_filter_syn_ast_map() {
+        let root = std::env::temp_dir().join(format!("sol-azy-cfg-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for (file, content) in [
+            (
+                "src/lib.rs",
+                "#[cfg(feature = \"devnet\")]\n\
+                 mod devnet;\n\
+                 mod state;\n\
+                 #[cfg(test)]\n\
+                 mod tests { fn test_withdraw() {} }\n\
+                 #[cfg_attr(not(feature = \"no-log\"), derive(Debug))]\n\
+                 pub struct Vault {\n\
+                 \x20   pub amount: u64,\n\
+                 \x20   #[cfg(feature = \"devnet\")]\n\
+                 \x20   pub faucet: u64,\n\
+                 }\n\
+                 pub fn withdraw(vault: &mut Vault) {\n\
+                 \x20   #[cfg(not(target_os = \"solana\"))]\n\
+                 \x20   println!(\"withdraw\");\n\
+                 \x20   vault.amount = 0;\n\
+                 }\n",
+            ),
+            ("src/devnet.rs", "pub fn airdrop() {}\n"),
+            ("src/devnet/faucet.rs", "pub fn drip() {}\n"),
+            (
+                "src/state.rs",
+                "#![cfg(all(feature = \"v2\", any(test, feature = \"devnet\")))]\n",
+            ),
+        ] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        let src = root.join("src");
+        let lib = src.join("lib.rs").to_string_lossy().to_string();
+
+        // without options, nothing is filtered
+        assert!(CfgSet::from_options(&[], &[]).unwrap().is_none());
+        assert!(CfgSet::from_options(&[], &["target os".to_string()]).is_err());
+
+        let mut ast_map =
+            get_syn_ast_recursive(&src.to_string_lossy(), ColumnEncoding::Chars).unwrap();
+        let cfg = CfgSet::from_options(&["v2".to_string()], &[])
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            filter_syn_ast_map(&mut ast_map, &cfg, ColumnEncoding::Chars),
+            3
+        );
+        let mut files: Vec<&String> = ast_map.keys().collect();
+        files.sort();
+        assert_eq!(files, [&lib]);
+
+        let json = ast_map[&lib].ast_json.to_string();
+        assert!(json.contains("\"state\""));
+        assert!(!json.contains("devnet") && !json.contains("\"tests\""));
+        assert!(json.contains("\"derive\"") && !json.contains("cfg_attr"));
+        assert!(!json.contains("\"faucet\"") && !json.contains("\"println\""));
+        assert!(json.contains("\"withdraw\"") && json.contains("\"amount\""));
+
+        // with the features of the devnet build, all the modules are kept
+        let mut ast_map =
+            get_syn_ast_recursive(&src.to_string_lossy(), ColumnEncoding::Chars).unwrap();
+        let cfg = CfgSet::from_options(&["v2 devnet".to_string()], &["test".to_string()])
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            filter_syn_ast_map(&mut ast_map, &cfg, ColumnEncoding::Chars),
+            0
+        );
+        let json = ast_map[&lib].ast_json.to_string();
+        assert!(json.contains("\"faucet\"") && json.contains("\"test_withdraw\""));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,681 @@
+//! Structured parsing of the programs written without Anchor: with Pinocchio, Steel or the
+//! bare `solana_program` crate.
+//!
+//! These programs have no `#[program]` module nor `#[derive(Accounts)]` structs to read the
+//! instructions and their account checks from. Instead:
+//!
+//! - the entrypoint is declared by a macro: `entrypoint!(process_instruction)`,
+//!   `program_entrypoint!(...)` or `lazy_program_entrypoint!(...)`,
+//! - the account types are plain `#[repr(C)]` or `Pod` structs, declared with
+//!   `account!(Discriminator, Type)` in Steel,
+//! - the accounts of an instruction are destructured from the account slice,
+//!   `let [owner, vault, ..] = accounts else { ... }`, and checked by hand:
+//!   `owner.is_signer()`, `vault.is_owned_by(&ID)`, or Steel's `vault.has_owner(&ID)?`,
+//! - Pinocchio borrows the data and lamports of the accounts without tracking with its
+//!   `_unchecked` methods: `vault.borrow_mut_data_unchecked()`.
+//!
+//! The framework of a file is told by the crates it uses. The items above are added to the
+//! JSON syntax tree given to the rules, as its `framework` (see [`FrameworkItems::to_json`]),
+//! and the account slices to the JSON of their functions as well (see
+//! [`enrich_json_with_account_slices`]).
+
+use proc_macro2::Span;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+use syn::{Attribute, Expr, Item, Pat, Token};
+
+use crate::parsers::anchor_accounts::{derives, items_of};
+
+/// The framework a file is written with, by priority: a Steel program also uses
+/// `solana_program`, and any program may use Anchor's crates for their types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Framework {
+    Anchor,
+    Steel,
+    Pinocchio,
+    SolanaProgram,
+}
+
+impl Framework {
+    /// The framework of a crate used by a file, e.g. `pinocchio_system`.
+    fn of_crate(name: &str) -> Option<Self> {
+        match name {
+            "anchor_lang" | "anchor_spl" => Some(Self::Anchor),
+            "steel" => Some(Self::Steel),
+            "pinocchio" => Some(Self::Pinocchio),
+            name if name.starts_with("pinocchio_") => Some(Self::Pinocchio),
+            "solana_program" => Some(Self::SolanaProgram),
+            _ => None,
+        }
+    }
+
+    /// The name given to the rules: `anchor`, `steel`, `pinocchio` or `solana_program`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Anchor => "anchor",
+            Self::Steel => "steel",
+            Self::Pinocchio => "pinocchio",
+            Self::SolanaProgram => "solana_program",
+        }
+    }
+}
+
+/// The macros declaring the entrypoint of a program.
+const ENTRYPOINT_MACROS: &[&str] = &[
+    "entrypoint",
+    "program_entrypoint",
+    "lazy_program_entrypoint",
+    "lazy_entrypoint",
+];
+
+/// The derives of the account types read in place from the account data.
+const ACCOUNT_DERIVES: &[&str] = &["Pod", "Zeroable", "ShankAccount"];
+
+/// The methods of Pinocchio borrowing an account without tracking the borrow.
+const UNCHECKED_BORROWS: &[&str] = &[
+    "borrow_data_unchecked",
+    "borrow_mut_data_unchecked",
+    "borrow_lamports_unchecked",
+    "borrow_mut_lamports_unchecked",
+];
+
+/// The suffixes of the `#[repr(C)]` structs holding instruction data rather than an account.
+const INSTRUCTION_DATA_SUFFIXES: &[&str] = &["Data", "Args", "Params", "Instruction", "Ix"];
+
+/// An entrypoint macro, e.g. `program_entrypoint!(process_instruction)`.
+pub struct Entrypoint {
+    /// The function called by the entrypoint.
+    pub function: String,
+    /// The macro, without `!`.
+    pub macro_name: String,
+    pub span: Span,
+}
+
+/// An account type of the program.
+pub struct AccountType {
+    pub name: String,
+    /// The discriminator enum of a Steel account, e.g. `ExampleAccount` for
+    /// `account!(ExampleAccount, Counter)`.
+    pub discriminator: Option<String>,
+    /// The struct, or the `account!` macro if the struct is declared in another file.
+    pub span: Span,
+}
+
+/// The accounts of an instruction destructured from the account slice, e.g.
+/// `let [owner, vault, ..] = accounts else { ... }`.
+pub struct AccountSlice {
+    /// The function destructuring the slice.
+    pub function: String,
+    /// The number of functions of the same name before this one in the file, e.g. the `try_from`
+    /// of several `TryFrom` impls.
+    pub function_index: usize,
+    /// The bound accounts, in order, `_` for the ignored ones.
+    pub accounts: Vec<String>,
+    /// Whether the pattern accepts more accounts (`..`).
+    pub rest: bool,
+    /// The `let` statement.
+    pub span: Span,
+}
+
+/// An untracked borrow of an account, e.g. `vault.borrow_mut_data_unchecked()`.
+pub struct UncheckedBorrow {
+    /// The function borrowing the account.
+    pub function: String,
+    /// The borrowed account, as written, e.g. `vault` or `accounts[1]`.
+    pub account: String,
+    pub method: String,
+    /// The method call.
+    pub span: Span,
+}
+
+/// The framework of a file and its items.
+pub struct FrameworkItems {
+    pub framework: Option<Framework>,
+    pub entrypoints: Vec<Entrypoint>,
+    pub accounts: Vec<AccountType>,
+    pub account_slices: Vec<AccountSlice>,
+    pub unchecked_borrows: Vec<UncheckedBorrow>,
+}
+
+impl FrameworkItems {
+    /// Returns the items as the `framework` of the JSON syntax tree, or `null` if the file
+    /// uses no framework:
+    ///
+    /// ```json
+    /// {
+    ///   "name": "pinocchio",
+    ///   "entrypoints": [{"function": "process_instruction", "macro": "entrypoint", "position": ...}],
+    ///   "accounts": [{"name": "Vault", "discriminator": null, "position": ...}],
+    ///   "account_slices": [{"function": "deposit", "function_index": 0, "accounts": ["owner", "vault"], "rest": true, "position": ...}],
+    ///   "unchecked_borrows": [{"function": "load", "account": "vault", "method": "borrow_data_unchecked", "position": ...}]
+    /// }
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - Returns the JSON position of a span.
+    pub fn to_json(&self, position: impl Fn(&Span) -> Value) -> Value {
+        let Some(framework) = self.framework else {
+            return Value::Null;
+        };
+        json!({
+            "name": framework.name(),
+            "entrypoints": self
+                .entrypoints
+                .iter()
+                .map(|entrypoint| json!({
+                    "function": entrypoint.function,
+                    "macro": entrypoint.macro_name,
+                    "position": position(&entrypoint.span),
+                }))
+                .collect::<Vec<_>>(),
+            "accounts": self
+                .accounts
+                .iter()
+                .map(|account| json!({
+                    "name": account.name,
+                    "discriminator": account.discriminator,
+                    "position": position(&account.span),
+                }))
+                .collect::<Vec<_>>(),
+            "account_slices": self
+                .account_slices
+                .iter()
+                .map(|slice| json!({
+                    "function": slice.function,
+                    "function_index": slice.function_index,
+                    "accounts": slice.accounts,
+                    "rest": slice.rest,
+                    "position": position(&slice.span),
+                }))
+                .collect::<Vec<_>>(),
+            "unchecked_borrows": self
+                .unchecked_borrows
+                .iter()
+                .map(|borrow| json!({
+                    "function": borrow.function,
+                    "account": borrow.account,
+                    "method": borrow.method,
+                    "position": position(&borrow.span),
+                }))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// Finds the crates used by a file, through its `use` items and its paths.
+#[derive(Default)]
+struct CrateCollector {
+    framework: Option<Framework>,
+}
+
+impl CrateCollector {
+    fn add(&mut self, name: &str) {
+        if let Some(framework) = Framework::of_crate(name) {
+            self.framework = Some(
+                self.framework
+                    .map_or(framework, |found| found.min(framework)),
+            );
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for CrateCollector {
+    fn visit_use_tree(&mut self, node: &'ast syn::UseTree) {
+        match node {
+            syn::UseTree::Path(path) => self.add(&path.ident.to_string()),
+            syn::UseTree::Name(name) => self.add(&name.ident.to_string()),
+            syn::UseTree::Rename(rename) => self.add(&rename.ident.to_string()),
+            syn::UseTree::Group(group) => {
+                for tree in &group.items {
+                    self.visit_use_tree(tree);
+                }
+            }
+            syn::UseTree::Glob(_) => {}
+        }
+    }
+
+    fn visit_path(&mut self, node: &'ast syn::Path) {
+        if let Some(segment) = node.segments.first() {
+            self.add(&segment.ident.to_string());
+        }
+        syn::visit::visit_path(self, node);
+    }
+}
+
+/// Finds the account slices destructured by the functions and their unchecked borrows.
+#[derive(Default)]
+struct FunctionCollector {
+    /// The name of the function being visited, with its index (see
+    /// [`AccountSlice::function_index`]).
+    function: Option<(String, usize)>,
+    /// The number of functions visited, by name.
+    occurrences: HashMap<String, usize>,
+    slices: Vec<AccountSlice>,
+    borrows: Vec<UncheckedBorrow>,
+}
+
+impl FunctionCollector {
+    fn visit_function(&mut self, ident: &syn::Ident, visit: impl FnOnce(&mut Self)) {
+        let name = ident.to_string();
+        let occurrences = self.occurrences.entry(name.clone()).or_default();
+        let index = *occurrences;
+        *occurrences += 1;
+        let outer = self.function.replace((name, index));
+        visit(self);
+        self.function = outer;
+    }
+}
+
+impl<'ast> Visit<'ast> for FunctionCollector {
+    fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+        self.visit_function(&node.sig.ident, |collector| {
+            syn::visit::visit_item_fn(collector, node)
+        });
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast syn::ImplItemFn) {
+        self.visit_function(&node.sig.ident, |collector| {
+            syn::visit::visit_impl_item_fn(collector, node)
+        });
+    }
+
+    fn visit_trait_item_fn(&mut self, node: &'ast syn::TraitItemFn) {
+        self.visit_function(&node.sig.ident, |collector| {
+            syn::visit::visit_trait_item_fn(collector, node)
+        });
+    }
+
+    fn visit_foreign_item_fn(&mut self, node: &'ast syn::ForeignItemFn) {
+        self.visit_function(&node.sig.ident, |collector| {
+            syn::visit::visit_foreign_item_fn(collector, node)
+        });
+    }
+
+    fn visit_local(&mut self, node: &'ast syn::Local) {
+        if let (Some((function, function_index)), Pat::Slice(slice), Some(init)) =
+            (&self.function, &node.pat, &node.init)
+        {
+            if is_variable(&init.expr) {
+                let mut accounts = vec![];
+                let mut rest = false;
+                for elem in &slice.elems {
+                    match elem {
+                        Pat::Ident(ident) => accounts.push(ident.ident.to_string()),
+                        Pat::Wild(_) => accounts.push("_".to_string()),
+                        Pat::Rest(_) => rest = true,
+                        _ => {}
+                    }
+                }
+                if accounts.iter().any(|account| account != "_") {
+                    self.slices.push(AccountSlice {
+                        function: function.clone(),
+                        function_index: *function_index,
+                        accounts,
+                        rest,
+                        span: node.span(),
+                    });
+                }
+            }
+        }
+        syn::visit::visit_local(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        let method = node.method.to_string();
+        if let Some((function, _)) = &self.function {
+            if UNCHECKED_BORROWS.contains(&method.as_str()) {
+                self.borrows.push(UncheckedBorrow {
+                    function: function.clone(),
+                    account: node
+                        .receiver
+                        .span()
+                        .source_text()
+                        .unwrap_or_else(|| "?".to_string()),
+                    method,
+                    span: node.span(),
+                });
+            }
+        }
+        syn::visit::visit_expr_method_call(self, node);
+    }
+}
+
+/// Whether an expression is a variable, possibly borrowed: `accounts`, `&accounts`.
+fn is_variable(expr: &Expr) -> bool {
+    match expr {
+        Expr::Reference(reference) => is_variable(&reference.expr),
+        Expr::Path(path) => path.path.get_ident().is_some(),
+        _ => false,
+    }
+}
+
+/// Whether `attrs` hold a `#[repr(C)]`, possibly with other representations.
+fn is_repr_c(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<syn::Meta, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .any(|meta| meta.path().is_ident("C"))
+}
+
+/// Returns the name of the macro of an item macro, e.g. `account` for `steel::account!(...)`.
+fn macro_name(item: &syn::ItemMacro) -> String {
+    item.mac
+        .path
+        .segments
+        .last()
+        .map(|segment| segment.ident.to_string())
+        .unwrap_or_default()
+}
+
+/// Returns the last identifier of each argument of a macro, e.g. `["ExampleAccount",
+/// "Counter"]` for `account!(ExampleAccount, Counter)`.
+fn macro_path_args(item: &syn::ItemMacro) -> Vec<String> {
+    item.mac
+        .parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+        .map(|args| {
+            args.iter()
+                .map(|arg| match arg {
+                    Expr::Path(path) => path
+                        .path
+                        .segments
+                        .last()
+                        .map(|segment| segment.ident.to_string())
+                        .unwrap_or_default(),
+                    _ => String::new(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parses the framework of a file and its entrypoints, account types, account slices and
+/// unchecked borrows.
+///
+/// The account types are the structs declared with Steel's `account!` in a Steel file, and
+/// otherwise the `#[repr(C)]` or `Pod` / `Zeroable` / `ShankAccount` structs without lifetime
+/// nor instruction data name (`DepositData`, `InitializeArgs`, ...).
+pub fn framework_items(file: &syn::File) -> FrameworkItems {
+    let mut crates = CrateCollector::default();
+    crates.visit_file(file);
+    let framework = crates.framework;
+
+    let mut entrypoints = vec![];
+    let mut steel_accounts = vec![];
+    for item in items_of(&file.items) {
+        let Item::Macro(item) = item else {
+            continue;
+        };
+        let name = macro_name(item);
+        let args = macro_path_args(item);
+        if ENTRYPOINT_MACROS.contains(&name.as_str()) {
+            if let Some(function) = args.first().filter(|function| !function.is_empty()) {
+                entrypoints.push(Entrypoint {
+                    function: function.clone(),
+                    macro_name: name,
+                    span: item.span(),
+                });
+            }
+        } else if name == "account" && args.len() == 2 && framework == Some(Framework::Steel) {
+            steel_accounts.push((args[1].clone(), args[0].clone(), item.span()));
+        }
+    }
+
+    let mut accounts = vec![];
+    for item in items_of(&file.items) {
+        let Item::Struct(item) = item else {
+            continue;
+        };
+        let name = item.ident.to_string();
+        let discriminator = if framework == Some(Framework::Steel) {
+            let Some(index) = steel_accounts
+                .iter()
+                .position(|(account, _, _)| *account == name)
+            else {
+                continue;
+            };
+            Some(steel_accounts.remove(index).1)
+        } else {
+            let is_layout = is_repr_c(&item.attrs)
+                || ACCOUNT_DERIVES
+                    .iter()
+                    .any(|derive| derives(&item.attrs, derive));
+            let is_instruction_data = INSTRUCTION_DATA_SUFFIXES
+                .iter()
+                .any(|suffix| name.ends_with(suffix));
+            if !is_layout || is_instruction_data || item.generics.lifetimes().next().is_some() {
+                continue;
+            }
+            None
+        };
+        accounts.push(AccountType {
+            name,
+            discriminator,
+            span: item.ident.span(),
+        });
+    }
+    // the structs of the `account!` of this file declared in another one
+    accounts.extend(
+        steel_accounts
+            .into_iter()
+            .map(|(name, discriminator, span)| AccountType {
+                name,
+                discriminator: Some(discriminator),
+                span,
+            }),
+    );
+
+    let mut functions = FunctionCollector::default();
+    functions.visit_file(file);
+
+    FrameworkItems {
+        framework,
+        entrypoints,
+        accounts,
+        account_slices: functions.slices,
+        unchecked_borrows: functions.borrows,
+    }
+}
+
+/// Adds the account slices of the `framework` of a JSON syntax tree to the JSON of their
+/// functions, as an `account_slices` list of the same entries: the rules find the functions by
+/// name, which several functions of a file may share.
+pub fn enrich_json_with_account_slices(ast_json: &mut Value) {
+    let slices = ast_json
+        .pointer("/framework/account_slices")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    if !slices.is_empty() {
+        add_account_slices(ast_json, &slices, &mut HashMap::new());
+    }
+}
+
+/// Adds the account slices to the functions of a JSON syntax tree, counting the functions by
+/// name in the order of [`FunctionCollector`].
+fn add_account_slices(
+    node: &mut Value,
+    slices: &[Value],
+    occurrences: &mut HashMap<String, usize>,
+) {
+    match node {
+        Value::Object(map) => {
+            let name = map
+                .get("fn")
+                .and_then(|function| function.get("ident"))
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            if let Some(name) = name {
+                let occurrences = occurrences.entry(name.clone()).or_default();
+                let function_slices: Vec<Value> = slices
+                    .iter()
+                    .filter(|slice| slice["function"] == name.as_str())
+                    .filter(|slice| slice["function_index"] == *occurrences)
+                    .cloned()
+                    .collect();
+                *occurrences += 1;
+                if !function_slices.is_empty() {
+                    map["fn"]["account_slices"] = Value::Array(function_slices);
+                }
+            }
+            for value in map.values_mut() {
+                add_account_slices(value, slices, occurrences);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                add_account_slices(item, slices, occurrences);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names<T>(items: &[T], name: impl Fn(&T) -> &str) -> Vec<&str> {
+        items.iter().map(name).collect()
+    }
+
+    #[test]
+    fn test_framework_items() {
+        let file = syn::parse_file(
+            r#"
+            use pinocchio::{account_info::AccountInfo, program_entrypoint, ProgramResult};
+            use solana_program::pubkey::Pubkey;
+
+            program_entrypoint!(process_instruction);
+
+            #[repr(C)]
+            pub struct Vault {
+                pub owner: [u8; 32],
+                pub balance: u64,
+            }
+
+            impl Vault {
+                pub fn load(account: &AccountInfo) -> &Self {
+                    unsafe { &*(account.borrow_data_unchecked().as_ptr() as *const Self) }
+                }
+            }
+
+            #[repr(C)]
+            pub struct DepositData {
+                pub amount: u64,
+            }
+
+            pub struct DepositAccounts<'a> {
+                pub owner: &'a AccountInfo,
+            }
+
+            impl<'a> TryFrom<&'a [AccountInfo]> for DepositAccounts<'a> {
+                type Error = ProgramError;
+
+                fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+                    let [owner, vault, _, ..] = accounts else {
+                        return Err(ProgramError::NotEnoughAccountKeys);
+                    };
+                    let [first, second] = data[..2] else { unreachable!() };
+                    Ok(Self { owner })
+                }
+            }
+
+            impl<'a> TryFrom<&'a [AccountInfo]> for WithdrawAccounts<'a> {
+                type Error = ProgramError;
+
+                fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+                    let [authority, ..] = accounts else {
+                        return Err(ProgramError::NotEnoughAccountKeys);
+                    };
+                    Ok(Self { authority })
+                }
+            }
+            "#,
+        )
+        .unwrap();
+        let items = framework_items(&file);
+        assert_eq!(items.framework, Some(Framework::Pinocchio));
+        assert_eq!(items.entrypoints[0].function, "process_instruction");
+        assert_eq!(items.entrypoints[0].macro_name, "program_entrypoint");
+        assert_eq!(
+            names(&items.accounts, |account| &account.name),
+            vec!["Vault"]
+        );
+        assert_eq!(items.account_slices.len(), 2);
+        let slice = &items.account_slices[0];
+        assert_eq!(slice.function, "try_from");
+        assert_eq!(slice.accounts, vec!["owner", "vault", "_"]);
+        assert!(slice.rest);
+        assert_eq!(items.account_slices[1].function_index, 1);
+        assert_eq!(items.unchecked_borrows.len(), 1);
+        assert_eq!(items.unchecked_borrows[0].function, "load");
+        assert_eq!(items.unchecked_borrows[0].account, "account");
+
+        // each slice goes to its own `try_from`
+        let mut ast_json: Value = serde_json::from_str(&syn_serde::json::to_string(&file)).unwrap();
+        ast_json["framework"] = items.to_json(|_| Value::Null);
+        enrich_json_with_account_slices(&mut ast_json);
+        let slices = |item: usize| {
+            ast_json["items"][item]["impl"]["items"][1]["fn"]["account_slices"][0]["accounts"]
+                .clone()
+        };
+        assert_eq!(slices(7), json!(["owner", "vault", "_"]));
+        assert_eq!(slices(8), json!(["authority"]));
+
+        let file = syn::parse_file(
+            r#"
+            use steel::*;
+
+            #[repr(u8)]
+            #[derive(Clone, Copy, Debug, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+            pub enum ExampleAccount {
+                Counter = 0,
+            }
+
+            #[repr(C)]
+            #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+            pub struct Counter {
+                pub value: u64,
+            }
+
+            #[repr(C)]
+            #[derive(Clone, Copy, Debug, Pod, Zeroable)]
+            pub struct Add {
+                pub amount: [u8; 8],
+            }
+
+            account!(ExampleAccount, Counter);
+            account!(ExampleAccount, Config);
+            instruction!(ExampleInstruction, Add);
+            "#,
+        )
+        .unwrap();
+        let items = framework_items(&file);
+        assert_eq!(items.framework, Some(Framework::Steel));
+        assert!(items.entrypoints.is_empty());
+        assert_eq!(
+            names(&items.accounts, |account| &account.name),
+            vec!["Counter", "Config"]
+        );
+        assert_eq!(
+            items.accounts[0].discriminator.as_deref(),
+            Some("ExampleAccount")
+        );
+        let json = items.to_json(|span| json!(span.start().line));
+        assert_eq!(json["name"], "steel");
+        assert_eq!(json["accounts"][0]["position"], 12);
+        assert_eq!(json["accounts"][1]["position"], 23);
+
+        let file = syn::parse_file("fn main() {}").unwrap();
+        assert_eq!(framework_items(&file).to_json(|_| Value::Null), Value::Null);
+    }
+}
@@ -0,0 +1,305 @@
+//! Direct manipulations of the lamports of accounts.
+//!
+//! Moving lamports by hand rather than through a transfer of the System Program skips its
+//! checks: the program has to keep the balances consistent itself. A manipulation is one of:
+//!
+//! - an assignment to a borrowed balance: `**vault.lamports.borrow_mut() -= amount`,
+//!   `**vault.try_borrow_mut_lamports()? = 0`,
+//! - a mutable borrow of a balance, kept to be assigned later:
+//!   `let lamports = vault.try_borrow_mut_lamports()?`,
+//! - the lamports helpers: `vault.sub_lamports(amount)?`, `add_lamports`, `set_lamports`,
+//! - the arithmetic on a balance: `vault.lamports() - amount`,
+//!   `vault.lamports().checked_sub(amount)`, `**vault.lamports.borrow() + fee`.
+//!
+//! They are listed for each instruction in the recap, and reported by the
+//! `direct_lamport_manipulation` rule, from the `lamports` of the JSON syntax tree (see
+//! [`crate::parsers::syn_ast::ast_to_json_with_positions`]).
+
+use serde::Serialize;
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+use syn::{BinOp, Expr};
+
+/// The operation of a mutable borrow kept for later, e.g. `let lamports = vault.try_borrow_mut_lamports()?`.
+pub const BORROW_MUT: &str = "borrow_mut";
+/// The operation of an arithmetic on a balance, e.g. `vault.lamports() - amount`.
+pub const ARITHMETIC: &str = "arithmetic";
+
+/// The lamports helpers of `AccountInfo`.
+const LAMPORTS_HELPERS: &[&str] = &["add_lamports", "sub_lamports", "set_lamports"];
+
+/// The methods borrowing a balance mutably.
+const BORROW_MUT_METHODS: &[&str] = &["try_borrow_mut_lamports", "borrow_mut_lamports"];
+
+/// The methods reading a balance.
+const READ_METHODS: &[&str] = &["lamports", "get_lamports", "try_lamports"];
+
+/// The arithmetic methods of the integers.
+const ARITHMETIC_METHODS: &[&str] = &[
+    "checked_add",
+    "checked_sub",
+    "checked_mul",
+    "checked_div",
+    "saturating_add",
+    "saturating_sub",
+    "saturating_mul",
+    "wrapping_add",
+    "wrapping_sub",
+    "wrapping_mul",
+    "overflowing_add",
+    "overflowing_sub",
+];
+
+/// The methods leading from an account to its `AccountInfo`, skipped to name the account.
+const ACCOUNT_INFO_METHODS: &[&str] = &["to_account_info", "as_account_info", "as_ref", "clone"];
+
+/// A direct manipulation of the lamports of an account.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct LamportManipulation {
+    /// The account, e.g. `vault` for `ctx.accounts.vault.to_account_info()`.
+    pub account: String,
+    /// The assignment operator (`=`, `-=`, ...), the lamports helper (`sub_lamports`, ...),
+    /// [`BORROW_MUT`] or [`ARITHMETIC`].
+    pub operation: String,
+}
+
+/// Returns the source text of a node, e.g. `accounts[0]`.
+fn source_text(node: &impl Spanned) -> String {
+    node.span().source_text().unwrap_or_else(|| "?".to_string())
+}
+
+/// Returns the expression under the references, dereferences, parentheses, `?`, `.unwrap()`
+/// and `.expect(...)` of `expr`.
+fn unwrapped(mut expr: &Expr) -> &Expr {
+    loop {
+        expr = match expr {
+            Expr::Paren(paren) => &paren.expr,
+            Expr::Group(group) => &group.expr,
+            Expr::Reference(reference) => &reference.expr,
+            Expr::Unary(unary) if matches!(unary.op, syn::UnOp::Deref(_)) => &unary.expr,
+            Expr::Try(try_expr) => &try_expr.expr,
+            Expr::Cast(cast) => &cast.expr,
+            Expr::MethodCall(call)
+                if matches!(call.method.to_string().as_str(), "unwrap" | "expect") =>
+            {
+                &call.receiver
+            }
+            _ => return expr,
+        };
+    }
+}
+
+/// Returns the name of the account of an expression: the last field or path segment, after
+/// the `to_account_info()` and the like.
+fn account_of(expr: &Expr) -> String {
+    match unwrapped(expr) {
+        Expr::MethodCall(call)
+            if ACCOUNT_INFO_METHODS.contains(&call.method.to_string().as_str()) =>
+        {
+            account_of(&call.receiver)
+        }
+        Expr::Field(field) => match &field.member {
+            syn::Member::Named(name) => name.to_string(),
+            syn::Member::Unnamed(_) => source_text(field),
+        },
+        Expr::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map_or_else(|| source_text(path), |s| s.ident.to_string()),
+        expr => source_text(expr),
+    }
+}
+
+/// Returns the account whose `lamports` field is borrowed by `expr` with `method`, e.g.
+/// `vault` for `vault.lamports.borrow_mut()` and `borrow_mut`.
+fn borrowed_field(expr: &Expr, methods: &[&str]) -> Option<String> {
+    let Expr::MethodCall(call) = expr else {
+        return None;
+    };
+    if !methods.contains(&call.method.to_string().as_str()) {
+        return None;
+    }
+    match unwrapped(&call.receiver) {
+        Expr::Field(field) if matches!(&field.member, syn::Member::Named(name) if name == "lamports") => {
+            Some(account_of(&field.base))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the account whose balance `expr` borrows mutably, e.g. `vault` for
+/// `**vault.lamports.borrow_mut()` or `vault.try_borrow_mut_lamports()?`.
+fn mutable_balance(expr: &Expr) -> Option<String> {
+    let expr = unwrapped(expr);
+    if let Some(account) = borrowed_field(expr, &["borrow_mut", "try_borrow_mut"]) {
+        return Some(account);
+    }
+    match expr {
+        Expr::MethodCall(call)
+            if BORROW_MUT_METHODS.contains(&call.method.to_string().as_str()) =>
+        {
+            Some(account_of(&call.receiver))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the account whose balance `expr` reads, e.g. `vault` for `vault.lamports()` or
+/// `**vault.lamports.borrow()`.
+fn balance(expr: &Expr) -> Option<String> {
+    let expr = unwrapped(expr);
+    if let Some(account) = borrowed_field(expr, &["borrow", "try_borrow"]) {
+        return Some(account);
+    }
+    match expr {
+        Expr::MethodCall(call)
+            if call.args.is_empty() && READ_METHODS.contains(&call.method.to_string().as_str()) =>
+        {
+            Some(account_of(&call.receiver))
+        }
+        expr => mutable_balance(expr),
+    }
+}
+
+/// Returns the text of a compound assignment operator, e.g. `-=`.
+fn assign_op(op: &BinOp) -> Option<&'static str> {
+    Some(match op {
+        BinOp::AddAssign(_) => "+=",
+        BinOp::SubAssign(_) => "-=",
+        BinOp::MulAssign(_) => "*=",
+        BinOp::DivAssign(_) => "/=",
+        BinOp::RemAssign(_) => "%=",
+        BinOp::BitXorAssign(_) => "^=",
+        BinOp::BitAndAssign(_) => "&=",
+        BinOp::BitOrAssign(_) => "|=",
+        BinOp::ShlAssign(_) => "<<=",
+        BinOp::ShrAssign(_) => ">>=",
+        _ => return None,
+    })
+}
+
+/// Collects the manipulations of a syntax tree, with their expressions.
+#[derive(Default)]
+struct ManipulationVisitor<'ast> {
+    found: Vec<(&'ast Expr, LamportManipulation)>,
+}
+
+impl<'ast> ManipulationVisitor<'ast> {
+    fn push(&mut self, expr: &'ast Expr, account: String, operation: &str) {
+        self.found.push((
+            expr,
+            LamportManipulation {
+                account,
+                operation: operation.to_string(),
+            },
+        ));
+    }
+}
+
+impl<'ast> Visit<'ast> for ManipulationVisitor<'ast> {
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        match expr {
+            Expr::Assign(assign) => {
+                if let Some(account) = mutable_balance(&assign.left) {
+                    self.push(expr, account, "=");
+                    // the borrow of the left side is part of the assignment
+                    self.visit_expr(&assign.right);
+                    return;
+                }
+            }
+            Expr::Binary(binary) => {
+                if let Some(op) = assign_op(&binary.op) {
+                    if let Some(account) = mutable_balance(&binary.left) {
+                        self.push(expr, account, op);
+                        self.visit_expr(&binary.right);
+                        return;
+                    }
+                } else if matches!(
+                    binary.op,
+                    BinOp::Add(_) | BinOp::Sub(_) | BinOp::Mul(_) | BinOp::Div(_) | BinOp::Rem(_)
+                ) {
+                    if let Some(account) = balance(&binary.left).or_else(|| balance(&binary.right))
+                    {
+                        self.push(expr, account, ARITHMETIC);
+                    }
+                }
+            }
+            Expr::MethodCall(call) => {
+                let method = call.method.to_string();
+                if LAMPORTS_HELPERS.contains(&method.as_str()) {
+                    self.push(expr, account_of(&call.receiver), &method);
+                } else if BORROW_MUT_METHODS.contains(&method.as_str()) {
+                    self.push(expr, account_of(&call.receiver), BORROW_MUT);
+                } else if let Some(account) =
+                    borrowed_field(expr, &["borrow_mut", "try_borrow_mut"])
+                {
+                    self.push(expr, account, BORROW_MUT);
+                } else if ARITHMETIC_METHODS.contains(&method.as_str()) {
+                    let account =
+                        balance(&call.receiver).or_else(|| call.args.iter().find_map(balance));
+                    if let Some(account) = account {
+                        self.push(expr, account, ARITHMETIC);
+                    }
+                }
+            }
+            _ => {}
+        }
+        syn::visit::visit_expr(self, expr);
+    }
+}
+
+/// Lists the direct lamport manipulations of a file, with their expressions, in source order.
+pub fn file_manipulations(file: &syn::File) -> Vec<(&Expr, LamportManipulation)> {
+    let mut visitor = ManipulationVisitor::default();
+    visitor.visit_file(file);
+    visitor.found
+}
+
+/// Lists the direct lamport manipulations of a function body, see [`file_manipulations`].
+#[cfg(feature = "cli")]
+pub fn block_manipulations(block: &syn::Block) -> Vec<(&Expr, LamportManipulation)> {
+    let mut visitor = ManipulationVisitor::default();
+    visitor.visit_block(block);
+    visitor.found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_manipulations() {
+        let source = r#"
+            fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+                **ctx.accounts.vault.to_account_info().try_borrow_mut_lamports()? -= amount;
+                **ctx.accounts.user.lamports.borrow_mut() += amount;
+                let rest = ctx.accounts.vault.lamports().checked_sub(amount).unwrap();
+                let fee = (**ctx.accounts.pool.lamports.borrow() as u128) * 3 / 1000;
+                let mut lamports = treasury.try_borrow_mut_lamports()?;
+                **lamports = rest;
+                ctx.accounts.escrow.sub_lamports(amount)?;
+                ctx.accounts.vault.amount -= amount;
+                let total = amount + fee;
+                Ok(())
+            }
+        "#;
+        let file = syn::parse_file(source).unwrap();
+        let found: Vec<(String, String)> = file_manipulations(&file)
+            .into_iter()
+            .map(|(_, m)| (m.account, m.operation))
+            .collect();
+        let expected: Vec<(String, String)> = [
+            ("vault", "-="),
+            ("user", "+="),
+            ("vault", ARITHMETIC),
+            ("pool", ARITHMETIC),
+            ("treasury", BORROW_MUT),
+            ("escrow", "sub_lamports"),
+        ]
+        .into_iter()
+        .map(|(account, operation)| (account.to_string(), operation.to_string()))
+        .collect();
+        assert_eq!(found, expected);
+    }
+}
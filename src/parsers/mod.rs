@@ -3,8 +3,17 @@
 //! This module provides utilities to parse Rust code and enrich it with additional metadata,
 //! especially useful for static analysis workflows.
 //!
+//! - [`cfg`] — Removes the code disabled by the `#[cfg(...)]` of the deployed configuration.
+//! - [`anchor_accounts`] — Reads the Anchor accounts structs and their `#[account(...)]` constraints.
+//! - [`frameworks`] — Reads the entrypoints and account types of the Pinocchio and Steel programs.
+//! - [`lamports`] — Finds the direct manipulations of the lamports of accounts.
 //! - [`syn_ast`] — Parses `.rs` files into `syn::File` ASTs and tracks spans for diagnostics.
 //!
 //! These parsers are used by rule engines to apply checks and extract semantic information from source code.
 
+pub mod anchor_accounts;
+#[cfg(feature = "cli")]
+pub mod cfg;
+pub mod frameworks;
+pub mod lamports;
 pub mod syn_ast;
@@ -1,11 +1,15 @@
+use crate::helpers::timing;
+use crate::parsers::anchor_accounts::enrich_json_with_constraints;
+use crate::parsers::frameworks::{enrich_json_with_account_slices, framework_items};
+use crate::parsers::lamports::{file_manipulations, LamportManipulation};
 use crate::state::sast_state::{SynAst, SynAstMap};
 use anyhow::{Context, Result};
-use log::error;
+use log::{error, warn};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::HashMap;
 use std::fmt::Formatter;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::{fmt, fs};
 use syn::spanned::Spanned;
 use syn::visit;
@@ -17,14 +21,28 @@ use syn::visit::Visit;
 /// # Arguments
 ///
 /// * `dir` - The path to the root directory to scan for Rust files.
+/// * `column_encoding` - The unit in which the columns of the source positions are expressed.
 ///
 /// # Returns
 ///
 /// A `Result` containing a `SynAstMap` that maps file paths to their corresponding
 /// enriched `SynAst` structures.
-pub fn get_syn_ast_recursive(dir: &str) -> Result<SynAstMap> {
+#[allow(dead_code)]
+pub fn get_syn_ast_recursive(dir: &str, column_encoding: ColumnEncoding) -> Result<SynAstMap> {
+    get_syn_ast_recursive_with(
+        dir,
+        &ParseOptions {
+            column_encoding,
+            ..ParseOptions::default()
+        },
+    )
+}
+
+/// Same as [`get_syn_ast_recursive`], with the memory guardrails of `options`.
+pub fn get_syn_ast_recursive_with(dir: &str, options: &ParseOptions) -> Result<SynAstMap> {
+    let _span = timing::span("walk");
     let mut ast_map = HashMap::new();
-    visit_dir(Path::new(dir), &mut ast_map)?;
+    visit_dir(Path::new(dir), &mut ast_map, options)?;
     Ok(ast_map)
 }
 
@@ -34,11 +52,12 @@ pub fn get_syn_ast_recursive(dir: &str) -> Result<SynAstMap> {
 ///
 /// * `dir_path` - The path of the directory to visit.
 /// * `ast_map` - A mutable reference to the `SynAstMap` to populate.
+/// * `options` - How the files are parsed.
 ///
 /// # Returns
 ///
 /// An empty `Result` on success, or an error if directory traversal fails.
-fn visit_dir(dir_path: &Path, ast_map: &mut SynAstMap) -> Result<()> {
+fn visit_dir(dir_path: &Path, ast_map: &mut SynAstMap, options: &ParseOptions) -> Result<()> {
     if !dir_path.exists() {
         return Ok(());
     }
@@ -55,11 +74,11 @@ fn visit_dir(dir_path: &Path, ast_map: &mut SynAstMap) -> Result<()> {
         let path = entry.path();
 
         if path.is_file() && path.extension().unwrap_or_default() == "rs" {
-            if let Err(e) = parse_rust_file(&path, ast_map) {
+            if let Err(e) = parse_rust_file_with(&path, ast_map, options) {
                 error!("Error parsing Rust file {:?}: {}", path, e);
             }
         } else if path.is_dir() {
-            if let Err(e) = visit_dir(&path, ast_map) {
+            if let Err(e) = visit_dir(&path, ast_map, options) {
                 error!("Error visiting directory {:?}: {}", path, e);
             }
         }
@@ -67,22 +86,82 @@ fn visit_dir(dir_path: &Path, ast_map: &mut SynAstMap) -> Result<()> {
     Ok(())
 }
 
+/// Recursively lists the `.rs` files of a directory.
+///
+/// # Arguments
+///
+/// * `dir` - The path to the root directory to scan for Rust files.
+///
+/// # Returns
+///
+/// The paths of the Rust files, sorted, or an empty list if `dir` doesn't exist.
+pub fn find_rust_files(dir: &Path) -> Vec<std::path::PathBuf> {
+    let mut files = vec![];
+    let Ok(dir_entries) = fs::read_dir(dir) else {
+        return files;
+    };
+    for entry in dir_entries.flatten() {
+        let path = entry.path();
+        if path.is_file() && path.extension().unwrap_or_default() == "rs" {
+            files.push(path);
+        } else if path.is_dir() {
+            files.extend(find_rust_files(&path));
+        }
+    }
+    files.sort();
+    files
+}
+
 /// Parses a single Rust file into a `SynAst` and adds it to the provided map.
 ///
-/// This function reads the file, parses it into a `syn::File`, enriches it with
-/// source code positions, creates a JSON representation, and stores the resulting
-/// `SynAst` in the `ast_map`.
+/// This function reads the file and parses it with [`parse_rust_source`]. A file that doesn't
+/// parse is logged and skipped.
 ///
 /// # Arguments
 ///
 /// * `path` - The path to the Rust file to parse.
 /// * `ast_map` - A mutable reference to the `SynAstMap` to add the parsed data to.
+/// * `column_encoding` - The unit in which the columns of the source positions are expressed.
 ///
 /// # Returns
 ///
-/// An empty `Result` on success, or an error if file reading or parsing fails.
-pub fn parse_rust_file(path: &Path, ast_map: &mut SynAstMap) -> Result<()> {
-    let file_content = match fs::read_to_string(path) {
+/// An empty `Result` on success, or an error if file reading fails.
+#[allow(dead_code)]
+pub fn parse_rust_file(
+    path: &Path,
+    ast_map: &mut SynAstMap,
+    column_encoding: ColumnEncoding,
+) -> Result<()> {
+    parse_rust_file_with(
+        path,
+        ast_map,
+        &ParseOptions {
+            column_encoding,
+            ..ParseOptions::default()
+        },
+    )
+}
+
+/// Same as [`parse_rust_file`], with the memory guardrails of `options`: a file larger than
+/// `max_file_size` is skipped with a warning, and the JSON syntax tree is spilled to
+/// `spill_dir` as soon as it is built.
+pub fn parse_rust_file_with(
+    path: &Path,
+    ast_map: &mut SynAstMap,
+    options: &ParseOptions,
+) -> Result<()> {
+    let _span = timing::span("parse");
+    if let Some(max_file_size) = options.max_file_size {
+        let size = fs::metadata(path)?.len();
+        if size > max_file_size {
+            warn!(
+                "Skipping {:?}: {} bytes, above the maximum file size ({} bytes)",
+                path, size, max_file_size
+            );
+            return Ok(());
+        }
+    }
+    let file_content = match timing::time("read", || fs::read_to_string(path)) {
         Ok(content) => content,
         Err(e) => {
             error!("Failed to read Rust file {:?}: {}", path, e);
@@ -91,39 +170,161 @@ pub fn parse_rust_file(path: &Path, ast_map: &mut SynAstMap) -> Result<()> {
     };
     let filename = path.to_str().unwrap_or("").to_string();
 
-    match syn::parse_file(&file_content) {
-        Ok(ast) => {
-            // Generate position info using access paths instead of hashes
-            let ast_positions = enrich_ast_with_source_lines(&ast, path);
-
-            // Generate enriched JSON with position information
-            let ast_json = ast_to_json_with_positions(&ast, &ast_positions);
-
-            ast_map.insert(
-                filename,
-                SynAst {
-                    ast: ast.clone(),
-                    ast_positions,
-                    ast_json,
-                    results: vec![],
-                },
-            );
+    if let Err(error) =
+        parse_rust_source(&filename, &file_content, ast_map, options.column_encoding)
+    {
+        error!("Failed to parse Rust file {:?}: {}", path, error);
+        return Ok(());
+    }
+    drop(file_content);
+    if let (Some(spill_dir), Some(syn_ast)) = (&options.spill_dir, ast_map.get_mut(&filename)) {
+        timing::time("spill", || syn_ast.spill_ast_json(&filename, spill_dir))?;
+    }
+    Ok(())
+}
+
+/// Parses the Rust source `source` of the file `filename` into a `SynAst` and adds it to the
+/// provided map.
+///
+/// The source is parsed into a `syn::File`, enriched with source code positions, and its JSON
+/// representation is created.
+///
+/// # Returns
+///
+/// An empty `Result` on success, or the parse error of the source.
+pub fn parse_rust_source(
+    filename: &str,
+    source: &str,
+    ast_map: &mut SynAstMap,
+    column_encoding: ColumnEncoding,
+) -> Result<()> {
+    let ast = timing::time("syn", || syn::parse_file(source))?;
+    // Generate position info using access paths instead of hashes
+    let ast_positions = timing::time("positions", || {
+        enrich_ast_with_source_lines(&ast, Path::new(filename), source, column_encoding)
+    });
+
+    // Generate enriched JSON with position information
+    let ast_json = timing::time("json", || ast_to_json_with_positions(&ast, &ast_positions));
+
+    ast_map.insert(
+        filename.to_string(),
+        SynAst {
+            ast,
+            ast_positions,
+            ast_json,
+            ast_json_file: None,
+            results: vec![],
+        },
+    );
+    Ok(())
+}
+
+/// How the source files are parsed, and how much of them is kept in memory.
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// The unit in which the columns of the source positions are expressed.
+    pub column_encoding: ColumnEncoding,
+    /// Size in bytes above which a file is skipped (with a warning) instead of parsed.
+    pub max_file_size: Option<u64>,
+    /// Directory to which the JSON syntax trees are written instead of being kept in memory.
+    pub spill_dir: Option<PathBuf>,
+}
+
+/// Parses a size in bytes, optionally followed by `K`, `M` or `G` (powers of 1024, with or
+/// without a trailing `B`, in any case), e.g. `500000`, `512K` or `2MB`.
+#[cfg(feature = "cli")]
+pub fn parse_size(value: &str) -> Result<u64> {
+    let upper = value.trim().to_uppercase();
+    let number = upper.strip_suffix('B').unwrap_or(&upper);
+    let (number, multiplier) = match number.char_indices().last() {
+        Some((i, 'K')) => (&number[..i], 1 << 10),
+        Some((i, 'M')) => (&number[..i], 1 << 20),
+        Some((i, 'G')) => (&number[..i], 1 << 30),
+        _ => (number, 1),
+    };
+    number
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|number| number.checked_mul(multiplier))
+        .ok_or_else(|| anyhow::anyhow!("Invalid size: {}", value))
+}
+
+/// The unit in which the (0-based) columns of a `SourcePosition` are counted.
+///
+/// `proc_macro2` spans count Unicode scalar values (`Chars`), while editors and LSP
+/// clients usually expect `Bytes` (UTF-8 offsets) or `Utf16` code units.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnEncoding {
+    #[default]
+    Chars,
+    Bytes,
+    Utf16,
+}
+
+impl std::str::FromStr for ColumnEncoding {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "chars" => Ok(Self::Chars),
+            "bytes" => Ok(Self::Bytes),
+            "utf16" => Ok(Self::Utf16),
+            _ => Err(anyhow::anyhow!("Unknown column encoding: {}", s)),
         }
-        Err(error) => {
-            error!("Failed to parse Rust file {:?}: {}", path, error);
+    }
+}
+
+impl ColumnEncoding {
+    /// Converts a column counted in chars on `line` into this encoding.
+    ///
+    /// Columns past the end of the line are kept as is.
+    pub fn convert_column(&self, line: &str, char_column: usize) -> usize {
+        let prefix = match line.char_indices().nth(char_column) {
+            Some((byte_offset, _)) => &line[..byte_offset],
+            None if line.chars().count() == char_column => line,
+            None => return char_column,
+        };
+        match self {
+            Self::Chars => char_column,
+            Self::Bytes => prefix.len(),
+            Self::Utf16 => prefix.encode_utf16().count(),
         }
-    };
-    Ok(())
+    }
+
+    /// Converts a column counted in this encoding on `line` into a byte offset of `line`.
+    ///
+    /// Columns past the end of the line are clamped to its length, and columns falling inside
+    /// a character are moved to its start.
+    pub fn byte_offset(&self, line: &str, column: usize) -> usize {
+        let mut units = 0;
+        for (byte_offset, c) in line.char_indices() {
+            let width = match self {
+                Self::Chars => 1,
+                Self::Bytes => c.len_utf8(),
+                Self::Utf16 => c.len_utf16(),
+            };
+            if units + width > column {
+                return byte_offset;
+            }
+            units += width;
+        }
+        line.len()
+    }
 }
 
 /// Represents a location in a source file, including start and end coordinates.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SourcePosition {
     pub start_line: u32,
     pub start_column: u32,
     pub end_line: u32,
     pub end_column: u32,
     pub source_file: String,
+    #[serde(default)]
+    pub column_encoding: ColumnEncoding,
 }
 
 impl SourcePosition {
@@ -133,21 +334,64 @@ impl SourcePosition {
     ///
     /// * `span` - The `Span` to convert.
     /// * `source_file` - The path to the source file containing the span.
-    pub fn from_span(span: &proc_macro2::Span, source_file: String) -> Self {
+    /// * `source_lines` - The lines of the original source, used to convert the columns.
+    /// * `column_encoding` - The unit in which the columns are expressed.
+    pub fn from_span(
+        span: &proc_macro2::Span,
+        source_file: String,
+        source_lines: &[&str],
+        column_encoding: ColumnEncoding,
+    ) -> Self {
+        let column = |location: proc_macro2::LineColumn| -> u32 {
+            match source_lines.get(location.line.saturating_sub(1)) {
+                Some(line) => column_encoding.convert_column(line, location.column) as u32,
+                None => location.column as u32,
+            }
+        };
         Self {
             start_line: span.start().line as u32,
-            start_column: span.start().column as u32,
+            start_column: column(span.start()),
             end_line: span.end().line as u32,
-            end_column: span.end().column as u32,
+            end_column: column(span.end()),
             source_file,
+            column_encoding,
         }
     }
 
-    /// Formats the position into a `file:line:column` string.
+    /// Returns the text covered by this position in `content`, the source of `source_file`.
+    ///
+    /// # Returns
+    ///
+    /// The text (lines joined with `\n`), or `None` if the position is out of `content`.
+    pub fn slice(&self, content: &str) -> Option<String> {
+        let lines: Vec<&str> = content.lines().collect();
+        let start_index = (self.start_line as usize).checked_sub(1)?;
+        let end_index = (self.end_line as usize).checked_sub(1)?;
+        let (first, last) = (lines.get(start_index)?, lines.get(end_index)?);
+        let start = self.column_encoding.byte_offset(first, self.start_column as usize);
+        let end = self.column_encoding.byte_offset(last, self.end_column as usize);
+
+        if start_index == end_index {
+            return first.get(start..end.max(start)).map(str::to_string);
+        }
+        if start_index > end_index {
+            return None;
+        }
+        let mut text = first[start..].to_string();
+        for line in &lines[start_index + 1..end_index] {
+            text.push('\n');
+            text.push_str(line);
+        }
+        text.push('\n');
+        text.push_str(&last[..end]);
+        Some(text)
+    }
+
+    /// Formats the position into a `file:line:column-end_line:end_column` string.
     pub fn get_pretty_string(&self) -> String {
         format!(
-            "{}:{}:{}",
-            self.source_file, self.start_line, self.start_column
+            "{}:{}:{}-{}:{}",
+            self.source_file, self.start_line, self.start_column, self.end_line, self.end_column
         )
     }
 }
@@ -166,6 +410,15 @@ impl fmt::Display for SourcePosition {
 pub struct AstPositions {
     // Store position info directly on nodes, removing the need for a HashMap
     pub nodes_with_positions: Vec<(String, SourcePosition)>, // Path -> Position
+    /// Positions of the `as` casts, keyed by the JSON of the cast expression since a cast has no
+    /// identifier (see [`enrich_json_with_casts`]).
+    pub casts: Vec<(serde_json::Value, SourcePosition)>,
+    /// Direct manipulations of lamports, keyed by the JSON of their expression like the casts
+    /// (see [`enrich_json_with_lamports`]).
+    pub lamports: Vec<(serde_json::Value, SourcePosition, LamportManipulation)>,
+    /// The framework of the file and its items, the `framework` of the JSON syntax tree (see
+    /// [`crate::parsers::frameworks`]), `null` if the file uses none.
+    pub framework: serde_json::Value,
 }
 
 impl AstPositions {
@@ -173,6 +426,9 @@ impl AstPositions {
     pub fn new() -> Self {
         Self {
             nodes_with_positions: Vec::new(),
+            casts: Vec::new(),
+            lamports: Vec::new(),
+            framework: serde_json::Value::Null,
         }
     }
 
@@ -185,23 +441,30 @@ impl AstPositions {
 /// A `syn::visit::Visit` implementation that collects source spans for `syn::Ident` nodes.
 struct SpanCollector<'a> {
     source_file_path: &'a Path,
+    source_lines: Vec<&'a str>,
+    column_encoding: ColumnEncoding,
     positions: AstPositions,
 }
 
 
 impl<'a, 'ast> SpanCollector<'a> {
+    /// Helper method to build the position of a span in the source file
+    fn span_position(&self, span: &proc_macro2::Span) -> SourcePosition {
+        SourcePosition::from_span(
+            span,
+            match self.source_file_path.to_str() {
+                Some(path) => path.to_string(),
+                None => "no_source_path".to_string(),
+            },
+            &self.source_lines,
+            self.column_encoding,
+        )
+    }
+
     /// Helper method to add position information for a span with a given prefix and name
     fn add_span_position(&mut self, name: &str, span: &proc_macro2::Span) {
-        self.positions.add_position(
-            name.parse().unwrap(),
-            SourcePosition::from_span(
-                span,
-                match self.source_file_path.to_str() {
-                    Some(path) => path.to_string(),
-                    None => "no_source_path".to_string(),
-                },
-            ),
-        );
+        let position = self.span_position(span);
+        self.positions.add_position(name.parse().unwrap(), position);
     }
 
     /// Helper method to extract path as string from syn::Path
@@ -395,6 +658,16 @@ impl<'a, 'ast> Visit<'ast> for SpanCollector<'a> {
         visit::visit_expr_struct(self, node);
     }
 
+    // Casts, matched back by their JSON in `enrich_json_with_casts`
+    fn visit_expr_cast(&mut self, node: &'ast syn::ExprCast) {
+        let cast = syn_serde::json::to_string(&syn::Expr::Cast(node.clone()));
+        if let Ok(cast) = serde_json::from_str(&cast) {
+            let position = self.span_position(&node.span());
+            self.positions.casts.push((cast, position));
+        }
+        visit::visit_expr_cast(self, node);
+    }
+
     // Literals
     fn visit_lit_str(&mut self, node: &'ast syn::LitStr) {
         self.add_span_position(&node.value(), &node.span());
@@ -423,20 +696,46 @@ impl<'a, 'ast> Visit<'ast> for SpanCollector<'a> {
 ///
 /// * `ast` - The parsed syntax tree (`syn::File`) to analyze.
 /// * `source_file_path` - The path to the source file, used to create full `SourcePosition` data.
+/// * `source` - The original source text the AST was parsed from.
+/// * `column_encoding` - The unit in which the columns are expressed.
 ///
 /// # Returns
 ///
 /// An `AstPositions` structure containing the collected position metadata.
-pub fn enrich_ast_with_source_lines(ast: &syn::File, source_file_path: &Path) -> AstPositions {
+pub fn enrich_ast_with_source_lines<'a>(
+    ast: &syn::File,
+    source_file_path: &'a Path,
+    source: &'a str,
+    column_encoding: ColumnEncoding,
+) -> AstPositions {
     let mut collector = SpanCollector {
         source_file_path,
+        source_lines: source.split('\n').collect(),
+        column_encoding,
         positions: AstPositions::new(),
     };
     collector.visit_file(ast);
+    for (expr, manipulation) in file_manipulations(ast) {
+        let Ok(json) = serde_json::from_str(&syn_serde::json::to_string(expr)) else {
+            continue;
+        };
+        let position = collector.span_position(&expr.span());
+        collector
+            .positions
+            .lamports
+            .push((json, position, manipulation));
+    }
+    collector.positions.framework =
+        framework_items(ast).to_json(|span| position_json(&collector.span_position(span)));
     collector.positions
 }
 
-/// Serializes a `syn::File` to a JSON value and injects source position information.
+/// Serializes a `syn::File` to a JSON value and injects source position information,
+/// as well as the normalized types of the typed nodes (see [`enrich_json_with_types`]), the
+/// source types of the casts (see [`enrich_json_with_casts`]), the direct lamport
+/// manipulations (see [`enrich_json_with_lamports`]), the Anchor account constraints of the
+/// fields (see [`enrich_json_with_constraints`]) and the `framework` of the file (see
+/// [`crate::parsers::frameworks`]).
 ///
 /// # Arguments
 ///
@@ -445,7 +744,7 @@ pub fn enrich_ast_with_source_lines(ast: &syn::File, source_file_path: &Path) ->
 ///
 /// # Returns
 ///
-/// A `serde_json::Value` representing the AST with embedded position data.
+/// A `serde_json::Value` representing the AST with embedded position and type data.
 pub fn ast_to_json_with_positions(ast: &syn::File, positions: &AstPositions) -> serde_json::Value {
     let ast_json_string = syn_serde::json::to_string(ast);
 
@@ -458,7 +757,22 @@ pub fn ast_to_json_with_positions(ast: &syn::File, positions: &AstPositions) ->
         .map(|(path, pos)| (path.as_str(), pos))
         .collect();
 
+    // first, as the casts and the lamport manipulations are matched against the JSON produced
+    // by syn-serde
+    let mut lamports = positions.lamports.clone();
+    let mut manipulations = vec![];
+    find_lamport_manipulations(&ast_json, "", &mut lamports, &mut manipulations);
+    let mut casts = positions.casts.clone();
+    let fields = struct_field_types(&ast_json);
+    enrich_json_with_casts(&mut ast_json, &fields, &mut casts);
+    enrich_json_with_lamports(&mut ast_json, &manipulations);
     enrich_json_with_positions(&mut ast_json, &positions_map);
+    enrich_json_with_types(&mut ast_json);
+    enrich_json_with_constraints(&mut ast_json, ast);
+    if !positions.framework.is_null() {
+        ast_json["framework"] = positions.framework.clone();
+        enrich_json_with_account_slices(&mut ast_json);
+    }
 
     ast_json
 }
@@ -478,16 +792,7 @@ fn enrich_json_with_positions(
         serde_json::Value::Object(map) => {
             if let Some(ident) = map.get("ident").and_then(|v| v.as_str()) {
                 if let Some(position) = positions.get(ident) {
-                    map.insert(
-                        "position".to_string(),
-                        json!({
-                            "start_line": position.start_line,
-                            "start_column": position.start_column,
-                            "end_line": position.end_line,
-                            "end_column": position.end_column,
-                            "source_file": position.source_file
-                        }),
-                    );
+                    map.insert("position".to_string(), position_json(position));
                 }
             }
 
@@ -503,3 +808,613 @@ fn enrich_json_with_positions(
         _ => {}
     }
 }
+
+/// Renders the JSON of a `syn::Type` as a normalized type string.
+///
+/// Paths are reduced to their last segment and lifetimes are dropped, so that
+/// `anchor_lang::prelude::Account<'info, Vault>` becomes `Account<Vault>` and
+/// `&'a mut [u8]` becomes `&mut [u8]`. Unsupported types are rendered as `_`.
+pub fn normalized_type(ty: &serde_json::Value) -> String {
+    let Some((kind, value)) = ty.as_object().and_then(|map| map.iter().next()) else {
+        return "_".to_string();
+    };
+    let elem = || normalized_type(&value["elem"]);
+    match kind.as_str() {
+        "path" => {
+            let Some(segment) = value["segments"].as_array().and_then(|s| s.last()) else {
+                return "_".to_string();
+            };
+            let ident = segment["ident"].as_str().unwrap_or("_");
+            let args = segment["arguments"]["angle_bracketed"]["args"]
+                .as_array()
+                .map(|args| {
+                    args.iter()
+                        .filter_map(|arg| arg.get("type"))
+                        .map(normalized_type)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            if args.is_empty() {
+                ident.to_string()
+            } else {
+                format!("{}<{}>", ident, args.join(", "))
+            }
+        }
+        "reference" => {
+            let mutability = if value["mut"].as_bool().unwrap_or(false) {
+                "mut "
+            } else {
+                ""
+            };
+            format!("&{}{}", mutability, elem())
+        }
+        "ptr" => {
+            let mutability = if value["mut"].as_bool().unwrap_or(false) {
+                "mut"
+            } else {
+                "const"
+            };
+            format!("*{} {}", mutability, elem())
+        }
+        "slice" => format!("[{}]", elem()),
+        "array" => format!("[{}; _]", elem()),
+        "tuple" => format!(
+            "({})",
+            value["elems"]
+                .as_array()
+                .map(|elems| elems.iter().map(normalized_type).collect::<Vec<_>>())
+                .unwrap_or_default()
+                .join(", ")
+        ),
+        "paren" | "group" => elem(),
+        _ => "_".to_string(),
+    }
+}
+
+/// Recursively adds a `normalized_type` field (see [`normalized_type`]) to the objects
+/// having a `ty` field: struct fields, fn parameters, let bindings with a type annotation,
+/// constants and statics. The identifier bound by a typed pattern (`pat.ident`) gets it too,
+/// so that both the field and the binding nodes carry their type.
+fn enrich_json_with_types(node: &mut serde_json::Value) {
+    match node {
+        serde_json::Value::Object(map) => {
+            if let Some(ty) = map.get("ty").map(normalized_type) {
+                if let Some(binding) = map
+                    .get_mut("pat")
+                    .and_then(|pat| pat.get_mut("ident"))
+                    .and_then(|ident| ident.as_object_mut())
+                {
+                    binding.insert("normalized_type".to_string(), json!(ty));
+                }
+                map.insert("normalized_type".to_string(), json!(ty));
+            }
+
+            for (_, value) in map {
+                enrich_json_with_types(value);
+            }
+        }
+        serde_json::Value::Array(arr) => {
+            for item in arr {
+                enrich_json_with_types(item);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Serializes a `SourcePosition` as the `position` field of the JSON nodes.
+fn position_json(position: &SourcePosition) -> serde_json::Value {
+    json!({
+        "start_line": position.start_line,
+        "start_column": position.start_column,
+        "end_line": position.end_line,
+        "end_column": position.end_column,
+        "source_file": position.source_file,
+        "column_encoding": position.column_encoding
+    })
+}
+
+/// Methods whose result has the type of their receiver, followed by the source type inference of
+/// the casts (`amount.checked_mul(rate).unwrap() as u64` casts a `u64` if `amount` is one).
+const TYPE_PRESERVING_METHODS: &[&str] = &[
+    "unwrap",
+    "expect",
+    "unwrap_or",
+    "unwrap_or_default",
+    "ok_or",
+    "ok_or_else",
+    "pow",
+    "min",
+    "max",
+    "clone",
+    "abs",
+];
+
+/// Returns the normalized types of the named struct fields of the file, by field name. The
+/// fields declared with different types in several structs are left out.
+fn struct_field_types(ast: &serde_json::Value) -> HashMap<String, String> {
+    fn collect(node: &serde_json::Value, types: &mut HashMap<String, Option<String>>) {
+        match node {
+            serde_json::Value::Object(map) => {
+                if let Some(fields) = map
+                    .get("struct")
+                    .and_then(|item| item["fields"]["named"].as_array())
+                {
+                    for field in fields {
+                        let (Some(ident), Some(ty)) = (field["ident"].as_str(), field.get("ty"))
+                        else {
+                            continue;
+                        };
+                        let ty = normalized_type(ty);
+                        types
+                            .entry(ident.to_string())
+                            .and_modify(|known| {
+                                if known.as_deref() != Some(ty.as_str()) {
+                                    *known = None
+                                }
+                            })
+                            .or_insert(Some(ty));
+                    }
+                }
+                map.values().for_each(|value| collect(value, types));
+            }
+            serde_json::Value::Array(arr) => arr.iter().for_each(|item| collect(item, types)),
+            _ => {}
+        }
+    }
+    let mut types = HashMap::new();
+    collect(ast, &mut types);
+    types
+        .into_iter()
+        .filter_map(|(ident, ty)| Some((ident, ty?)))
+        .collect()
+}
+
+/// Infers the type of a JSON expression from the bindings in scope and the struct fields.
+///
+/// Only the expressions whose type follows from their operands are handled: paths, casts,
+/// suffixed literals, fields, arithmetic and the [`TYPE_PRESERVING_METHODS`]. References are
+/// dropped (`&u64` is `u64`), as the arithmetic auto-dereferences them.
+fn expression_type(
+    expr: &serde_json::Value,
+    scope: &HashMap<String, String>,
+    fields: &HashMap<String, String>,
+) -> Option<String> {
+    let (kind, value) = expr.as_object()?.iter().next()?;
+    let ty = match kind.as_str() {
+        "path" => match value["segments"].as_array()?.as_slice() {
+            [segment] => scope.get(segment["ident"].as_str()?)?.clone(),
+            _ => return None,
+        },
+        "cast" => normalized_type(&value["ty"]),
+        "lit" => {
+            let int = value["int"].as_str()?;
+            [
+                "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128",
+                "isize",
+            ]
+            .into_iter()
+            .find(|suffix| int.ends_with(suffix))?
+            .to_string()
+        }
+        "field" => fields.get(value["ident"].as_str()?)?.clone(),
+        "paren" | "group" | "unary" | "try" | "reference" => {
+            return expression_type(&value["expr"], scope, fields)
+        }
+        "binary" => {
+            let op = value["op"].as_str()?;
+            if !["+", "-", "*", "/", "%", "&", "|", "^", "<<", ">>"].contains(&op) {
+                return None;
+            }
+            return expression_type(&value["left"], scope, fields)
+                .or_else(|| expression_type(&value["right"], scope, fields));
+        }
+        "method_call" => {
+            let method = value["method"].as_str()?;
+            let preserved = TYPE_PRESERVING_METHODS.contains(&method)
+                || ["checked_", "saturating_", "wrapping_", "overflowing_"]
+                    .iter()
+                    .any(|prefix| method.starts_with(prefix));
+            if !preserved {
+                return None;
+            }
+            return expression_type(&value["receiver"], scope, fields);
+        }
+        _ => return None,
+    };
+    Some(
+        ty.trim_start_matches("&mut ")
+            .trim_start_matches('&')
+            .to_string(),
+    )
+}
+
+/// Returns the names of the variables (single segment paths) and fields used by an expression,
+/// in source order.
+fn expression_operands(expr: &serde_json::Value, operands: &mut Vec<String>) {
+    match expr {
+        serde_json::Value::Object(map) => {
+            if let Some(path) = map.get("path") {
+                if let Some([segment]) = path["segments"].as_array().map(Vec::as_slice) {
+                    if let Some(ident) = segment["ident"].as_str() {
+                        operands.push(ident.to_string());
+                    }
+                }
+            }
+            // the target type of a nested cast is not an operand
+            map.iter()
+                .filter(|(key, _)| key.as_str() != "ty")
+                .for_each(|(_, value)| expression_operands(value, operands));
+            if let Some(ident) = map.get("field").and_then(|field| field["ident"].as_str()) {
+                operands.push(ident.to_string());
+            }
+        }
+        serde_json::Value::Array(arr) => arr
+            .iter()
+            .for_each(|item| expression_operands(item, operands)),
+        _ => {}
+    }
+}
+
+/// Recursively annotates the `as` casts of a JSON AST, so that the rules can reason about
+/// truncations:
+///
+/// * `source_type`: the inferred type of the casted expression (see [`expression_type`]), `_`
+///   when unknown. The target type is the `normalized_type` added by [`enrich_json_with_types`].
+/// * `cast_operands`: the variables and fields used by the casted expression.
+/// * `position`: the span of the whole cast, taken from `casts`.
+///
+/// The types of the bindings are tracked per function, in statement order: the typed parameters,
+/// the `let` with a type annotation and the `let` whose initializer type can be inferred.
+///
+/// # Arguments
+///
+/// * `node` - The JSON AST, as produced by syn-serde.
+/// * `fields` - The struct field types, see [`struct_field_types`].
+/// * `casts` - The cast positions collected by `SpanCollector`, consumed as they are matched.
+fn enrich_json_with_casts(
+    node: &mut serde_json::Value,
+    fields: &HashMap<String, String>,
+    casts: &mut Vec<(serde_json::Value, SourcePosition)>,
+) {
+    fn walk(
+        node: &mut serde_json::Value,
+        scope: &mut HashMap<String, String>,
+        fields: &HashMap<String, String>,
+        casts: &mut Vec<(serde_json::Value, SourcePosition)>,
+    ) {
+        match node {
+            serde_json::Value::Object(map) => {
+                if let Some(function) = map.get_mut("fn") {
+                    // new scope, seeded with the typed parameters
+                    let mut fn_scope = HashMap::new();
+                    for input in function["inputs"].as_array().into_iter().flatten() {
+                        let typed = &input["typed"];
+                        if let Some(ident) = typed["pat"]["ident"]["ident"].as_str() {
+                            fn_scope.insert(ident.to_string(), normalized_type(&typed["ty"]));
+                        }
+                    }
+                    walk(function, &mut fn_scope, fields, casts);
+                    return;
+                }
+
+                let matched = map.contains_key("cast").then(|| {
+                    let expr = serde_json::Value::Object(map.clone());
+                    casts.iter().position(|(cast, _)| *cast == expr)
+                });
+                if let Some(cast) = map.get_mut("cast").and_then(|cast| cast.as_object_mut()) {
+                    if let Some(index) = matched.flatten() {
+                        let (_, position) = casts.remove(index);
+                        cast.insert("position".to_string(), position_json(&position));
+                    }
+                    let source_type = expression_type(&cast["expr"], scope, fields);
+                    let mut operands = vec![];
+                    expression_operands(&cast["expr"], &mut operands);
+                    cast.insert(
+                        "source_type".to_string(),
+                        json!(source_type.unwrap_or_else(|| "_".to_string())),
+                    );
+                    cast.insert("cast_operands".to_string(), json!(operands));
+                }
+
+                let binding = map.get("let").and_then(|local| {
+                    let pat = &local["pat"];
+                    match pat.get("type") {
+                        Some(typed) => Some((
+                            typed["pat"]["ident"]["ident"].as_str()?,
+                            Some(normalized_type(&typed["ty"])),
+                        )),
+                        None => Some((
+                            pat["ident"]["ident"].as_str()?,
+                            expression_type(&local["init"]["expr"], scope, fields),
+                        )),
+                    }
+                });
+                let binding = binding.map(|(ident, ty)| (ident.to_string(), ty));
+
+                for (_, value) in map.iter_mut() {
+                    walk(value, scope, fields, casts);
+                }
+
+                // bound after its initializer, which can't use it
+                match binding {
+                    Some((ident, Some(ty))) => {
+                        scope.insert(ident, ty);
+                    }
+                    Some((ident, None)) => {
+                        scope.remove(&ident);
+                    }
+                    None => {}
+                }
+            }
+            serde_json::Value::Array(arr) => {
+                for item in arr {
+                    walk(item, scope, fields, casts);
+                }
+            }
+            _ => {}
+        }
+    }
+    walk(node, &mut HashMap::new(), fields, casts);
+}
+
+/// The kinds of expressions a lamport manipulation can be.
+const LAMPORT_EXPRESSIONS: &[&str] = &["assign", "binary", "method_call"];
+
+/// Finds the lamport manipulations of a JSON AST, as the JSON pointers of their expressions
+/// with their annotation (see [`enrich_json_with_lamports`]).
+///
+/// # Arguments
+///
+/// * `node` - The JSON AST, as produced by syn-serde.
+/// * `pointer` - The JSON pointer of `node`.
+/// * `lamports` - The manipulations collected by [`enrich_ast_with_source_lines`], consumed as
+///   they are matched.
+/// * `found` - The JSON pointers of the matched expressions, with their annotation.
+fn find_lamport_manipulations(
+    node: &serde_json::Value,
+    pointer: &str,
+    lamports: &mut Vec<(serde_json::Value, SourcePosition, LamportManipulation)>,
+    found: &mut Vec<(String, serde_json::Value)>,
+) {
+    if lamports.is_empty() {
+        return;
+    }
+    match node {
+        serde_json::Value::Object(map) => {
+            let is_expression = map.len() == 1
+                && map
+                    .keys()
+                    .all(|key| LAMPORT_EXPRESSIONS.contains(&key.as_str()));
+            let matched = is_expression
+                .then(|| lamports.iter().position(|(expr, _, _)| expr == node))
+                .flatten();
+            if let Some(index) = matched {
+                let (_, position, manipulation) = lamports.remove(index);
+                found.push((
+                    pointer.to_string(),
+                    json!({
+                        "account": manipulation.account,
+                        "operation": manipulation.operation,
+                        "position": position_json(&position),
+                    }),
+                ));
+            }
+            for (key, value) in map {
+                find_lamport_manipulations(value, &format!("{}/{}", pointer, key), lamports, found);
+            }
+        }
+        serde_json::Value::Array(arr) => {
+            for (index, item) in arr.iter().enumerate() {
+                find_lamport_manipulations(
+                    item,
+                    &format!("{}/{}", pointer, index),
+                    lamports,
+                    found,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Annotates the direct lamport manipulations of a JSON AST (see [`crate::parsers::lamports`])
+/// with a `lamports` object in their expression: `{"binary": {"left": ..., "op": "-=", ...,
+/// "lamports": {"account": "vault", "operation": "-=", "position": ...}}}`.
+///
+/// # Arguments
+///
+/// * `node` - The JSON AST.
+/// * `manipulations` - The JSON pointers of the expressions, with their annotation, see
+///   [`find_lamport_manipulations`].
+fn enrich_json_with_lamports(
+    node: &mut serde_json::Value,
+    manipulations: &[(String, serde_json::Value)],
+) {
+    for (pointer, annotation) in manipulations {
+        let expression = node
+            .pointer_mut(pointer)
+            .and_then(|expr| expr.as_object_mut())
+            .and_then(|expr| expr.values_mut().next())
+            .and_then(|expr| expr.as_object_mut());
+        if let Some(expression) = expression {
+            expression.insert("lamports".to_string(), annotation.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_encoding() {
+        // "é" is 2 bytes / 1 UTF-16 unit, "🦀" is 4 bytes / 2 UTF-16 units
+        let line = "let é🦀 = x; // ok";
+        assert_eq!(ColumnEncoding::Chars.convert_column(line, 7), 7);
+        assert_eq!(ColumnEncoding::Bytes.convert_column(line, 7), 11);
+        assert_eq!(ColumnEncoding::Utf16.convert_column(line, 7), 8);
+        assert_eq!(ColumnEncoding::Bytes.convert_column(line, 17), 21);
+        assert_eq!(ColumnEncoding::Bytes.convert_column(line, 42), 42);
+
+        let source = "// éàü commentaire\nfn /* 🦀 */ handler() {}\n";
+        let ast = syn::parse_file(source).unwrap();
+        let positions =
+            enrich_ast_with_source_lines(&ast, Path::new("lib.rs"), source, ColumnEncoding::Bytes);
+        let (_, position) = positions
+            .nodes_with_positions
+            .iter()
+            .find(|(name, _)| name == "handler")
+            .expect("handler should have a position");
+        assert_eq!((position.start_line, position.start_column), (2, 14));
+        assert_eq!((position.end_line, position.end_column), (2, 21));
+        assert_eq!(position.get_pretty_string(), "lib.rs:2:14-2:21");
+        assert_eq!(position.slice(source).as_deref(), Some("handler"));
+        assert_eq!(ColumnEncoding::Utf16.byte_offset(line, 8), 11);
+        assert_eq!(ColumnEncoding::Utf16.byte_offset(line, 6), 6);
+    }
+
+    #[test]
+    fn test_position_slice() {
+        let source = "fn f() {\n    x.unwrap();\n}\n";
+        let position = |start_line, start_column, end_line, end_column| SourcePosition {
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+            source_file: "lib.rs".to_string(),
+            column_encoding: ColumnEncoding::Chars,
+        };
+        assert_eq!(position(2, 4, 2, 14).slice(source).as_deref(), Some("x.unwrap()"));
+        assert_eq!(
+            position(1, 7, 3, 1).slice(source).as_deref(),
+            Some("{\n    x.unwrap();\n}")
+        );
+        assert_eq!(position(4, 0, 4, 1).slice(source), None);
+    }
+
+    #[test]
+    fn test_normalized_types() {
+        let path = |ident: &str, args: Vec<serde_json::Value>| {
+            let mut segment = json!({ "ident": ident });
+            if !args.is_empty() {
+                segment["arguments"] = json!({ "angle_bracketed": { "args": args } });
+            }
+            json!({ "path": { "segments": [segment] } })
+        };
+        let account = path(
+            "Account",
+            vec![json!({ "lifetime": "info" }), json!({ "type": path("Vault", vec![]) })],
+        );
+        assert_eq!(normalized_type(&account), "Account<Vault>");
+        let bytes = json!({ "reference": { "mut": true, "elem": { "slice": { "elem": path("u8", vec![]) } } } });
+        assert_eq!(normalized_type(&bytes), "&mut [u8]");
+
+        let mut ast = json!([
+            { "fn": { "inputs": [{ "typed": { "pat": { "ident": { "ident": "data" } }, "ty": bytes } }] } },
+            { "struct": { "fields": { "named": [{ "ident": "vault", "ty": account }] } } }
+        ]);
+        enrich_json_with_types(&mut ast);
+        let typed = &ast[0]["fn"]["inputs"][0]["typed"];
+        assert_eq!(typed["normalized_type"], "&mut [u8]");
+        assert_eq!(typed["pat"]["ident"]["normalized_type"], "&mut [u8]");
+        assert_eq!(
+            ast[1]["struct"]["fields"]["named"][0]["normalized_type"],
+            "Account<Vault>"
+        );
+    }
+
+    #[test]
+    fn test_cast_types() {
+        let source = "struct Vault { balance: u64 }\n\
+            fn f(vault: &Vault, amount: &u64, rate: u128) -> u8 {\n\
+                let total = (*amount as u128).checked_mul(rate).unwrap();\n\
+                let fee: u32 = vault.balance as u32;\n\
+                (total / 2) as u64 as u8 + fee as u8 + other as u8\n\
+            }\n";
+        let ast = syn::parse_file(source).unwrap();
+        let positions =
+            enrich_ast_with_source_lines(&ast, Path::new("lib.rs"), source, ColumnEncoding::Chars);
+        let json = ast_to_json_with_positions(&ast, &positions);
+
+        let mut casts = vec![];
+        fn collect(node: &serde_json::Value, casts: &mut Vec<serde_json::Value>) {
+            match node {
+                serde_json::Value::Object(map) => {
+                    if let Some(cast) = map.get("cast") {
+                        casts.push(cast.clone());
+                    }
+                    map.values().for_each(|value| collect(value, casts));
+                }
+                serde_json::Value::Array(arr) => arr.iter().for_each(|item| collect(item, casts)),
+                _ => {}
+            }
+        }
+        collect(&json, &mut casts);
+        let summary: Vec<(String, String, String)> = casts
+            .iter()
+            .map(|cast| {
+                (
+                    cast["source_type"].as_str().unwrap().to_string(),
+                    cast["normalized_type"].as_str().unwrap().to_string(),
+                    cast["position"].as_object().map_or(String::new(), |_| {
+                        serde_json::from_value::<SourcePosition>(cast["position"].clone())
+                            .unwrap()
+                            .slice(source)
+                            .unwrap()
+                    }),
+                )
+            })
+            .collect();
+        let expected = [
+            ("u64", "u128", "*amount as u128"),
+            ("u64", "u32", "vault.balance as u32"),
+            ("u64", "u8", "(total / 2) as u64 as u8"),
+            ("u128", "u64", "(total / 2) as u64"),
+            ("u32", "u8", "fee as u8"),
+            ("_", "u8", "other as u8"),
+        ];
+        assert_eq!(
+            summary,
+            expected
+                .iter()
+                .map(|(a, b, c)| (a.to_string(), b.to_string(), c.to_string()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(casts[2]["cast_operands"], json!(["total"]));
+        assert_eq!(casts[1]["cast_operands"], json!(["vault", "balance"]));
+    }
+
+    #[test]
+    fn test_parse_options() {
+        assert_eq!(parse_size("500000").unwrap(), 500_000);
+        assert_eq!(parse_size("512K").unwrap(), 512 * 1024);
+        assert_eq!(parse_size("2mb").unwrap(), 2 * 1024 * 1024);
+        assert!(parse_size("big").is_err());
+
+        let root =
+            std::env::temp_dir().join(format!("sol-azy-parse-options-{}", std::process::id()));
+        let src = root.join("src");
+        fs::create_dir_all(&src).unwrap();
+        fs::write(src.join("lib.rs"), "fn handler() {}\n").unwrap();
+        let huge = format!("const X: &str = \"{}\";\n", "x".repeat(4096));
+        fs::write(src.join("huge.rs"), huge).unwrap();
+
+        let options = ParseOptions {
+            max_file_size: Some(1024),
+            spill_dir: Some(root.join("spill")),
+            ..ParseOptions::default()
+        };
+        let ast_map = get_syn_ast_recursive_with(&src.to_string_lossy(), &options).unwrap();
+        assert_eq!(ast_map.len(), 1);
+        let syn_ast = ast_map.values().next().unwrap();
+        assert!(syn_ast.ast_json.is_null());
+        let spilled = syn_ast.ast_json_file.as_ref().unwrap();
+        assert!(spilled.starts_with(root.join("spill")));
+        let ast_json: serde_json::Value =
+            serde_json::from_str(&syn_ast.ast_json_string().unwrap()).unwrap();
+        assert_eq!(ast_json["items"][0]["fn"]["ident"], "handler");
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -3,8 +3,11 @@
 //! This module is responsible for presenting the results of static analysis
 //! in a readable way, either through tables or JSON.
 //!
+//! - [`relational_printer`] — Exports the AST facts, call graph and findings as relational tables.
 //! - [`sast_printer`] — Pretty-prints SAST rule results in the terminal and can serialize them as JSON.
 //!
 //! These tools are used after analysis to help users interpret and act on findings.
 
+#[cfg(feature = "cli")]
+pub mod relational_printer;
 pub mod sast_printer;
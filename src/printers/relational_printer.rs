@@ -0,0 +1,591 @@
+//! Export of the SAST data as normalized relational tables.
+//!
+//! `sast --export-db <dir>` writes one CSV file per table, so that a whole codebase can be
+//! queried with SQL or datalog tools (`sqlite3 .import`, DuckDB `read_csv_auto`, Soufflé
+//! `.input`, ...). The tables reference each other through the `file_id`, `node_id`,
+//! `function` and `rule_id` columns:
+//!
+//! - `scan.csv` — `root`, the scanned directory, to which the paths are relative
+//! - `files.csv` — `file_id, path`
+//! - `nodes.csv` — the nodes of the prepared AST seen by the rules:
+//!   `file_id, node_id, parent_id, kind, ident, start_line, start_column, end_line, end_column`
+//! - `functions.csv` — `file_id, function, name, impl_type, start_line, end_line`
+//! - `calls.csv` — the source call graph: `file_id, caller, callee, kind, line`
+//! - `rules.csv` — `rule_id, name, severity, certainty, classification, author, version, description`
+//! - `findings.csv` — `finding_id, rule_id, file_id, node_id, ident, parent, start_line,
+//!   start_column, end_line, end_column, compiled, description`
+//! - `rule_coverage.csv` — every loaded rule, even those that never matched:
+//!   `rule_id, files_evaluated, matches, errors, duration_ms`
+//! - `rule_errors.csv` — the files on which a rule failed: `rule_id, file_id, error`
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+use crate::helpers::events;
+use crate::reverse::correlation::COMPILED_METADATA;
+use crate::state::sast_state::{RuleCoverage, SastState, SynAst};
+
+/// Writes the relational tables of SAST states.
+#[derive(Debug, Clone)]
+pub struct RelationalPrinter;
+
+/// A table: its header and rows, written as `<name>.csv`.
+struct Table {
+    name: &'static str,
+    header: &'static [&'static str],
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn new(name: &'static str, header: &'static [&'static str]) -> Self {
+        Self {
+            name,
+            header,
+            rows: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, row: Vec<String>) {
+        debug_assert_eq!(row.len(), self.header.len());
+        self.rows.push(row);
+    }
+
+    /// Writes the table as RFC 4180 CSV in `out_dir`.
+    fn write(&self, out_dir: &Path) -> Result<PathBuf> {
+        let path = out_dir.join(format!("{}.csv", self.name));
+        let mut output = std::io::BufWriter::new(
+            std::fs::File::create(&path).with_context(|| format!("Creating {}", path.display()))?,
+        );
+        let header: Vec<String> = self
+            .header
+            .iter()
+            .map(|column| column.to_string())
+            .collect();
+        for row in std::iter::once(&header).chain(&self.rows) {
+            let fields: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+            write!(output, "{}\r\n", fields.join(","))?;
+        }
+        output.flush()?;
+        events::artifact_written(&path);
+        Ok(path)
+    }
+}
+
+/// Quotes a CSV field if it holds a separator, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Returns the kind of a node of the prepared AST from its access path: the last segment,
+/// without its index (`[2].fn.stmts[0].macro` -> `macro`).
+fn node_kind(access_path: &str) -> String {
+    let segment = access_path.rsplit('.').next().unwrap_or_default();
+    let kind = segment.split('[').next().unwrap_or_default();
+    if kind.is_empty() {
+        "item".to_string()
+    } else {
+        kind.to_string()
+    }
+}
+
+/// Returns the parent of a node: the nearest node whose access path is a prefix of its own,
+/// as linked by `syn_ast.prepare_ast`, or an empty string for a top-level node.
+fn node_parent<'a, T>(access_path: &'a str, nodes: &BTreeMap<String, T>) -> &'a str {
+    let mut path = access_path;
+    while let Some((parent, _)) = path.rsplit_once('.') {
+        if nodes.contains_key(parent) {
+            return parent;
+        }
+        path = parent;
+    }
+    ""
+}
+
+/// Collects the nodes of a prepared AST by access path (the same node can be listed both at
+/// the root and under its parent).
+fn collect_nodes<'a>(
+    node: &'a serde_json::Value,
+    nodes: &mut BTreeMap<String, &'a serde_json::Value>,
+) {
+    if let Some(access_path) = node.get("access_path").and_then(|path| path.as_str()) {
+        if !node
+            .get("root")
+            .and_then(|root| root.as_bool())
+            .unwrap_or(false)
+        {
+            nodes.entry(access_path.to_string()).or_insert(node);
+        }
+    }
+    for child in node
+        .get("children")
+        .and_then(|children| children.as_array())
+        .into_iter()
+        .flatten()
+    {
+        collect_nodes(child, nodes);
+    }
+}
+
+/// Formats the `start_line, start_column, end_line, end_column` of a `position`.
+fn position_fields(position: Option<&serde_json::Value>) -> Vec<String> {
+    ["start_line", "start_column", "end_line", "end_column"]
+        .iter()
+        .map(|field| {
+            position
+                .and_then(|position| position.get(field))
+                .map(|value| value.to_string())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Collects the functions of a file and the calls they make.
+struct CallCollector {
+    file_id: String,
+    /// Path of the current module, then of the current `impl` type.
+    scope: Vec<String>,
+    impl_type: Option<String>,
+    /// Functions being visited, innermost last.
+    functions: Vec<String>,
+    function_rows: Vec<Vec<String>>,
+    call_rows: Vec<Vec<String>>,
+}
+
+impl CallCollector {
+    fn new(file_id: &str) -> Self {
+        Self {
+            file_id: file_id.to_string(),
+            scope: Vec::new(),
+            impl_type: None,
+            functions: Vec::new(),
+            function_rows: Vec::new(),
+            call_rows: Vec::new(),
+        }
+    }
+
+    fn path_to_string(path: &syn::Path) -> String {
+        path.segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect::<Vec<_>>()
+            .join("::")
+    }
+
+    /// Records a function and visits its body with it as the caller.
+    fn visit_function(
+        &mut self,
+        ident: &syn::Ident,
+        span: proc_macro2::Span,
+        body: impl FnOnce(&mut Self),
+    ) {
+        let name = ident.to_string();
+        let function = self
+            .scope
+            .iter()
+            .chain(self.impl_type.iter())
+            .chain(std::iter::once(&name))
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("::");
+        self.function_rows.push(vec![
+            self.file_id.clone(),
+            function.clone(),
+            name,
+            self.impl_type.clone().unwrap_or_default(),
+            span.start().line.to_string(),
+            span.end().line.to_string(),
+        ]);
+        self.functions.push(function);
+        body(self);
+        self.functions.pop();
+    }
+
+    fn add_call(&mut self, callee: String, kind: &str, span: proc_macro2::Span) {
+        if let Some(caller) = self.functions.last() {
+            self.call_rows.push(vec![
+                self.file_id.clone(),
+                caller.clone(),
+                callee,
+                kind.to_string(),
+                span.start().line.to_string(),
+            ]);
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for CallCollector {
+    fn visit_item_mod(&mut self, node: &'ast syn::ItemMod) {
+        self.scope.push(node.ident.to_string());
+        visit::visit_item_mod(self, node);
+        self.scope.pop();
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast syn::ItemImpl) {
+        let impl_type = match &*node.self_ty {
+            syn::Type::Path(path) => path
+                .path
+                .segments
+                .last()
+                .map(|segment| segment.ident.to_string()),
+            _ => None,
+        };
+        let outer = std::mem::replace(&mut self.impl_type, impl_type);
+        visit::visit_item_impl(self, node);
+        self.impl_type = outer;
+    }
+
+    fn visit_item_trait(&mut self, node: &'ast syn::ItemTrait) {
+        let outer = self.impl_type.replace(node.ident.to_string());
+        visit::visit_item_trait(self, node);
+        self.impl_type = outer;
+    }
+
+    fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+        // a function nested in another one is not a method of the enclosing impl
+        let outer = self.impl_type.take();
+        self.visit_function(&node.sig.ident, node.span(), |this| {
+            visit::visit_item_fn(this, node)
+        });
+        self.impl_type = outer;
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast syn::ImplItemFn) {
+        self.visit_function(&node.sig.ident, node.span(), |this| {
+            visit::visit_impl_item_fn(this, node)
+        });
+    }
+
+    fn visit_trait_item_fn(&mut self, node: &'ast syn::TraitItemFn) {
+        if node.default.is_some() {
+            self.visit_function(&node.sig.ident, node.span(), |this| {
+                visit::visit_trait_item_fn(this, node)
+            });
+        }
+    }
+
+    fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
+        if let syn::Expr::Path(path) = &*node.func {
+            self.add_call(Self::path_to_string(&path.path), "call", path.span());
+        }
+        visit::visit_expr_call(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        self.add_call(node.method.to_string(), "method", node.method.span());
+        visit::visit_expr_method_call(self, node);
+    }
+
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        self.add_call(
+            format!("{}!", Self::path_to_string(&node.path)),
+            "macro",
+            node.path.span(),
+        );
+        visit::visit_macro(self, node);
+    }
+}
+
+impl RelationalPrinter {
+    /// Writes the tables of the files, prepared AST nodes, functions, calls, rules,
+    /// findings and rule coverage of SAST states to `out_dir`.
+    ///
+    /// # Arguments
+    ///
+    /// * `states` - The states of the scanned projects, whose rules were applied.
+    /// * `out_dir` - The directory receiving the CSV files, created if needed.
+    ///
+    /// # Returns
+    ///
+    /// The paths of the written tables.
+    pub fn export(states: &[SastState], out_dir: &Path) -> Result<Vec<PathBuf>> {
+        std::fs::create_dir_all(out_dir)
+            .with_context(|| format!("Creating {}", out_dir.display()))?;
+
+        let mut scan = Table::new("scan", &["root"]);
+        let roots: BTreeSet<PathBuf> = states
+            .iter()
+            .filter_map(|state| state.absolute_root())
+            .collect();
+        for root in roots {
+            scan.push(vec![root.to_string_lossy().to_string()]);
+        }
+        let mut files = Table::new("files", &["file_id", "path"]);
+        let mut nodes = Table::new(
+            "nodes",
+            &[
+                "file_id",
+                "node_id",
+                "parent_id",
+                "kind",
+                "ident",
+                "start_line",
+                "start_column",
+                "end_line",
+                "end_column",
+            ],
+        );
+        let mut functions = Table::new(
+            "functions",
+            &[
+                "file_id",
+                "function",
+                "name",
+                "impl_type",
+                "start_line",
+                "end_line",
+            ],
+        );
+        let mut calls = Table::new("calls", &["file_id", "caller", "callee", "kind", "line"]);
+        let mut rules = Table::new(
+            "rules",
+            &[
+                "rule_id",
+                "name",
+                "severity",
+                "certainty",
+                "classification",
+                "author",
+                "version",
+                "description",
+            ],
+        );
+        let mut findings = Table::new(
+            "findings",
+            &[
+                "finding_id",
+                "rule_id",
+                "file_id",
+                "node_id",
+                "ident",
+                "parent",
+                "start_line",
+                "start_column",
+                "end_line",
+                "end_column",
+                "compiled",
+                "description",
+            ],
+        );
+
+        let mut rule_coverage = Table::new(
+            "rule_coverage",
+            &[
+                "rule_id",
+                "files_evaluated",
+                "matches",
+                "errors",
+                "duration_ms",
+            ],
+        );
+        let mut rule_errors = Table::new("rule_errors", &["rule_id", "file_id", "error"]);
+
+        let mut rule_ids: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut coverage = RuleCoverage::new();
+        let mut file_id = 0;
+        for state in states {
+            let mut paths: Vec<&String> = state.syn_ast_map.keys().collect();
+            paths.sort();
+            let mut file_ids = BTreeMap::new();
+            for path in paths {
+                file_id += 1;
+                let id = file_id.to_string();
+                file_ids.insert(path.as_str(), id.clone());
+                let syn_ast = &state.syn_ast_map[path];
+                files.push(vec![id.clone(), path.clone()]);
+
+                Self::push_nodes(state, syn_ast, &id, path, &mut nodes);
+
+                let mut collector = CallCollector::new(&id);
+                collector.visit_file(&syn_ast.ast);
+                functions.rows.extend(collector.function_rows);
+                calls.rows.extend(collector.call_rows);
+
+                for result in &syn_ast.results {
+                    let metadata = &result.rule_metadata;
+                    rule_ids
+                        .entry(result.rule_filename.clone())
+                        .or_insert_with(|| {
+                            vec![
+                                result.rule_filename.clone(),
+                                metadata.name.clone(),
+                                format!("{:?}", metadata.severity),
+                                format!("{:?}", metadata.certainty),
+                                result.classification.describe(),
+                                metadata.author.clone(),
+                                metadata.version.clone(),
+                                metadata.description.clone(),
+                            ]
+                        });
+                    for match_result in &result.matches {
+                        let mut row = vec![
+                            (findings.rows.len() + 1).to_string(),
+                            result.rule_filename.clone(),
+                            id.clone(),
+                            match_result.access_path.clone(),
+                            match_result.ident.clone(),
+                            match_result.parent.clone(),
+                        ];
+                        row.extend(position_fields(match_result.metadata.get("position")));
+                        row.push(
+                            match_result
+                                .metadata
+                                .get(COMPILED_METADATA)
+                                .and_then(|compiled| compiled.as_str())
+                                .unwrap_or_default()
+                                .to_string(),
+                        );
+                        row.push(result.render_description(match_result).unwrap_or_default());
+                        findings.push(row);
+                    }
+                }
+            }
+
+            for (rule, stats) in &state.rule_coverage {
+                let total = coverage.entry(rule.clone()).or_default();
+                total.files_evaluated += stats.files_evaluated;
+                total.matches += stats.matches;
+                total.duration_ms += stats.duration_ms;
+                for failure in &stats.failures {
+                    rule_errors.push(vec![
+                        rule.clone(),
+                        file_ids
+                            .get(failure.file.as_str())
+                            .cloned()
+                            .unwrap_or_default(),
+                        failure.error.clone(),
+                    ]);
+                    total.failures.push(failure.clone());
+                }
+            }
+        }
+        rules.rows.extend(rule_ids.into_values());
+        for (rule, stats) in coverage {
+            rule_coverage.push(vec![
+                rule,
+                stats.files_evaluated.to_string(),
+                stats.matches.to_string(),
+                stats.errors().to_string(),
+                format!("{:.3}", stats.duration_ms),
+            ]);
+        }
+
+        let paths = [
+            scan,
+            files,
+            nodes,
+            functions,
+            calls,
+            rules,
+            findings,
+            rule_coverage,
+            rule_errors,
+        ]
+        .iter()
+        .map(|table| table.write(out_dir))
+        .collect::<Result<Vec<_>>>()?;
+        info!("SAST database exported to {}", out_dir.display());
+        Ok(paths)
+    }
+
+    /// Adds the nodes of the prepared AST of a file, as seen by the rules.
+    fn push_nodes(
+        state: &SastState,
+        syn_ast: &SynAst,
+        file_id: &str,
+        path: &str,
+        nodes: &mut Table,
+    ) {
+        let prepared = match state
+            .starlark_engine
+            .eval_get_prepared_ast("get_prepared_ast", String::new(), syn_ast)
+            .and_then(|prepared| Ok(serde_json::from_str::<serde_json::Value>(&prepared)?))
+        {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                warn!("Failed to prepare the AST of {}: {}", path, e);
+                return;
+            }
+        };
+        let mut by_path = BTreeMap::new();
+        collect_nodes(&prepared, &mut by_path);
+        for (access_path, node) in &by_path {
+            let mut row = vec![
+                file_id.to_string(),
+                access_path.clone(),
+                node_parent(access_path, &by_path).to_string(),
+                node_kind(access_path),
+                node.get("ident")
+                    .and_then(|ident| ident.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+            ];
+            row.extend(position_fields(
+                node.get("metadata")
+                    .and_then(|metadata| metadata.get("position")),
+            ));
+            nodes.push(row);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_call_collector() {
+        let file = syn::parse_file(
+            "mod prog {\n    pub fn update() {\n        helper(1);\n        msg!(\"x\");\n    }\n}\n\nimpl Vault {\n    fn close(&self) {\n        self.data.borrow_mut();\n    }\n}\n",
+        )
+        .unwrap();
+        let mut collector = CallCollector::new("1");
+        collector.visit_file(&file);
+        let functions: Vec<&str> = collector
+            .function_rows
+            .iter()
+            .map(|row| row[1].as_str())
+            .collect();
+        assert_eq!(functions, vec!["prog::update", "Vault::close"]);
+        let calls: Vec<(&str, &str, &str)> = collector
+            .call_rows
+            .iter()
+            .map(|row| (row[1].as_str(), row[2].as_str(), row[3].as_str()))
+            .collect();
+        assert_eq!(
+            calls,
+            vec![
+                ("prog::update", "helper", "call"),
+                ("prog::update", "msg!", "macro"),
+                ("Vault::close", "borrow_mut", "method"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nodes_and_csv() {
+        assert_eq!(node_kind("[2].fn.stmts[0].macro"), "macro");
+        assert_eq!(node_kind("[0]"), "item");
+        let nodes = BTreeMap::from([
+            ("[2].fn".to_string(), ()),
+            ("[2].fn.stmts[0].macro.path".to_string(), ()),
+        ]);
+        assert_eq!(
+            node_parent("[2].fn.stmts[0].macro.path.segments[0]", &nodes),
+            "[2].fn.stmts[0].macro.path"
+        );
+        assert_eq!(node_parent("[2].fn.output", &nodes), "[2].fn");
+        assert_eq!(node_parent("[2].fn", &nodes), "");
+
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a, \"b\""), "\"a, \"\"b\"\"\"");
+    }
+}
@@ -1,10 +1,19 @@
 // src/pretty_printer.rs
 
+use crate::helpers::events::{self, Event};
+#[cfg(feature = "cli")]
+use crate::reverse::correlation::{
+    COMPILED_METADATA, COMPILED_PROGRAM_METADATA, COMPILED_SYMBOL_METADATA,
+};
+#[cfg(feature = "cli")]
 use crate::state::sast_state::{
-    Certainty, SastState, Severity, SynAstMapExt, SynAstResult, SynRuleMetadata,
+    RuleCoverage, RuleFailure, SastState, SynAstMapExt, SynMatchResult, PROGRAMS_METADATA,
 };
+use crate::state::sast_state::{SynAstResult, SynRuleMetadata};
+use crate::state::taxonomy::{Classification, Taxonomy};
 use anyhow::{Context, Result};
 use prettytable::{format, Cell, Row, Table};
+#[cfg(feature = "cli")]
 use std::collections::HashMap;
 
 /// A utility for displaying Static Analysis (SAST) results in a readable format.
@@ -28,7 +37,24 @@ impl SastPrinter {
     /// # Returns
     ///
     /// An empty `Result` on success, or an error if printing fails.
+    ///
+    /// With `--events-json`, a `finding` event is emitted per match, and the report is not
+    /// printed when the events are written to stdout.
+    #[cfg(feature = "cli")]
     pub fn print_sast_state(state: &SastState, scanned_dir: &String) -> Result<()> {
+        if events::enabled() {
+            if let Some(root) = state.absolute_root() {
+                events::emit(Event::Scanned {
+                    root: root.to_string_lossy().to_string(),
+                    files: state.syn_ast_map.count_files(),
+                });
+            }
+            Self::emit_finding_events(&Self::collect_results_with_matches(state));
+        }
+        if !events::human_output() {
+            return Ok(());
+        }
+
         Self::print_scan_summary(state, scanned_dir);
 
         let all_results = Self::collect_all_results(state);
@@ -42,6 +68,9 @@ impl SastPrinter {
             println!("\nNo vulnerabilities detected.");
         }
 
+        Self::print_rule_coverage(&state.rule_coverage);
+        Self::print_engine_errors(&state.engine_errors());
+
         Ok(())
     }
 
@@ -51,12 +80,16 @@ impl SastPrinter {
     ///
     /// * `state` - The `SastState` from the analysis.
     /// * `scanned_dir` - The directory that was scanned.
+    #[cfg(feature = "cli")]
     fn print_scan_summary(state: &SastState, scanned_dir: &String) {
         println!(
             "\n================================================================================\n\n{} files scanned in {} directory\n",
             state.syn_ast_map.count_files(),
             scanned_dir
         );
+        if let Some(root) = state.absolute_root() {
+            println!("Paths relative to {}\n", root.display());
+        }
     }
 
     /// Collects and flattens all analysis results from the SAST state.
@@ -68,6 +101,7 @@ impl SastPrinter {
     /// # Returns
     ///
     /// A vector of `SynAstResult` containing all findings.
+    #[cfg(feature = "cli")]
     fn collect_all_results(state: &SastState) -> Vec<SynAstResult> {
         state
             .syn_ast_map
@@ -85,6 +119,7 @@ impl SastPrinter {
     /// # Returns
     ///
     /// A vector of tuples, each containing a filename and a reference to the `SynAstResult`.
+    #[cfg(feature = "cli")]
     fn collect_results_with_matches(state: &SastState) -> Vec<(String, &SynAstResult)> {
         state
             .syn_ast_map
@@ -107,6 +142,7 @@ impl SastPrinter {
     /// # Returns
     ///
     /// An empty `Result` on success, or an error if printing fails.
+    #[cfg(feature = "cli")]
     fn print_detailed_findings(results_with_matches: &[(String, &SynAstResult)]) -> Result<()> {
         println!("\nDetailed findings:");
         let grouped_results = Self::group_results_by_rule_name(results_with_matches);
@@ -116,6 +152,7 @@ impl SastPrinter {
             println!("\n{}", "=".repeat(80));
             Self::print_rule_metadata(
                 &first_result.rule_metadata,
+                &first_result.classification,
                 first_result.rule_filename.to_string(),
             )?;
 
@@ -129,6 +166,87 @@ impl SastPrinter {
         Ok(())
     }
 
+    /// Prints the findings of a built-in bytecode rule (e.g. from the reverse analysis) in the
+    /// same format as the detailed SAST findings, rated in the built-in taxonomy.
+    ///
+    /// # Arguments
+    ///
+    /// * `metadata` - The metadata of the built-in rule.
+    /// * `locations` - `(location, description)` pairs, one per match.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result` on success, or an error if printing fails.
+    pub fn print_bytecode_findings(
+        metadata: &SynRuleMetadata,
+        locations: &[(String, String)],
+    ) -> Result<()> {
+        let classification = Taxonomy::default().classify(metadata);
+        for (location, description) in locations {
+            events::emit(Self::finding_event(
+                metadata,
+                &classification,
+                location.clone(),
+                Some(description.clone()),
+            ));
+        }
+        if !events::human_output() {
+            return Ok(());
+        }
+
+        println!("\n{}", "=".repeat(80));
+        Self::print_rule_metadata(metadata, &classification, "(built-in)".to_string())?;
+
+        println!("\nMatches found: {}", locations.len());
+        for (location, description) in locations {
+            println!("{}", location);
+            println!("    -> {}", description);
+        }
+        println!("{}", "=".repeat(80));
+
+        Ok(())
+    }
+
+    /// Builds the `finding` event of a match, with its `ratings` when a taxonomy is configured.
+    fn finding_event(
+        metadata: &SynRuleMetadata,
+        classification: &Classification,
+        location: String,
+        description: Option<String>,
+    ) -> Event {
+        Event::Finding {
+            rule: metadata.name.clone(),
+            severity: format!("{:?}", metadata.severity),
+            certainty: format!("{:?}", metadata.certainty),
+            ratings: (!classification.is_default()).then(|| classification.levels()),
+            location,
+            description,
+        }
+    }
+
+    /// Emits a `finding` event for each match.
+    ///
+    /// # Arguments
+    ///
+    /// * `results` - A slice of tuples containing filenames and results with matches.
+    #[cfg(feature = "cli")]
+    fn emit_finding_events(results: &[(String, &SynAstResult)]) {
+        for (filename, ast_res) in results {
+            for match_result in &ast_res.matches {
+                let location = match match_result.get_location_metadata() {
+                    Ok(pos) => pos.get_pretty_string(),
+                    Err(_) => format!("{}: {}", filename, match_result.access_path),
+                };
+                events::emit(Self::finding_event(
+                    &ast_res.rule_metadata,
+                    &ast_res.classification,
+                    location,
+                    ast_res.render_description(match_result),
+                ));
+            }
+        }
+    }
+
     /// Groups analysis results by rule name for organized reporting.
     ///
     /// # Arguments
@@ -138,6 +256,7 @@ impl SastPrinter {
     /// # Returns
     ///
     /// A `HashMap` where keys are rule names and values are vectors of corresponding results.
+    #[cfg(feature = "cli")]
     fn group_results_by_rule_name<'a>(
         results_with_matches: &[(String, &'a SynAstResult)],
     ) -> HashMap<String, Vec<(String, &'a SynAstResult)>> {
@@ -154,11 +273,13 @@ impl SastPrinter {
         grouped_results
     }
 
-    /// Prints the source code location for each match in a set of results.
+    /// Prints the source code location for each match in a set of results, followed by
+    /// the rendered finding template when the rule selects one.
     ///
     /// # Arguments
     ///
     /// * `results` - A slice of tuples containing filenames and results to print locations for.
+    #[cfg(feature = "cli")]
     fn print_match_locations(results: &[(String, &SynAstResult)]) {
         for (filename, ast_res) in results {
             for match_result in &ast_res.matches {
@@ -166,13 +287,60 @@ impl SastPrinter {
                     Ok(pos) => println!("{}", pos.get_pretty_string()),
                     Err(_) => println!("{}: {}", filename, match_result.access_path),
                 }
+                if let Some(description) = ast_res.render_description(match_result) {
+                    println!("    -> {}", description);
+                }
+                if let Some(compiled) = Self::compiled_note(match_result) {
+                    println!("    -> bytecode: {}", compiled);
+                }
+                if let Some(programs) = Self::programs_note(match_result) {
+                    println!("    -> library of: {}", programs);
+                }
+                if let Some(fix) = &match_result.fix {
+                    println!("    -> fix: {} (--fix)", fix.description);
+                }
             }
         }
     }
 
+    /// Describes the correlation of a match with the compiled programs, if it was correlated
+    /// (`inlined`, `compiled (entrypoint in program.so)`, ...).
+    #[cfg(feature = "cli")]
+    fn compiled_note(match_result: &SynMatchResult) -> Option<String> {
+        let metadata = &match_result.metadata;
+        let status = metadata.get(COMPILED_METADATA)?.as_str()?;
+        let location = match (
+            metadata
+                .get(COMPILED_SYMBOL_METADATA)
+                .and_then(|symbol| symbol.as_str()),
+            metadata
+                .get(COMPILED_PROGRAM_METADATA)
+                .and_then(|program| program.as_str()),
+        ) {
+            (Some(symbol), Some(program)) => format!(" ({} in {})", symbol, program),
+            (None, Some(program)) => format!(" (instruction handler in {})", program),
+            _ => String::new(),
+        };
+        Some(format!("{}{}", status, location))
+    }
+
+    /// Lists the programs depending on the library crate of a match, if it is in one.
+    #[cfg(feature = "cli")]
+    fn programs_note(match_result: &SynMatchResult) -> Option<String> {
+        let programs = match_result.metadata.get(PROGRAMS_METADATA)?.as_array()?;
+        Some(
+            programs
+                .iter()
+                .filter_map(|program| program.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        )
+    }
+
     /// Displays a summary table of all matched rules.
     ///
-    /// Each row includes the rule name, severity, certainty, associated files, and total matches.
+    /// Each row includes the rule name, its rating on each axis of the taxonomy (severity and
+    /// certainty by default), associated files, and total matches.
     ///
     /// # Arguments
     ///
@@ -181,36 +349,26 @@ impl SastPrinter {
     /// # Returns
     ///
     /// An empty `Result` on success, or an error if rendering the table fails.
+    #[cfg(feature = "cli")]
     pub fn print_rules_summary(results: &[SynAstResult]) -> Result<()> {
         let mut table = Table::new();
         table.set_format(*format::consts::FORMAT_BOX_CHARS);
 
-        table.add_row(Row::new(vec![
-            Cell::new("Rule Name").style_spec("bFc"),
-            Cell::new("Severity").style_spec("bFc"),
-            Cell::new("Certainty").style_spec("bFc"),
-            Cell::new("Files").style_spec("bFc"),
-            Cell::new("Total Matches").style_spec("bFc"),
-        ]));
-
-        let severity_to_cell = |severity: &Severity| -> Cell {
-            match severity {
-                Severity::Critical => Cell::new("Critical").style_spec("Fr"),
-                Severity::High => Cell::new("High").style_spec("Fr"),
-                Severity::Medium => Cell::new("Medium").style_spec("Fy"),
-                Severity::Low => Cell::new("Low").style_spec("Fg"),
-                Severity::Unknown => Cell::new("Unknown").style_spec("Fw"),
-            }
+        let classification = match results.first() {
+            Some(result) => result.classification.clone(),
+            None => Taxonomy::default().classify(&SynRuleMetadata::default()),
         };
 
-        let certainty_to_cell = |certainty: &Certainty| -> Cell {
-            match certainty {
-                Certainty::High => Cell::new("High").style_spec("Fg"),
-                Certainty::Medium => Cell::new("Medium").style_spec("Fy"),
-                Certainty::Low => Cell::new("Low").style_spec("Fr"),
-                Certainty::Unknown => Cell::new("Unknown").style_spec("Fw"),
-            }
-        };
+        let mut header = vec![Cell::new("Rule Name").style_spec("bFc")];
+        header.extend(
+            classification
+                .ratings
+                .iter()
+                .map(|rating| Cell::new(&rating.axis).style_spec("bFc")),
+        );
+        header.push(Cell::new("Files").style_spec("bFc"));
+        header.push(Cell::new("Total Matches").style_spec("bFc"));
+        table.add_row(Row::new(header));
 
         let mut rule_groups: HashMap<String, Vec<&SynAstResult>> = HashMap::new();
 
@@ -232,13 +390,17 @@ impl SastPrinter {
                 .collect::<Vec<_>>()
                 .join(", ");
 
-            table.add_row(Row::new(vec![
-                Cell::new(&rule_name),
-                severity_to_cell(&first_result.rule_metadata.severity),
-                certainty_to_cell(&first_result.rule_metadata.certainty),
-                Cell::new(&file_list),
-                Cell::new(&total_matches.to_string()),
-            ]));
+            let mut row = vec![Cell::new(&rule_name)];
+            row.extend(
+                first_result
+                    .classification
+                    .ratings
+                    .iter()
+                    .map(|rating| Cell::new(rating.label()).style_spec(rating.style())),
+            );
+            row.push(Cell::new(&file_list));
+            row.push(Cell::new(&total_matches.to_string()));
+            table.add_row(Row::new(row));
         }
 
         table.printstd();
@@ -246,17 +408,86 @@ impl SastPrinter {
         Ok(())
     }
 
+    /// Prints the rule coverage: every loaded rule with the files it was evaluated on, its
+    /// matches, the files on which it failed and its evaluation time.
+    ///
+    /// The rules that failed on some files are shown in red, those that were never evaluated in
+    /// yellow.
+    ///
+    /// # Arguments
+    ///
+    /// * `coverage` - The rule coverage of the run.
+    #[cfg(feature = "cli")]
+    pub fn print_rule_coverage(coverage: &RuleCoverage) {
+        if coverage.is_empty() {
+            return;
+        }
+        println!("\nRule coverage:");
+        let mut table = Table::new();
+        table.set_format(*format::consts::FORMAT_BOX_CHARS);
+        table.add_row(Row::new(
+            ["Rule", "Files", "Matches", "Errors", "Time (ms)"]
+                .iter()
+                .map(|title| Cell::new(title).style_spec("bFc"))
+                .collect(),
+        ));
+        for (rule, stats) in coverage {
+            let style = if stats.errors() > 0 {
+                "Fr"
+            } else if stats.files_evaluated == 0 {
+                "Fy"
+            } else {
+                ""
+            };
+            table.add_row(Row::new(vec![
+                Cell::new(rule).style_spec(style),
+                Cell::new(&stats.files_evaluated.to_string()),
+                Cell::new(&stats.matches.to_string()),
+                Cell::new(&stats.errors().to_string()).style_spec(style),
+                Cell::new(&format!("{:.1}", stats.duration_ms)),
+            ]));
+        }
+        table.printstd();
+    }
+
+    /// Prints the engine errors: each failed evaluation of a rule, with its Starlark backtrace.
+    ///
+    /// # Arguments
+    ///
+    /// * `errors` - The failed evaluations, with the rule file of each.
+    #[cfg(feature = "cli")]
+    pub fn print_engine_errors(errors: &[(&String, &RuleFailure)]) {
+        if errors.is_empty() {
+            return;
+        }
+        println!("\nEngine errors ({}):", errors.len());
+        for (rule, failure) in errors {
+            println!("\n{}", "=".repeat(80));
+            println!("{} failed on {}: {}", rule, failure.file, failure.error);
+            if let Some(backtrace) = &failure.backtrace {
+                for line in backtrace.lines() {
+                    println!("    {}", line);
+                }
+            }
+        }
+    }
+
     /// Displays the metadata for a given rule in a structured table.
     ///
     /// # Arguments
     ///
     /// * `metadata` - The `SynRuleMetadata` object to display.
+    /// * `classification` - The rating of the rule, shown instead of its severity and certainty.
     /// * `rule_filename` - The filename of the rule being displayed.
     ///
     /// # Returns
     ///
     /// An empty `Result` on success.
-    fn print_rule_metadata(metadata: &SynRuleMetadata, rule_filename: String) -> Result<()> {
+    fn print_rule_metadata(
+        metadata: &SynRuleMetadata,
+        classification: &Classification,
+        rule_filename: String,
+    ) -> Result<()> {
         let mut table = Table::new();
         table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
 
@@ -280,38 +511,25 @@ impl SastPrinter {
             Cell::new(&metadata.author),
         ]));
 
-        let severity_text = format!("{:?}", metadata.severity);
-        let severity_cell = match metadata.severity {
-            Severity::Critical => Cell::new(&severity_text).style_spec("Fr"),
-            Severity::High => Cell::new(&severity_text).style_spec("Fr"),
-            Severity::Medium => Cell::new(&severity_text).style_spec("Fy"),
-            Severity::Low => Cell::new(&severity_text).style_spec("Fg"),
-            Severity::Unknown => Cell::new(&severity_text).style_spec("Fw"),
-        };
-
-        table.add_row(Row::new(vec![
-            Cell::new("Severity:").style_spec("b"),
-            severity_cell,
-        ]));
-
-        let certainty_text = format!("{:?}", metadata.certainty);
-        let certainty_cell = match metadata.certainty {
-            Certainty::High => Cell::new(&certainty_text).style_spec("Fg"),
-            Certainty::Medium => Cell::new(&certainty_text).style_spec("Fy"),
-            Certainty::Low => Cell::new(&certainty_text).style_spec("Fr"),
-            Certainty::Unknown => Cell::new(&certainty_text).style_spec("Fw"),
-        };
-
-        table.add_row(Row::new(vec![
-            Cell::new("Certainty:").style_spec("b"),
-            certainty_cell,
-        ]));
+        for rating in &classification.ratings {
+            table.add_row(Row::new(vec![
+                Cell::new(&format!("{}:", rating.axis)).style_spec("b"),
+                Cell::new(rating.label()).style_spec(rating.style()),
+            ]));
+        }
 
         table.add_row(Row::new(vec![
             Cell::new("Description:").style_spec("b"),
             Cell::new(&metadata.description),
         ]));
 
+        if let Some(template) = &metadata.template {
+            table.add_row(Row::new(vec![
+                Cell::new("Template:").style_spec("b"),
+                Cell::new(template),
+            ]));
+        }
+
         table.printstd();
 
         Ok(())
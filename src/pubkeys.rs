@@ -0,0 +1,584 @@
+//! Inventory of the public keys written in the sources of a project.
+//!
+//! The keys are collected from the `declare_id!("...")` and `pubkey!("...")` macros, the
+//! arrays of 32 byte literals and the string literals holding a base58 key, along with the
+//! constant or static holding them. The inventory tells the same key used at several places,
+//! and the constants of the same name holding different keys (e.g. an `ADMIN` differing
+//! between two modules), which may be a configuration drift.
+//!
+//! The report is part of the SAST findings, as two built-in rules, and of the recap.
+
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::str::FromStr;
+
+use solana_program::pubkey::Pubkey;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+use syn::{Expr, Lit, LitStr, Token};
+
+use crate::constraints::crate_root;
+use crate::parsers::syn_ast::{ColumnEncoding, SourcePosition};
+use crate::state::sast_state::{
+    Certainty, FindingTemplate, Severity, SynAstResult, SynMatchResult, SynRuleMetadata,
+};
+use crate::state::taxonomy::Taxonomy;
+
+/// Rule of the hardcoded keys in the SAST findings, also its ID for `--enable-rule` /
+/// `--disable-rule`.
+pub const HARDCODED_KEY_RULE: &str = "pubkeys/hardcoded_key";
+/// Rule of the constants of the same name holding different keys.
+pub const KEY_DRIFT_RULE: &str = "pubkeys/key_drift";
+/// The built-in rules of the key inventory.
+pub const PUBKEY_RULES: &[&str] = &[HARDCODED_KEY_RULE, KEY_DRIFT_RULE];
+
+/// Programs and sysvars whose addresses are expected in the sources.
+const WELL_KNOWN_KEYS: &[&str] = &[
+    "11111111111111111111111111111111",
+    "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+    "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb",
+    "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL",
+    "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s",
+    "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr",
+    "ComputeBudget111111111111111111111111111111",
+    "AddressLookupTab1e1111111111111111111111111",
+    "BPFLoaderUpgradeab1e11111111111111111111111",
+    "Ed25519SigVerify111111111111111111111111111",
+    "KeccakSecp256k11111111111111111111111111111",
+    "Stake11111111111111111111111111111111111111",
+    "Vote111111111111111111111111111111111111111",
+    "So11111111111111111111111111111111111111112",
+    "SysvarRent111111111111111111111111111111111",
+    "SysvarC1ock11111111111111111111111111111111",
+    "Sysvar1nstructions1111111111111111111111111",
+    "SysvarRecentB1ockHashes11111111111111111111",
+    "SysvarS1otHashes111111111111111111111111111",
+    "SysvarStakeHistory1111111111111111111111111",
+    "SysvarEpochSchedu1e111111111111111111111111",
+];
+
+/// Where a key is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyKind {
+    /// `declare_id!("...")`, the ID of the program.
+    DeclareId,
+    /// `pubkey!("...")`.
+    PubkeyMacro,
+    /// An array of 32 byte literals, e.g. `Pubkey::new_from_array([12, 34, ...])`.
+    ByteArray,
+    /// A string literal holding a base58 key, e.g. `Pubkey::from_str("...")`.
+    Base58Literal,
+}
+
+impl KeyKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyKind::DeclareId => "declare_id!",
+            KeyKind::PubkeyMacro => "pubkey!",
+            KeyKind::ByteArray => "byte array",
+            KeyKind::Base58Literal => "base58 string",
+        }
+    }
+}
+
+/// A key written in the sources.
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyOccurrence {
+    /// The key, in base58.
+    pub key: String,
+    pub kind: KeyKind,
+    /// The constant or static holding the key, e.g. `ADMIN` or `Config::ADMIN`.
+    pub name: Option<String>,
+    pub position: SourcePosition,
+}
+
+impl KeyOccurrence {
+    /// Whether the key is the address of a well-known program or sysvar.
+    pub fn is_well_known(&self) -> bool {
+        WELL_KNOWN_KEYS.contains(&self.key.as_str())
+    }
+
+    /// Names the occurrence in a description, e.g. `` `ADMIN` `` or `declare_id!`.
+    fn label(&self) -> String {
+        match &self.name {
+            Some(name) => format!("`{}`", name),
+            None => self.kind.as_str().to_string(),
+        }
+    }
+}
+
+/// Returns the key held by a string, if it is a base58 key.
+fn base58_key(value: &str) -> Option<String> {
+    // the base58 encoding of 32 bytes takes 32 to 44 characters
+    if !(32..=44).contains(&value.len()) {
+        return None;
+    }
+    Pubkey::from_str(value).ok().map(|key| key.to_string())
+}
+
+/// Returns the key held by an array of 32 byte literals, none for the default key.
+fn array_key(array: &syn::ExprArray) -> Option<String> {
+    if array.elems.len() != 32 {
+        return None;
+    }
+    let bytes: Vec<u8> = array
+        .elems
+        .iter()
+        .map(|elem| match elem {
+            Expr::Lit(lit) => match &lit.lit {
+                Lit::Int(int) => int.base10_parse::<u8>().ok(),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    if bytes.iter().all(|byte| *byte == 0) {
+        return None;
+    }
+    Some(Pubkey::new_from_array(bytes.try_into().ok()?).to_string())
+}
+
+/// Collects the keys of a file.
+struct KeyCollector<'a> {
+    source_file: &'a str,
+    /// The constant or static being visited.
+    item: Option<String>,
+    /// The type of the `impl` block being visited, for the associated constants.
+    impl_type: Option<String>,
+    occurrences: Vec<KeyOccurrence>,
+}
+
+impl KeyCollector<'_> {
+    fn push(&mut self, key: String, kind: KeyKind, span: proc_macro2::Span) {
+        self.occurrences.push(KeyOccurrence {
+            key,
+            kind,
+            name: self.item.clone(),
+            position: SourcePosition::from_span(
+                &span,
+                self.source_file.to_string(),
+                &[],
+                ColumnEncoding::Chars,
+            ),
+        });
+    }
+
+    /// Visits an item holding a value, named `name` while its value is visited.
+    fn with_item(&mut self, name: String, visit: impl FnOnce(&mut Self)) {
+        let name = match &self.impl_type {
+            Some(impl_type) => format!("{}::{}", impl_type, name),
+            None => name,
+        };
+        let previous = self.item.replace(name);
+        visit(self);
+        self.item = previous;
+    }
+}
+
+impl<'ast> Visit<'ast> for KeyCollector<'_> {
+    fn visit_item_const(&mut self, item: &'ast syn::ItemConst) {
+        self.with_item(item.ident.to_string(), |collector| {
+            syn::visit::visit_item_const(collector, item)
+        });
+    }
+
+    fn visit_item_static(&mut self, item: &'ast syn::ItemStatic) {
+        self.with_item(item.ident.to_string(), |collector| {
+            syn::visit::visit_item_static(collector, item)
+        });
+    }
+
+    fn visit_impl_item_const(&mut self, item: &'ast syn::ImplItemConst) {
+        self.with_item(item.ident.to_string(), |collector| {
+            syn::visit::visit_impl_item_const(collector, item)
+        });
+    }
+
+    fn visit_item_impl(&mut self, item: &'ast syn::ItemImpl) {
+        let impl_type = match &*item.self_ty {
+            syn::Type::Path(path) => path
+                .path
+                .segments
+                .last()
+                .map(|segment| segment.ident.to_string()),
+            _ => None,
+        };
+        let previous = std::mem::replace(&mut self.impl_type, impl_type);
+        syn::visit::visit_item_impl(self, item);
+        self.impl_type = previous;
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        let name = mac
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string())
+            .unwrap_or_default();
+        let kind = match name.as_str() {
+            "declare_id" => Some(KeyKind::DeclareId),
+            "pubkey" => Some(KeyKind::PubkeyMacro),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            if let Ok(lit) = mac.parse_body::<LitStr>() {
+                if let Some(key) = base58_key(&lit.value()) {
+                    self.push(key, kind, lit.span());
+                }
+            }
+            return;
+        }
+        // the arguments of the other macros, e.g. `require_keys_eq!(a, pubkey!("..."))`
+        if let Ok(args) = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) {
+            for arg in &args {
+                self.visit_expr(arg);
+            }
+        }
+    }
+
+    fn visit_lit_str(&mut self, lit: &'ast LitStr) {
+        if let Some(key) = base58_key(&lit.value()) {
+            self.push(key, KeyKind::Base58Literal, lit.span());
+        }
+    }
+
+    fn visit_expr_array(&mut self, array: &'ast syn::ExprArray) {
+        match array_key(array) {
+            Some(key) => self.push(key, KeyKind::ByteArray, array.span()),
+            None => syn::visit::visit_expr_array(self, array),
+        }
+    }
+}
+
+/// Constants of the same name holding different keys, or the different `declare_id!` of a
+/// crate.
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyDrift {
+    /// The name of the constants, or `declare_id!`.
+    pub name: String,
+    /// The occurrences, sorted by key then position.
+    pub occurrences: Vec<KeyOccurrence>,
+}
+
+/// The keys written in the sources of a project.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct PubkeyInventory {
+    /// The occurrences, in the order of the files.
+    pub occurrences: Vec<KeyOccurrence>,
+}
+
+impl PubkeyInventory {
+    /// Collects the keys of the files of a project.
+    ///
+    /// # Arguments
+    ///
+    /// * `files` - The path and the syntax tree of each file.
+    pub fn collect<'a>(files: impl IntoIterator<Item = (&'a str, &'a syn::File)>) -> Self {
+        let mut inventory = Self::default();
+        for (path, file) in files {
+            let mut collector = KeyCollector {
+                source_file: path,
+                item: None,
+                impl_type: None,
+                occurrences: vec![],
+            };
+            collector.visit_file(file);
+            inventory.occurrences.extend(collector.occurrences);
+        }
+        inventory
+    }
+
+    #[cfg(feature = "cli")]
+    pub fn is_empty(&self) -> bool {
+        self.occurrences.is_empty()
+    }
+
+    /// Returns the occurrences of each key, by key.
+    pub fn by_key(&self) -> BTreeMap<&str, Vec<&KeyOccurrence>> {
+        let mut keys: BTreeMap<&str, Vec<&KeyOccurrence>> = BTreeMap::new();
+        for occurrence in &self.occurrences {
+            keys.entry(&occurrence.key).or_default().push(occurrence);
+        }
+        keys
+    }
+
+    /// Finds the constants of the same name holding different keys across the project, and
+    /// the crates declaring several program IDs.
+    ///
+    /// The `declare_id!` are compared within each crate, the programs of a workspace having
+    /// different IDs.
+    pub fn drifts(&self) -> Vec<KeyDrift> {
+        let mut groups: BTreeMap<(String, String), Vec<&KeyOccurrence>> = BTreeMap::new();
+        for occurrence in &self.occurrences {
+            let group = match (&occurrence.name, occurrence.kind) {
+                (_, KeyKind::DeclareId) => (
+                    KeyKind::DeclareId.as_str().to_string(),
+                    crate_root(&occurrence.position.source_file)
+                        .to_string_lossy()
+                        .to_string(),
+                ),
+                (Some(name), _) => (name.clone(), String::new()),
+                (None, _) => continue,
+            };
+            groups.entry(group).or_default().push(occurrence);
+        }
+        groups
+            .into_iter()
+            .filter(|(_, occurrences)| {
+                occurrences
+                    .iter()
+                    .map(|occurrence| &occurrence.key)
+                    .collect::<BTreeSet<_>>()
+                    .len()
+                    > 1
+            })
+            .map(|((name, _), occurrences)| {
+                let mut occurrences: Vec<KeyOccurrence> =
+                    occurrences.into_iter().cloned().collect();
+                occurrences.sort_by(|a, b| {
+                    (&a.key, &a.position.source_file, a.position.start_line).cmp(&(
+                        &b.key,
+                        &b.position.source_file,
+                        b.position.start_line,
+                    ))
+                });
+                KeyDrift { name, occurrences }
+            })
+            .collect()
+    }
+
+    /// Converts the inventory to SAST results, one per file and rule, the match of each key
+    /// being described by its `description` metadata.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether a rule (one of [`PUBKEY_RULES`]) is enabled.
+    ///
+    /// # Returns
+    ///
+    /// The results, by file.
+    pub fn sast_results(
+        &self,
+        enabled: impl Fn(&str) -> bool,
+    ) -> BTreeMap<String, Vec<SynAstResult>> {
+        let mut matches: Vec<(&str, SynMatchResult)> = vec![];
+        if enabled(HARDCODED_KEY_RULE) {
+            let by_key = self.by_key();
+            for occurrence in &self.occurrences {
+                if occurrence.kind == KeyKind::DeclareId || occurrence.is_well_known() {
+                    continue;
+                }
+                let mut description = format!(
+                    "hardcoded key {} ({})",
+                    occurrence.key,
+                    match &occurrence.name {
+                        Some(name) => format!("`{}`, {}", name, occurrence.kind.as_str()),
+                        None => occurrence.kind.as_str().to_string(),
+                    }
+                );
+                let others = by_key[occurrence.key.as_str()].len() - 1;
+                if others > 0 {
+                    description.push_str(&format!(", also written at {} other place(s)", others));
+                }
+                matches.push((HARDCODED_KEY_RULE, key_match(occurrence, description)));
+            }
+        }
+        if enabled(KEY_DRIFT_RULE) {
+            for drift in self.drifts() {
+                for occurrence in &drift.occurrences {
+                    let others: Vec<String> = drift
+                        .occurrences
+                        .iter()
+                        .filter(|other| other.key != occurrence.key)
+                        .map(|other| {
+                            format!(
+                                "{} at {}:{}",
+                                other.key, other.position.source_file, other.position.start_line
+                            )
+                        })
+                        .collect();
+                    let description = format!(
+                        "{} is {} here, but {}",
+                        occurrence.label(),
+                        occurrence.key,
+                        others.join(", ")
+                    );
+                    matches.push((KEY_DRIFT_RULE, key_match(occurrence, description)));
+                }
+            }
+        }
+
+        let mut by_file: BTreeMap<(String, &str), Vec<SynMatchResult>> = BTreeMap::new();
+        for (rule, match_result) in matches {
+            let file = match_result
+                .get_location_metadata()
+                .map(|position| position.source_file)
+                .unwrap_or_default();
+            by_file.entry((file, rule)).or_default().push(match_result);
+        }
+        let mut results: BTreeMap<String, Vec<SynAstResult>> = BTreeMap::new();
+        for ((file, rule), matches) in by_file {
+            let rule_metadata = if rule == HARDCODED_KEY_RULE {
+                hardcoded_key_rule_metadata()
+            } else {
+                key_drift_rule_metadata()
+            };
+            results.entry(file).or_default().push(SynAstResult {
+                rule_filename: rule.to_string(),
+                result: serde_json::json!({ "matches": matches }).to_string(),
+                matches,
+                classification: Taxonomy::default().classify(&rule_metadata),
+                rule_metadata,
+                finding_template: Some(FindingTemplate {
+                    description: "{description}".to_string(),
+                    params: HashMap::new(),
+                }),
+            });
+        }
+        results
+    }
+}
+
+/// Metadata of the hardcoded key rule, in the same form as the SAST rules.
+pub fn hardcoded_key_rule_metadata() -> SynRuleMetadata {
+    SynRuleMetadata {
+        version: "0.1.0".to_string(),
+        author: "sol-azy".to_string(),
+        name: "Hardcoded public key".to_string(),
+        severity: Severity::Low,
+        certainty: Certainty::Low,
+        description: "A public key other than a well-known program or sysvar is written in \
+            the sources, e.g. an admin key: check that it is the expected one for the \
+            deployed cluster, and that it doesn't have to be rotated."
+            .to_string(),
+        ..SynRuleMetadata::default()
+    }
+}
+
+/// Metadata of the key drift rule, in the same form as the SAST rules.
+pub fn key_drift_rule_metadata() -> SynRuleMetadata {
+    SynRuleMetadata {
+        version: "0.1.0".to_string(),
+        author: "sol-azy".to_string(),
+        name: "Public key drift".to_string(),
+        severity: Severity::Medium,
+        certainty: Certainty::Low,
+        description: "Constants of the same name hold different public keys, or a crate \
+            declares several program IDs: one of them may be stale, or meant for another \
+            cluster."
+            .to_string(),
+        ..SynRuleMetadata::default()
+    }
+}
+
+/// Builds the match of a key.
+fn key_match(occurrence: &KeyOccurrence, description: String) -> SynMatchResult {
+    SynMatchResult {
+        children: vec![],
+        access_path: occurrence.name.clone().unwrap_or_default(),
+        metadata: HashMap::from([
+            (
+                "position".to_string(),
+                serde_json::to_value(&occurrence.position).unwrap_or_default(),
+            ),
+            ("description".to_string(), description.into()),
+        ]),
+        ident: occurrence.key.clone(),
+        parent: occurrence.name.clone().unwrap_or_default(),
+        fix: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADMIN: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+    const OTHER_ADMIN: &str = "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS";
+
+    #[test]
+    fn test_pubkey_inventory() {
+        let program = syn::parse_file(&format!(
+            r#"
+            declare_id!("{OTHER_ADMIN}");
+
+            pub const ADMIN: Pubkey = pubkey!("{ADMIN}");
+            pub const TOKEN: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+            impl Config {{
+                pub const FEE_OWNER: [u8; 32] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14,
+                    15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32];
+            }}
+
+            pub fn check(admin: &Pubkey) -> Result<()> {{
+                require_keys_eq!(*admin, Pubkey::from_str("{ADMIN}").unwrap());
+                let _ = "not a key, even if long enough to be one";
+                Ok(())
+            }}
+            "#
+        ))
+        .unwrap();
+        let module = syn::parse_file(&format!(
+            r#"pub const ADMIN: Pubkey = pubkey!("{OTHER_ADMIN}");"#
+        ))
+        .unwrap();
+        let inventory = PubkeyInventory::collect([
+            ("programs/p/src/lib.rs", &program),
+            ("programs/p/src/admin.rs", &module),
+        ]);
+
+        let found: Vec<(KeyKind, Option<&str>)> = inventory
+            .occurrences
+            .iter()
+            .map(|o| (o.kind, o.name.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (KeyKind::DeclareId, None),
+                (KeyKind::PubkeyMacro, Some("ADMIN")),
+                (KeyKind::Base58Literal, Some("TOKEN")),
+                (KeyKind::ByteArray, Some("Config::FEE_OWNER")),
+                (KeyKind::Base58Literal, None),
+                (KeyKind::PubkeyMacro, Some("ADMIN")),
+            ]
+        );
+        assert_eq!(inventory.occurrences[1].position.start_line, 4);
+        assert_eq!(inventory.by_key()[ADMIN].len(), 2);
+        assert_eq!(inventory.by_key()[OTHER_ADMIN].len(), 2);
+
+        let drifts = inventory.drifts();
+        assert_eq!(drifts.len(), 1);
+        assert_eq!(drifts[0].name, "ADMIN");
+        assert_eq!(drifts[0].occurrences[0].key, ADMIN);
+
+        let results = inventory.sast_results(|_| true);
+        let lib = &results["programs/p/src/lib.rs"];
+        let hardcoded = lib
+            .iter()
+            .find(|result| result.rule_filename == HARDCODED_KEY_RULE)
+            .unwrap();
+        // neither the program ID nor the token program
+        assert_eq!(hardcoded.matches.len(), 3);
+        assert_eq!(
+            hardcoded.render_description(&hardcoded.matches[0]).unwrap(),
+            format!(
+                "hardcoded key {} (`ADMIN`, pubkey!), also written at 1 other place(s)",
+                ADMIN
+            )
+        );
+        let drift = &results["programs/p/src/admin.rs"]
+            .iter()
+            .find(|result| result.rule_filename == KEY_DRIFT_RULE)
+            .unwrap();
+        assert_eq!(
+            drift.render_description(&drift.matches[0]).unwrap(),
+            format!(
+                "`ADMIN` is {} here, but {} at programs/p/src/lib.rs:4",
+                OTHER_ADMIN, ADMIN
+            )
+        );
+    }
+}
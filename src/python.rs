@@ -0,0 +1,166 @@
+//! Python bindings of sol-azy (`solazy` module), built with the `python` feature.
+//!
+//! They expose the scan, the disassembly and the fetcher to notebooks without going through
+//! the CLI and its output files. The results are lists of flat dicts (one per finding or
+//! instruction), ready for `pandas.DataFrame(...)`:
+//!
+//! ```python
+//! import pandas as pd
+//! import solazy
+//!
+//! findings = pd.DataFrame(solazy.scan_project("./my_anchor_project"))
+//! insns = pd.DataFrame(solazy.disassemble("./program.so"))
+//! program = solazy.fetch_program("4MEX8vDCZzAxQkuyd6onJCTeFdof6c1HJgznEtCGqA1N")
+//! ```
+//!
+//! The package is built with `maturin develop --release` (see `pyproject.toml`).
+
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use solana_sbpf::elf::Executable;
+use solana_sbpf::static_analysis::Analysis;
+use test_utils::TestContextObject;
+
+use crate::commands::sast_command::{self, SastCmd};
+use crate::engines::starlark_engine::{RuleFilter, RuleLimits, RuleTrust};
+use crate::fetcher::rpc::RpcClient;
+use crate::fetcher::{fetch_account_contents, MAINNET_RPC};
+use crate::reverse::elf_sections::has_symbol_table;
+use crate::reverse::new_loader;
+use crate::reverse::rusteq::translate_to_rust;
+use crate::reverse::sanitize::{disassemble_instruction, sanitize_labels};
+use crate::state::sast_state::finding_rows;
+
+fn to_py_err(e: anyhow::Error) -> PyErr {
+    PyRuntimeError::new_err(format!("{:#}", e))
+}
+
+/// Converts JSON to the matching Python objects (dicts, lists, str, int, float, bool, None).
+fn to_python(py: Python<'_>, value: &Value) -> PyResult<PyObject> {
+    let json = py.import("json")?;
+    Ok(json.call_method1("loads", (value.to_string(),))?.unbind())
+}
+
+/// Runs the SAST rules on a project and returns its findings, one dict per match.
+///
+/// The project is only parsed (`--syn-scan-only`) unless `syn_scan_only=False`, which builds
+/// it first like `sol-azy sast`.
+#[pyfunction]
+#[pyo3(signature = (target_dir, rules_dir=None, use_internal_rules=true, syn_scan_only=true, recursive=false))]
+fn scan_project(
+    py: Python<'_>,
+    target_dir: String,
+    rules_dir: Option<String>,
+    use_internal_rules: bool,
+    syn_scan_only: bool,
+    recursive: bool,
+) -> PyResult<PyObject> {
+    let cmd = SastCmd {
+        target_dir,
+        rules_dir,
+        rules_git: None,
+        syn_scan_only,
+        use_internal_rules,
+        recursive,
+        column_encoding: Default::default(),
+        rule_filter: RuleFilter::default(),
+        rule_trust: RuleTrust::default(),
+        rule_limits: RuleLimits::default(),
+        bytecodes: vec![],
+        build_manifest: None,
+        export_db: None,
+        taxonomy: None,
+        strict: false,
+        tui: false,
+        baseline: None,
+        advisories: None,
+        cfg: None,
+        fix: false,
+        dry_run: false,
+        timing_report: None,
+        max_file_size: None,
+        spill_ast: false,
+    };
+    let states = sast_command::run(&cmd).map_err(to_py_err)?;
+    to_python(py, &Value::Array(finding_rows(&states)))
+}
+
+/// Disassembles a compiled program, one dict per instruction with its function and its Rust
+/// equivalent.
+///
+/// The symbols are used when the ELF has a symbol table, unless `labeling` says otherwise.
+#[pyfunction]
+#[pyo3(signature = (path, labeling=None))]
+fn disassemble(py: Python<'_>, path: String, labeling: Option<bool>) -> PyResult<PyObject> {
+    let elf = std::fs::read(&path).map_err(|e| to_py_err(anyhow::anyhow!("{}: {}", path, e)))?;
+    let loader =
+        new_loader(labeling.unwrap_or_else(|| has_symbol_table(&elf))).map_err(to_py_err)?;
+    let executable = Executable::<TestContextObject>::from_elf(&elf, loader)
+        .map_err(|e| to_py_err(anyhow::anyhow!("Failed to construct executable: {:?}", e)))?;
+    let mut analysis = Analysis::from_executable(&executable)
+        .map_err(|e| to_py_err(anyhow::anyhow!("Failed to analyze the program: {:?}", e)))?;
+    sanitize_labels(&mut analysis);
+    let sbpf_version = executable.get_sbpf_version();
+
+    let rows = analysis
+        .instructions
+        .iter()
+        .enumerate()
+        .map(|(pc, insn)| {
+            let function = analysis
+                .functions
+                .range(..=insn.ptr)
+                .next_back()
+                .map(|(_, (_, name))| name.clone());
+            json!({
+                "pc": insn.ptr,
+                "opcode": insn.opc,
+                "function": function,
+                "instruction": disassemble_instruction(&analysis, insn, pc),
+                "rust": translate_to_rust(insn, sbpf_version),
+            })
+        })
+        .collect();
+    to_python(py, &Value::Array(rows))
+}
+
+/// Fetches an on-chain program (or, with `executable=False` in the result, a data account).
+///
+/// Returns a dict with the `program_id`, whether the account is `executable`, the `size` and
+/// `sha256` of the data and the `data` itself as `bytes` (the ELF for a program).
+#[pyfunction]
+#[pyo3(signature = (program_id, rpc_url=None))]
+fn fetch_program(
+    py: Python<'_>,
+    program_id: String,
+    rpc_url: Option<String>,
+) -> PyResult<PyObject> {
+    let rpc = RpcClient::new(&rpc_url.unwrap_or_else(|| MAINNET_RPC.to_string()));
+    let fetched = py
+        .allow_threads(|| {
+            tokio::runtime::Runtime::new()?.block_on(fetch_account_contents(&rpc, &program_id))
+        })
+        .map_err(to_py_err)?;
+
+    let result = PyDict::new(py);
+    result.set_item("program_id", &program_id)?;
+    result.set_item("executable", fetched.executable)?;
+    result.set_item("size", fetched.data.len())?;
+    result.set_item("sha256", hex::encode(Sha256::digest(&fetched.data)))?;
+    result.set_item("data", PyBytes::new(py, &fetched.data))?;
+    Ok(result.into_any().unbind())
+}
+
+/// The `solazy` Python module.
+#[pymodule]
+#[pyo3(name = "solazy")]
+fn solazy(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(scan_project, module)?)?;
+    module.add_function(wrap_pyfunction!(disassemble, module)?)?;
+    module.add_function(wrap_pyfunction!(fetch_program, module)?)?;
+    module.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    Ok(())
+}
@@ -0,0 +1,319 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+use super::idl::{flatten_accounts, Idl};
+use super::parser::{
+    account_type, extract_accounts_structs, map_instruction_to_struct, unboxed_type,
+    AccountsStructMap, BumpSource, FieldMeta,
+};
+use super::rows::merged_sources;
+
+/// Answer to a checklist item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Check {
+    Yes,
+    /// The fields (or reasons) failing the item.
+    No(Vec<String>),
+    /// What the recap can't tell, to check by hand.
+    Unknown(Vec<String>),
+    /// Nothing to check (e.g. no `realloc`).
+    NotApplicable,
+}
+
+impl Check {
+    /// Builds the answer from the failures and the unknowns found: `No` wins over `Unknown`.
+    fn from_findings(failures: Vec<String>, unknowns: Vec<String>) -> Self {
+        if !failures.is_empty() {
+            Check::No(failures)
+        } else if !unknowns.is_empty() {
+            Check::Unknown(unknowns)
+        } else {
+            Check::Yes
+        }
+    }
+}
+
+/// The threat-model checklist of an instruction.
+#[derive(Debug)]
+pub(crate) struct ChecklistRow {
+    pub(crate) instruction: String,
+    /// At least one signer checked by the source.
+    pub(crate) signer: Check,
+    /// Every writable account constrained.
+    pub(crate) writables_constrained: Check,
+    /// Canonical bumps, seeds not shared with another account type.
+    pub(crate) seeds_unique: Check,
+    /// `realloc::zero = true`.
+    pub(crate) realloc_zeroed: Check,
+    /// Program accounts pinned to a program id.
+    pub(crate) cpi_targets: Check,
+}
+
+impl ChecklistRow {
+    /// The items with their labels, in rendering order.
+    pub(crate) fn items(&self) -> [(&'static str, &Check); 5] {
+        [
+            ("Signer present", &self.signer),
+            ("Writables constrained", &self.writables_constrained),
+            ("PDA seeds unique", &self.seeds_unique),
+            ("Realloc zeroed", &self.realloc_zeroed),
+            ("CPI targets constrained", &self.cpi_targets),
+        ]
+    }
+}
+
+/// Lists the fields of an accounts struct, going through the nested accounts structs.
+/// `visited` guards against recursive structs.
+fn struct_fields<'a>(
+    struct_name: &str,
+    structs: &'a AccountsStructMap,
+    visited: &mut Vec<String>,
+) -> Vec<&'a FieldMeta> {
+    let Some(fields) = structs.get(struct_name) else {
+        return vec![];
+    };
+    if visited.iter().any(|visited| visited == struct_name) {
+        return vec![];
+    }
+    visited.push(struct_name.to_string());
+    let mut out = vec![];
+    for meta in fields.values() {
+        if structs.contains_key(account_type(&meta.ty)) {
+            out.extend(struct_fields(account_type(&meta.ty), structs, visited));
+        } else {
+            out.push(meta);
+        }
+    }
+    visited.pop();
+    out.sort_by(|a, b| a.name.cmp(&b.name));
+    out
+}
+
+/// Whether a writable account is tied to something: a constraint, seeds, its own signature,
+/// or its creation in the instruction.
+fn is_constrained(meta: &FieldMeta) -> bool {
+    meta.has_address
+        || meta.has_owner
+        || meta.has_has_one
+        || meta.has_constraint
+        || meta.has_seeds
+        || meta.has_spl
+        || meta.is_signer
+        || meta.is_init
+}
+
+fn check_writables(fields: &[&FieldMeta], idl_writables: &BTreeSet<String>, known: bool) -> Check {
+    let mut writables: BTreeSet<&str> = idl_writables.iter().map(String::as_str).collect();
+    writables.extend(
+        fields
+            .iter()
+            .filter(|meta| meta.is_writable)
+            .map(|meta| meta.name.as_str()),
+    );
+    if writables.is_empty() {
+        return Check::NotApplicable;
+    }
+    if !known {
+        return Check::Unknown(vec!["no accounts struct found".to_string()]);
+    }
+    let by_name: HashMap<&str, &FieldMeta> = fields
+        .iter()
+        .map(|meta| (meta.name.as_str(), *meta))
+        .collect();
+    let mut failures = vec![];
+    let mut unknowns = vec![];
+    for name in writables {
+        match by_name.get(name) {
+            Some(meta) if is_constrained(meta) => {}
+            Some(_) => failures.push(name.to_string()),
+            None => unknowns.push(format!("{} (not in source)", name)),
+        }
+    }
+    Check::from_findings(failures, unknowns)
+}
+
+fn check_seeds(fields: &[&FieldMeta], structs: &AccountsStructMap) -> Check {
+    let seeded: Vec<&&FieldMeta> = fields.iter().filter(|meta| meta.has_seeds).collect();
+    if seeded.is_empty() {
+        return Check::NotApplicable;
+    }
+    let mut failures = vec![];
+    let mut unknowns = vec![];
+    for meta in seeded {
+        match &meta.bump {
+            BumpSource::Missing => failures.push(format!("{} (no bump)", meta.name)),
+            BumpSource::Argument(expr) => failures.push(format!("{} (bump = {})", meta.name, expr)),
+            BumpSource::Expr => unknowns.push(format!("{} (stored bump)", meta.name)),
+            BumpSource::Canonical => {}
+        }
+        let Some(seeds) = &meta.seeds else {
+            continue;
+        };
+        // the same seeds for another account type: both PDAs are the same address
+        let clashes: BTreeSet<&str> = structs
+            .values()
+            .flat_map(|fields| fields.values())
+            .filter(|other| {
+                other.seeds.as_ref() == Some(seeds)
+                    && unboxed_type(&other.ty) != unboxed_type(&meta.ty)
+            })
+            .map(|other| other.name.as_str())
+            .collect();
+        if !clashes.is_empty() {
+            failures.push(format!(
+                "{} (seeds shared with {})",
+                meta.name,
+                clashes.into_iter().collect::<Vec<_>>().join(", ")
+            ));
+        } else if !seeds.contains("b\"") {
+            unknowns.push(format!("{} (no constant seed)", meta.name));
+        }
+    }
+    Check::from_findings(failures, unknowns)
+}
+
+fn check_realloc(fields: &[&FieldMeta]) -> Check {
+    let reallocated: Vec<&&FieldMeta> = fields.iter().filter(|meta| meta.has_realloc).collect();
+    if reallocated.is_empty() {
+        return Check::NotApplicable;
+    }
+    let mut failures = vec![];
+    let mut unknowns = vec![];
+    for meta in reallocated {
+        match meta.realloc_zero.as_deref() {
+            Some("true") => {}
+            Some("false") => failures.push(meta.name.clone()),
+            Some(expr) => unknowns.push(format!("{} (realloc::zero = {})", meta.name, expr)),
+            None => unknowns.push(format!("{} (no realloc::zero)", meta.name)),
+        }
+    }
+    Check::from_findings(failures, unknowns)
+}
+
+fn check_cpi_targets(fields: &[&FieldMeta]) -> Check {
+    let programs: Vec<&&FieldMeta> = fields
+        .iter()
+        .filter(|meta| match account_type(&meta.ty) {
+            "Program" | "Interface" => true,
+            "AccountInfo" | "UncheckedAccount" => meta.name.ends_with("program"),
+            _ => false,
+        })
+        .collect();
+    if programs.is_empty() {
+        return Check::NotApplicable;
+    }
+    let mut failures = vec![];
+    let mut unknowns = vec![];
+    for meta in programs {
+        if matches!(account_type(&meta.ty), "Program" | "Interface") || meta.has_address {
+            continue;
+        }
+        if meta.has_constraint || meta.has_owner {
+            unknowns.push(format!("{} (custom constraint)", meta.name));
+        } else {
+            failures.push(meta.name.clone());
+        }
+    }
+    Check::from_findings(failures, unknowns)
+}
+
+/// Builds the threat-model checklist of each instruction of a program, from the IDL flags and
+/// the `#[derive(Accounts)]` struct of the instruction.
+pub(crate) fn build_checklist(idl: &Idl, crate_root: &Path) -> Vec<ChecklistRow> {
+    let merged_src = merged_sources(crate_root);
+    let instr_to_struct = map_instruction_to_struct(&merged_src);
+    let structs = extract_accounts_structs(&merged_src);
+
+    let mut rows = vec![];
+    for ix in &idl.instructions {
+        let mut flat = vec![];
+        flatten_accounts(&ix.accounts, &mut flat);
+        let idl_signers: Vec<&str> = flat
+            .iter()
+            .filter(|(_, is_signer, _)| *is_signer)
+            .map(|(name, _, _)| name.as_str())
+            .collect();
+        let idl_writables: BTreeSet<String> = flat
+            .iter()
+            .filter(|(_, _, is_writable)| *is_writable)
+            .map(|(name, _, _)| name.clone())
+            .collect();
+
+        let struct_name = instr_to_struct.get(&ix.name);
+        let known = struct_name.is_some_and(|name| structs.contains_key(name));
+        let fields = struct_name
+            .map(|name| struct_fields(name, &structs, &mut Vec::new()))
+            .unwrap_or_default();
+        let unknown = || Check::Unknown(vec!["no accounts struct found".to_string()]);
+
+        // a signer only claimed by the IDL is not checked by the program
+        let signer = if !known {
+            unknown()
+        } else if fields.iter().any(|meta| meta.is_signer) {
+            Check::Yes
+        } else {
+            Check::No(
+                idl_signers
+                    .iter()
+                    .map(|name| format!("{} (signer in IDL only)", name))
+                    .collect(),
+            )
+        };
+        rows.push(ChecklistRow {
+            instruction: ix.name.clone(),
+            signer,
+            writables_constrained: check_writables(&fields, &idl_writables, known),
+            seeds_unique: if known {
+                check_seeds(&fields, &structs)
+            } else {
+                unknown()
+            },
+            realloc_zeroed: if known {
+                check_realloc(&fields)
+            } else {
+                unknown()
+            },
+            cpi_targets: if known {
+                check_cpi_targets(&fields)
+            } else {
+                unknown()
+            },
+        });
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_checklist, Check};
+    use crate::recap::idl::load_idl;
+    use std::path::Path;
+
+    #[test]
+    fn builds_checklist_for_fixture() {
+        let root = Path::new("test_cases/recap_anchor");
+        let idl = load_idl(&root.join("idl/recap_anchor.json")).unwrap();
+        let checklist = build_checklist(&idl, &root.join("programs/recap_anchor"));
+        let row = |name: &str| checklist.iter().find(|r| r.instruction == name).unwrap();
+
+        let deposit = row("deposit");
+        assert_eq!(deposit.signer, Check::Yes);
+        // `vault` is seeded, `user_state` created and `user` signs
+        assert_eq!(deposit.writables_constrained, Check::Yes);
+        assert_eq!(
+            deposit.seeds_unique,
+            Check::No(vec!["vault (bump = vault_bump)".to_string()])
+        );
+        assert_eq!(deposit.realloc_zeroed, Check::NotApplicable);
+        assert_eq!(deposit.cpi_targets, Check::Yes);
+
+        // the IDL claims `authority` signs, the source doesn't check it
+        let transfer = row("transfer");
+        assert_eq!(
+            transfer.signer,
+            Check::No(vec!["authority (signer in IDL only)".to_string()])
+        );
+        assert_eq!(transfer.writables_constrained, Check::Yes);
+        assert_eq!(transfer.seeds_unique, Check::NotApplicable);
+    }
+}
@@ -0,0 +1,630 @@
+//! Events and log lines of the instructions of a crate: the `emit!` / `emit_cpi!` and `msg!`
+//! of the instruction functions and of the functions they call.
+//!
+//! The calls are resolved by name within the crate:
+//!
+//! - `module::function(...)` — the free functions of a module whose path ends with `module`,
+//! - `function(...)` — the function of the same module, else the only one of that name,
+//! - `Type::function(...)` / `Self::function(...)` — the methods of the impls of `Type`,
+//! - `receiver.method(...)` — the methods of the impls of the same type for `self`, else the
+//!   only method of that name, else the one of the accounts struct of the instruction (e.g.
+//!   `ctx.accounts.process()`).
+//!
+//! The functions of other crates, the calls through traits or function pointers and the
+//! calls inside macros are not followed.
+//!
+//! The function bodies run by each instruction are also listed for the other summaries
+//! following the calls (see [`instruction_bodies`]).
+
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+use syn::{Expr, ImplItem, Item, Token};
+
+use super::synth_idl::context_accounts;
+use crate::parsers::anchor_accounts::items_of;
+
+/// Depth of the calls followed from an instruction.
+const MAX_CALL_DEPTH: usize = 8;
+
+/// An `emit!` or `emit_cpi!` of an event.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Emission {
+    pub(crate) event: String,
+    /// The fields set in the event, in the order of the struct expression.
+    pub(crate) fields: Vec<String>,
+    /// Emitted through a self-CPI (`emit_cpi!`), so readable from the instruction data.
+    pub(crate) cpi: bool,
+}
+
+/// A `msg!` log line.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct LogLine {
+    /// The format string, empty for `msg!(expr)`.
+    pub(crate) format: String,
+    /// The arguments, explicit or captured by the format string (`{amount}`).
+    pub(crate) args: Vec<String>,
+}
+
+/// What an instruction makes observable off-chain.
+#[derive(Debug, Default)]
+pub(crate) struct InstructionEvents {
+    pub(crate) instruction: String,
+    pub(crate) events: BTreeSet<Emission>,
+    pub(crate) logs: BTreeSet<LogLine>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct EventReport {
+    /// The instructions, in the order of the `#[program]` module.
+    pub(crate) instructions: Vec<InstructionEvents>,
+    /// The `#[event]` structs that no instruction emits.
+    pub(crate) unused_events: Vec<String>,
+}
+
+/// A function of the crate.
+struct Function<'a> {
+    /// Path of its module, from the crate root, e.g. `["instructions", "deposit"]`.
+    module: Vec<String>,
+    /// Name of the type of its impl, for a method.
+    self_ty: Option<String>,
+    name: String,
+    /// The accounts struct of its `Context<...>`, for an instruction.
+    accounts: Option<String>,
+    instruction: bool,
+    block: &'a syn::Block,
+}
+
+/// Returns the module path of a file from the crate root: the components following `src`,
+/// without `lib.rs`, `main.rs` and `mod.rs`.
+fn module_of(path: &str) -> Vec<String> {
+    let components: Vec<String> = Path::new(path)
+        .with_extension("")
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().to_string())
+        .collect();
+    let start = components
+        .iter()
+        .rposition(|component| component == "src")
+        .map_or(0, |index| index + 1);
+    let mut module = components[start..].to_vec();
+    if matches!(
+        module.last().map(String::as_str),
+        Some("lib" | "main" | "mod")
+    ) {
+        module.pop();
+    }
+    module
+}
+
+/// Returns the name of a type, without path nor generic arguments.
+fn type_name(ty: &syn::Type) -> Option<String> {
+    match ty {
+        syn::Type::Path(path) => path.path.segments.last().map(|s| s.ident.to_string()),
+        _ => None,
+    }
+}
+
+/// Lists the functions of the items of a module, going through the inline modules.
+fn collect_functions<'a>(
+    items: &'a [Item],
+    module: &[String],
+    in_program: bool,
+    functions: &mut Vec<Function<'a>>,
+) {
+    for item in items {
+        match item {
+            Item::Fn(function) => functions.push(Function {
+                module: module.to_vec(),
+                self_ty: None,
+                name: function.sig.ident.to_string(),
+                accounts: context_accounts(function),
+                instruction: in_program && matches!(function.vis, syn::Visibility::Public(_)),
+                block: &function.block,
+            }),
+            Item::Impl(item) => {
+                let self_ty = type_name(&item.self_ty);
+                for impl_item in &item.items {
+                    if let ImplItem::Fn(method) = impl_item {
+                        functions.push(Function {
+                            module: module.to_vec(),
+                            self_ty: self_ty.clone(),
+                            name: method.sig.ident.to_string(),
+                            accounts: None,
+                            instruction: false,
+                            block: &method.block,
+                        });
+                    }
+                }
+            }
+            Item::Mod(item) => {
+                if let Some((_, content)) = &item.content {
+                    let mut inner = module.to_vec();
+                    inner.push(item.ident.to_string());
+                    let is_program = item
+                        .attrs
+                        .iter()
+                        .any(|attr| attr.path().is_ident("program"));
+                    collect_functions(content, &inner, is_program, functions);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns the source text of a node, e.g. `ctx.accounts.user.key()`.
+fn source_text(node: &impl Spanned) -> String {
+    node.span().source_text().unwrap_or_else(|| "?".to_string())
+}
+
+/// Returns the arguments captured by a format string, e.g. `amount` for `"{amount:?}"`.
+fn captured_args(format: &str) -> Vec<String> {
+    let mut args = vec![];
+    let mut rest = format;
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start + 1..];
+        // `{{` is an escaped brace
+        if let Some(escaped) = rest.strip_prefix('{') {
+            rest = escaped;
+            continue;
+        }
+        let end = rest.find('}').unwrap_or(rest.len());
+        let name = rest[..end].split(':').next().unwrap_or_default().trim();
+        if name.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+            args.push(name.to_string());
+        }
+        rest = &rest[end..];
+    }
+    args
+}
+
+/// The events, logs and calls of a function body.
+#[derive(Default)]
+struct BodyVisitor {
+    events: Vec<Emission>,
+    logs: Vec<LogLine>,
+    /// The path segments of the called functions.
+    calls: Vec<Vec<String>>,
+    /// The called methods, with whether the receiver is `self`.
+    method_calls: Vec<(String, bool)>,
+}
+
+impl<'ast> Visit<'ast> for BodyVisitor {
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        let Some(name) = mac.path.segments.last().map(|s| s.ident.to_string()) else {
+            return;
+        };
+        match name.as_str() {
+            "emit" | "emit_cpi" => {
+                let Ok(event) = mac.parse_body::<Expr>() else {
+                    return;
+                };
+                let emission = match &event {
+                    Expr::Struct(event) => Emission {
+                        event: event
+                            .path
+                            .segments
+                            .last()
+                            .map(|s| s.ident.to_string())
+                            .unwrap_or_default(),
+                        fields: event
+                            .fields
+                            .iter()
+                            .map(|field| match &field.member {
+                                syn::Member::Named(name) => name.to_string(),
+                                syn::Member::Unnamed(index) => index.index.to_string(),
+                            })
+                            .collect(),
+                        cpi: name == "emit_cpi",
+                    },
+                    event => Emission {
+                        event: source_text(event),
+                        fields: vec![],
+                        cpi: name == "emit_cpi",
+                    },
+                };
+                self.events.push(emission);
+            }
+            "msg" => {
+                let Ok(args) = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+                else {
+                    return;
+                };
+                let mut args = args.iter();
+                let format = match args.clone().next() {
+                    Some(Expr::Lit(lit)) => match &lit.lit {
+                        syn::Lit::Str(format) => {
+                            args.next();
+                            format.value()
+                        }
+                        _ => String::new(),
+                    },
+                    _ => String::new(),
+                };
+                let mut line_args = captured_args(&format);
+                line_args.extend(args.map(source_text));
+                self.logs.push(LogLine {
+                    format,
+                    args: line_args,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        if let Expr::Path(path) = &*call.func {
+            self.calls.push(
+                path.path
+                    .segments
+                    .iter()
+                    .map(|segment| segment.ident.to_string())
+                    .collect(),
+            );
+        }
+        syn::visit::visit_expr_call(self, call);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        let on_self = matches!(&*call.receiver, Expr::Path(path) if path.path.is_ident("self"));
+        self.method_calls.push((call.method.to_string(), on_self));
+        syn::visit::visit_expr_method_call(self, call);
+    }
+}
+
+/// Resolves the calls of a function to the functions of the crate.
+struct Resolver<'a, 'f> {
+    functions: &'f [Function<'a>],
+}
+
+impl Resolver<'_, '_> {
+    /// Returns the indices of the functions `segments` may call from `caller`.
+    fn resolve_call(&self, caller: &Function, segments: &[String]) -> Vec<usize> {
+        let Some((name, qualifier)) = segments.split_last() else {
+            return vec![];
+        };
+        let qualifier: Vec<&String> = qualifier
+            .iter()
+            .filter(|segment| !matches!(segment.as_str(), "crate" | "self" | "super"))
+            .collect();
+        let named = || {
+            self.functions
+                .iter()
+                .enumerate()
+                .filter(move |(_, function)| &function.name == name)
+        };
+        match qualifier.last() {
+            Some(ty) if *ty == "Self" => named()
+                .filter(|(_, function)| {
+                    function.self_ty.is_some() && function.self_ty == caller.self_ty
+                })
+                .map(|(index, _)| index)
+                .collect(),
+            Some(ty) if ty.starts_with(char::is_uppercase) => named()
+                .filter(|(_, function)| function.self_ty.as_ref() == Some(ty))
+                .map(|(index, _)| index)
+                .collect(),
+            Some(_) => named()
+                .filter(|(_, function)| {
+                    function.self_ty.is_none()
+                        && function.module.len() >= qualifier.len()
+                        && function.module[function.module.len() - qualifier.len()..]
+                            .iter()
+                            .zip(&qualifier)
+                            .all(|(a, b)| a == *b)
+                })
+                .map(|(index, _)| index)
+                .collect(),
+            None => {
+                let free: Vec<(usize, &Function)> = named()
+                    .filter(|(_, function)| function.self_ty.is_none())
+                    .collect();
+                let local: Vec<usize> = free
+                    .iter()
+                    .filter(|(_, function)| function.module == caller.module)
+                    .map(|(index, _)| *index)
+                    .collect();
+                match (local.is_empty(), free.as_slice()) {
+                    (false, _) => local,
+                    (true, [(index, _)]) => vec![*index],
+                    _ => vec![],
+                }
+            }
+        }
+    }
+
+    /// Returns the indices of the methods `name` may be from `caller`, in an instruction
+    /// whose accounts struct is `accounts`.
+    fn resolve_method(
+        &self,
+        caller: &Function,
+        name: &str,
+        on_self: bool,
+        accounts: Option<&String>,
+    ) -> Vec<usize> {
+        let methods: Vec<(usize, &Function)> = self
+            .functions
+            .iter()
+            .enumerate()
+            .filter(|(_, function)| function.name == name && function.self_ty.is_some())
+            .collect();
+        let of_type = |ty: Option<&String>| -> Vec<usize> {
+            methods
+                .iter()
+                .filter(|(_, function)| ty.is_some() && function.self_ty.as_ref() == ty)
+                .map(|(index, _)| *index)
+                .collect()
+        };
+        if on_self {
+            return of_type(caller.self_ty.as_ref());
+        }
+        match methods.as_slice() {
+            [(index, _)] => vec![*index],
+            _ => of_type(accounts),
+        }
+    }
+}
+
+/// The functions of a crate, with what their bodies do.
+struct CrateFunctions<'a> {
+    functions: Vec<Function<'a>>,
+    bodies: Vec<BodyVisitor>,
+}
+
+impl<'a> CrateFunctions<'a> {
+    fn new(files: &'a [(String, syn::File)]) -> Self {
+        let mut functions = vec![];
+        for (path, file) in files {
+            collect_functions(&file.items, &module_of(path), false, &mut functions);
+        }
+        let bodies = functions
+            .iter()
+            .map(|function| {
+                let mut visitor = BodyVisitor::default();
+                visitor.visit_block(function.block);
+                visitor
+            })
+            .collect();
+        Self { functions, bodies }
+    }
+
+    /// Returns the indices of the instruction functions, in the order of the `#[program]` module.
+    fn instructions(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.functions.len()).filter(|index| self.functions[*index].instruction)
+    }
+
+    /// Returns the indices of the functions run by the instruction `index`: itself, then the
+    /// functions it calls, breadth-first and each once.
+    fn run_by(&self, index: usize) -> Vec<usize> {
+        let resolver = Resolver {
+            functions: &self.functions,
+        };
+        let instruction = &self.functions[index];
+        let mut visited = HashSet::from([index]);
+        let mut run = vec![index];
+        let mut frontier = vec![index];
+        for _ in 0..=MAX_CALL_DEPTH {
+            let mut next = vec![];
+            for caller_index in frontier {
+                let (caller, body) = (&self.functions[caller_index], &self.bodies[caller_index]);
+                let callees = body
+                    .calls
+                    .iter()
+                    .flat_map(|segments| resolver.resolve_call(caller, segments))
+                    .chain(body.method_calls.iter().flat_map(|(name, on_self)| {
+                        resolver.resolve_method(
+                            caller,
+                            name,
+                            *on_self,
+                            instruction.accounts.as_ref(),
+                        )
+                    }));
+                next.extend(callees.filter(|callee| visited.insert(*callee)));
+            }
+            run.extend(&next);
+            frontier = next;
+        }
+        run
+    }
+}
+
+/// The function bodies run by an instruction.
+pub(crate) struct InstructionBodies<'a> {
+    pub(crate) instruction: String,
+    /// The body of the instruction function, then the ones of the functions it calls.
+    pub(crate) blocks: Vec<&'a syn::Block>,
+}
+
+/// Lists the function bodies run by each instruction of a crate, in the order of the
+/// `#[program]` module, the calls being followed as for the events.
+///
+/// # Arguments
+///
+/// * `files` - The path and the syntax tree of each file of the crate.
+pub(crate) fn instruction_bodies(files: &[(String, syn::File)]) -> Vec<InstructionBodies<'_>> {
+    let functions = CrateFunctions::new(files);
+    functions
+        .instructions()
+        .map(|index| InstructionBodies {
+            instruction: functions.functions[index].name.clone(),
+            blocks: functions
+                .run_by(index)
+                .into_iter()
+                .map(|function| functions.functions[function].block)
+                .collect(),
+        })
+        .collect()
+}
+
+/// Lists the events and log lines of each instruction of a crate.
+///
+/// # Arguments
+///
+/// * `files` - The path and the syntax tree of each file of the crate.
+pub(crate) fn build_event_report(files: &[(String, syn::File)]) -> EventReport {
+    let mut defined_events = BTreeSet::new();
+    for (_, file) in files {
+        defined_events.extend(
+            items_of(&file.items)
+                .into_iter()
+                .filter_map(|item| match item {
+                    Item::Struct(item)
+                        if item.attrs.iter().any(|attr| attr.path().is_ident("event")) =>
+                    {
+                        Some(item.ident.to_string())
+                    }
+                    _ => None,
+                }),
+        );
+    }
+    let functions = CrateFunctions::new(files);
+
+    let mut report = EventReport::default();
+    let mut emitted = HashSet::new();
+    for index in functions.instructions() {
+        let mut events = InstructionEvents {
+            instruction: functions.functions[index].name.clone(),
+            ..InstructionEvents::default()
+        };
+        for function in functions.run_by(index) {
+            let body = &functions.bodies[function];
+            events.events.extend(body.events.iter().cloned());
+            events.logs.extend(body.logs.iter().cloned());
+        }
+        emitted.extend(events.events.iter().map(|emission| emission.event.clone()));
+        report.instructions.push(events);
+    }
+    report.unused_events = defined_events
+        .into_iter()
+        .filter(|event| !emitted.contains(event))
+        .collect();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = r#"
+        #[program]
+        pub mod vault {
+            use super::*;
+
+            pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+                instructions::deposit::handler(ctx, amount)
+            }
+
+            pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+                ctx.accounts.process(amount)?;
+                msg!("withdrawn {amount} by {}", ctx.accounts.user.key());
+                Ok(())
+            }
+        }
+
+        #[event]
+        pub struct Deposited { pub user: Pubkey, pub amount: u64 }
+
+        #[event]
+        pub struct Withdrawn { pub amount: u64 }
+
+        #[event]
+        pub struct Closed {}
+    "#;
+
+    const DEPOSIT: &str = r#"
+        pub fn handler(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+            record(&ctx, amount);
+            Ok(())
+        }
+
+        fn record(ctx: &Context<Deposit>, amount: u64) {
+            emit_cpi!(Deposited { user: ctx.accounts.user.key(), amount });
+        }
+
+        impl<'info> Deposit<'info> {
+            pub fn process(&mut self) -> Result<()> {
+                msg!("unreachable");
+                Ok(())
+            }
+        }
+    "#;
+
+    const WITHDRAW: &str = r#"
+        pub fn handler() {
+            msg!("unreachable");
+        }
+
+        impl<'info> Withdraw<'info> {
+            pub fn process(&mut self, amount: u64) -> Result<()> {
+                self.check()?;
+                emit!(Withdrawn { amount });
+                Ok(())
+            }
+
+            fn check(&self) -> Result<()> {
+                msg!("checking");
+                Ok(())
+            }
+        }
+    "#;
+
+    #[test]
+    fn test_build_event_report() {
+        let files: Vec<(String, syn::File)> = [
+            ("programs/vault/src/lib.rs", LIB),
+            ("programs/vault/src/instructions/deposit.rs", DEPOSIT),
+            ("programs/vault/src/instructions/withdraw.rs", WITHDRAW),
+        ]
+        .into_iter()
+        .map(|(path, source)| (path.to_string(), syn::parse_file(source).unwrap()))
+        .collect();
+        let report = build_event_report(&files);
+
+        let instructions: Vec<&str> = report
+            .instructions
+            .iter()
+            .map(|events| events.instruction.as_str())
+            .collect();
+        assert_eq!(instructions, vec!["deposit", "withdraw"]);
+
+        let deposit = &report.instructions[0];
+        assert_eq!(
+            deposit.events.iter().collect::<Vec<_>>(),
+            vec![&Emission {
+                event: "Deposited".to_string(),
+                fields: vec!["user".to_string(), "amount".to_string()],
+                cpi: true,
+            }]
+        );
+        assert!(deposit.logs.is_empty());
+
+        let withdraw = &report.instructions[1];
+        assert_eq!(withdraw.events.len(), 1);
+        let logs: Vec<(&str, Vec<&str>)> = withdraw
+            .logs
+            .iter()
+            .map(|log| {
+                (
+                    log.format.as_str(),
+                    log.args.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            logs,
+            vec![
+                ("checking", vec![]),
+                (
+                    "withdrawn {amount} by {}",
+                    vec!["amount", "ctx.accounts.user.key()"]
+                ),
+            ]
+        );
+
+        assert_eq!(report.unused_events, vec!["Closed"]);
+    }
+}
@@ -3,10 +3,14 @@ use serde::Deserialize;
 use std::fs;
 use std::path::Path;
 
+#[cfg_attr(not(feature = "cli"), allow(dead_code))]
 #[derive(Debug, Deserialize)]
 pub(crate) struct Idl {
     pub(crate) name: Option<String>,
     pub(crate) version: Option<String>,
+    // Only present in Anchor >= 0.30 IDLs, in `metadata` before
+    #[serde(default)]
+    pub(crate) address: Option<String>,
     #[serde(default)]
     pub(crate) instructions: Vec<IdlInstruction>,
     #[serde(default)]
@@ -18,6 +22,7 @@ pub(crate) struct Idl {
     pub(crate) metadata: Option<IdlMetadata>,
 }
 
+#[cfg_attr(not(feature = "cli"), allow(dead_code))]
 #[derive(Debug, Deserialize)]
 pub(crate) struct IdlInstruction {
     pub(crate) name: String,
@@ -25,9 +30,14 @@ pub(crate) struct IdlInstruction {
     pub(crate) accounts: Vec<IdlAccountItem>,
     #[serde(default)]
     pub(crate) args: Vec<IdlArg>,
+    // Only present in Anchor >= 0.30 IDLs
+    #[serde(default)]
+    pub(crate) discriminator: Option<Vec<u8>>,
 }
 
 // Anchor 0.29 vs >0.30 flag names (see: https://solana.stackexchange.com/questions/13076/anchor-idl-different-incorrect-from-solana-playground-idl-generated)
+#[allow(non_snake_case)]
+#[cfg_attr(not(feature = "cli"), allow(dead_code))]
 #[derive(Debug, Deserialize)]
 pub(crate) struct IdlAccountItem {
     pub(crate) name: String,
@@ -41,26 +51,57 @@ pub(crate) struct IdlAccountItem {
     pub(crate) writable: Option<bool>,
     #[serde(default)]
     pub(crate) accounts: Vec<IdlAccountItem>,
+    // Only present in Anchor >= 0.30 IDLs
+    #[serde(default)]
+    pub(crate) address: Option<String>,
+    #[serde(default)]
+    pub(crate) pda: Option<IdlPda>,
+}
+
+#[cfg_attr(not(feature = "cli"), allow(dead_code))]
+#[derive(Debug, Deserialize)]
+pub(crate) struct IdlPda {
+    #[serde(default)]
+    pub(crate) seeds: Vec<IdlSeed>,
+}
+
+/// A seed of a PDA: `const` (with `value`), `account` or `arg` (with `path`).
+#[cfg_attr(not(feature = "cli"), allow(dead_code))]
+#[derive(Debug, Deserialize)]
+pub(crate) struct IdlSeed {
+    pub(crate) kind: String,
+    #[serde(default)]
+    pub(crate) value: serde_json::Value,
+    #[serde(default)]
+    pub(crate) path: Option<String>,
 }
 
+#[cfg_attr(not(feature = "cli"), allow(dead_code))]
 #[derive(Debug, Deserialize)]
 pub(crate) struct IdlArg {
     pub(crate) name: String,
     #[serde(default)]
     pub(crate) r#type: serde_json::Value,
 }
+#[cfg_attr(not(feature = "cli"), allow(dead_code))]
 #[derive(Debug, Deserialize)]
 pub(crate) struct IdlStateAccount {
     pub(crate) name: String,
+    // Only present before Anchor 0.30, the type is in `types` after
     #[serde(default)]
     pub(crate) r#type: serde_json::Value,
+    // Only present in Anchor >= 0.30 IDLs
+    #[serde(default)]
+    pub(crate) discriminator: Option<Vec<u8>>,
 }
+#[cfg_attr(not(feature = "cli"), allow(dead_code))]
 #[derive(Debug, Deserialize)]
 pub(crate) struct IdlTypeDef {
     pub(crate) name: String,
     #[serde(default)]
     pub(crate) r#type: serde_json::Value,
 }
+#[cfg_attr(not(feature = "cli"), allow(dead_code))]
 #[derive(Debug, Deserialize)]
 pub(crate) struct IdlError {
     pub(crate) code: i64,
@@ -68,6 +109,7 @@ pub(crate) struct IdlError {
     #[serde(default)]
     pub(crate) msg: Option<String>,
 }
+#[cfg_attr(not(feature = "cli"), allow(dead_code))]
 #[derive(Debug, Deserialize)]
 pub(crate) struct IdlMetadata {
     #[serde(default)]
@@ -81,6 +123,7 @@ pub(crate) fn load_idl(path: &Path) -> Result<Idl> {
     Ok(idl)
 }
 
+#[cfg(feature = "cli")]
 pub(crate) fn flatten_accounts(items: &[IdlAccountItem], out: &mut Vec<(String, bool, bool)>) {
     for it in items {
         let is_signer = it.signer.unwrap_or_else(|| it.isSigner.unwrap_or(false));
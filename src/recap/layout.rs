@@ -0,0 +1,722 @@
+//! Byte layouts of the `#[account]` structs of a crate, and the `space = ...` constraints that
+//! don't match them.
+//!
+//! The accounts are Borsh-serialized after their 8-byte discriminator, each field taking the size
+//! of its value, without padding: `Option` takes one byte more than its value, an enum one byte
+//! more than its largest variant, `String` and `Vec` a 4-byte length then their items. The size
+//! of the latter is only known with the `#[max_len(...)]` of `#[derive(InitSpace)]`.
+//!
+//! The zero-copy accounts (`#[account(zero_copy)]`) are laid out as `#[repr(C)]`, with the
+//! natural alignment of the fields, or as `#[repr(packed)]` with `zero_copy(unsafe)`.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Add;
+
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Attribute, BinOp, Expr, Fields, GenericArgument, Item, Lit, PathArguments, Token, Type};
+
+use crate::parsers::anchor_accounts::{accounts_structs, items_of};
+
+/// Size of the discriminator prefixing the data of an Anchor account.
+pub(crate) const DISCRIMINATOR_SIZE: usize = 8;
+
+/// Nesting depth after which a type is given up, e.g. a recursive one.
+const MAX_DEPTH: usize = 16;
+
+/// Size of a serialized value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Size {
+    Fixed(usize),
+    /// At least this size, e.g. a `String` without `#[max_len(...)]`.
+    AtLeast(usize),
+    /// A type that is not known, e.g. from another crate.
+    Unknown,
+}
+
+impl Size {
+    fn times(self, count: usize) -> Size {
+        match self {
+            Size::Fixed(size) => Size::Fixed(size * count),
+            Size::AtLeast(size) => Size::AtLeast(size * count),
+            Size::Unknown => Size::Unknown,
+        }
+    }
+
+    fn lower_bound(&self) -> usize {
+        match self {
+            Size::Fixed(size) | Size::AtLeast(size) => *size,
+            Size::Unknown => 0,
+        }
+    }
+}
+
+impl Add for Size {
+    type Output = Size;
+
+    fn add(self, other: Size) -> Size {
+        match (self, other) {
+            (Size::Unknown, _) | (_, Size::Unknown) => Size::Unknown,
+            (Size::Fixed(a), Size::Fixed(b)) => Size::Fixed(a + b),
+            (Size::Fixed(a) | Size::AtLeast(a), Size::Fixed(b) | Size::AtLeast(b)) => {
+                Size::AtLeast(a + b)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Size::Fixed(size) => write!(f, "{}", size),
+            Size::AtLeast(size) => write!(f, "≥ {}", size),
+            Size::Unknown => write!(f, "?"),
+        }
+    }
+}
+
+/// A field of an account.
+#[derive(Debug)]
+pub(crate) struct FieldLayout {
+    pub(crate) name: String,
+    pub(crate) ty: String,
+    /// Offset in the account data, discriminator included. `None` after a field of variable size.
+    pub(crate) offset: Option<usize>,
+    pub(crate) size: Size,
+}
+
+/// The layout of an `#[account]` struct.
+#[derive(Debug)]
+pub(crate) struct AccountLayout {
+    pub(crate) account: String,
+    pub(crate) zero_copy: bool,
+    pub(crate) fields: Vec<FieldLayout>,
+    /// Size of the account data, discriminator included.
+    pub(crate) size: Size,
+}
+
+/// A `space = ...` constraint not matching the layout of the account it initializes.
+#[derive(Debug)]
+pub(crate) struct SpaceMismatch {
+    pub(crate) accounts_struct: String,
+    pub(crate) field: String,
+    pub(crate) account: String,
+    /// The expression of the constraint, and its value.
+    pub(crate) space: String,
+    pub(crate) value: usize,
+    pub(crate) expected: Size,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct LayoutReport {
+    pub(crate) layouts: Vec<AccountLayout>,
+    pub(crate) space_mismatches: Vec<SpaceMismatch>,
+}
+
+/// How a struct is laid out in memory, for the zero-copy accounts and `size_of::<T>()`.
+#[derive(Clone, Copy, PartialEq)]
+enum Repr {
+    C,
+    Packed,
+    /// The default representation: the fields may be reordered, removing the padding.
+    Rust,
+}
+
+/// Returns the source text of a node, e.g. `8 + Vault::INIT_SPACE`.
+fn source_text(node: &impl Spanned) -> String {
+    node.span().source_text().unwrap_or_else(|| "?".to_string())
+}
+
+/// Returns the name of a path type, without path, with its generic type arguments.
+fn path_type(ty: &Type) -> Option<(String, Vec<&Type>)> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    let arguments = match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) => arguments
+            .args
+            .iter()
+            .filter_map(|argument| match argument {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    };
+    Some((segment.ident.to_string(), arguments))
+}
+
+/// Size of the primitive types, which is also their alignment (except for `Pubkey`, a byte
+/// array).
+fn primitive_size(name: &str) -> Option<usize> {
+    match name {
+        "bool" | "u8" | "i8" => Some(1),
+        "u16" | "i16" => Some(2),
+        "u32" | "i32" | "f32" => Some(4),
+        "u64" | "i64" | "f64" | "usize" | "isize" => Some(8),
+        "u128" | "i128" => Some(16),
+        "Pubkey" => Some(32),
+        _ => None,
+    }
+}
+
+/// Whether the `#[account(...)]` / `#[zero_copy(...)]` / `#[repr(...)]` attributes of a struct
+/// hold `word`.
+fn attr_holds(attrs: &[Attribute], paths: &[&str], word: &str) -> bool {
+    attrs.iter().any(|attr| {
+        paths.iter().any(|path| attr.path().is_ident(path))
+            && matches!(&attr.meta, syn::Meta::List(list) if list.tokens.to_string().contains(word))
+    })
+}
+
+/// The structs, enums and integer constants of a crate, by name.
+struct Definitions<'a> {
+    structs: HashMap<String, &'a syn::ItemStruct>,
+    enums: HashMap<String, &'a syn::ItemEnum>,
+    /// The constants under their name and `Type::NAME` for the associated ones.
+    constants: HashMap<String, &'a Expr>,
+}
+
+impl<'a> Definitions<'a> {
+    fn new(files: &'a [(String, syn::File)]) -> Self {
+        let is_integer = |ty: &Type| {
+            path_type(ty)
+                .is_some_and(|(name, _)| name != "Pubkey" && primitive_size(&name).is_some())
+        };
+        let mut definitions = Definitions {
+            structs: HashMap::new(),
+            enums: HashMap::new(),
+            constants: HashMap::new(),
+        };
+        for (_, file) in files {
+            for item in items_of(&file.items) {
+                match item {
+                    Item::Struct(item) => {
+                        definitions.structs.insert(item.ident.to_string(), item);
+                    }
+                    Item::Enum(item) => {
+                        definitions.enums.insert(item.ident.to_string(), item);
+                    }
+                    Item::Const(item) if is_integer(&item.ty) => {
+                        definitions
+                            .constants
+                            .insert(item.ident.to_string(), &item.expr);
+                    }
+                    Item::Impl(item) => {
+                        let Some((type_name, _)) = path_type(&item.self_ty) else {
+                            continue;
+                        };
+                        for impl_item in &item.items {
+                            if let syn::ImplItem::Const(constant) = impl_item {
+                                if is_integer(&constant.ty) {
+                                    definitions.constants.insert(
+                                        format!("{}::{}", type_name, constant.ident),
+                                        &constant.expr,
+                                    );
+                                }
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        definitions
+    }
+
+    /// Evaluates an integer expression: literals, arithmetic, constants, `T::INIT_SPACE`,
+    /// `T::DISCRIMINATOR.len()` and `size_of::<T>()`.
+    fn eval(&self, expr: &Expr, depth: usize) -> Option<usize> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        match expr {
+            Expr::Lit(lit) => match &lit.lit {
+                Lit::Int(int) => int.base10_parse().ok(),
+                _ => None,
+            },
+            Expr::Paren(paren) => self.eval(&paren.expr, depth + 1),
+            Expr::Group(group) => self.eval(&group.expr, depth + 1),
+            Expr::Cast(cast) => self.eval(&cast.expr, depth + 1),
+            Expr::Binary(binary) => {
+                let left = self.eval(&binary.left, depth + 1)?;
+                let right = self.eval(&binary.right, depth + 1)?;
+                match binary.op {
+                    BinOp::Add(_) => left.checked_add(right),
+                    BinOp::Sub(_) => left.checked_sub(right),
+                    BinOp::Mul(_) => left.checked_mul(right),
+                    BinOp::Div(_) => left.checked_div(right),
+                    _ => None,
+                }
+            }
+            Expr::Path(path) => {
+                let segments: Vec<String> = path
+                    .path
+                    .segments
+                    .iter()
+                    .map(|segment| segment.ident.to_string())
+                    .collect();
+                let [.., owner, name] = segments.as_slice() else {
+                    let name = segments.first()?;
+                    return self.eval(self.constants.get(name)?, depth + 1);
+                };
+                if name == "INIT_SPACE" {
+                    return match self.borsh_size_of(owner, depth + 1) {
+                        Size::Fixed(size) => Some(size),
+                        _ => None,
+                    };
+                }
+                if let Some(constant) = self.constants.get(&format!("{}::{}", owner, name)) {
+                    return self.eval(constant, depth + 1);
+                }
+                // `crate::constants::MAX_LEN`, not an associated constant of another type
+                if owner.starts_with(|c: char| c.is_lowercase()) {
+                    return self.eval(self.constants.get(name)?, depth + 1);
+                }
+                None
+            }
+            Expr::MethodCall(call) if call.method == "len" && call.args.is_empty() => {
+                match &*call.receiver {
+                    Expr::Path(path)
+                        if path
+                            .path
+                            .segments
+                            .last()
+                            .is_some_and(|segment| segment.ident == "DISCRIMINATOR") =>
+                    {
+                        Some(DISCRIMINATOR_SIZE)
+                    }
+                    _ => None,
+                }
+            }
+            Expr::Call(call) => {
+                let Expr::Path(function) = &*call.func else {
+                    return None;
+                };
+                let segment = function.path.segments.last()?;
+                if segment.ident != "size_of" {
+                    return None;
+                }
+                let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+                    return None;
+                };
+                match arguments.args.first()? {
+                    GenericArgument::Type(ty) => {
+                        self.memory_layout(ty, depth + 1).map(|(size, _)| size)
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Evaluates the `#[max_len(...)]` of a field, one length per nested `Vec` / `String`.
+    fn max_len(&self, attrs: &[Attribute], depth: usize) -> Vec<usize> {
+        attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("max_len"))
+            .filter_map(|attr| {
+                attr.parse_args_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+                    .ok()
+            })
+            .flat_map(|lengths| {
+                lengths
+                    .iter()
+                    .map(|length| self.eval(length, depth + 1))
+                    .collect::<Option<Vec<_>>>()
+            })
+            .next()
+            .unwrap_or_default()
+    }
+
+    /// Borsh size of the struct or enum `name` (its `INIT_SPACE`).
+    fn borsh_size_of(&self, name: &str, depth: usize) -> Size {
+        if depth > MAX_DEPTH {
+            return Size::Unknown;
+        }
+        if let Some(item) = self.structs.get(name) {
+            return self.fields_size(&item.fields, depth + 1);
+        }
+        let Some(item) = self.enums.get(name) else {
+            return Size::Unknown;
+        };
+        let variants: Vec<Size> = item
+            .variants
+            .iter()
+            .map(|variant| self.fields_size(&variant.fields, depth + 1))
+            .collect();
+        let largest = if variants.contains(&Size::Unknown) {
+            Size::Unknown
+        } else if variants.iter().all(|size| matches!(size, Size::Fixed(_))) {
+            Size::Fixed(variants.iter().map(Size::lower_bound).max().unwrap_or(0))
+        } else {
+            Size::AtLeast(variants.iter().map(Size::lower_bound).min().unwrap_or(0))
+        };
+        Size::Fixed(1) + largest
+    }
+
+    fn fields_size(&self, fields: &Fields, depth: usize) -> Size {
+        fields.iter().fold(Size::Fixed(0), |size, field| {
+            size + self.borsh_size(&field.ty, &self.max_len(&field.attrs, depth), depth)
+        })
+    }
+
+    /// Borsh size of a value of type `ty`.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_len` - The `#[max_len(...)]` of the field, for its `Vec` and `String`.
+    fn borsh_size(&self, ty: &Type, max_len: &[usize], depth: usize) -> Size {
+        if depth > MAX_DEPTH {
+            return Size::Unknown;
+        }
+        match ty {
+            Type::Paren(paren) => self.borsh_size(&paren.elem, max_len, depth + 1),
+            Type::Group(group) => self.borsh_size(&group.elem, max_len, depth + 1),
+            Type::Tuple(tuple) => tuple.elems.iter().fold(Size::Fixed(0), |size, elem| {
+                size + self.borsh_size(elem, max_len, depth + 1)
+            }),
+            Type::Array(array) => match self.eval(&array.len, depth + 1) {
+                Some(count) => self
+                    .borsh_size(&array.elem, max_len, depth + 1)
+                    .times(count),
+                None => Size::Unknown,
+            },
+            _ => {
+                let Some((name, arguments)) = path_type(ty) else {
+                    return Size::Unknown;
+                };
+                if let Some(size) = primitive_size(&name) {
+                    return Size::Fixed(size);
+                }
+                match (name.as_str(), arguments.first()) {
+                    ("Box", Some(inner)) => self.borsh_size(inner, max_len, depth + 1),
+                    ("Option", Some(inner)) => {
+                        Size::Fixed(1) + self.borsh_size(inner, max_len, depth + 1)
+                    }
+                    ("String", _) => match max_len.first() {
+                        Some(length) => Size::Fixed(4 + length),
+                        None => Size::AtLeast(4),
+                    },
+                    ("Vec", Some(inner)) => match max_len.split_first() {
+                        Some((length, nested)) => {
+                            Size::Fixed(4)
+                                + self.borsh_size(inner, nested, depth + 1).times(*length)
+                        }
+                        None => Size::AtLeast(4),
+                    },
+                    _ => self.borsh_size_of(&name, depth + 1),
+                }
+            }
+        }
+    }
+
+    /// Returns the representation of the struct `name` and its fields.
+    fn repr_of(&self, item: &syn::ItemStruct) -> Repr {
+        if attr_holds(&item.attrs, &["account", "zero_copy", "repr"], "packed")
+            || attr_holds(&item.attrs, &["account", "zero_copy"], "unsafe")
+        {
+            Repr::Packed
+        } else if attr_holds(&item.attrs, &["account"], "zero_copy")
+            || item
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("zero_copy"))
+            || attr_holds(&item.attrs, &["repr"], "C")
+        {
+            Repr::C
+        } else {
+            Repr::Rust
+        }
+    }
+
+    /// In-memory size and alignment of a value of type `ty`, for the plain old data types
+    /// (primitives, arrays and structs of them).
+    fn memory_layout(&self, ty: &Type, depth: usize) -> Option<(usize, usize)> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        match ty {
+            Type::Array(array) => {
+                let count = self.eval(&array.len, depth + 1)?;
+                let (size, align) = self.memory_layout(&array.elem, depth + 1)?;
+                Some((size * count, align))
+            }
+            _ => {
+                let (name, _) = path_type(ty)?;
+                match (name.as_str(), primitive_size(&name)) {
+                    ("Pubkey", Some(size)) => Some((size, 1)),
+                    (_, Some(size)) => Some((size, size)),
+                    _ => {
+                        let item = self.structs.get(&name)?;
+                        let (_, size, align) = self.struct_layout(item, depth + 1)?;
+                        Some((size, align))
+                    }
+                }
+            }
+        }
+    }
+
+    /// In-memory layout of a struct: the offsets of its fields, its size and its alignment.
+    fn struct_layout(
+        &self,
+        item: &syn::ItemStruct,
+        depth: usize,
+    ) -> Option<(Vec<usize>, usize, usize)> {
+        let repr = self.repr_of(item);
+        let mut offsets = vec![];
+        let (mut offset, mut unpadded, mut struct_align) = (0usize, 0, 1);
+        for field in &item.fields {
+            let (size, align) = self.memory_layout(&field.ty, depth + 1)?;
+            let align = if repr == Repr::Packed { 1 } else { align };
+            offset = offset.next_multiple_of(align);
+            offsets.push(offset);
+            offset += size;
+            unpadded += size;
+            struct_align = struct_align.max(align);
+        }
+        // the compiler reorders the fields of the default representation, removing the padding
+        // between them (the offsets, in the order of the declaration, are then meaningless)
+        let size = match repr {
+            Repr::Rust => unpadded,
+            _ => offset,
+        };
+        Some((offsets, size.next_multiple_of(struct_align), struct_align))
+    }
+
+    /// Computes the layout of an `#[account]` struct.
+    fn account_layout(&self, item: &syn::ItemStruct) -> AccountLayout {
+        let zero_copy = attr_holds(&item.attrs, &["account"], "zero_copy");
+        let mut fields = vec![];
+        if zero_copy {
+            let layout = self.struct_layout(item, 0);
+            for (index, field) in item.fields.iter().enumerate() {
+                fields.push(FieldLayout {
+                    name: field_name(field, index),
+                    ty: source_text(&field.ty),
+                    offset: layout
+                        .as_ref()
+                        .map(|(offsets, _, _)| DISCRIMINATOR_SIZE + offsets[index]),
+                    size: self
+                        .memory_layout(&field.ty, 0)
+                        .map_or(Size::Unknown, |(size, _)| Size::Fixed(size)),
+                });
+            }
+            let size = layout.map_or(Size::Unknown, |(_, size, _)| {
+                Size::Fixed(DISCRIMINATOR_SIZE + size)
+            });
+            return AccountLayout {
+                account: item.ident.to_string(),
+                zero_copy,
+                fields,
+                size,
+            };
+        }
+
+        let mut size = Size::Fixed(DISCRIMINATOR_SIZE);
+        for (index, field) in item.fields.iter().enumerate() {
+            let field_size = self.borsh_size(&field.ty, &self.max_len(&field.attrs, 0), 0);
+            fields.push(FieldLayout {
+                name: field_name(field, index),
+                ty: source_text(&field.ty),
+                offset: match size {
+                    Size::Fixed(offset) => Some(offset),
+                    _ => None,
+                },
+                size: field_size,
+            });
+            size = size + field_size;
+        }
+        AccountLayout {
+            account: item.ident.to_string(),
+            zero_copy,
+            fields,
+            size,
+        }
+    }
+}
+
+fn field_name(field: &syn::Field, index: usize) -> String {
+    field
+        .ident
+        .as_ref()
+        .map_or_else(|| index.to_string(), ToString::to_string)
+}
+
+/// Computes the layouts of the `#[account]` structs of a crate and checks the `space = ...` of
+/// its accounts structs against them.
+///
+/// A `space` whose value can't be computed (e.g. from an instruction argument) is not checked,
+/// nor an account whose size is unknown. When the size is a lower bound (a `String` or `Vec`
+/// without `#[max_len(...)]`), only a smaller `space` is reported.
+///
+/// # Arguments
+///
+/// * `files` - The path and the syntax tree of each file of the crate.
+pub(crate) fn build_layouts(files: &[(String, syn::File)]) -> LayoutReport {
+    let definitions = Definitions::new(files);
+    let mut report = LayoutReport::default();
+    for (_, file) in files {
+        for item in items_of(&file.items) {
+            if let Item::Struct(item) = item {
+                if item
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.path().is_ident("account"))
+                {
+                    report.layouts.push(definitions.account_layout(item));
+                }
+            }
+        }
+    }
+
+    for (path, file) in files {
+        for accounts_struct in accounts_structs(file, path) {
+            for field in &accounts_struct.fields {
+                let Some(space) = field.arg("space").and_then(|arg| arg.value.as_ref()) else {
+                    continue;
+                };
+                let Some(account) = field.state_type() else {
+                    continue;
+                };
+                let Some(layout) = report.layouts.iter().find(|l| l.account == account) else {
+                    continue;
+                };
+                let Some(value) = definitions.eval(space, 0) else {
+                    continue;
+                };
+                let mismatch = match layout.size {
+                    Size::Fixed(size) => value != size,
+                    Size::AtLeast(size) => value < size,
+                    Size::Unknown => false,
+                };
+                if mismatch {
+                    report.space_mismatches.push(SpaceMismatch {
+                        accounts_struct: accounts_struct.name.clone(),
+                        field: field.name.clone(),
+                        account,
+                        space: source_text(space),
+                        value,
+                        expected: layout.size,
+                    });
+                }
+            }
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+        pub const MAX_NAME: usize = 16;
+
+        #[account]
+        #[derive(InitSpace)]
+        pub struct Vault {
+            pub authority: Pubkey,
+            #[max_len(MAX_NAME)]
+            pub name: String,
+            pub fees: Option<Fees>,
+            pub bump: u8,
+        }
+
+        impl Vault {
+            pub const LEN: usize = 8 + 32 + 4 + MAX_NAME + 1 + 8 + 2 + 7 + 1;
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+        pub struct Fees {
+            pub rate: u64,
+            pub kind: Kind,
+            pub flags: [u8; 7],
+        }
+
+        #[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+        pub enum Kind { Flat, Tiered(u16) }
+
+        #[account]
+        pub struct Log {
+            pub owner: Pubkey,
+            pub entries: Vec<u64>,
+        }
+
+        #[account(zero_copy)]
+        pub struct Book {
+            pub side: u8,
+            pub price: u64,
+            pub owner: Pubkey,
+        }
+
+        #[derive(Accounts)]
+        pub struct Init<'info> {
+            #[account(init, payer = payer, space = 8 + Vault::INIT_SPACE)]
+            pub vault: Account<'info, Vault>,
+            #[account(init, payer = payer, space = Vault::LEN)]
+            pub other: Account<'info, Vault>,
+            #[account(init, payer = payer, space = 8 + 32)]
+            pub log: Account<'info, Log>,
+            #[account(zero, space = 8 + std::mem::size_of::<Book>())]
+            pub book: AccountLoader<'info, Book>,
+            #[account(init, payer = payer, space = 8 + 1 + 8 + 32)]
+            pub small: AccountLoader<'info, Book>,
+            #[account(mut)]
+            pub payer: Signer<'info>,
+        }
+    "#;
+
+    #[test]
+    fn test_build_layouts() {
+        let files = vec![(
+            "programs/p/src/lib.rs".to_string(),
+            syn::parse_file(SOURCE).unwrap(),
+        )];
+        let report = build_layouts(&files);
+
+        let layout = |name: &str| {
+            report
+                .layouts
+                .iter()
+                .find(|layout| layout.account == name)
+                .unwrap()
+        };
+        // 8 + 32 + (4 + 16) + (1 + 8 + (1 + 2) + 7) + 1
+        let vault = layout("Vault");
+        assert_eq!(vault.size, Size::Fixed(80));
+        let offsets: Vec<Option<usize>> = vault.fields.iter().map(|f| f.offset).collect();
+        assert_eq!(offsets, vec![Some(8), Some(40), Some(60), Some(79)]);
+        assert_eq!(vault.fields[2].ty, "Option<Fees>");
+
+        let log = layout("Log");
+        assert_eq!(log.size, Size::AtLeast(44));
+
+        // repr(C): the price is aligned on 8 bytes
+        let book = layout("Book");
+        assert!(book.zero_copy);
+        let offsets: Vec<Option<usize>> = book.fields.iter().map(|f| f.offset).collect();
+        assert_eq!(offsets, vec![Some(8), Some(16), Some(24)]);
+        assert_eq!(book.size, Size::Fixed(56));
+
+        // `Vault::LEN` forgets the tag of `Kind`, `8 + 32` leaves no room for the entries length,
+        // `8 + 1 + 8 + 32` ignores the padding; `8 + INIT_SPACE` and `size_of` are right
+        let mismatches: Vec<(&str, usize, Size)> = report
+            .space_mismatches
+            .iter()
+            .map(|m| (m.field.as_str(), m.value, m.expected))
+            .collect();
+        assert_eq!(
+            mismatches,
+            vec![
+                ("other", 79, Size::Fixed(80)),
+                ("log", 40, Size::AtLeast(44)),
+                ("small", 49, Size::Fixed(56)),
+            ]
+        );
+        assert_eq!(report.space_mismatches[0].space, "Vault::LEN");
+    }
+}
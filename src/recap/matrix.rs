@@ -0,0 +1,131 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use super::idl::Idl;
+use super::parser::{
+    account_type, extract_accounts_structs, map_instruction_to_struct, state_accounts, state_type,
+    AccountsStructMap,
+};
+use super::rows::merged_sources;
+
+/// How the instructions of a program use a state account type.
+#[derive(Debug, Default)]
+pub(crate) struct StateRow {
+    pub(crate) account: String,
+    pub(crate) read: Vec<String>,    // instructions taking it read-only
+    pub(crate) written: Vec<String>, // instructions taking it writable (`mut`, init, close, realloc)
+    pub(crate) init: Vec<String>,    // `init` / `init_if_needed` / `zero`
+    pub(crate) close: Vec<String>,   // `close = ...`
+}
+
+/// Usage of a state account type by one instruction.
+#[derive(Default)]
+struct Usage {
+    written: bool,
+    init: bool,
+    close: bool,
+}
+
+/// Records the state accounts of an accounts struct, going through the nested accounts
+/// structs. `visited` guards against recursive structs.
+fn collect_usages(
+    struct_name: &str,
+    structs: &AccountsStructMap,
+    usages: &mut BTreeMap<String, Usage>,
+    visited: &mut Vec<String>,
+) {
+    let Some(fields) = structs.get(struct_name) else {
+        return;
+    };
+    if visited.iter().any(|visited| visited == struct_name) {
+        return;
+    }
+    visited.push(struct_name.to_string());
+    for meta in fields.values() {
+        let Some(state) = state_type(&meta.ty) else {
+            collect_usages(account_type(&meta.ty), structs, usages, visited);
+            continue;
+        };
+        let usage = usages.entry(state.to_string()).or_default();
+        usage.written |= meta.is_writable || meta.has_realloc || meta.close.is_some();
+        usage.init |= meta.is_init;
+        usage.close |= meta.close.is_some();
+    }
+    visited.pop();
+}
+
+/// Builds the state × instruction matrix of a program: for each state account type, the
+/// instructions reading, writing, initializing and closing it.
+///
+/// The account types are the `accounts` of the IDL, or the `#[account]` structs of the sources
+/// when the IDL lists none (IDLs older than Anchor 0.30, synthesized IDLs).
+pub(crate) fn build_state_matrix(idl: &Idl, crate_root: &Path) -> Vec<StateRow> {
+    let merged_src = merged_sources(crate_root);
+    let instr_to_struct = map_instruction_to_struct(&merged_src);
+    let structs = extract_accounts_structs(&merged_src);
+
+    let accounts: BTreeSet<String> = if idl.accounts.is_empty() {
+        state_accounts(&merged_src).into_iter().collect()
+    } else {
+        idl.accounts.iter().map(|a| a.name.clone()).collect()
+    };
+    let mut rows: BTreeMap<String, StateRow> = accounts
+        .into_iter()
+        .map(|account| {
+            let row = StateRow {
+                account: account.clone(),
+                ..Default::default()
+            };
+            (account, row)
+        })
+        .collect();
+
+    for ix in &idl.instructions {
+        let Some(struct_name) = instr_to_struct.get(&ix.name) else {
+            continue;
+        };
+        let mut usages = BTreeMap::new();
+        collect_usages(struct_name, &structs, &mut usages, &mut Vec::new());
+        for (state, usage) in usages {
+            let Some(row) = rows.get_mut(&state) else {
+                continue;
+            };
+            if usage.written {
+                row.written.push(ix.name.clone());
+            } else {
+                row.read.push(ix.name.clone());
+            }
+            if usage.init {
+                row.init.push(ix.name.clone());
+            }
+            if usage.close {
+                row.close.push(ix.name.clone());
+            }
+        }
+    }
+
+    rows.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_state_matrix;
+    use crate::recap::idl::load_idl;
+    use std::path::Path;
+
+    #[test]
+    fn builds_state_matrix_for_fixture() {
+        let root = Path::new("test_cases/recap_anchor");
+        let idl = load_idl(&root.join("idl/recap_anchor.json")).unwrap();
+        let matrix = build_state_matrix(&idl, &root.join("programs/recap_anchor"));
+
+        assert_eq!(matrix.len(), 1);
+        let user_state = &matrix[0];
+        assert_eq!(user_state.account, "UserState");
+        assert!(user_state.read.is_empty());
+        // the instructions follow the order of the IDL
+        assert_eq!(user_state.written, vec!["close_state", "deposit"]);
+        assert_eq!(user_state.init, vec!["deposit"]);
+        assert_eq!(user_state.close, vec!["close_state"]);
+    }
+}
@@ -1,33 +1,65 @@
+#[cfg(feature = "cli")]
 use anyhow::{anyhow, Context, Result};
 
+#[cfg(feature = "cli")]
 use crate::helpers;
 
+#[cfg(feature = "cli")]
 pub mod project;
+#[cfg(feature = "cli")]
 pub mod fs_utils;
 pub mod idl;
+#[cfg(feature = "cli")]
 pub mod crates;
+#[cfg(feature = "cli")]
 pub mod parser;
+#[cfg(feature = "cli")]
 pub mod rows;
+#[cfg(feature = "cli")]
 pub mod render;
+#[cfg(feature = "cli")]
+pub mod matrix;
+#[cfg(feature = "cli")]
+pub mod layout;
+#[cfg(feature = "cli")]
+pub mod events;
+#[cfg(feature = "cli")]
+pub mod checklist;
+#[cfg(feature = "cli")]
+pub mod synth_idl;
+#[cfg(feature = "cli")]
+pub mod ownership;
 
 
+/// Note following the header of a program whose IDL was synthesized from the sources.
+#[cfg(feature = "cli")]
+const SYNTHESIZED_IDL_NOTE: &str = "_IDL: synthesized from the sources, `target/idl` is missing. \
+The signer / writable flags are the ones enforced by the `#[derive(Accounts)]` structs._\n";
+
 /// Generates a markdown recap (`recap-solazy.md`) summarizing an Anchor project's structure.
 ///
 /// The function scans the specified Anchor project (or the current directory if none is provided),
 /// extracts its IDLs and crates, and analyzes each program's instructions and accounts.
 /// For each instruction, it lists the **signers**, **writable accounts**, **constraints**, **seeded accounts**, 
-/// and **memory-related attributes** in a markdown table.
+/// **memory-related attributes**, **PDA hygiene** issues and direct **lamport** manipulations in a markdown table, followed by the
+/// instructions taking a PDA bump from their arguments, the signer/writable flags of the IDL
+/// that do not match the source, the byte layouts of the `#[account]` structs (with the
+/// `space = ...` not matching them), the events and log lines of each instruction, the public
+/// keys written in the sources, and the instructions reading, writing, initializing and
+/// closing each state account type. An appendix answers a threat-model checklist for each
+/// instruction.
 ///
-/// The resulting report is written to a file named `recap-solazy.md` in the directory
-/// where the command was launched, and a spinner displays the current progress.
-pub fn recap_project(anchor_path: Option<String>) -> Result<()> {
-    use project::{detect_project_kind, ProjectKind};
-    use fs_utils::find_all_idls;
-    use crates::find_anchor_crates;
-    use idl::load_idl;
-    use render::to_markdown;
-    use rows::build_rows_for_program;
-    use log::{error, warn};
+/// The resulting report is written to a file named `recap-solazy.md` in `out_dir`, or in the
+/// directory where the command was launched if `out_dir` is `None`, and a spinner displays the
+/// current progress. With `graphs`, the ownership graph of each instruction is written next to
+/// it, to `ownership/<program>/<instruction>.dot`.
+#[cfg(feature = "cli")]
+pub fn recap_project(
+    anchor_path: Option<String>,
+    out_dir: Option<&std::path::Path>,
+    graphs: bool,
+) -> Result<()> {
+    use log::error;
     use std::path::{Path, PathBuf};
 
     let launch_dir = std::env::var_os("PWD")
@@ -47,7 +79,86 @@ pub fn recap_project(anchor_path: Option<String>) -> Result<()> {
         None => cwd,
     };
 
-    if detect_project_kind(&root) != ProjectKind::Anchor {
+    let report = recap_report(&root)?;
+    let out_all = report.markdown;
+
+    if graphs {
+        for (program, program_graphs) in &report.ownership_graphs {
+            let dir = out_dir
+                .unwrap_or(&launch_dir)
+                .join("ownership")
+                .join(program);
+            ownership::write_ownership_graphs(program_graphs, &dir)
+                .with_context(|| format!("Writing the ownership graphs to {}", dir.display()))?;
+            if out_dir.is_none() {
+                for graph in program_graphs {
+                    helpers::events::artifact_written(
+                        &dir.join(format!("{}.dot", graph.instruction)),
+                    );
+                }
+            }
+        }
+    }
+
+    let out_path = out_dir.unwrap_or(&launch_dir).join("recap-solazy.md");
+    if let Err(e) = std::fs::write(&out_path, out_all)
+        .with_context(|| format!("Writing {}", out_path.display()))
+    {
+        error!("Failed to write recap output to {}: {}", out_path.display(), e);
+        return Err(anyhow!("Failed to write recap output to {}: {}", out_path.display(), e));
+    }
+    // the files of a run directory are reported with the run
+    if out_dir.is_none() {
+        helpers::events::artifact_written(&out_path);
+    }
+
+    Ok(())
+}
+
+/// The recap of an Anchor project.
+#[cfg(feature = "cli")]
+pub(crate) struct RecapReport {
+    /// The markdown written to `recap-solazy.md`.
+    pub(crate) markdown: String,
+    /// The ownership graphs of the instructions of each program.
+    pub(crate) ownership_graphs: Vec<(String, Vec<ownership::OwnershipGraph>)>,
+}
+
+/// Builds the markdown recap of the Anchor project at `root`, as written by [`recap_project`].
+///
+/// A spinner displays the current progress.
+#[cfg(feature = "cli")]
+pub fn recap_markdown(root: &std::path::Path) -> Result<String> {
+    Ok(recap_report(root)?.markdown)
+}
+
+/// Builds the recap of the Anchor project at `root`: its markdown and the ownership graphs of
+/// its instructions.
+///
+/// A spinner displays the current progress.
+#[cfg(feature = "cli")]
+pub(crate) fn recap_report(root: &std::path::Path) -> Result<RecapReport> {
+    use project::{detect_project_kind, ProjectKind};
+    use fs_utils::find_all_idls;
+    use crates::find_anchor_crates;
+    use idl::load_idl;
+    use render::{
+        checklist_appendix, constraint_summary, event_summary, idl_mismatch_summary,
+        layout_summary, pda_hygiene_summary, pubkey_summary, state_matrix_summary, to_markdown,
+    };
+    use checklist::build_checklist;
+    use matrix::build_state_matrix;
+    use layout::build_layouts;
+    use events::build_event_report;
+    use rows::{build_rows_for_program, lamport_manipulations, parsed_sources};
+    use crate::constraints::ConstraintReport;
+    use crate::pubkeys::PubkeyInventory;
+    use synth_idl::synthesize_idl;
+    use ownership::build_ownership_graphs;
+    use log::{error, warn};
+    use std::path::PathBuf;
+
+    if detect_project_kind(root) != ProjectKind::Anchor {
         error!(
             "Non-Anchor project detected (no Anchor.toml at {}). This tool currently supports Anchor projects only.",
             root.display()
@@ -63,20 +174,7 @@ pub fn recap_project(anchor_path: Option<String>) -> Result<()> {
         root
     ));
 
-    let idl_paths = find_all_idls(&root);
-    if idl_paths.is_empty() {
-        spinner.finish_and_clear();
-        error!(
-            "No IDL files under {}/target/idl/. Run `anchor build` first.",
-            root.display()
-        );
-        return Err(anyhow!(
-            "No IDL files under {}/target/idl/. Run `anchor build` first.",
-            root.display()
-        ));
-    }
-
-    let crates = find_anchor_crates(&root);
+    let crates = find_anchor_crates(root);
     if crates.is_empty() {
         spinner.finish_and_clear();
         error!(
@@ -89,9 +187,11 @@ pub fn recap_project(anchor_path: Option<String>) -> Result<()> {
         ));
     }
 
-    let mut idls: Vec<(String, idl::Idl, PathBuf)> = vec![];
-    for p in idl_paths {
-        let idl = match load_idl(&p) {
+    // the IDL of each program, and where it comes from (`None` when synthesized)
+    let mut idls: Vec<(String, idl::Idl, Option<PathBuf>)> = vec![];
+    let idl_paths = find_all_idls(root);
+    for p in &idl_paths {
+        let idl = match load_idl(p) {
             Ok(i) => i,
             Err(e) => {
                 spinner.finish_and_clear();
@@ -103,26 +203,58 @@ pub fn recap_project(anchor_path: Option<String>) -> Result<()> {
             .name
             .clone()
             .unwrap_or_else(|| p.file_stem().unwrap().to_string_lossy().to_string());
-        idls.push((name, idl, p));
+        idls.push((name, idl, Some(p.clone())));
+    }
+    if idl_paths.is_empty() {
+        warn!(
+            "No IDL files under {}/target/idl/, synthesizing them from the sources (run `anchor build` for the exact ones).",
+            root.display()
+        );
+        idls.extend(crates.iter().filter_map(|krate| {
+            let idl = synthesize_idl(krate)?;
+            Some((idl.name.clone().unwrap_or_default(), idl, None))
+        }));
+    }
+    if idls.is_empty() {
+        spinner.finish_and_clear();
+        error!(
+            "No IDL files under {}/target/idl/ and no #[program] module in the sources. Run `anchor build` first.",
+            root.display()
+        );
+        return Err(anyhow!(
+            "No IDL files under {}/target/idl/ and no #[program] module in the sources. Run `anchor build` first.",
+            root.display()
+        ));
     }
 
     let mut out_all = String::new();
+    let mut checklists = vec![];
+    let mut ownership_graphs = vec![];
 
     for (prog_name, idl, idl_path) in idls {
-        spinner.set_message(format!("Processing program `{}`...", prog_name));
+        let msg = format!("Processing program `{}`...", prog_name);
+        helpers::events::progress(msg.as_str());
+        spinner.set_message(msg);
 
         let Some(krate) = crates::pick_crate_for_idl(&idl, &crates) else {
             warn!(
                 "# Program `{}` (IDL: {}) — no matching Anchor crate found. Skipping.",
                 prog_name,
-                idl_path.display()
+                idl_path
+                    .as_deref()
+                    .map_or("synthesized".into(), |p| p.display().to_string())
             );
             continue;
         };
 
         let header = format!(
-            "# Program `{}`{}",
+            "# Program `{}`{}{}",
             prog_name,
+            if idl_path.is_none() {
+                " (synthesized IDL)"
+            } else {
+                ""
+            },
             idl.metadata
                 .as_ref()
                 .and_then(|m| m.address.as_ref())
@@ -134,29 +266,69 @@ pub fn recap_project(anchor_path: Option<String>) -> Result<()> {
 
         let crate_line = format!("_Crate: {}_\n", krate.root.display());
         out_all.push_str(&crate_line);
+        if idl_path.is_none() {
+            out_all.push_str(SYNTHESIZED_IDL_NOTE);
+        }
         out_all.push('\n');
 
-        let rows = build_rows_for_program(&idl, &krate.root);
+        let mut rows = build_rows_for_program(&idl, &krate.root);
         if rows.is_empty() {
             out_all.push_str("(No instructions found)\n\n");
             continue;
         }
 
+        let sources = parsed_sources(&krate.root);
+        let mut lamports = lamport_manipulations(&sources);
+        for row in &mut rows {
+            row.lamports = lamports.remove(&row.instruction).unwrap_or_default();
+        }
+
         let md = to_markdown(&rows);
         out_all.push_str(&md);
         out_all.push('\n');
+
+        // the flags of a synthesized IDL are the ones of the source
+        let mismatches = idl_path.as_ref().and_then(|_| idl_mismatch_summary(&rows));
+        checklists.push((prog_name.clone(), build_checklist(&idl, &krate.root)));
+        let matrix = state_matrix_summary(&build_state_matrix(&idl, &krate.root));
+        ownership_graphs.push((
+            prog_name.clone(),
+            build_ownership_graphs(&idl, &krate.root, &sources),
+        ));
+        let constraints = constraint_summary(&ConstraintReport::analyze(
+            sources.iter().map(|(path, file)| (path.as_str(), file)),
+        ));
+        let layouts = layout_summary(&build_layouts(&sources));
+        let events = event_summary(&build_event_report(&sources));
+        let pubkeys = pubkey_summary(
+            &PubkeyInventory::collect(sources.iter().map(|(path, file)| (path.as_str(), file))),
+            &krate.root,
+        );
+        for summary in [
+            pda_hygiene_summary(&rows),
+            mismatches,
+            constraints,
+            layouts,
+            events,
+            pubkeys,
+            matrix,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            out_all.push_str(&summary);
+            out_all.push('\n');
+        }
     }
 
-    let out_path = launch_dir.join("recap-solazy.md");
-    if let Err(e) = std::fs::write(&out_path, out_all)
-        .with_context(|| format!("Writing {}", out_path.display()))
-    {
-        spinner.finish_and_clear();
-        error!("Failed to write recap output to {}: {}", out_path.display(), e);
-        return Err(anyhow!("Failed to write recap output to {}: {}", out_path.display(), e));
+    if let Some(appendix) = checklist_appendix(&checklists) {
+        out_all.push_str(&appendix);
     }
 
     spinner.finish_with_message("Recap scan completed.");
 
-    Ok(())
+    Ok(RecapReport {
+        markdown: out_all,
+        ownership_graphs,
+    })
 }
@@ -0,0 +1,441 @@
+//! Ownership graphs of the Anchor contexts: for each instruction, the accounts of its
+//! `#[derive(Accounts)]` struct and the relations their constraints set between them.
+//!
+//! An edge goes from the constrained account to the account it refers to: `vault -> authority`
+//! for `has_one = authority`, `vault -> user` for `seeds = [b"vault", user.key().as_ref()]`,
+//! `tokens -> owner` for `token::authority = owner`... The graphs are rendered to DOT.
+
+use std::collections::{BTreeSet, HashMap};
+use std::io::Write;
+use std::path::Path;
+
+use syn::visit::Visit;
+use syn::{Expr, Ident};
+
+use super::idl::Idl;
+use super::parser::map_instruction_to_struct;
+use super::rows::merged_sources;
+use crate::constraints::seeds::{normalize_seed, seeds_to_string};
+use crate::parsers::anchor_accounts::{
+    accounts_structs, byte_constants, AccountsField, AccountsStruct,
+};
+use crate::reverse::cfg_graph::write_graph_header;
+use crate::reverse::sanitize::html_escape;
+
+/// Account types that Anchor doesn't check when loading them.
+const UNCHECKED_TYPES: &[&str] = &["UncheckedAccount", "AccountInfo"];
+
+/// Account types of the programs and sysvars passed to the instruction.
+const PROGRAM_TYPES: &[&str] = &["Program", "Interface", "Sysvar"];
+
+/// The relation set by a constraint between two accounts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Relation {
+    /// `has_one = <target>`: the account stores the key of the target.
+    HasOne,
+    /// `token::authority`, `mint::authority`, `associated_token::authority`,
+    /// `mint::freeze_authority`.
+    Authority,
+    /// `seeds = [...]`, `bump = ...`: the address of the account is derived from the target.
+    Seeds,
+    /// `token::mint`, `associated_token::mint`, `*::token_program`.
+    Token,
+    /// `payer`, `realloc::payer`, `close`: lamports move between the accounts.
+    Lamports,
+    /// `constraint`, `address`, `owner` expressions referring to the target.
+    Constraint,
+}
+
+impl Relation {
+    /// Returns the relation set by an `#[account(...)]` argument, if it refers to accounts.
+    fn of_arg(key: &str) -> Option<Self> {
+        match key {
+            "has_one" => Some(Self::HasOne),
+            "seeds" | "bump" => Some(Self::Seeds),
+            "payer" | "realloc::payer" | "close" => Some(Self::Lamports),
+            "constraint" | "address" | "owner" => Some(Self::Constraint),
+            _ if key.ends_with("authority") => Some(Self::Authority),
+            _ if key.ends_with("::mint") || key.ends_with("::token_program") => Some(Self::Token),
+            _ => None,
+        }
+    }
+
+    /// DOT attributes of the edges of the relation.
+    fn edge_style(self) -> &'static str {
+        match self {
+            Self::HasOne => "color=\"#1f6feb\", penwidth=2",
+            Self::Authority => "color=\"#cf222e\", penwidth=2",
+            Self::Seeds => "color=\"#8250df\", style=dashed",
+            Self::Token => "color=\"#57606a\"",
+            Self::Lamports => "color=\"#9a6700\", style=dotted",
+            Self::Constraint => "color=\"#57606a\", style=dashed",
+        }
+    }
+}
+
+/// An account of the context, a node of the graph.
+#[derive(Debug, Clone)]
+pub(crate) struct AccountNode {
+    /// The name of the field, prefixed by the fields of the nested accounts structs holding it
+    /// (`common.vault`).
+    pub(crate) name: String,
+    /// The account type, with the state account it holds, e.g. `Account<Vault>`.
+    pub(crate) ty: String,
+    /// `signer`, `mut`, `init`, `close`, `pda`.
+    pub(crate) flags: Vec<&'static str>,
+    /// The normalized seeds of a PDA, e.g. `[b"vault", <pubkey>]`.
+    pub(crate) seeds: Option<String>,
+}
+
+/// A relation between two accounts, or between an account and an instruction argument.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OwnershipEdge {
+    pub(crate) from: String,
+    /// An account, or `arg:<name>` for an `#[instruction(...)]` argument.
+    pub(crate) to: String,
+    /// The `#[account(...)]` argument setting the relation, e.g. `has_one`.
+    pub(crate) label: String,
+    pub(crate) relation: Relation,
+}
+
+/// The ownership graph of an instruction.
+#[derive(Debug, Clone)]
+pub(crate) struct OwnershipGraph {
+    pub(crate) instruction: String,
+    pub(crate) accounts_struct: String,
+    pub(crate) accounts: Vec<AccountNode>,
+    /// The `#[instruction(...)]` arguments the constraints refer to.
+    pub(crate) args: Vec<String>,
+    pub(crate) edges: Vec<OwnershipEdge>,
+}
+
+/// Collects the identifiers of an expression.
+#[derive(Default)]
+struct Idents(BTreeSet<String>);
+
+impl<'ast> Visit<'ast> for Idents {
+    fn visit_ident(&mut self, ident: &'ast Ident) {
+        self.0.insert(ident.to_string());
+    }
+}
+
+fn idents_of(expr: &Expr) -> BTreeSet<String> {
+    let mut idents = Idents::default();
+    idents.visit_expr(expr);
+    idents.0
+}
+
+/// Returns the flags of a field, from its type and its constraints.
+fn flags_of(field: &AccountsField) -> Vec<&'static str> {
+    let mut flags = vec![];
+    if field.account_type() == "Signer" || field.has_arg("signer") {
+        flags.push("signer");
+    }
+    if ["mut", "init", "init_if_needed", "zero", "close", "realloc"]
+        .iter()
+        .any(|key| field.has_arg(key))
+    {
+        flags.push("mut");
+    }
+    if ["init", "init_if_needed", "zero"]
+        .iter()
+        .any(|key| field.has_arg(key))
+    {
+        flags.push("init");
+    }
+    if field.has_arg("close") {
+        flags.push("close");
+    }
+    if field.has_arg("seeds") {
+        flags.push("pda");
+    }
+    flags
+}
+
+/// Builds the graph of the accounts struct `name`.
+struct GraphBuilder<'a> {
+    structs: &'a HashMap<String, AccountsStruct>,
+    constants: &'a HashMap<String, Vec<u8>>,
+    graph: OwnershipGraph,
+    /// The nested structs being visited, against recursive structs.
+    visiting: Vec<String>,
+}
+
+impl GraphBuilder<'_> {
+    /// Adds the fields of an accounts struct, their names prefixed by `prefix`, and the
+    /// relations of their constraints.
+    fn add_struct(&mut self, accounts_struct: &AccountsStruct, prefix: &str) {
+        if self.visiting.contains(&accounts_struct.name) {
+            return;
+        }
+        self.visiting.push(accounts_struct.name.clone());
+
+        for field in &accounts_struct.fields {
+            let name = format!("{}{}", prefix, field.name);
+            if let Some(nested) = self.structs.get(&field.account_type()) {
+                self.add_struct(nested, &format!("{}.", name));
+                continue;
+            }
+
+            let seeds = match field.arg("seeds").and_then(|arg| arg.value.as_ref()) {
+                Some(Expr::Array(seeds)) => Some(seeds_to_string(
+                    &seeds
+                        .elems
+                        .iter()
+                        .map(|seed| {
+                            normalize_seed(seed, self.constants, &accounts_struct.instruction_args)
+                        })
+                        .collect::<Vec<_>>(),
+                )),
+                _ => None,
+            };
+            let ty = match field.state_type() {
+                Some(state) => format!("{}<{}>", field.account_type(), state),
+                None => field.account_type(),
+            };
+            self.graph.accounts.push(AccountNode {
+                name: name.clone(),
+                ty,
+                flags: flags_of(field),
+                seeds,
+            });
+
+            for arg in &field.args {
+                let (Some(relation), Some(value)) = (Relation::of_arg(&arg.key), &arg.value) else {
+                    continue;
+                };
+                for ident in idents_of(value) {
+                    let to = if ident != field.name && accounts_struct.field(&ident).is_some() {
+                        format!("{}{}", prefix, ident)
+                    } else if relation == Relation::Seeds
+                        && accounts_struct
+                            .instruction_args
+                            .iter()
+                            .any(|(arg, _)| *arg == ident)
+                    {
+                        if !self.graph.args.contains(&ident) {
+                            self.graph.args.push(ident.clone());
+                        }
+                        format!("arg:{}", ident)
+                    } else {
+                        continue;
+                    };
+                    let edge = OwnershipEdge {
+                        from: name.clone(),
+                        to,
+                        label: arg.key.clone(),
+                        relation,
+                    };
+                    if !self.graph.edges.contains(&edge) {
+                        self.graph.edges.push(edge);
+                    }
+                }
+            }
+        }
+
+        self.visiting.pop();
+    }
+}
+
+/// Builds the ownership graph of each instruction of a program whose accounts struct is
+/// found in the sources, in the order of the IDL.
+///
+/// A field holding a nested accounts struct is replaced by the fields of that struct, prefixed
+/// by its name. The relations to a nested account refer to its prefixed name.
+///
+/// # Arguments
+///
+/// * `idl` - The IDL of the program, for its instructions.
+/// * `crate_root` - The root of the crate of the program.
+/// * `files` - The path and the syntax tree of the sources of the crate.
+pub(crate) fn build_ownership_graphs(
+    idl: &Idl,
+    crate_root: &Path,
+    files: &[(String, syn::File)],
+) -> Vec<OwnershipGraph> {
+    let instr_to_struct = map_instruction_to_struct(&merged_sources(crate_root));
+    let mut structs = HashMap::new();
+    let mut constants = HashMap::new();
+    for (path, file) in files {
+        for accounts_struct in accounts_structs(file, path) {
+            structs.insert(accounts_struct.name.clone(), accounts_struct);
+        }
+        constants.extend(byte_constants(file));
+    }
+
+    idl.instructions
+        .iter()
+        .filter_map(|ix| {
+            let accounts_struct = structs.get(instr_to_struct.get(&ix.name)?)?;
+            let mut builder = GraphBuilder {
+                structs: &structs,
+                constants: &constants,
+                graph: OwnershipGraph {
+                    instruction: ix.name.clone(),
+                    accounts_struct: accounts_struct.name.clone(),
+                    accounts: vec![],
+                    args: vec![],
+                    edges: vec![],
+                },
+                visiting: vec![],
+            };
+            builder.add_struct(accounts_struct, "");
+            // a nested field may refer to an account of the parent struct, not a node
+            let graph = &mut builder.graph;
+            let names: BTreeSet<String> = graph
+                .accounts
+                .iter()
+                .map(|account| account.name.clone())
+                .collect();
+            graph
+                .edges
+                .retain(|edge| edge.to.starts_with("arg:") || names.contains(&edge.to));
+            Some(builder.graph)
+        })
+        .collect()
+}
+
+/// Name of a node in the DOT.
+fn node_name(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\\\""))
+}
+
+impl OwnershipGraph {
+    /// Renders the graph to DOT.
+    ///
+    /// The signers are green, the unchecked accounts (`UncheckedAccount`, `AccountInfo`)
+    /// yellow, the PDAs blue and the programs grey; the edges are colored by relation.
+    pub(crate) fn to_dot(&self) -> String {
+        let mut dot = vec![];
+        write_graph_header(&mut dot).expect("Writing to a Vec can't fail");
+        let mut dot = String::from_utf8(dot).expect("The DOT header is UTF-8");
+        // the parallel edges of different relations are kept apart
+        dot.push_str(&format!(
+            "concentrate=false;\nlabel=<<b>{}</b> ({})>;\nlabelloc=t;\n",
+            html_escape(&self.instruction),
+            html_escape(&self.accounts_struct)
+        ));
+
+        for account in &self.accounts {
+            let base_type = account.ty.split('<').next().unwrap_or_default();
+            let color = if account.flags.contains(&"signer") {
+                "#dafbe1"
+            } else if UNCHECKED_TYPES.contains(&base_type) {
+                "#fff8c5"
+            } else if account.flags.contains(&"pda") {
+                "#ddf4ff"
+            } else if PROGRAM_TYPES.contains(&base_type) {
+                "#eaeef2"
+            } else {
+                "white"
+            };
+            let mut rows = vec![
+                format!("<b>{}</b>", html_escape(&account.name)),
+                html_escape(&account.ty),
+            ];
+            if !account.flags.is_empty() {
+                rows.push(format!("[{}]", account.flags.join(", ")));
+            }
+            if let Some(seeds) = &account.seeds {
+                rows.push(format!("seeds = {}", html_escape(seeds)));
+            }
+            dot.push_str(&format!(
+                "{} [fillcolor=\"{}\", label=<<table border=\"0\">{}</table>>];\n",
+                node_name(&account.name),
+                color,
+                rows.iter()
+                    .map(|row| format!("<tr><td>{}</td></tr>", row))
+                    .collect::<String>()
+            ));
+        }
+        for arg in &self.args {
+            dot.push_str(&format!(
+                "{} [shape=ellipse, label=<arg <i>{}</i>>];\n",
+                node_name(&format!("arg:{}", arg)),
+                html_escape(arg)
+            ));
+        }
+        for edge in &self.edges {
+            dot.push_str(&format!(
+                "{} -> {} [label=<{}>, {}];\n",
+                node_name(&edge.from),
+                node_name(&edge.to),
+                html_escape(&edge.label),
+                edge.relation.edge_style()
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Writes the graphs of a program to `<dir>/<instruction>.dot`.
+pub(crate) fn write_ownership_graphs(graphs: &[OwnershipGraph], dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    for graph in graphs {
+        let mut file = std::fs::File::create(dir.join(format!("{}.dot", graph.instruction)))?;
+        file.write_all(graph.to_dot().as_bytes())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recap::idl::load_idl;
+    use crate::recap::rows::parsed_sources;
+
+    #[test]
+    fn builds_ownership_graphs_for_fixture() {
+        let root = Path::new("test_cases/recap_anchor");
+        let crate_root = root.join("programs/recap_anchor");
+        let idl = load_idl(&root.join("idl/recap_anchor.json")).unwrap();
+        let graphs = build_ownership_graphs(&idl, &crate_root, &parsed_sources(&crate_root));
+
+        let names: Vec<&str> = graphs.iter().map(|g| g.instruction.as_str()).collect();
+        // the order of the IDL
+        assert_eq!(names, vec!["close_state", "deposit", "transfer"]);
+        let edges = |graph: &OwnershipGraph| -> Vec<(String, String, String)> {
+            graph
+                .edges
+                .iter()
+                .map(|e| (e.from.clone(), e.to.clone(), e.label.clone()))
+                .collect()
+        };
+        let edge = |from: &str, to: &str, label: &str| {
+            (from.to_string(), to.to_string(), label.to_string())
+        };
+
+        let deposit = &graphs[1];
+        let vault = deposit.accounts.iter().find(|a| a.name == "vault").unwrap();
+        assert_eq!(vault.flags, vec!["mut", "pda"]);
+        assert_eq!(vault.seeds.as_deref(), Some("[b\"vault\", <pubkey>]"));
+        // the bump of the vault is an instruction argument
+        assert_eq!(deposit.args, vec!["vault_bump"]);
+        assert_eq!(
+            edges(deposit),
+            vec![
+                edge("user_state", "user", "payer"),
+                edge("user_state", "user", "seeds"),
+                edge("vault", "user", "seeds"),
+                edge("vault", "arg:vault_bump", "bump"),
+            ]
+        );
+
+        let transfer = &graphs[2];
+        assert_eq!(
+            edges(transfer),
+            vec![
+                edge("from", "mint", "token::mint"),
+                edge("from", "authority", "token::authority"),
+                edge("to", "mint", "token::mint"),
+            ]
+        );
+        assert_eq!(edges(&graphs[0])[0], edge("user_state", "user", "close"));
+
+        let dot = transfer.to_dot();
+        assert!(dot.starts_with("digraph {"));
+        assert!(dot.contains("\"from\" -> \"authority\" [label=<token::authority>"));
+        assert!(dot.contains("<b>authority</b>"));
+    }
+}
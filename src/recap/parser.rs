@@ -14,6 +14,31 @@ pub(crate) struct FieldMeta {
     pub(crate) has_space: bool,
     pub(crate) has_realloc: bool,
     pub(crate) has_realloc_zero: bool,
+    pub(crate) realloc_zero: Option<String>, // value of `realloc::zero = ...`
+    // lifecycle-related
+    pub(crate) has_init_if_needed: bool,
+    pub(crate) is_init: bool, // `init`, `init_if_needed` or `zero`
+    pub(crate) close: Option<String>, // destination of `close = ...`
+    // PDA-related
+    pub(crate) seeds: Option<String>, // `[...]` of `seeds = [...]`, whitespace-normalized
+    pub(crate) bump: BumpSource,
+    // what the source enforces, to compare with the IDL flags
+    pub(crate) ty: String,
+    pub(crate) is_signer: bool,
+    pub(crate) is_writable: bool,
+}
+
+/// Where the `bump` of a seeded account comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum BumpSource {
+    /// No `bump` in the attributes.
+    Missing,
+    /// Bare `bump`: Anchor finds the canonical bump.
+    Canonical,
+    /// `bump = <expr>` not coming from the arguments, e.g. a bump stored in an account.
+    Expr,
+    /// `bump = <expr>` where `<expr>` comes from an `#[instruction(...)]` argument.
+    Argument(String),
 }
 
 pub(crate) type AccountsStructMap = HashMap<String, HashMap<String, FieldMeta>>;
@@ -81,6 +106,165 @@ pub(crate) fn map_instruction_to_struct(src: &str) -> InstrToStructMap {
     out
 }
 
+/// Returns the top-level arguments of every `#[<name>(...)]` attribute in `attrs`.
+///
+/// Commas inside (), [], {} and string literals do not split arguments, so that
+/// `seeds = [b"a", user.key().as_ref()]` stays a single argument.
+fn attribute_arguments<'a>(attrs: &'a str, name: &str) -> Vec<&'a str> {
+    let attr_re = regex::Regex::new(&format!(r"#\s*\[\s*{}\s*\(", name)).unwrap();
+    let mut args = Vec::new();
+    for m in attr_re.find_iter(attrs) {
+        let mut depth = 1i32;
+        let mut in_string = false;
+        let mut escaped = false;
+        let mut start = m.end();
+        for (i, ch) in attrs[m.end()..].char_indices() {
+            let i = m.end() + i;
+            if in_string {
+                match ch {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    '"' => in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match ch {
+                '"' => in_string = true,
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        args.push(attrs[start..i].trim());
+                        break;
+                    }
+                }
+                ',' if depth == 1 => {
+                    args.push(attrs[start..i].trim());
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+    }
+    args.retain(|arg| !arg.is_empty());
+    args
+}
+
+/// Names of the arguments declared with `#[instruction(name: Type, ...)]`.
+fn instruction_arguments(attrs: &str) -> Vec<String> {
+    attribute_arguments(attrs, "instruction")
+        .into_iter()
+        .filter_map(|arg| arg.split_once(':'))
+        .map(|(name, _)| name.trim().trim_start_matches("mut ").trim().to_string())
+        .collect()
+}
+
+/// Returns the value of the `<key> = <expr>` argument among the arguments of stacked
+/// `#[account(...)]` attributes, whitespace-normalized.
+fn argument_value(account_args: &[&str], key: &str) -> Option<String> {
+    account_args.iter().find_map(|arg| {
+        let expr = arg
+            .strip_prefix(key)?
+            .trim_start()
+            .strip_prefix('=')
+            .filter(|rest| !rest.starts_with('='))?;
+        Some(expr.split_whitespace().collect::<Vec<_>>().join(" "))
+    })
+}
+
+/// Finds the `bump` among the arguments of stacked `#[account(...)]` attributes.
+///
+/// A `bump = <expr>` whose first identifier is one of `instruction_args` is user-supplied.
+fn bump_source(account_args: &[&str], instruction_args: &[String]) -> BumpSource {
+    for arg in account_args.iter().copied() {
+        if arg == "bump" {
+            return BumpSource::Canonical;
+        }
+        let Some(expr) = arg
+            .strip_prefix("bump")
+            .map(str::trim_start)
+            .and_then(|rest| rest.strip_prefix('='))
+            .filter(|rest| !rest.starts_with('='))
+        else {
+            continue;
+        };
+        let expr = expr.trim().to_string();
+        let root = expr
+            .trim_start_matches('*')
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .next()
+            .unwrap_or_default();
+        return if instruction_args.iter().any(|name| name == root) {
+            BumpSource::Argument(expr)
+        } else {
+            BumpSource::Expr
+        };
+    }
+    BumpSource::Missing
+}
+
+/// Returns the account type of a field, without `Box<...>` / `Option<...>` wrappers nor path,
+/// e.g. `Option<Box<anchor_lang::prelude::Signer<'info>>>` -> `Signer`.
+pub(crate) fn account_type(ty: &str) -> &str {
+    let mut ty = ty.trim();
+    loop {
+        let name = ty.split('<').next().unwrap_or(ty).trim();
+        let name = name.rsplit("::").next().unwrap_or(name);
+        match (name, ty.find('<'), ty.rfind('>')) {
+            ("Box" | "Option", Some(start), Some(end)) if start < end => {
+                ty = ty[start + 1..end].trim();
+            }
+            _ => return name,
+        }
+    }
+}
+
+/// Returns the state account held by a field of type `Account`, `AccountLoader` or
+/// `InterfaceAccount`, without its path, e.g. `Box<Account<'info, crate::state::Vault>>` -> `Vault`.
+pub(crate) fn state_type(ty: &str) -> Option<&str> {
+    if !matches!(
+        account_type(ty),
+        "Account" | "AccountLoader" | "InterfaceAccount"
+    ) {
+        return None;
+    }
+    let inner = &ty[ty.find('<')? + 1..ty.rfind('>')?];
+    let inner = inner.rsplit(',').next()?.trim();
+    let inner = inner
+        .split('<')
+        .next()
+        .unwrap_or(inner)
+        .trim_end_matches('>')
+        .trim();
+    Some(inner.rsplit("::").next().unwrap_or(inner))
+}
+
+/// Lists the names of the `#[account]` / `#[account(zero_copy)]` structs, the state accounts
+/// owned by the program.
+pub(crate) fn state_accounts(src: &str) -> Vec<String> {
+    let account_re = regex::Regex::new(concat!(
+        r"#\s*\[\s*account\s*(?:\([^\]]*\))?\s*\]",
+        r"(?:\s*#\s*\[[^\]]+\]|\s*//[^\n]*\n|\s)*",
+        r"pub\s+struct\s+([A-Za-z0-9_]+)"
+    ))
+    .unwrap();
+    account_re
+        .captures_iter(src)
+        .map(|c| c.get(1).unwrap().as_str().to_string())
+        .collect()
+}
+
+/// Returns the type of a field without its `Box<...>` wrapper, so that boxed and unboxed
+/// accounts of the same type compare equal.
+pub(crate) fn unboxed_type(ty: &str) -> &str {
+    ty.trim()
+        .strip_prefix("Box<")
+        .and_then(|inner| inner.strip_suffix('>'))
+        .map(str::trim)
+        .unwrap_or(ty.trim())
+}
+
 /// Extract #[derive(Accounts)] blocks and aggregate all #[account(...)] per field.
 pub(crate) fn extract_accounts_structs(src: &str) -> AccountsStructMap {
     let mut map: AccountsStructMap = HashMap::new();
@@ -88,7 +272,7 @@ pub(crate) fn extract_accounts_structs(src: &str) -> AccountsStructMap {
     // allow extra attributes (e.g. #[instruction(...)]) and comments between derive and struct.
     let struct_pat = concat!(
         r"#\s*\[\s*derive\s*\(\s*Accounts\s*\)\s*\]",
-        r"(?:\s*#\s*\[[^\]]+\]\s*)*",             // extra attributes
+        r"(?P<attrs>(?:\s*#\s*\[[^\]]+\]\s*)*)", // extra attributes
         r"(?:\s*//[^\n]*\n|\s*///[^\n]*\n|\s*)*", // comments/space
         r"\s*pub\s+struct\s+([A-Za-z0-9_]+)(?:\s*<[^>]*>)?\s*",
         r"\{([\s\S]*?)\n?\}"
@@ -98,13 +282,15 @@ pub(crate) fn extract_accounts_structs(src: &str) -> AccountsStructMap {
         .build()
         .unwrap();
 
-    // capture stacked #[account(...)] blocks; tolerate comments around; allow pub or pub(...)
+    // capture every field with its stacked #[account(...)] blocks, if any; tolerate comments
+    // around; allow pub or pub(...); the type may contain generics up to three levels deep.
     let field_pat = concat!(
         r"(?:\s*//[^\n]*\n|\s*///[^\n]*\n|\s*)*", // comments/space before
-        r"(?P<attrs>(?:#\s*\[\s*account\s*\((?:[\s\S]*?)\)\s*\]\s*)+)",
+        r"(?P<attrs>(?:#\s*\[\s*account\s*\((?:[\s\S]*?)\)\s*\]\s*)*)",
         r"(?:\s*//[^\n]*\n|\s*///[^\n]*\n|\s*)*", // comments/space after
         r"(?:pub(?:\([^)]+\))?\s+)?",
-        r"(?P<field>[A-Za-z0-9_]+)\s*:\s*[^,]+,\s*",
+        r"(?P<field>[A-Za-z0-9_]+)\s*:\s*",
+        r"(?P<ty>(?:[^,<>]|<(?:[^<>]|<(?:[^<>]|<[^<>]*>)*>)*>)+)(?:,|\s*$)\s*",
     );
     let field_re = regex::RegexBuilder::new(field_pat)
         .dot_matches_new_line(true)
@@ -133,14 +319,32 @@ pub(crate) fn extract_accounts_structs(src: &str) -> AccountsStructMap {
     let realloc_re = regex::Regex::new(r"\brealloc\b").unwrap();
     let realloc_zero_re = regex::Regex::new(r"realloc::zero\s*=").unwrap();
 
+    // lifecycle markers
+    let init_if_needed_re = regex::Regex::new(r"\binit_if_needed\b").unwrap();
+    let close_re = regex::Regex::new(r"\bclose\s*=\s*([A-Za-z0-9_]+)").unwrap();
+
     for cap in struct_re.captures_iter(src) {
-        let struct_name = cap.get(1).unwrap().as_str().to_string();
-        let body = cap.get(2).unwrap().as_str();
+        let instruction_args = instruction_arguments(cap.name("attrs").unwrap().as_str());
+        let struct_name = cap.get(2).unwrap().as_str().to_string();
+        let body = cap.get(3).unwrap().as_str();
         let mut fields: HashMap<String, FieldMeta> = HashMap::new();
 
         for f in field_re.captures_iter(body) {
             let attrs_chunk = f.name("attrs").unwrap().as_str();
             let fname = f.name("field").unwrap().as_str().to_string();
+            let ty = f
+                .name("ty")
+                .unwrap()
+                .as_str()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            let account_args = attribute_arguments(attrs_chunk, "account");
+            let has_arg = |name: &str| {
+                account_args
+                    .iter()
+                    .any(|arg| *arg == name || arg.starts_with(&format!("{} @", name)))
+            };
 
             // check spl constraints
             let has_token_mint = token_mint_re.is_match(attrs_chunk);
@@ -150,6 +354,14 @@ pub(crate) fn extract_accounts_structs(src: &str) -> AccountsStructMap {
             let has_mint_freeze_authority = mint_freeze_re.is_match(attrs_chunk);
             let has_assoc_mint = assoc_mint_re.is_match(attrs_chunk);
             let has_assoc_authority = assoc_authority_re.is_match(attrs_chunk);
+            let has_seeds = seeds_re.is_match(attrs_chunk);
+
+            // `init` of an account that is neither a PDA nor an ATA needs its keypair to sign
+            let has_init = has_arg("init") || has_arg("init_if_needed");
+            let is_signer = account_type(&ty) == "Signer"
+                || has_arg("signer")
+                || (has_init && !has_seeds && !has_assoc_mint && !has_assoc_authority);
+            let is_writable = has_arg("mut") || has_arg("zero") || has_init;
 
             let meta = FieldMeta {
                 name: fname.clone(),
@@ -157,7 +369,7 @@ pub(crate) fn extract_accounts_structs(src: &str) -> AccountsStructMap {
                 has_owner: owner_re.is_match(attrs_chunk),
                 has_has_one: has_one_re.is_match(attrs_chunk),
                 has_constraint: constraint_re.is_match(attrs_chunk),
-                has_seeds: seeds_re.is_match(attrs_chunk),
+                has_seeds,
                 has_spl: has_token_mint
                     || has_token_authority
                     || has_mint_authority
@@ -168,6 +380,17 @@ pub(crate) fn extract_accounts_structs(src: &str) -> AccountsStructMap {
                 has_space: space_re.is_match(attrs_chunk),
                 has_realloc: realloc_re.is_match(attrs_chunk),
                 has_realloc_zero: realloc_zero_re.is_match(attrs_chunk),
+                realloc_zero: argument_value(&account_args, "realloc::zero"),
+                has_init_if_needed: init_if_needed_re.is_match(attrs_chunk),
+                is_init: has_init || has_arg("zero"),
+                close: close_re
+                    .captures(attrs_chunk)
+                    .map(|c| c.get(1).unwrap().as_str().to_string()),
+                seeds: argument_value(&account_args, "seeds"),
+                bump: bump_source(&account_args, &instruction_args),
+                ty,
+                is_signer,
+                is_writable,
             };
 
             fields.insert(fname, meta);
@@ -183,7 +406,10 @@ pub(crate) fn extract_accounts_structs(src: &str) -> AccountsStructMap {
 
 #[cfg(test)]
 mod tests {
-    use super::map_instruction_to_struct;
+    use super::{
+        account_type, extract_accounts_structs, map_instruction_to_struct, state_type,
+        BumpSource,
+    };
 
     #[test]
     fn maps_context_with_lifetimes_and_nested_generics() {
@@ -238,4 +464,90 @@ mod tests {
         assert_eq!(got["initialize"], "Initialize");
         assert_eq!(got["update_mint_config"], "UpdateMintConfig");
     }
+
+    #[test]
+    fn detects_bump_sources() {
+        let src = r#"
+            #[derive(Accounts)]
+            #[instruction(vault_bump: u8, params: InitParams)]
+            pub struct Initialize<'info> {
+                #[account(init, payer = user, space = 8 + 32, seeds = [b"config", user.key().as_ref()], bump)]
+                pub config: Account<'info, Config>,
+                #[account(seeds = [b"vault", config.key().as_ref()], bump = vault_bump)]
+                pub vault: SystemAccount<'info>,
+                #[account(seeds = [b"escrow"], bump = params.escrow_bump)]
+                pub escrow: SystemAccount<'info>,
+                #[account(seeds = [b"state", config.key().as_ref()], bump = config.state_bump)]
+                pub state: Account<'info, State>,
+                #[account(mut, seeds = [b"treasury"])]
+                pub treasury: SystemAccount<'info>,
+                #[account(mut)]
+                pub user: Signer<'info>,
+            }
+        "#;
+
+        let structs = extract_accounts_structs(src);
+        let fields = &structs["Initialize"];
+        assert_eq!(fields["config"].bump, BumpSource::Canonical);
+        assert_eq!(
+            fields["vault"].bump,
+            BumpSource::Argument("vault_bump".to_string())
+        );
+        assert_eq!(
+            fields["escrow"].bump,
+            BumpSource::Argument("params.escrow_bump".to_string())
+        );
+        assert_eq!(fields["state"].bump, BumpSource::Expr);
+        assert_eq!(
+            fields["vault"].seeds.as_deref(),
+            Some("[b\"vault\", config.key().as_ref()]")
+        );
+        assert_eq!(fields["treasury"].bump, BumpSource::Missing);
+        assert!(fields["treasury"].has_seeds);
+        assert!(!fields["user"].has_seeds);
+    }
+
+    #[test]
+    fn detects_source_signers_and_writables() {
+        let src = r#"
+            #[derive(Accounts)]
+            pub struct Deposit<'info> {
+                #[account(mut, has_one = authority)]
+                pub vault: Box<Account<'info, Vault>>,
+                /// CHECK: checked by the `signer` constraint
+                #[account(signer)]
+                pub authority: UncheckedAccount<'info>,
+                #[account(init, payer = payer, space = 8)]
+                pub receipt: Account<'info, Receipt>,
+                #[account(init_if_needed, payer = payer, associated_token::mint = mint, associated_token::authority = payer)]
+                pub payer_ata: Account<'info, TokenAccount>,
+                #[account(mut)]
+                pub payer: Signer<'info>,
+                pub mint: Account<'info, Mint>,
+                pub system_program: Program<'info, System>
+            }
+        "#;
+
+        let structs = extract_accounts_structs(src);
+        let fields = &structs["Deposit"];
+        let flags = |name: &str| (fields[name].is_signer, fields[name].is_writable);
+        assert_eq!(fields.len(), 7);
+        assert_eq!(flags("vault"), (false, true));
+        assert_eq!(flags("authority"), (true, false));
+        assert_eq!(flags("receipt"), (true, true));
+        assert_eq!(flags("payer_ata"), (false, true));
+        assert_eq!(flags("payer"), (true, true));
+        assert_eq!(flags("mint"), (false, false));
+        assert_eq!(fields["system_program"].ty, "Program<'info, System>");
+        assert!(fields["vault"].has_has_one);
+
+        assert_eq!(account_type("Option<Box<Account<'info, Vault>>>"), "Account");
+        assert_eq!(account_type("anchor_lang::prelude::Signer<'info>"), "Signer");
+        assert_eq!(
+            state_type("Box<Account<'info, crate::state::Vault>>"),
+            Some("Vault")
+        );
+        assert_eq!(state_type("Signer<'info>"), None);
+        assert!(fields["receipt"].is_init && !fields["vault"].is_init);
+    }
 }
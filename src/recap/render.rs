@@ -1,9 +1,19 @@
+use super::checklist::{Check, ChecklistRow};
+use super::events::EventReport;
+use super::layout::{LayoutReport, Size, DISCRIMINATOR_SIZE};
+use super::matrix::StateRow;
 use super::rows::Row;
+use crate::constraints::seeds::seeds_to_string;
+use crate::constraints::ConstraintReport;
+use crate::pubkeys::PubkeyInventory;
 
 pub(crate) fn to_markdown(rows: &[Row]) -> String {
     let mut s = String::new();
-    s.push_str("| Instruction | Signers | Writable | Constrained | Seeded | Memory |\n");
-    s.push_str("|---|---|---|---|---|---|\n");
+    s.push_str(concat!(
+        "| Instruction | Signers | Writable | Constrained | Seeded | Memory ",
+        "| Init / Close | Duplicates | PDA hygiene | Lamports |\n"
+    ));
+    s.push_str("|---|---|---|---|---|---|---|---|---|---|\n");
     for r in rows {
         let signers = if r.signers.is_empty() {
             "—".to_string()
@@ -30,10 +40,417 @@ pub(crate) fn to_markdown(rows: &[Row]) -> String {
         } else {
             r.memory.join("; ")
         };
+        let lifecycle = if r.lifecycle.is_empty() {
+            "—".to_string()
+        } else {
+            r.lifecycle.join("; ")
+        };
+        let duplicates = if r.duplicates.is_empty() {
+            "—".to_string()
+        } else {
+            r.duplicates.join("; ")
+        };
+        let pda_hygiene = if r.pda_hygiene.is_empty() {
+            "—".to_string()
+        } else {
+            r.pda_hygiene.join("; ")
+        };
+        let lamports = if r.lamports.is_empty() {
+            "—".to_string()
+        } else {
+            r.lamports.join("; ")
+        };
         s.push_str(&format!(
-            "| {} | {} | {} | {} | {} | {} |\n",
-            r.instruction, signers, writables, constrained, seeded, memory
+            "| {} | {} | {} | {} | {} | {} | {} | {} | {} | {} |\n",
+            r.instruction,
+            signers,
+            writables,
+            constrained,
+            seeded,
+            memory,
+            lifecycle,
+            duplicates,
+            pda_hygiene,
+            lamports
         ));
     }
     s
 }
+
+/// Lists the instructions taking the bump of a PDA from their arguments, if any.
+///
+/// A user-supplied bump lets the caller pick a non-canonical bump, so that several addresses
+/// may be derived from the same seeds.
+pub(crate) fn pda_hygiene_summary(rows: &[Row]) -> Option<String> {
+    let flagged: Vec<&Row> = rows.iter().filter(|r| !r.bump_args.is_empty()).collect();
+    if flagged.is_empty() {
+        return None;
+    }
+
+    let mut s = String::new();
+    s.push_str("**PDA hygiene** — bump taken from instruction arguments:\n\n");
+    for r in flagged {
+        s.push_str(&format!("- `{}`: {}\n", r.instruction, r.bump_args.join(", ")));
+    }
+    Some(s)
+}
+
+/// Lists the signer/writable flags of the IDL that the source does not enforce, or the other
+/// way around, if any.
+///
+/// An IDL drifting from the source misleads the integrators building transactions from it.
+pub(crate) fn idl_mismatch_summary(rows: &[Row]) -> Option<String> {
+    let flagged: Vec<&Row> = rows
+        .iter()
+        .filter(|r| !r.idl_mismatches.is_empty())
+        .collect();
+    if flagged.is_empty() {
+        return None;
+    }
+
+    let mut s = String::new();
+    s.push_str("**IDL / source mismatches** — signer and writable flags:\n\n");
+    for r in flagged {
+        for mismatch in &r.idl_mismatches {
+            s.push_str(&format!("- `{}`: {}\n", r.instruction, mismatch));
+        }
+    }
+    Some(s)
+}
+
+/// Lists the seeds that could derive the same address for accounts of different types, and
+/// the `has_one` targets that are not validated, if any (see [`crate::constraints`]).
+pub(crate) fn constraint_summary(report: &ConstraintReport) -> Option<String> {
+    if report.is_empty() {
+        return None;
+    }
+
+    let mut s = String::new();
+    s.push_str(
+        "**Constraint consistency** — seeds collisions and unvalidated `has_one` targets:\n\n",
+    );
+    for collision in &report.seed_collisions {
+        let accounts: Vec<String> = collision
+            .accounts
+            .iter()
+            .map(|a| format!("`{}` ({}.{})", a.account, a.accounts_struct, a.field))
+            .collect();
+        s.push_str(&format!(
+            "- seeds `{}` shared by {}\n",
+            seeds_to_string(&collision.seeds),
+            accounts.join(", ")
+        ));
+    }
+    for finding in &report.unvalidated_has_one {
+        s.push_str(&format!(
+            "- `{}.{}`: `has_one = {}`, but `{}` is an unvalidated `{}`\n",
+            finding.accounts_struct,
+            finding.field,
+            finding.target,
+            finding.target,
+            finding.target_type
+        ));
+    }
+    Some(s)
+}
+
+/// Lists the public keys written in the sources of a crate, with where and how each one is
+/// written, then the constants of the same name holding different keys, if the crate has keys
+/// (see [`crate::pubkeys`]).
+///
+/// The paths are relative to `crate_root`.
+pub(crate) fn pubkey_summary(
+    inventory: &PubkeyInventory,
+    crate_root: &std::path::Path,
+) -> Option<String> {
+    if inventory.is_empty() {
+        return None;
+    }
+
+    let location = |position: &crate::parsers::syn_ast::SourcePosition| {
+        let path = std::path::Path::new(&position.source_file);
+        format!(
+            "{}:{}",
+            path.strip_prefix(crate_root).unwrap_or(path).display(),
+            position.start_line
+        )
+    };
+    let mut s = String::new();
+    s.push_str(
+        "**Public keys** — `declare_id!`, `pubkey!`, 32-byte arrays and base58 strings of the \
+        sources:\n\n",
+    );
+    s.push_str("| Key | Written as | Where |\n");
+    s.push_str("|---|---|---|\n");
+    for (key, occurrences) in inventory.by_key() {
+        let mut written_as: Vec<String> = vec![];
+        for occurrence in &occurrences {
+            let form = match &occurrence.name {
+                Some(name) => format!("`{}` ({})", name, occurrence.kind.as_str()),
+                None => occurrence.kind.as_str().to_string(),
+            };
+            if !written_as.contains(&form) {
+                written_as.push(form);
+            }
+        }
+        let places: Vec<String> = occurrences.iter().map(|o| location(&o.position)).collect();
+        s.push_str(&format!(
+            "| `{}`{} | {} | {} |\n",
+            key,
+            if occurrences[0].is_well_known() {
+                " (well-known)"
+            } else {
+                ""
+            },
+            written_as.join(", "),
+            places.join(", ")
+        ));
+    }
+    let drifts = inventory.drifts();
+    if !drifts.is_empty() {
+        s.push_str("\nDifferent keys under the same name:\n\n");
+        for drift in drifts {
+            let keys: Vec<String> = drift
+                .occurrences
+                .iter()
+                .map(|o| format!("`{}` ({})", o.key, location(&o.position)))
+                .collect();
+            s.push_str(&format!("- `{}`: {}\n", drift.name, keys.join(", ")));
+        }
+    }
+    Some(s)
+}
+
+/// Renders the byte layout of each `#[account]` struct, then the `space = ...` constraints not
+/// matching them, if the program has accounts.
+pub(crate) fn layout_summary(report: &LayoutReport) -> Option<String> {
+    if report.layouts.is_empty() {
+        return None;
+    }
+
+    let mut s = String::new();
+    s.push_str(&format!(
+        "**Account layouts** — byte offsets and sizes of the `#[account]` structs, after the \
+        {}-byte discriminator:\n",
+        DISCRIMINATOR_SIZE
+    ));
+    for layout in &report.layouts {
+        s.push_str(&format!(
+            "\n`{}`{} — {} bytes\n\n",
+            layout.account,
+            if layout.zero_copy { " (zero-copy)" } else { "" },
+            layout.size
+        ));
+        s.push_str("| Field | Type | Offset | Size |\n");
+        s.push_str("|---|---|---|---|\n");
+        s.push_str(&format!(
+            "| _discriminator_ | `[u8; {}]` | 0 | {} |\n",
+            DISCRIMINATOR_SIZE, DISCRIMINATOR_SIZE
+        ));
+        for field in &layout.fields {
+            s.push_str(&format!(
+                "| {} | `{}` | {} | {} |\n",
+                field.name,
+                field.ty,
+                field
+                    .offset
+                    .map_or("—".to_string(), |offset| offset.to_string()),
+                field.size
+            ));
+        }
+    }
+
+    if !report.space_mismatches.is_empty() {
+        s.push_str("\n`space = ...` not matching the layout:\n\n");
+    }
+    for mismatch in &report.space_mismatches {
+        let bytes = |count: usize| format!("{} byte{}", count, if count == 1 { "" } else { "s" });
+        let difference = match mismatch.expected {
+            Size::Fixed(size) if mismatch.value > size => {
+                format!("{} too many", bytes(mismatch.value - size))
+            }
+            Size::Fixed(size) | Size::AtLeast(size) => {
+                format!("{} short", bytes(size.saturating_sub(mismatch.value)))
+            }
+            Size::Unknown => continue,
+        };
+        s.push_str(&format!(
+            "- `{}.{}` (`{}`): `space = {}` is {} bytes, the layout takes {} ({})\n",
+            mismatch.accounts_struct,
+            mismatch.field,
+            mismatch.account,
+            mismatch.space,
+            mismatch.value,
+            mismatch.expected,
+            difference
+        ));
+    }
+    Some(s)
+}
+
+/// Renders the events and log lines of each instruction, then the `#[event]` structs that are
+/// never emitted, if the program emits or logs anything.
+pub(crate) fn event_summary(report: &EventReport) -> Option<String> {
+    let observable = report
+        .instructions
+        .iter()
+        .any(|i| !i.events.is_empty() || !i.logs.is_empty());
+    if !observable && report.unused_events.is_empty() {
+        return None;
+    }
+
+    // the cells are code spans of a table
+    let escape = |text: &str| text.replace('|', "\\|");
+    let mut s = String::new();
+    s.push_str(
+        "**Events and logs** — events emitted and lines logged by each instruction, through \
+        the functions it calls:\n\n",
+    );
+    s.push_str("| Instruction | Events | Logs |\n");
+    s.push_str("|---|---|---|\n");
+    for i in &report.instructions {
+        let events: Vec<String> = i
+            .events
+            .iter()
+            .map(|e| {
+                format!(
+                    "`{} {{ {} }}`{}",
+                    e.event,
+                    e.fields.join(", "),
+                    if e.cpi { " (CPI)" } else { "" }
+                )
+            })
+            .collect();
+        let logs: Vec<String> = i
+            .logs
+            .iter()
+            .map(|l| {
+                let mut line = format!("`\"{}\"`", escape(&l.format));
+                if !l.args.is_empty() {
+                    line.push_str(&format!(" ({})", escape(&l.args.join(", "))));
+                }
+                line
+            })
+            .collect();
+        let cell = |items: Vec<String>| {
+            if items.is_empty() {
+                "—".to_string()
+            } else {
+                items.join("<br>")
+            }
+        };
+        s.push_str(&format!(
+            "| {} | {} | {} |\n",
+            i.instruction,
+            cell(events),
+            cell(logs)
+        ));
+    }
+    if !report.unused_events.is_empty() {
+        let events: Vec<String> = report
+            .unused_events
+            .iter()
+            .map(|e| format!("`{}`", e))
+            .collect();
+        s.push_str(&format!(
+            "\n`#[event]` structs never emitted: {}\n",
+            events.join(", ")
+        ));
+    }
+    Some(s)
+}
+
+/// Renders the state × instruction matrix: for each state account type, the instructions
+/// reading, writing, initializing and closing it, if the program has state accounts.
+pub(crate) fn state_matrix_summary(rows: &[StateRow]) -> Option<String> {
+    if rows.is_empty() {
+        return None;
+    }
+
+    let cell = |instructions: &[String]| {
+        if instructions.is_empty() {
+            "—".to_string()
+        } else {
+            instructions.join(", ")
+        }
+    };
+    let mut s = String::new();
+    s.push_str("**Account state** — instructions using each state account type:\n\n");
+    s.push_str("| Account | Read | Written | Init | Close |\n");
+    s.push_str("|---|---|---|---|---|\n");
+    for r in rows {
+        s.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            r.account,
+            cell(&r.read),
+            cell(&r.written),
+            cell(&r.init),
+            cell(&r.close)
+        ));
+    }
+    Some(s)
+}
+
+/// Renders a checklist answer as a table cell.
+fn check_cell(check: &Check) -> String {
+    match check {
+        Check::Yes => "yes".to_string(),
+        Check::No(failures) if failures.is_empty() => "**no**".to_string(),
+        Check::No(failures) => format!("**no**: {}", failures.join(", ")),
+        Check::Unknown(_) => "_unknown_".to_string(),
+        Check::NotApplicable => "n/a".to_string(),
+    }
+}
+
+/// Renders the threat-model checklist appendix: a table per program answering each item for
+/// each instruction, followed by the unknown items to check by hand.
+pub(crate) fn checklist_appendix(programs: &[(String, Vec<ChecklistRow>)]) -> Option<String> {
+    if programs.iter().all(|(_, rows)| rows.is_empty()) {
+        return None;
+    }
+
+    let mut s = String::new();
+    s.push_str("# Appendix — Audit checklist\n\n");
+    s.push_str(concat!(
+        "Filled from the IDL flags and the `#[derive(Accounts)]` structs: **no** items are ",
+        "leads to review, _unknown_ items need a manual follow-up.\n\n"
+    ));
+    for (program, rows) in programs.iter().filter(|(_, rows)| !rows.is_empty()) {
+        s.push_str(&format!("## Program `{}`\n\n", program));
+        s.push_str("| Instruction |");
+        for (label, _) in rows[0].items() {
+            s.push_str(&format!(" {} |", label));
+        }
+        s.push_str("\n|---|---|---|---|---|---|\n");
+        for r in rows {
+            s.push_str(&format!("| {} |", r.instruction));
+            for (_, check) in r.items() {
+                s.push_str(&format!(" {} |", check_cell(check)));
+            }
+            s.push('\n');
+        }
+
+        let unknowns: Vec<String> = rows
+            .iter()
+            .flat_map(|r| {
+                r.items()
+                    .into_iter()
+                    .filter_map(|(label, check)| match check {
+                        Check::Unknown(reasons) => Some(format!(
+                            "- [ ] `{}` — {}: {}\n",
+                            r.instruction,
+                            label,
+                            reasons.join(", ")
+                        )),
+                        _ => None,
+                    })
+            })
+            .collect();
+        if !unknowns.is_empty() {
+            s.push_str("\n**Manual follow-up**:\n\n");
+            s.extend(unknowns);
+        }
+        s.push('\n');
+    }
+    Some(s)
+}
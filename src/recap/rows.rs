@@ -1,9 +1,15 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::Path;
 
+use crate::parsers::lamports::block_manipulations;
+
+use super::events::instruction_bodies;
 use super::fs_utils::{read, walk};
 use super::idl::{flatten_accounts, Idl};
-use super::parser::{extract_accounts_structs, map_instruction_to_struct, AccountsStructMap};
+use super::parser::{
+    extract_accounts_structs, map_instruction_to_struct, unboxed_type, AccountsStructMap,
+    BumpSource,
+};
 
 #[derive(Debug)]
 pub(crate) struct Row {
@@ -13,19 +19,43 @@ pub(crate) struct Row {
     pub(crate) constrained: Vec<String>, // "field(marker,...)" where marker in {address,has_one,constraint,spl}
     pub(crate) seeded: Vec<String>,      // field names with seeds=[...]
     pub(crate) memory: Vec<String>,      // memory management (realloc, realloc::zero, space)
+    pub(crate) lifecycle: Vec<String>,   // "field(init_if_needed)" / "field(close = dest)"
+    pub(crate) duplicates: Vec<String>,  // "`Type`: a, b" for types shared by mutable fields
+    pub(crate) pda_hygiene: Vec<String>, // "field(no bump)" / "field(bump arg)" for seeded fields
+    pub(crate) bump_args: Vec<String>,   // "field (bump = expr)" where the bump is an instruction arg
+    pub(crate) idl_mismatches: Vec<String>, // signer/writable flags of the IDL not matching the source
+    pub(crate) lamports: Vec<String>,       // "account(-=,arithmetic)" for the direct lamport manipulations
 }
 
-pub(crate) fn build_rows_for_program(idl: &Idl, crate_root: &Path) -> Vec<Row> {
+/// Concatenates the `src/**/*.rs` files of a crate, for the regex-based parsing.
+pub(crate) fn merged_sources(crate_root: &Path) -> String {
     let src_dir = crate_root.join("src");
     let rs_files = walk(&src_dir)
         .into_iter()
         .filter(|p| p.extension().map(|e| e == "rs").unwrap_or(false))
         .collect::<Vec<_>>();
-    let merged_src = rs_files
+    rs_files
         .iter()
         .map(|p| read(p))
         .collect::<Vec<_>>()
-        .join("\n/*--file--*/\n");
+        .join("\n/*--file--*/\n")
+}
+
+/// Parses the `src/**/*.rs` files of a crate, with their paths, skipping the ones that don't
+/// parse.
+pub(crate) fn parsed_sources(crate_root: &Path) -> Vec<(String, syn::File)> {
+    walk(&crate_root.join("src"))
+        .into_iter()
+        .filter(|p| p.extension().map(|e| e == "rs").unwrap_or(false))
+        .filter_map(|p| {
+            let file = syn::parse_file(&read(&p)).ok()?;
+            Some((p.to_string_lossy().to_string(), file))
+        })
+        .collect()
+}
+
+pub(crate) fn build_rows_for_program(idl: &Idl, crate_root: &Path) -> Vec<Row> {
+    let merged_src = merged_sources(crate_root);
 
     let instr_to_struct = map_instruction_to_struct(&merged_src);
     let structs: AccountsStructMap = extract_accounts_structs(&merged_src);
@@ -37,7 +67,9 @@ pub(crate) fn build_rows_for_program(idl: &Idl, crate_root: &Path) -> Vec<Row> {
         flatten_accounts(&ix.accounts, &mut flat);
         let mut signers = BTreeSet::new();
         let mut writables = BTreeSet::new();
+        let mut idl_flags = HashMap::new();
         for (name, is_signer, is_writable) in flat {
+            idl_flags.insert(name.clone(), (is_signer, is_writable));
             if is_signer {
                 signers.insert(name.clone());
             }
@@ -49,14 +81,46 @@ pub(crate) fn build_rows_for_program(idl: &Idl, crate_root: &Path) -> Vec<Row> {
         let mut constrained = BTreeSet::new();
         let mut seeded = BTreeSet::new();
         let mut memory = BTreeSet::new();
+        let mut lifecycle = BTreeSet::new();
+        let mut by_type: BTreeMap<&str, (BTreeSet<&str>, bool)> = BTreeMap::new();
+        let mut pda_hygiene = BTreeSet::new();
+        let mut bump_args = BTreeSet::new();
+        let mut idl_mismatches = BTreeSet::new();
 
         if let Some(struct_name) = instr_to_struct.get(&ix.name) {
             if let Some(fields) = structs.get(struct_name) {
                 for (field_name, meta) in fields {
-                    if !idl_account_present(idl, &ix.name, field_name) {
+                    let Some((idl_signer, idl_writable)) = idl_flags.get(field_name).copied()
+                    else {
                         continue;
+                    };
+
+                    for (flag, in_idl, in_source) in [
+                        ("signer", idl_signer, meta.is_signer),
+                        ("writable", idl_writable, meta.is_writable),
+                    ] {
+                        match (in_idl, in_source) {
+                            (true, false) => {
+                                idl_mismatches.insert(format!(
+                                    "{} (`{}`): {} in IDL, not in source",
+                                    field_name, meta.ty, flag
+                                ));
+                            }
+                            (false, true) => {
+                                idl_mismatches.insert(format!(
+                                    "{} (`{}`): {} in source, not in IDL",
+                                    field_name, meta.ty, flag
+                                ));
+                            }
+                            _ => {}
+                        }
                     }
 
+                    let (same_type, any_writable) =
+                        by_type.entry(unboxed_type(&meta.ty)).or_default();
+                    same_type.insert(field_name.as_str());
+                    *any_writable |= meta.is_writable || idl_writable;
+
                     let mut tags = vec![];
                     if meta.has_address {
                         tags.push("address");
@@ -80,6 +144,17 @@ pub(crate) fn build_rows_for_program(idl: &Idl, crate_root: &Path) -> Vec<Row> {
 
                     if meta.has_seeds {
                         seeded.insert(field_name.clone());
+
+                        match &meta.bump {
+                            BumpSource::Missing => {
+                                pda_hygiene.insert(format!("{}(no bump)", field_name));
+                            }
+                            BumpSource::Argument(expr) => {
+                                pda_hygiene.insert(format!("{}(bump arg)", field_name));
+                                bump_args.insert(format!("{} (bump = {})", field_name, expr));
+                            }
+                            BumpSource::Canonical | BumpSource::Expr => {}
+                        }
                     }
 
                     let mut mt = Vec::new();
@@ -95,10 +170,31 @@ pub(crate) fn build_rows_for_program(idl: &Idl, crate_root: &Path) -> Vec<Row> {
                     if !mt.is_empty() {
                         memory.insert(format!("{}({})", field_name, mt.join(",")));
                     }
+
+                    let mut lt = Vec::new();
+                    if meta.has_init_if_needed {
+                        lt.push("init_if_needed".to_string());
+                    }
+                    if let Some(destination) = &meta.close {
+                        lt.push(format!("close = {}", destination));
+                    }
+
+                    if !lt.is_empty() {
+                        lifecycle.insert(format!("{}({})", field_name, lt.join(",")));
+                    }
                 }
             }
         }
 
+        // the same account may be passed for two fields of the same type
+        let duplicates = by_type
+            .into_iter()
+            .filter(|(_, (fields, any_writable))| fields.len() > 1 && *any_writable)
+            .map(|(ty, (fields, _))| {
+                format!("`{}`: {}", ty, fields.into_iter().collect::<Vec<_>>().join(", "))
+            })
+            .collect();
+
         rows.push(Row {
             instruction: ix.name.clone(),
             signers: signers.into_iter().collect(),
@@ -106,20 +202,119 @@ pub(crate) fn build_rows_for_program(idl: &Idl, crate_root: &Path) -> Vec<Row> {
             constrained: constrained.into_iter().collect(),
             seeded: seeded.into_iter().collect(),
             memory: memory.into_iter().collect(),
+            lifecycle: lifecycle.into_iter().collect(),
+            duplicates,
+            pda_hygiene: pda_hygiene.into_iter().collect(),
+            bump_args: bump_args.into_iter().collect(),
+            idl_mismatches: idl_mismatches.into_iter().collect(),
+            lamports: vec![],
         });
     }
 
     rows
 }
 
-fn idl_account_present(idl: &Idl, ix_name: &str, field_name: &str) -> bool {
-    idl.instructions
-        .iter()
-        .find(|ix| ix.name == ix_name)
-        .map(|ix| {
-            let mut flat = vec![];
-            flatten_accounts(&ix.accounts, &mut flat);
-            flat.into_iter().any(|(n, _, _)| n == field_name)
+/// Lists the direct lamport manipulations of each instruction of a crate, through the functions
+/// it calls, as `account(op,...)` by account (see [`crate::parsers::lamports`]).
+///
+/// The instructions are the functions of the `#[program]` module; the ones without
+/// manipulations are left out.
+pub(crate) fn lamport_manipulations(
+    sources: &[(String, syn::File)],
+) -> HashMap<String, Vec<String>> {
+    instruction_bodies(sources)
+        .into_iter()
+        .filter_map(|instruction| {
+            let mut by_account: BTreeMap<String, Vec<String>> = BTreeMap::new();
+            for block in instruction.blocks {
+                for (_, manipulation) in block_manipulations(block) {
+                    let operations = by_account.entry(manipulation.account).or_default();
+                    if !operations.contains(&manipulation.operation) {
+                        operations.push(manipulation.operation);
+                    }
+                }
+            }
+            if by_account.is_empty() {
+                return None;
+            }
+            let cells = by_account
+                .into_iter()
+                .map(|(account, operations)| format!("{}({})", account, operations.join(",")))
+                .collect();
+            Some((instruction.instruction, cells))
         })
-        .unwrap_or(false)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_rows_for_program, lamport_manipulations};
+    use crate::recap::idl::load_idl;
+    use std::path::Path;
+
+    #[test]
+    fn builds_rows_for_fixture() {
+        let root = Path::new("test_cases/recap_anchor");
+        let idl = load_idl(&root.join("idl/recap_anchor.json")).unwrap();
+        let rows = build_rows_for_program(&idl, &root.join("programs/recap_anchor"));
+        let row = |name: &str| rows.iter().find(|r| r.instruction == name).unwrap();
+
+        let deposit = row("deposit");
+        assert_eq!(deposit.seeded, vec!["user_state", "vault"]);
+        assert_eq!(deposit.lifecycle, vec!["user_state(init_if_needed)"]);
+        assert_eq!(deposit.pda_hygiene, vec!["vault(bump arg)"]);
+        assert_eq!(deposit.bump_args, vec!["vault (bump = vault_bump)"]);
+        assert!(deposit.duplicates.is_empty());
+        assert!(deposit.idl_mismatches.is_empty());
+
+        let transfer = row("transfer");
+        assert_eq!(
+            transfer.duplicates,
+            vec!["`Account<'info, TokenAccount>`: from, to"]
+        );
+        assert_eq!(
+            transfer.idl_mismatches,
+            vec!["authority (`UncheckedAccount<'info>`): signer in IDL, not in source"]
+        );
+
+        assert_eq!(row("close_state").lifecycle, vec!["user_state(close = user)"]);
+    }
+
+    #[test]
+    fn lists_lamport_manipulations() {
+        let source = r#"
+            #[program]
+            pub mod vault {
+                use super::*;
+
+                pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+                    move_lamports(&ctx, amount)?;
+                    **ctx.accounts.user.lamports.borrow_mut() += amount;
+                    Ok(())
+                }
+
+                pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+                    ctx.accounts.vault.amount += amount;
+                    Ok(())
+                }
+            }
+
+            fn move_lamports(ctx: &Context<Withdraw>, amount: u64) -> Result<()> {
+                let vault = ctx.accounts.vault.to_account_info();
+                let rest = vault.lamports().checked_sub(amount).unwrap();
+                **vault.try_borrow_mut_lamports()? = rest;
+                Ok(())
+            }
+        "#;
+        let sources = vec![(
+            "programs/vault/src/lib.rs".to_string(),
+            syn::parse_file(source).unwrap(),
+        )];
+        let lamports = lamport_manipulations(&sources);
+        assert_eq!(
+            lamports["withdraw"],
+            vec!["user(+=)", "vault(arithmetic,=)"]
+        );
+        assert!(!lamports.contains_key("deposit"));
+    }
 }
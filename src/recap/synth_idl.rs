@@ -0,0 +1,301 @@
+//! Synthesis of a minimal IDL from the sources of an Anchor crate, used by `recap` when the
+//! project has no `target/idl` (e.g. a repository that can't be built).
+//!
+//! The instructions are the functions of the `#[program]` module, their accounts the fields
+//! of the `#[derive(Accounts)]` struct of their `Context<...>` (nested account structs
+//! become composite accounts). The flags are what the source enforces: `signer` for a
+//! `Signer<'info>` or an `#[account(signer)]` field, `writable` for `mut`, `init`,
+//! `init_if_needed`, `zero`, `close` or `realloc`.
+
+use log::debug;
+use std::collections::HashMap;
+
+use super::crates::CrateInfo;
+use super::fs_utils::{read, walk};
+use super::idl::{Idl, IdlAccountItem, IdlArg, IdlInstruction};
+use crate::parsers::syn_ast::normalized_type;
+
+/// Arguments of `#[account(...)]` that make an account writable.
+const WRITABLE_ARGUMENTS: &[&str] = &["mut", "init", "init_if_needed", "zero", "close", "realloc"];
+
+/// Items of the sources of a crate, across files and inline modules.
+#[derive(Default)]
+struct CrateItems {
+    /// Functions of the `#[program]` modules.
+    instructions: Vec<syn::ItemFn>,
+    /// `#[derive(Accounts)]` structs, by name.
+    accounts: HashMap<String, syn::ItemStruct>,
+}
+
+impl CrateItems {
+    fn collect(&mut self, items: &[syn::Item], in_program: bool) {
+        for item in items {
+            match item {
+                syn::Item::Fn(function) if in_program => self.instructions.push(function.clone()),
+                syn::Item::Mod(module) => {
+                    if let Some((_, items)) = &module.content {
+                        let is_program = module
+                            .attrs
+                            .iter()
+                            .any(|attr| attr.path().is_ident("program"));
+                        self.collect(items, is_program);
+                    }
+                }
+                syn::Item::Struct(definition) if derives_accounts(&definition.attrs) => {
+                    self.accounts
+                        .insert(definition.ident.to_string(), definition.clone());
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Returns `true` for the attributes of a `#[derive(Accounts)]` item.
+fn derives_accounts(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path().is_ident("derive")
+            && attr.meta.require_list().is_ok_and(|list| {
+                list.tokens
+                    .clone()
+                    .into_iter()
+                    .any(|token| token.to_string() == "Accounts")
+            })
+    })
+}
+
+/// Returns the last segment of a type path, looking through `Box<...>`
+/// (`Box<Account<'info, Vault>>` -> `Account<'info, Vault>`).
+fn type_segment(ty: &syn::Type) -> Option<&syn::PathSegment> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident == "Box" {
+        if let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments {
+            return arguments.args.iter().find_map(|argument| match argument {
+                syn::GenericArgument::Type(inner) => type_segment(inner),
+                _ => None,
+            });
+        }
+    }
+    Some(segment)
+}
+
+/// Returns the leading word of each top-level argument of the `#[account(...)]` attributes
+/// (`mut`, `seeds`, `token` for `token::mint = mint`, ...).
+fn account_arguments(attrs: &[syn::Attribute]) -> Vec<String> {
+    let mut arguments = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("account")) {
+        let Ok(list) = attr.meta.require_list() else {
+            continue;
+        };
+        let mut leading = true;
+        for token in list.tokens.clone() {
+            match token {
+                proc_macro2::TokenTree::Punct(punct) if punct.as_char() == ',' => leading = true,
+                proc_macro2::TokenTree::Ident(ident) if leading => {
+                    arguments.push(ident.to_string());
+                    leading = false;
+                }
+                _ => leading = false,
+            }
+        }
+    }
+    arguments
+}
+
+/// Returns the name of the accounts struct of an instruction, the last generic argument of
+/// its `Context<...>` parameter.
+pub(crate) fn context_accounts(function: &syn::ItemFn) -> Option<String> {
+    function.sig.inputs.iter().find_map(|input| {
+        let syn::FnArg::Typed(typed) = input else {
+            return None;
+        };
+        let segment = type_segment(&typed.ty)?;
+        if segment.ident != "Context" {
+            return None;
+        }
+        let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+            return None;
+        };
+        arguments
+            .args
+            .iter()
+            .rev()
+            .find_map(|argument| match argument {
+                syn::GenericArgument::Type(accounts) => {
+                    type_segment(accounts).map(|segment| segment.ident.to_string())
+                }
+                _ => None,
+            })
+    })
+}
+
+/// Renders the type of an instruction argument (`u64`, `Vec<u8>`, `Pubkey`, ...).
+fn arg_type(ty: &syn::Type) -> String {
+    serde_json::from_str(&syn_serde::json::to_string(ty))
+        .map(|json| normalized_type(&json))
+        .unwrap_or_else(|_| "_".to_string())
+}
+
+/// Builds the IDL accounts of an accounts struct. `visited` guards against recursive structs.
+fn struct_accounts(
+    name: &str,
+    items: &CrateItems,
+    visited: &mut Vec<String>,
+) -> Vec<IdlAccountItem> {
+    let Some(definition) = items.accounts.get(name) else {
+        return vec![];
+    };
+    if visited.iter().any(|visited| visited == name) {
+        return vec![];
+    }
+    visited.push(name.to_string());
+    let accounts = definition
+        .fields
+        .iter()
+        .filter_map(|field| {
+            let field_name = field.ident.as_ref()?.to_string();
+            let ty = type_segment(&field.ty).map(|segment| segment.ident.to_string());
+            // a nested accounts struct
+            if let Some(nested) = ty.as_ref().filter(|ty| items.accounts.contains_key(*ty)) {
+                return Some(IdlAccountItem {
+                    name: field_name,
+                    isSigner: None,
+                    signer: None,
+                    isMut: None,
+                    writable: None,
+                    accounts: struct_accounts(nested, items, visited),
+                    address: None,
+                    pda: None,
+                });
+            }
+            let arguments = account_arguments(&field.attrs);
+            let has = |wanted: &[&str]| {
+                arguments
+                    .iter()
+                    .any(|argument| wanted.contains(&argument.as_str()))
+            };
+            Some(IdlAccountItem {
+                name: field_name,
+                isSigner: None,
+                signer: Some(ty.as_deref() == Some("Signer") || has(&["signer"])),
+                isMut: None,
+                writable: Some(has(WRITABLE_ARGUMENTS)),
+                accounts: vec![],
+                address: None,
+                pda: None,
+            })
+        })
+        .collect();
+    visited.pop();
+    accounts
+}
+
+/// Synthesizes the IDL of an Anchor crate from its `src/` directory.
+///
+/// # Returns
+///
+/// The IDL, named after the crate, or `None` if the crate has no `#[program]` module.
+pub(crate) fn synthesize_idl(krate: &CrateInfo) -> Option<Idl> {
+    let mut items = CrateItems::default();
+    let mut files: Vec<_> = walk(&krate.root.join("src"))
+        .into_iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+        .collect();
+    files.sort();
+    for path in files {
+        match syn::parse_file(&read(&path)) {
+            Ok(file) => items.collect(&file.items, false),
+            Err(e) => debug!("Skipping {} for the IDL synthesis: {}", path.display(), e),
+        }
+    }
+    if items.instructions.is_empty() {
+        return None;
+    }
+
+    let instructions = items
+        .instructions
+        .iter()
+        .filter(|function| matches!(function.vis, syn::Visibility::Public(_)))
+        .filter_map(|function| {
+            let accounts = context_accounts(function)?;
+            let args = function
+                .sig
+                .inputs
+                .iter()
+                .filter_map(|input| match input {
+                    syn::FnArg::Typed(typed) => Some(typed),
+                    syn::FnArg::Receiver(_) => None,
+                })
+                .filter(|typed| {
+                    type_segment(&typed.ty).is_none_or(|segment| segment.ident != "Context")
+                })
+                .map(|typed| IdlArg {
+                    name: match &*typed.pat {
+                        syn::Pat::Ident(binding) => binding.ident.to_string(),
+                        _ => "_".to_string(),
+                    },
+                    r#type: serde_json::json!(arg_type(&typed.ty)),
+                })
+                .collect();
+            Some(IdlInstruction {
+                name: function.sig.ident.to_string(),
+                accounts: struct_accounts(&accounts, &items, &mut Vec::new()),
+                args,
+                discriminator: None,
+            })
+        })
+        .collect();
+
+    Some(Idl {
+        name: Some(krate.name.clone()),
+        version: None,
+        address: None,
+        instructions,
+        accounts: vec![],
+        types: vec![],
+        errors: vec![],
+        metadata: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recap::idl::{flatten_accounts, load_idl};
+    use std::path::Path;
+
+    #[test]
+    fn synthesizes_fixture_idl() {
+        let root = Path::new("test_cases/recap_anchor");
+        let krate = CrateInfo {
+            name: "recap_anchor".to_string(),
+            root: root.join("programs/recap_anchor"),
+        };
+        let synthesized = synthesize_idl(&krate).unwrap();
+        let built = load_idl(&root.join("idl/recap_anchor.json")).unwrap();
+        assert_eq!(synthesized.name.as_deref(), Some("recap_anchor"));
+
+        let flags = |idl: &Idl, name: &str| {
+            let instruction = idl.instructions.iter().find(|ix| ix.name == name).unwrap();
+            let mut flat = vec![];
+            flatten_accounts(&instruction.accounts, &mut flat);
+            flat
+        };
+        for name in ["deposit", "close_state"] {
+            assert_eq!(flags(&synthesized, name), flags(&built, name));
+        }
+        // the built IDL claims `authority` signs, which the source doesn't enforce
+        assert!(flags(&synthesized, "transfer").contains(&("authority".to_string(), false, false)));
+
+        let deposit = synthesized
+            .instructions
+            .iter()
+            .find(|ix| ix.name == "deposit")
+            .unwrap();
+        let args: Vec<&str> = deposit.args.iter().map(|arg| arg.name.as_str()).collect();
+        assert_eq!(args, vec!["vault_bump", "amount"]);
+    }
+}
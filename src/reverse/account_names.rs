@@ -0,0 +1,315 @@
+//! Identification of the Anchor account discriminators by name.
+//!
+//! A discriminator is the first 8 bytes of `sha256("account:<Name>")`, so it can't be reversed,
+//! but it can be recognized by hashing candidate names: a wordlist of common account names
+//! shipped in `src/static/account_names/common.txt`, a wordlist given with `--account-names`,
+//! the accounts of the IDL (`--idl`) and the structs of a source tree (`--account-sources`).
+//!
+//! The fetcher names the type of the accounts it downloads with it, and the reverse command
+//! lists the known discriminators compared or stored in the program
+//! (`account_discriminators.out`).
+
+use anyhow::{Context, Result};
+use log::debug;
+use sha2::{Digest, Sha256};
+use solana_sbpf::{ebpf, static_analysis::Analysis};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use syn::visit::Visit;
+
+use crate::helpers::static_dir;
+use crate::parsers::syn_ast::find_rust_files;
+use crate::recap::idl::load_idl;
+use crate::reverse::OutputFile;
+
+/// Anchor discriminator of an account type: the first 8 bytes of `sha256("account:<Name>")`.
+pub fn account_discriminator(name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("account:{}", name).as_bytes());
+    hash[..8].try_into().unwrap()
+}
+
+/// Account names by discriminator.
+#[derive(Debug, Default)]
+pub struct AccountNames {
+    names: BTreeMap<[u8; 8], String>,
+}
+
+impl AccountNames {
+    /// Loads the wordlist of common account names shipped with sol-azy.
+    pub fn common() -> Result<Self> {
+        let content = static_dir::read_file("account_names/common.txt")?;
+        let mut names = Self::default();
+        names.add_wordlist(&content);
+        Ok(names)
+    }
+
+    /// Loads the common account names, then the ones of the IDL, the wordlist file and the
+    /// source tree given.
+    pub fn load(
+        idl: Option<&Path>,
+        wordlist: Option<&Path>,
+        sources: Option<&Path>,
+    ) -> Result<Self> {
+        let mut names = Self::common()?;
+        if let Some(idl) = idl {
+            let count = names.extend_from_idl(idl)?;
+            debug!("{} account names loaded from the IDL.", count);
+        }
+        if let Some(wordlist) = wordlist {
+            let count = names.extend_from_wordlist(wordlist)?;
+            debug!(
+                "{} account names loaded from {}.",
+                count,
+                wordlist.display()
+            );
+        }
+        if let Some(sources) = sources {
+            let count = names.extend_from_sources(sources)?;
+            debug!("{} account names loaded from {}.", count, sources.display());
+        }
+        Ok(names)
+    }
+
+    /// Adds the names of a wordlist: one name per line, empty lines and `#` comments ignored.
+    fn add_wordlist(&mut self, content: &str) -> usize {
+        let mut count = 0;
+        for name in content
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default().trim())
+            .filter(|name| !name.is_empty())
+        {
+            self.insert(name);
+            count += 1;
+        }
+        count
+    }
+
+    fn insert(&mut self, name: &str) {
+        self.names
+            .insert(account_discriminator(name), name.to_string());
+    }
+
+    /// Adds the names of a wordlist file (see [`AccountNames::common`] for the format).
+    ///
+    /// # Returns
+    ///
+    /// The number of names read from the file.
+    pub fn extend_from_wordlist(&mut self, path: &Path) -> Result<usize> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read the account names of {}", path.display()))?;
+        Ok(self.add_wordlist(&content))
+    }
+
+    /// Adds the accounts of an Anchor IDL, with the discriminators it declares (Anchor >= 0.30)
+    /// or the ones computed from their names.
+    ///
+    /// # Returns
+    ///
+    /// The number of accounts read from the IDL.
+    pub fn extend_from_idl(&mut self, idl_path: &Path) -> Result<usize> {
+        let idl = load_idl(idl_path)?;
+        for account in &idl.accounts {
+            match account
+                .discriminator
+                .as_deref()
+                .and_then(|bytes| <[u8; 8]>::try_from(bytes).ok())
+            {
+                Some(discriminator) => {
+                    self.names.insert(discriminator, account.name.clone());
+                }
+                None => self.insert(&account.name),
+            }
+        }
+        Ok(idl.accounts.len())
+    }
+
+    /// Adds the names of the structs of the Rust files of a source tree: the `#[account]` ones,
+    /// and the others, as a hash matching by chance is unlikely.
+    ///
+    /// # Returns
+    ///
+    /// The number of structs read from the sources.
+    pub fn extend_from_sources(&mut self, dir: &Path) -> Result<usize> {
+        let files: Vec<PathBuf> = if dir.is_file() {
+            vec![dir.to_path_buf()]
+        } else {
+            find_rust_files(dir)
+        };
+        let mut count = 0;
+        for path in files {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            match self.add_source(&content) {
+                Some(added) => count += added,
+                None => debug!("{} is not valid Rust, skipped.", path.display()),
+            }
+        }
+        Ok(count)
+    }
+
+    /// Adds the names of the structs of a Rust file, `None` if it doesn't parse.
+    fn add_source(&mut self, content: &str) -> Option<usize> {
+        struct StructNames(Vec<String>);
+        impl Visit<'_> for StructNames {
+            fn visit_item_struct(&mut self, item: &syn::ItemStruct) {
+                self.0.push(item.ident.to_string());
+            }
+        }
+
+        let file = syn::parse_file(content).ok()?;
+        let mut visitor = StructNames(vec![]);
+        visitor.visit_file(&file);
+        for name in &visitor.0 {
+            self.insert(name);
+        }
+        Some(visitor.0.len())
+    }
+
+    /// Returns the name of an account discriminator, if it is known.
+    pub fn name(&self, discriminator: &[u8; 8]) -> Option<&str> {
+        self.names.get(discriminator).map(String::as_str)
+    }
+
+    /// Returns the name of the account type of some account data, from its first 8 bytes.
+    #[cfg(feature = "cli")]
+    pub fn identify(&self, data: &[u8]) -> Option<&str> {
+        self.name(data.get(..8)?.try_into().ok()?)
+    }
+
+    /// Number of names known.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+}
+
+/// Where a known discriminator is found in the program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscriminatorLocation {
+    /// Loaded by the `lddw` at this instruction pointer.
+    Instruction(usize),
+    /// Stored at this offset of the program (e.g. in `.rodata`).
+    Data(usize),
+}
+
+impl fmt::Display for DiscriminatorLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiscriminatorLocation::Instruction(pc) => write!(f, "lbb_{}", pc),
+            DiscriminatorLocation::Data(offset) => write!(f, "offset 0x{:x}", offset),
+        }
+    }
+}
+
+/// A known account discriminator found in the program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscriminatorMatch {
+    pub location: DiscriminatorLocation,
+    pub discriminator: [u8; 8],
+    pub name: String,
+}
+
+/// Finds the known discriminators stored in the program bytes, at any alignment.
+fn find_in_data(program: &[u8], names: &AccountNames) -> Vec<DiscriminatorMatch> {
+    program
+        .windows(8)
+        .enumerate()
+        .filter_map(|(offset, window)| {
+            let discriminator: [u8; 8] = window.try_into().unwrap();
+            Some(DiscriminatorMatch {
+                location: DiscriminatorLocation::Data(offset),
+                discriminator,
+                name: names.name(&discriminator)?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Finds the known account discriminators of the program: the 64-bit constants loaded by
+/// `lddw` (to be compared with the first 8 bytes of an account), then the ones stored in its
+/// bytes.
+pub fn find_account_discriminators(
+    program: &[u8],
+    analysis: &Analysis,
+    names: &AccountNames,
+) -> Vec<DiscriminatorMatch> {
+    let mut found: Vec<DiscriminatorMatch> = analysis
+        .instructions
+        .iter()
+        .filter(|insn| insn.opc == ebpf::LD_DW_IMM)
+        .filter_map(|insn| {
+            // the account data is read in little endian
+            let discriminator = (insn.imm as u64).to_le_bytes();
+            Some(DiscriminatorMatch {
+                location: DiscriminatorLocation::Instruction(insn.ptr),
+                discriminator,
+                name: names.name(&discriminator)?.to_string(),
+            })
+        })
+        .collect();
+    found.extend(find_in_data(program, names));
+    found
+}
+
+/// Writes the discriminators found to `account_discriminators.out`.
+pub fn export_account_discriminators<P: AsRef<Path>>(
+    found: &[DiscriminatorMatch],
+    path: P,
+) -> std::io::Result<()> {
+    let mut out_path = PathBuf::from(path.as_ref());
+    out_path.push(OutputFile::AccountDiscriminators.default_filename());
+    let mut output = File::create(out_path)?;
+
+    writeln!(output, "Account discriminators ({}):", found.len())?;
+    for found in found {
+        writeln!(
+            output,
+            "    {:<20} {}  {}",
+            found.location.to_string(),
+            hex::encode(found.discriminator),
+            found.name
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identify_account_names() {
+        let mut names = AccountNames::common().unwrap();
+        let mut vault = account_discriminator("Vault").to_vec();
+        vault.extend([0; 32]);
+        assert_eq!(names.identify(&vault), Some("Vault"));
+        assert_eq!(names.identify(&vault[..4]), None);
+        assert_eq!(names.identify(&account_discriminator("Sablier")), None);
+
+        assert_eq!(names.add_wordlist("# comment\n\nSablier # trailing\n"), 1);
+        assert_eq!(
+            names.identify(&account_discriminator("Sablier")),
+            Some("Sablier")
+        );
+        let source = r#"
+            #[account]
+            pub struct Hourglass { pub authority: Pubkey }
+            mod state { pub struct Clepsydra; }
+        "#;
+        assert_eq!(names.add_source(source), Some(2));
+        assert_eq!(
+            names.identify(&account_discriminator("Clepsydra")),
+            Some("Clepsydra")
+        );
+        assert_eq!(names.add_source("fn {"), None);
+
+        let mut program = vec![0xff; 3];
+        program.extend(account_discriminator("Hourglass"));
+        let found = find_in_data(&program, &names);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].location, DiscriminatorLocation::Data(3));
+        assert_eq!(found[0].name, "Hourglass");
+    }
+}
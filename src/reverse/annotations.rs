@@ -0,0 +1,333 @@
+//! User annotations of a program, kept across the reverse runs (`annotations.json`).
+//!
+//! The file records the naming work done on a program: function and block renames, comments
+//! on instructions and bookmarks. It is read by every `reverse` run, from `--annotations` or
+//! from the `annotations.json` next to the program, and merged into the outputs:
+//!
+//! ```json
+//! {
+//!   "renames": { "676": "process_deposit", "lbb_702": "check_owner" },
+//!   "comments": { "0x2c0": "r2 is the vault account" },
+//!   "bookmarks": [{ "pc": 714, "name": "unchecked add", "note": "overflow if amount > 2^63" }]
+//! }
+//! ```
+//!
+//! Addresses are instruction pointers, as numbers or as `"676"`, `"0x2a4"`, `"lbb_676"`,
+//! `"function_676"` or `"cluster_676"`.
+
+use anyhow::{anyhow, Context, Result};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_sbpf::static_analysis::Analysis;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::reverse::cfg_graph::CfgGraph;
+use crate::reverse::raw_input::parse_offset;
+
+/// Name of the annotations file looked up next to the program.
+#[cfg(feature = "cli")]
+pub const ANNOTATIONS_FILENAME: &str = "annotations.json";
+
+/// Content of an annotations file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AnnotationFile {
+    /// New names of functions and basic blocks, by address.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub renames: BTreeMap<String, String>,
+    /// Comments on instructions, by address. A comment can span several lines.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub comments: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bookmarks: Vec<Bookmark>,
+}
+
+/// A named location of the program.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Bookmark {
+    pub pc: Value,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl AnnotationFile {
+    /// Reads the annotations file at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Reading annotations {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Parsing annotations {}", path.display()))
+    }
+
+    /// Writes the annotations file at `path`.
+    #[cfg(feature = "cli")]
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)? + "\n")
+            .with_context(|| format!("Writing annotations {}", path.display()))
+    }
+
+    /// Sets the name of the function or block at `pc`, replacing the renames written with
+    /// another form of the same address.
+    ///
+    /// # Returns
+    ///
+    /// Whether the name changed.
+    #[cfg(feature = "cli")]
+    pub fn set_rename(&mut self, pc: usize, name: &str) -> bool {
+        set_entry(&mut self.renames, pc, name)
+    }
+
+    /// Sets the comment on the instruction at `pc`, like [`AnnotationFile::set_rename`].
+    #[cfg(feature = "cli")]
+    pub fn set_comment(&mut self, pc: usize, comment: &str) -> bool {
+        set_entry(&mut self.comments, pc, comment)
+    }
+}
+
+/// Sets the entry of `pc` in `entries`, keyed by any form of its address.
+#[cfg(feature = "cli")]
+fn set_entry(entries: &mut BTreeMap<String, String>, pc: usize, value: &str) -> bool {
+    let keys: Vec<String> = entries
+        .keys()
+        .filter(|key| parse_address(&Value::String(key.to_string())).ok() == Some(pc))
+        .cloned()
+        .collect();
+    if keys.len() == 1 && entries[&keys[0]] == value {
+        return false;
+    }
+    for key in keys {
+        entries.remove(&key);
+    }
+    entries.insert(pc.to_string(), value.to_string());
+    true
+}
+
+/// The annotations of a program, resolved against its analysis.
+#[derive(Debug, Clone, Default)]
+pub struct Annotations {
+    renames: BTreeMap<usize, String>,
+    comments: BTreeMap<usize, String>,
+    bookmarks: BTreeMap<usize, Bookmark>,
+}
+
+/// Parses an address (`676`, `"0x2a4"`, `"lbb_676"`, `"function_676"`, `"cluster_676"`).
+fn parse_address(address: &Value) -> Result<usize> {
+    match address {
+        Value::Number(number) => number
+            .as_u64()
+            .map(|pc| pc as usize)
+            .ok_or_else(|| anyhow!("Invalid address {}", number)),
+        Value::String(address) => {
+            let address = address.trim();
+            parse_offset(
+                ["lbb_", "function_", "cluster_"]
+                    .iter()
+                    .find_map(|prefix| address.strip_prefix(prefix))
+                    .unwrap_or(address),
+            )
+        }
+        other => Err(anyhow!("Invalid address {}", other)),
+    }
+}
+
+impl Annotations {
+    /// Reads the annotations file at `path` and resolves its addresses in `analysis`.
+    ///
+    /// The annotations whose address is not an instruction of the program (e.g. written for
+    /// another build) are ignored with a warning, as well as the renames of addresses that
+    /// don't start a basic block and of the entrypoint.
+    ///
+    /// # Errors
+    ///
+    /// If the file can't be read or parsed, or an address is invalid.
+    pub fn load(path: &Path, analysis: &Analysis) -> Result<Self> {
+        Self::resolve(&AnnotationFile::load(path)?, analysis)
+    }
+
+    /// Resolves the addresses of `file` in `analysis`, see [`Annotations::load`].
+    pub fn resolve(file: &AnnotationFile, analysis: &Analysis) -> Result<Self> {
+        let is_instruction = |pc: usize| {
+            analysis
+                .instructions
+                .binary_search_by_key(&pc, |insn| insn.ptr)
+                .is_ok()
+        };
+        let mut annotations = Self::default();
+
+        for (address, name) in &file.renames {
+            let pc = parse_address(&Value::String(address.clone()))?;
+            if pc == analysis.entrypoint {
+                warn!("The entrypoint can't be renamed, '{}' ignored", name);
+            } else if !analysis.cfg_nodes.contains_key(&pc) {
+                warn!(
+                    "Rename of {} to '{}' ignored: not the start of a basic block",
+                    address, name
+                );
+            } else {
+                annotations.renames.insert(pc, name.clone());
+            }
+        }
+        for (address, comment) in &file.comments {
+            let pc = parse_address(&Value::String(address.clone()))?;
+            if is_instruction(pc) {
+                annotations.comments.insert(pc, comment.clone());
+            } else {
+                warn!("Comment on {} ignored: not an instruction", address);
+            }
+        }
+        for bookmark in &file.bookmarks {
+            let pc = parse_address(&bookmark.pc)?;
+            if is_instruction(pc) {
+                annotations.bookmarks.insert(pc, bookmark.clone());
+            } else {
+                warn!(
+                    "Bookmark '{}' ignored: {} is not an instruction",
+                    bookmark.name, bookmark.pc
+                );
+            }
+        }
+        Ok(annotations)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.renames.is_empty() && self.comments.is_empty() && self.bookmarks.is_empty()
+    }
+
+    /// Number of renames, comments and bookmarks.
+    pub fn len(&self) -> usize {
+        self.renames.len() + self.comments.len() + self.bookmarks.len()
+    }
+
+    /// Renames the functions and basic blocks, over the names given by the symbols, the
+    /// signatures or the dispatch.
+    pub fn apply_renames(&self, analysis: &mut Analysis) {
+        for (pc, name) in &self.renames {
+            if let Some(cfg_node) = analysis.cfg_nodes.get_mut(pc) {
+                cfg_node.label = name.clone();
+            }
+            if let Some((_, function_name)) = analysis.functions.get_mut(pc) {
+                *function_name = name.clone();
+            }
+        }
+    }
+
+    /// Whether the function or block at `pc` is renamed by the annotations.
+    pub fn is_renamed(&self, pc: usize) -> bool {
+        self.renames.contains_key(&pc)
+    }
+
+    /// The comments, by instruction pointer.
+    pub fn comments(&self) -> &BTreeMap<usize, String> {
+        &self.comments
+    }
+
+    /// Returns the comment on the instruction at `pc`, if any.
+    pub fn comment(&self, pc: usize) -> Option<&str> {
+        self.comments.get(&pc).map(String::as_str)
+    }
+
+    /// Returns the bookmark set on the instruction at `pc`, if any.
+    pub fn bookmark(&self, pc: usize) -> Option<&Bookmark> {
+        self.bookmarks.get(&pc)
+    }
+
+    /// Returns the comment and bookmark lines written before the instruction at `pc` in the
+    /// listings, without the comment marker.
+    pub fn lines(&self, pc: usize) -> Vec<String> {
+        let mut lines = vec![];
+        if let Some(bookmark) = self.bookmark(pc) {
+            lines.push(match &bookmark.note {
+                Some(note) => format!("bookmark '{}': {}", bookmark.name, note),
+                None => format!("bookmark '{}'", bookmark.name),
+            });
+        }
+        if let Some(comment) = self.comment(pc) {
+            lines.extend(comment.lines().map(str::to_string));
+        }
+        lines
+    }
+
+    /// Adds the comments and bookmarks to the blocks of `graph`, the bookmarks being shown in
+    /// the header of their block.
+    pub fn annotate_graph(&self, graph: &mut CfgGraph, analysis: &Analysis) {
+        if self.comments.is_empty() && self.bookmarks.is_empty() {
+            return;
+        }
+        for block in graph
+            .clusters
+            .iter_mut()
+            .flat_map(|cluster| &mut cluster.blocks)
+        {
+            let Some(cfg_node) = analysis.cfg_nodes.get(&block.start) else {
+                continue;
+            };
+            let instructions = &analysis.instructions[cfg_node.instructions.clone()];
+            for (index, insn) in instructions.iter().enumerate() {
+                if let Some(comment) = self.comment(insn.ptr) {
+                    block.comments.insert(index, comment.to_string());
+                }
+                if let Some(bookmark) = self.bookmark(insn.ptr) {
+                    block.bookmarks.push(match &bookmark.note {
+                        Some(note) => format!("{}: {}", bookmark.name, note),
+                        None => bookmark.name.clone(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reverse::new_loader;
+    use solana_sbpf::elf::Executable;
+    use test_utils::TestContextObject;
+
+    #[test]
+    fn test_resolve_annotations() {
+        let elf =
+            std::fs::read("test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so")
+                .unwrap();
+        let executable =
+            Executable::<TestContextObject>::from_elf(&elf, new_loader(true).unwrap()).unwrap();
+        let mut analysis = Analysis::from_executable(&executable).unwrap();
+        let function = *analysis
+            .functions
+            .keys()
+            .find(|pc| **pc != analysis.entrypoint)
+            .unwrap();
+
+        let file: AnnotationFile = serde_json::from_value(serde_json::json!({
+            "renames": {
+                format!("lbb_{}", function): "my_function",
+                analysis.entrypoint.to_string(): "renamed_entrypoint",
+                "100000": "out_of_program",
+            },
+            "comments": { format!("{:#x}", function): "first line\nsecond line" },
+            "bookmarks": [{ "pc": function, "name": "here" }],
+        }))
+        .unwrap();
+        let annotations = Annotations::resolve(&file, &analysis).unwrap();
+        assert_eq!(annotations.len(), 3);
+        assert_eq!(
+            annotations.lines(function),
+            vec!["bookmark 'here'", "first line", "second line"]
+        );
+
+        annotations.apply_renames(&mut analysis);
+        assert_eq!(analysis.cfg_nodes[&function].label, "my_function");
+        assert_eq!(analysis.functions[&function].1, "my_function");
+        assert_ne!(
+            analysis.cfg_nodes[&analysis.entrypoint].label,
+            "renamed_entrypoint"
+        );
+
+        assert!(serde_json::from_str::<AnnotationFile>(r#"{"renamed": {}}"#).is_err());
+        assert!(parse_address(&Value::String("lbb_zz".to_string())).is_err());
+    }
+}
@@ -0,0 +1,327 @@
+//! Assembler of small snippets written in the syntax of `disassembly.out` (`asm` command).
+//!
+//! Each line is assembled by the `solana_sbpf` assembler, after resolving the jump and call
+//! targets written as in the disassembly: `lbb_<pc>`, `function_<pc>` and the function names of
+//! the program are absolute instruction pointers, turned into offsets relative to the
+//! instruction. A snippet can also define its own labels (`retry:`), and a jump or call to an
+//! integer (`ja +3`, `call -10`) is an offset relative to the next instruction.
+//!
+//! The annotations of the disassembly are ignored: the text after `-->`, `;`, `//` or `#`, and
+//! the columns after two spaces. Lines of `disassembly.out` can thus be copied back as is.
+
+use anyhow::{anyhow, Result};
+use solana_sbpf::{
+    assembler,
+    ebpf::{self, Insn},
+    elf::Executable,
+    program::{BuiltinProgram, SBPFVersion},
+    static_analysis::Analysis,
+    vm::Config,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use test_utils::TestContextObject;
+
+use crate::reverse::raw_input::parse_offset;
+use crate::reverse::syscalls;
+
+/// Program a snippet is assembled for: its SBPF version and the addresses of its named
+/// functions and basic blocks.
+#[derive(Debug, Clone)]
+pub struct AsmTarget {
+    pub sbpf_version: SBPFVersion,
+    labels: HashMap<String, usize>,
+}
+
+/// An assembled instruction (two slots for `lddw`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssembledInstruction {
+    pub pc: usize,
+    pub source: String,
+    pub bytes: Vec<u8>,
+}
+
+impl AsmTarget {
+    /// A target without labels, for snippets assembled standalone.
+    pub fn new(sbpf_version: SBPFVersion) -> Self {
+        Self {
+            sbpf_version,
+            labels: HashMap::new(),
+        }
+    }
+
+    /// A target knowing the labels of the analyzed program.
+    pub fn from_analysis(analysis: &Analysis, sbpf_version: SBPFVersion) -> Self {
+        let mut labels: HashMap<String, usize> = analysis
+            .cfg_nodes
+            .iter()
+            .map(|(pc, node)| (node.label.clone(), *pc))
+            .collect();
+        for (pc, (_, name)) in &analysis.functions {
+            labels.entry(name.clone()).or_insert(*pc);
+        }
+        Self {
+            sbpf_version,
+            labels,
+        }
+    }
+
+    /// Loads the ELF `elf` to get its version and labels.
+    pub fn from_elf(elf: &[u8]) -> Result<Self> {
+        let executable =
+            Executable::<TestContextObject>::from_elf(elf, crate::reverse::new_loader(true)?)
+                .map_err(|e| anyhow!("Failed to load the program: {:?}", e))?;
+        let analysis = Analysis::from_executable(&executable)
+            .map_err(|e| anyhow!("Failed to analyze the program: {:?}", e))?;
+        Ok(Self::from_analysis(
+            &analysis,
+            executable.get_sbpf_version(),
+        ))
+    }
+}
+
+/// Removes the annotations of a line of `disassembly.out`.
+fn strip_annotations(line: &str) -> &str {
+    let line = line.trim_start();
+    let end = ["-->", ";", "//", "#", "  ", "\t"]
+        .iter()
+        .filter_map(|marker| line.find(marker))
+        .min()
+        .unwrap_or(line.len());
+    line[..end].trim_end()
+}
+
+/// Returns the label defined by `line` (`name:`), if any.
+fn label_definition(line: &str) -> Option<&str> {
+    line.strip_suffix(':')
+        .filter(|name| !name.is_empty() && !name.contains(char::is_whitespace))
+}
+
+fn is_register(operand: &str) -> bool {
+    operand
+        .strip_prefix('r')
+        .is_some_and(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Parses a signed integer operand (`3`, `+3`, `-0x10`).
+fn parse_integer(operand: &str) -> Option<i64> {
+    let (sign, magnitude) = match operand.strip_prefix('-') {
+        Some(magnitude) => (-1, magnitude),
+        None => (1, operand.strip_prefix('+').unwrap_or(operand)),
+    };
+    parse_offset(magnitude)
+        .ok()
+        .map(|value| sign * value as i64)
+}
+
+/// Resolves a jump or call target to an instruction pointer.
+fn resolve_target(
+    target: &str,
+    local_labels: &HashMap<String, usize>,
+    asm_target: &AsmTarget,
+) -> Result<usize> {
+    if let Some(pc) = local_labels
+        .get(target)
+        .or_else(|| asm_target.labels.get(target))
+    {
+        return Ok(*pc);
+    }
+    ["lbb_", "function_"]
+        .iter()
+        .find_map(|prefix| target.strip_prefix(prefix))
+        .and_then(|pc| parse_offset(pc).ok())
+        .ok_or_else(|| anyhow!("Unknown label '{}'", target))
+}
+
+/// Offset from the instruction at `pc` to `target`, relative to the next instruction.
+fn relative_offset(pc: usize, target: usize) -> i64 {
+    target as i64 - pc as i64 - 1
+}
+
+/// Creates the loader the `solana_sbpf` assembler runs with, for `sbpf_version`.
+fn asm_loader(sbpf_version: SBPFVersion) -> Result<Arc<BuiltinProgram<TestContextObject>>> {
+    let mut loader = BuiltinProgram::new_loader(Config {
+        enabled_sbpf_versions: sbpf_version..=sbpf_version,
+        ..Config::default()
+    });
+    syscalls::register_solana_syscalls(&mut loader)
+        .map_err(|e| anyhow!("Failed to register syscalls: {:?}", e))?;
+    Ok(Arc::new(loader))
+}
+
+/// Assembles `source`, its first instruction being at `pc`.
+///
+/// # Errors
+///
+/// If an instruction is invalid, or a target is neither a label of the snippet or of the
+/// program nor an `lbb_<pc>` / `function_<pc>` address.
+pub fn assemble(source: &str, pc: usize, target: &AsmTarget) -> Result<Vec<AssembledInstruction>> {
+    let loader = asm_loader(target.sbpf_version)?;
+    let lines: Vec<&str> = source
+        .lines()
+        .map(strip_annotations)
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    let mut local_labels = HashMap::new();
+    let mut next_pc = pc;
+    for line in &lines {
+        match label_definition(line) {
+            Some(name) => {
+                local_labels.insert(name.to_string(), next_pc);
+            }
+            None if line.starts_with("lddw") => next_pc += 2,
+            None => next_pc += 1,
+        }
+    }
+
+    let mut instructions = vec![];
+    let mut next_pc = pc;
+    for line in lines {
+        if label_definition(line).is_some() {
+            continue;
+        }
+        let bytes = assemble_line(line, next_pc, &local_labels, target, &loader)
+            .map_err(|e| anyhow!("pc {}: '{}': {}", next_pc, line, e))?;
+        let slots = bytes.len() / ebpf::INSN_SIZE;
+        instructions.push(AssembledInstruction {
+            pc: next_pc,
+            source: line.to_string(),
+            bytes,
+        });
+        next_pc += slots;
+    }
+    Ok(instructions)
+}
+
+/// Assembles one instruction, at `pc`.
+fn assemble_line(
+    line: &str,
+    pc: usize,
+    local_labels: &HashMap<String, usize>,
+    target: &AsmTarget,
+    loader: &Arc<BuiltinProgram<TestContextObject>>,
+) -> Result<Vec<u8>> {
+    let (mnemonic, operands) = line.split_once(' ').unwrap_or((line, ""));
+    let mut operands: Vec<String> = operands
+        .split(',')
+        .map(|operand| operand.trim().to_string())
+        .filter(|operand| !operand.is_empty())
+        .collect();
+
+    if mnemonic == "call" {
+        if let [callee] = operands.as_slice() {
+            let is_syscall = loader
+                .get_function_registry()
+                .lookup_by_name(callee.as_bytes())
+                .is_some();
+            if !is_register(callee) && !is_syscall {
+                // Internal call, relative to the next instruction
+                let imm = match parse_integer(callee) {
+                    Some(offset) => offset,
+                    None => relative_offset(pc, resolve_target(callee, local_labels, target)?),
+                };
+                let insn = Insn {
+                    opc: ebpf::CALL_IMM,
+                    src: 1,
+                    imm,
+                    ..Insn::default()
+                };
+                return Ok(insn.to_vec());
+            }
+            if is_syscall {
+                return assemble_line(
+                    &format!("syscall {}", callee),
+                    pc,
+                    local_labels,
+                    target,
+                    loader,
+                );
+            }
+        }
+    }
+    if mnemonic == "syscall" {
+        if let [name] = operands.as_slice() {
+            if parse_integer(name).is_none()
+                && loader
+                    .get_function_registry()
+                    .lookup_by_name(name.as_bytes())
+                    .is_none()
+            {
+                return Err(anyhow!("Unknown syscall '{}'", name));
+            }
+        }
+    }
+    if mnemonic.starts_with('j') {
+        if let Some(last) = operands.last_mut() {
+            if !is_register(last) && parse_integer(last).is_none() {
+                let offset = relative_offset(pc, resolve_target(last, local_labels, target)?);
+                *last = format!("{:+}", offset);
+            }
+        }
+    }
+
+    let line = format!("{} {}", mnemonic, operands.join(", "));
+    let executable = assembler::assemble::<TestContextObject>(&line, loader.clone())
+        .map_err(|e| anyhow!("{}", e))?;
+    Ok(executable.get_text_bytes().1.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reverse::new_loader;
+
+    #[test]
+    fn test_assemble_snippet() {
+        let target = AsmTarget::new(SBPFVersion::V0);
+        let instructions = assemble(
+            "retry:\n    mov64 r0, 0    r0 = 0_i32 as i64 as u64\n    jne r1, 1337, retry\n    \
+             lddw r1, 0x100004610 --> b\"...\"\n    syscall sol_log_\n    ja lbb_100\n    exit",
+            10,
+            &target,
+        )
+        .unwrap();
+        let pcs: Vec<usize> = instructions.iter().map(|insn| insn.pc).collect();
+        assert_eq!(pcs, vec![10, 11, 12, 14, 15, 16]);
+        assert_eq!(hex::encode(&instructions[0].bytes), "b700000000000000");
+        // jne r1, 1337, -2
+        assert_eq!(hex::encode(&instructions[1].bytes), "5501feff39050000");
+        assert_eq!(instructions[2].bytes.len(), 16);
+        // ja +84
+        assert_eq!(hex::encode(&instructions[4].bytes), "0500540000000000");
+        assert!(assemble("call unknown_function", 0, &target).is_err());
+        assert!(assemble("syscall sol_unknown_", 0, &target).is_err());
+    }
+
+    #[test]
+    fn test_disassembly_round_trip() {
+        let elf =
+            std::fs::read("test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so")
+                .unwrap();
+        let executable =
+            Executable::<TestContextObject>::from_elf(&elf, new_loader(true).unwrap()).unwrap();
+        let analysis = Analysis::from_executable(&executable).unwrap();
+        let target = AsmTarget::from_analysis(&analysis, executable.get_sbpf_version());
+        let text = &elf[0x120..];
+
+        for (pc, insn) in analysis.instructions.iter().enumerate() {
+            // The lddw and syscalls (imm -1) are rewritten by the relocations at load time
+            let offset = insn.ptr * ebpf::INSN_SIZE;
+            if insn.opc == ebpf::LD_DW_IMM
+                || (insn.opc == ebpf::CALL_IMM && text[offset + 4..offset + 8] == [0xff; 4])
+            {
+                continue;
+            }
+            let line = analysis.disassemble_instruction(insn, pc);
+            let assembled = assemble(&line, insn.ptr, &target).unwrap();
+            assert_eq!(
+                assembled[0].bytes,
+                &text[offset..offset + ebpf::INSN_SIZE],
+                "pc {}: {}",
+                insn.ptr,
+                line
+            );
+        }
+    }
+}
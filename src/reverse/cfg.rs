@@ -1,21 +1,70 @@
 // Portions of this file are adapted from the `sbpf` project from anza,
 // licensed under the MIT license.
 // See https://github.com/anza-xyz/sbpf
-use solana_sbpf::{program::SBPFVersion, static_analysis::Analysis};
-use std::collections::{BTreeMap, HashSet};
-
-use crate::reverse::utils::{
-    update_string_resolution, MAX_BYTES_USED_TO_READ_FOR_IMMEDIATE_STRING_REPR,
+use serde_json::json;
+use solana_sbpf::{
+    program::SBPFVersion,
+    static_analysis::{Analysis, CfgNode},
 };
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::reverse::annotations::Annotations;
+use crate::reverse::cfg_graph::{CfgBlock, CfgCluster, CfgEdge, CfgGraph};
+use crate::reverse::dispatch::resolve_internal_call;
+use crate::reverse::function_filter::FunctionSelection;
+use crate::reverse::sanitize::disassemble_instruction;
+use crate::reverse::signatures::function_hash;
+use crate::reverse::stable_ids::StableIds;
+use crate::reverse::utils::update_string_resolution;
 use crate::reverse::OutputFile;
 use std::fs::File;
-use std::io::Write;
+use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
 
 use super::utils::RegisterTracker;
 
-/// Exports the control flow graph (CFG) of a program to a Graphviz-compatible DOT file.
+/// What the CFG holds and how its nodes are named, see [`export_cfg_to_dot`].
+#[derive(Clone, Copy)]
+pub struct CfgOptions<'a> {
+    /// Only includes the functions reachable from the program entrypoint, following the jumps
+    /// and the direct calls. This is useful to exclude prelude or system/library functions and
+    /// focus on the main logic.
+    pub reduced: bool,
+    /// Only includes the cluster corresponding to the entrypoint function (e.g., `cluster_XX`).
+    /// This enables minimal CFGs that users can extend manually using the `dotting` module.
+    pub only_entrypoint: bool,
+    /// If given, the block used as the root of `reduced` and `only_entrypoint` instead of the
+    /// entrypoint.
+    pub entry: Option<usize>,
+    /// If given, the clusters and blocks are named after their stable ids instead of their
+    /// address.
+    pub stable_ids: Option<&'a StableIds>,
+    /// If given, only these functions are included, without the edges leaving them.
+    pub selection: Option<&'a FunctionSelection>,
+    /// Comments and bookmarks added to the blocks.
+    pub annotations: &'a Annotations,
+}
+
+impl<'a> CfgOptions<'a> {
+    /// The whole CFG, with the blocks named after their address.
+    pub fn new(annotations: &'a Annotations) -> Self {
+        Self {
+            reduced: false,
+            only_entrypoint: false,
+            entry: None,
+            stable_ids: None,
+            selection: None,
+            annotations,
+        }
+    }
+}
+
+/// Exports the control flow graph (CFG) of a program to a Graphviz-compatible DOT file, along with
+/// its JSON representation (`cfg.json`, see [`CfgGraph`]) from which the DOT is rendered.
 /// Each function is rendered as a subgraph showing basic blocks (`lbb_XXX`) and instruction-level content.
+/// Blocks are collected by an iterative walk of the dominator tree and written through a buffer,
+/// so deep or huge functions don't exhaust the stack. See [`export_cfg_chunks`] to split the output.
 ///
 /// This function is a modified version of `visualize_graphically` from the `sbpf-solana` project,
 /// and supports advanced filtering for cleaner output in complex programs.
@@ -27,240 +76,725 @@ use super::utils::RegisterTracker;
 /// * `reg_tracker_wrapped` - Optional mutable reference to a `RegisterTracker` for tracking register states.
 /// * `sbpf_version` - The SBPF version from the executable.
 /// * `path` - Path to the output directory where the `.dot` file will be saved.
-/// * `reduced` - If `true`, only includes functions defined **after** the program entrypoint in the CFG output.
-///   This is useful to exclude prelude or system/library functions and focus on the main logic.
-/// * `only_entrypoint` - If `true`, only includes the cluster corresponding to the entrypoint function (e.g., `cluster_XX`)
-///   in the DOT output. This enables minimal CFGs that users can extend manually using the `dotting` module.
+/// * `options` - The functions included in the CFG and the naming of its nodes.
 ///
 /// # Returns
 ///
-/// * `Ok(())` if the DOT file was generated successfully.
-/// * `Err(std::io::Error)` if there was a problem writing the file.
+/// * `Ok(())` if the DOT and JSON files were generated successfully.
+/// * `Err(std::io::Error)` if there was a problem writing the files.
 pub fn export_cfg_to_dot<P: AsRef<Path>>(
     program: &[u8],
     analysis: &mut Analysis,
     reg_tracker_wrapped: Option<&mut RegisterTracker>,
     sbpf_version: SBPFVersion,
     path: P,
-    reduced: bool,
-    only_entrypoint: bool,
+    options: &CfgOptions,
 ) -> std::io::Result<()> {
-    let mut cfg_path = PathBuf::from(path.as_ref());
-    cfg_path.push(OutputFile::Cfg.default_filename());
-    let mut output = File::create(cfg_path)?;
-
     let mut reg_tracker_default = RegisterTracker::new();
     let reg_tracker: &mut RegisterTracker = match reg_tracker_wrapped {
         Some(ref_mut) => ref_mut,
         None => &mut reg_tracker_default,
     };
+    let graph = build_cfg_graph(program, analysis, reg_tracker, sbpf_version, options);
 
-    /// Escapes a string for safe inclusion in HTML (used in DOT labels).
-    fn html_escape(string: &str) -> String {
-        string
-            .replace('&', "&amp;")
-            .replace('<', "&lt;")
-            .replace('>', "&gt;")
-            .replace('\"', "&quot;")
-    }
+    let path = path.as_ref();
+    graph.save(&path.join(OutputFile::CfgJson.default_filename()))?;
+    let mut output = BufWriter::new(File::create(path.join(OutputFile::Cfg.default_filename()))?);
+    graph.write_dot(&mut output)?;
+    output.flush()
+}
 
-    /// Emits a single CFG node and recursively its children to the DOT output.
-    ///
-    /// # Arguments
-    ///
-    /// * `program` - The bytecode
-    /// * `output` - Output writer
-    /// * `analysis` - Reference to the analysis data
-    /// * `reg_tracker` - Mutable reference to register tracker
-    /// * `sbpf_version` - The SBPF version from the executable
-    /// * `function_range` - Bytecode range of the current function
-    /// * `alias_nodes` - Set of alias node indices
-    /// * `cfg_node_start` - Entry point of the current node
-    /// * `reduced` - Whether to emit reduced CFG
-    fn emit_cfg_node<W: std::io::Write>(
-        program: &[u8],
-        output: &mut W,
-        analysis: &Analysis,
-        reg_tracker: &mut RegisterTracker,
-        sbpf_version: SBPFVersion,
-        function_range: std::ops::Range<usize>,
-        alias_nodes: &mut HashSet<usize>,
-        visited_nodes: &mut HashSet<usize>,
-        cfg_node_start: usize,
-        reduced: bool,
-    ) -> std::io::Result<()> {
-        let cfg_node = &analysis.cfg_nodes[&cfg_node_start];
-        let insns = analysis.instructions[cfg_node.instructions.clone()].to_vec();
+/// Builds the control flow graph written by [`export_cfg_to_dot`], whose arguments it takes.
+pub fn build_cfg_graph(
+    program: &[u8],
+    analysis: &Analysis,
+    reg_tracker: &mut RegisterTracker,
+    sbpf_version: SBPFVersion,
+    options: &CfgOptions,
+) -> CfgGraph {
+    let CfgOptions {
+        reduced,
+        only_entrypoint,
+        entry,
+        stable_ids,
+        selection,
+        annotations,
+    } = *options;
+    let reduced = reduced || only_entrypoint;
+    let mut visited_nodes = HashSet::new();
+    let mut graph = CfgGraph::default();
 
-        if reduced {
-            // this will save some memory for not-reduced CFG
-            visited_nodes.insert(cfg_node_start);
+    for function_range in select_functions(
+        analysis,
+        sbpf_version,
+        reduced,
+        only_entrypoint,
+        entry,
+        selection,
+    ) {
+        graph.clusters.push(build_cluster(
+            program,
+            analysis,
+            reg_tracker,
+            sbpf_version,
+            function_range,
+            &mut visited_nodes,
+            reduced,
+        ));
+    }
+
+    let selected =
+        |pc: usize| selection.is_none_or(|selection| selection.contains_pc(analysis, pc));
+    for (function_range, cfg_node_start, cfg_node) in analysis.iter_cfg_by_function() {
+        if reduced && !visited_nodes.contains(&cfg_node_start) {
+            continue;
         }
+        if selection.is_some_and(|selection| !selection.contains(function_range.start)) {
+            continue;
+        }
+        graph
+            .edges
+            .extend(build_edges(cfg_node_start, cfg_node, reduced, selected));
+    }
+    annotations.annotate_graph(&mut graph, analysis);
+    if let Some(stable_ids) = stable_ids {
+        stable_ids.apply(&mut graph);
+    }
+    graph
+}
 
-        writeln!(output, "    lbb_{} [label=<<table border=\"0\" cellborder=\"0\" cellpadding=\"3\">{}</table>>];",
-            cfg_node_start,
-            analysis.instructions[cfg_node.instructions.clone()].iter()
-            .enumerate().map(|(pc, insn)| {
-                let mut desc = analysis.disassemble_instruction(insn, pc);
-
-                // next instruction lookup to gather information (like for string and their length when it uses MOV64_IMM)
-                let next_insn = insns.get(pc + 1);
-                // append immediate string representation if available
-                let str_repr = update_string_resolution(program, insn, next_insn, reg_tracker, sbpf_version);
-
-                if str_repr != "" {
-                    desc.push_str(" --> ");
-                    desc.push_str(&str_repr);
-                }
-                if let Some(split_index) = desc.find(' ') {
-                    let mut rest = desc[split_index+1..].to_string();
-                    if rest.len() > MAX_CELL_CONTENT_LENGTH + 1 {
-                        rest.truncate(MAX_CELL_CONTENT_LENGTH);
-                        rest = format!("{rest}…");
+/// Exports the CFG as several `.dot` files of `functions_per_chunk` function clusters each,
+/// along with an `index.json` describing them, inside the `cfg_chunks/` output directory.
+///
+/// Chunks are independent and rendered in parallel. Each one starts from a fresh
+/// `RegisterTracker`, so immediate string resolution never depends on another chunk.
+/// Edges leaving a chunk are not drawn (they would create dangling nodes) but listed in the
+/// index as `external_edges`, which lets the chunks be stitched back together.
+///
+/// # Arguments
+///
+/// * `program` - Raw bytecode of the program
+/// * `analysis` - The `Analysis` structure containing disassembly and CFG data.
+/// * `sbpf_version` - The SBPF version from the executable.
+/// * `path` - Path to the output directory in which `cfg_chunks/` is created.
+/// * `functions_per_chunk` - Number of function clusters per `.dot` file (`1` gives one file per function).
+/// * `options` - Same as for [`export_cfg_to_dot`], the chunks only holding the kept functions.
+///
+/// # Returns
+///
+/// * `Ok(())` if every chunk and the index were generated successfully.
+/// * `Err(std::io::Error)` if there was a problem writing one of the files.
+pub fn export_cfg_chunks<P: AsRef<Path>>(
+    program: &[u8],
+    analysis: &Analysis,
+    sbpf_version: SBPFVersion,
+    path: P,
+    functions_per_chunk: usize,
+    options: &CfgOptions,
+) -> std::io::Result<()> {
+    let CfgOptions {
+        reduced,
+        only_entrypoint,
+        entry,
+        selection,
+        ..
+    } = *options;
+    let mut chunks_dir = PathBuf::from(path.as_ref());
+    chunks_dir.push(OutputFile::CfgChunks.default_filename());
+    std::fs::create_dir_all(&chunks_dir)?;
+
+    let reduced = reduced || only_entrypoint;
+    let functions = select_functions(
+        analysis,
+        sbpf_version,
+        reduced,
+        only_entrypoint,
+        entry,
+        selection,
+    );
+    let chunks: Vec<&[std::ops::Range<usize>]> =
+        functions.chunks(functions_per_chunk.max(1)).collect();
+
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(chunks.len())
+        .max(1);
+    let next_chunk = AtomicUsize::new(0);
+
+    let mut entries = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| -> std::io::Result<Vec<(usize, serde_json::Value)>> {
+                    let mut entries = Vec::new();
+                    loop {
+                        let index = next_chunk.fetch_add(1, Ordering::Relaxed);
+                        let Some(chunk) = chunks.get(index) else {
+                            break;
+                        };
+                        let filename = format!("cfg_{}.dot", index);
+                        let entry = export_chunk(
+                            program,
+                            analysis,
+                            sbpf_version,
+                            &chunks_dir.join(&filename),
+                            chunk,
+                            reduced,
+                            options,
+                        )?;
+                        entries.push((index, json!({ "file": filename, "clusters": entry.0, "external_edges": entry.1 })));
                     }
-                    format!("<tr><td align=\"left\">{}</td><td align=\"left\">{}</td></tr>", html_escape(&desc[..split_index]), html_escape(&rest))
-                } else {
-                    format!("<tr><td align=\"left\">{}</td></tr>", html_escape(&desc))
-                }
-            }).collect::<String>()
-        )?;
-
-        for child in &cfg_node.dominated_children {
-            emit_cfg_node(
-                program,
-                output,
-                analysis,
-                reg_tracker,
-                sbpf_version,
-                function_range.clone(),
-                alias_nodes,
-                visited_nodes,
-                *child,
-                reduced,
-            )?;
-        }
+                    Ok(entries)
+                })
+            })
+            .collect();
 
-        Ok(())
-    }
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("CFG chunk worker panicked"))
+            .collect::<std::io::Result<Vec<_>>>()
+    })?
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
+    entries.sort_by_key(|(index, _)| *index);
 
+    let index = json!({
+        "functions_per_chunk": functions_per_chunk.max(1),
+        "reduced": reduced,
+        "only_entrypoint": only_entrypoint,
+        "entry": entry,
+        "chunks": entries.into_iter().map(|(_, entry)| entry).collect::<Vec<_>>(),
+    });
+    let mut index_file = File::create(chunks_dir.join("index.json"))?;
     writeln!(
-        output,
-        "digraph {{
-graph [
-rankdir=LR;
-concentrate=True;
-style=filled;
-color=lightgrey;
-];
-node [
-shape=rect;
-style=filled;
-fillcolor=white;
-fontname=\"Courier New\";
-];
-edge [
-fontname=\"Courier New\";
-];"
-    )?;
-
-    const MAX_CELL_CONTENT_LENGTH: usize =
-        15 + MAX_BYTES_USED_TO_READ_FOR_IMMEDIATE_STRING_REPR as usize;
-
-    let mut is_entrypoint_visited = false;
-    let function_iter = &mut analysis.functions.keys().peekable();
+        index_file,
+        "{}",
+        serde_json::to_string_pretty(&index).map_err(std::io::Error::other)?
+    )
+}
+
+/// Writes a single chunk of function clusters to `chunk_path`, `reduced` telling whether the
+/// CFG is reduced (`only_entrypoint` included).
+///
+/// # Returns
+///
+/// The index entries of the chunk: its clusters and the edges leaving it.
+fn export_chunk(
+    program: &[u8],
+    analysis: &Analysis,
+    sbpf_version: SBPFVersion,
+    chunk_path: &Path,
+    functions: &[std::ops::Range<usize>],
+    reduced: bool,
+    options: &CfgOptions,
+) -> std::io::Result<(Vec<serde_json::Value>, Vec<[usize; 2]>)> {
+    let CfgOptions {
+        stable_ids,
+        annotations,
+        ..
+    } = *options;
+    let mut reg_tracker = RegisterTracker::new();
     let mut visited_nodes = HashSet::new();
+    let mut graph = CfgGraph::default();
 
-    while let Some(function_start) = function_iter.next() {
-        let label = &analysis.cfg_nodes[function_start].label;
-        if (reduced || only_entrypoint) && !is_entrypoint_visited && label != "entrypoint" {
-            continue;
-        }
-        if is_entrypoint_visited && only_entrypoint {
-            break;
-        }
-        if label == "entrypoint" {
-            is_entrypoint_visited = true;
+    for function_range in functions {
+        graph.clusters.push(build_cluster(
+            program,
+            analysis,
+            &mut reg_tracker,
+            sbpf_version,
+            function_range.clone(),
+            &mut visited_nodes,
+            reduced,
+        ));
+    }
+
+    let in_chunk = |pc: usize| functions.iter().any(|range| range.contains(&pc));
+    let mut external_edges = Vec::new();
+    for function_range in functions {
+        for (cfg_node_start, cfg_node) in analysis.cfg_nodes.range(function_range.clone()) {
+            if reduced && !visited_nodes.contains(cfg_node_start) {
+                continue;
+            }
+            graph
+                .edges
+                .extend(build_edges(*cfg_node_start, cfg_node, reduced, in_chunk));
+            external_edges.extend(
+                cfg_node
+                    .destinations
+                    .iter()
+                    .filter(|destination| !in_chunk(**destination))
+                    .map(|destination| [*cfg_node_start, *destination]),
+            );
         }
+    }
+    annotations.annotate_graph(&mut graph, analysis);
+    if let Some(stable_ids) = stable_ids {
+        stable_ids.apply(&mut graph);
+    }
+    let mut output = BufWriter::new(File::create(chunk_path)?);
+    graph.write_dot(&mut output)?;
+    output.flush()?;
+
+    let clusters = functions
+        .iter()
+        .map(|range| {
+            let cluster = match stable_ids.and_then(|ids| ids.function(range.start)) {
+                Some(id) => format!("cluster_{}", id),
+                None => format!("cluster_{}", range.start),
+            };
+            json!({
+                "cluster": cluster,
+                "label": analysis.cfg_nodes[&range.start].label,
+                "start": range.start,
+                "end": range.end,
+            })
+        })
+        .collect();
+    Ok((clusters, external_edges))
+}
+
+/// Returns the bytecode ranges of the functions to render, in order.
+///
+/// With `reduced`, only the functions reachable from the root (the `entry` block, or the
+/// program entrypoint) are kept, and with `only_entrypoint` only the function containing the
+/// root. With a `selection`, only the selected functions among those are kept.
+fn select_functions(
+    analysis: &Analysis,
+    sbpf_version: SBPFVersion,
+    reduced: bool,
+    only_entrypoint: bool,
+    entry: Option<usize>,
+    selection: Option<&FunctionSelection>,
+) -> Vec<std::ops::Range<usize>> {
+    let root = entry.unwrap_or(analysis.entrypoint);
+    let kept = if only_entrypoint {
+        Some(BTreeSet::from([function_containing(analysis, root)]))
+    } else if reduced {
+        Some(reachable_functions(analysis, root, sbpf_version))
+    } else {
+        None
+    };
+    let mut functions = Vec::new();
+    let function_iter = &mut analysis.functions.keys().peekable();
+
+    while let Some(function_start) = function_iter.next() {
         let function_end = if let Some(next_function) = function_iter.peek() {
             **next_function
         } else {
-            &analysis.instructions.last().unwrap().ptr + 1
+            analysis.instructions.last().unwrap().ptr + 1
         };
+        if kept
+            .as_ref()
+            .is_some_and(|kept| !kept.contains(function_start))
+        {
+            continue;
+        }
+        if selection.is_some_and(|selection| !selection.contains(*function_start)) {
+            continue;
+        }
+        functions.push(*function_start..function_end);
+    }
+    functions
+}
 
-        let mut alias_nodes = HashSet::new();
+/// Returns the start of the function containing the instruction at `pc`.
+fn function_containing(analysis: &Analysis, pc: usize) -> usize {
+    analysis
+        .functions
+        .range(..=pc)
+        .next_back()
+        .map_or(pc, |(start, _)| *start)
+}
 
-        writeln!(output, "  subgraph cluster_{} {{", *function_start)?;
-        writeln!(
-            output,
-            "    label={:?};",
-            html_escape(&analysis.cfg_nodes[function_start].label)
-        )?;
-        writeln!(output, "    tooltip=lbb_{};", *function_start)?;
+/// Returns the starts of the functions reachable from the block at `root`, following the
+/// jumps and the direct calls (the function containing `root` included).
+pub(crate) fn reachable_functions(
+    analysis: &Analysis,
+    root: usize,
+    sbpf_version: SBPFVersion,
+) -> BTreeSet<usize> {
+    let mut functions = BTreeSet::new();
+    let mut visited = HashSet::new();
+    let mut stack = vec![root];
+    while let Some(cfg_node_start) = stack.pop() {
+        if !visited.insert(cfg_node_start) {
+            continue;
+        }
+        let Some(cfg_node) = analysis.cfg_nodes.get(&cfg_node_start) else {
+            continue;
+        };
+        functions.insert(function_containing(analysis, cfg_node_start));
+        stack.extend(&cfg_node.destinations);
+        stack.extend(
+            analysis.instructions[cfg_node.instructions.clone()]
+                .iter()
+                .filter_map(|insn| resolve_internal_call(analysis, insn, sbpf_version)),
+        );
+    }
+    functions
+}
 
-        emit_cfg_node(
+/// Builds the `cluster_<start>` subgraph of a function with all its blocks.
+///
+/// # Arguments
+///
+/// * `program` - The bytecode
+/// * `analysis` - Reference to the analysis data
+/// * `reg_tracker` - Mutable reference to register tracker
+/// * `sbpf_version` - The SBPF version from the executable
+/// * `function_range` - Bytecode range of the function
+/// * `visited_nodes` - Filled with the emitted nodes when `reduced`
+/// * `reduced` - Whether to emit reduced CFG
+fn build_cluster(
+    program: &[u8],
+    analysis: &Analysis,
+    reg_tracker: &mut RegisterTracker,
+    sbpf_version: SBPFVersion,
+    function_range: std::ops::Range<usize>,
+    visited_nodes: &mut HashSet<usize>,
+    reduced: bool,
+) -> CfgCluster {
+    let function_start = function_range.start;
+    let mut blocks = Vec::new();
+
+    // depth-first pre-order walk of the dominator tree, without recursion so that
+    // deeply nested functions can't overflow the stack
+    let mut stack = vec![function_start];
+    while let Some(cfg_node_start) = stack.pop() {
+        blocks.push(build_block(
             program,
-            &mut output,
-            &analysis,
+            analysis,
             reg_tracker,
             sbpf_version,
-            *function_start..function_end,
-            &mut alias_nodes,
-            &mut visited_nodes,
-            *function_start,
-            reduced || only_entrypoint,
-        )?;
-
-        for alias_node in alias_nodes.iter() {
-            writeln!(
-                output,
-                "    alias_{}_lbb_{} [",
-                *function_start, *alias_node
-            )?;
-            writeln!(output, "        label=lbb_{:?};", *alias_node)?;
-            writeln!(output, "        tooltip=lbb_{:?};", *alias_node)?;
-            writeln!(output, "        URL=\"#lbb_{:?}\";", *alias_node)?;
-            writeln!(output, "    ];")?;
+            &function_range,
+            cfg_node_start,
+        ));
+        if reduced {
+            // this will save some memory for not-reduced CFG
+            visited_nodes.insert(cfg_node_start);
         }
+        stack.extend(
+            analysis.cfg_nodes[&cfg_node_start]
+                .dominated_children
+                .iter()
+                .rev(),
+        );
+    }
+
+    let instructions = &analysis.instructions;
+    let first = instructions.partition_point(|insn| insn.ptr < function_range.start);
+    let last = instructions.partition_point(|insn| insn.ptr < function_range.end);
+    let calls: BTreeSet<usize> = instructions[first..last]
+        .iter()
+        .filter_map(|insn| resolve_internal_call(analysis, insn, sbpf_version))
+        .collect();
 
-        writeln!(output, "  }}")?;
+    CfgCluster {
+        start: function_start,
+        id: None,
+        hash: Some(function_hash(analysis, function_start, sbpf_version).0),
+        label: analysis.cfg_nodes[&function_start].label.clone(),
+        blocks,
+        calls: calls.into_iter().collect(),
     }
+}
 
-    for (_, cfg_node_start, cfg_node) in analysis.iter_cfg_by_function() {
-        if reduced || only_entrypoint {
-            if !visited_nodes.contains(&cfg_node_start) {
-                continue;
+/// Builds a single CFG node (basic block).
+///
+/// # Arguments
+///
+/// * `program` - The bytecode
+/// * `analysis` - Reference to the analysis data
+/// * `reg_tracker` - Mutable reference to register tracker
+/// * `sbpf_version` - The SBPF version from the executable
+/// * `function_range` - Bytecode range of the current function
+/// * `cfg_node_start` - Entry point of the current node
+fn build_block(
+    program: &[u8],
+    analysis: &Analysis,
+    reg_tracker: &mut RegisterTracker,
+    sbpf_version: SBPFVersion,
+    function_range: &std::ops::Range<usize>,
+    cfg_node_start: usize,
+) -> CfgBlock {
+    let cfg_node = &analysis.cfg_nodes[&cfg_node_start];
+    let insns = &analysis.instructions[cfg_node.instructions.clone()];
+
+    // blocks renamed by the analysis (e.g. dispatch handlers) get their name as a header row
+    let name = (cfg_node_start != function_range.start
+        && !cfg_node.label.is_empty()
+        && cfg_node.label != format!("lbb_{}", cfg_node_start)
+        && cfg_node.label != format!("function_{}", cfg_node_start))
+    .then(|| cfg_node.label.clone());
+
+    // strings and syscalls of the block, summarized in its tooltip
+    let mut strings = Vec::new();
+    let mut syscalls = Vec::new();
+    let instructions = insns
+        .iter()
+        .enumerate()
+        .map(|(pc, insn)| {
+            let mut desc = disassemble_instruction(analysis, insn, pc);
+            if let Some(syscall) = desc.strip_prefix("syscall ") {
+                if !syscalls.iter().any(|known| known == syscall) {
+                    syscalls.push(syscall.to_string());
+                }
+            }
+
+            // next instruction lookup to gather information (like for string and their length when it uses MOV64_IMM)
+            let next_insn = insns.get(pc + 1);
+            // append immediate string representation if available
+            let str_repr =
+                update_string_resolution(program, insn, next_insn, reg_tracker, sbpf_version);
+
+            if !str_repr.is_empty() {
+                desc.push_str(" --> ");
+                desc.push_str(&str_repr);
+                if !strings.contains(&str_repr) {
+                    strings.push(str_repr);
+                }
+            }
+            desc
+        })
+        .collect();
+
+    CfgBlock {
+        start: cfg_node_start,
+        id: None,
+        name,
+        instructions,
+        comments: BTreeMap::new(),
+        bookmarks: vec![],
+        strings,
+        syscalls,
+    }
+}
+
+/// Builds the outgoing edges of a CFG node, restricted to the destinations accepted by `keep`.
+///
+/// In `reduced` mode, a dotted edge to the dominator parent is added as well.
+fn build_edges(
+    cfg_node_start: usize,
+    cfg_node: &CfgNode,
+    reduced: bool,
+    keep: impl Fn(usize) -> bool,
+) -> Vec<CfgEdge> {
+    let mut edges = Vec::new();
+    if reduced && cfg_node_start != cfg_node.dominator_parent && keep(cfg_node.dominator_parent) {
+        edges.push(CfgEdge {
+            from: cfg_node_start,
+            to: vec![cfg_node.dominator_parent],
+            dominator: true,
+        });
+    }
+
+    let destinations: BTreeSet<usize> = cfg_node
+        .destinations
+        .iter()
+        .copied()
+        .filter(|destination| keep(*destination))
+        .collect();
+    if !destinations.is_empty() {
+        edges.push(CfgEdge {
+            from: cfg_node_start,
+            to: destinations.into_iter().collect(),
+            dominator: false,
+        });
+    }
+    edges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reverse::function_filter::resolve_entry;
+    use crate::reverse::new_loader;
+    use solana_sbpf::elf::Executable;
+    use test_utils::TestContextObject;
+
+    #[test]
+    fn test_entry_reduction() {
+        let elf =
+            std::fs::read("test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so")
+                .unwrap();
+        let executable =
+            Executable::<TestContextObject>::from_elf(&elf, new_loader(true).unwrap()).unwrap();
+        let analysis = Analysis::from_executable(&executable).unwrap();
+        let sbpf_version = executable.get_sbpf_version();
+        let starts = |entry: usize, only_entrypoint: bool| -> Vec<usize> {
+            select_functions(
+                &analysis,
+                sbpf_version,
+                true,
+                only_entrypoint,
+                Some(entry),
+                None,
+            )
+            .into_iter()
+            .map(|range| range.start)
+            .collect()
+        };
+
+        let entrypoint = resolve_entry(&analysis, "entrypoint").unwrap();
+        assert_eq!(entrypoint, analysis.entrypoint);
+        assert_eq!(starts(entrypoint, true), vec![entrypoint]);
+        let reachable = starts(entrypoint, false);
+        assert!(reachable.contains(&entrypoint));
+        assert!(reachable.len() < analysis.functions.len());
+
+        // an internal function as the root, by name or by an address inside it
+        let panic = resolve_entry(&analysis, "custom_panic").unwrap();
+        let from_panic = starts(panic, false);
+        assert!(from_panic.contains(&panic));
+        assert!(!from_panic.contains(&entrypoint));
+        assert!(from_panic.iter().all(|start| reachable.contains(start)));
+        let inside = resolve_entry(&analysis, &format!("lbb_{}", panic + 1)).unwrap();
+        assert_eq!(function_containing(&analysis, inside), panic);
+        assert_eq!(starts(inside, true), vec![panic]);
+
+        assert!(resolve_entry(&analysis, "no_such_function").is_err());
+    }
+
+    /// The reduced CFG holds the functions reached from the entrypoint in the full CFG, over
+    /// its calls and the edges between clusters, with all their blocks.
+    #[test]
+    fn test_reachable_reduction() {
+        for binary in ["addition_checker.so", "addition_checker_sbpf_solana.so"] {
+            let elf = std::fs::read(format!(
+                "test_cases/base_sbf_addition_checker/bytecodes/{}",
+                binary
+            ))
+            .unwrap();
+            let executable =
+                Executable::<TestContextObject>::from_elf(&elf, new_loader(false).unwrap())
+                    .unwrap();
+            let analysis = Analysis::from_executable(&executable).unwrap();
+            let sbpf_version = executable.get_sbpf_version();
+            let graph = |reduced: bool| {
+                build_cfg_graph(
+                    &elf,
+                    &analysis,
+                    &mut RegisterTracker::new(),
+                    sbpf_version,
+                    &CfgOptions {
+                        reduced,
+                        ..CfgOptions::new(&Annotations::default())
+                    },
+                )
+            };
+            let (full, reduced) = (graph(false), graph(true));
+
+            let cluster_of: BTreeMap<usize, usize> = full
+                .clusters
+                .iter()
+                .flat_map(|cluster| {
+                    cluster
+                        .blocks
+                        .iter()
+                        .map(|block| (block.start, cluster.start))
+                })
+                .collect();
+            let mut successors = full.call_graph();
+            // the edges leaving the blocks drawn in the clusters
+            for edge in &full.edges {
+                if let Some(from) = cluster_of.get(&edge.from) {
+                    successors
+                        .entry(*from)
+                        .or_default()
+                        .extend(edge.to.iter().filter_map(|to| cluster_of.get(to)));
+                }
             }
-            if cfg_node_start != cfg_node.dominator_parent {
-                writeln!(
-                    output,
-                    "  lbb_{} -> lbb_{} [style=dotted; arrowhead=none];",
-                    cfg_node_start, cfg_node.dominator_parent,
-                )?;
+            let mut expected = BTreeSet::new();
+            let mut stack = vec![analysis.entrypoint];
+            while let Some(function) = stack.pop() {
+                if expected.insert(function) {
+                    stack.extend(successors.get(&function).into_iter().flatten());
+                }
             }
+
+            let functions: BTreeSet<usize> = reduced
+                .clusters
+                .iter()
+                .map(|cluster| cluster.start)
+                .collect();
+            assert_eq!(functions, expected, "{}", binary);
+            let blocks = |graph: &CfgGraph, functions: &BTreeSet<usize>| -> BTreeSet<usize> {
+                graph
+                    .clusters
+                    .iter()
+                    .filter(|cluster| functions.contains(&cluster.start))
+                    .flat_map(|cluster| cluster.blocks.iter().map(|block| block.start))
+                    .collect()
+            };
+            assert_eq!(
+                blocks(&reduced, &functions),
+                blocks(&full, &expected),
+                "{}",
+                binary
+            );
+            // unlike the former "after the entrypoint" heuristic, unreachable functions laid out
+            // after it are dropped
+            assert!(full
+                .clusters
+                .iter()
+                .any(|cluster| cluster.start > analysis.entrypoint
+                    && !functions.contains(&cluster.start)));
         }
+    }
 
-        let edges: BTreeMap<usize, usize> = cfg_node
-            .destinations
+    #[test]
+    fn test_block_tooltips() {
+        let elf =
+            std::fs::read("test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so")
+                .unwrap();
+        let executable =
+            Executable::<TestContextObject>::from_elf(&elf, new_loader(true).unwrap()).unwrap();
+        let analysis = Analysis::from_executable(&executable).unwrap();
+        let graph = build_cfg_graph(
+            &elf,
+            &analysis,
+            &mut RegisterTracker::new(),
+            executable.get_sbpf_version(),
+            &CfgOptions {
+                reduced: true,
+                ..CfgOptions::new(&Annotations::default())
+            },
+        );
+        let dot = |block: &CfgBlock| {
+            let mut output = Vec::new();
+            block.write_dot(&mut output).unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        let blocks: Vec<&CfgBlock> = graph
+            .clusters
             .iter()
-            .map(|destination| (*destination, 0))
+            .flat_map(|cluster| &cluster.blocks)
             .collect();
-
-        let counter_sum: usize = edges.values().sum();
-
-        if counter_sum == 0 && !edges.is_empty() {
-            writeln!(
-                output,
-                "  lbb_{} -> {{{}}};",
-                cfg_node_start,
-                edges
-                    .keys()
-                    .map(|destination| format!("lbb_{}", *destination))
-                    .collect::<Vec<String>>()
-                    .join(" ")
-            )?;
+        for block in &blocks {
+            let logs = block
+                .instructions
+                .iter()
+                .any(|insn| insn == "syscall sol_log_");
+            assert_eq!(block.syscalls.contains(&"sol_log_".to_string()), logs);
         }
-    }
+        let logging = blocks
+            .iter()
+            .find(|block| block.syscalls == ["sol_log_"] && block.strings.is_empty())
+            .unwrap();
+        assert!(dot(logging).ends_with("; tooltip=\"syscalls: sol_log_\"];\n"));
 
-    writeln!(output, "}}")?;
-    Ok(())
+        let block = CfgBlock {
+            strings: vec![r#"b"You lose!""#.to_string(), r#"b"\x00""#.to_string()],
+            ..(*logging).clone()
+        };
+        assert!(dot(&block)
+            .contains(r#"tooltip="strings: b\"You lose!\", b\"\\x00\"\nsyscalls: sol_log_""#));
+        let quiet = CfgBlock {
+            syscalls: vec![],
+            ..(*logging).clone()
+        };
+        assert!(!dot(&quiet).contains("tooltip"));
+    }
 }
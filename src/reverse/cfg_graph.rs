@@ -0,0 +1,331 @@
+//! JSON representation of the control flow graph (`cfg.json`), written next to `cfg.dot`.
+//!
+//! The graph is a list of function clusters, each holding its basic blocks and their
+//! instructions, and a list of edges. Tools editing the CFG (such as `dotting`) work on this
+//! structure and render the DOT text as a final step with [`CfgGraph::to_dot`], instead of
+//! matching the DOT text.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::Path;
+
+use crate::reverse::sanitize::{dot_escape, html_escape, truncate_on_char_boundary};
+use crate::reverse::utils::MAX_BYTES_USED_TO_READ_FOR_IMMEDIATE_STRING_REPR;
+
+/// Maximum length of the operand cell of an instruction row.
+const MAX_CELL_CONTENT_LENGTH: usize =
+    15 + MAX_BYTES_USED_TO_READ_FOR_IMMEDIATE_STRING_REPR as usize;
+
+/// A control flow graph.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CfgGraph {
+    pub clusters: Vec<CfgCluster>,
+    pub edges: Vec<CfgEdge>,
+}
+
+/// A function (`cluster_<start>`, or `cluster_<id>` with a stable id).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CfgCluster {
+    /// Instruction pointer of the first block of the function.
+    pub start: usize,
+    /// Stable name of the function (see `--stable-ids`), used instead of `start` in the DOT.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Hash of the normalized instructions of the function, the same across builds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    pub label: String,
+    /// Blocks in rendering order (pre-order walk of the dominator tree).
+    pub blocks: Vec<CfgBlock>,
+    /// Starts of the functions called directly by this one, sorted. Not drawn in the DOT.
+    #[serde(default)]
+    pub calls: Vec<usize>,
+}
+
+/// A basic block (`lbb_<start>`, or `lbb_<id>` with a stable id).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CfgBlock {
+    pub start: usize,
+    /// Stable name of the block (see `--stable-ids`), used instead of `start` in the DOT.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Name given to the block by the analysis (e.g. a dispatch handler), if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Disassembled instructions, with the resolved immediate strings.
+    pub instructions: Vec<String>,
+    /// Comments of the annotations file, by index of the instruction they precede.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub comments: BTreeMap<usize, String>,
+    /// Bookmarks set on the instructions of the block (`<name>: <note>`), highlighted in the DOT.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bookmarks: Vec<String>,
+    /// Immediate strings resolved in the block (`b"..."`), once each, in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub strings: Vec<String>,
+    /// Syscalls invoked by the block, once each, in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub syscalls: Vec<String>,
+}
+
+/// Edges from a block: control flow to `to`, or a dotted link to the dominator parent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CfgEdge {
+    pub from: usize,
+    pub to: Vec<usize>,
+    #[serde(default)]
+    pub dominator: bool,
+}
+
+/// Writes the opening of the `digraph` and its default attributes.
+pub fn write_graph_header<W: Write>(output: &mut W) -> std::io::Result<()> {
+    writeln!(
+        output,
+        "digraph {{
+graph [
+rankdir=LR;
+concentrate=True;
+style=filled;
+color=lightgrey;
+];
+node [
+shape=rect;
+style=filled;
+fillcolor=white;
+fontname=\"Courier New\";
+];
+edge [
+fontname=\"Courier New\";
+];"
+    )
+}
+
+/// Renders an instruction as a table row, the mnemonic and the (truncated) operands in two cells.
+fn instruction_row(instruction: &str) -> String {
+    match instruction.split_once(' ') {
+        Some((mnemonic, operands)) => {
+            let mut operands = operands.to_string();
+            if operands.len() > MAX_CELL_CONTENT_LENGTH + 1 {
+                truncate_on_char_boundary(&mut operands, MAX_CELL_CONTENT_LENGTH);
+                operands = format!("{operands}…");
+            }
+            format!(
+                "<tr><td align=\"left\">{}</td><td align=\"left\">{}</td></tr>",
+                html_escape(mnemonic),
+                html_escape(&operands)
+            )
+        }
+        None => format!(
+            "<tr><td align=\"left\">{}</td></tr>",
+            html_escape(instruction)
+        ),
+    }
+}
+
+impl CfgBlock {
+    /// Name of the block in the DOT.
+    pub fn node_name(&self) -> String {
+        match &self.id {
+            Some(id) => format!("lbb_{}", id),
+            None => format!("lbb_{}", self.start),
+        }
+    }
+
+    /// Writes the block as a DOT node.
+    pub fn write_dot<W: Write>(&self, output: &mut W) -> std::io::Result<()> {
+        let header_row = |text: &str| {
+            format!(
+                "<tr><td align=\"left\" colspan=\"2\"><b>{}</b></td></tr>",
+                html_escape(text)
+            )
+        };
+        let mut header = self.name.as_deref().map_or_else(String::new, header_row);
+        for bookmark in &self.bookmarks {
+            header.push_str(&header_row(&format!("bookmark: {}", bookmark)));
+        }
+        let rows: String = self
+            .instructions
+            .iter()
+            .enumerate()
+            .map(|(index, instruction)| {
+                let comments: String = self
+                    .comments
+                    .get(&index)
+                    .into_iter()
+                    .flat_map(|comment| comment.lines())
+                    .map(|line| {
+                        format!(
+                            "<tr><td align=\"left\" colspan=\"2\"><i>// {}</i></td></tr>",
+                            html_escape(line)
+                        )
+                    })
+                    .collect();
+                comments + &instruction_row(instruction)
+            })
+            .collect();
+        let highlight = if self.bookmarks.is_empty() {
+            ""
+        } else {
+            "; fillcolor=lightyellow"
+        };
+        let tooltip = self
+            .tooltip()
+            .map_or_else(String::new, |tooltip| format!("; tooltip={}", tooltip));
+        writeln!(
+            output,
+            "    {} [label=<<table border=\"0\" cellborder=\"0\" cellpadding=\"3\">{}{}</table>>{}{}];",
+            self.node_name(),
+            header,
+            rows,
+            highlight,
+            tooltip
+        )
+    }
+
+    /// Returns the tooltip of the block as a quoted DOT string, one line for the strings and
+    /// one for the syscalls, `None` when it has neither.
+    fn tooltip(&self) -> Option<String> {
+        let mut lines = Vec::new();
+        if !self.strings.is_empty() {
+            lines.push(format!("strings: {}", self.strings.join(", ")));
+        }
+        if !self.syscalls.is_empty() {
+            lines.push(format!("syscalls: {}", self.syscalls.join(", ")));
+        }
+        if lines.is_empty() {
+            return None;
+        }
+        let escaped: Vec<String> = lines.iter().map(|line| dot_escape(line)).collect();
+        Some(format!("\"{}\"", escaped.join("\\n")))
+    }
+}
+
+impl CfgCluster {
+    /// Name of the cluster in the DOT.
+    pub fn node_name(&self) -> String {
+        match &self.id {
+            Some(id) => format!("cluster_{}", id),
+            None => format!("cluster_{}", self.start),
+        }
+    }
+
+    /// Writes the function as a DOT `subgraph`, its tooltip giving its entry block and hash.
+    pub fn write_dot<W: Write>(&self, output: &mut W) -> std::io::Result<()> {
+        let entry = self
+            .blocks
+            .iter()
+            .find(|block| block.start == self.start)
+            .map_or_else(|| format!("lbb_{}", self.start), CfgBlock::node_name);
+        writeln!(output, "  subgraph {} {{", self.node_name())?;
+        writeln!(output, "    label={:?};", html_escape(&self.label))?;
+        match &self.hash {
+            Some(hash) => writeln!(output, "    tooltip=\"{} {}\";", entry, hash)?,
+            None => writeln!(output, "    tooltip={};", entry)?,
+        }
+        for block in &self.blocks {
+            block.write_dot(output)?;
+        }
+        writeln!(output, "  }}")
+    }
+}
+
+impl CfgEdge {
+    /// Writes the edge as a DOT statement, the blocks named by `names` (`lbb_<start>` for the
+    /// others).
+    pub fn write_dot<W: Write>(
+        &self,
+        output: &mut W,
+        names: &HashMap<usize, String>,
+    ) -> std::io::Result<()> {
+        let name = |start: &usize| {
+            names
+                .get(start)
+                .cloned()
+                .unwrap_or_else(|| format!("lbb_{}", start))
+        };
+        if self.dominator {
+            for to in &self.to {
+                writeln!(
+                    output,
+                    "  {} -> {} [style=dotted; arrowhead=none];",
+                    name(&self.from),
+                    name(to)
+                )?;
+            }
+            return Ok(());
+        }
+        writeln!(
+            output,
+            "  {} -> {{{}}};",
+            name(&self.from),
+            self.to.iter().map(name).collect::<Vec<String>>().join(" ")
+        )
+    }
+}
+
+impl CfgGraph {
+    /// Writes the whole graph as DOT: the clusters, then the edges.
+    pub fn write_dot<W: Write>(&self, output: &mut W) -> std::io::Result<()> {
+        write_graph_header(output)?;
+        for cluster in &self.clusters {
+            cluster.write_dot(output)?;
+        }
+        let names: HashMap<usize, String> = self
+            .clusters
+            .iter()
+            .flat_map(|cluster| &cluster.blocks)
+            .filter(|block| block.id.is_some())
+            .map(|block| (block.start, block.node_name()))
+            .collect();
+        for edge in &self.edges {
+            edge.write_dot(output, &names)?;
+        }
+        writeln!(output, "}}")
+    }
+
+    /// Returns the graph as DOT text.
+    pub fn to_dot(&self) -> String {
+        let mut output = Vec::new();
+        // writing to a vector can't fail
+        let _ = self.write_dot(&mut output);
+        String::from_utf8_lossy(&output).into_owned()
+    }
+
+    /// Returns the start of the cluster named `function`: `"42"`, `"cluster_42"`, or the stable
+    /// id of the function with or without the `cluster_` prefix.
+    #[cfg(feature = "cli")]
+    pub fn cluster_start(&self, function: &str) -> Option<usize> {
+        let function = function.trim();
+        let name = function.strip_prefix("cluster_").unwrap_or(function);
+        self.clusters
+            .iter()
+            .find(|cluster| cluster.id.as_deref() == Some(name))
+            .map(|cluster| cluster.start)
+            .or_else(|| name.parse().ok())
+    }
+
+    /// Returns the call graph of the clusters: the functions called by each function.
+    #[cfg(feature = "cli")]
+    pub fn call_graph(&self) -> BTreeMap<usize, Vec<usize>> {
+        self.clusters
+            .iter()
+            .map(|cluster| (cluster.start, cluster.calls.clone()))
+            .collect()
+    }
+
+    /// Reads a `cfg.json`.
+    #[cfg(feature = "cli")]
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Writes the graph as `cfg.json`.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(
+            path,
+            serde_json::to_string_pretty(self).map_err(std::io::Error::other)?,
+        )
+    }
+}
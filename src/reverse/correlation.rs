@@ -0,0 +1,640 @@
+//! Correlation of the SAST findings with the compiled program.
+//!
+//! When the `.so` of a scanned project is available, each match is mapped to the item of the
+//! source enclosing it (a function, or a type such as an Anchor accounts `struct`) and the
+//! item is looked up in the program: among the function symbols of its symbol table or, for a
+//! stripped Anchor program, among the instruction handlers recognized in the dispatch with the
+//! IDL. Findings in code that didn't survive optimization can then be told apart.
+
+#[cfg(feature = "cli")]
+use anyhow::Result;
+#[cfg(feature = "cli")]
+use log::{debug, info, warn};
+#[cfg(feature = "cli")]
+use serde::Serialize;
+#[cfg(feature = "cli")]
+use solana_sbpf::{elf::Executable, static_analysis::Analysis};
+#[cfg(feature = "cli")]
+use std::collections::HashMap;
+#[cfg(feature = "cli")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "cli")]
+use syn::spanned::Spanned;
+#[cfg(feature = "cli")]
+use test_utils::TestContextObject;
+
+#[cfg(feature = "cli")]
+use crate::helpers::anchor_toml::crate_names;
+#[cfg(feature = "cli")]
+use crate::reverse::dispatch::{find_dispatch_table, load_idl_discriminators};
+#[cfg(feature = "cli")]
+use crate::reverse::elf_sections::function_symbols;
+#[cfg(feature = "cli")]
+use crate::reverse::new_loader;
+#[cfg(feature = "cli")]
+use crate::state::sast_state::SastState;
+
+/// Keys of the match metadata set by [`correlate_findings`].
+pub const COMPILED_METADATA: &str = "compiled";
+#[cfg(feature = "cli")]
+pub const COMPILED_SYMBOL_METADATA: &str = "compiled_symbol";
+#[cfg(feature = "cli")]
+pub const COMPILED_PROGRAM_METADATA: &str = "compiled_program";
+
+/// Whether the code of a finding is in the compiled program.
+#[cfg(feature = "cli")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompiledStatus {
+    /// A function of the enclosing item is in the program.
+    Compiled,
+    /// The item has no function of its own in the program but is referenced in the sources:
+    /// its code was most likely inlined into its callers.
+    Inlined,
+    /// The item is neither in the program nor referenced in the sources: dead code removed
+    /// by the compiler.
+    Removed,
+    /// The program can't tell: it is stripped (and the item is not a recognized instruction
+    /// handler), or the match is outside of any function or type.
+    Unknown,
+}
+
+#[cfg(feature = "cli")]
+impl CompiledStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompiledStatus::Compiled => "compiled",
+            CompiledStatus::Inlined => "inlined",
+            CompiledStatus::Removed => "removed",
+            CompiledStatus::Unknown => "unknown",
+        }
+    }
+}
+
+/// What is known about the functions of a compiled program.
+#[cfg(feature = "cli")]
+#[derive(Debug, Clone, Default)]
+pub struct ProgramFacts {
+    /// Path of the `.so`.
+    pub path: String,
+    /// File stem of the `.so`, the lib name of the crate for a regular build.
+    pub name: String,
+    /// Demangled names of the function symbols, empty for a stripped program.
+    pub symbols: Vec<String>,
+    /// Names of the IDL instructions whose handler was recognized in the dispatch.
+    pub handlers: Vec<String>,
+}
+
+#[cfg(feature = "cli")]
+impl ProgramFacts {
+    /// Reads the function symbols of a program and, with its IDL, the instruction handlers
+    /// reached by its dispatch.
+    pub fn load(path: &Path, idl: Option<&Path>) -> Result<Self> {
+        let elf = std::fs::read(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read program {}: {}", path.display(), e))?;
+        let symbols: Vec<String> = function_symbols(&elf)
+            .iter()
+            .map(|symbol| demangle(symbol))
+            .collect();
+        let handlers = match idl {
+            Some(idl) => dispatched_instructions(&elf, idl).unwrap_or_else(|e| {
+                warn!(
+                    "Failed to recognize the dispatch of {}: {}",
+                    path.display(),
+                    e
+                );
+                Vec::new()
+            }),
+            None => Vec::new(),
+        };
+        debug!(
+            "{}: {} function symbols, {} instruction handlers",
+            path.display(),
+            symbols.len(),
+            handlers.len()
+        );
+        Ok(Self {
+            path: path.to_string_lossy().to_string(),
+            name: path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            symbols,
+            handlers,
+        })
+    }
+
+    /// Returns the first function symbol of the program belonging to `item`, if any.
+    ///
+    /// Symbols outside of the crates of the item are ignored, so that a function of a
+    /// dependency with the same name doesn't count.
+    fn find_symbol(&self, item: &SourceItem, crates: &[String]) -> Option<&str> {
+        self.symbols
+            .iter()
+            .filter(|symbol| {
+                crates.is_empty()
+                    || crates
+                        .iter()
+                        .any(|name| symbol.contains(&format!("{}::", name)))
+            })
+            .find(|symbol| item.matches_symbol(symbol))
+            .map(String::as_str)
+    }
+
+    /// Returns `true` if `item` is an Anchor instruction recognized in the dispatch.
+    fn has_handler(&self, item: &SourceItem) -> bool {
+        item.instruction
+            && self
+                .handlers
+                .iter()
+                .any(|handler| normalize(handler) == normalize(&item.name))
+    }
+}
+
+/// Names compared regardless of case and underscores (IDL names are camelCase before
+/// Anchor 0.30).
+#[cfg(feature = "cli")]
+fn normalize(name: &str) -> String {
+    name.replace('_', "").to_lowercase()
+}
+
+/// Returns the IDL instructions whose handler is a target of the dispatch of the program.
+#[cfg(feature = "cli")]
+fn dispatched_instructions(elf: &[u8], idl: &Path) -> Result<Vec<String>> {
+    let idl_names = load_idl_discriminators(idl)?;
+    let executable = Executable::<TestContextObject>::from_elf(elf, new_loader(false)?)
+        .map_err(|e| anyhow::anyhow!("Failed to construct executable: {:?}", e))?;
+    let analysis = Analysis::from_executable(&executable)
+        .map_err(|e| anyhow::anyhow!("Failed to analyze executable: {:?}", e))?;
+    let Some(table) =
+        find_dispatch_table(&analysis, executable.get_sbpf_version(), Some(&idl_names))
+    else {
+        return Ok(Vec::new());
+    };
+    Ok(table
+        .targets
+        .into_iter()
+        .filter(|target| idl_names.contains_key(&target.selector_value))
+        .map(|target| target.name)
+        .collect())
+}
+
+/// Demangles a legacy Rust symbol (`_ZN...E`) into its path, without the hash
+/// (`_ZN14solana_program10entrypoint11deserialize17h...E` ->
+/// `solana_program::entrypoint::deserialize`). Other symbols are returned as is.
+#[cfg(feature = "cli")]
+pub fn demangle(symbol: &str) -> String {
+    let Some(mut rest) = symbol
+        .strip_prefix("_ZN")
+        .and_then(|rest| rest.strip_suffix('E'))
+    else {
+        return symbol.to_string();
+    };
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        let digits = rest.chars().take_while(char::is_ascii_digit).count();
+        let Some(segment) = rest[..digits]
+            .parse::<usize>()
+            .ok()
+            .and_then(|length| rest.get(digits..digits + length))
+        else {
+            return symbol.to_string();
+        };
+        rest = &rest[digits + segment.len()..];
+        segments.push(segment);
+    }
+    let is_hash = |segment: &&str| {
+        segment.len() == 17
+            && segment.starts_with('h')
+            && segment[1..].chars().all(|c| c.is_ascii_hexdigit())
+    };
+    if segments.last().is_some_and(is_hash) {
+        segments.pop();
+    }
+    segments
+        .iter()
+        .map(|segment| decode_segment(segment))
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+/// Decodes the `$..$` escapes and the `..` separators of a legacy mangled path segment.
+#[cfg(feature = "cli")]
+fn decode_segment(segment: &str) -> String {
+    let segment = segment
+        .strip_prefix("_$")
+        .map_or(segment, |_| &segment[1..]);
+    let mut decoded = String::with_capacity(segment.len());
+    let mut rest = segment;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("..") {
+            decoded.push_str("::");
+            rest = after;
+            continue;
+        }
+        if let Some((escape, after)) = rest
+            .strip_prefix('$')
+            .and_then(|after| after.split_once('$'))
+        {
+            let character = match escape {
+                "SP" => Some('@'),
+                "BP" => Some('*'),
+                "RF" => Some('&'),
+                "LT" => Some('<'),
+                "GT" => Some('>'),
+                "LP" => Some('('),
+                "RP" => Some(')'),
+                "C" => Some(','),
+                _ => escape
+                    .strip_prefix('u')
+                    .and_then(|code| u32::from_str_radix(code, 16).ok())
+                    .and_then(char::from_u32),
+            };
+            if let Some(character) = character {
+                decoded.push(character);
+                rest = after;
+                continue;
+            }
+        }
+        let mut chars = rest.chars();
+        decoded.extend(chars.next());
+        rest = chars.as_str();
+    }
+    decoded
+}
+
+/// The innermost item of a source file enclosing a match.
+#[cfg(feature = "cli")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceItem {
+    /// Name of the function, or of the type for a match in a `struct` or `enum`.
+    pub name: String,
+    /// `true` for a function, `false` for a type.
+    pub function: bool,
+    /// Type of the `impl` block of a method.
+    pub impl_type: Option<String>,
+    /// `true` for the instruction handlers of an Anchor `#[program]` module.
+    pub instruction: bool,
+}
+
+#[cfg(feature = "cli")]
+impl SourceItem {
+    /// Returns `true` if the demangled `symbol` is a function of the item: the function
+    /// itself (`crate::module::name`, `<crate::Type as Trait>::name`), or a method of the
+    /// type (e.g. the `try_accounts` generated for an accounts `struct`).
+    fn matches_symbol(&self, symbol: &str) -> bool {
+        let segments: Vec<&str> = symbol
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        if !self.function {
+            return segments.contains(&self.name.as_str());
+        }
+        segments.last() == Some(&self.name.as_str())
+            && self
+                .impl_type
+                .as_ref()
+                .is_none_or(|impl_type| segments.contains(&impl_type.as_str()))
+    }
+}
+
+/// Returns `true` if the lines of `span` include `line`.
+#[cfg(feature = "cli")]
+fn span_contains(span: proc_macro2::Span, line: usize) -> bool {
+    span.start().line <= line && line <= span.end().line
+}
+
+/// Returns the name of the last segment of a type path (`Type` for `crate::Type<'info>`).
+#[cfg(feature = "cli")]
+fn type_name(ty: &syn::Type) -> Option<String> {
+    match ty {
+        syn::Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string()),
+        _ => None,
+    }
+}
+
+/// Finds the innermost function or type of `items` enclosing `line`.
+///
+/// # Arguments
+///
+/// * `in_program` - `true` within an Anchor `#[program]` module.
+#[cfg(feature = "cli")]
+pub fn enclosing_item(items: &[syn::Item], line: usize, in_program: bool) -> Option<SourceItem> {
+    let item = items.iter().find(|item| span_contains(item.span(), line))?;
+    match item {
+        syn::Item::Fn(function) => Some(SourceItem {
+            name: function.sig.ident.to_string(),
+            function: true,
+            impl_type: None,
+            instruction: in_program,
+        }),
+        syn::Item::Mod(module) => {
+            let is_program = module
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("program"));
+            let (_, items) = module.content.as_ref()?;
+            enclosing_item(items, line, is_program)
+        }
+        syn::Item::Impl(block) => {
+            let impl_type = type_name(&block.self_ty);
+            let method = block.items.iter().find_map(|item| match item {
+                syn::ImplItem::Fn(method) if span_contains(method.span(), line) => {
+                    Some(method.sig.ident.to_string())
+                }
+                _ => None,
+            });
+            match method {
+                Some(name) => Some(SourceItem {
+                    name,
+                    function: true,
+                    impl_type,
+                    instruction: false,
+                }),
+                None => impl_type.map(|name| SourceItem {
+                    name,
+                    function: false,
+                    impl_type: None,
+                    instruction: false,
+                }),
+            }
+        }
+        syn::Item::Trait(definition) => definition.items.iter().find_map(|item| match item {
+            syn::TraitItem::Fn(method)
+                if method.default.is_some() && span_contains(method.span(), line) =>
+            {
+                Some(SourceItem {
+                    name: method.sig.ident.to_string(),
+                    function: true,
+                    impl_type: None,
+                    instruction: false,
+                })
+            }
+            _ => None,
+        }),
+        syn::Item::Struct(definition) => Some(SourceItem {
+            name: definition.ident.to_string(),
+            function: false,
+            impl_type: None,
+            instruction: false,
+        }),
+        syn::Item::Enum(definition) => Some(SourceItem {
+            name: definition.ident.to_string(),
+            function: false,
+            impl_type: None,
+            instruction: false,
+        }),
+        _ => None,
+    }
+}
+
+/// Returns the names of the crate holding a source file, from the nearest `Cargo.toml`.
+#[cfg(feature = "cli")]
+fn source_crate_names(file: &Path) -> Vec<String> {
+    file.ancestors()
+        .skip(1)
+        .find(|dir| dir.join("Cargo.toml").is_file())
+        .map(crate_names)
+        .unwrap_or_default()
+}
+
+/// Returns `true` if `name` appears in the sources besides its definition.
+#[cfg(feature = "cli")]
+fn is_referenced(name: &str, sources: &[String]) -> bool {
+    let occurrences: usize = sources
+        .iter()
+        .map(|source| {
+            source
+                .match_indices(name)
+                .filter(|(start, _)| {
+                    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+                    !source[..*start].ends_with(is_ident)
+                        && !source[start + name.len()..].starts_with(is_ident)
+                })
+                .count()
+        })
+        .sum();
+    occurrences > 1
+}
+
+/// Correlation of one match with the programs.
+#[cfg(feature = "cli")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Correlation {
+    pub status: CompiledStatus,
+    /// Function of the program the match was found in.
+    pub symbol: Option<String>,
+    /// Path of that program.
+    pub program: Option<String>,
+}
+
+/// Looks the item enclosing a match up in the programs.
+///
+/// # Arguments
+///
+/// * `item` - The item of the source enclosing the match, if any.
+/// * `crates` - The names of the crate of the source file.
+/// * `programs` - The programs built from the project.
+/// * `sources` - The sources of the project, to tell inlined from removed code.
+#[cfg(feature = "cli")]
+fn correlate(
+    item: Option<&SourceItem>,
+    crates: &[String],
+    programs: &[ProgramFacts],
+    sources: &[String],
+) -> Correlation {
+    let unknown = Correlation {
+        status: CompiledStatus::Unknown,
+        symbol: None,
+        program: None,
+    };
+    let Some(item) = item else {
+        return unknown;
+    };
+    // the programs of the crate, or all of them when none is named after it
+    let crate_programs: Vec<&ProgramFacts> = programs
+        .iter()
+        .filter(|program| crates.contains(&program.name))
+        .collect();
+    let candidates = if crate_programs.is_empty() {
+        programs.iter().collect()
+    } else {
+        crate_programs
+    };
+
+    for program in &candidates {
+        if let Some(symbol) = program.find_symbol(item, crates) {
+            return Correlation {
+                status: CompiledStatus::Compiled,
+                symbol: Some(symbol.to_string()),
+                program: Some(program.path.clone()),
+            };
+        }
+        if program.has_handler(item) {
+            return Correlation {
+                status: CompiledStatus::Compiled,
+                symbol: None,
+                program: Some(program.path.clone()),
+            };
+        }
+    }
+    // without symbols, a missing function proves nothing
+    if candidates.iter().all(|program| program.symbols.is_empty()) {
+        return unknown;
+    }
+    Correlation {
+        status: if item.instruction || is_referenced(&item.name, sources) {
+            CompiledStatus::Inlined
+        } else {
+            CompiledStatus::Removed
+        },
+        symbol: None,
+        program: None,
+    }
+}
+
+/// Number of matches per status, returned by [`correlate_findings`].
+#[cfg(feature = "cli")]
+pub type CorrelationSummary = HashMap<CompiledStatus, usize>;
+
+/// Annotates every match of a SAST state with the correlation of its enclosing item with
+/// the programs, in the `compiled`, `compiled_symbol` and `compiled_program` metadata.
+///
+/// # Returns
+///
+/// The number of matches per status.
+#[cfg(feature = "cli")]
+pub fn correlate_findings(state: &mut SastState, programs: &[ProgramFacts]) -> CorrelationSummary {
+    let sources: Vec<String> = state
+        .syn_ast_map
+        .keys()
+        .filter_map(|file| std::fs::read_to_string(file).ok())
+        .collect();
+    let mut crates_by_file: HashMap<PathBuf, Vec<String>> = HashMap::new();
+    let mut summary = CorrelationSummary::new();
+
+    for (file, syn_ast) in state.syn_ast_map.iter_mut() {
+        let crates = crates_by_file
+            .entry(PathBuf::from(file))
+            .or_insert_with(|| source_crate_names(Path::new(file)))
+            .clone();
+        for result in syn_ast.results.iter_mut() {
+            for match_result in result.matches.iter_mut() {
+                let item = match_result
+                    .get_location_metadata()
+                    .ok()
+                    .and_then(|position| {
+                        enclosing_item(&syn_ast.ast.items, position.start_line as usize, false)
+                    });
+                let correlation = correlate(item.as_ref(), &crates, programs, &sources);
+                *summary.entry(correlation.status).or_default() += 1;
+
+                let metadata = &mut match_result.metadata;
+                metadata.insert(
+                    COMPILED_METADATA.to_string(),
+                    serde_json::json!(correlation.status.as_str()),
+                );
+                if let Some(symbol) = correlation.symbol {
+                    metadata.insert(
+                        COMPILED_SYMBOL_METADATA.to_string(),
+                        serde_json::json!(symbol),
+                    );
+                }
+                if let Some(program) = correlation.program {
+                    metadata.insert(
+                        COMPILED_PROGRAM_METADATA.to_string(),
+                        serde_json::json!(program),
+                    );
+                }
+            }
+        }
+    }
+
+    let total: usize = summary.values().sum();
+    info!(
+        "Findings correlated with {} program(s): {} compiled, {} inlined, {} removed, {} unknown (of {}).",
+        programs.len(),
+        summary.get(&CompiledStatus::Compiled).unwrap_or(&0),
+        summary.get(&CompiledStatus::Inlined).unwrap_or(&0),
+        summary.get(&CompiledStatus::Removed).unwrap_or(&0),
+        summary.get(&CompiledStatus::Unknown).unwrap_or(&0),
+        total
+    );
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_demangle() {
+        assert_eq!(
+            demangle("_ZN14solana_program10entrypoint11deserialize17h7b1132848611f500E"),
+            "solana_program::entrypoint::deserialize"
+        );
+        assert_eq!(
+            demangle("_ZN44_$LT$$RF$T$u20$as$u20$core..fmt..Display$GT$3fmt17h1c81a57b8b93954aE"),
+            "<&T as core::fmt::Display>::fmt"
+        );
+        assert_eq!(demangle("entrypoint"), "entrypoint");
+    }
+
+    #[test]
+    fn test_correlate() {
+        let source = "#[program]\nmod prog {\n    pub fn update(ctx: Context<Update>) {\n        helper();\n    }\n}\n\nfn helper() {\n    realloc();\n}\n\nfn unused() {\n    realloc();\n}\n\nstruct Update {\n    data: u8,\n}\n";
+        let file = syn::parse_file(source).unwrap();
+        let item = |line| enclosing_item(&file.items, line, false).unwrap();
+        assert!(item(4).instruction);
+        assert_eq!(item(9).name, "helper");
+        assert!(!item(17).function);
+
+        let sources = vec![source.to_string()];
+        let crates = vec!["prog_crate".to_string()];
+        let program = ProgramFacts {
+            path: "prog_crate.so".to_string(),
+            name: "prog_crate".to_string(),
+            symbols: vec![
+                "prog_crate::prog::update".to_string(),
+                "<prog_crate::Update as anchor_lang::Accounts>::try_accounts".to_string(),
+                "other_crate::unused".to_string(),
+            ],
+            handlers: vec![],
+        };
+        let status = |line| {
+            correlate(
+                Some(&item(line)),
+                &crates,
+                std::slice::from_ref(&program),
+                &sources,
+            )
+            .status
+        };
+        assert_eq!(status(4), CompiledStatus::Compiled);
+        assert_eq!(status(9), CompiledStatus::Inlined);
+        assert_eq!(status(13), CompiledStatus::Removed);
+        assert_eq!(status(17), CompiledStatus::Compiled);
+
+        // a stripped program only knows the dispatched instructions
+        let stripped = ProgramFacts {
+            symbols: vec![],
+            handlers: vec!["update".to_string()],
+            ..program
+        };
+        let status = |line| {
+            correlate(
+                Some(&item(line)),
+                &crates,
+                std::slice::from_ref(&stripped),
+                &sources,
+            )
+            .status
+        };
+        assert_eq!(status(4), CompiledStatus::Compiled);
+        assert_eq!(status(13), CompiledStatus::Unknown);
+    }
+}
@@ -0,0 +1,145 @@
+//! Shared pieces of the forward data-flow analyses of the reverse passes
+//! ([`crate::reverse::pda_seeds`], [`crate::reverse::pointer_leaks`]).
+//!
+//! The analyses run on the disassembled mnemonics of each function (like
+//! [`crate::reverse::stats`]), so that they don't depend on the SBPF version encodings: this
+//! module groups the CFG nodes by function, parses the operands and iterates a transfer function
+//! over the blocks until a fixed point is reached.
+
+use solana_sbpf::static_analysis::Analysis;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::ops::Range;
+
+use crate::reverse::sanitize::disassemble_instruction;
+
+/// A function of the program, with the disassembled instructions of its CFG nodes.
+pub struct Function {
+    /// Start of the function, which is also its entry node.
+    pub start: usize,
+    /// Instruction range of the function.
+    pub range: Range<usize>,
+    /// Starts of the CFG nodes of the function.
+    pub nodes: Vec<usize>,
+    /// Disassembled instructions of each node, by node start.
+    pub descriptions: HashMap<usize, Vec<String>>,
+}
+
+impl Function {
+    /// Runs a forward data-flow analysis over the CFG of the function.
+    ///
+    /// # Arguments
+    ///
+    /// * `analysis` - The `Analysis` structure of the program.
+    /// * `entry_state` - The state at the entry of the function.
+    /// * `transfer` - Applies a disassembled instruction to a state.
+    /// * `meet` - Merges the state of a block with an incoming one.
+    ///
+    /// # Returns
+    ///
+    /// The state at the start of each block reachable from the entry, once a fixed point is
+    /// reached.
+    pub fn block_states<S: Clone + PartialEq>(
+        &self,
+        analysis: &Analysis,
+        entry_state: S,
+        transfer: impl Fn(&mut S, &str),
+        meet: impl Fn(&S, &S) -> S,
+    ) -> HashMap<usize, S> {
+        let mut block_states = HashMap::from([(self.start, entry_state)]);
+        let mut worklist = VecDeque::from([self.start]);
+        while let Some(node) = worklist.pop_front() {
+            let mut state = block_states[&node].clone();
+            for desc in &self.descriptions[&node] {
+                transfer(&mut state, desc);
+            }
+            for destination in analysis.cfg_nodes[&node]
+                .destinations
+                .iter()
+                .filter(|destination| self.range.contains(destination))
+            {
+                let next_state = match block_states.get(destination) {
+                    Some(current) => meet(current, &state),
+                    None => state.clone(),
+                };
+                if block_states.get(destination) != Some(&next_state) {
+                    block_states.insert(*destination, next_state);
+                    worklist.push_back(*destination);
+                }
+            }
+        }
+        block_states
+    }
+}
+
+/// Groups the CFG nodes of the program by function, with their disassembled instructions.
+pub fn functions(analysis: &Analysis) -> Vec<Function> {
+    let mut functions: BTreeMap<usize, (Range<usize>, Vec<usize>)> = BTreeMap::new();
+    for (function_range, cfg_node_start, _) in analysis.iter_cfg_by_function() {
+        // the super root is a virtual node linking all the functions
+        if !function_range.contains(&cfg_node_start) {
+            continue;
+        }
+        functions
+            .entry(function_range.start)
+            .or_insert_with(|| (function_range.clone(), Vec::new()))
+            .1
+            .push(cfg_node_start);
+    }
+
+    functions
+        .into_iter()
+        .map(|(start, (range, nodes))| {
+            let descriptions = nodes
+                .iter()
+                .map(|node| {
+                    let range = analysis.cfg_nodes[node].instructions.clone();
+                    let descriptions = analysis.instructions[range.clone()]
+                        .iter()
+                        .zip(range)
+                        .map(|(insn, pc)| disassemble_instruction(analysis, insn, pc))
+                        .collect();
+                    (*node, descriptions)
+                })
+                .collect();
+            Function {
+                start,
+                range,
+                nodes,
+                descriptions,
+            }
+        })
+        .collect()
+}
+
+/// Parses a register operand (`r0` to `r10`).
+pub fn parse_register(operand: &str) -> Option<u8> {
+    operand
+        .strip_prefix('r')
+        .and_then(|reg| reg.parse().ok())
+        .filter(|reg| *reg <= 10)
+}
+
+/// Parses an immediate operand, in decimal or hexadecimal (the 64-bit `lddw` immediates being
+/// wrapped).
+pub fn parse_immediate(operand: &str) -> Option<i64> {
+    match operand.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok().map(|imm| imm as i64),
+        None => operand.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_operands() {
+        assert_eq!(parse_register("r10"), Some(10));
+        assert_eq!(parse_register("r11"), None);
+        assert_eq!(parse_register("[r10-0x8]"), None);
+        assert_eq!(parse_immediate("-64"), Some(-64));
+        assert_eq!(parse_immediate("0x40"), Some(0x40));
+        assert_eq!(parse_immediate("0xffffffffffffffff"), Some(-1));
+        assert_eq!(parse_immediate("r1"), None);
+    }
+}
@@ -2,34 +2,126 @@
 // licensed under the MIT license.
 // See https://github.com/anza-xyz/sbpf
 
-use indicatif::ProgressIterator;
 use log::debug;
+use serde_json::json;
 use solana_sbpf::{ebpf, program::SBPFVersion, static_analysis::Analysis};
 
 use crate::helpers;
+use crate::reverse::annotations::Annotations;
+use crate::reverse::error_codes::ErrorCodeTable;
+use crate::reverse::function_filter::FunctionSelection;
 use crate::reverse::immediate_tracker::ImmediateTracker;
-use crate::reverse::rusteq::translate_to_rust;
+use crate::reverse::rusteq::{translate_to_rust, RustEqMode};
+use crate::reverse::rusteq_functions::export_rusteq_functions;
+use crate::reverse::sanitize::{disassemble_instruction, truncate_on_char_boundary};
 use crate::reverse::syscalls::get_syscall_signature;
 use crate::reverse::utils::{
     format_bytes, get_rodata_region_start, is_rodata_address, update_string_resolution,
     RegisterTracker, MAX_BYTES_USED_TO_READ_FOR_IMMEDIATE_STRING_REPR,
 };
 use crate::reverse::OutputFile;
+use std::collections::BTreeMap;
 use std::fs::File;
-use std::io::Write;
+use std::io::{BufWriter, Write};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+
+/// Format of the disassembly listing (`--disass-format`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DisassFormat {
+    /// `disassembly.out` with the labels and the inline annotations (strings, error codes,
+    /// Rust equivalent).
+    #[default]
+    Solazy,
+    /// `disassembly.out` laid out like `objdump -d`: address, bytes and assembly, without
+    /// annotations, for diffing tools.
+    Objdump,
+    /// `disassembly.json`: an array of `{pc, address, bytes, mnemonic, operands, annotations}`.
+    Json,
+}
+
+impl DisassFormat {
+    pub fn parse(format: &str) -> anyhow::Result<Self> {
+        match format {
+            "solazy" => Ok(DisassFormat::Solazy),
+            "objdump" => Ok(DisassFormat::Objdump),
+            "json" => Ok(DisassFormat::Json),
+            other => Err(anyhow::anyhow!("Unknown disassembly format: {}", other)),
+        }
+    }
+
+    fn output_file(self) -> OutputFile {
+        match self {
+            DisassFormat::Json => OutputFile::DisassemblyJson,
+            _ => OutputFile::Disassembly,
+        }
+    }
+}
+
+/// The `.text` section of the program, giving the addresses and bytes of the instructions in
+/// the `objdump` and `json` formats.
+#[derive(Debug, Clone, Copy)]
+pub struct TextSection<'a> {
+    pub bytes: &'a [u8],
+    /// Address of the first instruction (the `.text` address of the ELF, or the offset of the
+    /// instructions in a raw input).
+    pub address: u64,
+}
+
+impl TextSection<'_> {
+    fn address_of(&self, ptr: usize) -> u64 {
+        self.address + (ptr * ebpf::INSN_SIZE) as u64
+    }
+
+    /// Bytes of the instruction at `ptr`, spanning `slots` instruction slots (2 for `lddw`).
+    fn bytes_of(&self, ptr: usize, slots: usize) -> &[u8] {
+        let start = (ptr * ebpf::INSN_SIZE).min(self.bytes.len());
+        let end = ((ptr + slots) * ebpf::INSN_SIZE).min(self.bytes.len());
+        &self.bytes[start..end]
+    }
+}
+
+/// What the disassembly listing holds, on top of the instructions.
+#[derive(Clone, Copy)]
+pub struct DisassOptions<'a> {
+    /// Format of the listing.
+    pub format: DisassFormat,
+    /// Whether the bytes of the instructions are written before them in the `solazy` format
+    /// (the `objdump` and `json` formats always have them).
+    pub show_bytes: bool,
+    /// Whether the strings loaded by the instructions are shown.
+    pub resolve_strings: bool,
+    /// Error codes used to annotate the instructions using them.
+    pub error_codes: &'a ErrorCodeTable,
+    /// Whether the Rust equivalent is written next to the assembly, omitted, or written alone
+    /// (one line per pc, the instructions without equivalent kept as comments).
+    pub rusteq: RustEqMode,
+    /// If given, only these functions are disassembled (disassembly, `rusteq/` and the
+    /// immediates they load).
+    pub selection: Option<&'a FunctionSelection>,
+    /// Comments and bookmarks written before the instructions.
+    pub user_annotations: &'a Annotations,
+}
 
 /// Performs the core disassembly process of the program based on a provided static analysis.
 ///
 /// This function prints disassembled instructions into the output file, annotating
 /// each instruction and registering immediate values when encountered via `LD_DW_IMM`.
 ///
+/// Functions are formatted in parallel, each one starting from a fresh `RegisterTracker`
+/// (a function is only entered by a call, so the registers left by the previous one in the
+/// file are meaningless), and written back in address order as soon as they are ready.
+///
 /// # Arguments
 ///
 /// * `analysis` - The static analysis object containing instructions and metadata.
+/// * `text` - The `.text` section, for the addresses and bytes of the instructions.
 /// * `imm_tracker_wrapped` - An optional mutable reference to an `ImmediateTracker`
 ///   used to track offsets of immediate values.
 /// * `sbpf_version` - The SBPF version from the executable.
+/// * `options` - The format of the listing and what it holds.
 /// * `path` - Base path where the disassembly file should be written.
 ///
 /// # Returns
@@ -42,95 +134,472 @@ use std::path::{Path, PathBuf};
 /// enhanced static analysis features.
 fn disassemble<P: AsRef<Path>>(
     program: &[u8],
-    analysis: &mut Analysis,
+    analysis: &Analysis,
+    text: TextSection,
     mut imm_tracker_wrapped: Option<&mut ImmediateTracker>,
-    mut reg_tracker_wrapped: Option<&mut RegisterTracker>,
     sbpf_version: SBPFVersion,
+    options: &DisassOptions,
     path: P,
 ) -> std::io::Result<()> {
+    let DisassOptions {
+        format, selection, ..
+    } = *options;
     debug!("Disassembling...");
     let mut disass_path = PathBuf::from(path.as_ref());
-    disass_path.push(OutputFile::Disassembly.default_filename());
-    let mut output = File::create(disass_path)?;
-    let mut last_basic_block = usize::MAX;
+    disass_path.push(format.output_file().default_filename());
+    let mut output = BufWriter::new(File::create(disass_path)?);
+    match format {
+        DisassFormat::Solazy => {}
+        DisassFormat::Objdump => writeln!(output, "Disassembly of section .text:")?,
+        DisassFormat::Json => writeln!(output, "[")?,
+    }
+
+    let mut ranges = function_instruction_ranges(analysis);
+    if let Some(selection) = selection {
+        // the instructions before the first function belong to none
+        ranges.retain(|range| {
+            let start = analysis.instructions[range.start].ptr;
+            analysis.functions.contains_key(&start) && selection.contains(start)
+        });
+    }
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(ranges.len())
+        .max(1);
+    let next_range = AtomicUsize::new(0);
+    let progress = helpers::spinner::get_new_progress(
+        ranges.iter().map(|range| range.len()).sum(),
+        "Disassembling",
+    );
+
+    std::thread::scope(|scope| -> std::io::Result<()> {
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..workers {
+            let sender = sender.clone();
+            let (ranges, next_range, progress) = (&ranges, &next_range, &progress);
+            scope.spawn(move || loop {
+                let index = next_range.fetch_add(1, Ordering::Relaxed);
+                let Some(range) = ranges.get(index) else {
+                    break;
+                };
+                let formatted = disassemble_function(
+                    program,
+                    analysis,
+                    &text,
+                    range.clone(),
+                    index == 0,
+                    sbpf_version,
+                    options,
+                );
+                progress.inc(range.len());
+                if sender.send((index, formatted)).is_err() {
+                    // the writer stopped on an error
+                    break;
+                }
+            });
+        }
+        drop(sender);
 
-    for (pc, insn) in analysis.instructions.iter().enumerate().progress() {
-        analysis.disassemble_label(
-            &mut output,
-            Some(insn) == analysis.instructions.first(),
-            insn.ptr,
-            &mut last_basic_block,
+        // write the functions in order, keeping the ones formatted ahead of the next to write
+        let mut pending = BTreeMap::new();
+        let mut next_to_write = 0;
+        let mut written = false;
+        for (index, formatted) in receiver {
+            pending.insert(index, formatted);
+            while let Some(formatted) = pending.remove(&next_to_write) {
+                let (listing, rodata_loads) = formatted?;
+                // the JSON records of the functions are separated by commas
+                if format == DisassFormat::Json && written && !listing.is_empty() {
+                    writeln!(output, ",")?;
+                }
+                written |= !listing.is_empty();
+                output.write_all(&listing)?;
+                if let Some(ref mut imm_tracker) = imm_tracker_wrapped {
+                    for addr in rodata_loads {
+                        imm_tracker.register_offset(addr);
+                    }
+                }
+                next_to_write += 1;
+            }
+        }
+        if format == DisassFormat::Json {
+            if written {
+                writeln!(output)?;
+            }
+            writeln!(output, "]")?;
+        }
+        Ok(())
+    })?;
+    progress.finish();
+    output.flush()
+}
+
+/// Formats the `solazy` listing of `disassembly.out` in memory, one function after the other on
+/// the calling thread, for the builds that can't write files nor start threads (see
+/// [`crate::reverse::analyze_in_memory`]).
+pub fn disassemble_to_string(
+    program: &[u8],
+    analysis: &Analysis,
+    text: TextSection,
+    error_codes: &ErrorCodeTable,
+    sbpf_version: SBPFVersion,
+) -> std::io::Result<String> {
+    let user_annotations = Annotations::default();
+    let options = DisassOptions {
+        format: DisassFormat::Solazy,
+        show_bytes: false,
+        resolve_strings: true,
+        error_codes,
+        rusteq: RustEqMode::Column,
+        selection: None,
+        user_annotations: &user_annotations,
+    };
+    let mut listing = Vec::new();
+    for (index, range) in function_instruction_ranges(analysis)
+        .into_iter()
+        .enumerate()
+    {
+        let (function, _) = disassemble_function(
+            program,
+            analysis,
+            &text,
+            range,
+            index == 0,
+            sbpf_version,
+            &options,
         )?;
+        listing.extend(function);
+    }
+    Ok(String::from_utf8_lossy(&listing).into_owned())
+}
+
+/// Splits the instructions (indices in `analysis.instructions`) at every function start.
+///
+/// The instructions before the first function, if any, form their own range.
+pub(crate) fn function_instruction_ranges(analysis: &Analysis) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    let mut start = 0;
+    for (pc, insn) in analysis.instructions.iter().enumerate() {
+        if pc > start && analysis.functions.contains_key(&insn.ptr) {
+            ranges.push(start..pc);
+            start = pc;
+        }
+    }
+    if start < analysis.instructions.len() {
+        ranges.push(start..analysis.instructions.len());
+    }
+    ranges
+}
+
+/// Formats the instructions `range` of the disassembly (a function, see
+/// [`function_instruction_ranges`]) as set by `options`, `first` telling whether it starts the
+/// file. The `selection` of the options is left to the caller.
+///
+/// # Returns
+///
+/// The listing of the function (its records without the trailing comma in JSON), and the
+/// `.rodata` addresses loaded by its `LD_DW_IMM` instructions.
+fn disassemble_function(
+    program: &[u8],
+    analysis: &Analysis,
+    text: &TextSection,
+    range: Range<usize>,
+    first: bool,
+    sbpf_version: SBPFVersion,
+    options: &DisassOptions,
+) -> std::io::Result<(Vec<u8>, Vec<usize>)> {
+    let DisassOptions {
+        format,
+        show_bytes,
+        resolve_strings,
+        error_codes,
+        rusteq,
+        user_annotations,
+        ..
+    } = *options;
+    let mut output = Vec::new();
+    let mut rodata_loads = Vec::new();
+    let mut reg_tracker = RegisterTracker::new();
+    let mut last_basic_block = usize::MAX;
+    let function_start = analysis.instructions[range.start].ptr;
+    let function_label = analysis.functions.contains_key(&function_start).then(|| {
+        analysis
+            .cfg_nodes
+            .get(&function_start)
+            .map(|node| node.label.clone())
+            .filter(|label| !label.is_empty())
+            .unwrap_or_else(|| format!("function_{}", function_start))
+    });
+
+    for (pc, insn) in analysis.instructions[range.clone()]
+        .iter()
+        .enumerate()
+        .map(|(i, insn)| (range.start + i, insn))
+    {
+        match format {
+            DisassFormat::Solazy => {
+                analysis.disassemble_label(
+                    &mut output,
+                    first && pc == range.start,
+                    insn.ptr,
+                    &mut last_basic_block,
+                )?;
+                for line in user_annotations.lines(insn.ptr) {
+                    writeln!(output, "    // {}", line)?;
+                }
+            }
+            DisassFormat::Objdump => {
+                if let Some(label) = function_label.as_ref().filter(|_| pc == range.start) {
+                    writeln!(output, "\n{:016x} <{}>:", text.address_of(insn.ptr), label)?;
+                }
+            }
+            DisassFormat::Json => {}
+        }
 
         // Track immediate data from LD_DW_IMM instructions that point to .rodata section.
         if insn.opc == ebpf::LD_DW_IMM {
             let addr = insn.imm as u64;
 
             if is_rodata_address(addr, sbpf_version) {
-                if let Some(ref mut imm_tracker) = imm_tracker_wrapped {
-                    imm_tracker.register_offset(addr as usize)
-                }
+                rodata_loads.push(addr as usize);
             }
         }
 
-        // next instruction lookup to gather information (like for string and their length when it uses MOV64_IMM)
+        let AnnotatedInstruction {
+            asm,
+            line: insn_line,
+            syscall_signature,
+            error_name,
+            string: str_repr,
+        } = annotate_instruction(
+            program,
+            analysis,
+            pc,
+            resolve_strings,
+            error_codes,
+            &mut reg_tracker,
+            sbpf_version,
+        );
+
         let next_insn = analysis.instructions.get(pc + 1);
-        let mut insn_line = analysis.disassemble_instruction(insn, pc);
-
-        // `disassemble_instruction` provides a human string after the assembly instruction for most
-        // instructions, but not syscalls. Here we add a string in the same position to show which
-        // registers individual syscalls are reading.
-        if insn_line.starts_with("syscall ") {
-            // parse the disassembled output instead of looking for the CALL_IMM opcode
-            // as complicated logic has already separated syscalls from regular calls
-            if let Some(syscall_name) = insn_line.strip_prefix("syscall ").map(|s| s.trim()) {
-                if let Some(signature) = get_syscall_signature(syscall_name) {
-                    insn_line = format!("{:<48}{}", format!("syscall {}", syscall_name), signature);
+        let slots = next_insn.map_or(1, |next| next.ptr - insn.ptr);
+        match format {
+            DisassFormat::Solazy => {}
+            DisassFormat::Objdump => {
+                writeln!(
+                    output,
+                    "{:>8x}:\t{}\t{}",
+                    text.address_of(insn.ptr),
+                    spaced_hex(text.bytes_of(insn.ptr, slots)),
+                    asm.trim_end()
+                )?;
+                continue;
+            }
+            DisassFormat::Json => {
+                let mut annotations = serde_json::Map::new();
+                if let Some(label) = &function_label {
+                    annotations.insert("function".to_string(), label.as_str().into());
+                }
+                if let Some(node) = analysis.cfg_nodes.get(&insn.ptr) {
+                    annotations.insert("label".to_string(), node.label.as_str().into());
+                }
+                if !str_repr.is_empty() {
+                    annotations.insert("string".to_string(), str_repr.into());
+                }
+                if let Some(error_name) = error_name {
+                    annotations.insert("error_code".to_string(), error_name.into());
                 }
+                if let Some(comment) = user_annotations.comment(insn.ptr) {
+                    annotations.insert("comment".to_string(), comment.into());
+                }
+                if let Some(bookmark) = user_annotations.bookmark(insn.ptr) {
+                    annotations.insert(
+                        "bookmark".to_string(),
+                        json!({ "name": bookmark.name, "note": bookmark.note }),
+                    );
+                }
+                let rust_eq = syscall_signature.or_else(|| translate_to_rust(insn, sbpf_version));
+                if let Some(rust_eq) = rust_eq.filter(|_| rusteq != RustEqMode::Hidden) {
+                    annotations.insert("rust".to_string(), rust_eq.into());
+                }
+                let (mnemonic, operands) = asm.split_once(' ').unwrap_or((asm.as_str(), ""));
+                let record = json!({
+                    "pc": insn.ptr,
+                    "address": text.address_of(insn.ptr),
+                    "bytes": hex::encode(text.bytes_of(insn.ptr, slots)),
+                    "mnemonic": mnemonic,
+                    "operands": operands.trim(),
+                    "annotations": annotations,
+                });
+                if !output.is_empty() {
+                    writeln!(output, ",")?;
+                }
+                write!(output, "  {}", record)?;
+                continue;
             }
         }
 
-        // append immediate string representation if available
-        let str_repr = reg_tracker_wrapped.as_mut().map_or_else(
-            || String::new(),
-            |reg_tracker| {
-                update_string_resolution(program, insn, next_insn, reg_tracker, sbpf_version)
-            },
-        );
+        // raw bytes of the instruction, the second slot of `lddw` on a line of its own
+        let (bytes, continuation) = if show_bytes {
+            let bytes = text.bytes_of(insn.ptr, slots);
+            let (first, second) = bytes.split_at(bytes.len().min(ebpf::INSN_SIZE));
+            (
+                format!("{:<23}  ", spaced_hex(first)),
+                (!second.is_empty()).then(|| spaced_hex(second)),
+            )
+        } else {
+            (String::new(), None)
+        };
 
-        if !str_repr.is_empty() {
-            insn_line.push_str(" --> ");
-            insn_line.push_str(&str_repr);
-            if insn_line.len() > 2 * (MAX_BYTES_USED_TO_READ_FOR_IMMEDIATE_STRING_REPR as usize) + 1
-            {
-                insn_line.truncate(2 * (MAX_BYTES_USED_TO_READ_FOR_IMMEDIATE_STRING_REPR as usize));
-                insn_line = format!("{insn_line}…");
+        // add rust equivalence repr
+        let rust_eq = match syscall_signature {
+            // already written next to the syscall
+            Some(_) if rusteq == RustEqMode::Column => None,
+            Some(signature) => Some(signature),
+            None => translate_to_rust(insn, sbpf_version),
+        };
+        match (rusteq, rust_eq) {
+            (RustEqMode::Column, Some(rust_eq)) => {
+                let to_write = format!("{:<40}        {}", insn_line, rust_eq);
+                writeln!(output, "    {}{}", bytes, to_write)?;
+            }
+            (RustEqMode::Only, Some(rust_eq)) => {
+                writeln!(
+                    output,
+                    "    {:<8}{}{}",
+                    format!("{}:", insn.ptr),
+                    bytes,
+                    rust_eq
+                )?;
+            }
+            (RustEqMode::Only, None) => {
+                writeln!(
+                    output,
+                    "    {:<8}{}// {}",
+                    format!("{}:", insn.ptr),
+                    bytes,
+                    insn_line
+                )?;
+            }
+            _ => {
+                writeln!(output, "    {}{}", bytes, insn_line)?;
+            }
+        }
+        if let Some(continuation) = continuation {
+            let indent = if rusteq == RustEqMode::Only { 8 } else { 0 };
+            writeln!(output, "    {:indent$}{}", "", continuation)?;
+        }
+    }
+    Ok((output, rodata_loads))
+}
+
+/// An instruction of the disassembly, with what is known about it.
+pub(crate) struct AnnotatedInstruction<'e> {
+    /// The assembly, as given by `Analysis::disassemble_instruction`.
+    pub asm: String,
+    /// The assembly followed by the signature of the syscall, the error code and the string
+    /// loaded, as written in the `solazy` listing.
+    pub line: String,
+    pub syscall_signature: Option<String>,
+    pub error_name: Option<&'e str>,
+    /// The string loaded by the instruction, empty if none.
+    pub string: String,
+}
+
+/// Disassembles the instruction at `pc` (index in `analysis.instructions`) with its annotations,
+/// `reg_tracker` following the registers of the function from one instruction to the next.
+pub(crate) fn annotate_instruction<'e>(
+    program: &[u8],
+    analysis: &Analysis,
+    pc: usize,
+    resolve_strings: bool,
+    error_codes: &'e ErrorCodeTable,
+    reg_tracker: &mut RegisterTracker,
+    sbpf_version: SBPFVersion,
+) -> AnnotatedInstruction<'e> {
+    let insn = &analysis.instructions[pc];
+    // next instruction lookup to gather information (like for string and their length when it uses MOV64_IMM)
+    let next_insn = analysis.instructions.get(pc + 1);
+    let mut insn_line = disassemble_instruction(analysis, insn, pc);
+    let asm = insn_line.clone();
+    // `disassemble_instruction` provides a human string after the assembly instruction for most
+    // instructions, but not syscalls. Here we add a string in the same position to show which
+    // registers individual syscalls are reading.
+    // The signature is the Rust equivalent of the syscall, better than the generic call one.
+    let mut syscall_signature = None;
+    if insn_line.starts_with("syscall ") {
+        // parse the disassembled output instead of looking for the CALL_IMM opcode
+        // as complicated logic has already separated syscalls from regular calls
+        if let Some(syscall_name) = insn_line.strip_prefix("syscall ").map(|s| s.trim()) {
+            if let Some(signature) = get_syscall_signature(syscall_name) {
+                insn_line = format!("{:<48}{}", format!("syscall {}", syscall_name), signature);
+                syscall_signature = Some(signature.to_string());
             }
         }
+    }
 
-        // add rust equivalence repr
-        if let Some(rust_eq) = translate_to_rust(insn, sbpf_version) {
-            let to_write = format!("{:<40}        {}", insn_line, rust_eq);
-            writeln!(output, "    {}", to_write)?;
-        } else {
-            writeln!(output, "    {}", insn_line)?;
+    // name the error codes moved, stored or compared (e.g. `mov64 r0, 2006 ; ConstraintSeeds`)
+    let error_name = error_codes.annotate(insn, sbpf_version);
+    if let Some(error_name) = error_name {
+        insn_line.push_str(" ; ");
+        insn_line.push_str(error_name);
+    }
+
+    // append immediate string representation if available
+    let str_repr = if resolve_strings {
+        update_string_resolution(program, insn, next_insn, reg_tracker, sbpf_version)
+    } else {
+        String::new()
+    };
+
+    if !str_repr.is_empty() {
+        insn_line.push_str(" --> ");
+        insn_line.push_str(&str_repr);
+        if insn_line.len() > 2 * (MAX_BYTES_USED_TO_READ_FOR_IMMEDIATE_STRING_REPR as usize) + 1 {
+            truncate_on_char_boundary(
+                &mut insn_line,
+                2 * (MAX_BYTES_USED_TO_READ_FOR_IMMEDIATE_STRING_REPR as usize),
+            );
+            insn_line = format!("{insn_line}…");
         }
     }
-    Ok(())
+
+    AnnotatedInstruction {
+        asm,
+        line: insn_line,
+        syscall_signature,
+        error_name,
+        string: str_repr,
+    }
+}
+
+/// Formats bytes as space-separated hexadecimal pairs, like `objdump`.
+fn spaced_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 /// Wrapper function that performs disassembly and optionally generates an immediate data table.
 ///
-/// The disassembly output is written to its output file. If an `ImmediateTracker` is provided,
+/// The disassembly output is written to its output file, and, unless the Rust equivalent is
+/// hidden, the Rust equivalent of each function to `rusteq/`. If an `ImmediateTracker` is provided,
 /// an other file is also created, listing readable representations of tracked immediate byte slices.
 ///
 /// # Arguments
 ///
 /// * `program` - The raw bytecode of the SBPF program.
 /// * `analysis` - The static analysis object containing instructions and metadata.
+/// * `text` - The `.text` section, for the addresses and bytes of the instructions.
 /// * `imm_tracker_wrapped` - Optional mutable reference to an `ImmediateTracker` for tracking.
 /// * `sbpf_version` - The SBPF version from the executable.
-/// * `path` - Base path for writing output files (`disassembly.out`, `immediate_data_table.out`).
+/// * `options` - The format of the listing (`disassembly.out` or `disassembly.json`) and what
+///   it holds.
+/// * `path` - Base path for writing output files (`disassembly.out`, `immediate_data_table.out`,
+///   `rusteq/`).
 ///
 /// # Returns
 ///
@@ -138,19 +607,25 @@ fn disassemble<P: AsRef<Path>>(
 pub fn disassemble_wrapper<P: AsRef<Path>>(
     program: &[u8],
     analysis: &mut Analysis,
+    text: TextSection,
     mut imm_tracker_wrapped: Option<&mut ImmediateTracker>,
-    mut reg_tracker_wrapped: Option<&mut RegisterTracker>,
     sbpf_version: SBPFVersion,
+    options: &DisassOptions,
     path: P,
 ) -> std::io::Result<()> {
     disassemble(
         program,
         analysis,
+        text,
         imm_tracker_wrapped.as_deref_mut(),
-        reg_tracker_wrapped.as_deref_mut(),
         sbpf_version,
+        options,
         &path,
     )?;
+    if options.rusteq != RustEqMode::Hidden {
+        let functions = export_rusteq_functions(analysis, sbpf_version, options.selection, &path)?;
+        debug!("Rust equivalent of {} functions exported", functions);
+    }
     debug!("Tracking Immediates...");
 
     let spinner = helpers::spinner::get_new_spinner(String::from("Performing binary analysis..."));
@@ -189,3 +664,32 @@ pub fn disassemble_wrapper<P: AsRef<Path>>(
     spinner.finish_using_style();
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reverse::new_loader;
+    use solana_sbpf::elf::Executable;
+    use test_utils::TestContextObject;
+
+    #[test]
+    fn test_function_instruction_ranges() {
+        let elf =
+            std::fs::read("test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so")
+                .unwrap();
+        let executable =
+            Executable::<TestContextObject>::from_elf(&elf, new_loader(true).unwrap()).unwrap();
+        let analysis = Analysis::from_executable(&executable).unwrap();
+        let ranges = function_instruction_ranges(&analysis);
+
+        assert_eq!(ranges.len(), analysis.functions.len());
+        assert_eq!(ranges.first().unwrap().start, 0);
+        assert_eq!(ranges.last().unwrap().end, analysis.instructions.len());
+        for (range, next) in ranges.iter().zip(ranges.iter().skip(1)) {
+            assert_eq!(range.end, next.start);
+            assert!(analysis
+                .functions
+                .contains_key(&analysis.instructions[next.start].ptr));
+        }
+    }
+}
@@ -0,0 +1,491 @@
+//! Recognition of instruction dispatch patterns reachable from the entrypoint.
+//!
+//! Both Anchor and native programs usually route an incoming instruction to its handler
+//! through a chain of comparisons on a selector (an 8-byte Anchor discriminator or a small
+//! native tag). This module walks the functions reachable from the entrypoint, finds the
+//! longest such chain and names its targets `handler_0`, `handler_1`, ... or, when an IDL
+//! is supplied, after the matching Anchor instructions.
+
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use solana_sbpf::{ebpf, program::SBPFVersion, static_analysis::Analysis};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::recap::idl::load_idl;
+use crate::reverse::function_filter::FunctionSelection;
+use crate::reverse::signatures::function_hash;
+use crate::reverse::stable_ids::hash_id;
+use crate::reverse::OutputFile;
+
+/// How many call levels below the entrypoint are searched for a dispatcher
+/// (Anchor goes `entrypoint` -> `entry` -> `dispatch`).
+const MAX_DISPATCH_SEARCH_DEPTH: usize = 4;
+
+/// A dispatch chain needs at least this many distinct selector values to be reported.
+const MIN_DISPATCH_TARGETS: usize = 2;
+
+/// A single target of a recognized dispatch chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DispatchTarget {
+    /// Position of the target in the chain, in comparison order.
+    pub index: usize,
+    /// Selector value compared against (discriminator or instruction tag).
+    pub selector_value: u64,
+    /// Instruction pointer of the basic block reached when the comparison matches.
+    pub target_pc: usize,
+    /// Instruction pointer of the handler: the function called right away by the
+    /// target block if any, the target block itself otherwise.
+    pub handler_pc: usize,
+    /// Name given to the handler (`handler_N` or the IDL instruction name).
+    pub name: String,
+}
+
+/// A dispatch chain recognized in one function reachable from the entrypoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DispatchTable {
+    /// Start of the function holding the comparison chain.
+    pub dispatcher_pc: usize,
+    /// Register holding the selector value.
+    pub selector_reg: u8,
+    /// Dispatch targets, in comparison order.
+    pub targets: Vec<DispatchTarget>,
+}
+
+/// Computes the Anchor instruction discriminator of `name` as the little-endian `u64`
+/// it is compared against in the bytecode (`sha256("global:<snake_case_name>")[..8]`).
+pub fn anchor_instruction_discriminator(name: &str) -> u64 {
+    let preimage = format!("global:{}", to_snake_case(name));
+    let hash = Sha256::digest(preimage.as_bytes());
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&hash[..8]);
+    u64::from_le_bytes(bytes)
+}
+
+/// Converts IDL instruction names (camelCase before Anchor 0.30) to the snake_case
+/// form used when hashing discriminators.
+fn to_snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i != 0 {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Loads an Anchor IDL and maps each instruction discriminator to its instruction name.
+///
+/// Explicit `discriminator` arrays (Anchor >= 0.30) are preferred, otherwise the
+/// discriminator is derived from the instruction name.
+pub fn load_idl_discriminators(idl_path: &Path) -> Result<HashMap<u64, String>> {
+    let idl = load_idl(idl_path)?;
+    Ok(idl
+        .instructions
+        .iter()
+        .map(|ix| {
+            let disc = match ix.discriminator.as_deref() {
+                Some(bytes) if bytes.len() == 8 => {
+                    let mut raw = [0u8; 8];
+                    raw.copy_from_slice(bytes);
+                    u64::from_le_bytes(raw)
+                }
+                _ => anchor_instruction_discriminator(&ix.name),
+            };
+            (disc, ix.name.clone())
+        })
+        .collect())
+}
+
+/// Returns the index in `analysis.instructions` of the instruction located at `ptr`.
+fn insn_index_at(analysis: &Analysis, ptr: usize) -> Option<usize> {
+    analysis
+        .instructions
+        .binary_search_by_key(&ptr, |insn| insn.ptr)
+        .ok()
+}
+
+/// Resolves the function started by an internal `call` instruction, if any.
+/// Syscalls are not registered as functions and therefore resolve to `None`.
+pub(crate) fn resolve_internal_call(
+    analysis: &Analysis,
+    insn: &ebpf::Insn,
+    sbpf_version: SBPFVersion,
+) -> Option<usize> {
+    if insn.opc != ebpf::CALL_IMM {
+        return None;
+    }
+    let key = sbpf_version.calculate_call_imm_target_pc(insn.ptr, insn.imm);
+    analysis
+        .functions
+        .iter()
+        .find(|(_, (function_key, _))| *function_key == key)
+        .map(|(pc, _)| *pc)
+}
+
+/// Returns the `[start, end)` range of instruction pointers covered by the function at `start`.
+pub(crate) fn function_range(analysis: &Analysis, start: usize) -> std::ops::Range<usize> {
+    let end = analysis
+        .functions
+        .range(start + 1..)
+        .next()
+        .map(|(pc, _)| *pc)
+        .unwrap_or_else(|| analysis.instructions.last().map_or(start, |i| i.ptr + 1));
+    start..end
+}
+
+/// Lists the functions reachable from the entrypoint through direct calls, breadth first.
+fn functions_reachable_from_entrypoint(
+    analysis: &Analysis,
+    sbpf_version: SBPFVersion,
+) -> Vec<usize> {
+    functions_reachable_from(
+        analysis,
+        analysis.entrypoint,
+        MAX_DISPATCH_SEARCH_DEPTH,
+        sbpf_version,
+    )
+}
+
+/// Lists the functions reachable from the function at `start` within `max_depth` direct
+/// calls, breadth first (`start` included).
+pub(crate) fn functions_reachable_from(
+    analysis: &Analysis,
+    start: usize,
+    max_depth: usize,
+    sbpf_version: SBPFVersion,
+) -> Vec<usize> {
+    let mut order = Vec::new();
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::from([(start, 0usize)]);
+
+    while let Some((function_start, depth)) = queue.pop_front() {
+        if !seen.insert(function_start) {
+            continue;
+        }
+        order.push(function_start);
+        if depth >= max_depth {
+            continue;
+        }
+        let range = function_range(analysis, function_start);
+        for insn in analysis
+            .instructions
+            .iter()
+            .filter(|insn| range.contains(&insn.ptr))
+        {
+            if let Some(callee) = resolve_internal_call(analysis, insn, sbpf_version) {
+                queue.push_back((callee, depth + 1));
+            }
+        }
+    }
+    order
+}
+
+/// Comparison of a register against a known constant, collected while scanning a function.
+struct SelectorComparison {
+    selector_reg: u8,
+    value: u64,
+    target_pc: usize,
+}
+
+/// Scans one function for `jeq`/`jne` comparisons of a register against a constant,
+/// either an immediate or a register loaded with `lddw`/`mov` earlier in the same block.
+fn collect_selector_comparisons(
+    analysis: &Analysis,
+    function_start: usize,
+) -> Vec<SelectorComparison> {
+    let range = function_range(analysis, function_start);
+    let mut comparisons = Vec::new();
+    let mut constants: HashMap<u8, u64> = HashMap::new();
+
+    for insn in analysis
+        .instructions
+        .iter()
+        .filter(|insn| range.contains(&insn.ptr))
+    {
+        if analysis.cfg_nodes.contains_key(&insn.ptr) {
+            // constants are only tracked within a basic block
+            constants.clear();
+        }
+
+        let jump_target = (insn.ptr as isize + insn.off as isize + 1) as usize;
+        let fallthrough = insn.ptr + 1;
+
+        let comparison = match insn.opc {
+            ebpf::JEQ64_IMM | ebpf::JEQ32_IMM => Some((insn.dst, insn.imm as u64, jump_target)),
+            ebpf::JNE64_IMM | ebpf::JNE32_IMM => Some((insn.dst, insn.imm as u64, fallthrough)),
+            ebpf::JEQ64_REG | ebpf::JEQ32_REG | ebpf::JNE64_REG | ebpf::JNE32_REG => {
+                let target = if matches!(insn.opc, ebpf::JEQ64_REG | ebpf::JEQ32_REG) {
+                    jump_target
+                } else {
+                    fallthrough
+                };
+                match (constants.get(&insn.src), constants.get(&insn.dst)) {
+                    (Some(value), None) => Some((insn.dst, *value, target)),
+                    (None, Some(value)) => Some((insn.src, *value, target)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        if let Some((selector_reg, value, target_pc)) = comparison {
+            comparisons.push(SelectorComparison {
+                selector_reg,
+                value,
+                target_pc,
+            });
+            continue;
+        }
+
+        match insn.opc {
+            ebpf::LD_DW_IMM | ebpf::MOV64_IMM => {
+                constants.insert(insn.dst, insn.imm as u64);
+            }
+            ebpf::MOV32_IMM => {
+                constants.insert(insn.dst, insn.imm as u32 as u64);
+            }
+            ebpf::CALL_IMM | ebpf::CALL_REG => {
+                // r0 to r5 are clobbered by calls
+                constants.retain(|reg, _| *reg > 5);
+            }
+            _ => {
+                // anything else in a register-writing class invalidates the destination
+                if !matches!(insn.opc & 0x07, ebpf::BPF_JMP | ebpf::BPF_ST | ebpf::BPF_STX) {
+                    constants.remove(&insn.dst);
+                }
+            }
+        }
+    }
+    comparisons
+}
+
+/// Resolves the handler reached from `target_pc`: the first internal function called in the
+/// target basic block, or the block itself when it does not call anything.
+fn resolve_handler(analysis: &Analysis, target_pc: usize, sbpf_version: SBPFVersion) -> usize {
+    let Some(first) = insn_index_at(analysis, target_pc) else {
+        return target_pc;
+    };
+    for insn in &analysis.instructions[first..] {
+        if insn.ptr != target_pc && analysis.cfg_nodes.contains_key(&insn.ptr) {
+            break;
+        }
+        if let Some(callee) = resolve_internal_call(analysis, insn, sbpf_version) {
+            return callee;
+        }
+    }
+    target_pc
+}
+
+/// Tells a dispatch chain apart from other `match`-like chains (e.g. error code conversions):
+/// either every selector is a full 8-byte Anchor discriminator, or at least half of the
+/// targets call into a function right away.
+fn looks_like_dispatch(
+    analysis: &Analysis,
+    sbpf_version: SBPFVersion,
+    comparisons: &[SelectorComparison],
+) -> bool {
+    if comparisons.len() < MIN_DISPATCH_TARGETS {
+        return false;
+    }
+    if comparisons.iter().all(|c| c.value > u32::MAX as u64) {
+        return true;
+    }
+    let calling_targets = comparisons
+        .iter()
+        .filter(|c| resolve_handler(analysis, c.target_pc, sbpf_version) != c.target_pc)
+        .count();
+    calling_targets * 2 >= comparisons.len()
+}
+
+/// Searches the functions reachable from the entrypoint for the longest comparison-based
+/// dispatch chain and names its targets.
+///
+/// # Arguments
+///
+/// * `analysis` - The static analysis of the program.
+/// * `sbpf_version` - The SBPF version from the executable, used to resolve call targets.
+/// * `idl_names` - Optional discriminator -> instruction name map (see [`load_idl_discriminators`]).
+///
+/// # Returns
+///
+/// The recognized `DispatchTable`, or `None` if no chain with at least two distinct
+/// selector values was found.
+pub fn find_dispatch_table(
+    analysis: &Analysis,
+    sbpf_version: SBPFVersion,
+    idl_names: Option<&HashMap<u64, String>>,
+) -> Option<DispatchTable> {
+    let mut best: Option<(usize, u8, Vec<SelectorComparison>)> = None;
+
+    for function_start in functions_reachable_from_entrypoint(analysis, sbpf_version) {
+        let mut by_register: BTreeMap<u8, Vec<SelectorComparison>> = BTreeMap::new();
+        for comparison in collect_selector_comparisons(analysis, function_start) {
+            by_register
+                .entry(comparison.selector_reg)
+                .or_default()
+                .push(comparison);
+        }
+
+        for (selector_reg, mut comparisons) in by_register {
+            let mut values = HashSet::new();
+            comparisons.retain(|c| values.insert(c.value));
+            if !looks_like_dispatch(analysis, sbpf_version, &comparisons) {
+                continue;
+            }
+            let is_better = best
+                .as_ref()
+                .is_none_or(|(_, _, current)| comparisons.len() > current.len());
+            if is_better {
+                best = Some((function_start, selector_reg, comparisons));
+            }
+        }
+    }
+
+    let (dispatcher_pc, selector_reg, comparisons) = best?;
+    let targets = comparisons
+        .into_iter()
+        .enumerate()
+        .map(|(index, comparison)| DispatchTarget {
+            index,
+            selector_value: comparison.value,
+            target_pc: comparison.target_pc,
+            handler_pc: resolve_handler(analysis, comparison.target_pc, sbpf_version),
+            name: idl_names
+                .and_then(|names| names.get(&comparison.value).cloned())
+                .unwrap_or_else(|| format!("handler_{}", index)),
+        })
+        .collect();
+
+    Some(DispatchTable {
+        dispatcher_pc,
+        selector_reg,
+        targets,
+    })
+}
+
+/// Renames the handlers of a dispatch table in the analysis, so that the new names show up
+/// in the disassembly labels, in jump/call operands and in the CFG cluster labels.
+///
+/// Only generic labels (`function_N`, `lbb_N`) are replaced; symbols recovered with
+/// `--labeling` are kept as they already carry a meaningful name.
+pub fn apply_dispatch_names(analysis: &mut Analysis, table: &DispatchTable) {
+    for target in &table.targets {
+        if target.handler_pc == analysis.entrypoint {
+            continue;
+        }
+        if let Some(cfg_node) = analysis.cfg_nodes.get_mut(&target.handler_pc) {
+            if cfg_node.label.starts_with("function_") || cfg_node.label.starts_with("lbb_") {
+                cfg_node.label = target.name.clone();
+                if let Some((_, name)) = analysis.functions.get_mut(&target.handler_pc) {
+                    *name = target.name.clone();
+                }
+            }
+        }
+    }
+}
+
+/// Writes the function summary (`functions.out`): every function with its address, size,
+/// hash (`f<hash prefix>`, see [`hash_id`]) and label, followed by the recognized
+/// dispatch table if any.
+///
+/// # Arguments
+///
+/// * `analysis` - The static analysis of the program.
+/// * `dispatch` - The dispatch table recognized by [`find_dispatch_table`], if any.
+/// * `selection` - If given, only these functions are listed.
+/// * `sbpf_version` - The SBPF version of the program, for the hashes.
+/// * `path` - Output directory.
+pub fn export_function_summary<P: AsRef<Path>>(
+    analysis: &Analysis,
+    dispatch: Option<&DispatchTable>,
+    selection: Option<&FunctionSelection>,
+    sbpf_version: SBPFVersion,
+    path: P,
+) -> std::io::Result<()> {
+    let mut summary_path = PathBuf::from(path.as_ref());
+    summary_path.push(OutputFile::FunctionSummary.default_filename());
+    let mut output = File::create(summary_path)?;
+
+    match selection {
+        Some(selection) => writeln!(
+            output,
+            "Functions ({} of {}, filtered):",
+            selection.len(),
+            analysis.functions.len()
+        )?,
+        None => writeln!(output, "Functions ({}):", analysis.functions.len())?,
+    }
+    for function_start in analysis
+        .functions
+        .keys()
+        .filter(|start| selection.is_none_or(|selection| selection.contains(**start)))
+    {
+        let range = function_range(analysis, *function_start);
+        let label = analysis
+            .cfg_nodes
+            .get(function_start)
+            .map_or("", |node| node.label.as_str());
+        let marker = if *function_start == analysis.entrypoint {
+            " (entrypoint)"
+        } else {
+            ""
+        };
+        let hash = function_hash(analysis, *function_start, sbpf_version).0;
+        writeln!(
+            output,
+            "    {:<10} {:>6} insns    {}    {}{}",
+            format!("lbb_{}", function_start),
+            range.len(),
+            hash_id(&hash),
+            label,
+            marker
+        )?;
+    }
+
+    match dispatch {
+        Some(table) => {
+            writeln!(
+                output,
+                "\nDispatch in lbb_{} on r{} ({} targets):",
+                table.dispatcher_pc,
+                table.selector_reg,
+                table.targets.len()
+            )?;
+            for target in &table.targets {
+                writeln!(
+                    output,
+                    "    {:<32} selector=0x{:016x}    target=lbb_{}    handler=lbb_{}",
+                    target.name, target.selector_value, target.target_pc, target.handler_pc
+                )?;
+            }
+        }
+        None => writeln!(output, "\nNo dispatch pattern recognized.")?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anchor_discriminator_matches_known_value() {
+        // sha256("global:initialize")[..8] = afaf6d1f0d989bed
+        assert_eq!(
+            anchor_instruction_discriminator("initialize").to_le_bytes(),
+            [0xaf, 0xaf, 0x6d, 0x1f, 0x0d, 0x98, 0x9b, 0xed]
+        );
+        assert_eq!(
+            anchor_instruction_discriminator("updateConfig"),
+            anchor_instruction_discriminator("update_config")
+        );
+    }
+}
@@ -0,0 +1,281 @@
+//! Export of the dominance relationships of the CFG (`dominators.json`).
+//!
+//! For every basic block, the file gives its immediate dominator (as computed by the
+//! static `Analysis` and used by the CFG export), its dominance frontier and its immediate
+//! post-dominator. All of them are computed per function, on intra-function edges only.
+
+use serde::Serialize;
+use solana_sbpf::static_analysis::Analysis;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::reverse::OutputFile;
+
+/// Virtual node post-dominating every exit block of a function.
+const VIRTUAL_EXIT: usize = usize::MAX;
+
+/// Dominance information of a single basic block.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct BlockDominance {
+    /// Start of the basic block (`lbb_<block>`).
+    pub block: usize,
+    pub label: String,
+    /// Start of the function containing the block.
+    pub function: usize,
+    /// `None` for the function entry (and blocks unreachable from it).
+    pub immediate_dominator: Option<usize>,
+    pub dominance_frontier: Vec<usize>,
+    /// `None` for exit blocks (and blocks that never reach an exit, e.g. infinite loops).
+    pub immediate_post_dominator: Option<usize>,
+}
+
+/// Computes the dominance information of every basic block of the program, ordered by address.
+pub fn compute_dominance(analysis: &Analysis) -> Vec<BlockDominance> {
+    let mut functions: BTreeMap<usize, (std::ops::Range<usize>, Vec<usize>)> = BTreeMap::new();
+    for (function_range, cfg_node_start, _) in analysis.iter_cfg_by_function() {
+        // the super root is a virtual node linking all the functions
+        if !function_range.contains(&cfg_node_start) {
+            continue;
+        }
+        functions
+            .entry(function_range.start)
+            .or_insert_with(|| (function_range.clone(), Vec::new()))
+            .1
+            .push(cfg_node_start);
+    }
+
+    let mut blocks = Vec::new();
+    for (function_start, (function_range, nodes)) in functions {
+        let in_function = |pc: &usize| function_range.contains(pc);
+        let predecessors: HashMap<usize, Vec<usize>> = nodes
+            .iter()
+            .map(|node| {
+                let sources = analysis.cfg_nodes[node].sources.iter().copied().filter(in_function);
+                (*node, sources.collect())
+            })
+            .collect();
+        let successors: HashMap<usize, Vec<usize>> = nodes
+            .iter()
+            .map(|node| {
+                let destinations = analysis.cfg_nodes[node]
+                    .destinations
+                    .iter()
+                    .copied()
+                    .filter(in_function);
+                (*node, destinations.collect())
+            })
+            .collect();
+
+        let immediate_dominators: HashMap<usize, usize> = nodes
+            .iter()
+            .filter_map(|node| {
+                let parent = analysis.cfg_nodes[node].dominator_parent;
+                (parent != *node && in_function(&parent)).then_some((*node, parent))
+            })
+            .collect();
+        let frontiers = dominance_frontiers(&nodes, &predecessors, &immediate_dominators);
+        let post_dominators = immediate_post_dominators(&nodes, &predecessors, &successors);
+
+        for node in nodes {
+            blocks.push(BlockDominance {
+                block: node,
+                label: analysis.cfg_nodes[&node].label.clone(),
+                function: function_start,
+                immediate_dominator: immediate_dominators.get(&node).copied(),
+                dominance_frontier: frontiers
+                    .get(&node)
+                    .map(|frontier| frontier.iter().copied().collect())
+                    .unwrap_or_default(),
+                immediate_post_dominator: post_dominators
+                    .get(&node)
+                    .copied()
+                    .filter(|post_dominator| *post_dominator != VIRTUAL_EXIT),
+            });
+        }
+    }
+    blocks
+}
+
+/// Computes the dominance frontier of each node from the immediate dominators
+/// (Cooper, Harvey & Kennedy, "A Simple, Fast Dominance Algorithm").
+///
+/// Entry blocks (without immediate dominator) are join points as soon as they have a
+/// predecessor, since they are also reached from outside of the function.
+fn dominance_frontiers(
+    nodes: &[usize],
+    predecessors: &HashMap<usize, Vec<usize>>,
+    immediate_dominators: &HashMap<usize, usize>,
+) -> HashMap<usize, BTreeSet<usize>> {
+    let mut frontiers: HashMap<usize, BTreeSet<usize>> = HashMap::new();
+    for node in nodes {
+        let preds = &predecessors[node];
+        let idom = immediate_dominators.get(node);
+        if preds.len() < 2 && (idom.is_some() || preds.is_empty()) {
+            continue;
+        }
+        for pred in preds {
+            let mut runner = Some(*pred);
+            while let Some(current) = runner {
+                if Some(&current) == idom {
+                    break;
+                }
+                frontiers.entry(current).or_default().insert(*node);
+                runner = immediate_dominators.get(&current).copied();
+            }
+        }
+    }
+    frontiers
+}
+
+/// Computes the immediate post-dominator of each node, i.e. its immediate dominator in the
+/// reversed CFG rooted at a virtual exit reached from every block without successor.
+fn immediate_post_dominators(
+    nodes: &[usize],
+    predecessors: &HashMap<usize, Vec<usize>>,
+    successors: &HashMap<usize, Vec<usize>>,
+) -> HashMap<usize, usize> {
+    let exits: Vec<usize> = nodes
+        .iter()
+        .copied()
+        .filter(|node| successors[node].is_empty())
+        .collect();
+
+    // in the reversed graph, the successors of a node are its original predecessors
+    let reversed_successors = |node: usize| -> &[usize] {
+        if node == VIRTUAL_EXIT {
+            &exits
+        } else {
+            &predecessors[&node]
+        }
+    };
+    // ... and its predecessors are its original successors (plus the virtual exit for exits)
+    let reversed_predecessors = |node: usize| -> Vec<usize> {
+        let mut preds = successors[&node].clone();
+        if preds.is_empty() {
+            preds.push(VIRTUAL_EXIT);
+        }
+        preds
+    };
+
+    // iterative post-order of the reversed graph, from the virtual exit
+    let mut post_order = Vec::new();
+    let mut visited = HashSet::from([VIRTUAL_EXIT]);
+    let mut stack = vec![(VIRTUAL_EXIT, 0)];
+    while let Some((node, next_child)) = stack.pop() {
+        match reversed_successors(node).get(next_child) {
+            Some(child) => {
+                stack.push((node, next_child + 1));
+                if visited.insert(*child) {
+                    stack.push((*child, 0));
+                }
+            }
+            None => post_order.push(node),
+        }
+    }
+    let order_index: HashMap<usize, usize> = post_order
+        .iter()
+        .enumerate()
+        .map(|(index, node)| (*node, index))
+        .collect();
+
+    let mut idoms = HashMap::from([(VIRTUAL_EXIT, VIRTUAL_EXIT)]);
+    let intersect = |idoms: &HashMap<usize, usize>, mut a: usize, mut b: usize| -> usize {
+        while a != b {
+            while order_index[&a] < order_index[&b] {
+                a = idoms[&a];
+            }
+            while order_index[&b] < order_index[&a] {
+                b = idoms[&b];
+            }
+        }
+        a
+    };
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        // reverse post-order, skipping the root
+        for node in post_order.iter().rev().skip(1) {
+            let mut new_idom = None;
+            for pred in reversed_predecessors(*node) {
+                if !idoms.contains_key(&pred) {
+                    continue;
+                }
+                new_idom = Some(match new_idom {
+                    None => pred,
+                    Some(current) => intersect(&idoms, pred, current),
+                });
+            }
+            if let Some(new_idom) = new_idom {
+                if idoms.get(node) != Some(&new_idom) {
+                    idoms.insert(*node, new_idom);
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    idoms.remove(&VIRTUAL_EXIT);
+    idoms
+}
+
+/// Writes `dominators.json`: the dominance information of every basic block, ordered by address.
+///
+/// # Arguments
+///
+/// * `analysis` - The `Analysis` structure containing the CFG and its dominator tree.
+/// * `path` - Output directory.
+pub fn export_dominators<P: AsRef<Path>>(analysis: &Analysis, path: P) -> std::io::Result<()> {
+    let mut dominators_path = PathBuf::from(path.as_ref());
+    dominators_path.push(OutputFile::Dominators.default_filename());
+    let mut output = File::create(dominators_path)?;
+
+    let blocks = compute_dominance(analysis);
+    writeln!(
+        output,
+        "{}",
+        serde_json::to_string_pretty(&blocks).map_err(std::io::Error::other)?
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_post_dominators_and_frontiers() {
+        // 0 -> {1, 2}, 1 -> 3, 2 -> 3, 3 -> {0, 4}, 4 exits
+        let nodes = vec![0, 1, 2, 3, 4];
+        let successors: HashMap<usize, Vec<usize>> = HashMap::from([
+            (0, vec![1, 2]),
+            (1, vec![3]),
+            (2, vec![3]),
+            (3, vec![0, 4]),
+            (4, vec![]),
+        ]);
+        let predecessors: HashMap<usize, Vec<usize>> = HashMap::from([
+            (0, vec![3]),
+            (1, vec![0]),
+            (2, vec![0]),
+            (3, vec![1, 2]),
+            (4, vec![3]),
+        ]);
+        let immediate_dominators = HashMap::from([(1, 0), (2, 0), (3, 0), (4, 3)]);
+
+        let frontiers = dominance_frontiers(&nodes, &predecessors, &immediate_dominators);
+        assert_eq!(frontiers[&1], BTreeSet::from([3]));
+        assert_eq!(frontiers[&2], BTreeSet::from([3]));
+        assert_eq!(frontiers[&3], BTreeSet::from([0]));
+        assert_eq!(frontiers[&0], BTreeSet::from([0]));
+        assert!(!frontiers.contains_key(&4));
+
+        let post_dominators = immediate_post_dominators(&nodes, &predecessors, &successors);
+        assert_eq!(post_dominators[&0], 3);
+        assert_eq!(post_dominators[&1], 3);
+        assert_eq!(post_dominators[&2], 3);
+        assert_eq!(post_dominators[&3], 4);
+        assert_eq!(post_dominators[&4], VIRTUAL_EXIT);
+    }
+}
@@ -0,0 +1,179 @@
+//! Minimal reading of the section headers of a 64-bit little-endian ELF, independent of the
+//! loader, so that it also works on programs the loader rejects.
+
+use crate::reverse::sanitize::sanitize_lossy;
+
+pub const SHT_PROGBITS: u32 = 1;
+pub const SHT_SYMTAB: u32 = 2;
+pub const SHF_ALLOC: u64 = 0x2;
+pub const SHF_EXECINSTR: u64 = 0x4;
+#[cfg(feature = "cli")]
+pub const STT_FUNC: u8 = 2;
+
+/// Size of an `Elf64_Sym` entry.
+const SYMBOL_SIZE: usize = 24;
+
+/// A section header of the ELF.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElfSection {
+    pub name: String,
+    pub sh_type: u32,
+    pub flags: u64,
+    /// Virtual address of the section.
+    pub address: u64,
+    /// Offset of the section in the file.
+    pub offset: usize,
+    pub size: usize,
+}
+
+/// Lists the sections of a 64-bit little-endian ELF whose content lies within the file.
+///
+/// # Returns
+///
+/// The sections, or an empty vector if `elf` is not a well-formed ELF64.
+pub fn elf_sections(elf: &[u8]) -> Vec<ElfSection> {
+    let read = |at: usize, length: usize| elf.get(at..at.checked_add(length)?);
+    let read_u16 = |at: usize| read(at, 2).map(|b| u16::from_le_bytes([b[0], b[1]]) as usize);
+    let read_u32 = |at: usize| read(at, 4).map(|b| u32::from_le_bytes(b.try_into().unwrap()));
+    let read_u64 = |at: usize| read(at, 8).map(|b| u64::from_le_bytes(b.try_into().unwrap()));
+
+    // ELF64, little-endian
+    if elf.len() < 64 || &elf[..4] != b"\x7fELF" || elf[4] != 2 || elf[5] != 1 {
+        return Vec::new();
+    }
+    let (Some(shoff), Some(shentsize), Some(shnum), Some(shstrndx)) = (
+        read_u64(0x28),
+        read_u16(0x3a),
+        read_u16(0x3c),
+        read_u16(0x3e),
+    ) else {
+        return Vec::new();
+    };
+    // the offsets come from the file: a crafted one must not overflow
+    let header = |index: usize| {
+        usize::try_from(shoff)
+            .ok()?
+            .checked_add(index.checked_mul(shentsize)?)
+    };
+    let Some(names_offset) = header(shstrndx)
+        .and_then(|at| read_u64(at.checked_add(0x18)?))
+        .and_then(|offset| usize::try_from(offset).ok())
+    else {
+        return Vec::new();
+    };
+
+    (0..shnum)
+        .filter_map(|index| {
+            let at = header(index)?;
+            let name_offset = read_u32(at)? as usize;
+            let sh_type = read_u32(at.checked_add(0x4)?)?;
+            let flags = read_u64(at.checked_add(0x8)?)?;
+            let address = read_u64(at.checked_add(0x10)?)?;
+            let offset = usize::try_from(read_u64(at.checked_add(0x18)?)?).ok()?;
+            let size = usize::try_from(read_u64(at.checked_add(0x20)?)?).ok()?;
+            elf.get(offset..offset.checked_add(size)?)?;
+
+            let name_start = names_offset.checked_add(name_offset)?;
+            let name = elf.get(name_start..)?.split(|b| *b == 0).next()?;
+            Some(ElfSection {
+                name: sanitize_lossy(name),
+                sh_type,
+                flags,
+                address,
+                offset,
+                size,
+            })
+        })
+        .collect()
+}
+
+/// Returns `true` if the ELF has a symbol table (`.symtab`, with its `.strtab`) holding more
+/// than the null symbol, i.e. if it is not stripped.
+pub fn has_symbol_table(elf: &[u8]) -> bool {
+    let sections = elf_sections(elf);
+    let symtab = sections
+        .iter()
+        .any(|section| section.sh_type == SHT_SYMTAB && section.size > SYMBOL_SIZE);
+    symtab && sections.iter().any(|section| section.name == ".strtab")
+}
+
+/// Lists the names of the function symbols (`STT_FUNC`) of the symbol table, as written in the
+/// ELF (i.e. mangled).
+///
+/// # Returns
+///
+/// The names, or an empty vector if the ELF is stripped.
+#[cfg(feature = "cli")]
+pub fn function_symbols(elf: &[u8]) -> Vec<String> {
+    let sections = elf_sections(elf);
+    let (Some(symtab), Some(strtab)) = (
+        sections
+            .iter()
+            .find(|section| section.sh_type == SHT_SYMTAB),
+        sections.iter().find(|section| section.name == ".strtab"),
+    ) else {
+        return Vec::new();
+    };
+    let strings = &elf[strtab.offset..strtab.offset + strtab.size];
+
+    elf[symtab.offset..symtab.offset + symtab.size]
+        .chunks_exact(SYMBOL_SIZE)
+        .filter(|symbol| symbol[4] & 0xf == STT_FUNC)
+        .filter_map(|symbol| {
+            let name_offset = u32::from_le_bytes(symbol[..4].try_into().unwrap()) as usize;
+            let name = strings.get(name_offset..)?.split(|b| *b == 0).next()?;
+            (!name.is_empty()).then(|| sanitize_lossy(name))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_symbol_table() {
+        let stripped =
+            std::fs::read("test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so")
+                .unwrap();
+        let unstripped = std::fs::read(
+            "test_cases/base_sbf_addition_checker/bytecodes/addition_checker_sbpf_solana.so",
+        )
+        .unwrap();
+        assert!(!has_symbol_table(&stripped));
+        assert!(has_symbol_table(&unstripped));
+        assert!(elf_sections(&stripped)
+            .iter()
+            .any(|section| section.name == ".dynsym"));
+        assert!(!has_symbol_table(b"not an elf"));
+
+        let functions = function_symbols(&unstripped);
+        assert!(functions.iter().any(|name| name == "entrypoint"));
+        assert!(functions
+            .iter()
+            .any(|name| name.starts_with("_ZN14solana_program10entrypoint11deserialize")));
+        assert!(function_symbols(&stripped).is_empty());
+    }
+    /// Offsets overflowing the address space leave the sections out instead of panicking.
+    #[test]
+    fn test_overflowing_offsets() {
+        let elf =
+            std::fs::read("test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so")
+                .unwrap();
+        let sections = elf_sections(&elf);
+        assert!(!sections.is_empty());
+
+        let mut huge_shoff = elf.clone();
+        huge_shoff[0x28..0x30].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(elf_sections(&huge_shoff).is_empty());
+
+        // the names offset of `.shstrtab`, read from its own section header
+        let shoff = u64::from_le_bytes(elf[0x28..0x30].try_into().unwrap()) as usize;
+        let shentsize = u16::from_le_bytes([elf[0x3a], elf[0x3b]]) as usize;
+        let shstrndx = u16::from_le_bytes([elf[0x3e], elf[0x3f]]) as usize;
+        let names_offset = shoff + shstrndx * shentsize + 0x18;
+        let mut huge_names = elf.clone();
+        huge_names[names_offset..names_offset + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(elf_sections(&huge_names).is_empty());
+    }
+}
@@ -0,0 +1,277 @@
+//! Detection of high-entropy regions in the data sections of a program.
+//!
+//! The Shannon entropy of a sliding window is computed over every allocated, non-executable
+//! section of the ELF (`.rodata`, `.data.rel.ro`, ...). Windows above [`ENTROPY_THRESHOLD`]
+//! are merged into regions, which may be compressed or encrypted payloads, or embedded keys,
+//! and are listed in `program_info.out`.
+//!
+//! Strings, lookup tables and public keys lists stay well below the threshold, so a flagged
+//! region is mostly a hint that the program warrants deeper manual review.
+//!
+//! The same sections are searched for the 32-byte windows that may be public keys, which the
+//! fetcher resolves against the chain (see [`find_pubkey_candidates`]).
+
+#[cfg(feature = "cli")]
+use solana_program::pubkey::Pubkey;
+#[cfg(feature = "cli")]
+use std::collections::HashSet;
+
+use crate::reverse::elf_sections::{elf_sections, SHF_ALLOC, SHF_EXECINSTR, SHT_PROGBITS};
+
+/// Size in bytes of the sliding window.
+pub const ENTROPY_WINDOW: usize = 256;
+
+/// Step in bytes between two windows.
+pub const ENTROPY_STEP: usize = 64;
+
+/// Entropy (in bits per byte, out of 8) above which a window is flagged.
+pub const ENTROPY_THRESHOLD: f64 = 7.0;
+
+/// Minimum entropy of a 32-byte window to be taken for a public key (a random window is ~4.8).
+#[cfg(feature = "cli")]
+pub const PUBKEY_MIN_ENTROPY: f64 = 4.2;
+
+/// Maximum number of printable ASCII bytes of a window taken for a public key (~12 in a random
+/// window), leaving out the strings.
+#[cfg(feature = "cli")]
+const PUBKEY_MAX_PRINTABLE: usize = 24;
+
+/// A data section of the ELF.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataSection {
+    pub name: String,
+    /// Offset of the section in the file.
+    pub offset: usize,
+    /// Virtual address of the section.
+    pub address: u64,
+    pub size: usize,
+}
+
+/// A contiguous run of high-entropy windows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HighEntropyRegion {
+    pub section: String,
+    /// Offset of the region in the file.
+    pub offset: usize,
+    /// Virtual address of the region.
+    pub address: u64,
+    pub size: usize,
+    /// Highest entropy of the windows of the region.
+    pub max_entropy: f64,
+}
+
+/// Shannon entropy of `data`, in bits per byte.
+pub fn shannon_entropy(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+    let mut counts = [0usize; 256];
+    for byte in data {
+        counts[*byte as usize] += 1;
+    }
+    let len = data.len() as f64;
+    counts
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let p = *count as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Lists the allocated, non-executable `PROGBITS` sections of a 64-bit little-endian ELF.
+///
+/// # Returns
+///
+/// The data sections, or an empty vector if `elf` is not a well-formed ELF64.
+pub fn data_sections(elf: &[u8]) -> Vec<DataSection> {
+    elf_sections(elf)
+        .into_iter()
+        .filter(|section| {
+            section.sh_type == SHT_PROGBITS
+                && section.flags & SHF_ALLOC != 0
+                && section.flags & SHF_EXECINSTR == 0
+        })
+        .map(|section| DataSection {
+            name: section.name,
+            offset: section.offset,
+            address: section.address,
+            size: section.size,
+        })
+        .collect()
+}
+
+/// Finds the high-entropy regions of `data`, with offsets relative to `data`.
+///
+/// # Returns
+///
+/// `(offset, size, max_entropy)` for each run of overlapping windows above the threshold.
+fn high_entropy_runs(data: &[u8]) -> Vec<(usize, usize, f64)> {
+    let mut runs: Vec<(usize, usize, f64)> = Vec::new();
+    if data.len() < ENTROPY_WINDOW {
+        return runs;
+    }
+
+    let mut start = 0;
+    loop {
+        let entropy = shannon_entropy(&data[start..start + ENTROPY_WINDOW]);
+        if entropy >= ENTROPY_THRESHOLD {
+            match runs.last_mut() {
+                Some((offset, size, max_entropy)) if *offset + *size >= start => {
+                    *size = start + ENTROPY_WINDOW - *offset;
+                    *max_entropy = max_entropy.max(entropy);
+                }
+                _ => runs.push((start, ENTROPY_WINDOW, entropy)),
+            }
+        }
+
+        if start + ENTROPY_WINDOW == data.len() {
+            break;
+        }
+        // the last window is aligned on the end of the data
+        start = (start + ENTROPY_STEP).min(data.len() - ENTROPY_WINDOW);
+    }
+    runs
+}
+
+/// Finds the high-entropy regions in the data sections of the program.
+///
+/// # Arguments
+///
+/// * `program` - Raw bytes of the program (ELF).
+///
+/// # Returns
+///
+/// The flagged regions, ordered by section and offset.
+pub fn find_high_entropy_regions(program: &[u8]) -> Vec<HighEntropyRegion> {
+    data_sections(program)
+        .into_iter()
+        .flat_map(|section| {
+            let data = &program[section.offset..section.offset + section.size];
+            high_entropy_runs(data)
+                .into_iter()
+                .map(|(offset, size, max_entropy)| HighEntropyRegion {
+                    section: section.name.clone(),
+                    offset: section.offset + offset,
+                    address: section.address + offset as u64,
+                    size,
+                    max_entropy,
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// A 32-byte window of a data section that may hold a public key.
+#[cfg(feature = "cli")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PubkeyCandidate {
+    pub section: String,
+    /// Offset of the window in the file.
+    pub offset: usize,
+    pub pubkey: Pubkey,
+}
+
+/// Finds the offsets of the 32-byte windows of `data` that may be public keys: a high entropy,
+/// and not mostly printable.
+#[cfg(feature = "cli")]
+fn pubkey_windows(data: &[u8]) -> Vec<usize> {
+    data.windows(32)
+        .enumerate()
+        .filter(|(_, window)| {
+            window.iter().filter(|b| b.is_ascii_graphic()).count() <= PUBKEY_MAX_PRINTABLE
+                && shannon_entropy(window) >= PUBKEY_MIN_ENTROPY
+        })
+        .map(|(offset, _)| offset)
+        .collect()
+}
+
+/// Finds the 32-byte windows of the data sections that may be public keys, each key once.
+///
+/// The keys are not aligned, so the overlapping windows are all kept: only the windows
+/// holding a real key are expected to exist on-chain.
+#[cfg(feature = "cli")]
+pub fn find_pubkey_candidates(program: &[u8]) -> Vec<PubkeyCandidate> {
+    let mut seen = HashSet::new();
+    data_sections(program)
+        .into_iter()
+        .flat_map(|section| {
+            let data = &program[section.offset..section.offset + section.size];
+            pubkey_windows(data)
+                .into_iter()
+                .map(|offset| PubkeyCandidate {
+                    section: section.name.clone(),
+                    offset: section.offset + offset,
+                    pubkey: Pubkey::try_from(&data[offset..offset + 32]).unwrap(),
+                })
+                .collect::<Vec<_>>()
+        })
+        .filter(|candidate| seen.insert(candidate.pubkey))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_high_entropy_runs() {
+        assert_eq!(shannon_entropy(&[0u8; 64]), 0.0);
+        let all_bytes: Vec<u8> = (0..=255).collect();
+        assert!((shannon_entropy(&all_bytes) - 8.0).abs() < 1e-9);
+
+        // low-entropy text around a 1 KiB pseudo-random blob
+        let mut data = b"Instruction: Transfer ".repeat(40);
+        let blob_start = data.len();
+        let mut state: u32 = 0x1234_5678;
+        for _ in 0..1024 {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            data.push(state as u8);
+        }
+        data.extend(b"Program log: done ".repeat(40));
+
+        let runs = high_entropy_runs(&data);
+        assert_eq!(runs.len(), 1);
+        let (offset, size, max_entropy) = runs[0];
+        assert!(offset + ENTROPY_WINDOW > blob_start && offset < blob_start + ENTROPY_STEP);
+        assert!(size >= 1024 - ENTROPY_STEP && size <= 1024 + 2 * ENTROPY_WINDOW);
+        assert!(max_entropy >= ENTROPY_THRESHOLD);
+
+        assert!(high_entropy_runs(&b"Program log: ".repeat(100)).is_empty());
+    }
+
+    #[test]
+    fn test_data_sections() {
+        let elf =
+            std::fs::read("test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so")
+                .unwrap();
+        let sections = data_sections(&elf);
+        let rodata = sections
+            .iter()
+            .find(|section| section.name == ".rodata")
+            .expect(".rodata should be listed");
+        assert_eq!(rodata.offset, 0x43d0);
+        assert!(sections.iter().all(|section| section.name != ".text"));
+        assert!(find_high_entropy_regions(&elf).is_empty());
+
+        assert!(data_sections(b"not an elf").is_empty());
+    }
+
+    #[test]
+    fn test_pubkey_windows() {
+        use std::str::FromStr;
+
+        let key = Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap();
+        let mut data = vec![0u8; 40];
+        data.extend(key.to_bytes());
+        data.extend([0u8; 40]);
+        data.extend(b"ProgramLogInstructionTransferChecked");
+        let offsets = pubkey_windows(&data);
+        assert!(offsets.contains(&40));
+        // the windows overlapping the key and the zeros, but not the string
+        assert!(offsets.iter().all(|offset| (30..=50).contains(offset)));
+    }
+}
@@ -0,0 +1,132 @@
+//! Naming of the error codes returned through `ProgramError::Custom(N)`.
+//!
+//! The Anchor framework error codes are shipped in `src/static/error_codes/anchor.json`, and
+//! the program-specific ones (`#[error_code]`, from 6000) are read from the IDL given with
+//! `--idl`. The disassembly then annotates the instructions moving, storing or comparing
+//! one of these constants with its name, e.g. `mov64 r0, 2006 ; ConstraintSeeds`.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use solana_sbpf::{ebpf, ebpf::Insn, program::SBPFVersion};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::helpers::static_dir;
+use crate::recap::idl::load_idl;
+use crate::reverse::rusteq::value_immediate;
+
+/// Framework codes below this value (`InstructionMissing`, ...) are also common lengths and
+/// sizes, so they are only named when returned (moved into `r0`).
+const MIN_UNAMBIGUOUS_FRAMEWORK_CODE: u32 = 1000;
+
+#[derive(Debug, Deserialize)]
+struct ErrorCodeFile {
+    errors: Vec<ErrorCodeEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorCodeEntry {
+    code: u32,
+    name: String,
+}
+
+/// Known error codes and their names.
+#[derive(Debug, Default)]
+pub struct ErrorCodeTable {
+    framework: BTreeMap<u32, String>,
+    program: BTreeMap<u32, String>,
+}
+
+impl ErrorCodeTable {
+    /// Loads the Anchor framework error codes shipped with sol-azy.
+    pub fn anchor() -> Result<Self> {
+        let content = static_dir::read_file("error_codes/anchor.json")?;
+        let file: ErrorCodeFile =
+            serde_json::from_str(&content).context("Invalid Anchor error codes table")?;
+        Ok(Self {
+            framework: file
+                .errors
+                .into_iter()
+                .map(|entry| (entry.code, entry.name))
+                .collect(),
+            program: BTreeMap::new(),
+        })
+    }
+
+    /// Adds the program-specific error codes declared in an Anchor IDL.
+    ///
+    /// # Returns
+    ///
+    /// The number of error codes read from the IDL.
+    pub fn extend_from_idl(&mut self, idl_path: &Path) -> Result<usize> {
+        let idl = load_idl(idl_path)?;
+        let count = idl.errors.len();
+        self.program.extend(
+            idl.errors
+                .into_iter()
+                .filter_map(|error| Some((u32::try_from(error.code).ok()?, error.name))),
+        );
+        Ok(count)
+    }
+
+    /// Returns the name of an error code, program-specific codes first.
+    pub fn name(&self, code: u32) -> Option<&str> {
+        self.program
+            .get(&code)
+            .or_else(|| self.framework.get(&code))
+            .map(String::as_str)
+    }
+
+    /// Returns the name of the error code used as a value by `insn` (see
+    /// [`value_immediate`]), if any.
+    pub fn annotate(&self, insn: &Insn, sbpf_version: SBPFVersion) -> Option<&str> {
+        let code = u32::try_from(value_immediate(insn, sbpf_version)?).ok()?;
+        let name = self.name(code)?;
+        let returned = insn.dst == 0 && matches!(insn.opc, ebpf::MOV32_IMM | ebpf::MOV64_IMM);
+        (self.program.contains_key(&code) || code >= MIN_UNAMBIGUOUS_FRAMEWORK_CODE || returned)
+            .then_some(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anchor_error_codes() {
+        let mut table = ErrorCodeTable::anchor().unwrap();
+        assert_eq!(table.name(2006), Some("ConstraintSeeds"));
+        assert_eq!(table.name(3012), Some("AccountNotInitialized"));
+        table.program.insert(6000, "InvalidAmount".to_string());
+
+        let insn = |opc, dst, imm| Insn {
+            opc,
+            dst,
+            imm,
+            ..Insn::default()
+        };
+        let version = SBPFVersion::V0;
+        assert_eq!(
+            table.annotate(&insn(ebpf::MOV64_IMM, 0, 2006), version),
+            Some("ConstraintSeeds")
+        );
+        assert_eq!(
+            table.annotate(&insn(ebpf::JNE64_IMM, 3, 6000), version),
+            Some("InvalidAmount")
+        );
+        // not a value
+        assert_eq!(
+            table.annotate(&insn(ebpf::ADD64_IMM, 0, 2006), version),
+            None
+        );
+        // small framework codes only when returned
+        assert_eq!(
+            table.annotate(&insn(ebpf::MOV64_IMM, 0, 101), version),
+            Some("InstructionFallbackNotFound")
+        );
+        assert_eq!(
+            table.annotate(&insn(ebpf::MOV64_IMM, 2, 101), version),
+            None
+        );
+    }
+}
@@ -0,0 +1,224 @@
+//! Selection of the functions written to the reverse outputs.
+//!
+//! `--only-function`, `--skip-libs` and `--range` restrict the disassembly (with `rusteq/` and
+//! the immediate data table), the CFG and the function summary to the same functions. The
+//! whole-program outputs (statistics, dominators, PDA seeds, findings) are not filtered.
+//! The root of the reduced CFG (`--entry`) is resolved here too, from the same names and
+//! addresses.
+
+use anyhow::{anyhow, Result};
+use solana_sbpf::static_analysis::Analysis;
+use std::collections::BTreeSet;
+
+use crate::reverse::raw_input::parse_offset;
+use crate::reverse::signatures::SignatureMatch;
+
+/// Range of instruction pointers (`--range <start>-<end>`, both included).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PcRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl PcRange {
+    /// Parses `<start>-<end>`, each bound in decimal or `0x` hexadecimal.
+    pub fn parse(range: &str) -> Result<Self> {
+        let (start, end) = range
+            .split_once('-')
+            .ok_or_else(|| anyhow!("Invalid range '{}' (expected <start>-<end>)", range))?;
+        let range = PcRange {
+            start: parse_offset(start)?,
+            end: parse_offset(end)?,
+        };
+        if range.start > range.end {
+            return Err(anyhow!(
+                "Invalid range {}-{}: the start is after the end",
+                range.start,
+                range.end
+            ));
+        }
+        Ok(range)
+    }
+
+    fn contains(&self, pc: usize) -> bool {
+        (self.start..=self.end).contains(&pc)
+    }
+}
+
+/// Filters on the functions given on the command line, combined with an "and".
+#[derive(Debug, Clone, Default)]
+pub struct FunctionFilter {
+    /// Names or addresses (`42`, `0x2a`, `lbb_42`) of the only functions to keep.
+    pub only_functions: Vec<String>,
+    /// Drops the library functions identified by signature.
+    pub skip_libs: bool,
+    /// Keeps the functions starting in this range.
+    pub range: Option<PcRange>,
+}
+
+/// The functions kept by a [`FunctionFilter`], by start.
+#[derive(Debug, Clone, Default)]
+pub struct FunctionSelection {
+    starts: BTreeSet<usize>,
+}
+
+impl FunctionFilter {
+    pub fn is_empty(&self) -> bool {
+        self.only_functions.is_empty() && !self.skip_libs && self.range.is_none()
+    }
+
+    /// Resolves the filter on the program, `None` when no filter is set.
+    ///
+    /// # Errors
+    ///
+    /// If an `--only-function` matches no function of the program.
+    pub fn select(
+        &self,
+        analysis: &Analysis,
+        library_functions: &[SignatureMatch],
+    ) -> Result<Option<FunctionSelection>> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        let mut starts: BTreeSet<usize> = analysis.functions.keys().copied().collect();
+        if !self.only_functions.is_empty() {
+            let mut requested = BTreeSet::new();
+            for function in &self.only_functions {
+                let found = resolve_function(analysis, function);
+                if found.is_empty() {
+                    return Err(anyhow!("No function matches '{}'", function));
+                }
+                requested.extend(found);
+            }
+            starts.retain(|start| requested.contains(start));
+        }
+        if self.skip_libs {
+            for library_function in library_functions {
+                starts.remove(&library_function.function_pc);
+            }
+        }
+        if let Some(range) = self.range {
+            starts.retain(|start| range.contains(*start));
+        }
+        Ok(Some(FunctionSelection { starts }))
+    }
+}
+
+/// Returns the starts of the functions named `function`, or the one containing the address.
+fn resolve_function(analysis: &Analysis, function: &str) -> Vec<usize> {
+    let function = function.trim();
+    let named: Vec<usize> = analysis
+        .functions
+        .iter()
+        .filter(|(start, (_, name))| {
+            name == function
+                || analysis
+                    .cfg_nodes
+                    .get(start)
+                    .is_some_and(|node| node.label == function)
+        })
+        .map(|(start, _)| *start)
+        .collect();
+    if !named.is_empty() {
+        return named;
+    }
+    let address = ["lbb_", "function_", "cluster_"]
+        .iter()
+        .find_map(|prefix| function.strip_prefix(prefix))
+        .unwrap_or(function);
+    parse_offset(address)
+        .ok()
+        .and_then(|pc| analysis.functions.range(..=pc).next_back())
+        .map(|(start, _)| vec![*start])
+        .unwrap_or_default()
+}
+
+/// Resolves `--entry`: the start of the function or the block named `entry`, or of the block
+/// containing the address.
+///
+/// # Errors
+///
+/// If `entry` matches no block, or names several functions.
+pub fn resolve_entry(analysis: &Analysis, entry: &str) -> Result<usize> {
+    let entry = entry.trim();
+    let named: Vec<usize> = analysis
+        .cfg_nodes
+        .iter()
+        .filter(|(start, node)| {
+            node.label == entry
+                || analysis
+                    .functions
+                    .get(start)
+                    .is_some_and(|(_, name)| name == entry)
+        })
+        .map(|(start, _)| *start)
+        .collect();
+    match named.as_slice() {
+        [start] => return Ok(*start),
+        [] => {}
+        _ => {
+            return Err(anyhow!(
+                "'{}' names {} blocks, give the address of the entry instead",
+                entry,
+                named.len()
+            ))
+        }
+    }
+    let address = ["lbb_", "function_", "cluster_"]
+        .iter()
+        .find_map(|prefix| entry.strip_prefix(prefix))
+        .unwrap_or(entry);
+    parse_offset(address)
+        .ok()
+        .filter(|pc| {
+            analysis
+                .instructions
+                .last()
+                .is_some_and(|last| *pc <= last.ptr)
+        })
+        .and_then(|pc| analysis.cfg_nodes.range(..=pc).next_back())
+        .map(|(start, _)| *start)
+        .ok_or_else(|| anyhow!("No function nor block matches the entry '{}'", entry))
+}
+
+impl FunctionSelection {
+    /// Whether the function starting at `start` is kept.
+    pub fn contains(&self, start: usize) -> bool {
+        self.starts.contains(&start)
+    }
+
+    /// Whether the instruction at `pc` belongs to a kept function.
+    pub fn contains_pc(&self, analysis: &Analysis, pc: usize) -> bool {
+        analysis
+            .functions
+            .range(..=pc)
+            .next_back()
+            .is_some_and(|(start, _)| self.contains(*start))
+    }
+
+    pub fn len(&self) -> usize {
+        self.starts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.starts.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(
+            PcRange::parse("100-0x100").unwrap(),
+            PcRange {
+                start: 100,
+                end: 256
+            }
+        );
+        assert!(PcRange::parse("100").is_err());
+        assert!(PcRange::parse("200-100").is_err());
+    }
+}
@@ -0,0 +1,276 @@
+//! Generation of an initial fuzzing corpus from the constants compared in the program
+//! (`fuzz_corpus/`).
+//!
+//! A fuzzer starting from random inputs rarely gets past the first checks of an instruction,
+//! e.g. `if a + b == 1337` or the match on its selector. The functions reachable from the
+//! entrypoint are walked, and every conditional jump comparing a register against a constant
+//! (an immediate, or a register loaded with a constant earlier in the block, as followed by the
+//! [`RegisterTracker`]) gives a seed holding that constant in little-endian.
+//!
+//! When a [dispatch chain](crate::reverse::dispatch) is recognized, each selector gives a seed
+//! of its own, and the constants compared in the functions reachable from a handler are placed
+//! after the selector of that handler, so that the seed reaches the check.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use solana_sbpf::{ebpf, program::SBPFVersion, static_analysis::Analysis};
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::reverse::dispatch::{find_dispatch_table, function_range, functions_reachable_from};
+use crate::reverse::utils::{RegisterTracker, Value};
+use crate::reverse::OutputFile;
+
+/// How many call levels below the entrypoint, and below each handler, are searched for
+/// comparisons.
+const MAX_CORPUS_SEARCH_DEPTH: usize = 4;
+
+/// Upper bound on the number of seeds written, the library code reachable from the entrypoint
+/// comparing many constants of its own.
+const MAX_CORPUS_SEEDS: usize = 512;
+
+/// Bytes written after the selector, so that the inputs pass the usual length checks on a
+/// `u64` (or two `u32`) argument.
+const MIN_ARGUMENT_BYTES: usize = 8;
+
+/// A constant compared by a conditional jump.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ComparedConstant {
+    pc: usize,
+    value: u64,
+    /// 4 for the 32-bit jumps, 8 otherwise.
+    width: usize,
+}
+
+/// A seed of the corpus.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CorpusSeed {
+    /// Name of the file, in `fuzz_corpus/`.
+    pub file: String,
+    /// Handler whose selector starts the seed, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handler: Option<String>,
+    /// The constant held by the seed (the selector itself for a selector seed).
+    pub value: u64,
+    /// Instruction comparing the constant, `None` for a selector seed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pc: Option<usize>,
+    #[serde(skip)]
+    pub bytes: Vec<u8>,
+}
+
+/// Tells the constants worth a seed apart from booleans, `-1` and addresses of the memory
+/// regions (strings of `.rodata`, stack, heap and input pointers).
+fn is_interesting(value: u64) -> bool {
+    let region = value / ebpf::MM_REGION_SIZE;
+    !matches!(value, 0 | 1 | u64::MAX) && value != u32::MAX as u64 && !(1..=4).contains(&region)
+}
+
+/// Lists the constants compared by the conditional jumps of the function at `function_start`.
+fn collect_compared_constants(analysis: &Analysis, function_start: usize) -> Vec<ComparedConstant> {
+    let range = function_range(analysis, function_start);
+    let mut constants = Vec::new();
+    let mut tracker = RegisterTracker::new();
+
+    for insn in analysis
+        .instructions
+        .iter()
+        .filter(|insn| range.contains(&insn.ptr))
+    {
+        if analysis.cfg_nodes.contains_key(&insn.ptr) {
+            // constants are only tracked within a basic block
+            tracker = RegisterTracker::new();
+        }
+        let class = insn.opc & ebpf::BPF_CLS_MASK;
+        let operation = insn.opc & ebpf::BPF_ALU_OP_MASK;
+        let is_conditional_jump = matches!(class, ebpf::BPF_JMP | ebpf::BPF_JMP32)
+            && !matches!(operation, ebpf::BPF_JA | ebpf::BPF_CALL | ebpf::BPF_EXIT);
+        if is_conditional_jump {
+            let width = if class == ebpf::BPF_JMP32 { 4 } else { 8 };
+            let value = if insn.opc & ebpf::BPF_X == 0 {
+                Some(insn.imm as u64)
+            } else {
+                match (tracker.get(insn.src), tracker.get(insn.dst)) {
+                    (Some(Value::Const(value)), _) | (_, Some(Value::Const(value))) => Some(*value),
+                    _ => None,
+                }
+            };
+            if let Some(value) = value {
+                let value = if width == 4 {
+                    value & u32::MAX as u64
+                } else {
+                    value
+                };
+                constants.push(ComparedConstant {
+                    pc: insn.ptr,
+                    value,
+                    width,
+                });
+            }
+            continue;
+        }
+        match insn.opc {
+            ebpf::LD_DW_IMM => tracker.set(insn.dst, Value::Const(insn.imm as u64)),
+            ebpf::CALL_IMM | ebpf::CALL_REG => tracker = RegisterTracker::new(),
+            _ if matches!(class, ebpf::BPF_ST | ebpf::BPF_STX) => {}
+            _ => tracker.update(insn),
+        }
+    }
+    constants
+}
+
+/// Encodes a selector as compared by the dispatcher: a full Anchor discriminator, a `u8` tag
+/// (Borsh enums) or a `u32` one.
+fn selector_bytes(selector: u64) -> Vec<u8> {
+    if selector > u32::MAX as u64 {
+        selector.to_le_bytes().to_vec()
+    } else if selector <= u8::MAX as u64 {
+        vec![selector as u8]
+    } else {
+        (selector as u32).to_le_bytes().to_vec()
+    }
+}
+
+/// Builds the seeds of the program: one per dispatch selector, then one per compared constant,
+/// after the selector of the handler it is compared in.
+///
+/// # Returns
+///
+/// The seeds, at most [`MAX_CORPUS_SEEDS`], without duplicates.
+pub fn build_corpus(analysis: &Analysis, sbpf_version: SBPFVersion) -> Vec<CorpusSeed> {
+    let table = find_dispatch_table(analysis, sbpf_version, None);
+    let targets = table.as_ref().map_or(&[][..], |table| &table.targets[..]);
+    let selectors: HashSet<u64> = targets.iter().map(|t| t.selector_value).collect();
+
+    let mut seeds = Vec::new();
+    let mut seen = HashSet::new();
+    let mut push = |handler: Option<&str>, value: u64, pc: Option<usize>, bytes: Vec<u8>| {
+        if seeds.len() >= MAX_CORPUS_SEEDS || !seen.insert(bytes.clone()) {
+            return;
+        }
+        let file = format!(
+            "{:04}_{}_{:x}",
+            seeds.len(),
+            handler.unwrap_or("input"),
+            value
+        );
+        seeds.push(CorpusSeed {
+            file,
+            handler: handler.map(str::to_string),
+            value,
+            pc,
+            bytes,
+        });
+    };
+
+    for target in targets {
+        let mut bytes = selector_bytes(target.selector_value);
+        bytes.resize(bytes.len() + MIN_ARGUMENT_BYTES, 0);
+        push(Some(&target.name), target.selector_value, None, bytes);
+    }
+
+    // the handlers first, the functions they share being searched once
+    let handler_functions = targets
+        .iter()
+        .filter(|target| analysis.functions.contains_key(&target.handler_pc))
+        .map(|target| (Some(target), target.handler_pc));
+    let mut searched = HashSet::new();
+    for (target, start) in handler_functions.chain([(None, analysis.entrypoint)]) {
+        let prefix = target.map_or(vec![], |target| selector_bytes(target.selector_value));
+        for function in
+            functions_reachable_from(analysis, start, MAX_CORPUS_SEARCH_DEPTH, sbpf_version)
+        {
+            if !searched.insert(function) {
+                continue;
+            }
+            for constant in collect_compared_constants(analysis, function) {
+                if !is_interesting(constant.value) || selectors.contains(&constant.value) {
+                    continue;
+                }
+                let mut bytes = prefix.clone();
+                bytes.extend_from_slice(&constant.value.to_le_bytes()[..constant.width]);
+                bytes.resize(prefix.len() + constant.width.max(MIN_ARGUMENT_BYTES), 0);
+                push(
+                    target.map(|target| target.name.as_str()),
+                    constant.value,
+                    Some(constant.pc),
+                    bytes,
+                );
+            }
+        }
+    }
+    seeds
+}
+
+/// Writes each seed to `fuzz_corpus/<file>` in `out_dir`.
+pub fn export_corpus<P: AsRef<Path>>(seeds: &[CorpusSeed], out_dir: P) -> Result<()> {
+    let dir = out_dir
+        .as_ref()
+        .join(OutputFile::FuzzCorpus.default_filename());
+    std::fs::create_dir_all(&dir)?;
+    for seed in seeds {
+        std::fs::write(dir.join(&seed.file), &seed.bytes)?;
+    }
+    Ok(())
+}
+
+/// Copies the corpus written in `out_dir` to the corpus directory of a fuzz workspace
+/// (e.g. `fuzz/corpus/<target>` for cargo-fuzz), created if needed.
+///
+/// # Returns
+///
+/// The number of seeds copied.
+pub fn copy_corpus<P: AsRef<Path>>(out_dir: P, corpus_dir: &Path) -> Result<usize> {
+    let dir = out_dir
+        .as_ref()
+        .join(OutputFile::FuzzCorpus.default_filename());
+    if !dir.is_dir() {
+        return Ok(0);
+    }
+    std::fs::create_dir_all(corpus_dir)
+        .with_context(|| format!("Creating corpus directory {}", corpus_dir.display()))?;
+    let mut copied = 0;
+    for entry in std::fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if let Some(name) = path.file_name() {
+            std::fs::copy(&path, corpus_dir.join(name))
+                .with_context(|| format!("Copying {}", path.display()))?;
+            copied += 1;
+        }
+    }
+    Ok(copied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reverse::new_loader;
+    use solana_sbpf::elf::Executable;
+    use test_utils::TestContextObject;
+
+    #[test]
+    fn test_addition_checker_corpus() {
+        let elf =
+            std::fs::read("test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so")
+                .unwrap();
+        let executable =
+            Executable::<TestContextObject>::from_elf(&elf, new_loader(false).unwrap()).unwrap();
+        let analysis = Analysis::from_executable(&executable).unwrap();
+        let seeds = build_corpus(&analysis, executable.get_sbpf_version());
+
+        // a + b == 1337 on the two u32 of the instruction data
+        let sum = seeds
+            .iter()
+            .find(|seed| seed.value == 1337)
+            .expect("the 1337 comparison gives a seed");
+        assert_eq!(sum.bytes, [0x39, 0x05, 0, 0, 0, 0, 0, 0]);
+        assert!(sum.handler.is_none());
+        assert!(seeds.iter().all(|seed| is_interesting(seed.value)));
+
+        assert_eq!(selector_bytes(2), [2]);
+        assert_eq!(
+            selector_bytes(0xafaf6d1f0d989bed),
+            0xafaf6d1f0d989bedu64.to_le_bytes()
+        );
+    }
+}
@@ -4,24 +4,90 @@
 //! and track immediate values from read-only memory segments.
 //!
 //! It includes:
+//! - [`account_names`] — Identification of the Anchor account discriminators by name.
+//! - [`annotations`] — User renames, comments and bookmarks kept across runs (`annotations.json`).
+//! - [`asm`] — Assembler of snippets written in the syntax of the disassembly.
 //! - [`mod@cfg`] — CFG generation and `.dot` export based on instruction analysis.
+//! - [`correlation`] — Correlation of the SAST findings with the functions of the compiled program.
 //! - [`disass`] — Disassembler with immediate tracking support.
+//! - [`dispatch`] — Recognition of the instruction dispatch chain and handler naming.
+//! - [`dominators`] — Export of the dominator / post-dominator relationships of the CFG.
+//! - [`entropy`] — Detection of high-entropy regions in the data sections.
+//! - [`error_codes`] — Names of the Anchor and program-specific error codes.
+//! - [`fuzz_corpus`] — Initial fuzzing corpus built from the constants compared in the program.
+//! - [`function_filter`] — Selection of the functions written to the outputs.
 //! - [`immediate_tracker`] — Tracks offset ranges for immediate data.
+//! - [`names`] — Export and import of the function names for Ghidra and Binary Ninja.
+//! - [`passes`] — Pluggable analysis passes (dominators, PDA seeds, pointer leaks, statistics, ...).
+//! - [`patch`] — Patching of the instructions of a compiled program.
+//! - [`pda_seeds`] — Reconstruction of the seeds of program address derivations.
+//! - [`pointer_leaks`] — Detection of pointers passed to logging syscalls.
+//! - [`raw_input`] — Loading of raw instruction buffers (memory dumps, hex dumps, base64).
+//! - [`security_txt`] — Extraction of the embedded `security.txt` and program metadata.
+//! - [`signatures`] — Identification of known library functions by signature.
+//! - [`stats`] — Instruction statistics and opcode histogram.
 //! - [`utils`] — Low-level utilities used by the analysis engine.
 //!
 //! The main entry point is [`analyze_program`], which drives the analysis based on the selected output mode.
 
+pub mod account_names;
+pub mod annotations;
+#[cfg(feature = "cli")]
+pub mod asm;
 pub mod cfg;
+pub mod cfg_graph;
+#[cfg(any(feature = "cli", feature = "wasm"))]
+pub mod correlation;
+pub mod dataflow;
 pub mod disass;
+pub mod dispatch;
+pub mod dominators;
+pub mod elf_sections;
+pub mod entropy;
+pub mod error_codes;
+pub mod function_filter;
+pub mod fuzz_corpus;
 pub mod immediate_tracker;
+pub mod names;
+pub mod passes;
+#[cfg(feature = "cli")]
+pub mod patch;
+pub mod pda_seeds;
+pub mod pointer_leaks;
+#[cfg(feature = "cli")]
+pub mod query;
+pub mod raw_input;
 pub mod rusteq;
+pub mod rusteq_functions;
+pub mod sanitize;
+pub mod security_txt;
+pub mod signatures;
+pub mod stable_ids;
+pub mod stats;
 pub mod syscalls;
 pub mod utils;
 
 use cfg::*;
-use disass::disassemble_wrapper;
+use account_names::{export_account_discriminators, find_account_discriminators, AccountNames};
+use annotations::Annotations;
+use disass::{
+    disassemble_to_string, disassemble_wrapper, DisassFormat, DisassOptions, TextSection,
+};
+use dispatch::{
+    apply_dispatch_names, export_function_summary, find_dispatch_table, load_idl_discriminators,
+};
+use error_codes::ErrorCodeTable;
+use function_filter::{resolve_entry, FunctionFilter};
 use immediate_tracker::ImmediateTracker;
-use log::{debug, error};
+use names::{collect_names, export_names, names_program, NameSources};
+use passes::{PassRegistry, PassToggles};
+use raw_input::{decode_raw_input, executable_from_raw, RawInput};
+use rusteq::RustEqMode;
+use sanitize::sanitize_labels;
+use signatures::{apply_signature_names, export_signatures, match_signatures, SignatureDb};
+use stable_ids::StableIds;
+use log::{debug, error, info, warn};
+use serde::Serialize;
 use solana_sbpf::{
     ebpf::MM_RODATA_START, elf::Executable, program::BuiltinProgram, static_analysis::Analysis,
     vm::Config,
@@ -36,8 +102,26 @@ use anyhow::Result;
 /// Represents the different types of output files that can be generated by the analysis.
 pub enum OutputFile {
     Disassembly,
+    DisassemblyJson,
     ImmediateDataTable,
     Cfg,
+    CfgJson,
+    CfgChunks,
+    FunctionSummary,
+    ProgramInfo,
+    Dominators,
+    StatsJson,
+    OpcodeHistogram,
+    FunctionStats,
+    Signatures,
+    Findings,
+    PdaSeeds,
+    RustEqFunctions,
+    IdMap,
+    NamesCsv,
+    NamesJson,
+    FuzzCorpus,
+    AccountDiscriminators,
 }
 
 /// Returns the default filename associated with each type of output file.
@@ -45,8 +129,26 @@ impl OutputFile {
     pub fn default_filename(&self) -> &'static str {
         match self {
             OutputFile::Disassembly => "disassembly.out",
+            OutputFile::DisassemblyJson => "disassembly.json",
             OutputFile::ImmediateDataTable => "immediate_data_table.out",
             OutputFile::Cfg => "cfg.dot",
+            OutputFile::CfgJson => "cfg.json",
+            OutputFile::CfgChunks => "cfg_chunks",
+            OutputFile::FunctionSummary => "functions.out",
+            OutputFile::ProgramInfo => "program_info.out",
+            OutputFile::Dominators => "dominators.json",
+            OutputFile::StatsJson => "stats.json",
+            OutputFile::OpcodeHistogram => "opcode_histogram.csv",
+            OutputFile::FunctionStats => "function_stats.csv",
+            OutputFile::Signatures => "signatures.sig",
+            OutputFile::Findings => "findings.json",
+            OutputFile::PdaSeeds => "pda_seeds.out",
+            OutputFile::RustEqFunctions => "rusteq",
+            OutputFile::IdMap => "id_map.json",
+            OutputFile::NamesCsv => "names.csv",
+            OutputFile::NamesJson => "names.json",
+            OutputFile::FuzzCorpus => "fuzz_corpus",
+            OutputFile::AccountDiscriminators => "account_discriminators.out",
         }
     }
 }
@@ -61,7 +163,6 @@ pub enum ReverseOutputMode {
     DisassemblyAndCFG(String),
 }
 
-#[allow(dead_code)]
 impl ReverseOutputMode {
     /// Retrieves the associated path string for the selected output mode.
     pub fn path(&self) -> &str {
@@ -73,6 +174,80 @@ impl ReverseOutputMode {
     }
 }
 
+/// Creates the loader of the executable, with all the Solana syscalls registered so that the
+/// disassembler can resolve their names.
+pub(crate) fn new_loader(labeling: bool) -> Result<Arc<BuiltinProgram<TestContextObject>>> {
+    let mut loader = BuiltinProgram::new_loader(Config {
+        enable_symbol_and_section_labels: labeling,
+        ..Config::default()
+    });
+    syscalls::register_solana_syscalls(&mut loader)
+        .map_err(|e| anyhow::anyhow!("Failed to register syscalls: {:?}", e))?;
+    Ok(Arc::new(loader))
+}
+
+/// Options of the reverse analysis, on top of the selected `ReverseOutputMode`.
+#[derive(Debug, Clone, Default)]
+pub struct ReverseOptions {
+    /// Forces symbol and section labeling on or off. When `None`, labeling is enabled if the
+    /// ELF has a symbol table (i.e. is not stripped), and disabled for `--raw` inputs.
+    pub labeling: Option<bool>,
+    /// Only includes the functions reachable from the program's entrypoint in the generated CFG,
+    /// omitting the system-level or library-defined functions that are never run.
+    pub reduced: bool,
+    /// Generates a CFG containing only the entrypoint (`cluster_{entry}`) block,
+    /// allowing users to build out a focused CFG incrementally (e.g., with the `dotting` module).
+    pub only_entrypoint: bool,
+    /// Root of the reduced CFG instead of the entrypoint: a function or block name, or an
+    /// address. The reduced CFG then holds the functions reachable from it, and `reduced`
+    /// is implied.
+    pub entry: Option<String>,
+    /// Path to an Anchor IDL, used to name the recognized dispatch targets after
+    /// their instructions instead of `handler_N`, and the program error codes.
+    pub idl: Option<String>,
+    /// Wordlist of account names (one per line) hashed, with the shipped one and the accounts of
+    /// the IDL, to name the account discriminators of the program.
+    pub account_names: Option<String>,
+    /// Source tree whose struct names are hashed to name the account discriminators.
+    pub account_sources: Option<String>,
+    /// Splits the CFG into `.dot` files of this many functions each (in `cfg_chunks/`,
+    /// with an index) rendered in parallel, instead of a single `cfg.dot`.
+    pub cfg_chunk: Option<usize>,
+    /// Exports the instruction statistics (`stats.json`, `opcode_histogram.csv`, `function_stats.csv`).
+    pub stats: bool,
+    /// Additional `.sig` file (or directory of `.sig` files) used, with the shipped ones,
+    /// to identify statically linked library functions.
+    pub signatures: Option<String>,
+    /// Exports the signatures of the named functions of the program (`signatures.sig`),
+    /// to build a signature file from an unstripped library build.
+    pub export_signatures: bool,
+    /// Reads the input as a raw instruction buffer instead of an ELF.
+    pub raw: Option<RawInput>,
+    /// How the Rust equivalent of the instructions is shown in the disassembly.
+    pub rusteq: RustEqMode,
+    /// Format of the disassembly listing.
+    pub disass_format: DisassFormat,
+    /// Writes the bytes of each instruction before it in the `solazy` disassembly, like `objdump`.
+    pub show_bytes: bool,
+    /// Names the CFG clusters and blocks after the hash of their function instead of their
+    /// address, and writes the mapping between both (`id_map.json`).
+    pub stable_ids: bool,
+    /// Restricts the disassembly, the CFG and the function summary to some functions
+    /// (`--only-function`, `--skip-libs`, `--range`).
+    pub functions: FunctionFilter,
+    /// Passes enabled or disabled on top of the default ones (`--pass`, `--no-pass`).
+    pub passes: PassToggles,
+    /// Path to an annotations file (renames, comments and bookmarks) merged into the outputs.
+    pub annotations: Option<String>,
+    /// Exports the names of the functions and blocks for other tools (`names.csv`, `names.json`).
+    pub export_names: bool,
+    /// Corpus directory of a fuzz workspace (e.g. `fuzz/corpus/<target>`) where the seeds of the
+    /// `fuzz-corpus` pass are copied, the pass being enabled.
+    pub fuzz_corpus: Option<String>,
+    /// Browses the functions and their disassembly in the terminal once the outputs are written.
+    pub tui: bool,
+}
+
 /// Analyzes a compiled SBPF program and generates output depending on the selected `ReverseOutputMode`.
 ///
 /// This function supports optional configurations to reduce the complexity of the generated Control Flow Graph (CFG),
@@ -82,11 +257,8 @@ impl ReverseOutputMode {
 ///
 /// * `mode` - Output mode that determines the type of reverse engineering output to generate (disassembly, CFG, both, or rust equivalent).
 /// * `target_bytecode` - Path to the ELF binary of the SBPF program.
-/// * `labeling` - Enables symbol and section labeling if `true`. Useful for better disassembly readability.
-/// * `reduced` - If `true`, only includes functions defined after the program's entrypoint in the generated CFG,
-///   omitting system-level or library-defined functions that may not be relevant.
-/// * `only_entrypoint` - If `true`, generates a CFG containing only the entrypoint (`cluster_{entry}`) block,
-///   allowing users to build out a focused CFG incrementally (e.g., with the `dotting` module).
+/// * `options` - The `ReverseOptions` (labeling, CFG reduction and splitting, IDL, statistics, signatures,
+///   Rust equivalent column).
 ///
 /// # Returns
 ///
@@ -95,43 +267,263 @@ impl ReverseOutputMode {
 pub fn analyze_program(
     mode: ReverseOutputMode,
     target_bytecode: String,
-    labeling: bool,
-    reduced: bool,
-    only_entrypoint: bool,
+    options: &ReverseOptions,
 ) -> Result<()> {
-    // Mocking a loader & create an executable
-    let mut loader = BuiltinProgram::new_loader(Config {
-        enable_symbol_and_section_labels: labeling,
-        ..Config::default()
-    });
+    analyze_program_with_passes(mode, target_bytecode, options, &PassRegistry::builtin())
+}
 
-    // Register all Solana syscalls so the disassembler can resolve their names
-    syscalls::register_solana_syscalls(&mut loader)
-        .map_err(|e| anyhow::anyhow!("Failed to register syscalls: {:?}", e))?;
+/// Same as [`analyze_program`], running the passes of `registry` instead of the builtin ones,
+/// e.g. with passes defined outside of sol-azy:
+///
+/// ```ignore
+/// let mut registry = PassRegistry::builtin();
+/// registry.register(Box::new(MyPass))?;
+/// analyze_program_with_passes(mode, target_bytecode, &options, &registry)?;
+/// ```
+pub fn analyze_program_with_passes(
+    mode: ReverseOutputMode,
+    target_bytecode: String,
+    options: &ReverseOptions,
+    registry: &PassRegistry,
+) -> Result<()> {
+    let ReverseOptions {
+        labeling,
+        reduced,
+        only_entrypoint,
+        ref entry,
+        ref idl,
+        account_names: ref account_names_path,
+        ref account_sources,
+        cfg_chunk,
+        stats,
+        ref signatures,
+        export_signatures: export_signatures_enabled,
+        raw,
+        rusteq,
+        disass_format,
+        show_bytes,
+        stable_ids,
+        ref functions,
+        ref passes,
+        annotations: ref annotations_path,
+        export_names: export_names_enabled,
+        fuzz_corpus: ref fuzz_corpus_dir,
+        tui,
+    } = *options;
 
-    let loader = Arc::new(loader);
-    let mut file = File::open(Path::new(&target_bytecode)).unwrap();
+    let mut file = File::open(Path::new(&target_bytecode))?;
     let mut elf = Vec::new();
-    file.read_to_end(&mut elf).unwrap();
-    let program = elf.clone();
-    let executable = match Executable::<TestContextObject>::from_elf(&elf, loader) {
-        Ok(executable) => executable,
-        Err(err) => {
-            error!("Executable constructor failed: {:?}", err);
-            if labeling {
-                debug!("Hint: Try disabling '--labeling' if your binary is not stripped properly (e.g., contains unexpected symbols).");
+    file.read_to_end(&mut elf)?;
+
+    let detected = labeling.is_none() && raw.is_none();
+    let labeling = match labeling {
+        Some(labeling) => labeling,
+        None if raw.is_some() => false,
+        None => {
+            let has_symbols = elf_sections::has_symbol_table(&elf);
+            if has_symbols {
+                info!("Symbol table found, symbol and section labeling enabled");
+            } else {
+                info!("No symbol table (stripped binary), symbol and section labeling disabled");
             }
-            return Err(anyhow::anyhow!("Failed to construct executable: {:?}", err));
+            has_symbols
+        }
+    };
+
+    let loader = new_loader(labeling)?;
+    let (program, executable) = match raw {
+        Some(raw) => {
+            let program = decode_raw_input(&elf, raw.format)?;
+            info!(
+                "Raw input: {} bytes, instructions from offset {:#x}, {:?}",
+                program.len(),
+                raw.text_offset,
+                raw.sbpf_version
+            );
+            let executable = executable_from_raw(&program, &raw, loader)?;
+            (program, executable)
+        }
+        None => {
+            let executable = match Executable::<TestContextObject>::from_elf(&elf, loader) {
+                Ok(executable) => executable,
+                // the symbol table was only a guess, load the program without the labels
+                Err(err) if detected && labeling => {
+                    warn!(
+                        "Loading with symbol and section labels failed ({:?}), retrying without them",
+                        err
+                    );
+                    Executable::<TestContextObject>::from_elf(&elf, new_loader(false)?).map_err(
+                        |err| anyhow::anyhow!("Failed to construct executable: {:?}", err),
+                    )?
+                }
+                Err(err) => {
+                    error!("Executable constructor failed: {:?}", err);
+                    if labeling {
+                        debug!("Hint: Try '--no-labeling' if your binary is not stripped properly (e.g., contains unexpected symbols).");
+                    }
+                    debug!("Hint: Use '--raw' if the input is not an ELF (memory dump, hex dump or base64).");
+                    return Err(anyhow::anyhow!("Failed to construct executable: {:?}", err));
+                }
+            };
+            (elf, executable)
         }
     };
 
     let spinner = helpers::spinner::get_new_spinner(String::from("Performing binary analysis..."));
     // Perform analysis on the executable (e.g., necessary for disassembly, control flow graph, etc..).
     let mut analysis = Analysis::from_executable(&executable).unwrap();
+    sanitize_labels(&mut analysis);
     // Extract sbpf_version from the executable to use where needed
     let sbpf_version = executable.get_sbpf_version();
     spinner.finish_using_style();
 
+    if export_signatures_enabled {
+        let exported = export_signatures(&analysis, sbpf_version, mode.path())?;
+        info!("{} function signatures exported.", exported);
+    }
+    // Identify known library functions and name them before any output is produced
+    let signature_db = SignatureDb::load(signatures.as_deref().map(Path::new))?;
+    let signature_matches = match_signatures(&analysis, sbpf_version, &signature_db);
+    if !signature_matches.is_empty() {
+        info!(
+            "{} functions identified by signature ({} signatures loaded).",
+            signature_matches.len(),
+            signature_db.len()
+        );
+    }
+    apply_signature_names(&mut analysis, &signature_matches);
+
+    // Recognize the instruction dispatch and name its handlers before any output is produced
+    let idl_names = idl
+        .as_ref()
+        .map(|idl_path| load_idl_discriminators(Path::new(&idl_path)))
+        .transpose()?;
+    let mut error_codes = ErrorCodeTable::anchor()?;
+    if let Some(idl_path) = idl {
+        let count = error_codes.extend_from_idl(Path::new(idl_path))?;
+        debug!("{} program error codes loaded from the IDL.", count);
+    }
+    let dispatch_table = find_dispatch_table(&analysis, sbpf_version, idl_names.as_ref());
+    match &dispatch_table {
+        Some(table) => {
+            info!(
+                "Dispatch pattern recognized with {} targets.",
+                table.targets.len()
+            );
+            apply_dispatch_names(&mut analysis, table);
+        }
+        None => debug!("No dispatch pattern recognized."),
+    }
+    // Name the account discriminators the program checks the accounts against
+    let account_names = AccountNames::load(
+        idl.as_deref().map(Path::new),
+        account_names_path.as_deref().map(Path::new),
+        account_sources.as_deref().map(Path::new),
+    )?;
+    let discriminators = find_account_discriminators(&program, &analysis, &account_names);
+    if !discriminators.is_empty() {
+        export_account_discriminators(&discriminators, mode.path())?;
+        info!(
+            "{} account discriminator(s) identified ({} names), listed in {}.",
+            discriminators.len(),
+            account_names.len(),
+            OutputFile::AccountDiscriminators.default_filename()
+        );
+    }
+    // The names given by the user take over the recovered ones
+    let annotations = match annotations_path {
+        Some(annotations_path) => {
+            let annotations = Annotations::load(Path::new(annotations_path), &analysis)?;
+            if annotations.is_empty() {
+                warn!(
+                    "No annotation of {} applies to this program.",
+                    annotations_path
+                );
+            } else {
+                info!(
+                    "{} annotations loaded from {}.",
+                    annotations.len(),
+                    annotations_path
+                );
+            }
+            annotations.apply_renames(&mut analysis);
+            annotations
+        }
+        None => Annotations::default(),
+    };
+    let selection = functions.select(&analysis, &signature_matches)?;
+    let entry = entry
+        .as_deref()
+        .map(|entry| resolve_entry(&analysis, entry))
+        .transpose()?;
+    let reduced = reduced || entry.is_some();
+    if let Some(selection) = &selection {
+        info!(
+            "{} of {} functions kept by the filters.",
+            selection.len(),
+            analysis.functions.len()
+        );
+        if selection.is_empty() {
+            warn!("No function left after filtering, the disassembly and CFG will be empty.");
+        }
+    }
+    export_function_summary(
+        &analysis,
+        dispatch_table.as_ref(),
+        selection.as_ref(),
+        sbpf_version,
+        mode.path(),
+    )?;
+    // addresses of the objdump and JSON listings: the ELF `.text`, or the offset in a raw input
+    let text_address = match raw {
+        Some(raw) => raw.text_offset as u64,
+        None => elf_sections::elf_sections(&program)
+            .iter()
+            .find(|section| section.name == ".text")
+            .map_or(0, |section| section.address),
+    };
+    if export_names_enabled {
+        let document = collect_names(
+            &analysis,
+            names_program(&target_bytecode, sbpf_version, text_address),
+            &NameSources {
+                signatures: &signature_matches,
+                dispatch: dispatch_table.as_ref(),
+                annotations: &annotations,
+            },
+        );
+        let exported = export_names(&document, mode.path())?;
+        info!("{} function and block names exported.", exported);
+    }
+    let stable_ids = stable_ids.then(|| StableIds::compute(&analysis, sbpf_version));
+    if let Some(stable_ids) = &stable_ids {
+        stable_ids.export(mode.path())?;
+        debug!(
+            "Stable ids written to {}",
+            OutputFile::IdMap.default_filename()
+        );
+    }
+    let mut toggles = passes.clone();
+    if stats {
+        toggles.enabled.push("stats".to_string());
+    }
+    if fuzz_corpus_dir.is_some() {
+        toggles.enabled.push("fuzz-corpus".to_string());
+    }
+    let selected_passes = registry.select(&toggles)?;
+    registry.run(
+        &selected_passes,
+        &target_bytecode,
+        &program,
+        &analysis,
+        sbpf_version,
+        mode.path(),
+    )?;
+    if let Some(corpus_dir) = fuzz_corpus_dir {
+        let copied = fuzz_corpus::copy_corpus(mode.path(), Path::new(corpus_dir))?;
+        info!("{} seed(s) copied to the fuzz corpus {}.", copied, corpus_dir);
+    }
+
     // Used to track all immediate datas in order to create a table with their possible associated values
     let mut imm_tracker = ImmediateTracker::new(program.len() + MM_RODATA_START as usize);
     let imm_tracker_wrapped = Some(&mut imm_tracker);
@@ -139,54 +531,187 @@ pub fn analyze_program(
     let mut reg_tracker = RegisterTracker::new();
     let reg_tracker_wrapped = Some(&mut reg_tracker);
 
+    let text = TextSection {
+        bytes: executable.get_text_bytes().1,
+        address: text_address,
+    };
+    let disass_options = DisassOptions {
+        format: disass_format,
+        show_bytes,
+        resolve_strings: true,
+        error_codes: &error_codes,
+        rusteq,
+        selection: selection.as_ref(),
+        user_annotations: &annotations,
+    };
+    let cfg_options = CfgOptions {
+        reduced,
+        only_entrypoint,
+        entry,
+        stable_ids: stable_ids.as_ref(),
+        selection: selection.as_ref(),
+        annotations: &annotations,
+    };
+
     match mode {
         ReverseOutputMode::Disassembly(path) => {
             let _ = disassemble_wrapper(
                 &program,
                 &mut analysis,
+                text,
                 imm_tracker_wrapped,
-                reg_tracker_wrapped,
                 sbpf_version,
+                &disass_options,
                 &path,
             );
         }
         ReverseOutputMode::ControlFlowGraph(path) => {
-            export_cfg_to_dot(
+            export_cfg(
                 &program,
                 &mut analysis,
                 reg_tracker_wrapped,
                 sbpf_version,
                 &path,
-                reduced,
-                only_entrypoint,
+                cfg_chunk,
+                &cfg_options,
             )?;
         }
         ReverseOutputMode::DisassemblyAndCFG(path) => {
             let _ = disassemble_wrapper(
                 &program,
                 &mut analysis,
+                text,
                 imm_tracker_wrapped,
-                reg_tracker_wrapped,
                 sbpf_version,
+                &disass_options,
                 &path,
             );
-            // shadowing old one ref
-            let mut reg_tracker = RegisterTracker::new();
-            let reg_tracker_wrapped = Some(&mut reg_tracker);
-            export_cfg_to_dot(
+            export_cfg(
                 &program,
                 &mut analysis,
                 reg_tracker_wrapped,
                 sbpf_version,
                 &path,
-                reduced,
-                only_entrypoint,
+                cfg_chunk,
+                &cfg_options,
             )?;
         }
     }
+    if tui {
+        #[cfg(feature = "cli")]
+        crate::tui::reverse::run(
+            &program,
+            &analysis,
+            sbpf_version,
+            &error_codes,
+            &annotations,
+            selection.as_ref(),
+        )?;
+        #[cfg(not(feature = "cli"))]
+        warn!("The terminal interface needs the `cli` feature, nothing to browse.");
+    }
     Ok(())
 }
 
+/// Outputs of [`analyze_in_memory`].
+#[derive(Debug, Clone, Serialize)]
+pub struct InMemoryOutputs {
+    /// The listing of `disassembly.out`.
+    pub disassembly: String,
+    /// The graph of `cfg.dot`.
+    pub cfg: String,
+}
+
+/// Analyzes the ELF program `elf` without touching the filesystem nor starting threads, for the
+/// builds without them (the browser bindings of the `wasm` feature).
+///
+/// The names are recovered as in [`analyze_program`] (symbols when the ELF has a symbol table,
+/// shipped signatures, dispatch), and the disassembly and the CFG (`reduced` or not) are
+/// returned instead of written.
+pub fn analyze_in_memory(elf: &[u8], reduced: bool) -> Result<InMemoryOutputs> {
+    let labeling = elf_sections::has_symbol_table(elf);
+    let executable = match Executable::<TestContextObject>::from_elf(elf, new_loader(labeling)?) {
+        Ok(executable) => executable,
+        // the symbol table was only a guess, load the program without the labels
+        Err(_) if labeling => Executable::<TestContextObject>::from_elf(elf, new_loader(false)?)
+            .map_err(|err| anyhow::anyhow!("Failed to construct executable: {:?}", err))?,
+        Err(err) => return Err(anyhow::anyhow!("Failed to construct executable: {:?}", err)),
+    };
+    let mut analysis = Analysis::from_executable(&executable)
+        .map_err(|err| anyhow::anyhow!("Failed to analyze the program: {:?}", err))?;
+    sanitize_labels(&mut analysis);
+    let sbpf_version = executable.get_sbpf_version();
+
+    let signature_matches = match_signatures(&analysis, sbpf_version, &SignatureDb::load(None)?);
+    apply_signature_names(&mut analysis, &signature_matches);
+    if let Some(table) = find_dispatch_table(&analysis, sbpf_version, None) {
+        apply_dispatch_names(&mut analysis, &table);
+    }
+    let error_codes = ErrorCodeTable::anchor()?;
+    let text = TextSection {
+        bytes: executable.get_text_bytes().1,
+        address: elf_sections::elf_sections(elf)
+            .iter()
+            .find(|section| section.name == ".text")
+            .map_or(0, |section| section.address),
+    };
+
+    let disassembly = disassemble_to_string(elf, &analysis, text, &error_codes, sbpf_version)?;
+    let cfg = build_cfg_graph(
+        elf,
+        &analysis,
+        &mut RegisterTracker::new(),
+        sbpf_version,
+        &CfgOptions {
+            reduced,
+            ..CfgOptions::new(&Annotations::default())
+        },
+    );
+    Ok(InMemoryOutputs {
+        disassembly,
+        cfg: cfg.to_dot(),
+    })
+}
+
+/// Exports the CFG either as a single `cfg.dot` or, when `cfg_chunk` is set, as chunks.
+fn export_cfg(
+    program: &[u8],
+    analysis: &mut Analysis,
+    reg_tracker_wrapped: Option<&mut RegisterTracker>,
+    sbpf_version: solana_sbpf::program::SBPFVersion,
+    path: &str,
+    cfg_chunk: Option<usize>,
+    options: &CfgOptions,
+) -> std::io::Result<()> {
+    match cfg_chunk {
+        Some(functions_per_chunk) => {
+            export_cfg_chunks(
+                program,
+                analysis,
+                sbpf_version,
+                path,
+                functions_per_chunk,
+                options,
+            )?;
+            info!(
+                "CFG split into chunks of {} function(s) in {}/{}",
+                functions_per_chunk,
+                path,
+                OutputFile::CfgChunks.default_filename()
+            );
+            Ok(())
+        }
+        None => export_cfg_to_dot(
+            program,
+            analysis,
+            reg_tracker_wrapped,
+            sbpf_version,
+            path,
+            options,
+        ),
+    }
+}
+
 /// Integration tests for the `analyze_program` function using real bytecode inputs.
 #[cfg(test)]
 mod tests {
@@ -200,9 +725,21 @@ mod tests {
                 "test_cases/base_sbf_addition_checker/out1/".to_string(),
             ),
             "test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so".to_string(),
-            true,
-            false,
-            false,
+            &ReverseOptions {
+                labeling: Some(true),
+                ..ReverseOptions::default()
+            },
+        );
+
+        // the DOT is rendered from the JSON graph
+        let out_dir = Path::new("test_cases/base_sbf_addition_checker/out1/");
+        let graph =
+            cfg_graph::CfgGraph::load(&out_dir.join(OutputFile::CfgJson.default_filename()))
+                .unwrap();
+        assert!(!graph.clusters.is_empty());
+        assert_eq!(
+            graph.to_dot(),
+            std::fs::read_to_string(out_dir.join(OutputFile::Cfg.default_filename())).unwrap()
         );
     }
 
@@ -215,9 +752,313 @@ mod tests {
             ),
             "test_cases/base_sbf_addition_checker/bytecodes/addition_checker_sbpf_solana.so"
                 .to_string(),
-            false,
-            false,
-            false,
+            &ReverseOptions::default(),
         );
     }
+
+    /// Tests the chunked CFG export: every chunk is written and listed in the index.
+    #[test]
+    fn test_cfg_chunks() {
+        let out_dir = std::env::temp_dir().join("sol_azy_test_cfg_chunks");
+        std::fs::create_dir_all(&out_dir).unwrap();
+
+        analyze_program(
+            ReverseOutputMode::ControlFlowGraph(out_dir.to_string_lossy().to_string()),
+            "test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so".to_string(),
+            &ReverseOptions {
+                labeling: Some(true),
+                cfg_chunk: Some(8),
+                ..ReverseOptions::default()
+            },
+        )
+        .unwrap();
+
+        let chunks_dir = out_dir.join(OutputFile::CfgChunks.default_filename());
+        let index: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(chunks_dir.join("index.json")).unwrap(),
+        )
+        .unwrap();
+        let chunks = index["chunks"].as_array().unwrap();
+        assert!(chunks.len() > 1);
+        for chunk in chunks {
+            assert!(chunk["clusters"].as_array().unwrap().len() <= 8);
+            let dot = std::fs::read_to_string(chunks_dir.join(chunk["file"].as_str().unwrap()))
+                .unwrap();
+            assert!(dot.starts_with("digraph {"));
+            assert!(dot.trim_end().ends_with('}'));
+        }
+
+        std::fs::remove_dir_all(&out_dir).unwrap();
+    }
+
+    /// Tests that the function filters apply to the summary, the disassembly and the CFG.
+    #[test]
+    fn test_function_filter() {
+        let out_dir = std::env::temp_dir().join("sol_azy_test_function_filter");
+        std::fs::create_dir_all(&out_dir).unwrap();
+
+        analyze_program(
+            ReverseOutputMode::DisassemblyAndCFG(out_dir.to_string_lossy().to_string()),
+            "test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so".to_string(),
+            &ReverseOptions {
+                labeling: Some(true),
+                functions: FunctionFilter {
+                    only_functions: vec!["entrypoint".to_string(), "lbb_680".to_string()],
+                    ..FunctionFilter::default()
+                },
+                ..ReverseOptions::default()
+            },
+        )
+        .unwrap();
+
+        let summary =
+            std::fs::read_to_string(out_dir.join(OutputFile::FunctionSummary.default_filename()))
+                .unwrap();
+        assert!(summary.starts_with("Functions (2 of 50, filtered):"));
+        let graph =
+            cfg_graph::CfgGraph::load(&out_dir.join(OutputFile::CfgJson.default_filename()))
+                .unwrap();
+        let starts: Vec<usize> = graph.clusters.iter().map(|cluster| cluster.start).collect();
+        assert_eq!(starts, vec![3, 676]);
+        let disassembly =
+            std::fs::read_to_string(out_dir.join(OutputFile::Disassembly.default_filename()))
+                .unwrap();
+        assert!(disassembly.starts_with("entrypoint:"));
+        assert!(!disassembly.contains("function_0:"));
+
+        std::fs::remove_dir_all(&out_dir).unwrap();
+    }
+
+    /// Tests the JSON disassembly: one record per instruction, with its bytes.
+    #[test]
+    fn test_disass_format_json() {
+        let out_dir = std::env::temp_dir().join("sol_azy_test_disass_json");
+        std::fs::create_dir_all(&out_dir).unwrap();
+
+        analyze_program(
+            ReverseOutputMode::Disassembly(out_dir.to_string_lossy().to_string()),
+            "test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so".to_string(),
+            &ReverseOptions {
+                labeling: Some(true),
+                disass_format: DisassFormat::Json,
+                ..ReverseOptions::default()
+            },
+        )
+        .unwrap();
+
+        let records: Vec<serde_json::Value> = serde_json::from_str(
+            &std::fs::read_to_string(out_dir.join(OutputFile::DisassemblyJson.default_filename()))
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(records.len(), 2044);
+        assert_eq!(records[3]["pc"], 3);
+        assert_eq!(records[3]["bytes"], "bf12000000000000");
+        assert_eq!(records[3]["mnemonic"], "mov64");
+        assert_eq!(records[3]["operands"], "r2, r1");
+        assert_eq!(records[3]["annotations"]["function"], "entrypoint");
+        let lddw = records.iter().find(|r| r["mnemonic"] == "lddw").unwrap();
+        assert_eq!(lddw["bytes"].as_str().unwrap().len(), 32);
+
+        std::fs::remove_dir_all(&out_dir).unwrap();
+    }
+
+    /// Tests the in-memory analysis used by the WASM bindings.
+    #[test]
+    fn test_analyze_in_memory() {
+        let elf =
+            std::fs::read("test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so")
+                .unwrap();
+        let outputs = analyze_in_memory(&elf, true).unwrap();
+        assert!(outputs.disassembly.contains("entrypoint"));
+        assert!(outputs.cfg.starts_with("digraph"));
+        assert!(analyze_in_memory(b"not an elf", false).is_err());
+    }
+
+    /// Tests the instruction statistics export on a SBF program.
+    #[test]
+    fn test_stats() {
+        let out_dir = std::env::temp_dir().join("sol_azy_test_stats");
+        std::fs::create_dir_all(&out_dir).unwrap();
+
+        analyze_program(
+            ReverseOutputMode::Disassembly(out_dir.to_string_lossy().to_string()),
+            "test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so".to_string(),
+            &ReverseOptions {
+                stats: true,
+                ..ReverseOptions::default()
+            },
+        )
+        .unwrap();
+
+        let stats: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(out_dir.join(OutputFile::StatsJson.default_filename()))
+                .unwrap(),
+        )
+        .unwrap();
+        let histogram_total: u64 = stats["opcode_histogram"]
+            .as_object()
+            .unwrap()
+            .values()
+            .map(|count| count.as_u64().unwrap())
+            .sum();
+        assert_eq!(Some(histogram_total), stats["instructions"].as_u64());
+        assert!(stats["syscalls"]["sol_log_"].as_u64().unwrap() > 0);
+
+        let histogram =
+            std::fs::read_to_string(out_dir.join(OutputFile::OpcodeHistogram.default_filename()))
+                .unwrap();
+        assert!(histogram.starts_with("mnemonic,count\n"));
+
+        std::fs::remove_dir_all(&out_dir).unwrap();
+    }
+
+    /// Tests that signatures exported from a labeled build name the functions of a stripped one.
+    #[test]
+    fn test_signatures_roundtrip() {
+        let out_dir = std::env::temp_dir().join("sol_azy_test_signatures");
+        std::fs::create_dir_all(&out_dir).unwrap();
+        let out_path = out_dir.to_string_lossy().to_string();
+        let bytecode = "test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so";
+
+        analyze_program(
+            ReverseOutputMode::Disassembly(out_path.clone()),
+            bytecode.to_string(),
+            &ReverseOptions {
+                labeling: Some(true),
+                export_signatures: true,
+                ..ReverseOptions::default()
+            },
+        )
+        .unwrap();
+        let signatures_path = out_dir.join(OutputFile::Signatures.default_filename());
+        let exported = std::fs::read_to_string(&signatures_path).unwrap();
+        let names: Vec<&str> = exported
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_whitespace().nth(2))
+            .collect();
+        assert!(!names.is_empty());
+
+        analyze_program(
+            ReverseOutputMode::Disassembly(out_path),
+            bytecode.to_string(),
+            &ReverseOptions {
+                signatures: Some(signatures_path.to_string_lossy().to_string()),
+                ..ReverseOptions::default()
+            },
+        )
+        .unwrap();
+        let disassembly =
+            std::fs::read_to_string(out_dir.join(OutputFile::Disassembly.default_filename()))
+                .unwrap();
+        for name in names {
+            assert!(disassembly.contains(name), "{} not identified", name);
+        }
+
+        std::fs::remove_dir_all(&out_dir).unwrap();
+    }
+
+    /// Tests the disassembly of a raw (non-ELF) hex dump of instructions.
+    #[test]
+    fn test_raw_hex_input() {
+        let out_dir = std::env::temp_dir().join("sol_azy_test_raw_input");
+        std::fs::create_dir_all(&out_dir).unwrap();
+        // padding, then `mov64 r0, 42` and `exit`
+        let input = out_dir.join("text.hex");
+        std::fs::write(
+            &input,
+            "ff ff ff ff\nb7 00 00 00 2a 00 00 00\n95 00 00 00 00 00 00 00\n",
+        )
+        .unwrap();
+
+        analyze_program(
+            ReverseOutputMode::Disassembly(out_dir.to_string_lossy().to_string()),
+            input.to_string_lossy().to_string(),
+            &ReverseOptions {
+                raw: Some(RawInput {
+                    format: raw_input::RawFormat::Auto,
+                    text_offset: 4,
+                    sbpf_version: solana_sbpf::program::SBPFVersion::V0,
+                }),
+                ..ReverseOptions::default()
+            },
+        )
+        .unwrap();
+        let disassembly =
+            std::fs::read_to_string(out_dir.join(OutputFile::Disassembly.default_filename()))
+                .unwrap();
+        assert!(disassembly.contains("mov64 r0, 42"));
+        assert!(disassembly.contains("exit"));
+
+        std::fs::remove_dir_all(&out_dir).unwrap();
+    }
+    /// Tests that `--no-rusteq` drops the Rust equivalent column and `--rusteq-only` keeps it alone,
+    /// and the per-function export of the Rust equivalent.
+    #[test]
+    fn test_rusteq_modes() {
+        let out_dir = std::env::temp_dir().join("sol_azy_test_rusteq");
+        std::fs::create_dir_all(&out_dir).unwrap();
+        let disassemble = |rusteq| {
+            analyze_program(
+                ReverseOutputMode::Disassembly(out_dir.to_string_lossy().to_string()),
+                "test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so".to_string(),
+                &ReverseOptions {
+                    rusteq,
+                    ..ReverseOptions::default()
+                },
+            )
+            .unwrap();
+            std::fs::read_to_string(out_dir.join(OutputFile::Disassembly.default_filename()))
+                .unwrap()
+        };
+
+        let hidden = disassemble(RustEqMode::Hidden);
+        assert!(hidden.contains("syscall sol_log_"));
+        assert!(!hidden.contains("r1 = r10"));
+        let functions_dir = out_dir.join(OutputFile::RustEqFunctions.default_filename());
+        assert!(!functions_dir.exists());
+
+        let column = disassemble(RustEqMode::Column);
+        assert!(column.contains("r0 = sol_log_(r1, r2)"));
+        let entrypoint = std::fs::read_to_string(functions_dir.join("entrypoint.rs")).unwrap();
+        assert!(entrypoint.contains("fn entrypoint(r1: u64) -> u64 {"));
+        assert!(entrypoint.contains("    // lbb_"));
+
+        let only = disassemble(RustEqMode::Only);
+        assert!(!only.contains("mov64 r1, r10"));
+        assert!(only.lines().any(|line| line.trim_start().starts_with("0:")));
+        assert!(only.contains("r1 = r10"));
+
+        std::fs::remove_dir_all(&out_dir).unwrap();
+    }
+
+    /// Tests that `--show-bytes` writes the bytes of the instructions, `lddw` on two lines.
+    #[test]
+    fn test_show_bytes() {
+        let out_dir = std::env::temp_dir().join("sol_azy_test_show_bytes");
+        std::fs::create_dir_all(&out_dir).unwrap();
+        analyze_program(
+            ReverseOutputMode::Disassembly(out_dir.to_string_lossy().to_string()),
+            "test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so".to_string(),
+            &ReverseOptions {
+                show_bytes: true,
+                ..ReverseOptions::default()
+            },
+        )
+        .unwrap();
+        let disassembly =
+            std::fs::read_to_string(out_dir.join(OutputFile::Disassembly.default_filename()))
+                .unwrap();
+        assert!(disassembly.contains("    95 00 00 00 00 00 00 00  exit"));
+        let lines: Vec<&str> = disassembly.lines().collect();
+        let lddw = lines
+            .iter()
+            .position(|line| line.contains("lddw r1, 0x100004610"))
+            .unwrap();
+        assert!(lines[lddw].starts_with("    18 01 00 00 10 46 00 00  lddw"));
+        assert_eq!(lines[lddw + 1], "    00 00 00 00 01 00 00 00");
+
+        std::fs::remove_dir_all(&out_dir).unwrap();
+    }
 }
@@ -0,0 +1,385 @@
+//! Export and import of the names of a program, to pivot with general-purpose RE tools.
+//!
+//! With `--export-names`, the names of the functions and blocks, whether they come from the
+//! symbols, the signatures, the dispatch or the annotations, are written to:
+//!
+//! - `names.csv`: `address,name,kind` lines, for a Ghidra script;
+//! - `names.json`: a BinExport-lite document with the program, its named functions and blocks,
+//!   and the comments of the annotations.
+//!
+//! The addresses are the virtual addresses of the instructions (`.text` address + 8 × pc), as
+//! shown by Ghidra, Binary Ninja and the `objdump` listing. The `import-names` command reads
+//! either file back, e.g. after renaming functions in another tool, into `annotations.json`.
+
+#[cfg(feature = "cli")]
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_sbpf::{ebpf, program::SBPFVersion, static_analysis::Analysis};
+use std::io::Write;
+use std::path::Path;
+
+#[cfg(feature = "cli")]
+use crate::reverse::annotations::AnnotationFile;
+use crate::reverse::annotations::Annotations;
+use crate::reverse::dispatch::DispatchTable;
+#[cfg(feature = "cli")]
+use crate::reverse::raw_input::parse_offset;
+use crate::reverse::signatures::SignatureMatch;
+use crate::reverse::OutputFile;
+
+/// Value of the `format` field of `names.json`.
+pub const NAMES_FORMAT: &str = "sol-azy-names";
+
+/// A BinExport-lite document (`names.json`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NamesDocument {
+    #[serde(default)]
+    pub format: String,
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
+    pub program: NamesProgram,
+    #[serde(default)]
+    pub functions: Vec<NamedLocation>,
+    #[serde(default)]
+    pub blocks: Vec<NamedLocation>,
+    #[serde(default)]
+    pub comments: Vec<NamedComment>,
+}
+
+/// The program the names belong to.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NamesProgram {
+    #[serde(default)]
+    pub file: String,
+    #[serde(default)]
+    pub sbpf_version: String,
+    /// Virtual address of the first instruction.
+    #[serde(default)]
+    pub text_address: u64,
+}
+
+/// A named function or block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamedLocation {
+    /// Virtual address, as a number or a `0x` hex string.
+    pub address: Value,
+    #[serde(default)]
+    pub pc: usize,
+    pub name: String,
+    /// Where the name comes from: `symbol`, `signature`, `dispatch` or `annotation`.
+    #[serde(default)]
+    pub source: String,
+    /// Start of the function holding the block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function: Option<usize>,
+}
+
+/// A comment on an instruction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamedComment {
+    pub address: Value,
+    #[serde(default)]
+    pub pc: usize,
+    pub text: String,
+}
+
+/// Whether `label` is the default name of the block at `pc` (or no name).
+fn is_default_name(label: &str, pc: usize) -> bool {
+    label.is_empty() || label == format!("function_{}", pc) || label == format!("lbb_{}", pc)
+}
+
+/// Whether `name` is a default name given by sol-azy, Ghidra or Binary Ninja, not worth importing.
+/// The entrypoint keeps its name, see [`Annotations`].
+#[cfg(feature = "cli")]
+fn is_tool_default_name(name: &str) -> bool {
+    name == "entrypoint"
+        || ["function_", "lbb_", "FUN_", "LAB_", "sub_", "label_"]
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+}
+
+/// Where the names were recovered, to tell the source of each name.
+pub struct NameSources<'a> {
+    pub signatures: &'a [SignatureMatch],
+    pub dispatch: Option<&'a DispatchTable>,
+    pub annotations: &'a Annotations,
+}
+
+impl NameSources<'_> {
+    /// Source of the name `label` of the block at `pc`.
+    fn source(&self, pc: usize, label: &str) -> &'static str {
+        if self.annotations.is_renamed(pc) {
+            "annotation"
+        } else if self.dispatch.is_some_and(|table| {
+            table
+                .targets
+                .iter()
+                .any(|target| target.handler_pc == pc && target.name == label)
+        }) {
+            "dispatch"
+        } else if self
+            .signatures
+            .iter()
+            .any(|signature| signature.function_pc == pc && signature.name == label)
+        {
+            "signature"
+        } else {
+            "symbol"
+        }
+    }
+}
+
+/// Collects the names of the functions and blocks of `analysis` that are not default names,
+/// and the comments of the annotations.
+pub fn collect_names(
+    analysis: &Analysis,
+    program: NamesProgram,
+    sources: &NameSources,
+) -> NamesDocument {
+    let text_address = program.text_address;
+    let address = |pc: usize| {
+        Value::String(format!(
+            "{:#x}",
+            text_address + (pc * ebpf::INSN_SIZE) as u64
+        ))
+    };
+    let mut document = NamesDocument {
+        format: NAMES_FORMAT.to_string(),
+        version: 1,
+        program,
+        ..NamesDocument::default()
+    };
+
+    for (pc, cfg_node) in &analysis.cfg_nodes {
+        // the analysis adds a `super_root` node past the last instruction
+        if is_default_name(&cfg_node.label, *pc) || cfg_node.instructions.is_empty() {
+            continue;
+        }
+        let function = analysis
+            .functions
+            .range(..=*pc)
+            .next_back()
+            .map(|(start, _)| *start);
+        let location = NamedLocation {
+            address: address(*pc),
+            pc: *pc,
+            name: cfg_node.label.clone(),
+            source: sources.source(*pc, &cfg_node.label).to_string(),
+            function: function.filter(|start| start != pc),
+        };
+        if analysis.functions.contains_key(pc) {
+            document.functions.push(location);
+        } else {
+            document.blocks.push(location);
+        }
+    }
+    document.comments = sources
+        .annotations
+        .comments()
+        .iter()
+        .map(|(pc, text)| NamedComment {
+            address: address(*pc),
+            pc: *pc,
+            text: text.clone(),
+        })
+        .collect();
+    document
+}
+
+/// Writes `names.csv` and `names.json` in `path`.
+///
+/// # Returns
+///
+/// The number of named functions and blocks.
+pub fn export_names<P: AsRef<Path>>(document: &NamesDocument, path: P) -> std::io::Result<usize> {
+    let path = path.as_ref();
+    let mut csv = std::fs::File::create(path.join(OutputFile::NamesCsv.default_filename()))?;
+    writeln!(csv, "address,name,kind")?;
+    for (kind, locations) in [
+        ("function", &document.functions),
+        ("block", &document.blocks),
+    ] {
+        for location in locations {
+            writeln!(
+                csv,
+                "{},{},{}",
+                location.address.as_str().unwrap_or_default(),
+                location.name.replace(',', "_"),
+                kind
+            )?;
+        }
+    }
+    std::fs::write(
+        path.join(OutputFile::NamesJson.default_filename()),
+        serde_json::to_string_pretty(document).map_err(std::io::Error::other)?,
+    )?;
+    Ok(document.functions.len() + document.blocks.len())
+}
+
+/// Parses an address given as a number or as a decimal or `0x` hex string.
+#[cfg(feature = "cli")]
+fn parse_address(address: &Value) -> Result<u64> {
+    match address {
+        Value::Number(number) => number
+            .as_u64()
+            .ok_or_else(|| anyhow!("Invalid address {}", number)),
+        Value::String(address) => Ok(parse_offset(address.trim())? as u64),
+        other => Err(anyhow!("Invalid address {}", other)),
+    }
+}
+
+/// `(address, text)` pairs read from a names file.
+#[cfg(feature = "cli")]
+pub type AddressedEntries = Vec<(u64, String)>;
+
+/// Reads the names and comments of a `names.csv` (`address,name[,...]` lines, with an
+/// optional header) or of a `names.json`, as `(address, name)` and `(address, comment)` pairs.
+#[cfg(feature = "cli")]
+pub fn read_names(path: &Path) -> Result<(AddressedEntries, AddressedEntries)> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Reading names {}", path.display()))?;
+    if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+        let document: NamesDocument = serde_json::from_str(&content)
+            .with_context(|| format!("Parsing names {}", path.display()))?;
+        let names = document
+            .functions
+            .iter()
+            .chain(&document.blocks)
+            .map(|location| Ok((parse_address(&location.address)?, location.name.clone())))
+            .collect::<Result<_>>()?;
+        let comments = document
+            .comments
+            .iter()
+            .map(|comment| Ok((parse_address(&comment.address)?, comment.text.clone())))
+            .collect::<Result<_>>()?;
+        return Ok((names, comments));
+    }
+
+    let mut names = vec![];
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || (index == 0 && line.starts_with("address")) {
+            continue;
+        }
+        let mut fields = line.split(',').map(|field| field.trim().trim_matches('"'));
+        let (Some(address), Some(name)) = (fields.next(), fields.next()) else {
+            return Err(anyhow!(
+                "Invalid line {}:{}: {}",
+                path.display(),
+                index + 1,
+                line
+            ));
+        };
+        let address = parse_offset(address)
+            .with_context(|| format!("Invalid address at {}:{}", path.display(), index + 1))?;
+        names.push((address as u64, name.to_string()));
+    }
+    Ok((names, vec![]))
+}
+
+/// Converts a virtual address into the instruction pointer, given the `.text` address.
+#[cfg(feature = "cli")]
+fn address_to_pc(address: u64, text_address: u64) -> Option<usize> {
+    let offset = address.checked_sub(text_address)?;
+    (offset % ebpf::INSN_SIZE as u64 == 0).then_some((offset / ebpf::INSN_SIZE as u64) as usize)
+}
+
+/// Outcome of [`import_names`].
+#[cfg(feature = "cli")]
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub renames: usize,
+    pub comments: usize,
+    /// Names skipped: default names of the tools, or addresses outside of `.text`.
+    pub skipped: usize,
+}
+
+/// Merges the names and comments read by [`read_names`] into `annotations`, the imported ones
+/// replacing those at the same address.
+#[cfg(feature = "cli")]
+pub fn import_names(
+    annotations: &mut AnnotationFile,
+    names: &[(u64, String)],
+    comments: &[(u64, String)],
+    text_address: u64,
+) -> ImportSummary {
+    let mut summary = ImportSummary::default();
+    for (address, name) in names {
+        match address_to_pc(*address, text_address) {
+            Some(pc) if !is_tool_default_name(name) => {
+                summary.renames += annotations.set_rename(pc, name) as usize;
+            }
+            _ => summary.skipped += 1,
+        }
+    }
+    for (address, comment) in comments {
+        match address_to_pc(*address, text_address) {
+            Some(pc) => summary.comments += annotations.set_comment(pc, comment) as usize,
+            None => summary.skipped += 1,
+        }
+    }
+    summary
+}
+
+/// Returns the [`NamesProgram`] of a program.
+pub fn names_program(file: &str, sbpf_version: SBPFVersion, text_address: u64) -> NamesProgram {
+    NamesProgram {
+        file: file.to_string(),
+        sbpf_version: format!("{:?}", sbpf_version),
+        text_address,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_names() {
+        let dir = std::env::temp_dir().join(format!("solazy_names_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let csv = dir.join("names.csv");
+        std::fs::write(
+            &csv,
+            "address,name,kind\n0x128,process_deposit,function\n0x130,FUN_00000130,function\n0x8,before_text,function\n",
+        )
+        .unwrap();
+
+        let mut annotations: AnnotationFile = serde_json::from_value(serde_json::json!({
+            "renames": { "lbb_1": "old_name", "2": "kept" }
+        }))
+        .unwrap();
+        let (names, comments) = read_names(&csv).unwrap();
+        let summary = import_names(&mut annotations, &names, &comments, 0x120);
+        assert_eq!(
+            summary,
+            ImportSummary {
+                renames: 1,
+                comments: 0,
+                skipped: 2
+            }
+        );
+        assert_eq!(
+            annotations.renames,
+            [("1", "process_deposit"), ("2", "kept")]
+                .into_iter()
+                .map(|(pc, name)| (pc.to_string(), name.to_string()))
+                .collect()
+        );
+
+        let json = dir.join("names.json");
+        std::fs::write(
+            &json,
+            r#"{"functions": [{"address": 304, "name": "process_deposit"}],
+                "comments": [{"address": "0x138", "text": "vault"}]}"#,
+        )
+        .unwrap();
+        let (names, comments) = read_names(&json).unwrap();
+        let summary = import_names(&mut annotations, &names, &comments, 0x120);
+        assert_eq!((summary.renames, summary.comments), (1, 1));
+        assert_eq!(annotations.comments["3"], "vault");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,545 @@
+//! Pluggable analysis passes of the reverse command.
+//!
+//! A [`ReversePass`] reads the program, its [`Analysis`] and the outputs of the passes run
+//! before it, writes its own files to the output directory and returns named artifacts and
+//! annotations on instructions. The [`PassRegistry`] holds the passes in the order they run:
+//! the shipped ones ([`PassRegistry::builtin`]) and those registered by crates using sol-azy as
+//! a library, passed to [`analyze_program_with_passes`](crate::reverse::analyze_program_with_passes).
+//!
+//! The passes are toggled with `--pass <name>` and `--no-pass <name>`. The non-empty outputs
+//! are written to `passes/<name>.json`.
+
+use anyhow::{anyhow, Result};
+use log::{debug, info};
+use serde::Serialize;
+use serde_json::Value;
+use solana_sbpf::{program::SBPFVersion, static_analysis::Analysis};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use crate::printers::sast_printer::SastPrinter;
+use crate::reverse::dominators::export_dominators;
+use crate::reverse::entropy::find_high_entropy_regions;
+use crate::reverse::fuzz_corpus::{build_corpus, export_corpus};
+use crate::reverse::pda_seeds::{export_pda_seeds, find_pda_derivations};
+use crate::reverse::pointer_leaks::{
+    export_findings, find_pointer_leaks, pointer_leak_rule_metadata,
+};
+use crate::reverse::security_txt::export_program_info;
+use crate::reverse::stats::export_stats;
+use crate::reverse::OutputFile;
+
+/// Directory of the pass outputs, in the output directory.
+pub const PASSES_DIR: &str = "passes";
+
+/// What a pass gets to work on.
+pub struct PassContext<'a> {
+    /// Path of the analyzed file.
+    pub file: &'a str,
+    /// The ELF, or the decoded raw input.
+    pub program: &'a [u8],
+    pub analysis: &'a Analysis<'a>,
+    pub sbpf_version: SBPFVersion,
+    /// Output directory of the run.
+    pub out_dir: &'a str,
+    /// Outputs of the passes run before, by pass name.
+    pub outputs: &'a BTreeMap<String, PassOutput>,
+}
+
+impl PassContext<'_> {
+    /// Returns the artifact `name` of the pass `pass`, if it ran and produced it.
+    pub fn artifact(&self, pass: &str, name: &str) -> Option<&Value> {
+        self.outputs.get(pass)?.artifacts.get(name)
+    }
+}
+
+/// A comment of a pass on an instruction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PassAnnotation {
+    pub pc: usize,
+    pub text: String,
+}
+
+/// What a pass returns, on top of the files it writes.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PassOutput {
+    pub artifacts: BTreeMap<String, Value>,
+    pub annotations: Vec<PassAnnotation>,
+}
+
+impl PassOutput {
+    pub fn is_empty(&self) -> bool {
+        self.artifacts.is_empty() && self.annotations.is_empty()
+    }
+}
+
+/// An analysis of the compiled program.
+pub trait ReversePass: Send + Sync {
+    /// Name of the pass, as given to `--pass` / `--no-pass`.
+    fn name(&self) -> &'static str;
+    /// One-line description, as listed by [`PassRegistry::list`].
+    fn description(&self) -> &'static str;
+    /// Whether the pass runs without `--pass`.
+    fn enabled_by_default(&self) -> bool {
+        true
+    }
+    /// Passes whose outputs are read by this one, enabled along with it.
+    fn dependencies(&self) -> &'static [&'static str] {
+        &[]
+    }
+    fn run(&self, context: &PassContext) -> Result<PassOutput>;
+}
+
+/// Passes enabled or disabled on the command line, on top of the default ones.
+#[derive(Debug, Clone, Default)]
+pub struct PassToggles {
+    pub enabled: Vec<String>,
+    pub disabled: Vec<String>,
+}
+
+/// The passes, in the order they run.
+pub struct PassRegistry {
+    passes: Vec<Box<dyn ReversePass>>,
+}
+
+impl PassRegistry {
+    pub fn empty() -> Self {
+        Self { passes: vec![] }
+    }
+
+    /// The passes shipped with sol-azy.
+    pub fn builtin() -> Self {
+        let mut registry = Self::empty();
+        let passes: [Box<dyn ReversePass>; 6] = [
+            Box::new(DominatorsPass),
+            Box::new(PdaSeedsPass),
+            Box::new(PointerLeaksPass),
+            Box::new(StatsPass),
+            Box::new(ProgramInfoPass),
+            Box::new(FuzzCorpusPass),
+        ];
+        for pass in passes {
+            registry
+                .register(pass)
+                .expect("the builtin passes have distinct names");
+        }
+        registry
+    }
+
+    /// Adds a pass, run after the ones already registered.
+    ///
+    /// # Errors
+    ///
+    /// If a pass of the same name is registered, or a dependency is not registered before.
+    pub fn register(&mut self, pass: Box<dyn ReversePass>) -> Result<()> {
+        if self.get(pass.name()).is_some() {
+            return Err(anyhow!(
+                "A pass named '{}' is already registered",
+                pass.name()
+            ));
+        }
+        if let Some(missing) = pass
+            .dependencies()
+            .iter()
+            .find(|dependency| self.get(dependency).is_none())
+        {
+            return Err(anyhow!(
+                "Pass '{}' depends on '{}', which must be registered before it",
+                pass.name(),
+                missing
+            ));
+        }
+        self.passes.push(pass);
+        Ok(())
+    }
+
+    fn get(&self, name: &str) -> Option<&dyn ReversePass> {
+        self.passes
+            .iter()
+            .find(|pass| pass.name() == name)
+            .map(|pass| pass.as_ref())
+    }
+
+    /// Name, description and default state of the registered passes.
+    pub fn list(&self) -> Vec<(&'static str, &'static str, bool)> {
+        self.passes
+            .iter()
+            .map(|pass| (pass.name(), pass.description(), pass.enabled_by_default()))
+            .collect()
+    }
+
+    /// Resolves the toggles into the names of the passes to run, with their dependencies.
+    ///
+    /// # Errors
+    ///
+    /// If a toggle names an unknown pass, or a pass is disabled while an enabled one depends
+    /// on it.
+    pub fn select(&self, toggles: &PassToggles) -> Result<BTreeSet<&'static str>> {
+        for name in toggles.enabled.iter().chain(&toggles.disabled) {
+            if self.get(name).is_none() {
+                return Err(anyhow!(
+                    "Unknown pass '{}' (available: {})",
+                    name,
+                    self.passes
+                        .iter()
+                        .map(|pass| pass.name())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+        }
+        let disabled = |name: &str| toggles.disabled.iter().any(|disabled| disabled == name);
+        let mut selected = BTreeSet::new();
+        // the dependencies are registered first: a reverse walk sees the dependents first
+        for pass in self.passes.iter().rev() {
+            let wanted = selected.contains(pass.name())
+                || toggles.enabled.iter().any(|enabled| enabled == pass.name())
+                || (pass.enabled_by_default() && !disabled(pass.name()));
+            if !wanted {
+                continue;
+            }
+            if disabled(pass.name()) && selected.contains(pass.name()) {
+                return Err(anyhow!(
+                    "Pass '{}' is disabled but needed by another enabled pass",
+                    pass.name()
+                ));
+            }
+            selected.insert(pass.name());
+            selected.extend(pass.dependencies().iter().copied());
+        }
+        Ok(selected)
+    }
+
+    /// Runs the `selected` passes in order and writes their non-empty outputs.
+    ///
+    /// # Returns
+    ///
+    /// The outputs of the passes, by name.
+    pub fn run(
+        &self,
+        selected: &BTreeSet<&'static str>,
+        file: &str,
+        program: &[u8],
+        analysis: &Analysis,
+        sbpf_version: SBPFVersion,
+        out_dir: &str,
+    ) -> Result<BTreeMap<String, PassOutput>> {
+        let mut outputs = BTreeMap::new();
+        for pass in self
+            .passes
+            .iter()
+            .filter(|pass| selected.contains(pass.name()))
+        {
+            debug!("Running pass '{}'", pass.name());
+            let context = PassContext {
+                file,
+                program,
+                analysis,
+                sbpf_version,
+                out_dir,
+                outputs: &outputs,
+            };
+            let output = pass
+                .run(&context)
+                .map_err(|e| anyhow!("Pass '{}' failed: {}", pass.name(), e))?;
+            if !output.is_empty() {
+                write_pass_output(pass.name(), &output, out_dir)?;
+            }
+            outputs.insert(pass.name().to_string(), output);
+        }
+        Ok(outputs)
+    }
+}
+
+/// Writes the output of the pass `name` to `passes/<name>.json`.
+fn write_pass_output(name: &str, output: &PassOutput, out_dir: &str) -> Result<()> {
+    let mut path = PathBuf::from(out_dir);
+    path.push(PASSES_DIR);
+    std::fs::create_dir_all(&path)?;
+    path.push(format!("{}.json", name));
+    std::fs::write(&path, serde_json::to_string_pretty(output)?)?;
+    Ok(())
+}
+
+/// `dominators.json`.
+struct DominatorsPass;
+
+impl ReversePass for DominatorsPass {
+    fn name(&self) -> &'static str {
+        "dominators"
+    }
+
+    fn description(&self) -> &'static str {
+        "Dominator and post-dominator trees of the functions (dominators.json)"
+    }
+
+    fn run(&self, context: &PassContext) -> Result<PassOutput> {
+        export_dominators(context.analysis, context.out_dir)?;
+        Ok(PassOutput::default())
+    }
+}
+
+/// `pda_seeds.out`, with an annotation on each derivation.
+struct PdaSeedsPass;
+
+impl ReversePass for PdaSeedsPass {
+    fn name(&self) -> &'static str {
+        "pda-seeds"
+    }
+
+    fn description(&self) -> &'static str {
+        "Seeds of the program address derivations (pda_seeds.out)"
+    }
+
+    fn run(&self, context: &PassContext) -> Result<PassOutput> {
+        let derivations =
+            find_pda_derivations(context.program, context.analysis, context.sbpf_version);
+        export_pda_seeds(&derivations, context.out_dir)?;
+        if !derivations.is_empty() {
+            info!(
+                "{} PDA derivation(s) found, seeds reconstructed in {}.",
+                derivations.len(),
+                OutputFile::PdaSeeds.default_filename()
+            );
+        }
+        Ok(PassOutput {
+            annotations: derivations
+                .iter()
+                .map(|derivation| PassAnnotation {
+                    pc: derivation.pc,
+                    text: format!(
+                        "PDA seeds: {}",
+                        derivation
+                            .seeds
+                            .iter()
+                            .map(|seed| seed.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                })
+                .collect(),
+            ..PassOutput::default()
+        })
+    }
+}
+
+/// `findings.json`, printed like the SAST findings.
+struct PointerLeaksPass;
+
+impl ReversePass for PointerLeaksPass {
+    fn name(&self) -> &'static str {
+        "pointer-leaks"
+    }
+
+    fn description(&self) -> &'static str {
+        "Stack, heap and input pointers passed to logging syscalls (findings.json)"
+    }
+
+    fn run(&self, context: &PassContext) -> Result<PassOutput> {
+        let leaks = find_pointer_leaks(context.analysis, context.sbpf_version);
+        export_findings(&leaks, context.out_dir)?;
+        if leaks.is_empty() {
+            return Ok(PassOutput::default());
+        }
+        let locations: Vec<(String, String)> = leaks
+            .iter()
+            .map(|leak| {
+                let location = format!(
+                    "lbb_{} (in {}, {}): syscall {}",
+                    leak.pc, leak.function_label, leak.location, leak.syscall
+                );
+                (location, leak.description())
+            })
+            .collect();
+        SastPrinter::print_bytecode_findings(&pointer_leak_rule_metadata(), &locations)?;
+        Ok(PassOutput {
+            artifacts: BTreeMap::from([("findings".to_string(), serde_json::to_value(&leaks)?)]),
+            annotations: leaks
+                .iter()
+                .map(|leak| PassAnnotation {
+                    pc: leak.pc,
+                    text: leak.description(),
+                })
+                .collect(),
+        })
+    }
+}
+
+/// `stats.json`, `opcode_histogram.csv` and `function_stats.csv` (`--stats`).
+struct StatsPass;
+
+impl ReversePass for StatsPass {
+    fn name(&self) -> &'static str {
+        "stats"
+    }
+
+    fn description(&self) -> &'static str {
+        "Instruction statistics and opcode histogram (stats.json, *.csv)"
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+
+    fn run(&self, context: &PassContext) -> Result<PassOutput> {
+        let program_stats = export_stats(context.analysis, context.out_dir)?;
+        info!(
+            "Instruction statistics exported ({} instructions, {} functions, {} syscalls).",
+            program_stats.instructions,
+            program_stats.functions,
+            program_stats.syscalls.values().sum::<usize>()
+        );
+        Ok(PassOutput::default())
+    }
+}
+
+/// `program_info.out`: details, `security.txt` and high-entropy regions.
+struct ProgramInfoPass;
+
+impl ReversePass for ProgramInfoPass {
+    fn name(&self) -> &'static str {
+        "program-info"
+    }
+
+    fn description(&self) -> &'static str {
+        "Program details, security.txt and high-entropy data regions (program_info.out)"
+    }
+
+    fn run(&self, context: &PassContext) -> Result<PassOutput> {
+        let analysis = context.analysis;
+        let program_details = [
+            ("file", context.file.to_string()),
+            ("size", format!("{} bytes", context.program.len())),
+            ("sbpf_version", format!("{:?}", context.sbpf_version)),
+            ("entrypoint", format!("lbb_{}", analysis.entrypoint)),
+            ("functions", analysis.functions.len().to_string()),
+            ("instructions", analysis.instructions.len().to_string()),
+        ];
+        let high_entropy_regions = find_high_entropy_regions(context.program);
+        if !high_entropy_regions.is_empty() {
+            info!(
+                "{} high-entropy region(s) found in the data sections, see {}.",
+                high_entropy_regions.len(),
+                OutputFile::ProgramInfo.default_filename()
+            );
+        }
+        if let Some(security_txt) = export_program_info(
+            context.program,
+            &program_details,
+            &high_entropy_regions,
+            Path::new(context.out_dir),
+        )? {
+            info!(
+                "security.txt found (contacts: {})",
+                security_txt.get("contacts").unwrap_or("none")
+            );
+        }
+        Ok(PassOutput::default())
+    }
+}
+
+/// `fuzz_corpus/`: seeds holding the constants compared in the program (`--fuzz-corpus`).
+struct FuzzCorpusPass;
+
+impl ReversePass for FuzzCorpusPass {
+    fn name(&self) -> &'static str {
+        "fuzz-corpus"
+    }
+
+    fn description(&self) -> &'static str {
+        "Fuzzing seeds built from the selectors and the constants compared in the program (fuzz_corpus/)"
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+
+    fn run(&self, context: &PassContext) -> Result<PassOutput> {
+        let seeds = build_corpus(context.analysis, context.sbpf_version);
+        if seeds.is_empty() {
+            return Ok(PassOutput::default());
+        }
+        export_corpus(&seeds, context.out_dir)?;
+        info!(
+            "{} fuzzing seed(s) written to {}.",
+            seeds.len(),
+            OutputFile::FuzzCorpus.default_filename()
+        );
+        Ok(PassOutput {
+            artifacts: BTreeMap::from([("seeds".to_string(), serde_json::to_value(&seeds)?)]),
+            annotations: seeds
+                .iter()
+                .filter_map(|seed| {
+                    Some(PassAnnotation {
+                        pc: seed.pc?,
+                        text: format!("Fuzzing seed {}", seed.file),
+                    })
+                })
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestPass(&'static str, &'static [&'static str], bool);
+
+    impl ReversePass for TestPass {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn description(&self) -> &'static str {
+            "test"
+        }
+
+        fn enabled_by_default(&self) -> bool {
+            self.2
+        }
+
+        fn dependencies(&self) -> &'static [&'static str] {
+            self.1
+        }
+
+        fn run(&self, _context: &PassContext) -> Result<PassOutput> {
+            Ok(PassOutput::default())
+        }
+    }
+
+    #[test]
+    fn test_select_passes() {
+        let mut registry = PassRegistry::empty();
+        registry
+            .register(Box::new(TestPass("base", &[], false)))
+            .unwrap();
+        registry
+            .register(Box::new(TestPass("derived", &["base"], false)))
+            .unwrap();
+        registry
+            .register(Box::new(TestPass("other", &[], true)))
+            .unwrap();
+        assert!(registry
+            .register(Box::new(TestPass("orphan", &["missing"], true)))
+            .is_err());
+        assert!(registry
+            .register(Box::new(TestPass("base", &[], true)))
+            .is_err());
+
+        let toggles = |enabled: &[&str], disabled: &[&str]| PassToggles {
+            enabled: enabled.iter().map(|name| name.to_string()).collect(),
+            disabled: disabled.iter().map(|name| name.to_string()).collect(),
+        };
+        assert_eq!(
+            registry.select(&toggles(&[], &[])).unwrap(),
+            BTreeSet::from(["other"])
+        );
+        assert_eq!(
+            registry.select(&toggles(&["derived"], &["other"])).unwrap(),
+            BTreeSet::from(["base", "derived"])
+        );
+        assert!(registry.select(&toggles(&["derived"], &["base"])).is_err());
+        assert!(registry.select(&toggles(&["unknown"], &[])).is_err());
+    }
+}
@@ -0,0 +1,484 @@
+//! Patching of the instructions of a compiled program (`patch` command).
+//!
+//! A patch spec lists the instructions to replace, by instruction pointer (the `<pc>` of
+//! `lbb_<pc>` in the disassembly), and the ranges to turn into no-ops:
+//!
+//! ```json
+//! {
+//!   "patches": [
+//!     { "pc": "lbb_42", "instructions": ["b700000000000000", "9500000000000000"], "replace": 3 },
+//!     { "pc": "lbb_97", "asm": ["mov64 r0, 0", "ja lbb_110"] },
+//!     { "nop": "120-125", "comment": "skip the owner check" }
+//!   ]
+//! }
+//! ```
+//!
+//! Instructions are hex encoded (8 bytes, 16 for `lddw`), or written in the syntax of the
+//! disassembly and assembled at their address (see [`crate::reverse::asm`]). The replaced slots
+//! left over are
+//! filled with no-ops (`ja +0`), and a range ending in the middle of an `lddw` is extended to
+//! cover it. The instructions with a dynamic relocation (the `lddw` of addresses and the `call`
+//! of syscalls) can only be replaced by an instruction of the same opcode, the relocation being
+//! applied to its immediate by the loader, and a relocated call can only be replaced by a call
+//! to the same function. The patched program is loaded and verified like the
+//! validator does before being written.
+
+use anyhow::{anyhow, Context, Result};
+use log::{debug, warn};
+use serde::Deserialize;
+use serde_json::Value;
+use solana_sbpf::{ebpf, elf::Executable, static_analysis::Analysis, verifier::RequisiteVerifier};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use test_utils::TestContextObject;
+
+use crate::reverse::asm::{assemble, AsmTarget};
+use crate::reverse::elf_sections::{elf_sections, ElfSection};
+use crate::reverse::function_filter::PcRange;
+use crate::reverse::new_loader;
+use crate::reverse::raw_input::parse_offset;
+use crate::reverse::sanitize::disassemble_instruction;
+
+/// `ja +0`, the no-op filling the replaced slots.
+const NOP: [u8; ebpf::INSN_SIZE] = [ebpf::JA, 0, 0, 0, 0, 0, 0, 0];
+
+/// A patch spec (see the module documentation).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PatchSpec {
+    pub patches: Vec<PatchEntry>,
+}
+
+/// A patch: instructions replaced at `pc`, or a `nop` range.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PatchEntry {
+    /// Instruction pointer of the first replaced instruction: `42`, `"0x2a"` or `"lbb_42"`.
+    #[serde(default)]
+    pub pc: Option<Value>,
+    /// New instructions, hex encoded.
+    #[serde(default)]
+    pub instructions: Vec<String>,
+    /// New instructions, in the syntax of the disassembly.
+    #[serde(default)]
+    pub asm: Vec<String>,
+    /// Number of instruction slots replaced, by default those of the new instructions.
+    #[serde(default)]
+    pub replace: Option<usize>,
+    /// Range of instruction pointers replaced by no-ops, `"<start>-<end>"` (both included).
+    #[serde(default)]
+    pub nop: Option<String>,
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+/// A patch applied to the program, with the instructions before and after.
+#[derive(Debug, Clone)]
+pub struct AppliedPatch {
+    /// Instruction pointers of the replaced slots.
+    pub start: usize,
+    pub end: usize,
+    pub comment: Option<String>,
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+}
+
+/// A patch resolved on the program: the slots `start..start + slots.len()` are overwritten.
+struct ResolvedPatch {
+    start: usize,
+    slots: Vec<[u8; ebpf::INSN_SIZE]>,
+    comment: Option<String>,
+}
+
+impl PatchSpec {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content =
+            std::fs::read_to_string(path).with_context(|| format!("Reading {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid patch spec {}", path.display()))
+    }
+}
+
+/// Parses an instruction pointer given as a number or as `"42"`, `"0x2a"` or `"lbb_42"`.
+fn parse_pc(pc: &Value) -> Result<usize> {
+    match pc {
+        Value::Number(number) => number
+            .as_u64()
+            .map(|pc| pc as usize)
+            .ok_or_else(|| anyhow!("Invalid pc {}", number)),
+        Value::String(pc) => parse_offset(pc.trim().strip_prefix("lbb_").unwrap_or(pc)),
+        other => Err(anyhow!("Invalid pc {}", other)),
+    }
+}
+
+/// Decodes the hex encoded instructions into instruction slots, checking that each one is a
+/// whole instruction (16 bytes for `lddw`, 8 for the others).
+fn decode_instructions(
+    instructions: &[String],
+    lddw_enabled: bool,
+) -> Result<Vec<[u8; ebpf::INSN_SIZE]>> {
+    let mut slots = vec![];
+    for instruction in instructions {
+        let digits: String = instruction
+            .trim()
+            .trim_start_matches("0x")
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        let bytes = hex::decode(&digits)
+            .map_err(|e| anyhow!("Invalid instruction '{}': {}", instruction, e))?;
+        let is_lddw = lddw_enabled && bytes.first() == Some(&ebpf::LD_DW_IMM);
+        let expected = if is_lddw { 16 } else { ebpf::INSN_SIZE };
+        if bytes.len() != expected {
+            return Err(anyhow!(
+                "Invalid instruction '{}': {} bytes instead of {}",
+                instruction,
+                bytes.len(),
+                expected
+            ));
+        }
+        if is_lddw && bytes[ebpf::INSN_SIZE] != 0 {
+            return Err(anyhow!(
+                "Invalid instruction '{}': the second half of an lddw has a 0 opcode",
+                instruction
+            ));
+        }
+        slots.extend(
+            bytes
+                .chunks(ebpf::INSN_SIZE)
+                .map(|slot| <[u8; ebpf::INSN_SIZE]>::try_from(slot).unwrap()),
+        );
+    }
+    Ok(slots)
+}
+
+/// Resolves an entry of the spec on a `.text` of `text_slots` slots, `lddw_halves` being the
+/// second slots of its `lddw` instructions.
+fn resolve(
+    entry: &PatchEntry,
+    text_slots: usize,
+    lddw_halves: &HashSet<usize>,
+    target: &AsmTarget,
+) -> Result<ResolvedPatch> {
+    let (start, mut end, new) = match (&entry.pc, &entry.nop) {
+        (Some(pc), None) => {
+            let start = parse_pc(pc)?;
+            let new = if entry.asm.is_empty() {
+                decode_instructions(&entry.instructions, !target.sbpf_version.disable_lddw())?
+            } else if entry.instructions.is_empty() {
+                let assembled = assemble(&entry.asm.join("\n"), start, target)?;
+                decode_instructions(
+                    &assembled
+                        .iter()
+                        .map(|insn| hex::encode(&insn.bytes))
+                        .collect::<Vec<_>>(),
+                    !target.sbpf_version.disable_lddw(),
+                )?
+            } else {
+                return Err(anyhow!("pc {} has both instructions and asm", start));
+            };
+            if new.is_empty() {
+                return Err(anyhow!("No instruction given for pc {}", start));
+            }
+            let replaced = entry.replace.unwrap_or(new.len());
+            if replaced < new.len() {
+                return Err(anyhow!(
+                    "{} instruction slots don't fit in the {} replaced at pc {}",
+                    new.len(),
+                    replaced,
+                    start
+                ));
+            }
+            (start, start + replaced, new)
+        }
+        (None, Some(nop)) => {
+            if !entry.instructions.is_empty() || !entry.asm.is_empty() || entry.replace.is_some() {
+                return Err(anyhow!("A nop range takes no instructions"));
+            }
+            let range = PcRange::parse(nop)?;
+            (range.start, range.end + 1, vec![])
+        }
+        _ => return Err(anyhow!("A patch has either a pc or a nop range")),
+    };
+
+    if end > text_slots {
+        return Err(anyhow!(
+            "Patch at pc {} goes past the end of the program ({} instructions)",
+            start,
+            text_slots
+        ));
+    }
+    if lddw_halves.contains(&start) {
+        return Err(anyhow!(
+            "pc {} is the second half of the lddw at pc {}",
+            start,
+            start - 1
+        ));
+    }
+    if lddw_halves.contains(&end) {
+        warn!(
+            "Patch at pc {} extended to pc {} to cover the whole lddw at pc {}",
+            start,
+            end,
+            end - 1
+        );
+        end += 1;
+    }
+
+    let mut slots = new;
+    slots.resize(end - start, NOP);
+    Ok(ResolvedPatch {
+        start,
+        slots,
+        comment: entry.comment.clone(),
+    })
+}
+
+/// Returns the opcodes of the instructions of `.text` patched by a dynamic relocation, by
+/// instruction pointer.
+fn relocated_instructions(elf: &[u8], text: &ElfSection) -> HashMap<usize, u8> {
+    const SHT_REL: u32 = 9;
+    const REL_SIZE: usize = 16;
+    let text_range = text.address..text.address + text.size as u64;
+    elf_sections(elf)
+        .iter()
+        .filter(|section| section.sh_type == SHT_REL)
+        .filter_map(|section| elf.get(section.offset..section.offset + section.size))
+        .flat_map(|relocations| relocations.chunks_exact(REL_SIZE))
+        .map(|relocation| u64::from_le_bytes(relocation[..8].try_into().unwrap()))
+        .filter(|address| text_range.contains(address))
+        .map(|address| {
+            let pc = (address - text.address) as usize / ebpf::INSN_SIZE;
+            (pc, elf[text.offset + pc * ebpf::INSN_SIZE])
+        })
+        .collect()
+}
+
+/// Disassembles the instructions of `analysis` in `start..end`.
+fn disassemble_range(analysis: &Analysis, start: usize, end: usize) -> Vec<String> {
+    analysis
+        .instructions
+        .iter()
+        .enumerate()
+        .filter(|(_, insn)| (start..end).contains(&insn.ptr))
+        .map(|(pc, insn)| disassemble_instruction(analysis, insn, pc))
+        .collect()
+}
+
+/// Applies the patches of `spec` to the ELF `elf`.
+///
+/// # Returns
+///
+/// The patched ELF and the applied patches, in address order.
+///
+/// # Errors
+///
+/// If an entry is invalid, two patches overlap, a patch changes the opcode of a relocated
+/// instruction, or the patched program doesn't pass the verifier.
+pub fn apply_patches(elf: &[u8], spec: &PatchSpec) -> Result<(Vec<u8>, Vec<AppliedPatch>)> {
+    let text = elf_sections(elf)
+        .into_iter()
+        .find(|section| section.name == ".text")
+        .ok_or_else(|| anyhow!("No .text section in the program"))?;
+    let original = Executable::<TestContextObject>::from_elf(elf, new_loader(true)?)
+        .map_err(|e| anyhow!("Failed to load the program: {:?}", e))?;
+    let lddw_enabled = !original.get_sbpf_version().disable_lddw();
+    let before = Analysis::from_executable(&original)
+        .map_err(|e| anyhow!("Failed to analyze the program: {:?}", e))?;
+    let target = AsmTarget::from_analysis(&before, original.get_sbpf_version());
+    let original_verified = original.verify::<RequisiteVerifier>();
+    if let Err(e) = &original_verified {
+        warn!("The original program doesn't pass the verifier: {}", e);
+    }
+
+    let text_bytes = &elf[text.offset..text.offset + text.size];
+    let text_slots = text.size / ebpf::INSN_SIZE;
+    let lddw_halves: HashSet<usize> = if lddw_enabled {
+        (0..text_slots)
+            .filter(|slot| text_bytes[slot * ebpf::INSN_SIZE] == ebpf::LD_DW_IMM)
+            .map(|slot| slot + 1)
+            .collect()
+    } else {
+        HashSet::new()
+    };
+
+    let mut patches = spec
+        .patches
+        .iter()
+        .map(|entry| resolve(entry, text_slots, &lddw_halves, &target))
+        .collect::<Result<Vec<_>>>()?;
+    patches.sort_by_key(|patch| patch.start);
+    for pair in patches.windows(2) {
+        if pair[0].start + pair[0].slots.len() > pair[1].start {
+            return Err(anyhow!(
+                "The patches at pc {} and pc {} overlap",
+                pair[0].start,
+                pair[1].start
+            ));
+        }
+    }
+
+    let relocated = relocated_instructions(elf, &text);
+    let loaded_text = original.get_text_bytes().1;
+    for patch in &mut patches {
+        for (pc, slot) in (patch.start..).zip(patch.slots.iter_mut()) {
+            let Some(opcode) = relocated.get(&pc) else {
+                continue;
+            };
+            if *opcode != slot[0] {
+                return Err(anyhow!(
+                    "pc {} has a dynamic relocation and can only be replaced by an instruction \
+                     of the same opcode (0x{:02x})",
+                    pc,
+                    opcode
+                ));
+            }
+            if *opcode == ebpf::CALL_IMM {
+                // The callee is given by the relocation: the call is kept as in the file, once
+                // checked that it is the same as in the loaded program
+                let range = pc * ebpf::INSN_SIZE..(pc + 1) * ebpf::INSN_SIZE;
+                let (file, loaded) = (&text_bytes[range.clone()], &loaded_text[range]);
+                if slot[4..] != file[4..] && slot[4..] != loaded[4..] {
+                    return Err(anyhow!(
+                        "pc {} is linked by a dynamic relocation ({}) and can only be replaced \
+                         by the same call",
+                        pc,
+                        disassemble_range(&before, pc, pc + 1).join("")
+                    ));
+                }
+                slot.copy_from_slice(file);
+            }
+        }
+    }
+
+    let mut patched = elf.to_vec();
+    for patch in &patches {
+        let offset = text.offset + patch.start * ebpf::INSN_SIZE;
+        patched[offset..offset + patch.slots.len() * ebpf::INSN_SIZE]
+            .copy_from_slice(&patch.slots.concat());
+    }
+
+    let executable = Executable::<TestContextObject>::from_elf(&patched, new_loader(true)?)
+        .map_err(|e| anyhow!("The patched program can't be loaded: {:?}", e))?;
+    if original_verified.is_ok() {
+        executable
+            .verify::<RequisiteVerifier>()
+            .map_err(|e| anyhow!("The patched program doesn't pass the verifier: {}", e))?;
+    }
+    debug!("Patched program loaded and verified");
+
+    let after = Analysis::from_executable(&executable)
+        .map_err(|e| anyhow!("Failed to analyze the patched program: {:?}", e))?;
+    let applied = patches
+        .into_iter()
+        .map(|patch| {
+            let end = patch.start + patch.slots.len();
+            AppliedPatch {
+                start: patch.start,
+                end,
+                comment: patch.comment,
+                before: disassemble_range(&before, patch.start, end),
+                after: disassemble_range(&after, patch.start, end),
+            }
+        })
+        .collect();
+    Ok((patched, applied))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sbpf::program::SBPFVersion;
+
+    const PROGRAM: &str = "test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so";
+
+    fn patch_program(entries: Vec<PatchEntry>) -> Result<(Vec<u8>, Vec<AppliedPatch>)> {
+        let elf = std::fs::read(PROGRAM).unwrap();
+        apply_patches(&elf, &PatchSpec { patches: entries })
+    }
+
+    #[test]
+    fn test_replace_and_pad() {
+        let (patched, applied) = patch_program(vec![PatchEntry {
+            pc: Some(Value::from("lbb_3")),
+            instructions: vec!["b700000000000000".to_string()],
+            replace: Some(2),
+            ..PatchEntry::default()
+        }])
+        .unwrap();
+        let elf = std::fs::read(PROGRAM).unwrap();
+        assert_eq!(patched.len(), elf.len());
+        assert_eq!(applied[0].before, vec!["mov64 r2, r1", "mov64 r1, r10"]);
+        assert_eq!(applied[0].after, vec!["mov64 r0, 0", "ja lbb_5"]);
+    }
+
+    #[test]
+    fn test_lddw_bounds() {
+        // pc 19 is an lddw, its second half is pc 20
+        let halves = HashSet::from([20]);
+        let target = AsmTarget::new(SBPFVersion::V0);
+        let nop = |range: &str| PatchEntry {
+            nop: Some(range.to_string()),
+            ..PatchEntry::default()
+        };
+        let patch = resolve(&nop("18-19"), 2044, &halves, &target).unwrap();
+        assert_eq!((patch.start, patch.slots.len()), (18, 3));
+        assert!(resolve(&nop("20-21"), 2044, &halves, &target).is_err());
+
+        // the lddw is relocated, a nop can't replace it
+        assert!(patch_program(vec![nop("18-19")]).is_err());
+        assert!(patch_program(vec![nop("21-22")]).is_ok());
+    }
+
+    #[test]
+    fn test_asm_patches() {
+        let (_, applied) = patch_program(vec![PatchEntry {
+            pc: Some(Value::from("lbb_3")),
+            asm: vec!["mov64 r0, 0".to_string(), "ja lbb_91".to_string()],
+            ..PatchEntry::default()
+        }])
+        .unwrap();
+        assert_eq!(applied[0].after, vec!["mov64 r0, 0", "ja lbb_91"]);
+
+        // pc 45 calls sol_log_ through a relocation
+        let syscall = |name: &str| PatchEntry {
+            pc: Some(Value::from(45)),
+            asm: vec![format!("syscall {}", name)],
+            ..PatchEntry::default()
+        };
+        let elf = std::fs::read(PROGRAM).unwrap();
+        let (patched, _) = patch_program(vec![syscall("sol_log_")]).unwrap();
+        assert_eq!(patched, elf);
+        assert!(patch_program(vec![syscall("sol_log_64_")]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_patches() {
+        // unknown opcode, rejected by the verifier
+        assert!(patch_program(vec![PatchEntry {
+            pc: Some(Value::from(3)),
+            instructions: vec!["ff00000000000000".to_string()],
+            ..PatchEntry::default()
+        }])
+        .is_err());
+        // not a whole instruction
+        assert!(patch_program(vec![PatchEntry {
+            pc: Some(Value::from(3)),
+            instructions: vec!["b7000000".to_string()],
+            ..PatchEntry::default()
+        }])
+        .is_err());
+        // overlapping
+        assert!(patch_program(vec![
+            PatchEntry {
+                nop: Some("3-5".to_string()),
+                ..PatchEntry::default()
+            },
+            PatchEntry {
+                nop: Some("5-6".to_string()),
+                ..PatchEntry::default()
+            },
+        ])
+        .is_err());
+    }
+}
@@ -0,0 +1,529 @@
+//! Reconstruction of the seeds passed to `sol_create_program_address` and
+//! `sol_try_find_program_address` (`pda_seeds.out`).
+//!
+//! Both syscalls take in `r1` an array of `(address, length)` seed slices, in `r2` the number
+//! of seeds and in `r3` the address of the program id. The array is almost always built on
+//! the stack right before the call, so each function is walked with a forward data-flow
+//! analysis tracking constants, stack addresses (relative to the frame's `r10`) and the
+//! 8-byte values stored in the stack. At each call site, the seed slices are then read back
+//! from the tracked stack, and the constant ones are resolved from `.rodata`.
+
+use solana_sbpf::{program::SBPFVersion, static_analysis::Analysis};
+use solana_program::pubkey::Pubkey;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::reverse::dataflow::{functions, parse_immediate, parse_register};
+use crate::reverse::signatures::function_hash;
+use crate::reverse::stable_ids::FunctionLocation;
+use crate::reverse::utils::{format_bytes, get_rodata_region_start, is_rodata_address};
+use crate::reverse::OutputFile;
+
+/// Syscalls deriving a program address.
+const PDA_SYSCALLS: &[&str] = &["sol_create_program_address", "sol_try_find_program_address"];
+
+/// Seeds are limited to 16 by the runtime (`MAX_SEEDS`).
+const MAX_SEEDS: u64 = 16;
+
+/// Abstract value of a register or a stack slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Value {
+    Const(u64),
+    /// Address relative to the `r10` of the current frame.
+    Stack(i64),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct State {
+    registers: BTreeMap<u8, Value>,
+    /// 8-byte values stored in the stack, by offset from `r10`.
+    stack: BTreeMap<i64, Value>,
+}
+
+impl State {
+    fn function_entry() -> Self {
+        Self {
+            registers: BTreeMap::from([(10, Value::Stack(0))]),
+            stack: BTreeMap::new(),
+        }
+    }
+
+    /// Keeps the registers and stack slots with the same value in both states.
+    fn meet(&self, other: &Self) -> Self {
+        fn intersect<K: Ord + Copy>(
+            a: &BTreeMap<K, Value>,
+            b: &BTreeMap<K, Value>,
+        ) -> BTreeMap<K, Value> {
+            a.iter()
+                .filter(|(key, value)| b.get(key) == Some(value))
+                .map(|(key, value)| (*key, *value))
+                .collect()
+        }
+        Self {
+            registers: intersect(&self.registers, &other.registers),
+            stack: intersect(&self.stack, &other.stack),
+        }
+    }
+
+    fn set_register(&mut self, reg: u8, value: Option<Value>) {
+        match value {
+            Some(value) => self.registers.insert(reg, value),
+            None => self.registers.remove(&reg),
+        };
+    }
+
+    /// Forgets the stack slots overlapping `[offset, offset + size)`.
+    fn clobber_stack(&mut self, offset: i64, size: i64) {
+        self.stack
+            .retain(|slot, _| *slot + 8 <= offset || *slot >= offset + size);
+    }
+}
+
+/// One seed slice of a derivation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Seed {
+    /// Constant bytes from `.rodata`.
+    Constant { address: u64, bytes: Vec<u8> },
+    /// Buffer in the stack of the calling function; `value` is the 8-byte constant stored
+    /// there, if any (e.g. a `u64` seed serialized with `to_le_bytes`).
+    Stack {
+        offset: i64,
+        length: Option<u64>,
+        value: Option<u64>,
+    },
+    /// Slice whose address could not be resolved (accounts data, heap, computed, ...).
+    Unknown { length: Option<u64> },
+}
+
+impl std::fmt::Display for Seed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let length = |length: &Option<u64>| match length {
+            Some(length) => format!("{} bytes", length),
+            None => "unknown length".to_string(),
+        };
+        match self {
+            Seed::Constant { address, bytes } => write!(
+                f,
+                "{} (rodata {:#x}, {} bytes)",
+                format_bytes(bytes),
+                address,
+                bytes.len()
+            ),
+            Seed::Stack {
+                offset,
+                length: seed_length,
+                value,
+            } => {
+                let sign = if *offset < 0 { '-' } else { '+' };
+                write!(
+                    f,
+                    "stack r10{}{:#x}, {}",
+                    sign,
+                    offset.unsigned_abs(),
+                    length(seed_length)
+                )?;
+                if let Some(value) = value {
+                    write!(f, " = {:#x}", value)?;
+                }
+                if *seed_length == Some(32) {
+                    write!(f, " (pubkey?)")?;
+                }
+                Ok(())
+            }
+            Seed::Unknown {
+                length: seed_length,
+            } => write!(f, "unknown, {}", length(seed_length)),
+        }
+    }
+}
+
+/// A `sol_create_program_address` / `sol_try_find_program_address` call site.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PdaDerivation {
+    /// Index of the `syscall` instruction.
+    pub pc: usize,
+    pub function: usize,
+    pub function_label: String,
+    /// Hash of the function and offset of the syscall in it, stable across builds.
+    pub location: FunctionLocation,
+    pub syscall: String,
+    /// Number of seeds, if constant.
+    pub seed_count: Option<u64>,
+    /// The seeds that could be located (all of them when `seed_count` is known).
+    pub seeds: Vec<Seed>,
+    /// The program id, when it is a constant of `.rodata`.
+    pub program_id: Option<Pubkey>,
+}
+
+/// Parses a memory operand (`[r10-0x40]`) into its base register and offset.
+fn parse_memory(operand: &str) -> Option<(u8, i64)> {
+    let inner = operand.strip_prefix('[')?.strip_suffix(']')?;
+    let split = inner.find(['+', '-']).unwrap_or(inner.len());
+    let (base, offset) = inner.split_at(split);
+    let offset = match offset.split_at(offset.len().min(1)) {
+        ("", _) => 0,
+        ("-", value) => -parse_immediate(value)?,
+        (_, value) => parse_immediate(value)?,
+    };
+    Some((parse_register(base)?, offset))
+}
+
+/// Size in bytes of a memory access, from the suffix of its mnemonic (`stxdw`, `ldxb`, ...).
+fn access_size(mnemonic: &str) -> i64 {
+    match mnemonic.chars().last() {
+        Some('b') => 1,
+        Some('h') => 2,
+        Some('w') if !mnemonic.ends_with("dw") => 4,
+        _ => 8,
+    }
+}
+
+/// Applies a disassembled instruction (e.g. `stxdw [r10-0x40], r1`) to the state.
+///
+/// # Returns
+///
+/// The name of the syscall if the instruction is one, so that callers can inspect its
+/// arguments in the state before the call.
+fn transfer<'a>(state: &mut State, desc: &'a str) -> Option<&'a str> {
+    let (mnemonic, operands) = desc.split_once(' ').unwrap_or((desc, ""));
+    let operands: Vec<&str> = operands.split(", ").map(str::trim).collect();
+
+    match mnemonic {
+        "syscall" | "call" | "callx" => {
+            // caller-saved registers
+            for reg in 0..=5 {
+                state.registers.remove(&reg);
+            }
+            return (mnemonic == "syscall").then_some(operands[0]);
+        }
+        "exit" | "return" => return None,
+        m if m.starts_with('j') => return None,
+        m if m.starts_with("st") => {
+            let (base, offset) = parse_memory(operands[0])?;
+            let Some(Value::Stack(base_offset)) = state.registers.get(&base).copied() else {
+                return None;
+            };
+            let slot = base_offset + offset;
+            let size = access_size(m);
+            state.clobber_stack(slot, size);
+            if size == 8 {
+                let value = match m {
+                    "stxdw" => parse_register(operands[1])
+                        .and_then(|reg| state.registers.get(&reg).copied()),
+                    _ => parse_immediate(operands[1]).map(|imm| Value::Const(imm as u64)),
+                };
+                if let Some(value) = value {
+                    state.stack.insert(slot, value);
+                }
+            }
+            return None;
+        }
+        _ => {}
+    }
+
+    let dst = parse_register(operands.first()?)?;
+    let src = operands.get(1).copied().unwrap_or("");
+    let src_value = match parse_register(src) {
+        Some(reg) => state.registers.get(&reg).copied(),
+        None => parse_immediate(src).map(|imm| Value::Const(imm as u64)),
+    };
+    let value = match mnemonic {
+        "mov64" | "lddw" => src_value,
+        "mov32" => match src_value {
+            Some(Value::Const(imm)) => Some(Value::Const(imm as u32 as u64)),
+            _ => None,
+        },
+        "hor64" => match (state.registers.get(&dst), src_value) {
+            (Some(Value::Const(low)), Some(Value::Const(high))) => {
+                Some(Value::Const((high << 32) | (low & 0xFFFF_FFFF)))
+            }
+            _ => None,
+        },
+        "add64" | "sub64" => {
+            let sign = if mnemonic == "add64" { 1 } else { -1 };
+            match (state.registers.get(&dst).copied(), src_value) {
+                (Some(Value::Stack(offset)), Some(Value::Const(imm))) => {
+                    Some(Value::Stack(offset + sign * imm as i64))
+                }
+                (Some(Value::Const(imm)), Some(Value::Stack(offset))) if sign == 1 => {
+                    Some(Value::Stack(offset + imm as i64))
+                }
+                (Some(Value::Const(a)), Some(Value::Const(b))) => {
+                    Some(Value::Const(a.wrapping_add((sign * b as i64) as u64)))
+                }
+                _ => None,
+            }
+        }
+        "ldxdw" => match parse_memory(src) {
+            Some((base, offset)) => match state.registers.get(&base) {
+                Some(Value::Stack(base_offset)) => {
+                    state.stack.get(&(base_offset + offset)).copied()
+                }
+                _ => None,
+            },
+            None => None,
+        },
+        _ => None,
+    };
+    state.set_register(dst, value);
+    None
+}
+
+/// Reads `length` bytes of `.rodata` at the virtual address `address`.
+fn read_rodata(
+    program: &[u8],
+    address: u64,
+    length: u64,
+    sbpf_version: SBPFVersion,
+) -> Option<Vec<u8>> {
+    if !is_rodata_address(address, sbpf_version) {
+        return None;
+    }
+    let start = (address - get_rodata_region_start(sbpf_version)) as usize;
+    program
+        .get(start..start.checked_add(length as usize)?)
+        .map(<[u8]>::to_vec)
+}
+
+/// Reconstructs the seeds and program id of a derivation from the state before the syscall.
+///
+/// # Returns
+///
+/// `(seed_count, seeds, program_id)`.
+fn reconstruct_seeds(
+    state: &State,
+    program: &[u8],
+    sbpf_version: SBPFVersion,
+) -> (Option<u64>, Vec<Seed>, Option<Pubkey>) {
+    let seed_count = match state.registers.get(&2) {
+        Some(Value::Const(count)) if *count <= MAX_SEEDS => Some(*count),
+        _ => None,
+    };
+
+    let mut seeds = Vec::new();
+    if let Some(Value::Stack(array)) = state.registers.get(&1) {
+        for index in 0..seed_count.unwrap_or(MAX_SEEDS) as i64 {
+            let address = state.stack.get(&(array + 16 * index));
+            let length = match state.stack.get(&(array + 16 * index + 8)) {
+                Some(Value::Const(length)) => Some(*length),
+                _ => None,
+            };
+            let seed = match address {
+                Some(Value::Const(address)) => {
+                    match length
+                        .and_then(|length| read_rodata(program, *address, length, sbpf_version))
+                    {
+                        Some(bytes) => Seed::Constant {
+                            address: *address,
+                            bytes,
+                        },
+                        None => Seed::Unknown { length },
+                    }
+                }
+                Some(Value::Stack(offset)) => Seed::Stack {
+                    offset: *offset,
+                    length,
+                    value: match state.stack.get(offset) {
+                        Some(Value::Const(value)) if length == Some(8) => Some(*value),
+                        _ => None,
+                    },
+                },
+                // without a known count, stop at the first slot not built here
+                None if seed_count.is_none() => break,
+                None => Seed::Unknown { length },
+            };
+            seeds.push(seed);
+        }
+    }
+
+    let program_id = match state.registers.get(&3) {
+        Some(Value::Const(address)) => read_rodata(program, *address, 32, sbpf_version)
+            .and_then(|bytes| Pubkey::try_from(bytes.as_slice()).ok()),
+        _ => None,
+    };
+    (seed_count, seeds, program_id)
+}
+
+/// Finds the program address derivations of every function and reconstructs their seeds.
+///
+/// # Arguments
+///
+/// * `program` - Raw bytes of the program (ELF), to read the constant seeds.
+/// * `analysis` - The `Analysis` structure of the program.
+/// * `sbpf_version` - The SBPF version, to locate `.rodata`.
+pub fn find_pda_derivations(
+    program: &[u8],
+    analysis: &Analysis,
+    sbpf_version: SBPFVersion,
+) -> Vec<PdaDerivation> {
+    let mut derivations = Vec::new();
+    for function in functions(analysis) {
+        let calls_pda_syscall = function.descriptions.values().flatten().any(|desc| {
+            desc.strip_prefix("syscall ")
+                .is_some_and(|name| PDA_SYSCALLS.contains(&name.trim()))
+        });
+        if !calls_pda_syscall {
+            continue;
+        }
+        let hash = function_hash(analysis, function.start, sbpf_version).0;
+
+        let block_states = function.block_states(
+            analysis,
+            State::function_entry(),
+            |state, desc| {
+                transfer(state, desc);
+            },
+            State::meet,
+        );
+
+        for node in &function.nodes {
+            let Some(mut state) = block_states.get(node).cloned() else {
+                // unreachable from the function entry
+                continue;
+            };
+            let instructions =
+                &analysis.instructions[analysis.cfg_nodes[node].instructions.clone()];
+            for (desc, insn) in function.descriptions[node].iter().zip(instructions) {
+                let before = state.clone();
+                let Some(syscall) = transfer(&mut state, desc) else {
+                    continue;
+                };
+                if !PDA_SYSCALLS.contains(&syscall) {
+                    continue;
+                }
+                let (seed_count, seeds, program_id) =
+                    reconstruct_seeds(&before, program, sbpf_version);
+                derivations.push(PdaDerivation {
+                    pc: insn.ptr,
+                    function: function.start,
+                    function_label: analysis.cfg_nodes[&function.start].label.clone(),
+                    location: FunctionLocation::new(&hash, function.start, insn.ptr),
+                    syscall: syscall.to_string(),
+                    seed_count,
+                    seeds,
+                    program_id,
+                });
+            }
+        }
+    }
+    derivations.sort_by_key(|derivation| derivation.pc);
+    derivations
+}
+
+/// Writes `pda_seeds.out`: every program address derivation with its reconstructed seeds.
+///
+/// # Arguments
+///
+/// * `derivations` - The derivations found by [`find_pda_derivations`].
+/// * `path` - Output directory.
+pub fn export_pda_seeds<P: AsRef<Path>>(
+    derivations: &[PdaDerivation],
+    path: P,
+) -> std::io::Result<()> {
+    let mut seeds_path = PathBuf::from(path.as_ref());
+    seeds_path.push(OutputFile::PdaSeeds.default_filename());
+    let mut output = File::create(seeds_path)?;
+
+    writeln!(output, "PDA derivations ({}):", derivations.len())?;
+    for derivation in derivations {
+        writeln!(
+            output,
+            "\n    lbb_{} (in {}, {}): {}",
+            derivation.pc, derivation.function_label, derivation.location, derivation.syscall
+        )?;
+        writeln!(
+            output,
+            "        seeds:      {}",
+            derivation
+                .seed_count
+                .map_or("unknown".to_string(), |count| count.to_string())
+        )?;
+        writeln!(
+            output,
+            "        program_id: {}",
+            derivation
+                .program_id
+                .map_or("not constant".to_string(), |program_id| program_id
+                    .to_string())
+        )?;
+        for (index, seed) in derivation.seeds.iter().enumerate() {
+            writeln!(output, "        seed[{}]:    {}", index, seed)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconstruct_seeds() {
+        // b"vault" at offset 0x10 of .rodata
+        let mut program = vec![0u8; 0x10];
+        program.extend_from_slice(b"vault");
+        let vault = get_rodata_region_start(SBPFVersion::V0) + 0x10;
+        let load_vault = format!("lddw r1, {:#x}", vault);
+
+        let mut state = State::function_entry();
+        for desc in [
+            // seed[0] = b"vault"
+            load_vault.as_str(),
+            "stxdw [r10-0x40], r1",
+            "mov64 r1, 5",
+            "stxdw [r10-0x38], r1",
+            // seed[1] = 32-byte buffer at r10-0x80
+            "mov64 r2, r10",
+            "add64 r2, -128",
+            "stxdw [r10-0x30], r2",
+            "stdw [r10-0x28], 32",
+            // seed[2] = u64 at r10-0x88
+            "stdw [r10-0x88], 7",
+            "mov64 r3, r10",
+            "add64 r3, -136",
+            "stxdw [r10-0x20], r3",
+            "stdw [r10-0x18], 8",
+            "mov64 r1, r10",
+            "add64 r1, -64",
+            "mov64 r2, 3",
+        ] {
+            assert_eq!(transfer(&mut state, desc), None);
+        }
+
+        let (seed_count, seeds, program_id) = reconstruct_seeds(&state, &program, SBPFVersion::V0);
+        assert_eq!(seed_count, Some(3));
+        assert_eq!(program_id, None);
+        assert_eq!(
+            seeds,
+            vec![
+                Seed::Constant {
+                    address: vault,
+                    bytes: b"vault".to_vec()
+                },
+                Seed::Stack {
+                    offset: -0x80,
+                    length: Some(32),
+                    value: None
+                },
+                Seed::Stack {
+                    offset: -0x88,
+                    length: Some(8),
+                    value: Some(7)
+                },
+            ]
+        );
+        assert_eq!(
+            seeds[0].to_string(),
+            format!("b\"vault\" (rodata {:#x}, 5 bytes)", vault)
+        );
+        assert_eq!(seeds[2].to_string(), "stack r10-0x88, 8 bytes = 0x7");
+
+        assert_eq!(
+            transfer(&mut state, "syscall sol_try_find_program_address"),
+            Some("sol_try_find_program_address")
+        );
+        assert!(!state.registers.contains_key(&1));
+    }
+}
@@ -0,0 +1,303 @@
+//! Detection of stack, heap and input pointers passed to logging syscalls.
+//!
+//! `sol_log_64_` prints its arguments as integers: when one of them holds an address (e.g.
+//! `mov64 r1, r10` left over from debugging), the memory layout of the program ends up in
+//! the transaction logs. This pass tracks, within each function, which registers point into
+//! the stack, the heap or the input region and reports the syscalls receiving them.
+//!
+//! The tracking is a forward data-flow analysis over the CFG of each function, on the
+//! disassembled mnemonics (see [`crate::reverse::dataflow`]). Memory is not tracked: a pointer spilled to the stack and reloaded
+//! is lost, so the pass favors precision over recall.
+
+use serde::Serialize;
+use solana_sbpf::{ebpf, program::SBPFVersion, static_analysis::Analysis};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::reverse::dataflow::{functions, parse_immediate, parse_register};
+use crate::reverse::signatures::function_hash;
+use crate::reverse::stable_ids::FunctionLocation;
+use crate::reverse::OutputFile;
+use crate::state::sast_state::{Certainty, Severity, SynRuleMetadata};
+
+/// Logging syscalls printing their arguments as values, with the registers holding them.
+const VALUE_LOGGING_SYSCALLS: &[(&str, &[u8])] = &[("sol_log_64_", &[1, 2, 3, 4, 5])];
+
+/// Memory region a register points into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PointerKind {
+    Stack,
+    Heap,
+    Input,
+}
+
+impl PointerKind {
+    /// Returns the region containing the virtual address `addr`, if it is a writable one.
+    pub fn from_address(addr: u64) -> Option<Self> {
+        if addr >= ebpf::MM_INPUT_START {
+            Some(Self::Input)
+        } else if addr >= ebpf::MM_HEAP_START {
+            Some(Self::Heap)
+        } else if addr >= ebpf::MM_STACK_START {
+            Some(Self::Stack)
+        } else {
+            None
+        }
+    }
+}
+
+/// Registers known to hold a pointer; the others hold scalars or unknown values.
+type PointerState = BTreeMap<u8, PointerKind>;
+
+/// A pointer passed to a logging syscall.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PointerLeak {
+    /// Index of the `syscall` instruction.
+    pub pc: usize,
+    /// Start and label of the function containing it.
+    pub function: usize,
+    pub function_label: String,
+    /// Hash of the function and offset of the syscall in it, stable across builds.
+    #[serde(flatten)]
+    pub location: FunctionLocation,
+    pub syscall: String,
+    pub register: u8,
+    pub pointer: PointerKind,
+}
+
+impl PointerLeak {
+    /// One-line description of the finding, as printed under its location.
+    pub fn description(&self) -> String {
+        format!(
+            "r{} holds a pointer into the {} region and is logged by {}",
+            self.register,
+            match self.pointer {
+                PointerKind::Stack => "stack",
+                PointerKind::Heap => "heap",
+                PointerKind::Input => "input",
+            },
+            self.syscall
+        )
+    }
+}
+
+/// Metadata of the built-in rule, in the same form as the SAST rules.
+pub fn pointer_leak_rule_metadata() -> SynRuleMetadata {
+    SynRuleMetadata {
+        version: "0.1.0".to_string(),
+        author: "sol-azy".to_string(),
+        name: "Pointer leak to log syscall".to_string(),
+        severity: Severity::Low,
+        certainty: Certainty::Medium,
+        description:
+            "A pointer into the stack, heap or input region is printed by a logging syscall, \
+            leaking the memory layout of the program. Often a debugging leftover."
+                .to_string(),
+        ..SynRuleMetadata::default()
+    }
+}
+
+/// Applies a disassembled instruction (e.g. `add64 r1, -64`) to the pointer state.
+///
+/// # Returns
+///
+/// The name of the syscall if the instruction is one, so that callers can check its
+/// arguments against the state before the call.
+fn transfer<'a>(state: &mut PointerState, desc: &'a str) -> Option<&'a str> {
+    let (mnemonic, operands) = desc.split_once(' ').unwrap_or((desc, ""));
+    let operands: Vec<&str> = operands.split(',').map(str::trim).collect();
+
+    match mnemonic {
+        "syscall" | "call" | "callx" => {
+            // caller-saved registers
+            for reg in 0..=5 {
+                state.remove(&reg);
+            }
+            return (mnemonic == "syscall").then_some(operands[0]);
+        }
+        "exit" | "return" => return None,
+        m if m.starts_with('j') || m.starts_with("st") => return None,
+        _ => {}
+    }
+
+    let dst = operands.first().copied().and_then(parse_register)?;
+    let src = operands.get(1).copied();
+    let src_register = src.and_then(parse_register);
+    let kind = match mnemonic {
+        "mov64" => src_register.and_then(|reg| state.get(&reg).copied()),
+        "add64" => match src_register {
+            Some(reg) => match (state.get(&dst), state.get(&reg)) {
+                (Some(kind), None) | (None, Some(kind)) => Some(*kind),
+                _ => None,
+            },
+            None => state.get(&dst).copied(),
+        },
+        "sub64" => match src_register {
+            // the difference of two pointers is a scalar
+            Some(reg) if state.contains_key(&reg) => None,
+            _ => state.get(&dst).copied(),
+        },
+        "lddw" => src
+            .and_then(parse_immediate)
+            .and_then(|imm| PointerKind::from_address(imm as u64)),
+        _ => None,
+    };
+    match kind {
+        Some(kind) => state.insert(dst, kind),
+        None => state.remove(&dst),
+    };
+    None
+}
+
+/// Registers of a logging syscall's arguments holding a pointer in `state`.
+fn leaked_registers(state: &PointerState, syscall: &str) -> Vec<(u8, PointerKind)> {
+    VALUE_LOGGING_SYSCALLS
+        .iter()
+        .filter(|(name, _)| *name == syscall)
+        .flat_map(|(_, registers)| registers.iter())
+        .filter_map(|reg| state.get(reg).map(|kind| (*reg, *kind)))
+        .collect()
+}
+
+/// Keeps the registers holding the same pointer kind in both states.
+fn meet(current: &PointerState, incoming: &PointerState) -> PointerState {
+    current
+        .iter()
+        .filter(|(reg, kind)| incoming.get(reg) == Some(kind))
+        .map(|(reg, kind)| (*reg, *kind))
+        .collect()
+}
+
+/// Finds the pointers passed to logging syscalls in every function of the program.
+///
+/// The SBPF version is the one of the program, for the hashes of the functions.
+pub fn find_pointer_leaks(analysis: &Analysis, sbpf_version: SBPFVersion) -> Vec<PointerLeak> {
+    let mut leaks = Vec::new();
+    for function in functions(analysis) {
+        let mut entry_state = PointerState::from([(10, PointerKind::Stack)]);
+        if function.start == analysis.entrypoint {
+            entry_state.insert(1, PointerKind::Input);
+        }
+        let block_states = function.block_states(
+            analysis,
+            entry_state,
+            |state, desc| {
+                transfer(state, desc);
+            },
+            meet,
+        );
+
+        // only hashed when the function leaks a pointer
+        let mut hash = None;
+        for node in &function.nodes {
+            let Some(mut state) = block_states.get(node).cloned() else {
+                // unreachable from the function entry
+                continue;
+            };
+            let instructions =
+                &analysis.instructions[analysis.cfg_nodes[node].instructions.clone()];
+            for (desc, insn) in function.descriptions[node].iter().zip(instructions) {
+                let before = state.clone();
+                let Some(syscall) = transfer(&mut state, desc) else {
+                    continue;
+                };
+                for (register, pointer) in leaked_registers(&before, syscall) {
+                    let hash: &String = hash.get_or_insert_with(|| {
+                        function_hash(analysis, function.start, sbpf_version).0
+                    });
+                    leaks.push(PointerLeak {
+                        pc: insn.ptr,
+                        function: function.start,
+                        function_label: analysis.cfg_nodes[&function.start].label.clone(),
+                        location: FunctionLocation::new(hash, function.start, insn.ptr),
+                        syscall: syscall.to_string(),
+                        register,
+                        pointer,
+                    });
+                }
+            }
+        }
+    }
+    leaks.sort_by_key(|leak| (leak.pc, leak.register));
+    leaks
+}
+
+/// Writes `findings.json`: the built-in bytecode findings, grouped by rule like the SAST results.
+///
+/// # Arguments
+///
+/// * `leaks` - The pointer leaks found by [`find_pointer_leaks`].
+/// * `path` - Output directory.
+pub fn export_findings<P: AsRef<Path>>(leaks: &[PointerLeak], path: P) -> std::io::Result<()> {
+    #[derive(Serialize)]
+    struct BytecodeResult<'a> {
+        rule_metadata: SynRuleMetadata,
+        matches: &'a [PointerLeak],
+    }
+
+    let mut findings_path = PathBuf::from(path.as_ref());
+    findings_path.push(OutputFile::Findings.default_filename());
+    let mut output = File::create(findings_path)?;
+
+    let results = [BytecodeResult {
+        rule_metadata: pointer_leak_rule_metadata(),
+        matches: leaks,
+    }];
+    writeln!(
+        output,
+        "{}",
+        serde_json::to_string_pretty(&results).map_err(std::io::Error::other)?
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_and_leaks() {
+        let mut state = PointerState::from([(10, PointerKind::Stack), (1, PointerKind::Input)]);
+        for desc in [
+            "mov64 r2, r10",
+            "add64 r2, -64",
+            "mov64 r3, 8",
+            "add64 r3, r1",
+            "ldxdw r4, [r1+0x8]",
+            "lddw r5, 0x300000010",
+            "stxdw [r10-0x8], r2",
+        ] {
+            assert_eq!(transfer(&mut state, desc), None);
+        }
+        assert_eq!(state.get(&2), Some(&PointerKind::Stack));
+        assert_eq!(state.get(&3), Some(&PointerKind::Input));
+        assert_eq!(state.get(&4), None);
+        assert_eq!(state.get(&5), Some(&PointerKind::Heap));
+
+        let mut leaks = leaked_registers(&state, "sol_log_64_");
+        leaks.sort();
+        assert_eq!(
+            leaks,
+            vec![
+                (1, PointerKind::Input),
+                (2, PointerKind::Stack),
+                (3, PointerKind::Input),
+                (5, PointerKind::Heap)
+            ]
+        );
+        // sol_log_ prints the pointed string, not the pointer
+        assert!(leaked_registers(&state, "sol_log_").is_empty());
+
+        assert_eq!(
+            transfer(&mut state, "syscall sol_log_64_"),
+            Some("sol_log_64_")
+        );
+        assert_eq!(state.keys().copied().collect::<Vec<_>>(), vec![10]);
+
+        transfer(&mut state, "mov64 r1, r10");
+        transfer(&mut state, "sub64 r1, r10");
+        assert_eq!(state.get(&1), None);
+    }
+}
@@ -0,0 +1,363 @@
+//! Queries over the artifacts of a previous `reverse` run (`query` command).
+//!
+//! The blocks of the program are read from `cfg.json` (see [`CfgGraph`]) or, when the run only
+//! produced a disassembly, rebuilt from `disassembly.json` (`--disass-format json`), whose
+//! `label` annotations mark the starts of the blocks. A [`Query`] then keeps the blocks matching
+//! all of its filters: the syscalls and functions called, the strings loaded, the number of
+//! instructions and the function holding the block.
+
+use anyhow::{anyhow, Context, Result};
+use regex::Regex;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use crate::reverse::cfg_graph::CfgGraph;
+use crate::reverse::sanitize::sanitize_text;
+use crate::reverse::OutputFile;
+
+/// A basic block of the program, with the facts the queries filter on.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryBlock {
+    /// Instruction pointer of the block.
+    pub start: usize,
+    /// Instruction pointer of the function holding the block.
+    pub function_start: usize,
+    pub function: String,
+    pub instructions: usize,
+    /// Immediate strings loaded by the block (`b"..."`).
+    pub strings: Vec<String>,
+    /// Syscalls and functions called by the block.
+    pub calls: Vec<String>,
+}
+
+impl QueryBlock {
+    /// Records the callee of an instruction, if it is a call (`call <name>` or `syscall <name>`).
+    fn add_call(&mut self, instruction: &str) {
+        let callee = instruction
+            .strip_prefix("call ")
+            .or_else(|| instruction.strip_prefix("syscall "))
+            .and_then(|operands| operands.split_whitespace().next());
+        if let Some(callee) = callee {
+            if !self.calls.iter().any(|known| known == callee) {
+                self.calls.push(callee.to_string());
+            }
+        }
+    }
+}
+
+/// The blocks of a reverse run, in the order of the artifact they were read from.
+#[derive(Debug, Clone, Default)]
+pub struct Artifacts {
+    /// The artifact read.
+    pub source: PathBuf,
+    pub blocks: Vec<QueryBlock>,
+}
+
+impl Artifacts {
+    /// Loads the blocks of the reverse run directory `dir`, from `cfg.json`, or
+    /// `disassembly.json` without a CFG.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let cfg_path = dir.join(OutputFile::CfgJson.default_filename());
+        if cfg_path.is_file() {
+            let graph = CfgGraph::load(&cfg_path)
+                .with_context(|| format!("Failed to read {}", cfg_path.display()))?;
+            return Ok(Self::from_cfg(&graph, cfg_path));
+        }
+        let disassembly_path = dir.join(OutputFile::DisassemblyJson.default_filename());
+        if disassembly_path.is_file() {
+            let content = std::fs::read_to_string(&disassembly_path)
+                .with_context(|| format!("Failed to read {}", disassembly_path.display()))?;
+            let records: Vec<Value> = serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse {}", disassembly_path.display()))?;
+            return Ok(Self::from_disassembly(&records, disassembly_path));
+        }
+        Err(anyhow!(
+            "Neither {} nor {} found in {}: run `reverse` with `--mode cfg` or `--disass-format json`",
+            OutputFile::CfgJson.default_filename(),
+            OutputFile::DisassemblyJson.default_filename(),
+            dir.display()
+        ))
+    }
+
+    /// Reads the blocks of the clusters of a CFG.
+    fn from_cfg(graph: &CfgGraph, source: PathBuf) -> Self {
+        let blocks = graph
+            .clusters
+            .iter()
+            .flat_map(|cluster| {
+                cluster.blocks.iter().map(|block| {
+                    let mut query_block = QueryBlock {
+                        start: block.start,
+                        function_start: cluster.start,
+                        function: cluster.label.clone(),
+                        instructions: block.instructions.len(),
+                        strings: block.strings.clone(),
+                        calls: block.syscalls.clone(),
+                    };
+                    for instruction in &block.instructions {
+                        query_block.add_call(instruction);
+                    }
+                    query_block
+                })
+            })
+            .collect();
+        Self { source, blocks }
+    }
+
+    /// Rebuilds the blocks from the records of a JSON disassembly: a block starts at each
+    /// `label` annotation, a function at each change of the `function` annotation.
+    fn from_disassembly(records: &[Value], source: PathBuf) -> Self {
+        let mut blocks: Vec<QueryBlock> = Vec::new();
+        for record in records {
+            let pc = record["pc"].as_u64().unwrap_or_default() as usize;
+            let annotations = &record["annotations"];
+            let function = annotations["function"].as_str().unwrap_or_default();
+            let same_function = blocks.last().is_some_and(|last| last.function == function);
+            if blocks.is_empty() || !same_function || !annotations["label"].is_null() {
+                let function_start = match blocks.last() {
+                    Some(last) if same_function => last.function_start,
+                    _ => pc,
+                };
+                blocks.push(QueryBlock {
+                    start: pc,
+                    function_start,
+                    function: function.to_string(),
+                    ..QueryBlock::default()
+                });
+            }
+            let block = blocks.last_mut().unwrap();
+            block.instructions += 1;
+            if let Some(string) = annotations["string"].as_str() {
+                if !block.strings.iter().any(|known| known == string) {
+                    block.strings.push(string.to_string());
+                }
+            }
+            let instruction = format!(
+                "{} {}",
+                record["mnemonic"].as_str().unwrap_or_default(),
+                record["operands"].as_str().unwrap_or_default()
+            );
+            block.add_call(&instruction);
+        }
+        Self { source, blocks }
+    }
+}
+
+/// Filters of a query, all of which a block has to match.
+#[derive(Debug, Default)]
+pub struct Query {
+    /// Name of a syscall or function the block calls.
+    pub calls: Option<Regex>,
+    /// Immediate string the block loads.
+    pub strings: Option<Regex>,
+    /// Minimum number of instructions of the block.
+    pub min_instructions: Option<usize>,
+    /// Name of the function holding the block.
+    pub function: Option<Regex>,
+}
+
+/// A block matching a query, with what matched.
+#[derive(Debug, PartialEq, Eq)]
+pub struct QueryMatch<'a> {
+    pub block: &'a QueryBlock,
+    /// The calls and strings matching the filters.
+    pub matched: Vec<&'a str>,
+}
+
+impl Query {
+    /// Builds a query from the filters of the command line.
+    ///
+    /// # Errors
+    ///
+    /// If a pattern is not a valid regex, or if no filter is given.
+    pub fn new(
+        calls: Option<&str>,
+        strings: Option<&str>,
+        min_instructions: Option<usize>,
+        function: Option<&str>,
+    ) -> Result<Self> {
+        let regex = |pattern: Option<&str>| {
+            pattern
+                .map(|pattern| {
+                    Regex::new(pattern).with_context(|| format!("Invalid pattern '{}'", pattern))
+                })
+                .transpose()
+        };
+        let query = Self {
+            calls: regex(calls)?,
+            strings: regex(strings)?,
+            min_instructions,
+            function: regex(function)?,
+        };
+        if query.calls.is_none()
+            && query.strings.is_none()
+            && query.min_instructions.is_none()
+            && query.function.is_none()
+        {
+            return Err(anyhow!(
+                "No filter given: use --calls, --strings, --min-instructions or --function"
+            ));
+        }
+        Ok(query)
+    }
+
+    /// Returns the blocks matching all the filters, in the order of the artifacts.
+    pub fn run<'a>(&self, artifacts: &'a Artifacts) -> Vec<QueryMatch<'a>> {
+        let matching = |regex: &Option<Regex>, values: &'a [String]| -> Option<Vec<&'a str>> {
+            match regex {
+                None => Some(Vec::new()),
+                Some(regex) => {
+                    let found: Vec<&str> = values
+                        .iter()
+                        .map(String::as_str)
+                        .filter(|value| regex.is_match(value))
+                        .collect();
+                    (!found.is_empty()).then_some(found)
+                }
+            }
+        };
+        artifacts
+            .blocks
+            .iter()
+            .filter(|block| {
+                self.min_instructions
+                    .is_none_or(|min| block.instructions >= min)
+                    && self
+                        .function
+                        .as_ref()
+                        .is_none_or(|regex| regex.is_match(&block.function))
+            })
+            .filter_map(|block| {
+                let mut matched = matching(&self.calls, &block.calls)?;
+                matched.extend(matching(&self.strings, &block.strings)?);
+                Some(QueryMatch { block, matched })
+            })
+            .collect()
+    }
+}
+
+/// Renders the matches, one per line: `lbb_<pc>`, the function and what matched (or the number
+/// of instructions), separated by tabs. With `functions`, the functions holding the matches are
+/// listed instead, once each: `lbb_<pc>` and the name. The names and strings are sanitized, the
+/// artifacts possibly predating [`crate::reverse::sanitize`].
+pub fn render_matches(matches: &[QueryMatch], functions: bool) -> String {
+    let mut output = String::new();
+    if functions {
+        let mut seen = BTreeSet::new();
+        for found in matches {
+            if seen.insert(found.block.function_start) {
+                output.push_str(&format!(
+                    "lbb_{}\t{}\n",
+                    found.block.function_start,
+                    sanitize_text(&found.block.function)
+                ));
+            }
+        }
+        return output;
+    }
+    for found in matches {
+        let details = if found.matched.is_empty() {
+            format!("{} instructions", found.block.instructions)
+        } else {
+            found.matched.join(", ")
+        };
+        output.push_str(&format!(
+            "lbb_{}\t{}\t{}\n",
+            found.block.start,
+            sanitize_text(&found.block.function),
+            sanitize_text(&details)
+        ));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_query_artifacts() {
+        let graph: CfgGraph = serde_json::from_value(json!({
+            "clusters": [{
+                "start": 10,
+                "label": "process_instruction",
+                "blocks": [
+                    {
+                        "start": 10,
+                        "instructions": ["lddw r1, 0x100004000 --> b\"vault_seed\"", "mov64 r2, 10", "call derive_vault"],
+                        "strings": ["b\"vault_seed\""]
+                    },
+                    {
+                        "start": 13,
+                        "instructions": ["syscall sol_invoke_signed_rust", "exit"],
+                        "syscalls": ["sol_invoke_signed_rust"]
+                    }
+                ]
+            }, {
+                "start": 40,
+                "label": "derive_vault",
+                "blocks": [{ "start": 40, "instructions": ["mov64 r0, 0", "exit"] }]
+            }],
+            "edges": []
+        }))
+        .unwrap();
+        let artifacts = Artifacts::from_cfg(&graph, PathBuf::from("cfg.json"));
+        assert_eq!(artifacts.blocks[0].calls, vec!["derive_vault"]);
+
+        let query = Query::new(Some("sol_invoke_signed"), None, None, None).unwrap();
+        assert_eq!(
+            render_matches(&query.run(&artifacts), true),
+            "lbb_10\tprocess_instruction\n"
+        );
+        let query = Query::new(None, Some("seed"), None, None).unwrap();
+        assert_eq!(
+            render_matches(&query.run(&artifacts), false),
+            "lbb_10\tprocess_instruction\tb\"vault_seed\"\n"
+        );
+        let query = Query::new(None, None, Some(2), Some("^derive")).unwrap();
+        assert_eq!(
+            render_matches(&query.run(&artifacts), false),
+            "lbb_40\tderive_vault\t2 instructions\n"
+        );
+        assert!(Query::new(None, None, None, None).is_err());
+        assert!(Query::new(Some("("), None, None, None).is_err());
+
+        // the same blocks rebuilt from a JSON disassembly
+        let record = |pc: usize, function: &str, label: bool, mnemonic: &str, operands: &str| {
+            let mut annotations = json!({ "function": function });
+            if label {
+                annotations["label"] = json!(format!("lbb_{}", pc));
+            }
+            json!({ "pc": pc, "mnemonic": mnemonic, "operands": operands, "annotations": annotations })
+        };
+        let mut records = vec![
+            record(10, "process_instruction", true, "lddw", "r1, 0x100004000"),
+            record(12, "process_instruction", false, "call", "derive_vault"),
+            record(
+                13,
+                "process_instruction",
+                true,
+                "syscall",
+                "sol_invoke_signed_rust",
+            ),
+            record(14, "process_instruction", false, "exit", ""),
+            record(40, "derive_vault", true, "exit", ""),
+        ];
+        records[0]["annotations"]["string"] = json!("b\"vault_seed\"");
+        let rebuilt = Artifacts::from_disassembly(&records, PathBuf::from("disassembly.json"));
+        let starts: Vec<(usize, usize, usize)> = rebuilt
+            .blocks
+            .iter()
+            .map(|block| (block.start, block.function_start, block.instructions))
+            .collect();
+        assert_eq!(starts, vec![(10, 10, 2), (13, 10, 2), (40, 40, 1)]);
+        let query = Query::new(Some("sol_invoke_signed|derive"), None, None, None).unwrap();
+        assert_eq!(
+            render_matches(&query.run(&rebuilt), false),
+            "lbb_10\tprocess_instruction\tderive_vault\n\
+             lbb_13\tprocess_instruction\tsol_invoke_signed_rust\n"
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! Loading of raw instruction buffers (`reverse --raw`), for programs only available as a memory
+//! dump, a hex dump or a base64 blob instead of a valid ELF.
+//!
+//! The buffer is decoded, the instructions are taken from `--text-offset`, and an executable is
+//! built from them with `Executable::new_from_text_bytes` for the given SBPF version. There is no
+//! symbol, relocation or function registry: the functions are only discovered from the calls, so
+//! the disassembly and CFG are best-effort.
+
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose, Engine};
+use log::{debug, warn};
+use solana_sbpf::{
+    ebpf::INSN_SIZE,
+    elf::Executable,
+    program::{BuiltinProgram, FunctionRegistry, SBPFVersion},
+};
+use std::sync::Arc;
+use test_utils::TestContextObject;
+
+/// Encoding of a raw input file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RawFormat {
+    /// Hex dump or base64 if the file parses as one, raw bytes otherwise.
+    #[default]
+    Auto,
+    Bin,
+    Hex,
+    Base64,
+}
+
+impl RawFormat {
+    pub fn parse(format: &str) -> Result<Self> {
+        match format {
+            "auto" => Ok(RawFormat::Auto),
+            "bin" => Ok(RawFormat::Bin),
+            "hex" => Ok(RawFormat::Hex),
+            "base64" => Ok(RawFormat::Base64),
+            other => Err(anyhow!("Unknown raw input format: {}", other)),
+        }
+    }
+}
+
+/// Options of a raw (non-ELF) input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawInput {
+    pub format: RawFormat,
+    /// Offset of the first instruction in the decoded buffer.
+    pub text_offset: usize,
+    pub sbpf_version: SBPFVersion,
+}
+
+/// Parses `v0` to `v3` (or `0` to `3`).
+#[cfg(feature = "cli")]
+pub fn parse_sbpf_version(version: &str) -> Result<SBPFVersion> {
+    match version.trim().trim_start_matches(['v', 'V']) {
+        "0" => Ok(SBPFVersion::V0),
+        "1" => Ok(SBPFVersion::V1),
+        "2" => Ok(SBPFVersion::V2),
+        "3" => Ok(SBPFVersion::V3),
+        _ => Err(anyhow!(
+            "Unknown SBPF version '{}' (expected v0 to v3)",
+            version
+        )),
+    }
+}
+
+/// Parses a decimal or `0x` prefixed hexadecimal offset.
+pub fn parse_offset(offset: &str) -> Result<usize> {
+    let offset = offset.trim();
+    let parsed = match offset
+        .strip_prefix("0x")
+        .or_else(|| offset.strip_prefix("0X"))
+    {
+        Some(hex) => usize::from_str_radix(hex, 16),
+        None => offset.parse(),
+    };
+    parsed.map_err(|_| anyhow!("Invalid offset '{}'", offset))
+}
+
+/// Decodes a hex dump: plain hex bytes (optionally `0x` prefixed and separated by spaces,
+/// commas or newlines), or `xxd` output (`00000010: b701 0000 ...  ascii`).
+fn decode_hex_dump(text: &str) -> Option<Vec<u8>> {
+    let mut digits = String::new();
+    for line in text.lines() {
+        let mut line = line.trim();
+        // `xxd` offset column and ASCII column
+        if let Some((offset, rest)) = line.split_once(": ") {
+            if offset.chars().all(|c| c.is_ascii_hexdigit()) {
+                line = rest.split_once("  ").map_or(rest, |(hex, _ascii)| hex);
+            }
+        }
+        for token in line.split(|c: char| c.is_whitespace() || c == ',') {
+            let token = token
+                .strip_prefix("0x")
+                .or_else(|| token.strip_prefix("0X"))
+                .unwrap_or(token);
+            if !token.chars().all(|c| c.is_ascii_hexdigit()) {
+                return None;
+            }
+            digits.push_str(token);
+        }
+    }
+    if digits.is_empty() || !digits.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Decodes a base64 blob, possibly split over several lines.
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let blob: String = text.split_whitespace().collect();
+    if blob.is_empty() {
+        return None;
+    }
+    general_purpose::STANDARD.decode(blob).ok()
+}
+
+/// Decodes the content of a raw input file.
+pub fn decode_raw_input(content: &[u8], format: RawFormat) -> Result<Vec<u8>> {
+    let text = std::str::from_utf8(content).ok();
+    match format {
+        RawFormat::Bin => Ok(content.to_vec()),
+        RawFormat::Hex => text
+            .and_then(decode_hex_dump)
+            .ok_or_else(|| anyhow!("The input is not a valid hex dump")),
+        RawFormat::Base64 => text
+            .and_then(decode_base64)
+            .ok_or_else(|| anyhow!("The input is not valid base64")),
+        RawFormat::Auto => {
+            if let Some(bytes) = text.and_then(decode_hex_dump) {
+                debug!("Raw input decoded as a hex dump");
+                Ok(bytes)
+            } else if let Some(bytes) = text.and_then(decode_base64) {
+                debug!("Raw input decoded as base64");
+                Ok(bytes)
+            } else {
+                debug!("Raw input read as raw bytes");
+                Ok(content.to_vec())
+            }
+        }
+    }
+}
+
+/// Builds an executable from the instructions of a raw buffer.
+///
+/// # Arguments
+///
+/// * `buffer` - The decoded input.
+/// * `raw` - The raw input options (text offset, SBPF version).
+/// * `loader` - The loader with the registered syscalls.
+///
+/// # Returns
+///
+/// The executable, whose entrypoint is the first instruction.
+pub fn executable_from_raw(
+    buffer: &[u8],
+    raw: &RawInput,
+    loader: Arc<BuiltinProgram<TestContextObject>>,
+) -> Result<Executable<TestContextObject>> {
+    let text = buffer.get(raw.text_offset..).ok_or_else(|| {
+        anyhow!(
+            "Text offset {} is beyond the end of the input ({} bytes)",
+            raw.text_offset,
+            buffer.len()
+        )
+    })?;
+    let trailing = text.len() % INSN_SIZE;
+    if trailing != 0 {
+        warn!(
+            "Ignoring the last {} byte(s) of the input, not a whole instruction",
+            trailing
+        );
+    }
+    let text = &text[..text.len() - trailing];
+    if text.is_empty() {
+        return Err(anyhow!("No instruction in the raw input"));
+    }
+    Executable::new_from_text_bytes(text, loader, raw.sbpf_version, FunctionRegistry::default())
+        .map_err(|e| anyhow!("Failed to construct executable from raw bytes: {:?}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_raw_input() {
+        let bytes = [
+            0xb7, 0x00, 0x00, 0x00, 0x2a, 0x00, 0x00, 0x00, 0x95, 0, 0, 0, 0, 0, 0, 0,
+        ];
+
+        let hex = "b7 00 00 00 2a 00 00 00\n0x95,0x00,0x00,0x00,0x00,0x00,0x00,0x00\n";
+        assert_eq!(
+            decode_raw_input(hex.as_bytes(), RawFormat::Auto).unwrap(),
+            bytes
+        );
+        let xxd = "00000000: b700 0000 2a00 0000 9500 0000 0000 0000  ....*...........\n";
+        assert_eq!(
+            decode_raw_input(xxd.as_bytes(), RawFormat::Hex).unwrap(),
+            bytes
+        );
+
+        let base64 = general_purpose::STANDARD.encode(bytes);
+        assert_eq!(
+            decode_raw_input(base64.as_bytes(), RawFormat::Auto).unwrap(),
+            bytes
+        );
+        assert_eq!(decode_raw_input(&bytes, RawFormat::Auto).unwrap(), bytes);
+        assert!(decode_raw_input(b"zz", RawFormat::Hex).is_err());
+
+        assert_eq!(parse_offset("0x10").unwrap(), 16);
+        assert_eq!(parse_offset("120").unwrap(), 120);
+        assert_eq!(parse_sbpf_version("v2").unwrap(), SBPFVersion::V2);
+        assert!(parse_sbpf_version("v9").is_err());
+    }
+}
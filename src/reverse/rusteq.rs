@@ -1,6 +1,28 @@
 use solana_sbpf::ebpf::{self, Insn};
 use solana_sbpf::program::SBPFVersion;
 
+/// How the Rust equivalent of the instructions is shown in the disassembly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RustEqMode {
+    /// In a column next to the assembly.
+    #[default]
+    Column,
+    /// Not shown (`--no-rusteq`).
+    Hidden,
+    /// Only the Rust equivalent, one line per pc prefixed with it (`--rusteq-only`).
+    Only,
+}
+
+impl RustEqMode {
+    pub fn from_flags(no_rusteq: bool, rusteq_only: bool) -> Self {
+        match (no_rusteq, rusteq_only) {
+            (true, _) => RustEqMode::Hidden,
+            (false, true) => RustEqMode::Only,
+            (false, false) => RustEqMode::Column,
+        }
+    }
+}
+
 /// Return the Rust equivalent of an SBPF instruction if available
 pub fn translate_to_rust(insn: &ebpf::Insn, sbpf_version: SBPFVersion) -> Option<String> {
     // Handle version-specific opcodes (conflicts and version-exclusive instructions)
@@ -35,6 +57,20 @@ pub fn translate_to_rust(insn: &ebpf::Insn, sbpf_version: SBPFVersion) -> Option
             ebpf::SREM64_IMM => srem64_imm(insn),
             ebpf::SREM64_REG => srem64_reg(insn),
 
+            // V2+ memory instruction classes (moved into the 32/64-bit arithmetic classes)
+            ebpf::LD_1B_REG => load_reg(insn, "u8"),
+            ebpf::LD_2B_REG => load_reg(insn, "u16"),
+            ebpf::LD_4B_REG => load_reg(insn, "u32"),
+            ebpf::LD_8B_REG => load_reg(insn, "u64"),
+            ebpf::ST_1B_IMM => store_imm(insn, "u8"),
+            ebpf::ST_2B_IMM => store_imm(insn, "u16"),
+            ebpf::ST_4B_IMM => store_imm(insn, "u32"),
+            ebpf::ST_8B_IMM => store_imm(insn, "u64"),
+            ebpf::ST_1B_REG => store_reg(insn, "u8"),
+            ebpf::ST_2B_REG => store_reg(insn, "u16"),
+            ebpf::ST_4B_REG => store_reg(insn, "u32"),
+            ebpf::ST_8B_REG => store_reg(insn, "u64"),
+
             // Not a version-specific instruction, try common instructions
             _ => return translate_common(insn, sbpf_version),
         }
@@ -79,6 +115,20 @@ pub fn translate_to_rust(insn: &ebpf::Insn, sbpf_version: SBPFVersion) -> Option
             ebpf::JSLE32_IMM => jsle32_imm(insn),
             ebpf::JSLE32_REG => jsle32_reg(insn),
 
+            // V1-only memory instruction classes (moved in V2+)
+            ebpf::LD_B_REG => load_reg(insn, "u8"),
+            ebpf::LD_H_REG => load_reg(insn, "u16"),
+            ebpf::LD_W_REG => load_reg(insn, "u32"),
+            ebpf::LD_DW_REG => load_reg(insn, "u64"),
+            ebpf::ST_B_IMM => store_imm(insn, "u8"),
+            ebpf::ST_H_IMM => store_imm(insn, "u16"),
+            ebpf::ST_W_IMM => store_imm(insn, "u32"),
+            ebpf::ST_DW_IMM => store_imm(insn, "u64"),
+            ebpf::ST_B_REG => store_reg(insn, "u8"),
+            ebpf::ST_H_REG => store_reg(insn, "u16"),
+            ebpf::ST_W_REG => store_reg(insn, "u32"),
+            ebpf::ST_DW_REG => store_reg(insn, "u64"),
+
             // Not a version-specific instruction, try common instructions
             _ => return translate_common(insn, sbpf_version),
         }
@@ -87,6 +137,33 @@ pub fn translate_to_rust(insn: &ebpf::Insn, sbpf_version: SBPFVersion) -> Option
     Some(versioned)
 }
 
+/// Return the immediate of an instruction using it as a plain value: moved into a register,
+/// stored to memory or compared for (in)equality. Such values are the ones worth naming
+/// (e.g. error codes), unlike offsets, shifts or arithmetic operands.
+pub fn value_immediate(insn: &ebpf::Insn, sbpf_version: SBPFVersion) -> Option<i64> {
+    let is_store_imm = if sbpf_version.move_memory_instruction_classes() {
+        matches!(
+            insn.opc,
+            ebpf::ST_1B_IMM | ebpf::ST_2B_IMM | ebpf::ST_4B_IMM | ebpf::ST_8B_IMM
+        )
+    } else {
+        matches!(
+            insn.opc,
+            ebpf::ST_B_IMM | ebpf::ST_H_IMM | ebpf::ST_W_IMM | ebpf::ST_DW_IMM
+        )
+    };
+    match insn.opc {
+        ebpf::MOV32_IMM
+        | ebpf::MOV64_IMM
+        | ebpf::JEQ32_IMM
+        | ebpf::JEQ64_IMM
+        | ebpf::JNE32_IMM
+        | ebpf::JNE64_IMM => Some(insn.imm),
+        _ if is_store_imm => Some(insn.imm),
+        _ => None,
+    }
+}
+
 /// Translate instructions that are stable across SBPF versions
 fn translate_common(insn: &ebpf::Insn, sbpf_version: SBPFVersion) -> Option<String> {
     let result = match insn.opc {
@@ -135,8 +212,17 @@ fn translate_common(insn: &ebpf::Insn, sbpf_version: SBPFVersion) -> Option<Stri
         ebpf::MOV64_REG => mov64_reg(insn),
 
         // === Load/Store ===
+        // Note: LD_*_REG and ST_* opcodes depend on the version (handled in version-specific block)
         ebpf::LD_DW_IMM => ld_dw_imm(insn, sbpf_version),
 
+        // === Calls and Exit ===
+        ebpf::CALL_IMM => call_imm(insn, sbpf_version),
+        ebpf::CALL_REG => call_reg(insn, sbpf_version),
+        // SYSCALL and EXIT share their opcode, EXIT is replaced by RETURN with static syscalls
+        ebpf::SYSCALL if sbpf_version.static_syscalls() => syscall(insn),
+        ebpf::EXIT if !sbpf_version.static_syscalls() => exit(),
+        ebpf::RETURN if sbpf_version.static_syscalls() => exit(),
+
         // === 32-bit Jump Instructions (valid in both V1 and V2+) ===
         ebpf::JA => ja(insn),
         ebpf::JEQ32_IMM => jeq32_imm(insn),
@@ -811,6 +897,82 @@ fn ld_dw_imm(insn: &Insn, sbpf_version: SBPFVersion) -> String {
     }
 }
 
+/// Formats the address `reg + off` of a memory access (e.g. `r10 - 0x48`)
+fn address(reg: u8, off: i16) -> String {
+    if off < 0 {
+        format!("r{} - {:#x}", reg, -(off as i32))
+    } else {
+        format!("r{} + {:#x}", reg, off)
+    }
+}
+
+fn load_reg(insn: &Insn, ty: &str) -> String {
+    format!(
+        "r{d} = *({a}) as {ty}   ///  r{d} = *((r{s} as i64).wrapping_add({o}) as *const {ty}) as u64",
+        a = address(insn.src, insn.off),
+        d = insn.dst,
+        s = insn.src,
+        o = insn.off
+    )
+}
+
+fn store_imm(insn: &Insn, ty: &str) -> String {
+    format!(
+        "*({a}) = {i} as {ty}   ///  *((r{d} as i64).wrapping_add({o}) as *mut {ty}) = {i}_i32 as {ty}",
+        a = address(insn.dst, insn.off),
+        d = insn.dst,
+        i = insn.imm,
+        o = insn.off
+    )
+}
+
+fn store_reg(insn: &Insn, ty: &str) -> String {
+    format!(
+        "*({a}) = r{s} as {ty}   ///  *((r{d} as i64).wrapping_add({o}) as *mut {ty}) = r{s} as {ty}",
+        a = address(insn.dst, insn.off),
+        d = insn.dst,
+        s = insn.src,
+        o = insn.off
+    )
+}
+
+fn call_imm(insn: &Insn, sbpf_version: SBPFVersion) -> String {
+    if sbpf_version.static_syscalls() {
+        // the immediate is relative to the next instruction
+        let target = (insn.ptr as i64).saturating_add(insn.imm).saturating_add(1);
+        format!("r0 = function_{}(r1, r2, r3, r4, r5)", target)
+    } else {
+        // the immediate is the key of a syscall or of a registered function
+        format!(
+            "r0 = fn_{k:#x}(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key {k:#x}",
+            k = insn.imm as u32
+        )
+    }
+}
+
+fn call_reg(insn: &Insn, sbpf_version: SBPFVersion) -> String {
+    let target = if sbpf_version.callx_uses_src_reg() {
+        insn.src as i64
+    } else {
+        insn.imm
+    };
+    format!(
+        "r0 = (r{t} as fn)(r1, r2, r3, r4, r5)   ///  pc = (r{t} - text_vaddr) / 8",
+        t = target
+    )
+}
+
+fn syscall(insn: &Insn) -> String {
+    format!(
+        "r0 = syscall_{k:#x}(r1, r2, r3, r4, r5)",
+        k = insn.imm as u32
+    )
+}
+
+fn exit() -> String {
+    "return r0".to_string()
+}
+
 fn ja(insn: &Insn) -> String {
     format!("if true {{ pc += {} }}", insn.off)
 }
@@ -1214,3 +1376,204 @@ fn jsle64_reg(insn: &Insn) -> String {
         o = insn.off
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sbpf::{
+        aligned_memory::AlignedMemory,
+        elf::Executable,
+        memory_region::{MemoryMapping, MemoryRegion},
+        program::{BuiltinProgram, FunctionRegistry},
+        vm::{Config, EbpfVm},
+    };
+    use std::sync::Arc;
+    use test_utils::TestContextObject;
+
+    fn insn(opc: u8, dst: u8, src: u8, off: i16, imm: i64) -> Insn {
+        Insn {
+            ptr: 0,
+            opc,
+            dst,
+            src,
+            off,
+            imm,
+        }
+    }
+
+    /// Runs the instructions with the interpreter and returns `r0`.
+    fn interpret(insns: &[Insn], sbpf_version: SBPFVersion) -> u64 {
+        let text: Vec<u8> = insns.iter().flat_map(|insn| insn.to_array()).collect();
+        let loader = Arc::new(BuiltinProgram::new_loader(Config::default()));
+        let executable = Executable::<TestContextObject>::new_from_text_bytes(
+            &text,
+            loader.clone(),
+            sbpf_version,
+            FunctionRegistry::default(),
+        )
+        .unwrap();
+        let config = executable.get_config();
+        let mut stack = AlignedMemory::<{ ebpf::HOST_ALIGN }>::zero_filled(config.stack_size());
+        let stack_len = stack.len();
+        let regions = vec![
+            executable.get_ro_region(),
+            MemoryRegion::new_writable(stack.as_slice_mut(), ebpf::MM_STACK_START),
+        ];
+        let memory_mapping = MemoryMapping::new(regions, config, sbpf_version).unwrap();
+        let mut context = TestContextObject::new(100);
+        let mut vm = EbpfVm::new(
+            loader,
+            sbpf_version,
+            &mut context,
+            memory_mapping,
+            stack_len,
+        );
+        let (_, result) = vm.execute_program(&executable, true);
+        Result::from(result).unwrap()
+    }
+
+    /// Checks the translation of the loads and stores of each width against the interpreter,
+    /// with the opcodes of `sbpf_version` given as `(bits, load, store imm, store reg)`.
+    fn check_memory(sbpf_version: SBPFVersion, opcodes: [(u32, u8, u8, u8); 4]) {
+        let (_, ld_dw_reg, _, st_dw_reg) = opcodes[3];
+        for (bits, ld, st_imm, st_reg) in opcodes {
+            let ty = format!("u{}", bits);
+            let mask = u64::MAX >> (64 - bits);
+
+            // ldx: zero extended read of the low bytes (little endian)
+            let load = insn(ld, 0, 10, -8, 0);
+            let program = [
+                insn(ebpf::MOV64_IMM, 1, 0, 0, -2),
+                insn(st_dw_reg, 10, 1, -8, 0),
+                load.clone(),
+                insn(ebpf::EXIT, 0, 0, 0, 0),
+            ];
+            assert_eq!(
+                translate_to_rust(&load, sbpf_version).unwrap(),
+                format!(
+                    "r0 = *(r10 - 0x8) as {ty}   ///  r0 = *((r10 as i64).wrapping_add(-8) as *const {ty}) as u64"
+                )
+            );
+            assert_eq!(
+                interpret(&program, sbpf_version),
+                (-2_i64 as u64) & mask,
+                "{}",
+                ty
+            );
+
+            // st: the immediate is sign extended, then truncated
+            let store = insn(st_imm, 10, 0, -8, -3);
+            let program = [
+                store.clone(),
+                insn(ld_dw_reg, 0, 10, -8, 0),
+                insn(ebpf::EXIT, 0, 0, 0, 0),
+            ];
+            assert_eq!(
+                translate_to_rust(&store, sbpf_version).unwrap(),
+                format!(
+                    "*(r10 - 0x8) = -3 as {ty}   ///  *((r10 as i64).wrapping_add(-8) as *mut {ty}) = -3_i32 as {ty}"
+                )
+            );
+            assert_eq!(
+                interpret(&program, sbpf_version),
+                (-3_i32 as i64 as u64) & mask,
+                "{}",
+                ty
+            );
+
+            // stx: truncated register
+            let store = insn(st_reg, 10, 1, 0x10, 0);
+            let program = [
+                insn(ebpf::MOV64_IMM, 1, 0, 0, -4),
+                insn(st_reg, 10, 1, -16, 0),
+                insn(ld_dw_reg, 0, 10, -16, 0),
+                insn(ebpf::EXIT, 0, 0, 0, 0),
+            ];
+            assert_eq!(
+                translate_to_rust(&store, sbpf_version).unwrap(),
+                format!(
+                    "*(r10 + 0x10) = r1 as {ty}   ///  *((r10 as i64).wrapping_add(16) as *mut {ty}) = r1 as {ty}"
+                )
+            );
+            assert_eq!(
+                interpret(&program, sbpf_version),
+                (-4_i64 as u64) & mask,
+                "{}",
+                ty
+            );
+        }
+    }
+
+    #[test]
+    fn test_memory_translations() {
+        check_memory(
+            SBPFVersion::V0,
+            [
+                (8, ebpf::LD_B_REG, ebpf::ST_B_IMM, ebpf::ST_B_REG),
+                (16, ebpf::LD_H_REG, ebpf::ST_H_IMM, ebpf::ST_H_REG),
+                (32, ebpf::LD_W_REG, ebpf::ST_W_IMM, ebpf::ST_W_REG),
+                (64, ebpf::LD_DW_REG, ebpf::ST_DW_IMM, ebpf::ST_DW_REG),
+            ],
+        );
+        check_memory(
+            SBPFVersion::V2,
+            [
+                (8, ebpf::LD_1B_REG, ebpf::ST_1B_IMM, ebpf::ST_1B_REG),
+                (16, ebpf::LD_2B_REG, ebpf::ST_2B_IMM, ebpf::ST_2B_REG),
+                (32, ebpf::LD_4B_REG, ebpf::ST_4B_IMM, ebpf::ST_4B_REG),
+                (64, ebpf::LD_8B_REG, ebpf::ST_8B_IMM, ebpf::ST_8B_REG),
+            ],
+        );
+        // the V2+ memory opcodes are multiplications in V0
+        assert!(
+            translate_to_rust(&insn(ebpf::LD_1B_REG, 0, 1, 0, 0), SBPFVersion::V0)
+                .unwrap()
+                .contains("wrapping_mul")
+        );
+    }
+
+    #[test]
+    fn test_call_and_exit_translations() {
+        let exit = insn(ebpf::EXIT, 0, 0, 0, 0);
+        let program = [insn(ebpf::MOV64_IMM, 0, 0, 0, 42), exit.clone()];
+        assert_eq!(
+            translate_to_rust(&exit, SBPFVersion::V0).unwrap(),
+            "return r0"
+        );
+        assert_eq!(interpret(&program, SBPFVersion::V0), 42);
+
+        // with static syscalls, EXIT is SYSCALL and RETURN exits
+        let ret = insn(ebpf::RETURN, 0, 0, 0, 0);
+        let program = [insn(ebpf::MOV64_IMM, 0, 0, 0, 42), ret.clone()];
+        assert_eq!(
+            translate_to_rust(&ret, SBPFVersion::V3).unwrap(),
+            "return r0"
+        );
+        assert_eq!(interpret(&program, SBPFVersion::V3), 42);
+        assert_eq!(translate_to_rust(&ret, SBPFVersion::V0), None);
+        assert_eq!(
+            translate_to_rust(&insn(ebpf::SYSCALL, 0, 0, 0, 0x207559bd), SBPFVersion::V3).unwrap(),
+            "r0 = syscall_0x207559bd(r1, r2, r3, r4, r5)"
+        );
+
+        // CALL_IMM: key of the callee before V3, relative to the next instruction since
+        let mut call = insn(ebpf::CALL_IMM, 0, 0, 0, -10);
+        call.ptr = 20;
+        assert_eq!(
+            translate_to_rust(&call, SBPFVersion::V3).unwrap(),
+            "r0 = function_11(r1, r2, r3, r4, r5)"
+        );
+        assert!(translate_to_rust(&call, SBPFVersion::V0)
+            .unwrap()
+            .starts_with("r0 = fn_0xfffffff6(r1, r2, r3, r4, r5)"));
+
+        // CALL_REG: target register in the immediate before V2, in src since
+        let callx = insn(ebpf::CALL_REG, 0, 3, 0, 5);
+        assert!(translate_to_rust(&callx, SBPFVersion::V0)
+            .unwrap()
+            .starts_with("r0 = (r5 as fn)"));
+        assert!(translate_to_rust(&callx, SBPFVersion::V2)
+            .unwrap()
+            .starts_with("r0 = (r3 as fn)"));
+    }
+}
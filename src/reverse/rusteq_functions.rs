@@ -0,0 +1,193 @@
+//! Per-function export of the Rust equivalent of the program (`rusteq/`).
+//!
+//! Each function is written to its own `rusteq/<label>.rs`, with an inferred signature and its
+//! basic block labels as comments, which is easier to navigate than the flat disassembly for
+//! programs with hundreds of functions.
+//!
+//! The arity of a function is guessed from the argument registers (`r1` to `r5`) it reads
+//! before writing them, scanning its instructions in address order: the highest such register
+//! gives the number of arguments.
+
+use solana_sbpf::{program::SBPFVersion, static_analysis::Analysis};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::reverse::function_filter::FunctionSelection;
+use crate::reverse::rusteq::translate_to_rust;
+use crate::reverse::sanitize::disassemble_instruction;
+use crate::reverse::syscalls::get_syscall_signature;
+use crate::reverse::OutputFile;
+
+/// Registers read and written by an instruction, from its disassembly (e.g. `add64 r1, r2`).
+///
+/// Calls clobber the scratch registers `r0` to `r5`, so they count as writes.
+fn register_effects(asm: &str) -> (Vec<u8>, Vec<u8>) {
+    let mut words = asm.splitn(2, ' ');
+    let mnemonic = words.next().unwrap_or_default();
+    let registers: Vec<u8> = words
+        .next()
+        .unwrap_or_default()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter_map(|token| token.strip_prefix('r')?.parse::<u8>().ok())
+        .filter(|register| *register <= 10)
+        .collect();
+    let clobbered = (0..=5).collect();
+
+    match mnemonic {
+        "call" | "syscall" => (vec![], clobbered),
+        "callx" => (registers, clobbered),
+        "exit" | "return" => (vec![0], vec![]),
+        "ja" => (vec![], vec![]),
+        // the destination is written, the other registers (source, address) are read
+        m if m.starts_with("mov") || m.starts_with("ld") => match registers.split_first() {
+            Some((dst, read)) => (read.to_vec(), vec![*dst]),
+            None => (vec![], vec![]),
+        },
+        // stores and conditional jumps only read
+        m if m.starts_with("st") || m.starts_with('j') => (registers, vec![]),
+        // arithmetic: `dst op= src`
+        _ => {
+            let written = registers.first().copied().into_iter().collect();
+            (registers, written)
+        }
+    }
+}
+
+/// Guesses the number of arguments of a function from the disassembly of its instructions.
+///
+/// # Returns
+///
+/// The index of the highest argument register (`r1` to `r5`) read before being written, or 0.
+pub fn infer_arity<S: AsRef<str>>(instructions: &[S]) -> usize {
+    let mut written = [false; 11];
+    let mut arity = 0;
+    for asm in instructions {
+        let (read, write) = register_effects(asm.as_ref());
+        for register in read {
+            let register = register as usize;
+            if (1..=5).contains(&register) && !written[register] {
+                arity = arity.max(register);
+            }
+        }
+        for register in write {
+            written[register as usize] = true;
+        }
+    }
+    arity
+}
+
+/// Turns a function label into a file name.
+fn file_name(label: &str) -> String {
+    let name: String = label
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}.rs", name)
+}
+
+/// Writes the Rust equivalent of each function to `rusteq/<label>.rs` in `path`.
+///
+/// # Arguments
+///
+/// * `analysis` - The `Analysis` structure of the program.
+/// * `sbpf_version` - The SBPF version from the executable.
+/// * `selection` - If given, only these functions are written.
+/// * `path` - Output directory.
+///
+/// # Returns
+///
+/// The number of functions written.
+pub fn export_rusteq_functions<P: AsRef<Path>>(
+    analysis: &Analysis,
+    sbpf_version: SBPFVersion,
+    selection: Option<&FunctionSelection>,
+    path: P,
+) -> std::io::Result<usize> {
+    let mut dir = PathBuf::from(path.as_ref());
+    dir.push(OutputFile::RustEqFunctions.default_filename());
+    std::fs::create_dir_all(&dir)?;
+
+    let starts: Vec<usize> = analysis.functions.keys().copied().collect();
+    let mut written = 0;
+    for (i, start) in starts.iter().enumerate() {
+        if selection.is_some_and(|selection| !selection.contains(*start)) {
+            continue;
+        }
+        let end = starts.get(i + 1).copied().unwrap_or(usize::MAX);
+        let instructions: Vec<(usize, String)> = analysis
+            .instructions
+            .iter()
+            .enumerate()
+            .filter(|(_, insn)| insn.ptr >= *start && insn.ptr < end)
+            .map(|(pc, insn)| (pc, disassemble_instruction(analysis, insn, pc)))
+            .collect();
+        let Some((last_pc, _)) = instructions.last() else {
+            continue;
+        };
+        let label = analysis
+            .cfg_nodes
+            .get(start)
+            .map_or_else(|| format!("function_{}", start), |node| node.label.clone());
+
+        let asm: Vec<&str> = instructions.iter().map(|(_, asm)| asm.as_str()).collect();
+        let arguments: Vec<String> = (1..=infer_arity(&asm))
+            .map(|register| format!("r{}: u64", register))
+            .collect();
+
+        let mut output = File::create(dir.join(file_name(&label)))?;
+        let end_ptr = analysis.instructions[*last_pc].ptr;
+        writeln!(output, "// {} (pc {} to {})", label, start, end_ptr)?;
+        writeln!(output, "fn {}({}) -> u64 {{", label, arguments.join(", "))?;
+        for (pc, asm) in &instructions {
+            let insn = &analysis.instructions[*pc];
+            if insn.ptr != *start {
+                if let Some(node) = analysis.cfg_nodes.get(&insn.ptr) {
+                    writeln!(output, "    // {}:", node.label)?;
+                }
+            }
+            let syscall_signature = asm
+                .strip_prefix("syscall ")
+                .and_then(|name| get_syscall_signature(name.trim()))
+                .map(str::to_string);
+            match syscall_signature.or_else(|| translate_to_rust(insn, sbpf_version)) {
+                Some(rust_eq) => writeln!(output, "    /* {:>5} */ {}", insn.ptr, rust_eq)?,
+                None => writeln!(output, "    /* {:>5} */ // {}", insn.ptr, asm)?,
+            }
+        }
+        writeln!(output, "}}")?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_arity() {
+        // entrypoint-like: reads r1 only
+        assert_eq!(infer_arity(&["mov64 r2, r1", "mov64 r1, r10", "exit"]), 1);
+        // r3 read before being written, r1 written first
+        assert_eq!(
+            infer_arity(&["mov64 r1, 0", "add64 r1, r3", "stxdw [r10-0x8], r2", "exit"]),
+            3
+        );
+        // arguments of a call are not the caller's
+        assert_eq!(
+            infer_arity(&["call function_12", "mov64 r0, r5", "exit"]),
+            0
+        );
+        assert_eq!(infer_arity(&["ldxdw r0, [r4+0x8]", "exit"]), 4);
+        assert_eq!(infer_arity(&["jgt r2, r10, lbb_4", "exit"]), 2);
+        assert_eq!(file_name("function_308"), "function_308.rs");
+        assert_eq!(file_name("<lambda>::call"), "_lambda___call.rs");
+    }
+}
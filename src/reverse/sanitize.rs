@@ -0,0 +1,215 @@
+//! Sanitization of the text read from the reversed program.
+//!
+//! The symbol and section names, the `security.txt` fields and the strings of a program are
+//! whatever bytes its author put there: ANSI escape sequences rewriting the terminal, newlines
+//! breaking a DOT file, bidirectional overrides reordering a listing, or invalid UTF-8. They are
+//! sanitized once, where they become text ([`sanitize_lossy`], [`sanitize_labels`],
+//! [`disassemble_instruction`]): the control and invisible formatting characters are replaced by
+//! escapes (`\x1b`, `\n`, `\u{202e}`). Each output then escapes the text for its format:
+//!
+//! | Output                                   | Escaping                                       |
+//! | ---------------------------------------- | ---------------------------------------------- |
+//! | Listings, tables, logs, TUI              | [`sanitize_text`]                              |
+//! | DOT HTML labels (CFG, ownership graph)   | [`html_escape`]                                |
+//! | DOT quoted strings (tooltips, labels)    | [`dot_escape`]                                 |
+//! | Bytes of the program (`b"..."`)          | [`format_bytes`](crate::reverse::utils::format_bytes) |
+//! | JSON                                     | `serde_json`                                   |
+
+use solana_sbpf::{ebpf::Insn, static_analysis::Analysis};
+use std::borrow::Cow;
+use std::fmt::Write as _;
+
+/// Whether `c` is escaped: the C0 and C1 controls, `DEL`, and the invisible formatting
+/// characters (zero-width spaces and joiners, bidirectional marks, overrides and isolates,
+/// byte order mark).
+fn is_unsafe(c: char) -> bool {
+    c.is_control()
+        || matches!(
+            c,
+            '\u{200b}'..='\u{200f}' | '\u{202a}'..='\u{202e}' | '\u{2060}'..='\u{2069}' | '\u{feff}'
+        )
+}
+
+/// Replaces the control and invisible formatting characters of `text` by escapes: `\n`, `\r`,
+/// `\t`, `\xNN` for the other ASCII ones and `\u{NNNN}` beyond.
+///
+/// The escapes are printable, so sanitizing a sanitized text leaves it as is.
+pub fn sanitize_text(text: &str) -> Cow<'_, str> {
+    if !text.chars().any(is_unsafe) {
+        return Cow::Borrowed(text);
+    }
+    let mut sanitized = String::with_capacity(text.len() + 8);
+    for c in text.chars() {
+        match c {
+            '\n' => sanitized.push_str("\\n"),
+            '\r' => sanitized.push_str("\\r"),
+            '\t' => sanitized.push_str("\\t"),
+            c if c.is_ascii() && is_unsafe(c) => {
+                write!(sanitized, "\\x{:02x}", c as u32).unwrap();
+            }
+            c if is_unsafe(c) => write!(sanitized, "\\u{{{:x}}}", c as u32).unwrap(),
+            c => sanitized.push(c),
+        }
+    }
+    Cow::Owned(sanitized)
+}
+
+/// Decodes bytes of the program (invalid UTF-8 replaced by `U+FFFD`) and sanitizes them.
+pub fn sanitize_lossy(bytes: &[u8]) -> String {
+    sanitize_text(&String::from_utf8_lossy(bytes)).into_owned()
+}
+
+/// Escapes a string for safe inclusion in HTML (used in DOT labels).
+pub fn html_escape(string: &str) -> String {
+    sanitize_text(string)
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\"', "&quot;")
+}
+
+/// Escapes a string for a quoted DOT string (`"..."`), where `\` starts an escape.
+pub fn dot_escape(string: &str) -> String {
+    sanitize_text(string)
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+}
+
+/// Truncates `text` to at most `max_len` bytes, without cutting a character in two.
+pub fn truncate_on_char_boundary(text: &mut String, max_len: usize) {
+    if text.len() <= max_len {
+        return;
+    }
+    let mut len = max_len;
+    while !text.is_char_boundary(len) {
+        len -= 1;
+    }
+    text.truncate(len);
+}
+
+/// Sanitizes the names of the functions and the labels of the blocks, taken from the symbols
+/// of the program, before any output is produced.
+pub fn sanitize_labels(analysis: &mut Analysis) {
+    for (_, name) in analysis.functions.values_mut() {
+        if let Cow::Owned(sanitized) = sanitize_text(name) {
+            *name = sanitized;
+        }
+    }
+    for node in analysis.cfg_nodes.values_mut() {
+        if let Cow::Owned(sanitized) = sanitize_text(&node.label) {
+            node.label = sanitized;
+        }
+    }
+}
+
+/// Disassembles an instruction, sanitized: the name of a called function is read from the
+/// symbols of the program rather than from the (sanitized) labels.
+pub fn disassemble_instruction(analysis: &Analysis, insn: &Insn, pc: usize) -> String {
+    let asm = analysis.disassemble_instruction(insn, pc);
+    match sanitize_text(&asm) {
+        Cow::Borrowed(_) => asm,
+        Cow::Owned(sanitized) => sanitized,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reverse::{
+        analyze_program, new_loader, OutputFile, ReverseOptions, ReverseOutputMode,
+    };
+    use solana_sbpf::elf::Executable;
+    use test_utils::TestContextObject;
+
+    /// Name of a function of the test program, replaced by `HOSTILE_NAME` (same length).
+    const SYMBOL: &[u8] = b"custom_panic";
+    /// Clears the screen, reverses the text, rings the bell and isn't valid UTF-8.
+    const HOSTILE_NAME: &[u8] = b"\x1b[2J\xe2\x80\xaepwn\x07\xff";
+
+    /// The test program with the name of one of its functions replaced by `HOSTILE_NAME`.
+    fn adversarial_program() -> Vec<u8> {
+        let mut elf =
+            std::fs::read("test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so")
+                .unwrap();
+        let position = elf
+            .windows(SYMBOL.len())
+            .position(|window| window == SYMBOL)
+            .unwrap();
+        elf[position..position + SYMBOL.len()].copy_from_slice(HOSTILE_NAME);
+        elf
+    }
+
+    /// Whether `text` holds a control or invisible formatting character other than a newline.
+    fn has_unsafe(text: &str) -> bool {
+        text.chars().any(|c| c != '\n' && is_unsafe(c))
+    }
+
+    #[test]
+    fn test_sanitize_text() {
+        assert!(matches!(sanitize_text("entrypoint"), Cow::Borrowed(_)));
+        let sanitized = sanitize_lossy(HOSTILE_NAME);
+        assert_eq!(sanitized, "\\x1b[2J\\u{202e}pwn\\x07\u{fffd}");
+        assert_eq!(sanitize_text(&sanitized), sanitized);
+        assert_eq!(sanitize_text("a\tb\r\n"), "a\\tb\\r\\n");
+        assert_eq!(sanitize_text("\u{9b}31m\u{7f}"), "\\u{9b}31m\\x7f");
+
+        assert_eq!(html_escape("<b>\x1b</b>"), "&lt;b&gt;\\x1b&lt;/b&gt;");
+        assert_eq!(dot_escape("say \"hi\"\n"), "say \\\"hi\\\"\\\\n");
+
+        let mut text = "ab\u{fffd}".to_string();
+        truncate_on_char_boundary(&mut text, 4);
+        assert_eq!(text, "ab");
+    }
+
+    #[test]
+    fn test_adversarial_symbols() {
+        let elf = adversarial_program();
+        let executable =
+            Executable::<TestContextObject>::from_elf(&elf, new_loader(true).unwrap()).unwrap();
+        let mut analysis = Analysis::from_executable(&executable).unwrap();
+        assert!(analysis
+            .cfg_nodes
+            .values()
+            .any(|node| has_unsafe(&node.label)));
+        sanitize_labels(&mut analysis);
+        assert!(analysis
+            .cfg_nodes
+            .values()
+            .all(|node| !has_unsafe(&node.label)));
+        for (pc, insn) in analysis.instructions.iter().enumerate() {
+            assert!(!has_unsafe(&disassemble_instruction(&analysis, insn, pc)));
+        }
+
+        // every output of the reverse command is clean
+        let out_dir = std::env::temp_dir().join("sol_azy_test_adversarial_symbols");
+        std::fs::create_dir_all(&out_dir).unwrap();
+        let program = out_dir.join("adversarial.so");
+        std::fs::write(&program, &elf).unwrap();
+        analyze_program(
+            ReverseOutputMode::DisassemblyAndCFG(out_dir.to_string_lossy().to_string()),
+            program.to_string_lossy().to_string(),
+            &ReverseOptions {
+                labeling: Some(true),
+                ..ReverseOptions::default()
+            },
+        )
+        .unwrap();
+        for output in [
+            OutputFile::Disassembly,
+            OutputFile::Cfg,
+            OutputFile::CfgJson,
+            OutputFile::ImmediateDataTable,
+        ] {
+            let content = std::fs::read_to_string(out_dir.join(output.default_filename())).unwrap();
+            assert!(
+                !has_unsafe(&content),
+                "{} holds a control character",
+                output.default_filename()
+            );
+        }
+        let listing =
+            std::fs::read_to_string(out_dir.join(OutputFile::Disassembly.default_filename()))
+                .unwrap();
+        assert!(listing.contains("\\x1b[2J\\u{202e}pwn\\x07"));
+    }
+}
@@ -0,0 +1,153 @@
+//! Extraction of the `security.txt` blob embedded by the `solana-security-txt` crate
+//! and export of the program metadata (`program_info.out`), including the high-entropy
+//! regions found in the data sections.
+//!
+//! The blob is a sequence of NUL-terminated `key`/`value` strings enclosed between
+//! `=======BEGIN SECURITY.TXT V1=======\0` and `=======END SECURITY.TXT V1=======\0`.
+//! See https://github.com/neodyme-labs/solana-security-txt
+
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::reverse::entropy::{HighEntropyRegion, ENTROPY_THRESHOLD};
+use crate::reverse::sanitize::sanitize_lossy;
+use crate::reverse::OutputFile;
+
+const SECURITY_TXT_BEGIN: &[u8] = b"=======BEGIN SECURITY.TXT V1=======\0";
+const SECURITY_TXT_END: &[u8] = b"=======END SECURITY.TXT V1=======\0";
+
+/// Fields of an embedded `security.txt`, in the order they were declared.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SecurityTxt {
+    pub fields: Vec<(String, String)>,
+}
+
+impl SecurityTxt {
+    /// Returns the value of `key` (e.g. `contacts`, `policy`), if present.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Searches `data` (typically the whole ELF) for an embedded `security.txt` and parses it.
+///
+/// # Returns
+///
+/// The parsed `SecurityTxt`, or `None` if no well-formed blob is present.
+pub fn find_security_txt(data: &[u8]) -> Option<SecurityTxt> {
+    let begin = data
+        .windows(SECURITY_TXT_BEGIN.len())
+        .position(|w| w == SECURITY_TXT_BEGIN)?
+        + SECURITY_TXT_BEGIN.len();
+    let end = begin
+        + data[begin..]
+            .windows(SECURITY_TXT_END.len())
+            .position(|w| w == SECURITY_TXT_END)?;
+
+    let mut parts = data[begin..end].split(|b| *b == 0).map(sanitize_lossy);
+    let mut fields = Vec::new();
+    while let (Some(key), Some(value)) = (parts.next(), parts.next()) {
+        if key.is_empty() {
+            break;
+        }
+        fields.push((key, value));
+    }
+    Some(SecurityTxt { fields })
+}
+
+/// Writes `program_info.out`: the given program details followed by the embedded
+/// `security.txt` fields (contacts, policy, source code, ...) if any, and the high-entropy
+/// regions of the data sections.
+///
+/// # Arguments
+///
+/// * `program` - Raw bytes of the program (ELF).
+/// * `details` - `(name, value)` pairs describing the program (version, entrypoint, ...).
+/// * `high_entropy_regions` - Regions found by `entropy::find_high_entropy_regions`.
+/// * `path` - Output directory.
+///
+/// # Returns
+///
+/// The parsed `SecurityTxt` if one was found, so that callers can report it.
+pub fn export_program_info<P: AsRef<Path>>(
+    program: &[u8],
+    details: &[(&str, String)],
+    high_entropy_regions: &[HighEntropyRegion],
+    path: P,
+) -> std::io::Result<Option<SecurityTxt>> {
+    let mut info_path = PathBuf::from(path.as_ref());
+    info_path.push(OutputFile::ProgramInfo.default_filename());
+    let mut output = File::create(info_path)?;
+
+    writeln!(output, "Program:")?;
+    for (name, value) in details {
+        writeln!(output, "    {:<24}{}", format!("{}:", name), value)?;
+    }
+
+    let security_txt = find_security_txt(program);
+    match &security_txt {
+        Some(security_txt) => {
+            writeln!(output, "\nsecurity.txt:")?;
+            for (key, value) in &security_txt.fields {
+                writeln!(output, "    {:<24}{}", format!("{}:", key), value)?;
+            }
+        }
+        None => writeln!(output, "\nNo security.txt found.")?,
+    }
+
+    if high_entropy_regions.is_empty() {
+        writeln!(output, "\nNo high-entropy region found.")?;
+    } else {
+        writeln!(
+            output,
+            "\nHigh-entropy regions (>= {:.1} bits/byte):",
+            ENTROPY_THRESHOLD
+        )?;
+        writeln!(
+            output,
+            "    {:<16}{:<12}{:<20}{:<10}entropy",
+            "section", "offset", "address", "size"
+        )?;
+        for region in high_entropy_regions {
+            writeln!(
+                output,
+                "    {:<16}{:<12}{:<20}{:<10}{:.2}",
+                region.section,
+                format!("{:#x}", region.offset),
+                format!("{:#x}", region.address),
+                region.size,
+                region.max_entropy
+            )?;
+        }
+    }
+    Ok(security_txt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_security_txt() {
+        let mut elf = b"\x7fELF some code".to_vec();
+        elf.extend_from_slice(SECURITY_TXT_BEGIN);
+        elf.extend_from_slice(b"name\0Example\0contacts\0email:security@example.com\0policy\0https://example.com/policy\0");
+        elf.extend_from_slice(SECURITY_TXT_END);
+        elf.extend_from_slice(b"trailing rodata");
+
+        let security_txt = find_security_txt(&elf).expect("security.txt should be found");
+        assert_eq!(security_txt.fields.len(), 3);
+        assert_eq!(security_txt.get("name"), Some("Example"));
+        assert_eq!(
+            security_txt.get("contacts"),
+            Some("email:security@example.com")
+        );
+        assert_eq!(security_txt.get("auditors"), None);
+
+        assert!(find_security_txt(b"\x7fELF without blob").is_none());
+    }
+}
@@ -0,0 +1,350 @@
+//! Signature-based identification of statically linked library functions (FLIRT-like).
+//!
+//! A function signature is a hash of its normalized basic blocks: each instruction keeps
+//! its opcode, registers and offset, while the immediates that depend on where the code was
+//! linked (internal call targets, `lddw`/`hor64` addresses) are masked out. The same library
+//! function therefore gets the same signature in every program it is linked into.
+//!
+//! Signatures are read from `.sig` files: the ones shipped in `src/static/signatures/`, plus
+//! the ones given with `--signatures`. Each non-empty line not starting with `#` is
+//!
+//! ```text
+//! <hash> <instruction count> <name>
+//! ```
+//!
+//! and such files can be produced from an unstripped build with `--export-signatures`.
+
+use anyhow::{Context, Result};
+use log::{debug, warn};
+use sha2::{Digest, Sha256};
+use solana_sbpf::{ebpf, program::SBPFVersion, static_analysis::Analysis};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::helpers::static_dir;
+use crate::reverse::dispatch::{function_range, resolve_internal_call};
+use crate::reverse::OutputFile;
+
+/// Functions shorter than this are too generic to be identified reliably.
+pub const MIN_SIGNATURE_INSTRUCTIONS: usize = 8;
+
+/// Extension of the signature files.
+pub const SIGNATURE_FILE_EXTENSION: &str = "sig";
+
+/// The signature of a known function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionSignature {
+    pub hash: String,
+    pub instructions: usize,
+    pub name: String,
+}
+
+/// A function of the analyzed program identified by its signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureMatch {
+    pub function_pc: usize,
+    pub name: String,
+}
+
+/// A set of known signatures, indexed by hash.
+///
+/// Hashes claimed by several different names are ambiguous and never matched.
+#[derive(Debug, Default)]
+pub struct SignatureDb {
+    by_hash: HashMap<String, Option<FunctionSignature>>,
+}
+
+impl SignatureDb {
+    /// Loads the shipped signatures and, if given, the `.sig` file or directory at `extra`.
+    pub fn load(extra: Option<&Path>) -> Result<Self> {
+        let mut db = Self::default();
+
+        for (filename, content) in static_dir::read_all_files_in_dir("signatures")? {
+            if filename.ends_with(SIGNATURE_FILE_EXTENSION) {
+                db.extend(parse_signatures(&content, &filename)?);
+            }
+        }
+
+        if let Some(extra) = extra {
+            let files = if extra.is_dir() {
+                let mut files: Vec<PathBuf> = std::fs::read_dir(extra)?
+                    .filter_map(Result::ok)
+                    .map(|entry| entry.path())
+                    .filter(|path| {
+                        path.extension().and_then(|ext| ext.to_str())
+                            == Some(SIGNATURE_FILE_EXTENSION)
+                    })
+                    .collect();
+                files.sort();
+                files
+            } else {
+                vec![extra.to_path_buf()]
+            };
+            for file in files {
+                let content = std::fs::read_to_string(&file)
+                    .with_context(|| format!("Failed to read signatures {}", file.display()))?;
+                db.extend(parse_signatures(&content, &file.display().to_string())?);
+            }
+        }
+
+        Ok(db)
+    }
+
+    /// Adds signatures to the set.
+    pub fn extend(&mut self, signatures: impl IntoIterator<Item = FunctionSignature>) {
+        for signature in signatures {
+            match self.by_hash.get(&signature.hash) {
+                Some(Some(known)) if known.name != signature.name => {
+                    debug!(
+                        "Ambiguous signature {} ({} / {})",
+                        signature.hash, known.name, signature.name
+                    );
+                    self.by_hash.insert(signature.hash, None);
+                }
+                Some(_) => {}
+                None => {
+                    self.by_hash.insert(signature.hash.clone(), Some(signature));
+                }
+            }
+        }
+    }
+
+    /// Returns the known function with this hash, unless it is ambiguous.
+    pub fn get(&self, hash: &str) -> Option<&FunctionSignature> {
+        self.by_hash.get(hash).and_then(Option::as_ref)
+    }
+
+    /// Number of distinct hashes in the set.
+    pub fn len(&self) -> usize {
+        self.by_hash.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_hash.is_empty()
+    }
+}
+
+/// Parses the content of a `.sig` file.
+fn parse_signatures(content: &str, source: &str) -> Result<Vec<FunctionSignature>> {
+    content
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(index, line)| {
+            let mut fields = line.splitn(3, char::is_whitespace);
+            let (Some(hash), Some(instructions), Some(name)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                return Err(anyhow::anyhow!(
+                    "Invalid signature at {}:{}: {}",
+                    source,
+                    index + 1,
+                    line
+                ));
+            };
+            Ok(FunctionSignature {
+                hash: hash.to_string(),
+                instructions: instructions.parse().with_context(|| {
+                    format!("Invalid instruction count at {}:{}", source, index + 1)
+                })?,
+                name: name.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Computes the signature hash of the function starting at `function_pc`.
+///
+/// # Returns
+///
+/// The hash and the number of instructions of the function, or `None` if it is shorter
+/// than [`MIN_SIGNATURE_INSTRUCTIONS`].
+pub fn function_signature(
+    analysis: &Analysis,
+    function_pc: usize,
+    sbpf_version: SBPFVersion,
+) -> Option<(String, usize)> {
+    let (hash, instructions) = function_hash(analysis, function_pc, sbpf_version);
+    (instructions >= MIN_SIGNATURE_INSTRUCTIONS).then_some((hash, instructions))
+}
+
+/// Hashes the normalized instructions of the function starting at `function_pc`, whatever
+/// its length. Used by the signatures and by the stable node names (`--stable-ids`).
+///
+/// # Returns
+///
+/// The hash and the number of instructions of the function.
+pub fn function_hash(
+    analysis: &Analysis,
+    function_pc: usize,
+    sbpf_version: SBPFVersion,
+) -> (String, usize) {
+    let range = function_range(analysis, function_pc);
+    let mut function_hasher = Sha256::new();
+    let mut instructions = 0;
+
+    for (_, cfg_node) in analysis.cfg_nodes.range(range) {
+        let mut block_hasher = Sha256::new();
+        for insn in &analysis.instructions[cfg_node.instructions.clone()] {
+            let imm = match insn.opc {
+                ebpf::CALL_IMM if resolve_internal_call(analysis, insn, sbpf_version).is_some() => {
+                    0
+                }
+                ebpf::LD_DW_IMM | ebpf::HOR64_IMM => 0,
+                _ => insn.imm,
+            };
+            block_hasher.update([insn.opc, insn.dst, insn.src]);
+            block_hasher.update(insn.off.to_le_bytes());
+            block_hasher.update(imm.to_le_bytes());
+            instructions += 1;
+        }
+        function_hasher.update(block_hasher.finalize());
+    }
+    (hex::encode(&function_hasher.finalize()[..16]), instructions)
+}
+
+/// Looks up every function of the program in the signature set.
+pub fn match_signatures(
+    analysis: &Analysis,
+    sbpf_version: SBPFVersion,
+    db: &SignatureDb,
+) -> Vec<SignatureMatch> {
+    if db.is_empty() {
+        return vec![];
+    }
+    analysis
+        .functions
+        .keys()
+        .filter_map(|function_pc| {
+            let (hash, instructions) = function_signature(analysis, *function_pc, sbpf_version)?;
+            let signature = db.get(&hash)?;
+            if signature.instructions != instructions {
+                warn!(
+                    "Signature {} of {} matched with {} instructions instead of {}",
+                    hash, signature.name, instructions, signature.instructions
+                );
+                return None;
+            }
+            Some(SignatureMatch {
+                function_pc: *function_pc,
+                name: signature.name.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Renames the identified functions in the analysis, so that their names show up in the
+/// disassembly, in call operands and in the CFG cluster labels.
+///
+/// Like for the dispatch handlers, only generic labels (`function_N`, `lbb_N`) are replaced,
+/// as well as the empty ones of functions left unnamed when labeling is disabled.
+pub fn apply_signature_names(analysis: &mut Analysis, matches: &[SignatureMatch]) {
+    for signature_match in matches {
+        if signature_match.function_pc == analysis.entrypoint {
+            continue;
+        }
+        if let Some(cfg_node) = analysis.cfg_nodes.get_mut(&signature_match.function_pc) {
+            if cfg_node.label.is_empty()
+                || cfg_node.label.starts_with("function_")
+                || cfg_node.label.starts_with("lbb_")
+            {
+                cfg_node.label = signature_match.name.clone();
+                if let Some((_, name)) = analysis.functions.get_mut(&signature_match.function_pc) {
+                    *name = signature_match.name.clone();
+                }
+            }
+        }
+    }
+}
+
+/// Writes `signatures.sig` with the signature of every named function of the program
+/// (i.e. whose label was recovered from the symbols, see `--labeling`).
+///
+/// # Returns
+///
+/// The number of exported signatures.
+pub fn export_signatures<P: AsRef<Path>>(
+    analysis: &Analysis,
+    sbpf_version: SBPFVersion,
+    path: P,
+) -> std::io::Result<usize> {
+    let mut signatures_path = PathBuf::from(path.as_ref());
+    signatures_path.push(OutputFile::Signatures.default_filename());
+    let mut output = File::create(signatures_path)?;
+
+    writeln!(output, "# <hash> <instruction count> <name>")?;
+    let mut exported = 0;
+    for function_pc in analysis.functions.keys() {
+        let label = &analysis.cfg_nodes[function_pc].label;
+        if label.is_empty()
+            || label.starts_with("function_")
+            || label.starts_with("lbb_")
+            || label == "entrypoint"
+        {
+            continue;
+        }
+        if let Some((hash, instructions)) = function_signature(analysis, *function_pc, sbpf_version)
+        {
+            writeln!(output, "{} {} {}", hash, instructions, label)?;
+            exported += 1;
+        }
+    }
+    Ok(exported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reverse::new_loader;
+    use solana_sbpf::elf::Executable;
+    use test_utils::TestContextObject;
+
+    #[test]
+    fn test_parse_signatures_and_ambiguity() {
+        let content = "# comment\n\
+            0011 12 borsh::de::deserialize_u64\n\
+            \n\
+            2233 9 core::fmt::write\n";
+        let signatures = parse_signatures(content, "test.sig").unwrap();
+        assert_eq!(signatures.len(), 2);
+        assert_eq!(signatures[0].name, "borsh::de::deserialize_u64");
+        assert_eq!(signatures[1].instructions, 9);
+        assert!(parse_signatures("0011 twelve name", "bad.sig").is_err());
+
+        let mut db = SignatureDb::default();
+        db.extend(signatures);
+        db.extend([FunctionSignature {
+            hash: "2233".to_string(),
+            instructions: 9,
+            name: "alloc::fmt::format".to_string(),
+        }]);
+        assert_eq!(db.len(), 2);
+        assert!(db.get("0011").is_some());
+        assert!(db.get("2233").is_none());
+    }
+    /// The shipped signatures, exported from the unstripped build of the fixture, name the
+    /// library functions of its stripped build.
+    #[test]
+    fn test_shipped_signatures_on_stripped_build() {
+        let elf =
+            std::fs::read("test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so")
+                .unwrap();
+        let executable =
+            Executable::<TestContextObject>::from_elf(&elf, new_loader(false).unwrap()).unwrap();
+        let analysis = Analysis::from_executable(&executable).unwrap();
+        let db = SignatureDb::load(None).unwrap();
+        assert!(!db.is_empty());
+
+        let matches = match_signatures(&analysis, executable.get_sbpf_version(), &db);
+        let prefix = "solana_program::entrypoint::deserialize::";
+        let deserialize = matches
+            .iter()
+            .find(|m| m.name.starts_with(prefix))
+            .unwrap_or_else(|| panic!("{:?}", matches));
+        assert_eq!(deserialize.function_pc, 308);
+        assert!(matches.iter().any(|m| m.name == "memcpy"));
+    }
+}
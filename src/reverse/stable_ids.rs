@@ -0,0 +1,227 @@
+//! Stable names of the CFG nodes (`--stable-ids`) and their mapping file (`id_map.json`).
+//!
+//! By default the clusters and blocks are named after their instruction pointer
+//! (`cluster_<start>`, `lbb_<start>`), so recompiling a program shifts every name. With
+//! `--stable-ids`, a function is named after the hash of its normalized instructions (the one
+//! of the library signatures, which masks the immediates depending on where the code is linked)
+//! and a block after its offset in its function:
+//!
+//! ```text
+//! cluster_f3a9c0d12e4b7      lbb_f3a9c0d12e4b7_0      lbb_f3a9c0d12e4b7_12
+//! ```
+//!
+//! An unchanged function keeps its names across rebuilds. Identical functions of the same
+//! program get a `_2`, `_3`, ... suffix in address order. `id_map.json` maps every name of the
+//! run to its stable name, to carry dotting configs and notes over to another build.
+//!
+//! The findings of the bytecode passes are located the same way, whatever `--stable-ids`: a
+//! [`FunctionLocation`] is the hash of the function and the offset of the instruction in it
+//! (`f3a9c0d12e4b7+24`), to match a finding across versions of a stripped program.
+
+use serde::Serialize;
+use solana_sbpf::{program::SBPFVersion, static_analysis::Analysis};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::reverse::cfg_graph::CfgGraph;
+use crate::reverse::dispatch::function_range;
+use crate::reverse::signatures::function_hash;
+use crate::reverse::OutputFile;
+
+/// Number of hexadecimal digits of the hash kept in the function names.
+const FUNCTION_ID_DIGITS: usize = 12;
+
+/// A function of the `id_map.json`.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct FunctionId {
+    /// Stable name of the function (`f<hash prefix>`).
+    pub id: String,
+    /// Instruction pointer of the function (`cluster_<start>`).
+    pub start: usize,
+    pub label: String,
+    /// Full hash of the normalized instructions.
+    pub hash: String,
+    pub instructions: usize,
+}
+
+/// Short name of a function hash (`f<hash prefix>`), its stable name when it is unique.
+pub fn hash_id(hash: &str) -> String {
+    format!("f{}", &hash[..FUNCTION_ID_DIGITS.min(hash.len())])
+}
+
+/// Location of an instruction independent of the addresses of the program: the hash of its
+/// function and its offset in it, written `f<hash prefix>+<offset>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct FunctionLocation {
+    /// Full hash of the normalized instructions of the function (see [`function_hash`]).
+    pub function_hash: String,
+    /// Instruction pointer relative to the start of the function.
+    pub offset: usize,
+}
+
+impl FunctionLocation {
+    /// Location of `pc` in the function starting at `function_start`, of hash `function_hash`.
+    pub fn new(function_hash: &str, function_start: usize, pc: usize) -> Self {
+        Self {
+            function_hash: function_hash.to_string(),
+            offset: pc - function_start,
+        }
+    }
+}
+
+impl fmt::Display for FunctionLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}+{}", hash_id(&self.function_hash), self.offset)
+    }
+}
+
+/// Stable names of the functions and blocks of a program.
+#[derive(Debug, Default)]
+pub struct StableIds {
+    functions: BTreeMap<usize, FunctionId>,
+    /// Stable name of each block (`<function id>_<offset>`), by instruction pointer.
+    blocks: BTreeMap<usize, String>,
+}
+
+impl StableIds {
+    /// Names the functions and blocks of the program.
+    pub fn compute(analysis: &Analysis, sbpf_version: SBPFVersion) -> Self {
+        let mut ids = Self::default();
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        for (start, (_, name)) in &analysis.functions {
+            let (hash, instructions) = function_hash(analysis, *start, sbpf_version);
+            let occurrences = seen.entry(hash.clone()).or_default();
+            *occurrences += 1;
+            let id = match *occurrences {
+                1 => hash_id(&hash),
+                n => format!("{}_{}", hash_id(&hash), n),
+            };
+            for block in analysis
+                .cfg_nodes
+                .range(function_range(analysis, *start))
+                .map(|(pc, _)| *pc)
+            {
+                ids.blocks
+                    .insert(block, format!("{}_{}", id, block - start));
+            }
+            ids.functions.insert(
+                *start,
+                FunctionId {
+                    id,
+                    start: *start,
+                    label: name.clone(),
+                    hash,
+                    instructions,
+                },
+            );
+        }
+        ids
+    }
+
+    /// Stable name of the function starting at `start`.
+    pub fn function(&self, start: usize) -> Option<&str> {
+        self.functions
+            .get(&start)
+            .map(|function| function.id.as_str())
+    }
+
+    /// Stable name of the block starting at `start`.
+    pub fn block(&self, start: usize) -> Option<&str> {
+        self.blocks.get(&start).map(String::as_str)
+    }
+
+    /// Names the clusters and blocks of a graph with their stable names.
+    pub fn apply(&self, graph: &mut CfgGraph) {
+        for cluster in &mut graph.clusters {
+            cluster.id = self.function(cluster.start).map(str::to_string);
+            for block in &mut cluster.blocks {
+                block.id = self.block(block.start).map(str::to_string);
+            }
+        }
+    }
+
+    /// Writes `id_map.json`: the functions with their stable name, and the stable name of
+    /// every `cluster_<start>` and `lbb_<start>` of the run.
+    pub fn export<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let mut names = BTreeMap::new();
+        for function in self.functions.values() {
+            names.insert(
+                format!("cluster_{}", function.start),
+                format!("cluster_{}", function.id),
+            );
+        }
+        for (start, id) in &self.blocks {
+            names.insert(format!("lbb_{}", start), format!("lbb_{}", id));
+        }
+        let map = serde_json::json!({
+            "functions": self.functions.values().collect::<Vec<_>>(),
+            "names": names,
+        });
+
+        let mut map_path = PathBuf::from(path.as_ref());
+        map_path.push(OutputFile::IdMap.default_filename());
+        let mut output = File::create(map_path)?;
+        writeln!(
+            output,
+            "{}",
+            serde_json::to_string_pretty(&map).map_err(std::io::Error::other)?
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reverse::new_loader;
+    use solana_sbpf::elf::Executable;
+    use std::collections::HashSet;
+    use test_utils::TestContextObject;
+
+    #[test]
+    fn test_stable_ids() {
+        let elf =
+            std::fs::read("test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so")
+                .unwrap();
+        let executable =
+            Executable::<TestContextObject>::from_elf(&elf, new_loader(true).unwrap()).unwrap();
+        let analysis = Analysis::from_executable(&executable).unwrap();
+        let ids = StableIds::compute(&analysis, executable.get_sbpf_version());
+
+        let names: HashSet<&str> = ids.functions.values().map(|f| f.id.as_str()).collect();
+        assert_eq!(names.len(), analysis.functions.len());
+        for start in analysis.functions.keys() {
+            let id = ids.function(*start).unwrap();
+            assert!(id.starts_with('f'));
+            assert_eq!(ids.block(*start), Some(format!("{}_0", id).as_str()));
+        }
+
+        let mut graph = CfgGraph {
+            clusters: vec![crate::reverse::cfg_graph::CfgCluster {
+                start: *analysis.functions.keys().next().unwrap(),
+                id: None,
+                hash: None,
+                label: "entrypoint".to_string(),
+                blocks: vec![],
+                calls: vec![],
+            }],
+            edges: vec![],
+        };
+        ids.apply(&mut graph);
+        let id = graph.clusters[0].id.clone().unwrap();
+        assert!(graph
+            .to_dot()
+            .contains(&format!("subgraph cluster_{} ", id)));
+        assert_eq!(
+            graph.cluster_start(&format!("cluster_{}", id)),
+            Some(graph.clusters[0].start)
+        );
+
+        let function = ids.functions.values().next().unwrap();
+        let location = FunctionLocation::new(&function.hash, function.start, function.start + 7);
+        assert_eq!(location.offset, 7);
+        assert_eq!(location.to_string(), format!("{}+7", function.id));
+    }
+}
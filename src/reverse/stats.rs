@@ -0,0 +1,226 @@
+//! Instruction statistics of a program (`--stats`).
+//!
+//! Produces an opcode histogram, per-function instruction counts, call/syscall counts and
+//! the distribution of arithmetic, memory and branch instructions, exported as
+//! `stats.json`, `opcode_histogram.csv` and `function_stats.csv`.
+//!
+//! Instructions are classified by the mnemonic given by the disassembler, which keeps the
+//! statistics comparable across SBPF versions (whose encodings differ).
+
+use serde::Serialize;
+use solana_sbpf::static_analysis::Analysis;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::reverse::OutputFile;
+
+/// Coarse category of an instruction, derived from its mnemonic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstructionClass {
+    Arithmetic,
+    Memory,
+    Branch,
+    Call,
+    Other,
+}
+
+impl InstructionClass {
+    /// Classifies a mnemonic as printed by the disassembler (e.g. `add64`, `ldxdw`, `jeq`).
+    pub fn from_mnemonic(mnemonic: &str) -> Self {
+        const ARITHMETIC: &[&str] = &[
+            "add", "sub", "mul", "div", "mod", "or", "and", "lsh", "rsh", "arsh", "neg", "xor",
+            "mov", "le", "be", "hor", "lmul", "uhmul", "shmul", "udiv", "urem", "sdiv", "srem",
+        ];
+        match mnemonic {
+            "call" | "callx" | "syscall" => Self::Call,
+            // loads a 64-bit immediate, not memory
+            "lddw" => Self::Arithmetic,
+            "exit" | "return" | "ja" => Self::Branch,
+            m if m.starts_with("ld") || m.starts_with("st") => Self::Memory,
+            m if m.starts_with('j') => Self::Branch,
+            m if ARITHMETIC.contains(&m.trim_end_matches(|c: char| c.is_ascii_digit())) => {
+                Self::Arithmetic
+            }
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Instruction counts of a single function.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct FunctionStats {
+    pub start: usize,
+    pub label: String,
+    pub instructions: usize,
+    pub basic_blocks: usize,
+    pub calls: usize,
+    pub syscalls: usize,
+}
+
+/// Instruction statistics of a whole program.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct ProgramStats {
+    pub instructions: usize,
+    pub functions: usize,
+    pub basic_blocks: usize,
+    pub opcode_histogram: BTreeMap<String, usize>,
+    pub calls: usize,
+    pub indirect_calls: usize,
+    pub syscalls: BTreeMap<String, usize>,
+    pub arithmetic: usize,
+    pub memory: usize,
+    pub branch: usize,
+    pub other: usize,
+    /// `arithmetic / memory`, `0` if the program has no memory instruction.
+    pub arithmetic_memory_ratio: f64,
+    pub per_function: Vec<FunctionStats>,
+}
+
+/// Computes the instruction statistics of the analyzed program.
+pub fn compute_stats(analysis: &Analysis) -> ProgramStats {
+    let mut stats = ProgramStats {
+        instructions: analysis.instructions.len(),
+        functions: analysis.functions.len(),
+        ..ProgramStats::default()
+    };
+
+    let mut per_function: BTreeMap<usize, FunctionStats> = analysis
+        .functions
+        .keys()
+        .map(|start| {
+            let stats = FunctionStats {
+                start: *start,
+                label: analysis.cfg_nodes[start].label.clone(),
+                ..FunctionStats::default()
+            };
+            (*start, stats)
+        })
+        .collect();
+    let function_of = |ptr: usize| analysis.functions.range(..=ptr).next_back().map(|(pc, _)| *pc);
+
+    let last_ptr = analysis.instructions.last().map_or(0, |insn| insn.ptr);
+    for cfg_node_start in analysis.cfg_nodes.keys() {
+        // skip the virtual super root
+        if *cfg_node_start > last_ptr {
+            continue;
+        }
+        stats.basic_blocks += 1;
+        if let Some(function) = function_of(*cfg_node_start).and_then(|pc| per_function.get_mut(&pc)) {
+            function.basic_blocks += 1;
+        }
+    }
+
+    for (pc, insn) in analysis.instructions.iter().enumerate() {
+        let desc = analysis.disassemble_instruction(insn, pc);
+        let mut words = desc.split_whitespace();
+        let mnemonic = words.next().unwrap_or("unknown").to_string();
+        let (mut is_call, mut is_syscall) = (false, false);
+
+        match InstructionClass::from_mnemonic(&mnemonic) {
+            InstructionClass::Arithmetic => stats.arithmetic += 1,
+            InstructionClass::Memory => stats.memory += 1,
+            InstructionClass::Branch => stats.branch += 1,
+            InstructionClass::Other => stats.other += 1,
+            InstructionClass::Call => match mnemonic.as_str() {
+                "syscall" => {
+                    let name = words.next().unwrap_or("[invalid]").to_string();
+                    *stats.syscalls.entry(name).or_default() += 1;
+                    is_syscall = true;
+                }
+                "callx" => stats.indirect_calls += 1,
+                _ => {
+                    stats.calls += 1;
+                    is_call = true;
+                }
+            },
+        }
+        if let Some(function) = function_of(insn.ptr).and_then(|start| per_function.get_mut(&start)) {
+            function.instructions += 1;
+            function.calls += is_call as usize;
+            function.syscalls += is_syscall as usize;
+        }
+        *stats.opcode_histogram.entry(mnemonic).or_default() += 1;
+    }
+
+    stats.arithmetic_memory_ratio = if stats.memory == 0 {
+        0.0
+    } else {
+        stats.arithmetic as f64 / stats.memory as f64
+    };
+    stats.per_function = per_function.into_values().collect();
+    stats
+}
+
+/// Writes `stats.json`, `opcode_histogram.csv` and `function_stats.csv` in `path`.
+///
+/// # Arguments
+///
+/// * `analysis` - The `Analysis` structure of the program.
+/// * `path` - Output directory.
+///
+/// # Returns
+///
+/// The computed statistics, so that callers can report them.
+pub fn export_stats<P: AsRef<Path>>(analysis: &Analysis, path: P) -> std::io::Result<ProgramStats> {
+    let stats = compute_stats(analysis);
+    let out_path = |file: OutputFile| -> PathBuf {
+        let mut out_path = PathBuf::from(path.as_ref());
+        out_path.push(file.default_filename());
+        out_path
+    };
+
+    let mut json_output = File::create(out_path(OutputFile::StatsJson))?;
+    writeln!(
+        json_output,
+        "{}",
+        serde_json::to_string_pretty(&stats).map_err(std::io::Error::other)?
+    )?;
+
+    let mut histogram_output = File::create(out_path(OutputFile::OpcodeHistogram))?;
+    writeln!(histogram_output, "mnemonic,count")?;
+    for (mnemonic, count) in &stats.opcode_histogram {
+        writeln!(histogram_output, "{},{}", mnemonic, count)?;
+    }
+
+    let mut functions_output = File::create(out_path(OutputFile::FunctionStats))?;
+    writeln!(
+        functions_output,
+        "start,label,instructions,basic_blocks,calls,syscalls"
+    )?;
+    for function in &stats.per_function {
+        writeln!(
+            functions_output,
+            "{},{},{},{},{},{}",
+            function.start,
+            function.label.replace(',', "_"),
+            function.instructions,
+            function.basic_blocks,
+            function.calls,
+            function.syscalls
+        )?;
+    }
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instruction_class_from_mnemonic() {
+        assert_eq!(InstructionClass::from_mnemonic("add64"), InstructionClass::Arithmetic);
+        assert_eq!(InstructionClass::from_mnemonic("mov32"), InstructionClass::Arithmetic);
+        assert_eq!(InstructionClass::from_mnemonic("le"), InstructionClass::Arithmetic);
+        assert_eq!(InstructionClass::from_mnemonic("uhmul64"), InstructionClass::Arithmetic);
+        assert_eq!(InstructionClass::from_mnemonic("ldxdw"), InstructionClass::Memory);
+        assert_eq!(InstructionClass::from_mnemonic("lddw"), InstructionClass::Arithmetic);
+        assert_eq!(InstructionClass::from_mnemonic("stxb"), InstructionClass::Memory);
+        assert_eq!(InstructionClass::from_mnemonic("jsgt"), InstructionClass::Branch);
+        assert_eq!(InstructionClass::from_mnemonic("exit"), InstructionClass::Branch);
+        assert_eq!(InstructionClass::from_mnemonic("syscall"), InstructionClass::Call);
+        assert_eq!(InstructionClass::from_mnemonic("unknown"), InstructionClass::Other);
+    }
+}
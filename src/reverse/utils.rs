@@ -99,6 +99,12 @@ impl RegisterTracker {
     pub fn get(&self, reg: u8) -> Option<&Value> {
         self.registers.get(&reg)
     }
+
+    /// Overrides the value of `reg`, for the instructions [`update`](Self::update) doesn't
+    /// resolve (e.g. `lddw`).
+    pub fn set(&mut self, reg: u8, value: Value) {
+        self.registers.insert(reg, value);
+    }
 }
 
 /// Attempts to resolve a string representation from memory based on the current instruction context
@@ -214,8 +220,10 @@ pub fn update_string_resolution(
 
 /// Formats a byte slice into a Rust-style byte string literal (`b"..."`).
 ///
-/// Printable ASCII characters (including spaces) are rendered as-is.
-/// Non-printable or non-ASCII bytes are rendered using hexadecimal escapes (`\xNN`).
+/// Printable ASCII characters (including spaces) are rendered as-is, except `"` and `\`
+/// which are escaped with a backslash.
+/// Non-printable or non-ASCII bytes are rendered using hexadecimal escapes (`\xNN`), so that
+/// the strings of a program can't inject control characters into the outputs.
 ///
 /// # Arguments
 ///
@@ -230,7 +238,10 @@ pub fn format_bytes(slice: &[u8]) -> String {
 
     // Render printable ASCII as-is, otherwise use hex escape
     for &b in slice {
-        if b.is_ascii_graphic() || b == b' ' {
+        if b == b'"' || b == b'\\' {
+            bytes_repr.push('\\');
+            bytes_repr.push(b as char);
+        } else if b.is_ascii_graphic() || b == b' ' {
             bytes_repr.push(b as char);
         } else {
             // Add hexadecimal escape sequence for non-printable bytes
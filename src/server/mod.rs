@@ -0,0 +1,958 @@
+//! HTTP+JSON API of `serve`, running `sast`, `reverse` and `fetcher` jobs for remote clients.
+//!
+//! A job is submitted with the command line arguments of one of these commands, runs in the
+//! background in its own directory under the work directory, and its status and artifacts are
+//! then polled and downloaded:
+//!
+//! - `POST /jobs` `{"command": "reverse", "args": ["--mode", "disass", ...]}` -> the job,
+//! - `GET /jobs` -> all the jobs, `GET /jobs/<id>` -> one job,
+//! - `GET /jobs/<id>/artifacts/<path>` -> a file written by the job,
+//! - `POST /uploads/<name>` -> the path of the uploaded file (or of the extracted archive with
+//!   `?extract`), to be given to a job,
+//! - `GET /health`.
+//!
+//! The output options (`--out-dir`, `--label`, `--export-db`) are set by the server, and the paths read by a job must be in the work directory: uploaded files, or files of
+//! other jobs.
+
+use anyhow::{anyhow, Context, Result};
+use clap::Parser;
+use hyper::body::HttpBody;
+use hyper::{Body, Method, Request, Response, StatusCode};
+use log::{debug, error, info};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
+
+use crate::commands::{fetcher_command, reverse_command, sast_command};
+use crate::helpers::bundle::{extract_to, BundleFormat};
+use crate::helpers::run_layout::{list_files, now_utc};
+use crate::{Cli, Commands};
+
+/// Commands that can be run as jobs.
+const JOB_COMMANDS: &[&str] = &["sast", "reverse", "fetcher"];
+
+/// Options set by the server, refused in the arguments of a job.
+const RESERVED_OPTIONS: &[&str] = &["-o", "--out-dir", "--label", "--export-db", "--events-json"];
+
+/// Options needing a terminal, refused in the arguments of a job.
+const INTERACTIVE_OPTIONS: &[&str] = &["--tui"];
+
+/// Options rewriting the scanned sources on the server, refused in the arguments of a job.
+const SOURCE_WRITING_OPTIONS: &[&str] = &["--fix", "--dry-run"];
+
+/// Options writing to a directory given in the arguments, outside of the job directory,
+/// refused in the arguments of a job.
+const OUTSIDE_WRITING_OPTIONS: &[&str] = &["--fuzz-corpus"];
+
+/// Options relying on a state of the server process, which its concurrent jobs would share:
+/// the temporary directory of `--spill-ast`, removed by each job under the others, and the
+/// stage timings of `--timing-report`, which would add up the stages of all the running jobs.
+/// Refused in the arguments of a job.
+const PROCESS_WIDE_OPTIONS: &[&str] = &["--spill-ast", "--timing-report"];
+
+/// Label of the run directory of `reverse` and `fetcher` jobs.
+const JOB_RUN_LABEL: &str = "run";
+
+/// Directory of the uploaded files, under the work directory.
+const UPLOADS_DIR: &str = "uploads";
+
+/// Directory an uploaded archive is extracted to, next to it.
+const EXTRACTED_DIR: &str = "extracted";
+
+/// Largest body of a `POST /jobs` request.
+const MAX_JOB_REQUEST_BYTES: u64 = 64 * 1024;
+
+/// Status of a job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// A job, as returned by the API.
+#[derive(Debug, Clone, Serialize)]
+pub struct Job {
+    pub id: String,
+    pub command: String,
+    pub args: Vec<String>,
+    pub status: JobStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub submitted_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<String>,
+    /// Directory of the job on the server, whose files can be passed to other jobs.
+    pub dir: PathBuf,
+    /// Files written by the job, relative to `dir`.
+    pub artifacts: Vec<String>,
+}
+
+/// Body of `POST /jobs`.
+#[derive(Debug, Deserialize)]
+struct JobRequest {
+    command: String,
+    #[serde(default)]
+    args: Vec<String>,
+}
+
+/// An uploaded file, as returned by the API.
+#[derive(Debug, Clone, Serialize)]
+pub struct Upload {
+    /// Path of the file, or of the directory of the extracted archive, on the server.
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// State shared by the request handlers and the jobs.
+pub struct ServerState {
+    /// Canonical work directory: the paths of the jobs are confined to it.
+    work_dir: PathBuf,
+    /// Bearer token required by every request, if any.
+    token: Option<String>,
+    /// Largest uploaded file, in bytes.
+    max_upload: u64,
+    jobs: Mutex<BTreeMap<String, Job>>,
+    /// Limits the number of jobs running at the same time.
+    slots: Arc<Semaphore>,
+    submitted: AtomicUsize,
+    uploaded: AtomicUsize,
+}
+
+impl ServerState {
+    pub fn new(
+        work_dir: PathBuf,
+        token: Option<String>,
+        max_jobs: usize,
+        max_upload_mb: u64,
+    ) -> Self {
+        Self {
+            work_dir: work_dir.canonicalize().unwrap_or(work_dir),
+            token,
+            max_upload: max_upload_mb.saturating_mul(1024 * 1024),
+            jobs: Mutex::new(BTreeMap::new()),
+            slots: Arc::new(Semaphore::new(max_jobs.max(1))),
+            submitted: AtomicUsize::new(0),
+            uploaded: AtomicUsize::new(0),
+        }
+    }
+
+    fn job(&self, id: &str) -> Option<Job> {
+        self.jobs.lock().unwrap().get(id).cloned()
+    }
+
+    fn update(&self, id: &str, update: impl FnOnce(&mut Job)) {
+        if let Some(job) = self.jobs.lock().unwrap().get_mut(id) {
+            update(job);
+        }
+    }
+
+    /// Parses the arguments of a job as a command line, with the output options of its
+    /// directory `dir`.
+    fn parse_job(&self, request: &JobRequest, dir: &Path) -> Result<Commands> {
+        if !JOB_COMMANDS.contains(&request.command.as_str()) {
+            return Err(anyhow!(
+                "Unsupported command '{}' (expected one of: {})",
+                request.command,
+                JOB_COMMANDS.join(", ")
+            ));
+        }
+        if let Some(reserved) = request.args.iter().find(|arg| {
+            RESERVED_OPTIONS
+                .iter()
+                .any(|option| arg == option || arg.starts_with(&format!("{}=", option)))
+        }) {
+            return Err(anyhow!("Option '{}' is set by the server", reserved));
+        }
+        if let Some(interactive) = request
+            .args
+            .iter()
+            .find(|arg| INTERACTIVE_OPTIONS.contains(&arg.as_str()))
+        {
+            return Err(anyhow!("Option '{}' is not available to jobs", interactive));
+        }
+        if let Some(writing) = request.args.iter().find(|arg| {
+            SOURCE_WRITING_OPTIONS
+                .iter()
+                .chain(OUTSIDE_WRITING_OPTIONS)
+                .any(|option| arg == option || arg.starts_with(&format!("{}=", option)))
+        }) {
+            return Err(anyhow!(
+                "Option '{}' writes to the files of the server and is not available to jobs",
+                writing
+            ));
+        }
+        if let Some(process_wide) = request.args.iter().find(|arg| {
+            PROCESS_WIDE_OPTIONS
+                .iter()
+                .any(|option| arg == option || arg.starts_with(&format!("{}=", option)))
+        }) {
+            return Err(anyhow!(
+                "Option '{}' is shared by the jobs of the server and is not available to jobs",
+                process_wide
+            ));
+        }
+
+        let dir = dir.to_string_lossy().to_string();
+        let mut argv = vec!["sol-azy".to_string(), request.command.clone()];
+        argv.extend(request.args.iter().cloned());
+        match request.command.as_str() {
+            "sast" => argv.extend(["--export-db".to_string(), format!("{}/db", dir)]),
+            _ => argv.extend([
+                "--out-dir".to_string(),
+                dir,
+                "--label".to_string(),
+                JOB_RUN_LABEL.to_string(),
+            ]),
+        }
+        let cli = Cli::try_parse_from(argv).map_err(|e| anyhow!("{}", e.render()))?;
+        let command = cli
+            .command
+            .ok_or_else(|| anyhow!("No command in the request"))?;
+        for path in input_paths(&command) {
+            self.confine(path)?;
+        }
+        Ok(command)
+    }
+
+    /// Checks that `path`, read by a job, is in the work directory once its symbolic links are
+    /// resolved.
+    fn confine(&self, path: &str) -> Result<()> {
+        let resolved = Path::new(path)
+            .canonicalize()
+            .map_err(|_| anyhow!("Path '{}' doesn't exist on the server", path))?;
+        if !resolved.starts_with(&self.work_dir) {
+            return Err(anyhow!(
+                "Path '{}' is outside the work directory of the server: upload it with POST /uploads/<name>",
+                path
+            ));
+        }
+        Ok(())
+    }
+
+    /// Creates the directory of a new upload.
+    fn create_upload_dir(&self) -> Result<PathBuf> {
+        let number = self.uploaded.fetch_add(1, Ordering::SeqCst) + 1;
+        let root = self.work_dir.join(UPLOADS_DIR);
+        std::fs::create_dir_all(&root).with_context(|| format!("Creating {}", root.display()))?;
+        let dir = root.join(format!("{}-{}", now_utc().replace(':', "-"), number));
+        std::fs::create_dir(&dir).with_context(|| format!("Creating {}", dir.display()))?;
+        Ok(dir)
+    }
+
+    /// Registers a job and starts it in the background.
+    fn submit(self: &Arc<Self>, request: JobRequest) -> Result<Job> {
+        let number = self.submitted.fetch_add(1, Ordering::SeqCst) + 1;
+        let submitted_at = now_utc();
+        let id = format!("{}-{}", submitted_at.replace(':', "-"), number);
+        let dir = self.work_dir.join(&id);
+        let command = self.parse_job(&request, &dir)?;
+        std::fs::create_dir_all(&dir).with_context(|| format!("Creating {}", dir.display()))?;
+
+        let job = Job {
+            id: id.clone(),
+            command: request.command,
+            args: request.args,
+            status: JobStatus::Queued,
+            error: None,
+            submitted_at,
+            started_at: None,
+            finished_at: None,
+            dir: dir.clone(),
+            artifacts: vec![],
+        };
+        self.jobs.lock().unwrap().insert(id.clone(), job.clone());
+        info!(
+            "Job {} submitted: {} {}",
+            id,
+            job.command,
+            job.args.join(" ")
+        );
+        tokio::spawn(execute(self.clone(), id, command, dir));
+        Ok(job)
+    }
+}
+
+/// Paths read by a job, which must be in the work directory.
+fn input_paths(command: &Commands) -> Vec<&String> {
+    match command {
+        Commands::Sast {
+            target_dir,
+            rules_dir,
+            bytecodes,
+            build_manifest,
+            taxonomy,
+            baseline,
+            advisories,
+            ..
+        } => std::iter::once(target_dir)
+            .chain(bytecodes)
+            .chain(rules_dir)
+            .chain(build_manifest)
+            .chain(taxonomy)
+            .chain(baseline)
+            .chain(advisories)
+            .collect(),
+        Commands::Reverse {
+            bytecodes_file,
+            bundle,
+            idl,
+            account_names,
+            account_sources,
+            signatures,
+            annotations,
+            ..
+        } => bytecodes_file
+            .iter()
+            .chain(bundle)
+            .chain(idl)
+            .chain(account_names)
+            .chain(account_sources)
+            .chain(signatures)
+            .chain(annotations)
+            .collect(),
+        Commands::Fetcher {
+            idl,
+            account_names,
+            account_sources,
+            ..
+        } => idl
+            .iter()
+            .chain(account_names)
+            .chain(account_sources)
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Runs a job once a slot is free, then records its outcome and artifacts.
+async fn execute(state: Arc<ServerState>, id: String, command: Commands, dir: PathBuf) {
+    let _slot = state.slots.clone().acquire_owned().await;
+    state.update(&id, |job| {
+        job.status = JobStatus::Running;
+        job.started_at = Some(now_utc());
+    });
+
+    let result = match command {
+        cmd @ Commands::Fetcher { .. } => {
+            fetcher_command::run(&fetcher_command::FetcherCmd::new_from_clap(&cmd))
+                .await
+                .map(|_| ())
+        }
+        cmd => tokio::task::spawn_blocking(move || run_blocking(&cmd))
+            .await
+            .map_err(|e| anyhow!("Job panicked: {}", e))
+            .and_then(|result| result),
+    };
+    if let Err(e) = &result {
+        error!("Job {} failed: {}", id, e);
+    }
+
+    let mut files = vec![];
+    list_files(&dir, &dir, &mut files);
+    state.update(&id, |job| {
+        job.status = match result {
+            Ok(_) => JobStatus::Succeeded,
+            Err(_) => JobStatus::Failed,
+        };
+        job.error = result.err().map(|e| e.to_string());
+        job.finished_at = Some(now_utc());
+        job.artifacts = files
+            .iter()
+            .map(|file| file.to_string_lossy().replace('\\', "/"))
+            .collect();
+    });
+}
+
+/// Runs the synchronous commands.
+fn run_blocking(cmd: &Commands) -> Result<()> {
+    match cmd {
+        Commands::Sast { .. } => {
+            sast_command::run(&sast_command::SastCmd::new_from_clap(cmd)).map(|_| ())
+        }
+        Commands::Reverse { .. } => {
+            reverse_command::run(&reverse_command::ReverseCmd::new_from_clap(cmd))
+        }
+        _ => Err(anyhow!("Unsupported job command")),
+    }
+}
+
+fn json_response(status: StatusCode, value: &impl Serialize) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .body(Body::from(
+            serde_json::to_string_pretty(value).unwrap_or_default(),
+        ))
+        .unwrap()
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Body> {
+    json_response(status, &serde_json::json!({ "error": message }))
+}
+
+/// Returns `true` if the `Content-Length` of `request` is above `limit`.
+fn declares_more_than(request: &Request<Body>, limit: u64) -> bool {
+    request
+        .headers()
+        .get("content-length")
+        .and_then(|value| value.to_str().ok()?.parse::<u64>().ok())
+        .is_some_and(|length| length > limit)
+}
+
+/// Reads a request body, refusing it past `limit` bytes.
+async fn read_body(mut body: Body, limit: u64) -> Result<Vec<u8>> {
+    let mut bytes = vec![];
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk?;
+        if (bytes.len() + chunk.len()) as u64 > limit {
+            return Err(anyhow!("Request body larger than {} bytes", limit));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+/// Writes a request body to `path`, refusing it past `limit` bytes, and returns its size.
+async fn write_body(mut body: Body, path: &Path, limit: u64) -> Result<u64> {
+    let mut file =
+        std::fs::File::create(path).with_context(|| format!("Creating {}", path.display()))?;
+    let mut size = 0;
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk?;
+        size += chunk.len() as u64;
+        if size > limit {
+            return Err(anyhow!("Uploaded file larger than {} bytes", limit));
+        }
+        file.write_all(&chunk)
+            .with_context(|| format!("Writing {}", path.display()))?;
+    }
+    Ok(size)
+}
+
+/// Removes the symbolic links under `dir`, which could lead the jobs out of the work
+/// directory.
+fn remove_symlinks(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_symlink() {
+            debug!("Removing the symbolic link {}", entry.path().display());
+            let _ = std::fs::remove_file(entry.path());
+        } else if file_type.is_dir() {
+            remove_symlinks(&entry.path());
+        }
+    }
+}
+
+/// Stores an uploaded file `name` in a new upload directory, extracting it if `extract` is set.
+async fn upload(state: &ServerState, name: &str, extract: bool, body: Body) -> Result<Upload> {
+    let mut components = Path::new(name).components();
+    if !matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    ) {
+        return Err(anyhow!("Invalid upload name '{}'", name));
+    }
+    let dir = state.create_upload_dir()?;
+    let file = dir.join(name);
+    let size = match write_body(body, &file, state.max_upload).await {
+        Ok(size) => size,
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&dir);
+            return Err(e);
+        }
+    };
+    info!("Uploaded {} ({} bytes)", file.display(), size);
+    if !extract {
+        return Ok(Upload { path: file, size });
+    }
+
+    let extracted = dir.join(EXTRACTED_DIR);
+    let archive = file.clone();
+    let destination = extracted.clone();
+    tokio::task::spawn_blocking(move || {
+        std::fs::create_dir(&destination)?;
+        extract_to(&archive, BundleFormat::detect(&archive)?, &destination)?;
+        remove_symlinks(&destination);
+        Ok(())
+    })
+    .await
+    .map_err(|e| anyhow!("Extraction panicked: {}", e))
+    .and_then(|result: Result<()>| result)
+    .with_context(|| format!("Extracting {}", name))?;
+    Ok(Upload {
+        path: extracted,
+        size,
+    })
+}
+
+/// Serves a file of a job, refusing the paths leaving its directory.
+fn artifact_response(job: &Job, path: &str) -> Response<Body> {
+    let relative = Path::new(path);
+    if relative
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_)))
+    {
+        return error_response(StatusCode::BAD_REQUEST, "Invalid artifact path");
+    }
+    match std::fs::read(job.dir.join(relative)) {
+        Ok(content) => Response::builder()
+            .header("content-type", "application/octet-stream")
+            .body(Body::from(content))
+            .unwrap(),
+        Err(_) => error_response(StatusCode::NOT_FOUND, "No such artifact"),
+    }
+}
+
+/// Handles a request of the API.
+pub async fn handle(state: Arc<ServerState>, request: Request<Body>) -> Response<Body> {
+    if let Some(token) = &state.token {
+        let expected = format!("Bearer {}", token);
+        let authorized = request
+            .headers()
+            .get("authorization")
+            .is_some_and(|value| value.as_bytes() == expected.as_bytes());
+        if !authorized {
+            return error_response(StatusCode::UNAUTHORIZED, "Missing or invalid bearer token");
+        }
+    }
+
+    let path = request.uri().path().trim_end_matches('/').to_string();
+    let segments: Vec<&str> = path.split('/').skip(1).collect();
+    match (request.method(), segments.as_slice()) {
+        (&Method::GET, ["health"]) => json_response(
+            StatusCode::OK,
+            &serde_json::json!({ "status": "ok", "version": env!("CARGO_PKG_VERSION") }),
+        ),
+        (&Method::GET, ["jobs"]) => {
+            let jobs: Vec<Job> = state.jobs.lock().unwrap().values().cloned().collect();
+            json_response(StatusCode::OK, &jobs)
+        }
+        (&Method::POST, ["jobs"]) if declares_more_than(&request, MAX_JOB_REQUEST_BYTES) => {
+            error_response(StatusCode::PAYLOAD_TOO_LARGE, "Job request too large")
+        }
+        (&Method::POST, ["jobs"]) => {
+            let body = match read_body(request.into_body(), MAX_JOB_REQUEST_BYTES).await {
+                Ok(body) => body,
+                Err(e) => return error_response(StatusCode::BAD_REQUEST, &e.to_string()),
+            };
+            let job_request: JobRequest = match serde_json::from_slice(&body) {
+                Ok(job_request) => job_request,
+                Err(e) => {
+                    return error_response(
+                        StatusCode::BAD_REQUEST,
+                        &format!("Invalid job request: {}", e),
+                    )
+                }
+            };
+            match state.submit(job_request) {
+                Ok(job) => json_response(StatusCode::ACCEPTED, &job),
+                Err(e) => error_response(StatusCode::BAD_REQUEST, &e.to_string()),
+            }
+        }
+        (&Method::GET, ["jobs", id]) => match state.job(id) {
+            Some(job) => json_response(StatusCode::OK, &job),
+            None => error_response(StatusCode::NOT_FOUND, "No such job"),
+        },
+        (&Method::GET, ["jobs", id, "artifacts", artifact @ ..]) if !artifact.is_empty() => {
+            match state.job(id) {
+                Some(job) => artifact_response(&job, &artifact.join("/")),
+                None => error_response(StatusCode::NOT_FOUND, "No such job"),
+            }
+        }
+        (&Method::POST, ["uploads", _]) if declares_more_than(&request, state.max_upload) => {
+            error_response(StatusCode::PAYLOAD_TOO_LARGE, "Uploaded file too large")
+        }
+        (&Method::POST, ["uploads", name]) => {
+            let name = name.to_string();
+            let extract = request
+                .uri()
+                .query()
+                .is_some_and(|query| query.split('&').any(|param| param == "extract"));
+            match upload(&state, &name, extract, request.into_body()).await {
+                Ok(upload) => json_response(StatusCode::CREATED, &upload),
+                Err(e) => error_response(StatusCode::BAD_REQUEST, &format!("{:#}", e)),
+            }
+        }
+        _ => error_response(StatusCode::NOT_FOUND, "Unknown endpoint"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn call(
+        state: &Arc<ServerState>,
+        method: Method,
+        uri: &str,
+        body: &str,
+    ) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = handle(state.clone(), request).await;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or_default())
+    }
+
+    /// Creates an empty work directory, with an empty `project/` directory to give to jobs.
+    fn work_dir(name: &str) -> PathBuf {
+        let work_dir =
+            std::env::temp_dir().join(format!("solazy_serve_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&work_dir);
+        std::fs::create_dir_all(work_dir.join("project")).unwrap();
+        work_dir.canonicalize().unwrap()
+    }
+
+    async fn upload(
+        state: &Arc<ServerState>,
+        uri: &str,
+        content: Vec<u8>,
+    ) -> (StatusCode, serde_json::Value) {
+        let request = Request::post(uri).body(Body::from(content)).unwrap();
+        let response = handle(state.clone(), request).await;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn runs_reverse_job() {
+        let work_dir = work_dir("reverse");
+        let state = Arc::new(ServerState::new(work_dir.clone(), None, 1, 16));
+
+        let (status, _) = call(&state, Method::POST, "/jobs", r#"{"command": "recap"}"#).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, body) = call(
+            &state,
+            Method::POST,
+            "/jobs",
+            r#"{"command": "reverse", "args": ["--out-dir", "/tmp"]}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"]
+            .as_str()
+            .unwrap()
+            .contains("set by the server"));
+        let bytecodes = "test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so";
+        let (status, body) = call(
+            &state,
+            Method::POST,
+            "/jobs",
+            &format!(
+                r#"{{"command": "reverse", "args": ["--mode", "disass", "--bytecodes-file", "{}"]}}"#,
+                bytecodes
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(
+            body["error"]
+                .as_str()
+                .unwrap()
+                .contains("outside the work directory"),
+            "{}",
+            body
+        );
+
+        let (status, uploaded) = upload(
+            &state,
+            "/uploads/addition_checker.so",
+            std::fs::read(bytecodes).unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{}", uploaded);
+        let request = serde_json::json!({
+            "command": "reverse",
+            "args": ["--mode", "disass", "--bytecodes-file", uploaded["path"]],
+        });
+        let (status, job) = call(&state, Method::POST, "/jobs", &request.to_string()).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let uri = format!("/jobs/{}", job["id"].as_str().unwrap());
+        let job = loop {
+            let (_, job) = call(&state, Method::GET, &uri, "").await;
+            if job["status"] != "queued" && job["status"] != "running" {
+                break job;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        };
+        assert_eq!(job["status"], "succeeded", "{}", job);
+        let artifacts: Vec<&str> = job["artifacts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|artifact| artifact.as_str())
+            .collect();
+        assert!(
+            artifacts.contains(&"reverse/run/disassembly.out"),
+            "{:?}",
+            artifacts
+        );
+
+        let request = Request::get(format!("{}/artifacts/reverse/run/disassembly.out", uri))
+            .body(Body::empty())
+            .unwrap();
+        let response = handle(state.clone(), request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let (status, _) = call(&state, Method::GET, &format!("{}/artifacts/../x", uri), "").await;
+        assert_ne!(status, StatusCode::OK);
+
+        let _ = std::fs::remove_dir_all(work_dir);
+    }
+
+    #[tokio::test]
+    async fn limits_the_request_bodies() {
+        let work_dir = work_dir("limits");
+        let state = Arc::new(ServerState::new(work_dir.clone(), None, 1, 1));
+
+        let request = Request::post("/jobs")
+            .header("content-length", MAX_JOB_REQUEST_BYTES + 1)
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(
+            handle(state.clone(), request).await.status(),
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        let (status, _) = call(
+            &state,
+            Method::POST,
+            "/jobs",
+            &" ".repeat(MAX_JOB_REQUEST_BYTES as usize + 1),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, body) = upload(&state, "/uploads/big.so", vec![0; 1024 * 1024 + 1]).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("larger than"));
+        let uploads = std::fs::read_dir(work_dir.join(UPLOADS_DIR)).unwrap();
+        assert_eq!(uploads.count(), 0);
+        let (status, _) = upload(&state, "/uploads/..", vec![0]).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let _ = std::fs::remove_dir_all(work_dir);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn extracts_the_uploaded_archives() {
+        let work_dir = work_dir("extract");
+        let state = Arc::new(ServerState::new(work_dir.clone(), None, 1, 16));
+        let sources = std::env::temp_dir().join(format!("solazy_serve_src_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&sources);
+        std::fs::create_dir_all(sources.join("src")).unwrap();
+        std::fs::write(sources.join("src/lib.rs"), "pub fn f() {}").unwrap();
+        std::os::unix::fs::symlink("/etc", sources.join("src/etc")).unwrap();
+        let archive = sources.with_extension("tar");
+        crate::helpers::run_command(
+            "tar",
+            &[
+                "-cf",
+                &archive.to_string_lossy(),
+                "-C",
+                &sources.to_string_lossy(),
+                "src",
+            ],
+            vec![],
+        )
+        .unwrap();
+
+        let (status, uploaded) = upload(
+            &state,
+            "/uploads/sources.tar?extract",
+            std::fs::read(&archive).unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{}", uploaded);
+        let extracted = PathBuf::from(uploaded["path"].as_str().unwrap());
+        assert!(extracted.join("src/lib.rs").is_file());
+        assert!(std::fs::symlink_metadata(extracted.join("src/etc")).is_err());
+        let request = JobRequest {
+            command: "sast".to_string(),
+            args: vec!["-d".to_string(), extracted.to_string_lossy().to_string()],
+        };
+        assert!(state.parse_job(&request, &work_dir.join("job")).is_ok());
+
+        let _ = std::fs::remove_dir_all(work_dir);
+        let _ = std::fs::remove_dir_all(sources);
+        let _ = std::fs::remove_file(archive);
+    }
+
+    #[test]
+    fn refuses_the_source_writing_options() {
+        let work_dir = work_dir("writing");
+        let state = ServerState::new(work_dir.clone(), None, 1, 16);
+        let parse = |args: &[&str]| {
+            let mut args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+            args.extend([
+                "-d".to_string(),
+                work_dir.join("project").to_string_lossy().to_string(),
+            ]);
+            let request = JobRequest {
+                command: "sast".to_string(),
+                args,
+            };
+            state.parse_job(&request, Path::new("/tmp/job"))
+        };
+        assert!(parse(&[]).is_ok());
+        for args in [
+            &["--fix"][..],
+            &["--fix", "--dry-run"],
+            &["--dry-run"],
+            &["--spill-ast"],
+            &["--timing-report", "timing.json"],
+            &["--timing-report=timing.json"],
+        ] {
+            let Err(error) = parse(args) else {
+                panic!("{:?} accepted", args);
+            };
+            assert!(
+                error.to_string().contains("not available to jobs"),
+                "{}",
+                error
+            );
+        }
+        let _ = std::fs::remove_dir_all(work_dir);
+    }
+
+    #[test]
+    fn refuses_the_outside_writing_options() {
+        let work_dir = work_dir("outside");
+        let state = ServerState::new(work_dir.clone(), None, 1, 16);
+        let corpus = work_dir.join("project").to_string_lossy().to_string();
+        let corpus_option = format!("--fuzz-corpus={}", corpus);
+        for args in [&["--fuzz-corpus", &corpus][..], &[&corpus_option]] {
+            let mut args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+            args.extend(["--mode", "disass", "--bytecodes-file", "program.so"].map(String::from));
+            let request = JobRequest {
+                command: "reverse".to_string(),
+                args,
+            };
+            let Err(error) = state.parse_job(&request, &work_dir.join("job")) else {
+                panic!("{:?} accepted", request.args);
+            };
+            assert!(
+                error.to_string().contains("not available to jobs"),
+                "{}",
+                error
+            );
+        }
+        let _ = std::fs::remove_dir_all(work_dir);
+    }
+
+    #[test]
+    fn confines_the_paths() {
+        let work_dir = work_dir("paths");
+        let state = ServerState::new(work_dir.clone(), None, 1, 16);
+        let outside = std::env::temp_dir().join(format!("solazy_serve_out_{}", std::process::id()));
+        std::fs::create_dir_all(&outside).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&outside, work_dir.join("link")).unwrap();
+        let parse = |command: &str, args: &[&str]| {
+            let request = JobRequest {
+                command: command.to_string(),
+                args: args.iter().map(|arg| arg.to_string()).collect(),
+            };
+            state.parse_job(&request, &work_dir.join("job"))
+        };
+        let project = work_dir.join("project").to_string_lossy().to_string();
+        let outside = outside.to_string_lossy().to_string();
+        assert!(parse("sast", &["-d", &project]).is_ok());
+        for (command, args) in [
+            ("sast", vec!["-d", outside.as_str()]),
+            ("sast", vec!["-d", &project, "--rules-dir", &outside]),
+            ("sast", vec!["-d", "/nonexistent"]),
+            (
+                "reverse",
+                vec!["--mode", "disass", "--bytecodes-file", "/etc/hostname"],
+            ),
+            ("reverse", vec!["--mode", "disass", "--bundle", &outside]),
+            (
+                "fetcher",
+                vec![
+                    "-p",
+                    "11111111111111111111111111111111",
+                    "--layout",
+                    "--account-sources",
+                    &outside,
+                ],
+            ),
+        ] {
+            let Err(error) = parse(command, &args) else {
+                panic!("{:?} accepted", args);
+            };
+            assert!(
+                error.to_string().contains("outside the work directory")
+                    || error.to_string().contains("doesn't exist"),
+                "{}",
+                error
+            );
+        }
+        #[cfg(unix)]
+        {
+            let link = work_dir.join("link").to_string_lossy().to_string();
+            assert!(parse("sast", &["-d", &link]).is_err());
+        }
+
+        let _ = std::fs::remove_dir_all(work_dir);
+        let _ = std::fs::remove_dir_all(outside);
+    }
+
+    #[test]
+    fn sets_the_output_files() {
+        let work_dir = work_dir("outputs");
+        let state = ServerState::new(work_dir.clone(), None, 1, 16);
+        let request = |args: &[&str]| JobRequest {
+            command: "sast".to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        };
+        let dir = Path::new("/tmp/job");
+        let Err(error) = state.parse_job(&request(&["--export-db", "/etc/cron.d"]), dir) else {
+            panic!("--export-db accepted");
+        };
+        assert!(error.to_string().contains("set by the server"), "{}", error);
+
+        let project = work_dir.join("project").to_string_lossy().to_string();
+        let command = state.parse_job(&request(&["-d", &project]), dir).unwrap();
+        let sast = sast_command::SastCmd::new_from_clap(&command);
+        assert_eq!(sast.export_db.as_deref(), Some("/tmp/job/db"));
+        assert_eq!(sast.timing_report, None);
+        let _ = std::fs::remove_dir_all(work_dir);
+    }
+
+    #[tokio::test]
+    async fn requires_the_token() {
+        let state = Arc::new(ServerState::new(
+            std::env::temp_dir(),
+            Some("secret".into()),
+            1,
+            16,
+        ));
+        let (status, _) = call(&state, Method::GET, "/health", "").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let request = Request::get("/health")
+            .header("authorization", "Bearer secret")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(handle(state, request).await.status(), StatusCode::OK);
+    }
+}
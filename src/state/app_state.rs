@@ -1,7 +1,9 @@
+use crate::helpers::events::{self, Event};
 use crate::state::build_state::BuildState;
 use crate::state::sast_state::SastState;
 use crate::{commands, Cli, Commands};
-use log::{error, info};
+use log::{debug, error, info};
+use std::time::Instant;
 
 /// Represents the global application state, including parsed CLI options and collected results.
 ///
@@ -22,39 +24,55 @@ impl AppState {
     /// # Behavior
     ///
     /// If no command is matched, it logs a message without performing any action.
-    pub async fn run_cli(&mut self) {
-        match &self.cli.command {
-            Commands::Reverse {
-                mode,
-                out_dir,
-                bytecodes_file,
-                labeling,
-                reduced,
-                only_entrypoint,
-            } => self.run_reverse(
-                mode.clone(),
-                out_dir.clone(),
-                bytecodes_file.clone(),
-                *labeling,
-                *reduced,
-                *only_entrypoint,
-            ),
+    /// With `--events-json`, the command is surrounded by `started` and `finished` events.
+    ///
+    /// # Returns
+    ///
+    /// The result of the command, already logged on failure.
+    pub async fn run_cli(&mut self) -> anyhow::Result<()> {
+        let Some(cli_command) = &self.cli.command else {
+            return match self.cli.generate_man.clone() {
+                Some(out_dir) => self.run_generate_man(&out_dir),
+                None => Ok(()),
+            };
+        };
+        let command = command_name(cli_command);
+        let started = Instant::now();
+        events::emit(Event::Started {
+            command: command.to_string(),
+            arguments: std::env::args().collect(),
+        });
+
+        let result = match cli_command {
+            cmd @ Commands::Reverse { .. } => {
+                self.run_reverse(&commands::reverse_command::ReverseCmd::new_from_clap(cmd))
+            }
             Commands::Dotting {
                 config,
                 reduced_dot_path,
                 full_dot_path,
+                depth,
             } => self.run_dotting(
                 config.clone(),
                 reduced_dot_path.clone(),
                 full_dot_path.clone(),
+                *depth,
+            ),
+            cmd @ Commands::Asm { .. } => {
+                self.run_asm(&commands::asm_command::AsmCmd::new_from_clap(cmd))
+            }
+            cmd @ Commands::Patch { .. } => {
+                self.run_patch(&commands::patch_command::PatchCmd::new_from_clap(cmd))
+            }
+            cmd @ Commands::ImportNames { .. } => self.run_import_names(
+                &commands::import_names_command::ImportNamesCmd::new_from_clap(cmd),
             ),
-            Commands::Fetcher {
-                program_id,
-                out_dir,
-                rpc_url,
-            } => {
-                self.run_fetcher(program_id.clone(), out_dir.clone(), rpc_url.clone())
-                    .await;
+            cmd @ Commands::Query { .. } => {
+                self.run_query(&commands::query_command::QueryCmd::new_from_clap(cmd))
+            }
+            cmd @ Commands::Fetcher { .. } => {
+                self.run_fetcher(&commands::fetcher_command::FetcherCmd::new_from_clap(cmd))
+                    .await
             }
             cmd @ Commands::Recap { .. } => {
                 self.run_recap(&commands::recap_command::RecapCmd::new_from_clap(cmd))
@@ -65,29 +83,67 @@ impl AppState {
             cmd @ Commands::Sast { .. } => {
                 self.run_sast(&commands::sast_command::SastCmd::new_from_clap(cmd))
             },
+            cmd @ Commands::ScanAll { .. } => {
+                self.run_scan_all(&commands::scan_all_command::ScanAllCmd::new_from_clap(cmd))
+            }
+            cmd @ Commands::Doctor { .. } => {
+                self.run_doctor(&commands::doctor_command::DoctorCmd::new_from_clap(cmd))
+            }
+            cmd @ Commands::Serve { .. } => {
+                self.run_serve(&commands::serve_command::ServeCmd::new_from_clap(cmd))
+                    .await
+            }
+            cmd @ Commands::Fuzz { .. } => {
+                self.run_fuzz(&commands::fuzz_command::FuzzCmd::new_from_clap(cmd))
+            }
+            cmd @ Commands::Completions { .. } => self.run_completions(
+                &commands::completions_command::CompletionsCmd::new_from_clap(cmd),
+            ),
             cmd@ Commands::AstUtils { .. } => {
-                self.run_ast_utils(&commands::ast_utils_command::AstUtilsCmd::new_from_clap(cmd)).await;
+                self.run_ast_utils(&commands::ast_utils_command::AstUtilsCmd::new_from_clap(cmd)).await
             }
-            _ => info!("No command selected"),
-        }
+            _ => {
+                info!("No command selected");
+                Ok(())
+            }
+        };
+
+        events::emit(Event::Finished {
+            command: command.to_string(),
+            success: result.is_ok(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+            duration_ms: started.elapsed().as_millis() as u64,
+        });
+        result
     }
 
-    /// Executes the build command for the given project path and stores the result.
+    /// Executes the build command for the given project paths and stores the results.
     ///
     /// # Arguments
     ///
-    /// * `target_dir` - Path to the project to build.
+    /// * `target_dirs` - Paths to the projects to build.
     /// * `out_dir` - Output directory for build artifacts.
     ///
     /// # Side Effects
     ///
-    /// On success, the resulting `BuildState` is stored in `build_states`.
-    /// On failure, an error is logged.
-    pub fn build_project(&mut self, cmd: &commands::build_command::BuildCmd) {
+    /// The resulting `BuildState`s are stored in `build_states`, including those of the
+    /// projects that were built when others failed. On failure, an error is logged.
+    pub fn build_project(&mut self, cmd: &commands::build_command::BuildCmd) -> anyhow::Result<()> {
         match commands::build_command::run(cmd) {
-            Ok(bs) => self.build_states.push(bs),
-            Err(e) => error!("An error occurred during build of {} {}", cmd.target_dir, e),
+            Ok(bs) => self.build_states.extend(bs),
+            Err(e) => {
+                if let Some(failures) = e.downcast_ref::<commands::build_command::BuildFailures>() {
+                    self.build_states.extend(failures.states.iter().cloned());
+                }
+                error!(
+                    "An error occurred during build of {} {}",
+                    cmd.target_dirs.join(", "),
+                    e
+                );
+                return Err(e);
+            }
         }
+        Ok(())
     }
 
     /// Runs static analysis (SAST) on the given project using the provided rule set.
@@ -102,45 +158,58 @@ impl AppState {
     ///
     /// On success, the resulting `SastState` is stored in `sast_states`.
     /// On failure, an error is logged.
-    fn run_sast(&mut self, cmd: &commands::sast_command::SastCmd) {
+    fn run_sast(&mut self, cmd: &commands::sast_command::SastCmd) -> anyhow::Result<()> {
         match commands::sast_command::run(cmd) {
             Ok(ss) => self.sast_states.extend(ss),
-            Err(e) => error!("An error occurred during SAST of {} {}", cmd.target_dir, e),
+            Err(e) => {
+                error!("An error occurred during SAST of {} {}", cmd.target_dir, e);
+                return Err(e);
+            }
         }
+        Ok(())
+    }
+
+    /// Runs SAST (and optionally recap) on every project listed in a workspace file.
+    ///
+    /// # Arguments
+    ///
+    /// * `cmd` - A reference to the `ScanAllCmd` struct, containing the workspace file and the
+    ///   path of the aggregated report.
+    ///
+    /// # Side Effects
+    ///
+    /// The `SastState`s of all the projects are stored in `sast_states`.
+    /// On failure, an error is logged.
+    fn run_scan_all(&mut self, cmd: &commands::scan_all_command::ScanAllCmd) -> anyhow::Result<()> {
+        match commands::scan_all_command::run(cmd) {
+            Ok(ss) => self.sast_states.extend(ss),
+            Err(e) => {
+                error!("An error occurred during scan-all of {}: {}", cmd.config, e);
+                return Err(e);
+            }
+        }
+        Ok(())
     }
 
     /// Runs reverse engineering (static analysis) based on compiled bytecode.
     ///
     /// # Arguments
     ///
-    /// * `mode` - The mode of analysis (e.g., disass, cfg, both).
-    /// * `out_dir` - Directory to write output files.
-    /// * `bytecodes_file` - Path to the compiled eBPF bytecode (.so).
-    /// * `labeling` - Whether to enable symbol and section labeling.
+    /// * `cmd` - A reference to the `ReverseCmd` struct, containing the mode of analysis
+    ///   (disass, cfg, both), the bytecode and output paths, and the CFG/labeling options.
     ///
     /// # Side Effects
     ///
     /// Logs success or error messages based on the result.
-    fn run_reverse(
-        &mut self,
-        mode: String,
-        out_dir: String,
-        bytecodes_file: String,
-        labeling: bool,
-        reduced: bool,
-        only_entrypoint: bool,
-    ) {
-        match commands::reverse_command::run(
-            mode,
-            out_dir,
-            bytecodes_file,
-            labeling,
-            reduced,
-            only_entrypoint,
-        ) {
+    fn run_reverse(&mut self, cmd: &commands::reverse_command::ReverseCmd) -> anyhow::Result<()> {
+        match commands::reverse_command::run(cmd) {
             Ok(_) => info!("Reverse (static analysis) completed."),
-            Err(e) => error!("An error occurred during reverse (static analysis): {}", e),
+            Err(e) => {
+                error!("An error occurred during reverse (static analysis): {}", e);
+                return Err(e);
+            }
         }
+        Ok(())
     }
 
     /// Executes the dotting process to enrich a reduced `.dot` control flow graph file.
@@ -154,28 +223,128 @@ impl AppState {
     /// * `config` - Path to the JSON file listing the `cluster_<id>` functions to re-add.
     /// * `reduced_dot_path` - Path to the previously generated reduced CFG file.
     /// * `full_dot_path` - Path to the full CFG file used as source of truth.
+    /// * `depth` - Optional number of call edges to follow from the requested functions.
     ///
     /// # Behavior
     ///
     /// Logs success if the process completes without error, or prints an error otherwise.
-    fn run_dotting(&mut self, config: String, reduced_dot_path: String, full_dot_path: String) {
-        match commands::dotting_command::run(config, reduced_dot_path, full_dot_path) {
+    fn run_dotting(
+        &mut self,
+        config: String,
+        reduced_dot_path: String,
+        full_dot_path: String,
+        depth: Option<usize>,
+    ) -> anyhow::Result<()> {
+        match commands::dotting_command::run(config, reduced_dot_path, full_dot_path, depth) {
             Ok(_) => info!("Dotting completed successfully."),
-            Err(e) => error!("Dotting failed: {}", e),
+            Err(e) => {
+                error!("Dotting failed: {}", e);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Assembles instructions written in the syntax of the disassembly.
+    ///
+    /// # Arguments
+    ///
+    /// * `cmd` - A reference to the `AsmCmd` struct, containing the input (stdin by default), the
+    ///   optional program giving the labels and SBPF version, the address of the first
+    ///   instruction, and the output format and file.
+    fn run_asm(&mut self, cmd: &commands::asm_command::AsmCmd) -> anyhow::Result<()> {
+        if let Err(e) = commands::asm_command::run(cmd) {
+            error!("Assembly failed: {}", e);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Applies a JSON patch spec to a compiled program and writes the patched program.
+    ///
+    /// # Arguments
+    ///
+    /// * `cmd` - A reference to the `PatchCmd` struct, containing the program, the patch spec
+    ///   and the optional output path (`<input>.patched.so` by default).
+    fn run_patch(&mut self, cmd: &commands::patch_command::PatchCmd) -> anyhow::Result<()> {
+        match commands::patch_command::run(cmd) {
+            Ok(output) => info!("Patched program written to '{}'", output.display()),
+            Err(e) => {
+                error!("Patch failed: {}", e);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Generates a skeleton harness spec from an IDL, or validates a harness spec.
+    ///
+    /// # Arguments
+    ///
+    /// * `cmd` - A reference to the `FuzzCmd` struct, containing the harness spec to validate, or
+    ///   the IDL and the path of the skeleton to write.
+    fn run_fuzz(&mut self, cmd: &commands::fuzz_command::FuzzCmd) -> anyhow::Result<()> {
+        if let Err(e) = commands::fuzz_command::run(cmd) {
+            error!("Fuzz failed: {}", e);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Merges the function names exported by another tool into the annotations file of a program.
+    ///
+    /// # Arguments
+    ///
+    /// * `cmd` - A reference to the `ImportNamesCmd` struct, containing the program, the names
+    ///   file and the optional annotations file (`annotations.json` next to the program by default).
+    fn run_import_names(
+        &mut self,
+        cmd: &commands::import_names_command::ImportNamesCmd,
+    ) -> anyhow::Result<()> {
+        match commands::import_names_command::run(cmd) {
+            Ok((path, summary)) => info!(
+                "{} names and {} comments imported into '{}'",
+                summary.renames,
+                summary.comments,
+                path.display()
+            ),
+            Err(e) => {
+                error!("Import of the names failed: {}", e);
+                return Err(e);
+            }
         }
+        Ok(())
+    }
+
+    /// Prints the blocks (or functions) of a previous reverse run matching the filters.
+    ///
+    /// # Arguments
+    ///
+    /// * `cmd` - A reference to the `QueryCmd` struct, containing the run directory and the
+    ///   filters.
+    fn run_query(&mut self, cmd: &commands::query_command::QueryCmd) -> anyhow::Result<()> {
+        match commands::query_command::run(cmd) {
+            Ok(count) => debug!("{} matches", count),
+            Err(e) => {
+                error!("Query failed: {}", e);
+                return Err(e);
+            }
+        }
+        Ok(())
     }
 
     /// Fetches the bytecode of a Solana program and writes it to a local file.
     ///
     /// This function wraps the `fetcher_command::run` logic with appropriate logging,
     /// and resolves the default Solana RPC endpoint if none is provided. It writes
-    /// the fetched bytecode to `<output_path>/fetched_program.so`.
+    /// the fetched bytecode to `<out_dir>/fetcher/<label or timestamp>/fetched_program.so` (or
+    /// `fetched_program_slot_<slot>.so` when a slot is requested).
     ///
     /// # Arguments
     ///
-    /// * `program_id` - The Solana program ID to fetch from the blockchain.
-    /// * `output_path` - Path to the directory where the program will be saved.
-    /// * `rpc_url` - Optional RPC endpoint; if `None`, defaults to the mainnet RPC (`https://api.mainnet-beta.solana.com`).
+    /// * `cmd` - A reference to the `FetcherCmd` struct, containing the program ID, the output
+    ///   directory, the optional RPC endpoint (defaults to `https://api.mainnet-beta.solana.com`)
+    ///   and the optional slot at which the wanted deployment was live.
     ///
     /// # Logging
     ///
@@ -185,43 +354,134 @@ impl AppState {
     /// # Errors
     ///
     /// This function logs but does not propagate errors. All failure handling is internal.
-    async fn run_fetcher(
-        &mut self,
-        program_id: String,
-        output_path: String,
-        rpc_url: Option<String>,
-    ) {
-        let display_rpc_url = match &rpc_url {
+    async fn run_fetcher(&mut self, cmd: &commands::fetcher_command::FetcherCmd) -> anyhow::Result<()> {
+        let display_rpc_url = match &cmd.rpc_url {
             Some(url) => format!("{url}"),
             None => format!("https://api.mainnet-beta.solana.com (by default)"),
         };
 
-        match commands::fetcher_command::run(program_id, output_path.clone(), rpc_url.clone()).await
-        {
-            Ok(_) => info!(
-                "Bytecode successfully fetched from RPC '{}' and saved to '{}/fetched_program.so'",
-                display_rpc_url, output_path
+        match commands::fetcher_command::run(cmd).await {
+            Ok(out_path) => info!(
+                "Bytecode successfully fetched from RPC '{}' and saved to '{}'",
+                display_rpc_url,
+                out_path.display()
             ),
-            Err(e) => error!("Fetcher failed: {}", e),
+            Err(e) => {
+                error!("Fetcher failed: {}", e);
+                return Err(e);
+            }
         }
+        Ok(())
     }
-    
-    async fn run_ast_utils(&mut self, cmd: &commands::ast_utils_command::AstUtilsCmd) {
+
+    async fn run_ast_utils(&mut self, cmd: &commands::ast_utils_command::AstUtilsCmd) -> anyhow::Result<()> {
         match commands::ast_utils_command::run(cmd) {
             Ok(_) => info!("AST utils completed."),
-            Err(e) => error!("An error occurred during AST utils: {}", e),
+            Err(e) => {
+                error!("An error occurred during AST utils: {}", e);
+                return Err(e);
+            }
         }
+        Ok(())
     }
 
     fn run_recap(
         &mut self,
         cmd: &commands::recap_command::RecapCmd,
-    ) {
+    ) -> anyhow::Result<()> {
         match commands::recap_command::run(
             cmd
         ) {
             Ok(_) => info!("Recap completed."),
-            Err(e) => error!("An error occurred during recap: {}", e),
+            Err(e) => {
+                error!("An error occurred during recap: {}", e);
+                return Err(e);
+            }
         }
+        Ok(())
+    }
+
+    /// Serves the HTTP API running `sast`, `reverse` and `fetcher` jobs until interrupted.
+    ///
+    /// # Arguments
+    ///
+    /// * `cmd` - A reference to the `ServeCmd` struct, containing the listen address, the work
+    ///   directory of the jobs, the number of concurrent jobs, the upload size limit and the
+    ///   optional bearer token.
+    async fn run_serve(&mut self, cmd: &commands::serve_command::ServeCmd) -> anyhow::Result<()> {
+        if let Err(e) = commands::serve_command::run(cmd).await {
+            error!("Server failed: {}", e);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Writes the completion script of a shell to stdout.
+    ///
+    /// # Arguments
+    ///
+    /// * `cmd` - A reference to the `CompletionsCmd` struct, containing the shell.
+    fn run_completions(
+        &mut self,
+        cmd: &commands::completions_command::CompletionsCmd,
+    ) -> anyhow::Result<()> {
+        commands::completions_command::run(cmd).map_err(|e| {
+            error!("An error occurred during completions generation: {}", e);
+            e
+        })
+    }
+
+    /// Writes the man pages of the CLI (`--generate-man`).
+    ///
+    /// # Arguments
+    ///
+    /// * `out_dir` - The directory receiving the pages.
+    fn run_generate_man(&mut self, out_dir: &str) -> anyhow::Result<()> {
+        commands::completions_command::generate_man(std::path::Path::new(out_dir)).map_err(|e| {
+            error!("An error occurred during man pages generation: {}", e);
+            e
+        })
+    }
+
+    /// Checks the toolchain (cargo, rustc, Solana and Anchor CLIs, graphviz) and prints
+    /// the remediation steps for what is missing.
+    ///
+    /// # Arguments
+    ///
+    /// * `cmd` - A reference to the `DoctorCmd` struct, containing the optional project whose
+    ///   `Anchor.toml` pins the expected versions.
+    fn run_doctor(&mut self, cmd: &commands::doctor_command::DoctorCmd) -> anyhow::Result<()> {
+        match commands::doctor_command::run(cmd) {
+            Ok(_) => info!("Doctor completed."),
+            Err(e) => {
+                error!("Doctor found problems: {}", e);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the name of a command, as given on the command line.
+fn command_name(cmd: &Commands) -> &'static str {
+    match cmd {
+        Commands::Build { .. } => "build",
+        Commands::Reverse { .. } => "reverse",
+        Commands::Dotting { .. } => "dotting",
+        Commands::Patch { .. } => "patch",
+        Commands::Asm { .. } => "asm",
+        Commands::ImportNames { .. } => "import-names",
+        Commands::Query { .. } => "query",
+        Commands::Fetcher { .. } => "fetcher",
+        Commands::AstUtils { .. } => "ast-utils",
+        Commands::Sast { .. } => "sast",
+        Commands::Recap { .. } => "recap",
+        Commands::Doctor { .. } => "doctor",
+        Commands::ScanAll { .. } => "scan-all",
+        Commands::Serve { .. } => "serve",
+        Commands::Fuzz { .. } => "fuzz",
+        Commands::Test {} => "test",
+        Commands::Clean {} => "clean",
+        Commands::Completions { .. } => "completions",
     }
 }
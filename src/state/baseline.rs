@@ -0,0 +1,105 @@
+//! Baseline of the triaged SAST findings (`.solazy-baseline.json` in the scanned directory), so
+//! that a finding reviewed once is known as such in the next scans.
+//!
+//! A finding is identified by its rule, its file (relative to the scanned directory) and the
+//! access path of the matched node, which, unlike its position, doesn't change when lines are
+//! added above it.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::state::sast_state::{SynAstResult, SynMatchResult};
+
+/// Name of the baseline file, in the scanned directory, when `--baseline` is not given.
+pub const BASELINE_FILE: &str = ".solazy-baseline.json";
+
+/// Identity of a finding across scans.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct FindingKey {
+    /// Rule file of the finding.
+    pub rule: String,
+    /// File of the finding, relative to the scanned directory, with `/` separators.
+    pub file: String,
+    pub access_path: String,
+}
+
+impl FindingKey {
+    pub fn new(file: &str, result: &SynAstResult, match_result: &SynMatchResult) -> Self {
+        Self {
+            rule: result.rule_filename.clone(),
+            file: file.to_string(),
+            access_path: match_result.access_path.clone(),
+        }
+    }
+}
+
+/// The triaged findings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    #[serde(default)]
+    pub triaged: BTreeSet<FindingKey>,
+}
+
+impl Baseline {
+    /// Reads the baseline at `path`, empty if the file doesn't exist yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Reading baseline {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid baseline {}", path.display()))
+    }
+
+    /// Writes the baseline as JSON at `path`.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)? + "\n")
+            .with_context(|| format!("Writing baseline {}", path.display()))
+    }
+
+    pub fn is_triaged(&self, key: &FindingKey) -> bool {
+        self.triaged.contains(key)
+    }
+
+    /// Marks a finding as triaged, or as not triaged anymore if it was.
+    ///
+    /// # Returns
+    ///
+    /// Whether the finding is now triaged.
+    pub fn toggle(&mut self, key: &FindingKey) -> bool {
+        if self.triaged.remove(key) {
+            return false;
+        }
+        self.triaged.insert(key.clone());
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_baseline_toggle_and_persist() {
+        let path = Path::new("temp_test_dir_baseline.json");
+        let mut baseline = Baseline::load(path).unwrap();
+        assert!(baseline.triaged.is_empty());
+
+        let key = FindingKey {
+            rule: "missing_signer_check.star".to_string(),
+            file: "programs/vault/src/lib.rs".to_string(),
+            access_path: "[3].struct.fields[1]".to_string(),
+        };
+        assert!(baseline.toggle(&key));
+        baseline.save(path).unwrap();
+        let mut reloaded = Baseline::load(path).unwrap();
+        assert!(reloaded.is_triaged(&key));
+
+        assert!(!reloaded.toggle(&key));
+        assert!(!reloaded.is_triaged(&key));
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -1,3 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+/// Outcome of the build of a project, recorded in `build-manifest.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuildState {
     #[allow(dead_code)]
     pub name: String,
@@ -5,4 +9,25 @@ pub struct BuildState {
     pub target_dir: String,
     #[allow(dead_code)]
     pub out_dir: String,
+    /// Tool that built the project.
+    pub toolchain: BuildToolchain,
+    /// Programs found in `target/deploy` after the build.
+    pub artifacts: Vec<BuildArtifact>,
+}
+
+/// The `anchor` or `cargo build-sbf` used for a build.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildToolchain {
+    /// First line of its `--version`, if it could be read.
+    pub version: Option<String>,
+    pub path: String,
+    /// `true` for an Anchor CLI installed by sol-azy in `~/.solazy/toolchains`.
+    pub managed: bool,
+}
+
+/// A program built by a project.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildArtifact {
+    pub path: String,
+    pub sha256: String,
 }
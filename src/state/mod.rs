@@ -3,11 +3,18 @@
 //! This module defines the persistent and transitional state for the CLI and analysis pipelines:
 //!
 //! - [`app_state`] — Central dispatcher that holds CLI arguments and accumulated results.
+//! - [`baseline`] — The SAST findings already triaged, kept between scans.
 //! - [`build_state`] — Represents the outcome of a build process (e.g., output paths).
 //! - [`sast_state`] — Contains static analysis results, syntax trees, and rule evaluations.
+//! - [`taxonomy`] — Severity / certainty, or configured taxonomies, in which findings are rated.
 //!
 //! These types are used throughout the CLI flow to coordinate between command execution and result reporting.
 
+#[cfg(feature = "cli")]
 pub mod app_state;
+#[cfg(feature = "cli")]
+pub mod baseline;
+#[cfg(feature = "cli")]
 pub mod build_state;
 pub mod sast_state;
+pub mod taxonomy;
@@ -1,11 +1,41 @@
-use crate::engines::starlark_engine::{StarlarkEngine, StarlarkRuleDirExt, StarlarkRulesDir};
+#[cfg(any(feature = "cli", feature = "wasm"))]
+use crate::constraints::{ConstraintReport, CONSTRAINT_RULES};
+#[cfg(feature = "cli")]
+use crate::deps::{audit_lock, sast_results as dependency_results, AdvisoryDb};
+#[cfg(any(feature = "cli", feature = "wasm"))]
+use crate::engines::starlark_engine::{
+    load_user_templates, RuleFilter, RuleLimits, RuleTrust, StarlarkEngine, StarlarkRuleDirExt,
+    StarlarkRulesDir,
+};
+#[cfg(any(feature = "cli", feature = "wasm"))]
+use crate::helpers::timing;
+#[cfg(any(feature = "cli", feature = "wasm"))]
+use crate::parsers::syn_ast::{parse_rust_source, ColumnEncoding};
 use crate::parsers::syn_ast::{AstPositions, SourcePosition};
+#[cfg(feature = "cli")]
 use crate::printers::sast_printer::SastPrinter;
+#[cfg(any(feature = "cli", feature = "wasm"))]
+use crate::pubkeys::{PubkeyInventory, PUBKEY_RULES};
+use crate::state::taxonomy::Classification;
+#[cfg(any(feature = "cli", feature = "wasm"))]
+use crate::state::taxonomy::Taxonomy;
 use anyhow::{Context, Result};
-use log::{debug, error};
+#[cfg(any(feature = "cli", feature = "wasm"))]
+use log::{debug, error, warn};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+#[cfg(feature = "cli")]
+use std::collections::BTreeSet;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
+use std::path::{Path, PathBuf};
+#[cfg(all(any(feature = "cli", feature = "wasm"), not(target_arch = "wasm32")))]
+use std::time::Instant;
+
+/// Metadata of the matches of a library crate: the programs depending on it (see
+/// [`SastState::record_dependent_programs`]).
+#[cfg(any(feature = "cli", feature = "wasm"))]
+pub const PROGRAMS_METADATA: &str = "programs";
 
 /// Represents the severity level of a rule match in static analysis.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -35,6 +65,16 @@ pub struct SynRuleMetadata {
     pub severity: Severity,
     pub certainty: Certainty,
     pub description: String,
+    /// Name of the entry of `template_manager.FINDING_TEMPLATES` used to describe each match.
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Values interpolated in the finding template, overriding its default `params`.
+    #[serde(default)]
+    pub template_params: HashMap<String, String>,
+    /// Scores of the rule on the axes of a taxonomy (`{"likelihood": 4}`), see
+    /// [`crate::state::taxonomy`].
+    #[serde(default)]
+    pub scores: BTreeMap<String, u32>,
 }
 
 impl SynRuleMetadata {
@@ -47,10 +87,24 @@ impl SynRuleMetadata {
             severity: Severity::Unknown,
             certainty: Certainty::Unknown,
             description: "DEFAULT_RULE_DESC".to_string(),
+            template: None,
+            template_params: HashMap::new(),
+            scores: BTreeMap::new(),
         }
     }
 }
 
+/// A parameterizable finding description selected through `RULE_METADATA["template"]`.
+///
+/// Placeholders (`{ident}`, `{parent}`, `{access_path}`, `{rule}` and any `{param}`)
+/// are interpolated for each match by `SynAstResult::render_description`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FindingTemplate {
+    pub description: String,
+    #[serde(default)]
+    pub params: HashMap<String, String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 /// Represents a single match result from a syntactic rule evaluation.
 ///
@@ -62,6 +116,28 @@ pub struct SynMatchResult {
     pub metadata: HashMap<String, serde_json::Value>,
     pub ident: String,
     pub parent: String,
+    /// The fix suggested by the rule, applied with `sast --fix`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix: Option<SynFix>,
+}
+
+/// A fix of a match: edits of the source, which `sast --fix` applies (see [`crate::fixer`]).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SynFix {
+    /// What the fix does, e.g. "use `u32::try_from`".
+    pub description: String,
+    pub edits: Vec<SynFixEdit>,
+}
+
+/// An edit of a fix: the text of a span replaced.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SynFixEdit {
+    pub position: SourcePosition,
+    pub replacement: String,
+    /// The text to replace, when the span is only known to be on its lines (the position of
+    /// the closest identifier, for instance): it is then looked up there.
+    #[serde(default)]
+    pub original: Option<String>,
 }
 
 /// Stores the result of evaluating a single syntactic rule against a file's AST.
@@ -69,6 +145,7 @@ pub struct SynMatchResult {
 /// Contains the original rule filename, raw JSON result string, match results,
 /// and associated rule metadata.
 impl SynMatchResult {
+    #[cfg(any(feature = "cli", feature = "wasm"))]
     pub fn get_location_metadata(&self) -> Result<SourcePosition> {
         let value = self
             .metadata
@@ -101,6 +178,11 @@ pub struct SynAstResult {
     pub result: String,
     pub matches: Vec<SynMatchResult>,
     pub rule_metadata: SynRuleMetadata,
+    #[serde(default)]
+    pub finding_template: Option<FindingTemplate>,
+    /// Rating of the rule in the taxonomy of the scan (the built-in one by default).
+    #[serde(default)]
+    pub classification: Classification,
 }
 
 impl SynAstResult {
@@ -117,6 +199,7 @@ impl SynAstResult {
     /// # Returns
     ///
     /// A parsed `SynAstResult` or an error if JSON deserialization fails.
+    #[cfg(any(feature = "cli", feature = "wasm"))]
     pub fn new_from_json(rule_filename: String, result: String) -> Result<Self> {
         let parsed: serde_json::Value = serde_json::from_str(&result)
             .with_context(|| format!("Failed to parse JSON result for rule: {}", rule_filename))?;
@@ -161,23 +244,105 @@ impl SynAstResult {
             }
         };
 
+        let finding_template = match parsed.get("finding_template") {
+            Some(serde_json::Value::Null) | None => {
+                if let Some(template) = &rule_metadata.template {
+                    warn!(
+                        "Unknown finding template '{}' in rule {}",
+                        template, rule_filename
+                    );
+                }
+                None
+            }
+            Some(template_value) => serde_json::from_value(template_value.clone())
+                .map_err(|err| {
+                    warn!(
+                        "Failed to deserialize finding template for rule {}: {}",
+                        rule_filename, err
+                    )
+                })
+                .ok(),
+        };
+
+        let classification = Taxonomy::default().classify(&rule_metadata);
         Ok(Self {
             rule_filename,
             result,
             matches,
             rule_metadata,
+            finding_template,
+            classification,
         })
     }
+
+    /// Renders the finding template of the rule for a given match.
+    ///
+    /// The parameters are taken from the template, then the rule's `template_params`, then the
+    /// string values of the match metadata. Unknown placeholders are left untouched.
+    ///
+    /// # Returns
+    ///
+    /// The interpolated description, or `None` if the rule does not use a finding template.
+    #[cfg(any(feature = "cli", feature = "wasm"))]
+    pub fn render_description(&self, match_result: &SynMatchResult) -> Option<String> {
+        let template = self.finding_template.as_ref()?;
+
+        let mut values: HashMap<&str, &str> = template
+            .params
+            .iter()
+            .chain(self.rule_metadata.template_params.iter())
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        // string metadata of the match, e.g. the variant of the issue found by the rule
+        values.extend(
+            match_result
+                .metadata
+                .iter()
+                .filter_map(|(k, v)| Some((k.as_str(), v.as_str()?))),
+        );
+        values.insert("ident", &match_result.ident);
+        values.insert("parent", &match_result.parent);
+        values.insert("access_path", &match_result.access_path);
+        values.insert("rule", &self.rule_metadata.name);
+
+        Some(interpolate(&template.description, &values))
+    }
+}
+
+/// Replaces each `{key}` of `text` by its value in `values`.
+#[cfg(any(feature = "cli", feature = "wasm"))]
+fn interpolate(text: &str, values: &HashMap<&str, &str>) -> String {
+    let mut rendered = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}').and_then(|end| Some((end, values.get(&after[..end])?))) {
+            Some((end, value)) => {
+                rendered.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                rendered.push('{');
+                rest = after;
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
 }
 
 /// Represents an enriched syntax tree (`syn::File`) along with AST positions
 /// and a collection of results from rule evaluations.
 #[derive(Clone)]
 pub struct SynAst {
-    #[allow(dead_code)]
+    #[cfg_attr(not(any(feature = "cli", feature = "wasm")), allow(dead_code))]
     pub ast: syn::File,
     pub ast_positions: AstPositions,
+    /// The JSON syntax tree given to the rules, `Null` once spilled to `ast_json_file`.
     pub ast_json: serde_json::Value,
+    /// The file holding the JSON syntax tree, when it was spilled to disk to save memory.
+    pub ast_json_file: Option<PathBuf>,
     pub results: Vec<SynAstResult>,
 }
 
@@ -192,52 +357,156 @@ impl fmt::Debug for SynAst {
 }
 
 impl SynAst {
+    /// Writes the JSON syntax tree of the file `path` to a file of `dir` and drops it from
+    /// memory, the rules reading it back with [`Self::ast_json_string`].
+    pub fn spill_ast_json(&mut self, path: &str, dir: &Path) -> Result<()> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Creating the directory {}", dir.display()))?;
+        let file = dir.join(format!(
+            "{}.json",
+            hex::encode(Sha256::digest(path.as_bytes()))
+        ));
+        std::fs::write(&file, serde_json::to_vec(&self.ast_json)?)
+            .with_context(|| format!("Spilling the syntax tree of {}", path))?;
+        self.ast_json = serde_json::Value::Null;
+        self.ast_json_file = Some(file);
+        Ok(())
+    }
+
+    /// Returns the serialized JSON syntax tree, read back from its file if it was spilled.
+    pub fn ast_json_string(&self) -> Result<String> {
+        match &self.ast_json_file {
+            Some(file) => std::fs::read_to_string(file)
+                .with_context(|| format!("Reading the spilled syntax tree {}", file.display())),
+            None => Ok(serde_json::to_string(&self.ast_json)?),
+        }
+    }
+
+    /// Replaces the JSON syntax tree, in its file if it was spilled.
+    #[cfg(feature = "cli")]
+    pub fn set_ast_json(&mut self, ast_json: serde_json::Value) -> Result<()> {
+        match &self.ast_json_file {
+            Some(file) => std::fs::write(file, serde_json::to_vec(&ast_json)?)
+                .with_context(|| format!("Spilling the syntax tree to {}", file.display())),
+            None => {
+                self.ast_json = ast_json;
+                Ok(())
+            }
+        }
+    }
+
     /// Applies all rules in a directory to this syntax tree using the provided engine.
     ///
     /// # Arguments
     ///
+    /// * `path` - The path of the file, as reported in the rule coverage.
     /// * `rules_dir` - A directory of Starlark-based rule files.
     /// * `starlark_engine` - The engine used to evaluate rules.
-    ///
-    /// # Returns
-    ///
-    /// `true` if at least one rule was applied successfully, otherwise `false`.
+    /// * `coverage` - The rule coverage, updated with the evaluation of each rule.
+    #[cfg(any(feature = "cli", feature = "wasm"))]
     pub fn scan_ast(
         &mut self,
+        path: &str,
         rules_dir: &StarlarkRulesDir,
         starlark_engine: &StarlarkEngine,
-    ) -> bool {
-        rules_dir
-            .iter()
-            .map(|rule| {
-                debug!("Applying rule {}", rule.filename);
-                let res = match starlark_engine.eval_syn_rule(
-                    rule.filename.as_str(),
-                    rule.content.clone(),
-                    self,
-                ) {
-                    Ok(res) => res,
-                    Err(e) => {
-                        error!("Failed to evaluate rule: {}", e);
-                        return false;
-                    }
-                };
-                match SynAstResult::new_from_json(rule.filename.clone(), res.clone()) {
-                    Ok(result) => {
-                        debug!("Matches num: {}", result.matches.len());
-                        self.results.push(result);
-                        true
-                    }
-                    Err(e) => {
-                        error!("Failed to parse result: {}", e);
-                        false
-                    }
+        coverage: &mut RuleCoverage,
+    ) {
+        // the tree is serialized once for all the rules, and only kept while they run
+        let ast_json = timing::time("serialize", || self.ast_json_string());
+        for rule in rules_dir.iter() {
+            debug!("Applying rule {}", rule.filename);
+            let _span = timing::span(&rule.filename);
+            // there is no clock on wasm32, where the evaluations are not timed
+            #[cfg(not(target_arch = "wasm32"))]
+            let start = Instant::now();
+            let res = match &ast_json {
+                Ok(ast_json) => starlark_engine.eval_rule_on_json(rule, ast_json),
+                Err(e) => Err(anyhow::anyhow!("{:#}", e)),
+            }
+            .and_then(|res| SynAstResult::new_from_json(rule.filename.clone(), res));
+            let stats = coverage.entry(rule.filename.clone()).or_default();
+            stats.files_evaluated += 1;
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                stats.duration_ms += start.elapsed().as_secs_f64() * 1000.0;
+            }
+            match res {
+                Ok(result) => {
+                    debug!("Matches num: {}", result.matches.len());
+                    stats.matches += result.matches.len();
+                    self.results.push(result);
                 }
-            })
-            .all(|res| res)
+                Err(e) => {
+                    let failure = RuleFailure::new(path, &e);
+                    error!(
+                        "Failed to evaluate rule {} on {}: {}",
+                        rule.filename, path, failure.error
+                    );
+                    stats.failures.push(failure);
+                }
+            }
+        }
+    }
+}
+
+/// A failed evaluation of a rule on a file.
+#[cfg(any(feature = "cli", feature = "wasm"))]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RuleFailure {
+    pub file: String,
+    /// The error, on a single line.
+    pub error: String,
+    /// The Starlark call stack and the code of the error, when the rule failed while running.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backtrace: Option<String>,
+}
+
+#[cfg(any(feature = "cli", feature = "wasm"))]
+impl RuleFailure {
+    /// Splits an error of the engine into its message and, for a Starlark error, the backtrace
+    /// (`Traceback (most recent call last): ...`) it is rendered with.
+    pub fn new(file: &str, error: &anyhow::Error) -> Self {
+        let rendered = format!("{:#}", error);
+        let message = rendered
+            .lines()
+            .find_map(|line| line.strip_prefix("error: "))
+            .or_else(|| rendered.lines().next())
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        Self {
+            file: file.to_string(),
+            error: message,
+            backtrace: (rendered.lines().count() > 1).then(|| rendered.trim_end().to_string()),
+        }
     }
 }
 
+/// What a rule did over the files of a SAST run.
+#[cfg(any(feature = "cli", feature = "wasm"))]
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RuleStats {
+    pub files_evaluated: usize,
+    pub matches: usize,
+    /// The files on which the rule (or the parsing of its result) failed.
+    pub failures: Vec<RuleFailure>,
+    /// Total evaluation time, in milliseconds.
+    pub duration_ms: f64,
+}
+
+#[cfg(any(feature = "cli", feature = "wasm"))]
+impl RuleStats {
+    /// Returns the number of files on which the rule failed.
+    #[cfg(feature = "cli")]
+    pub fn errors(&self) -> usize {
+        self.failures.len()
+    }
+}
+
+/// The rule coverage of a SAST run: every loaded rule, by rule file, with what it did.
+#[cfg(any(feature = "cli", feature = "wasm"))]
+pub type RuleCoverage = BTreeMap<String, RuleStats>;
+
 /// A mapping of file paths to their parsed and enriched syntax trees (`SynAst`).
 pub type SynAstMap = HashMap<String, SynAst>;
 
@@ -247,36 +516,44 @@ pub trait SynAstMapExt {
     ///
     /// # Returns
     ///
-    /// `Ok(true)` if at least one rule matched across all files, otherwise `Ok(false)` or an error.
+    /// The rule coverage of the run, holding every rule of the directory, even those that
+    /// were never evaluated.
+    #[cfg(any(feature = "cli", feature = "wasm"))]
     fn apply_rules(
         &mut self,
         rules_dir: &StarlarkRulesDir,
         starlark_engine: &StarlarkEngine,
-    ) -> Result<bool>;
+    ) -> Result<RuleCoverage>;
     /// Returns all file paths present in the syntax map.
     #[allow(dead_code)]
     fn get_file_paths(&self) -> Vec<&String>;
     /// Returns the number of syntax trees (files) in the map.
+    #[cfg(feature = "cli")]
     fn count_files(&self) -> usize;
 }
 
 impl SynAstMapExt for SynAstMap {
+    #[cfg(any(feature = "cli", feature = "wasm"))]
     fn apply_rules(
         &mut self,
         rules_dir: &StarlarkRulesDir,
         starlark_engine: &StarlarkEngine,
-    ) -> Result<bool> {
-        let results = self
-            .values_mut()
-            .map(|syn_ast| syn_ast.scan_ast(rules_dir, starlark_engine))
-            .collect::<Vec<bool>>();
-        Ok(results.into_iter().any(|applied| applied))
+    ) -> Result<RuleCoverage> {
+        let mut coverage: RuleCoverage = rules_dir
+            .iter()
+            .map(|rule| (rule.filename.clone(), RuleStats::default()))
+            .collect();
+        for (path, syn_ast) in self.iter_mut() {
+            syn_ast.scan_ast(path, rules_dir, starlark_engine, &mut coverage);
+        }
+        Ok(coverage)
     }
 
     fn get_file_paths(&self) -> Vec<&String> {
         self.keys().collect()
     }
 
+    #[cfg(feature = "cli")]
     fn count_files(&self) -> usize {
         self.len()
     }
@@ -284,13 +561,27 @@ impl SynAstMapExt for SynAstMap {
 
 /// Represents the global state of a SAST session, including parsed syntax trees,
 /// rule directory, and rule engine.
+#[cfg(any(feature = "cli", feature = "wasm"))]
 #[derive(Debug, Clone)]
 pub struct SastState {
     pub syn_ast_map: SynAstMap,
     pub starlark_rules_dir: StarlarkRulesDir,
     pub starlark_engine: StarlarkEngine,
+    /// What each loaded rule did, filled by [`SastState::apply_rules`].
+    pub rule_coverage: RuleCoverage,
+    /// The enabled built-in constraint checks (see [`crate::constraints`]), which run with the
+    /// internal rules.
+    pub constraint_rules: Vec<String>,
+    /// The enabled built-in rules of the key inventory (see [`crate::pubkeys`]), which run with
+    /// the internal rules.
+    pub pubkey_rules: Vec<String>,
+    /// The scanned directory, once the paths of the files and of the findings were made
+    /// relative to it by [`SastState::relativize_paths`].
+    #[cfg_attr(not(feature = "cli"), allow(dead_code))]
+    pub root: Option<PathBuf>,
 }
 
+#[cfg(any(feature = "cli", feature = "wasm"))]
 impl SastState {
     /// Initializes a new `SastState` by loading rules and preparing the engine.
     ///
@@ -298,33 +589,317 @@ impl SastState {
     ///
     /// * `syn_ast_map` - Map of all parsed source files to their AST representations.
     /// * `starlark_rules_dir_path` - Path to the directory containing rule files.
+    /// * `use_internal_rules` - Whether to include the built-in rules.
+    /// * `rule_filter` - Rules to enable or disable, by rule ID.
+    /// * `rule_trust` - Which external rules may be evaluated.
+    /// * `rule_limits` - Execution limits of each rule.
     ///
     /// # Returns
     ///
-    /// A new `SastState` instance, or an error if the rule directory couldn't be parsed.
+    /// A new `SastState` instance, or an error if the rule directory couldn't be parsed or
+    /// holds untrusted rules.
     pub fn new(
         syn_ast_map: SynAstMap,
         starlark_rules_dir_path: Option<String>,
         use_internal_rules: bool,
+        rule_filter: &RuleFilter,
+        rule_trust: &RuleTrust,
+        rule_limits: RuleLimits,
     ) -> Result<Self> {
+        let user_templates = load_user_templates(starlark_rules_dir_path.as_deref())?;
+        let starlark_rules_dir = StarlarkRulesDir::new_from_dir(
+            starlark_rules_dir_path,
+            use_internal_rules,
+            rule_filter,
+        )?;
+        rule_trust.check(&starlark_rules_dir, &user_templates)?;
+        let enabled_builtin_rules = |rules: &[&str]| -> Vec<String> {
+            rules
+                .iter()
+                .filter(|rule| use_internal_rules && rule_filter.is_enabled(rule))
+                .map(|rule| rule.to_string())
+                .collect()
+        };
         Ok(Self {
+            starlark_rules_dir,
+            starlark_engine: StarlarkEngine::new()
+                .with_user_templates(user_templates)
+                .with_limits(rule_limits)
+                .with_source_files(syn_ast_map.keys().cloned()),
             syn_ast_map,
-            starlark_rules_dir: StarlarkRulesDir::new_from_dir(
-                starlark_rules_dir_path,
-                use_internal_rules,
-            )?,
-            starlark_engine: StarlarkEngine::new(),
+            rule_coverage: RuleCoverage::new(),
+            constraint_rules: enabled_builtin_rules(CONSTRAINT_RULES),
+            pubkey_rules: enabled_builtin_rules(PUBKEY_RULES),
+            root: None,
         })
     }
 
-    /// Applies all loaded rules to the parsed syntax trees.
+    /// Scans the Rust source `source` of the file `filename` with the internal rules, without
+    /// touching the filesystem, for the builds without one (the browser bindings of the `wasm`
+    /// feature).
+    ///
+    /// The rules run without limits, their timeout needing a clock.
     ///
     /// # Returns
     ///
-    /// A boolean indicating whether any rules were successfully applied.
-    pub fn apply_rules(&mut self) -> Result<bool> {
-        self.syn_ast_map
-            .apply_rules(&self.starlark_rules_dir, &self.starlark_engine)
+    /// The state of the scan, with the rules applied, or the parse error of the source.
+    pub fn scan_source(filename: &str, source: &str) -> Result<Self> {
+        let mut syn_ast_map = SynAstMap::new();
+        parse_rust_source(
+            filename,
+            source,
+            &mut syn_ast_map,
+            ColumnEncoding::default(),
+        )?;
+        let mut state = Self::new(
+            syn_ast_map,
+            None,
+            true,
+            &RuleFilter::default(),
+            &RuleTrust::default(),
+            RuleLimits::unlimited(),
+        )?;
+        state.apply_rules()?;
+        Ok(state)
+    }
+
+    /// Applies all loaded rules to the parsed syntax trees, then the enabled constraint
+    /// checks and key inventory rules, recording the rule coverage.
+    pub fn apply_rules(&mut self) -> Result<()> {
+        self.rule_coverage = timing::time("rules", || {
+            self.syn_ast_map
+                .apply_rules(&self.starlark_rules_dir, &self.starlark_engine)
+        })?;
+        timing::time("constraints", || self.apply_constraint_checks());
+        timing::time("pubkeys", || self.apply_pubkey_inventory());
+        Ok(())
+    }
+
+    /// Checks the consistency of the Anchor account constraints of all the files, adding the
+    /// findings of each enabled check to the results of the files, like a rule.
+    fn apply_constraint_checks(&mut self) {
+        if self.constraint_rules.is_empty() {
+            return;
+        }
+        let report = ConstraintReport::analyze(
+            self.syn_ast_map
+                .iter()
+                .map(|(path, syn_ast)| (path.as_str(), &syn_ast.ast)),
+        );
+        let results = report.sast_results(|rule| self.constraint_rules.iter().any(|r| r == rule));
+        self.add_builtin_results(&self.constraint_rules.clone(), results);
+    }
+
+    /// Collects the public keys written in all the files, adding the hardcoded keys and the
+    /// key drifts to the results of the files, like a rule.
+    fn apply_pubkey_inventory(&mut self) {
+        if self.pubkey_rules.is_empty() {
+            return;
+        }
+        let inventory = PubkeyInventory::collect(
+            self.syn_ast_map
+                .iter()
+                .map(|(path, syn_ast)| (path.as_str(), &syn_ast.ast)),
+        );
+        let results = inventory.sast_results(|rule| self.pubkey_rules.iter().any(|r| r == rule));
+        self.add_builtin_results(&self.pubkey_rules.clone(), results);
+    }
+
+    /// Adds the results of built-in rules to the results of the files, recording the coverage
+    /// of the rules `rules`.
+    fn add_builtin_results(
+        &mut self,
+        rules: &[String],
+        results: BTreeMap<String, Vec<SynAstResult>>,
+    ) {
+        for rule in rules {
+            self.rule_coverage.insert(
+                rule.clone(),
+                RuleStats {
+                    files_evaluated: self.syn_ast_map.len(),
+                    ..RuleStats::default()
+                },
+            );
+        }
+        for (file, results) in results {
+            for result in &results {
+                if let Some(stats) = self.rule_coverage.get_mut(&result.rule_filename) {
+                    stats.matches += result.matches.len();
+                }
+            }
+            if let Some(syn_ast) = self.syn_ast_map.get_mut(&file) {
+                syn_ast.results.extend(results);
+            }
+        }
+    }
+
+    /// Audits the `Cargo.lock` of the project against the advisories, adding the packages
+    /// affected by each enabled advisory (rule `deps/<id>`) as the results of the lock file,
+    /// like a rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `lock_file` - The `Cargo.lock` of the project.
+    /// * `advisories` - The advisories.
+    /// * `rule_filter` - The rules to enable or disable, by rule ID.
+    #[cfg(feature = "cli")]
+    pub fn audit_dependencies(
+        &mut self,
+        lock_file: &Path,
+        advisories: &AdvisoryDb,
+        rule_filter: &RuleFilter,
+    ) -> Result<()> {
+        let content = std::fs::read_to_string(lock_file)
+            .with_context(|| format!("Reading {}", lock_file.display()))?;
+        let affected = audit_lock(&content, advisories, |rule| rule_filter.is_enabled(rule))
+            .with_context(|| format!("Auditing {}", lock_file.display()))?;
+        for advisory in advisories.advisories() {
+            let rule = advisory.rule_id();
+            if rule_filter.is_enabled(&rule) {
+                self.rule_coverage.insert(
+                    rule,
+                    RuleStats {
+                        files_evaluated: 1,
+                        matches: affected
+                            .iter()
+                            .filter(|a| a.advisory.id == advisory.id)
+                            .count(),
+                        ..RuleStats::default()
+                    },
+                );
+            }
+        }
+        if affected.is_empty() {
+            return Ok(());
+        }
+
+        // the lock file has no syntax tree, only the results
+        let path = lock_file.to_string_lossy().to_string();
+        let results = dependency_results(&affected, &path);
+        self.syn_ast_map.insert(
+            path,
+            SynAst {
+                ast: syn::File {
+                    shebang: None,
+                    attrs: vec![],
+                    items: vec![],
+                },
+                ast_positions: AstPositions::new(),
+                ast_json: serde_json::Value::Null,
+                ast_json_file: None,
+                results,
+            },
+        );
+        Ok(())
+    }
+
+    /// Returns the failed evaluations of the rules (the engine errors), with the rule file of
+    /// each.
+    #[cfg(feature = "cli")]
+    pub fn engine_errors(&self) -> Vec<(&String, &RuleFailure)> {
+        self.rule_coverage
+            .iter()
+            .flat_map(|(rule, stats)| stats.failures.iter().map(move |failure| (rule, failure)))
+            .collect()
+    }
+
+    /// Records the programs depending on the files of library crates in the `programs`
+    /// metadata of their matches, the files being scanned once for all of these programs.
+    ///
+    /// # Arguments
+    ///
+    /// * `dependents` - The names of the programs depending on each library file, by canonical
+    ///   path.
+    #[cfg(feature = "cli")]
+    pub fn record_dependent_programs(&mut self, dependents: &HashMap<PathBuf, BTreeSet<String>>) {
+        if dependents.is_empty() {
+            return;
+        }
+        for (file, syn_ast) in self.syn_ast_map.iter_mut() {
+            let canonical = std::fs::canonicalize(file).unwrap_or_else(|_| PathBuf::from(file));
+            let Some(programs) = dependents.get(&canonical) else {
+                continue;
+            };
+            for match_result in syn_ast
+                .results
+                .iter_mut()
+                .flat_map(|result| result.matches.iter_mut())
+            {
+                match_result
+                    .metadata
+                    .insert(PROGRAMS_METADATA.to_string(), serde_json::json!(programs));
+            }
+        }
+    }
+
+    /// Makes the paths of the files, of the source positions of the findings and of the engine
+    /// errors relative to `root`, the scanned directory, so that the reports and the baseline
+    /// don't depend on where the scan ran. The root is recorded in [`SastState::root`].
+    ///
+    /// The rules and the checks read the files by their path as found, so this runs once the
+    /// findings are complete.
+    #[cfg(feature = "cli")]
+    pub fn relativize_paths(&mut self, root: &Path) {
+        self.syn_ast_map = std::mem::take(&mut self.syn_ast_map)
+            .into_iter()
+            .map(|(path, mut syn_ast)| {
+                let positions = &mut syn_ast.ast_positions;
+                for position in positions
+                    .nodes_with_positions
+                    .iter_mut()
+                    .map(|(_, position)| position)
+                    .chain(positions.casts.iter_mut().map(|(_, position)| position))
+                {
+                    position.source_file = relative_path(&position.source_file, root);
+                }
+                relativize_json(&mut positions.framework, root);
+                for match_result in syn_ast
+                    .results
+                    .iter_mut()
+                    .flat_map(|result| result.matches.iter_mut())
+                {
+                    relativize_match(match_result, root);
+                }
+                (relative_path(&path, root), syn_ast)
+            })
+            .collect();
+        for failure in self
+            .rule_coverage
+            .values_mut()
+            .flat_map(|stats| stats.failures.iter_mut())
+        {
+            failure.file = relative_path(&failure.file, root);
+        }
+        self.root = Some(root.to_path_buf());
+    }
+
+    /// Returns the path of the file `file` of the results, to read it: joined to the root once
+    /// the paths are relative.
+    #[cfg(feature = "cli")]
+    pub fn source_path(&self, file: &str) -> PathBuf {
+        match &self.root {
+            Some(root) => root.join(file),
+            None => PathBuf::from(file),
+        }
+    }
+
+    /// Returns the absolute path of the root, recorded in the headers of the reports.
+    #[cfg(feature = "cli")]
+    pub fn absolute_root(&self) -> Option<PathBuf> {
+        let root = self.root.as_ref()?;
+        Some(std::fs::canonicalize(root).unwrap_or_else(|_| root.clone()))
+    }
+
+    /// Rates the results of the rules in `taxonomy` instead of the built-in one.
+    #[cfg(feature = "cli")]
+    pub fn apply_taxonomy(&mut self, taxonomy: &Taxonomy) {
+        for result in self
+            .syn_ast_map
+            .values_mut()
+            .flat_map(|syn_ast| syn_ast.results.iter_mut())
+        {
+            result.classification = taxonomy.classify(&result.rule_metadata);
+        }
     }
 
     /// Delegates printing of the rule evaluation results to a printer component.
@@ -332,7 +907,289 @@ impl SastState {
     /// # Returns
     ///
     /// `Ok(())` on success, or an error if the print operation fails.
+    #[cfg(feature = "cli")]
     pub fn print_results(&self, scanned_dir: &String) -> Result<()> {
         SastPrinter::print_sast_state(self, scanned_dir)
     }
 }
+
+/// Returns `path` relative to `root`, with `/` separators whatever the platform: a file
+/// outside of `root`, e.g. in a library shared with other projects, goes up with `..`.
+///
+/// # Returns
+///
+/// The relative path, or `path` itself if it can't be expressed from `root` (one of them is
+/// absolute and the other not, or `root` goes up with `..` past their common prefix).
+#[cfg(feature = "cli")]
+pub fn relative_path(path: &str, root: &Path) -> String {
+    use std::path::Component;
+
+    let path_components: Vec<Component> = Path::new(path).components().collect();
+    let root_components: Vec<Component> = root.components().collect();
+    let common = path_components
+        .iter()
+        .zip(&root_components)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let up = &root_components[common..];
+    let absolute = |components: &[Component]| {
+        matches!(
+            components.first(),
+            Some(Component::RootDir | Component::Prefix(_))
+        )
+    };
+    let unrelated = common == 0 && (absolute(&path_components) || absolute(&root_components));
+    let past_root = !up
+        .iter()
+        .all(|component| matches!(component, Component::Normal(_)));
+    if unrelated || past_root {
+        return path.to_string();
+    }
+    std::iter::repeat_n("..".into(), up.len())
+        .chain(
+            path_components[common..]
+                .iter()
+                .map(|component| component.as_os_str().to_string_lossy()),
+        )
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Makes the `source_file` of the positions of a match (its metadata, its fix and its
+/// children) relative to `root`.
+#[cfg(feature = "cli")]
+fn relativize_match(match_result: &mut SynMatchResult, root: &Path) {
+    for value in match_result.metadata.values_mut() {
+        relativize_json(value, root);
+    }
+    for edit in match_result
+        .fix
+        .iter_mut()
+        .flat_map(|fix| fix.edits.iter_mut())
+    {
+        edit.position.source_file = relative_path(&edit.position.source_file, root);
+    }
+    for child in match_result.children.iter_mut() {
+        relativize_match(child, root);
+    }
+}
+
+/// Makes the `source_file` fields found in `value`, at any depth, relative to `root`.
+#[cfg(feature = "cli")]
+fn relativize_json(value: &mut serde_json::Value, root: &Path) {
+    match value {
+        serde_json::Value::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                match value {
+                    serde_json::Value::String(file) if key == "source_file" => {
+                        *file = relative_path(file, root);
+                    }
+                    value => relativize_json(value, root),
+                }
+            }
+        }
+        serde_json::Value::Array(values) => {
+            for value in values {
+                relativize_json(value, root);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Flattens the findings of a scan, one row per match (the findings of the Python and
+/// browser bindings).
+#[cfg(any(feature = "python", feature = "wasm"))]
+pub fn finding_rows(states: &[SastState]) -> Vec<serde_json::Value> {
+    use crate::reverse::correlation::COMPILED_METADATA;
+    use serde_json::{json, Value};
+
+    let mut rows = vec![];
+    for state in states {
+        let mut paths: Vec<&String> = state.syn_ast_map.keys().collect();
+        paths.sort();
+        for path in paths {
+            for result in &state.syn_ast_map[path].results {
+                let metadata = &result.rule_metadata;
+                for match_result in &result.matches {
+                    let position = match_result.metadata.get("position");
+                    let field = |name: &str| {
+                        position
+                            .and_then(|position| position.get(name))
+                            .cloned()
+                            .unwrap_or(Value::Null)
+                    };
+                    rows.push(json!({
+                        "file": path,
+                        "rule_id": result.rule_filename,
+                        "rule": metadata.name,
+                        "severity": format!("{:?}", metadata.severity),
+                        "certainty": format!("{:?}", metadata.certainty),
+                        "ratings": result.classification.levels(),
+                        "ident": match_result.ident,
+                        "parent": match_result.parent,
+                        "access_path": match_result.access_path,
+                        "start_line": field("start_line"),
+                        "start_column": field("start_column"),
+                        "end_line": field("end_line"),
+                        "end_column": field("end_column"),
+                        "compiled": match_result.metadata.get(COMPILED_METADATA),
+                        "programs": match_result.metadata.get(PROGRAMS_METADATA),
+                        "description": result.render_description(match_result),
+                    }));
+                }
+            }
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engines::starlark_engine::{StarlarkRule, StarlarkRuleType};
+
+    #[test]
+    fn test_render_description() {
+        let mut rule_metadata = SynRuleMetadata::default();
+        rule_metadata.name = "Missing Signer Check".to_string();
+        rule_metadata.template = Some("MISSING_ACCOUNT_CHECK".to_string());
+        rule_metadata
+            .template_params
+            .insert("check".to_string(), "is_signer".to_string());
+
+        let match_result = SynMatchResult {
+            children: vec![],
+            access_path: "Withdraw.authority".to_string(),
+            metadata: HashMap::new(),
+            ident: "authority".to_string(),
+            parent: "Withdraw".to_string(),
+            fix: None,
+        };
+
+        let mut result = SynAstResult {
+            rule_filename: "missing_signer_check.star".to_string(),
+            result: String::new(),
+            matches: vec![match_result.clone()],
+            rule_metadata,
+            finding_template: None,
+            classification: Classification::default(),
+        };
+        assert_eq!(result.render_description(&match_result), None);
+
+        result.finding_template = Some(FindingTemplate {
+            description: "[{rule}] `{ident}` in `{parent}` lacks {check} ({unknown})".to_string(),
+            params: HashMap::from([("check".to_string(), "a check".to_string())]),
+        });
+        assert_eq!(
+            result.render_description(&match_result).as_deref(),
+            Some("[Missing Signer Check] `authority` in `Withdraw` lacks is_signer ({unknown})")
+        );
+
+        let mut match_result = match_result;
+        match_result
+            .metadata
+            .insert("check".to_string(), serde_json::json!("`has_one`"));
+        assert_eq!(
+            result.render_description(&match_result).as_deref(),
+            Some("[Missing Signer Check] `authority` in `Withdraw` lacks `has_one` ({unknown})")
+        );
+    }
+
+    #[test]
+    fn test_rule_coverage() {
+        let metadata = r#"RULE_METADATA = {"version": "0.1.0", "author": "test", "name": "Test", "severity": "Low", "certainty": "Low", "description": ""}"#;
+        let rule = |filename: &str, body: &str| StarlarkRule {
+            id: filename.trim_end_matches(".star").to_string(),
+            filename: filename.to_string(),
+            content: format!(
+                "{}\n\ndef syn_ast_rule(root: dict) -> list[dict]:\n    {}\n",
+                metadata, body
+            ),
+            rule_type: StarlarkRuleType::Syn,
+            external: true,
+            rules_dir: None,
+        };
+        let rules_dir = vec![
+            rule("quiet.star", "return []"),
+            rule("broken.star", "fail(\"boom\")"),
+        ];
+        let syn_ast = SynAst {
+            ast: syn::parse_file("").unwrap(),
+            ast_positions: AstPositions::new(),
+            ast_json: serde_json::json!({ "items": [] }),
+            ast_json_file: None,
+            results: vec![],
+        };
+        let engine = StarlarkEngine::new();
+
+        let mut syn_ast_map = SynAstMap::from([("src/lib.rs".to_string(), syn_ast)]);
+        let coverage = syn_ast_map.apply_rules(&rules_dir, &engine).unwrap();
+        assert_eq!(coverage["quiet.star"].files_evaluated, 1);
+        assert_eq!(coverage["quiet.star"].errors(), 0);
+        let broken = &coverage["broken.star"];
+        assert_eq!(broken.files_evaluated, 1);
+        assert_eq!(broken.failures.len(), 1);
+        assert_eq!(broken.failures[0].file, "src/lib.rs");
+        assert_eq!(broken.failures[0].error, "fail: boom");
+        let backtrace = broken.failures[0].backtrace.as_deref().unwrap_or_default();
+        assert!(backtrace.starts_with("Traceback"), "{:?}", broken);
+        assert!(backtrace.contains("in syn_ast_rule"), "{:?}", broken);
+
+        // the rules that never ran are listed too
+        let coverage = SynAstMap::new().apply_rules(&rules_dir, &engine).unwrap();
+        assert_eq!(coverage.len(), 2);
+        assert!(coverage.values().all(|stats| stats.files_evaluated == 0));
+    }
+
+    #[test]
+    fn test_scan_source() {
+        let state = SastState::scan_source("lib.rs", "pub fn f() {}").unwrap();
+        assert!(!state.rule_coverage.is_empty());
+        assert!(state
+            .rule_coverage
+            .values()
+            .all(|stats| stats.files_evaluated == 1 && stats.errors() == 0));
+        assert!(SastState::scan_source("lib.rs", "pub fn f( {").is_err());
+    }
+
+    #[test]
+    fn test_relativize_paths() {
+        let source = "pub fn f(amount: u64) -> u64 {\n    amount.saturating_sub(1)\n}\n";
+        let mut state = SastState::scan_source("/work/vault/src/lib.rs", source).unwrap();
+        state.relativize_paths(Path::new("/work/vault"));
+
+        assert_eq!(state.root, Some(PathBuf::from("/work/vault")));
+        assert_eq!(
+            state.syn_ast_map.keys().collect::<Vec<_>>(),
+            vec!["src/lib.rs"]
+        );
+        let matches: Vec<&SynMatchResult> = state.syn_ast_map["src/lib.rs"]
+            .results
+            .iter()
+            .flat_map(|result| &result.matches)
+            .collect();
+        assert!(!matches.is_empty());
+        for match_result in matches {
+            let position = match_result.get_location_metadata().unwrap();
+            assert_eq!(position.source_file, "src/lib.rs");
+        }
+        assert_eq!(
+            state.source_path("src/lib.rs"),
+            PathBuf::from("/work/vault/src/lib.rs")
+        );
+
+        assert_eq!(
+            relative_path("./vault/programs/a/src/lib.rs", Path::new("./vault")),
+            "programs/a/src/lib.rs"
+        );
+        assert_eq!(
+            relative_path("/work/libs/math/src/lib.rs", Path::new("/work/vault")),
+            "../libs/math/src/lib.rs"
+        );
+        assert_eq!(
+            relative_path("/work/lib.rs", Path::new("./vault")),
+            "/work/lib.rs"
+        );
+    }
+}
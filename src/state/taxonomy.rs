@@ -0,0 +1,382 @@
+//! Taxonomies in which the findings are rated (`sast --taxonomy`).
+//!
+//! By default, the findings are rated with the `severity` and `certainty` of their rule. A
+//! taxonomy file replaces them with other axes, each with its own ordered levels, e.g. a
+//! likelihood / impact matrix. A rule is rated on an axis by the score it declares for it in
+//! `RULE_METADATA["scores"]` (the 1-based index of a level), or else through the mapping of its
+//! `severity` or `certainty` given by the axis. An overall rating can be derived from the product
+//! of the scores:
+//!
+//! ```toml
+//! name = "likelihood-impact"
+//!
+//! [[axes]]
+//! name = "Likelihood"
+//! levels = ["Rare", "Unlikely", "Possible", "Likely", "Almost certain"]
+//! certainty = { Low = 2, Medium = 3, High = 4 }
+//!
+//! [[axes]]
+//! name = "Impact"
+//! levels = ["Negligible", "Minor", "Moderate", "Major", "Severe"]
+//! severity = { Low = 2, Medium = 3, High = 4, Critical = 5 }
+//!
+//! [risk]
+//! name = "Risk"
+//! levels = [
+//!     { label = "Low", min = 1 },
+//!     { label = "Medium", min = 5 },
+//!     { label = "High", min = 10 },
+//!     { label = "Critical", min = 17 },
+//! ]
+//! ```
+
+use crate::state::sast_state::SynRuleMetadata;
+#[cfg(feature = "cli")]
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+#[cfg(feature = "cli")]
+use std::path::Path;
+
+/// Name of the built-in taxonomy, made of the severity and certainty of the rules.
+pub const DEFAULT_TAXONOMY: &str = "sol-azy";
+
+const SEVERITIES: &[&str] = &["Unknown", "Low", "Medium", "High", "Critical"];
+const CERTAINTIES: &[&str] = &["Unknown", "Low", "Medium", "High"];
+
+/// A taxonomy: the axes the findings are rated on, and the optional overall rating.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Taxonomy {
+    pub name: String,
+    pub axes: Vec<Axis>,
+    #[serde(default)]
+    pub risk: Option<Risk>,
+}
+
+/// An axis of a taxonomy.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Axis {
+    pub name: String,
+    /// Levels from the lowest, the score of a level being its 1-based index.
+    pub levels: Vec<String>,
+    /// Whether the highest levels are the good ones (e.g. a certainty), which only changes
+    /// their color.
+    #[serde(default)]
+    pub confidence: bool,
+    /// Score of the rules declaring no score for this axis, by severity.
+    #[serde(default)]
+    pub severity: BTreeMap<String, u32>,
+    /// Same, by certainty, when the severity gives no score.
+    #[serde(default)]
+    pub certainty: BTreeMap<String, u32>,
+}
+
+/// Overall rating derived from the product of the scores of all the axes.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Risk {
+    pub name: String,
+    /// Levels from the lowest: the rating is the last level whose `min` is reached.
+    pub levels: Vec<RiskLevel>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RiskLevel {
+    pub label: String,
+    pub min: u32,
+}
+
+/// The rating of a rule in a taxonomy.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Classification {
+    pub taxonomy: String,
+    /// One rating per axis, then the overall rating if the taxonomy has one.
+    pub ratings: Vec<Rating>,
+}
+
+/// The level of a rule on an axis.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rating {
+    pub axis: String,
+    /// `None` if the rule can't be rated on this axis.
+    pub level: Option<String>,
+    pub score: Option<u32>,
+    /// Number of levels of the axis.
+    pub max_score: u32,
+    #[serde(default)]
+    pub confidence: bool,
+}
+
+impl Rating {
+    /// The level, or `Unknown`.
+    pub fn label(&self) -> &str {
+        self.level.as_deref().unwrap_or("Unknown")
+    }
+
+    /// Style of the level in the tables: red for the upper levels, yellow for the middle ones
+    /// and green for the lower ones (the other way around for a confidence), white if unknown.
+    pub fn style(&self) -> &'static str {
+        let Some(score) = self.score else {
+            return "Fw";
+        };
+        let fraction = score as f64 / self.max_score.max(1) as f64;
+        match (fraction >= 0.75, fraction >= 0.5, self.confidence) {
+            (true, _, false) | (false, false, true) => "Fr",
+            (false, true, _) => "Fy",
+            _ => "Fg",
+        }
+    }
+}
+
+impl Classification {
+    /// The rating on the axis `axis` (case insensitive).
+    #[allow(dead_code)]
+    pub fn get(&self, axis: &str) -> Option<&Rating> {
+        self.ratings
+            .iter()
+            .find(|rating| rating.axis.eq_ignore_ascii_case(axis))
+    }
+
+    /// The levels by axis, for the exports.
+    pub fn levels(&self) -> BTreeMap<String, String> {
+        self.ratings
+            .iter()
+            .map(|rating| (rating.axis.clone(), rating.label().to_string()))
+            .collect()
+    }
+
+    /// `Likelihood: Likely, Impact: Major, Risk: High`.
+    #[cfg(feature = "cli")]
+    pub fn describe(&self) -> String {
+        self.ratings
+            .iter()
+            .map(|rating| format!("{}: {}", rating.axis, rating.label()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Whether the classification is in the built-in taxonomy.
+    pub fn is_default(&self) -> bool {
+        self.taxonomy == DEFAULT_TAXONOMY
+    }
+}
+
+impl Default for Taxonomy {
+    /// The built-in taxonomy: the severity and certainty of the rules.
+    fn default() -> Self {
+        let scores = |names: &[&str]| {
+            names
+                .iter()
+                .enumerate()
+                .map(|(index, name)| (name.to_string(), index as u32 + 1))
+                .collect()
+        };
+        let severities = &SEVERITIES[1..];
+        let certainties = &CERTAINTIES[1..];
+        Self {
+            name: DEFAULT_TAXONOMY.to_string(),
+            axes: vec![
+                Axis {
+                    name: "Severity".to_string(),
+                    levels: severities.iter().map(|s| s.to_string()).collect(),
+                    confidence: false,
+                    severity: scores(severities),
+                    certainty: BTreeMap::new(),
+                },
+                Axis {
+                    name: "Certainty".to_string(),
+                    levels: certainties.iter().map(|s| s.to_string()).collect(),
+                    confidence: true,
+                    severity: BTreeMap::new(),
+                    certainty: scores(certainties),
+                },
+            ],
+            risk: None,
+        }
+    }
+}
+
+impl Taxonomy {
+    /// Reads and validates the taxonomy file at `path`.
+    #[cfg(feature = "cli")]
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Reading taxonomy {}", path.display()))?;
+        let taxonomy: Self = toml::from_str(&content)
+            .map_err(|e| anyhow!("Invalid taxonomy {}: {}", path.display(), e))?;
+        taxonomy
+            .validate()
+            .with_context(|| format!("Invalid taxonomy {}", path.display()))?;
+        Ok(taxonomy)
+    }
+
+    #[cfg(feature = "cli")]
+    fn validate(&self) -> Result<()> {
+        if self.axes.is_empty() {
+            return Err(anyhow!("no axes"));
+        }
+        for axis in &self.axes {
+            if axis.levels.is_empty() {
+                return Err(anyhow!("axis '{}' has no levels", axis.name));
+            }
+            for (mapping, names) in [(&axis.severity, SEVERITIES), (&axis.certainty, CERTAINTIES)] {
+                for (name, score) in mapping {
+                    if !names.contains(&name.as_str()) {
+                        return Err(anyhow!(
+                            "axis '{}': unknown level '{}' (expected one of {})",
+                            axis.name,
+                            name,
+                            names.join(", ")
+                        ));
+                    }
+                    if *score == 0 || *score as usize > axis.levels.len() {
+                        return Err(anyhow!(
+                            "axis '{}': score {} of '{}' is not between 1 and {}",
+                            axis.name,
+                            score,
+                            name,
+                            axis.levels.len()
+                        ));
+                    }
+                }
+            }
+        }
+        if let Some(risk) = &self.risk {
+            if risk.levels.is_empty() {
+                return Err(anyhow!("'{}' has no levels", risk.name));
+            }
+            if risk
+                .levels
+                .windows(2)
+                .any(|pair| pair[0].min >= pair[1].min)
+            {
+                return Err(anyhow!(
+                    "the levels of '{}' must have increasing minimums",
+                    risk.name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Rates the rule described by `metadata`.
+    pub fn classify(&self, metadata: &SynRuleMetadata) -> Classification {
+        let severity = format!("{:?}", metadata.severity);
+        let certainty = format!("{:?}", metadata.certainty);
+        let mut ratings: Vec<Rating> = self
+            .axes
+            .iter()
+            .map(|axis| {
+                let declared = metadata
+                    .scores
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(&axis.name))
+                    .map(|(_, score)| *score)
+                    .filter(|score| (1..=axis.levels.len() as u32).contains(score));
+                let score = declared
+                    .or_else(|| axis.severity.get(&severity).copied())
+                    .or_else(|| axis.certainty.get(&certainty).copied());
+                Rating {
+                    axis: axis.name.clone(),
+                    level: score.map(|score| axis.levels[score as usize - 1].clone()),
+                    score,
+                    max_score: axis.levels.len() as u32,
+                    confidence: axis.confidence,
+                }
+            })
+            .collect();
+
+        if let Some(risk) = &self.risk {
+            let product = ratings
+                .iter()
+                .map(|rating| rating.score)
+                .product::<Option<u32>>();
+            let index = product
+                .and_then(|product| risk.levels.iter().rposition(|level| product >= level.min));
+            ratings.push(Rating {
+                axis: risk.name.clone(),
+                level: index.map(|index| risk.levels[index].label.clone()),
+                score: index.map(|index| index as u32 + 1),
+                max_score: risk.levels.len() as u32,
+                confidence: false,
+            });
+        }
+
+        Classification {
+            taxonomy: self.name.clone(),
+            ratings,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::sast_state::{Certainty, Severity};
+
+    #[test]
+    fn test_classify() {
+        let mut metadata = SynRuleMetadata::default();
+        metadata.severity = Severity::High;
+        metadata.certainty = Certainty::Low;
+
+        let default = Taxonomy::default().classify(&metadata);
+        assert_eq!(default.describe(), "Severity: High, Certainty: Low");
+        assert_eq!(
+            default
+                .ratings
+                .iter()
+                .map(Rating::style)
+                .collect::<Vec<_>>(),
+            vec!["Fr", "Fr"]
+        );
+
+        let taxonomy: Taxonomy = toml::from_str(
+            r#"
+            name = "likelihood-impact"
+            [[axes]]
+            name = "Likelihood"
+            levels = ["Rare", "Unlikely", "Possible", "Likely", "Almost certain"]
+            certainty = { Low = 2, Medium = 3, High = 4 }
+            [[axes]]
+            name = "Impact"
+            levels = ["Negligible", "Minor", "Moderate", "Major", "Severe"]
+            severity = { Low = 2, Medium = 3, High = 4, Critical = 5 }
+            [risk]
+            name = "Risk"
+            levels = [{ label = "Low", min = 1 }, { label = "High", min = 10 }]
+            "#,
+        )
+        .unwrap();
+        taxonomy.validate().unwrap();
+        assert_eq!(
+            taxonomy.classify(&metadata).describe(),
+            "Likelihood: Unlikely, Impact: Major, Risk: Low"
+        );
+
+        // declared scores take precedence, out of range ones are ignored
+        metadata.scores =
+            BTreeMap::from([("likelihood".to_string(), 5), ("Impact".to_string(), 9)]);
+        assert_eq!(
+            taxonomy.classify(&metadata).levels(),
+            BTreeMap::from([
+                ("Likelihood".to_string(), "Almost certain".to_string()),
+                ("Impact".to_string(), "Major".to_string()),
+                ("Risk".to_string(), "High".to_string()),
+            ])
+        );
+
+        metadata.severity = Severity::Unknown;
+        metadata.scores.clear();
+        let unknown = taxonomy.classify(&metadata);
+        assert_eq!(unknown.get("impact").unwrap().label(), "Unknown");
+        assert_eq!(unknown.get("risk").unwrap().level, None);
+
+        let mut invalid = taxonomy.clone();
+        invalid.axes[0].certainty.insert("Sure".to_string(), 1);
+        assert!(invalid.validate().is_err());
+    }
+}
@@ -0,0 +1,42 @@
+//! Interactive terminal interfaces (ratatui), to browse the results of a command instead of
+//! scrolling through its output.
+//!
+//! - [`sast`] — `sast --tui`: the findings beside their source, triaged into the baseline.
+//! - [`reverse`] — `reverse --tui`: the functions beside their disassembly, following the calls.
+
+pub mod reverse;
+pub mod sast;
+
+use anyhow::{anyhow, Context, Result};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{enable_raw_mode, EnterAlternateScreen};
+use ratatui::DefaultTerminal;
+
+/// Leaves the interface to open `file` at `line` in `$VISUAL` or `$EDITOR` (`vi` if neither is
+/// set), and comes back to it once the editor exits.
+pub fn open_in_editor(terminal: &mut DefaultTerminal, file: &str, line: u32) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    // the editor may come with its own arguments, e.g. `code --wait`
+    let mut words = editor.split_whitespace();
+    let program = words
+        .next()
+        .ok_or_else(|| anyhow!("Empty editor command"))?;
+
+    ratatui::try_restore()?;
+    let status = std::process::Command::new(program)
+        .args(words)
+        .arg(format!("+{}", line))
+        .arg(file)
+        .status()
+        .with_context(|| format!("Running editor '{}'", editor));
+    enable_raw_mode()?;
+    execute!(std::io::stdout(), EnterAlternateScreen)?;
+    terminal.clear()?;
+
+    if !status?.success() {
+        return Err(anyhow!("Editor '{}' failed on {}", editor, file));
+    }
+    Ok(())
+}
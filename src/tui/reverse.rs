@@ -0,0 +1,506 @@
+//! `reverse --tui`: the functions of the program in a navigable list, beside the disassembly of
+//! the selected one (with the strings, syscalls and error codes resolved), following the calls
+//! and jumps.
+//!
+//! | Key                | Action                                                         |
+//! | ------------------ | -------------------------------------------------------------- |
+//! | `↑` `↓` / `k` `j`  | Previous / next function, or instruction in the disassembly    |
+//! | `PgUp` `PgDn`      | Scroll by a page                                               |
+//! | `Tab`              | Switch between the functions and the disassembly               |
+//! | `Enter`            | On a call, go to the called function; on a jump, to its target |
+//! | `Backspace`        | Go back to where the last jump was taken                       |
+//! | `q` / `Esc`        | Quit                                                           |
+
+use anyhow::Result;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use solana_sbpf::{ebpf, program::SBPFVersion, static_analysis::Analysis};
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::reverse::annotations::Annotations;
+use crate::reverse::disass::{annotate_instruction, function_instruction_ranges};
+use crate::reverse::dispatch::resolve_internal_call;
+use crate::reverse::error_codes::ErrorCodeTable;
+use crate::reverse::function_filter::FunctionSelection;
+use crate::reverse::sanitize::sanitize_text;
+use crate::reverse::utils::RegisterTracker;
+
+/// Rows moved by `PgUp` and `PgDn`.
+const PAGE_ROWS: usize = 20;
+
+/// A function of the list.
+struct Function {
+    /// Pc of its first instruction.
+    start: usize,
+    name: String,
+    /// Its instructions, as indices in `analysis.instructions`.
+    instructions: Range<usize>,
+}
+
+/// A row of the disassembly: a block label, an annotation of the user, or an instruction.
+enum Row {
+    Label(String),
+    Comment(String),
+    Instruction {
+        pc: usize,
+        text: String,
+        /// Pc the instruction calls or jumps to.
+        target: Option<usize>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Functions,
+    Disassembly,
+}
+
+/// A place of the disassembly, to come back to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Location {
+    /// Index in `functions`.
+    function: usize,
+    /// Index in the rows of the function.
+    row: usize,
+}
+
+/// Name of the function at `start`, as in the listing.
+fn function_name(analysis: &Analysis, start: usize) -> String {
+    analysis
+        .cfg_nodes
+        .get(&start)
+        .map(|node| node.label.clone())
+        .filter(|label| !label.is_empty())
+        .unwrap_or_else(|| format!("function_{}", start))
+}
+
+/// Pc reached by a call to an internal function or by a jump, if any.
+fn branch_target(analysis: &Analysis, pc: usize, sbpf_version: SBPFVersion) -> Option<usize> {
+    let insn = &analysis.instructions[pc];
+    if let Some(callee) = resolve_internal_call(analysis, insn, sbpf_version) {
+        return Some(callee);
+    }
+    let class = insn.opc & ebpf::BPF_CLS_MASK;
+    let operation = insn.opc & ebpf::BPF_ALU_OP_MASK;
+    let is_jump = matches!(class, ebpf::BPF_JMP | ebpf::BPF_JMP32)
+        && !matches!(operation, ebpf::BPF_CALL | ebpf::BPF_EXIT);
+    is_jump
+        .then(|| usize::try_from(insn.ptr as isize + insn.off as isize + 1).ok())
+        .flatten()
+}
+
+struct DisassemblyTui<'a> {
+    program: &'a [u8],
+    analysis: &'a Analysis<'a>,
+    sbpf_version: SBPFVersion,
+    error_codes: &'a ErrorCodeTable,
+    annotations: &'a Annotations,
+    functions: Vec<Function>,
+    /// Rows of the functions already disassembled, by index in `functions`.
+    listings: HashMap<usize, Vec<Row>>,
+    function_list: ListState,
+    listing: ListState,
+    focus: Focus,
+    /// Where the jumps were taken, the last one on top.
+    back: Vec<Location>,
+    status: String,
+}
+
+impl<'a> DisassemblyTui<'a> {
+    fn new(
+        program: &'a [u8],
+        analysis: &'a Analysis<'a>,
+        sbpf_version: SBPFVersion,
+        error_codes: &'a ErrorCodeTable,
+        annotations: &'a Annotations,
+        selection: Option<&FunctionSelection>,
+    ) -> Self {
+        let functions = function_instruction_ranges(analysis)
+            .into_iter()
+            .filter_map(|instructions| {
+                let start = analysis.instructions[instructions.start].ptr;
+                let kept = selection.is_none_or(|selection| selection.contains(start));
+                (analysis.functions.contains_key(&start) && kept).then(|| Function {
+                    start,
+                    name: function_name(analysis, start),
+                    instructions,
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut tui = Self {
+            program,
+            analysis,
+            sbpf_version,
+            error_codes,
+            annotations,
+            listings: HashMap::new(),
+            function_list: ListState::default(),
+            listing: ListState::default(),
+            focus: Focus::Functions,
+            back: Vec::new(),
+            status: String::new(),
+            functions,
+        };
+        // start on the entrypoint, if it is listed
+        let entrypoint = tui
+            .functions
+            .iter()
+            .position(|function| function.start == analysis.entrypoint)
+            .unwrap_or(0);
+        tui.go_to(Location {
+            function: entrypoint,
+            row: 0,
+        });
+        tui
+    }
+
+    /// Disassembles the function at `index` in `functions`, one row per line of the `solazy`
+    /// listing.
+    fn disassemble(&self, index: usize) -> Vec<Row> {
+        let function = &self.functions[index];
+        let mut rows = Vec::new();
+        let mut reg_tracker = RegisterTracker::new();
+        let mut last_basic_block = usize::MAX;
+        for pc in function.instructions.clone() {
+            let ptr = self.analysis.instructions[pc].ptr;
+            let mut label = Vec::new();
+            if self
+                .analysis
+                .disassemble_label(&mut label, true, ptr, &mut last_basic_block)
+                .is_ok()
+            {
+                rows.extend(
+                    String::from_utf8_lossy(&label)
+                        .lines()
+                        .filter(|line| !line.is_empty())
+                        .map(|line| Row::Label(line.to_string())),
+                );
+            }
+            rows.extend(
+                self.annotations
+                    .lines(ptr)
+                    .into_iter()
+                    .map(|line| Row::Comment(sanitize_text(&line).into_owned())),
+            );
+            let instruction = annotate_instruction(
+                self.program,
+                self.analysis,
+                pc,
+                true,
+                self.error_codes,
+                &mut reg_tracker,
+                self.sbpf_version,
+            );
+            let target = branch_target(self.analysis, pc, self.sbpf_version);
+            let mut text = instruction.line;
+            // the calls to functions without a symbol don't name their target
+            if let Some(callee) =
+                target.filter(|target| self.analysis.functions.contains_key(target))
+            {
+                let name = function_name(self.analysis, callee);
+                if !text.contains(&name) {
+                    text = format!("{}  -> {}", text, name);
+                }
+            }
+            rows.push(Row::Instruction {
+                pc: ptr,
+                text,
+                target,
+            });
+        }
+        rows
+    }
+
+    fn rows(&mut self, function: usize) -> &[Row] {
+        if !self.listings.contains_key(&function) {
+            let rows = self.disassemble(function);
+            self.listings.insert(function, rows);
+        }
+        &self.listings[&function]
+    }
+
+    fn location(&self) -> Option<Location> {
+        Some(Location {
+            function: self.function_list.selected()?,
+            row: self.listing.selected().unwrap_or(0),
+        })
+    }
+
+    fn go_to(&mut self, location: Location) {
+        if location.function >= self.functions.len() {
+            return;
+        }
+        let rows = self.rows(location.function).len();
+        self.function_list.select(Some(location.function));
+        self.listing
+            .select(Some(location.row.min(rows.saturating_sub(1))));
+    }
+
+    /// Location of the instruction at `pc`, in the listed functions.
+    fn find(&mut self, pc: usize) -> Option<Location> {
+        let function = self
+            .functions
+            .iter()
+            .rposition(|function| function.start <= pc)?;
+        let row = self
+            .rows(function)
+            .iter()
+            .position(|row| matches!(row, Row::Instruction { pc: row_pc, .. } if *row_pc == pc))?;
+        Some(Location { function, row })
+    }
+
+    /// Follows the call or jump of the selected instruction, remembering where it was taken.
+    fn follow(&mut self) {
+        let Some(location) = self.location() else {
+            return;
+        };
+        let target = match self.rows(location.function).get(location.row) {
+            Some(Row::Instruction {
+                target: Some(target),
+                ..
+            }) => *target,
+            _ => return,
+        };
+        match self.find(target) {
+            Some(destination) => {
+                self.back.push(location);
+                self.go_to(destination);
+                self.status = String::new();
+            }
+            None => self.status = format!("pc {} is not in the listed functions", target),
+        }
+    }
+
+    /// Comes back to where the last jump was taken.
+    fn go_back(&mut self) {
+        if let Some(location) = self.back.pop() {
+            self.go_to(location);
+            self.status = String::new();
+        }
+    }
+
+    /// Moves the selection of the focused pane by `delta` rows.
+    fn move_selection(&mut self, delta: isize) {
+        let Some(location) = self.location() else {
+            return;
+        };
+        match self.focus {
+            Focus::Functions => {
+                let function = location.function.saturating_add_signed(delta);
+                self.go_to(Location {
+                    function: function.min(self.functions.len() - 1),
+                    row: 0,
+                });
+            }
+            Focus::Disassembly => self.go_to(Location {
+                row: location.row.saturating_add_signed(delta),
+                ..location
+            }),
+        }
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+                KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+                KeyCode::PageDown => self.move_selection(PAGE_ROWS as isize),
+                KeyCode::PageUp => self.move_selection(-(PAGE_ROWS as isize)),
+                KeyCode::Tab => {
+                    self.focus = match self.focus {
+                        Focus::Functions => Focus::Disassembly,
+                        Focus::Disassembly => Focus::Functions,
+                    }
+                }
+                KeyCode::Enter if self.focus == Focus::Functions => self.focus = Focus::Disassembly,
+                KeyCode::Enter => self.follow(),
+                KeyCode::Backspace => self.go_back(),
+                _ => {}
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [functions_area, listing_area] =
+            Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)])
+                .areas(main);
+        let highlight = |focused: bool| {
+            if focused {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default().add_modifier(Modifier::BOLD)
+            }
+        };
+
+        let items: Vec<ListItem> = self
+            .functions
+            .iter()
+            .map(|function| {
+                let text = format!("{:>6}  {}", function.start, function.name);
+                if function.start == self.analysis.entrypoint {
+                    ListItem::new(Line::styled(text, Style::default().fg(Color::Green)))
+                } else {
+                    ListItem::new(text)
+                }
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered().title(format!(" Functions ({}) ", self.functions.len())))
+            .highlight_style(highlight(self.focus == Focus::Functions));
+        frame.render_stateful_widget(list, functions_area, &mut self.function_list);
+
+        let function = self.function_list.selected().unwrap_or(0);
+        let title = format!(" {} ", self.functions[function].name);
+        let items: Vec<ListItem> = self
+            .rows(function)
+            .iter()
+            .map(|row| match row {
+                Row::Label(label) => ListItem::new(Line::styled(
+                    label.clone(),
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                )),
+                Row::Comment(comment) => ListItem::new(Line::styled(
+                    format!("    // {}", comment),
+                    Style::default().fg(Color::Green),
+                )),
+                Row::Instruction { pc, text, target } => {
+                    let line = format!("{:>6}    {}", pc, text);
+                    let style = match target {
+                        Some(_) => Style::default().fg(Color::Yellow),
+                        None => Style::default(),
+                    };
+                    ListItem::new(Line::styled(line, style))
+                }
+            })
+            .collect();
+        let listing = List::new(items)
+            .block(Block::bordered().title(title))
+            .highlight_style(highlight(self.focus == Focus::Disassembly));
+        frame.render_stateful_widget(listing, listing_area, &mut self.listing);
+
+        let help = "↑↓ move  Tab switch pane  Enter follow call/jump  Backspace back  q quit";
+        let status_line = if self.status.is_empty() {
+            help.to_string()
+        } else {
+            format!("{}  |  {}", self.status, help)
+        };
+        frame.render_widget(
+            Paragraph::new(status_line).style(Style::default().add_modifier(Modifier::DIM)),
+            status,
+        );
+    }
+}
+
+/// Browses the disassembly of an analyzed program in the terminal.
+///
+/// # Arguments
+///
+/// * `program` - The bytes of the program, to resolve the strings it loads.
+/// * `analysis` - The analysis of the program, with the recovered names applied.
+/// * `sbpf_version` - The SBPF version of the program.
+/// * `error_codes` - Error codes used to annotate the instructions using them.
+/// * `annotations` - Comments and bookmarks shown before the instructions.
+/// * `selection` - If given, only these functions are listed.
+pub fn run(
+    program: &[u8],
+    analysis: &Analysis,
+    sbpf_version: SBPFVersion,
+    error_codes: &ErrorCodeTable,
+    annotations: &Annotations,
+    selection: Option<&FunctionSelection>,
+) -> Result<()> {
+    let mut tui = DisassemblyTui::new(
+        program,
+        analysis,
+        sbpf_version,
+        error_codes,
+        annotations,
+        selection,
+    );
+    if tui.functions.is_empty() {
+        println!("No function to browse.");
+        return Ok(());
+    }
+    let mut terminal = ratatui::try_init()?;
+    let result = tui.run(&mut terminal);
+    ratatui::try_restore()?;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reverse::new_loader;
+    use solana_sbpf::elf::Executable;
+    use test_utils::TestContextObject;
+
+    #[test]
+    fn test_follow_call_and_go_back() {
+        let elf =
+            std::fs::read("test_cases/base_sbf_addition_checker/bytecodes/addition_checker.so")
+                .unwrap();
+        let executable =
+            Executable::<TestContextObject>::from_elf(&elf, new_loader(false).unwrap()).unwrap();
+        let analysis = Analysis::from_executable(&executable).unwrap();
+        let error_codes = ErrorCodeTable::anchor().unwrap();
+        let annotations = Annotations::default();
+        let mut tui = DisassemblyTui::new(
+            &elf,
+            &analysis,
+            executable.get_sbpf_version(),
+            &error_codes,
+            &annotations,
+            None,
+        );
+        let entrypoint = tui.location().unwrap();
+        assert_eq!(
+            tui.functions[entrypoint.function].start,
+            analysis.entrypoint
+        );
+
+        // the first call of the entrypoint to another function
+        let (row, callee) = tui
+            .rows(entrypoint.function)
+            .iter()
+            .enumerate()
+            .find_map(|(row, content)| match content {
+                Row::Instruction {
+                    target: Some(target),
+                    text,
+                    ..
+                } if text.starts_with("call") => Some((row, *target)),
+                _ => None,
+            })
+            .expect("the entrypoint calls a function");
+        let call = Location { row, ..entrypoint };
+        tui.go_to(call);
+        tui.follow();
+        let location = tui.location().unwrap();
+        assert_eq!(tui.functions[location.function].start, callee);
+        assert!(matches!(
+            tui.rows(location.function)[location.row],
+            Row::Instruction { pc, .. } if pc == callee
+        ));
+
+        tui.go_back();
+        assert_eq!(tui.location(), Some(call));
+        assert!(tui.back.is_empty());
+    }
+}
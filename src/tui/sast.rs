@@ -0,0 +1,424 @@
+//! `sast --tui`: the findings of a scan, grouped by severity and rule in a navigable list, with
+//! the source around the selected finding beside it.
+//!
+//! | Key                | Action                                                      |
+//! | ------------------ | ----------------------------------------------------------- |
+//! | `↑` `↓` / `k` `j`  | Previous / next finding                                     |
+//! | `PgUp` `PgDn`      | Previous / next rule                                        |
+//! | `e` / `Enter`      | Open the file at the finding in `$EDITOR`                   |
+//! | `t` / `Space`      | Mark the finding as triaged (or not), saved to the baseline |
+//! | `q` / `Esc`        | Quit                                                        |
+
+use anyhow::Result;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::commands::scan_all_command::severity_rank;
+use crate::parsers::syn_ast::SourcePosition;
+use crate::state::baseline::{Baseline, FindingKey};
+use crate::state::sast_state::{SastState, Severity, SynAstResult, SynMatchResult};
+
+/// Lines of source shown above and below the matched lines.
+const SNIPPET_CONTEXT: u32 = 8;
+
+/// A finding of the list.
+struct Finding<'a> {
+    /// The file, relative to the scanned directory.
+    file: &'a str,
+    /// The path to read the file.
+    path: PathBuf,
+    result: &'a SynAstResult,
+    match_result: &'a SynMatchResult,
+    position: Option<SourcePosition>,
+    key: FindingKey,
+}
+
+impl Finding<'_> {
+    fn line(&self) -> u32 {
+        self.position
+            .as_ref()
+            .map_or(1, |position| position.start_line)
+    }
+}
+
+/// A row of the list: the header of a rule, or a finding (index in `findings`).
+enum Row {
+    Rule(String, Severity),
+    Finding(usize),
+}
+
+struct SastTui<'a> {
+    findings: Vec<Finding<'a>>,
+    rows: Vec<Row>,
+    list: ListState,
+    baseline: Baseline,
+    baseline_path: PathBuf,
+    sources: HashMap<PathBuf, Option<String>>,
+    status: String,
+}
+
+/// Returns the source lines from `SNIPPET_CONTEXT` lines before the match to `SNIPPET_CONTEXT`
+/// lines after it, with their number and whether they are matched.
+fn snippet<'s>(source: &'s str, position: &SourcePosition) -> Vec<(u32, &'s str, bool)> {
+    let first = position.start_line.saturating_sub(SNIPPET_CONTEXT).max(1);
+    let last = position.end_line.max(position.start_line) + SNIPPET_CONTEXT;
+    source
+        .lines()
+        .zip(1..)
+        .filter(|(_, number)| (first..=last).contains(number))
+        .map(|(line, number)| {
+            let matched = (position.start_line..=position.end_line).contains(&number);
+            (number, line, matched)
+        })
+        .collect()
+}
+
+fn severity_color(severity: &Severity) -> Color {
+    match severity {
+        Severity::Critical => Color::Magenta,
+        Severity::High => Color::Red,
+        Severity::Medium => Color::Yellow,
+        Severity::Low => Color::Green,
+        Severity::Unknown => Color::Gray,
+    }
+}
+
+impl<'a> SastTui<'a> {
+    fn new(states: &'a [SastState], baseline: Baseline, baseline_path: PathBuf) -> Self {
+        let mut findings: Vec<Finding> = states
+            .iter()
+            .flat_map(|state| state.syn_ast_map.iter().map(move |entry| (state, entry)))
+            .flat_map(|(state, (file, syn_ast))| {
+                syn_ast.results.iter().flat_map(move |result| {
+                    result.matches.iter().map(move |match_result| Finding {
+                        file,
+                        path: state.source_path(file),
+                        result,
+                        match_result,
+                        position: match_result.get_location_metadata().ok(),
+                        // the files are relative to the scanned directory, like the baseline
+                        key: FindingKey::new(file, result, match_result),
+                    })
+                })
+            })
+            .collect();
+        findings.sort_by(|a, b| {
+            let metadata = (&a.result.rule_metadata, &b.result.rule_metadata);
+            severity_rank(&metadata.0.severity)
+                .cmp(&severity_rank(&metadata.1.severity))
+                .then_with(|| metadata.0.name.cmp(&metadata.1.name))
+                .then_with(|| a.file.cmp(b.file))
+                .then_with(|| a.line().cmp(&b.line()))
+        });
+
+        let mut rows = Vec::new();
+        for (index, finding) in findings.iter().enumerate() {
+            let metadata = &finding.result.rule_metadata;
+            let new_rule = match index {
+                0 => true,
+                _ => findings[index - 1].result.rule_metadata.name != metadata.name,
+            };
+            if new_rule {
+                rows.push(Row::Rule(metadata.name.clone(), metadata.severity.clone()));
+            }
+            rows.push(Row::Finding(index));
+        }
+
+        let mut tui = Self {
+            findings,
+            rows,
+            list: ListState::default(),
+            baseline,
+            baseline_path,
+            sources: HashMap::new(),
+            status: String::new(),
+        };
+        tui.select_next(0);
+        tui
+    }
+
+    fn selected(&self) -> Option<&Finding<'a>> {
+        match self.rows.get(self.list.selected()?)? {
+            Row::Finding(index) => self.findings.get(*index),
+            Row::Rule(..) => None,
+        }
+    }
+
+    /// Selects the first finding after the `skip` rows following the selected one.
+    fn select_next(&mut self, skip: usize) {
+        let start = self.list.selected().map_or(0, |selected| selected + skip);
+        if let Some(row) =
+            (start..self.rows.len()).find(|&row| matches!(self.rows[row], Row::Finding(_)))
+        {
+            self.list.select(Some(row));
+        }
+    }
+
+    /// Selects the last finding before the selected one.
+    fn select_previous(&mut self) {
+        let end = self.list.selected().unwrap_or(0);
+        if let Some(row) = (0..end)
+            .rev()
+            .find(|&row| matches!(self.rows[row], Row::Finding(_)))
+        {
+            self.list.select(Some(row));
+        }
+    }
+
+    /// Selects the first finding of the next rule (`forward`) or of the previous one.
+    fn select_rule(&mut self, forward: bool) {
+        let selected = self.list.selected().unwrap_or(0);
+        let header = |row: &usize| matches!(self.rows[*row], Row::Rule(..));
+        let target = if forward {
+            (selected + 1..self.rows.len()).find(header)
+        } else {
+            // the header of the current rule, then the one before it
+            (0..selected).rev().filter(header).nth(1).or(Some(0))
+        };
+        if let Some(row) = target {
+            self.list.select(Some(row));
+            self.select_next(0);
+        }
+    }
+
+    fn toggle_triaged(&mut self) {
+        let Some(key) = self.selected().map(|finding| finding.key.clone()) else {
+            return;
+        };
+        let triaged = self.baseline.toggle(&key);
+        self.status = match self.baseline.save(&self.baseline_path) {
+            Ok(()) if triaged => format!("Triaged, saved to {}", self.baseline_path.display()),
+            Ok(()) => format!(
+                "Not triaged anymore, saved to {}",
+                self.baseline_path.display()
+            ),
+            Err(e) => format!("{:#}", e),
+        };
+    }
+
+    fn source(&mut self, path: &Path) -> Option<&str> {
+        self.sources
+            .entry(path.to_path_buf())
+            .or_insert_with(|| std::fs::read_to_string(path).ok())
+            .as_deref()
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Down | KeyCode::Char('j') => self.select_next(1),
+                KeyCode::Up | KeyCode::Char('k') => self.select_previous(),
+                KeyCode::PageDown => self.select_rule(true),
+                KeyCode::PageUp => self.select_rule(false),
+                KeyCode::Char('t') | KeyCode::Char(' ') => self.toggle_triaged(),
+                KeyCode::Char('e') | KeyCode::Enter => {
+                    if let Some((file, line)) = self
+                        .selected()
+                        .map(|finding| (finding.path.to_string_lossy().to_string(), finding.line()))
+                    {
+                        self.status = match super::open_in_editor(terminal, &file, line) {
+                            Ok(()) => String::new(),
+                            Err(e) => format!("{:#}", e),
+                        };
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [list_area, detail_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(main);
+
+        let items: Vec<ListItem> = self
+            .rows
+            .iter()
+            .map(|row| match row {
+                Row::Rule(name, severity) => ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{:?} ", severity),
+                        Style::default()
+                            .fg(severity_color(severity))
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(name.clone(), Style::default().add_modifier(Modifier::BOLD)),
+                ])),
+                Row::Finding(index) => {
+                    let finding = &self.findings[*index];
+                    let triaged = self.baseline.is_triaged(&finding.key);
+                    let text = format!(
+                        "  {} {}:{} {}",
+                        if triaged { "✓" } else { " " },
+                        finding.key.file,
+                        finding.line(),
+                        finding.match_result.ident
+                    );
+                    let style = if triaged {
+                        Style::default().add_modifier(Modifier::DIM)
+                    } else {
+                        Style::default()
+                    };
+                    ListItem::new(Line::styled(text, style))
+                }
+            })
+            .collect();
+        let triaged = self
+            .findings
+            .iter()
+            .filter(|finding| self.baseline.is_triaged(&finding.key))
+            .count();
+        let list = List::new(items)
+            .block(Block::bordered().title(format!(
+                " Findings ({}, {} triaged) ",
+                self.findings.len(),
+                triaged
+            )))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, list_area, &mut self.list);
+
+        let detail = self.detail();
+        frame.render_widget(
+            Paragraph::new(detail)
+                .block(Block::bordered().title(" Source "))
+                .wrap(Wrap { trim: false }),
+            detail_area,
+        );
+
+        let help = "↑↓ finding  PgUp/PgDn rule  e open in $EDITOR  t triage  q quit";
+        let status_line = if self.status.is_empty() {
+            help.to_string()
+        } else {
+            format!("{}  |  {}", self.status, help)
+        };
+        frame.render_widget(
+            Paragraph::new(status_line).style(Style::default().add_modifier(Modifier::DIM)),
+            status,
+        );
+    }
+
+    /// The description of the selected finding, then its source.
+    fn detail(&mut self) -> Text<'static> {
+        let Some(finding) = self.selected() else {
+            return Text::default();
+        };
+        let metadata = &finding.result.rule_metadata;
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let mut lines = vec![
+            Line::styled(metadata.name.clone(), bold),
+            Line::from(finding.result.classification.describe()),
+            Line::from(match &finding.position {
+                Some(position) => position.get_pretty_string(),
+                None => format!("{}: {}", finding.file, finding.match_result.access_path),
+            }),
+            Line::from(
+                finding
+                    .result
+                    .render_description(finding.match_result)
+                    .unwrap_or_else(|| metadata.description.clone()),
+            ),
+        ];
+        if self.baseline.is_triaged(&finding.key) {
+            lines.push(Line::styled("Triaged", Style::default().fg(Color::Green)));
+        }
+        lines.push(Line::default());
+
+        let (path, position) = (finding.path.clone(), finding.position.clone());
+        match (self.source(&path), position) {
+            (Some(source), Some(position)) => {
+                for (number, line, matched) in snippet(source, &position) {
+                    let style = if matched {
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default()
+                    };
+                    lines.push(Line::styled(format!("{:>5} │ {}", number, line), style));
+                }
+            }
+            (None, _) => lines.push(Line::from(format!("Can't read {}", path.display()))),
+            (_, None) => lines.push(Line::from("No position for this finding")),
+        }
+        Text::from(lines)
+    }
+}
+
+/// Browses the findings of SAST states in the terminal.
+///
+/// # Arguments
+///
+/// * `states` - The states of the scanned projects, whose rules were applied and whose paths
+///   are relative to the scanned directory, like the files of the baseline.
+/// * `baseline_path` - The baseline file, read if it exists and written when a finding is
+///   triaged.
+pub fn run(states: &[SastState], baseline_path: &Path) -> Result<()> {
+    let baseline = Baseline::load(baseline_path)?;
+    let mut tui = SastTui::new(states, baseline, baseline_path.to_path_buf());
+    if tui.findings.is_empty() {
+        println!("No vulnerabilities detected.");
+        return Ok(());
+    }
+    let mut terminal = ratatui::try_init()?;
+    let result = tui.run(&mut terminal);
+    ratatui::try_restore()?;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippet() {
+        let source = (1..=30)
+            .map(|line| format!("line {}", line))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let position = SourcePosition {
+            start_line: 12,
+            start_column: 4,
+            end_line: 13,
+            end_column: 8,
+            source_file: "src/lib.rs".to_string(),
+            column_encoding: Default::default(),
+        };
+        let lines = snippet(&source, &position);
+        assert_eq!(lines.first(), Some(&(4, "line 4", false)));
+        assert_eq!(lines.last(), Some(&(21, "line 21", false)));
+        let matched: Vec<u32> = lines
+            .iter()
+            .filter(|(_, _, matched)| *matched)
+            .map(|(number, _, _)| *number)
+            .collect();
+        assert_eq!(matched, [12, 13]);
+
+        // near the top of the file
+        let position = SourcePosition {
+            start_line: 2,
+            end_line: 2,
+            ..position
+        };
+        assert_eq!(
+            snippet(&source, &position).first(),
+            Some(&(1, "line 1", false))
+        );
+    }
+}
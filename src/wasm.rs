@@ -0,0 +1,41 @@
+//! Browser bindings of the analysis core, built with the `wasm` feature (without the default
+//! `cli` one) for `wasm32-unknown-unknown`.
+//!
+//! They work on what the page gives them, without filesystem, process nor threads: the bytes
+//! of a program, or the source of a Rust file. The results are JSON strings:
+//!
+//! ```js
+//! import init, { disassemble, scan_source } from "./pkg/sol_azy.js";
+//!
+//! await init();
+//! const { disassembly, cfg } = JSON.parse(disassemble(programBytes, true));
+//! const findings = JSON.parse(scan_source("lib.rs", source));
+//! ```
+//!
+//! See `docs/src/wasm.md` for the build.
+
+use wasm_bindgen::prelude::*;
+
+use crate::reverse::analyze_in_memory;
+use crate::state::sast_state::{finding_rows, SastState};
+
+fn to_js_error(e: anyhow::Error) -> JsError {
+    JsError::new(&format!("{:#}", e))
+}
+
+/// Disassembles an ELF program, returning `{"disassembly": ..., "cfg": ...}`: the listing of
+/// `disassembly.out` and the DOT of `cfg.dot`, reduced to the functions reachable from the
+/// entrypoint with `reduced`.
+#[wasm_bindgen]
+pub fn disassemble(elf: &[u8], reduced: bool) -> Result<String, JsError> {
+    let outputs = analyze_in_memory(elf, reduced).map_err(to_js_error)?;
+    serde_json::to_string(&outputs).map_err(|e| to_js_error(e.into()))
+}
+
+/// Runs the internal SAST rules on the Rust source of the file `filename`, returning its
+/// findings, one object per match (the rows of the Python `scan_project`).
+#[wasm_bindgen]
+pub fn scan_source(filename: &str, source: &str) -> Result<String, JsError> {
+    let state = SastState::scan_source(filename, source).map_err(to_js_error)?;
+    serde_json::to_string(&finding_rows(&[state])).map_err(|e| to_js_error(e.into()))
+}
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Cast111111111111111111111111111111111111111");
+
+/// Each instruction converts an amount between integer widths, safely or not. The vulnerable
+/// ones are prefixed with `vulnerable_`.
+#[program]
+pub mod amount_casts {
+    use super::*;
+
+    /// `amount as u32` keeps the low 32 bits of the deposit.
+    pub fn vulnerable_deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.last_deposit = amount as u32;
+        pool.total = pool.total.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.last_deposit = u32::try_from(amount).map_err(|_| ErrorCode::Overflow)?;
+        pool.total = pool.total.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    /// The share computation is done in `u128`, but its result is truncated back to `u64`.
+    pub fn vulnerable_shares(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let minted = (amount as u128 * pool.shares as u128 / pool.total as u128) as u64;
+        pool.shares += minted;
+        Ok(())
+    }
+
+    pub fn shares(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let minted = (amount as u128)
+            .checked_mul(pool.shares as u128)
+            .and_then(|product| product.checked_div(pool.total as u128))
+            .ok_or(ErrorCode::Overflow)?;
+        pool.shares += u64::try_from(minted).map_err(|_| ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    /// The fee of a `u128` reward is stored as an `u64`.
+    pub fn vulnerable_reward(ctx: Context<Deposit>, reward: u128) -> Result<()> {
+        let fee_total = reward / 100;
+        ctx.accounts.pool.fees = fee_total as u64;
+        Ok(())
+    }
+
+    /// Widening casts and casts of non-amount values are fine.
+    pub fn counters(ctx: Context<Deposit>, index: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.total += pool.last_deposit as u64;
+        pool.bucket = (index % 256) as u8;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut, has_one = authority)]
+    pub pool: Account<'info, Pool>,
+    pub authority: Signer<'info>,
+}
+
+#[account]
+pub struct Pool {
+    pub authority: Pubkey,
+    pub total: u64,
+    pub shares: u64,
+    pub fees: u64,
+    pub last_deposit: u32,
+    pub bucket: u8,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Arithmetic overflow")]
+    Overflow,
+}
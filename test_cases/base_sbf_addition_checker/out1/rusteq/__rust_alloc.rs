@@ -0,0 +1,33 @@
+// __rust_alloc (pc 126 to 151)
+fn __rust_alloc(r1: u64, r2: u64) -> u64 {
+    /*   126 */ r3 load str located at 12884901888
+    /*   128 */ r3 = *(r3 + 0x0) as u64   ///  r3 = *((r3 as i64).wrapping_add(0) as *const u64) as u64
+    /*   129 */ r4 load str located at 12884934656
+    /*   131 */ if r3 == (0_i32 as i64 as u64) { pc += 1 }
+    // lbb_132:
+    /*   132 */ r4 = r3
+    // lbb_133:
+    /*   133 */ r3 = r4
+    /*   134 */ r3 -= r1   ///  r3 = r3.wrapping_sub(r1)
+    /*   135 */ r0 = 0_i32 as i64 as u64
+    /*   136 */ r5 = 1_i32 as i64 as u64
+    /*   137 */ if r3 > r4 { pc += 1 }
+    // lbb_138:
+    /*   138 */ r5 = 0_i32 as i64 as u64
+    // lbb_139:
+    /*   139 */ r1 = 0_i32 as i64 as u64
+    /*   140 */ if r5 != (0_i32 as i64 as u64) { pc += 1 }
+    // lbb_141:
+    /*   141 */ r1 = r3
+    // lbb_142:
+    /*   142 */ r2 = -r2   ///  r2 = (r2 as i64).wrapping_neg() as u64
+    /*   143 */ r1 &= r2   ///  r1 = r1 & r2
+    /*   144 */ r2 load str located at 12884901896
+    /*   146 */ if r2 > r1 { pc += 4 }
+    // lbb_147:
+    /*   147 */ r2 load str located at 12884901888
+    /*   149 */ *(r2 + 0x0) = r1 as u64   ///  *((r2 as i64).wrapping_add(0) as *mut u64) = r1 as u64
+    /*   150 */ r0 = r1
+    // lbb_151:
+    /*   151 */ return r0
+}
@@ -0,0 +1,105 @@
+// alloc::fmt::format::format_inner::h03e4c89f2f461111 (pc 858 to 937)
+fn alloc::fmt::format::format_inner::h03e4c89f2f461111(r1: u64, r2: u64) -> u64 {
+    /*   858 */ r7 = r2
+    /*   859 */ r6 = r1
+    /*   860 */ r2 = *(r7 + 0x8) as u64   ///  r2 = *((r7 as i64).wrapping_add(8) as *const u64) as u64
+    /*   861 */ if r2 == (0_i32 as i64 as u64) { pc += 41 }
+    // lbb_862:
+    /*   862 */ r1 = *(r7 + 0x0) as u64   ///  r1 = *((r7 as i64).wrapping_add(0) as *const u64) as u64
+    /*   863 */ r4 = 0_i32 as i64 as u64
+    /*   864 */ r3 = r1
+    /*   865 */ r3 += 8   ///  r3 = r3.wrapping_add(8_i32 as i64 as u64)
+    // lbb_866:
+    /*   866 */ r8 = *(r3 + 0x0) as u64   ///  r8 = *((r3 as i64).wrapping_add(0) as *const u64) as u64
+    /*   867 */ r8 += r4   ///  r8 = r8.wrapping_add(r4)
+    /*   868 */ r3 += 16   ///  r3 = r3.wrapping_add(16_i32 as i64 as u64)
+    /*   869 */ r2 += -1   ///  r2 = r2.wrapping_add(-1_i32 as i64 as u64)
+    /*   870 */ r4 = r8
+    /*   871 */ if r2 != (0_i32 as i64 as u64) { pc += -6 }
+    // lbb_872:
+    /*   872 */ r2 = *(r7 + 0x18) as u64   ///  r2 = *((r7 as i64).wrapping_add(24) as *const u64) as u64
+    /*   873 */ if r2 == (0_i32 as i64 as u64) { pc += 14 }
+    // lbb_874:
+    /*   874 */ r4 = 0_i32 as i64 as u64
+    /*   875 */ r3 = 1_i32 as i64 as u64
+    /*   876 */ r5 = 16_i32 as i64 as u64
+    /*   877 */ r2 = 1_i32 as i64 as u64
+    /*   878 */ if r5 > r8 { pc += 1 }
+    // lbb_879:
+    /*   879 */ r2 = 0_i32 as i64 as u64
+    // lbb_880:
+    /*   880 */ r1 = *(r1 + 0x8) as u64   ///  r1 = *((r1 as i64).wrapping_add(8) as *const u64) as u64
+    /*   881 */ if r1 == (0_i32 as i64 as u64) { pc += 1 }
+    // lbb_882:
+    /*   882 */ r3 = 0_i32 as i64 as u64
+    // lbb_883:
+    /*   883 */ if (r4 as i64) > (r8 as i64) { pc += 23 }
+    // lbb_884:
+    /*   884 */ r2 &= r3   ///  r2 = r2 & r3
+    /*   885 */ r2 &= 1   ///  r2 = r2 & (1_i32 as i64 as u64)
+    /*   886 */ if r2 != (0_i32 as i64 as u64) { pc += 20 }
+    // lbb_887:
+    /*   887 */ r8 <<= 1   ///  r8 = r8.wrapping_shl(1)
+    // lbb_888:
+    /*   888 */ r0 = 1_i32 as i64 as u64
+    /*   889 */ r1 = 0_i32 as i64 as u64
+    /*   890 */ if r8 == (0_i32 as i64 as u64) { pc += 18 }
+    // lbb_891:
+    /*   891 */ if (r8 as i64) > (-1 as i32 as i64) { pc += 2 }
+    // lbb_892:
+    /*   892 */ r0 = fn_0xa8220fca(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xa8220fca
+    // lbb_893:
+    /*   893 */ abort()
+    // lbb_894:
+    /*   894 */ r1 = r8
+    /*   895 */ r2 = 1_i32 as i64 as u64
+    /*   896 */ r0 = fn_0xd203373a(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xd203373a
+    // lbb_897:
+    /*   897 */ r1 = r8
+    /*   898 */ if r0 != (0_i32 as i64 as u64) { pc += 10 }
+    // lbb_899:
+    /*   899 */ r1 = 1_i32 as i64 as u64
+    /*   900 */ r2 = r8
+    /*   901 */ r0 = fn_0xdeeafbd2(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xdeeafbd2
+    // lbb_902:
+    /*   902 */ abort()
+    // lbb_903:
+    /*   903 */ r8 = 0_i32 as i64 as u64
+    /*   904 */ r1 = *(r7 + 0x18) as u64   ///  r1 = *((r7 as i64).wrapping_add(24) as *const u64) as u64
+    /*   905 */ if r1 == (0_i32 as i64 as u64) { pc += 1 }
+    // lbb_906:
+    /*   906 */ if true { pc += -20 }
+    // lbb_907:
+    /*   907 */ r0 = 1_i32 as i64 as u64
+    /*   908 */ r1 = 0_i32 as i64 as u64
+    // lbb_909:
+    /*   909 */ r2 = 0_i32 as i64 as u64
+    /*   910 */ *(r10 - 0x10) = r2 as u64   ///  *((r10 as i64).wrapping_add(-16) as *mut u64) = r2 as u64
+    /*   911 */ *(r10 - 0x18) = r1 as u64   ///  *((r10 as i64).wrapping_add(-24) as *mut u64) = r1 as u64
+    /*   912 */ *(r10 - 0x20) = r0 as u64   ///  *((r10 as i64).wrapping_add(-32) as *mut u64) = r0 as u64
+    /*   913 */ r1 = r10
+    /*   914 */ r1 += -32   ///  r1 = r1.wrapping_add(-32_i32 as i64 as u64)
+    /*   915 */ r2 load str located at 4294985320
+    /*   917 */ r3 = r7
+    /*   918 */ r0 = fn_0xadc9dcba(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xadc9dcba
+    // lbb_919:
+    /*   919 */ if r0 != (0_i32 as i64 as u64) { pc += 7 }
+    // lbb_920:
+    /*   920 */ r1 = *(r10 - 0x10) as u64   ///  r1 = *((r10 as i64).wrapping_add(-16) as *const u64) as u64
+    /*   921 */ *(r6 + 0x10) = r1 as u64   ///  *((r6 as i64).wrapping_add(16) as *mut u64) = r1 as u64
+    /*   922 */ r1 = *(r10 - 0x18) as u64   ///  r1 = *((r10 as i64).wrapping_add(-24) as *const u64) as u64
+    /*   923 */ *(r6 + 0x8) = r1 as u64   ///  *((r6 as i64).wrapping_add(8) as *mut u64) = r1 as u64
+    /*   924 */ r1 = *(r10 - 0x20) as u64   ///  r1 = *((r10 as i64).wrapping_add(-32) as *const u64) as u64
+    /*   925 */ *(r6 + 0x0) = r1 as u64   ///  *((r6 as i64).wrapping_add(0) as *mut u64) = r1 as u64
+    /*   926 */ return r0
+    // lbb_927:
+    /*   927 */ r3 = r10
+    /*   928 */ r3 += -1   ///  r3 = r3.wrapping_add(-1_i32 as i64 as u64)
+    /*   929 */ r1 load str located at 4294984842
+    /*   931 */ r2 = 51_i32 as i64 as u64
+    /*   932 */ r4 load str located at 4294985408
+    /*   934 */ r5 load str located at 4294985440
+    /*   936 */ r0 = fn_0xddeca13(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xddeca13
+    // lbb_937:
+    /*   937 */ abort()
+}
@@ -0,0 +1,69 @@
+// alloc::raw_vec::RawVec<T,A>::reserve::do_reserve_and_handle::h15e3580015b80bfc (pc 696 to 743)
+fn alloc::raw_vec::RawVec<T,A>::reserve::do_reserve_and_handle::h15e3580015b80bfc(r1: u64, r2: u64, r3: u64) -> u64 {
+    /*   696 */ r6 = r1
+    /*   697 */ r4 = r2
+    /*   698 */ r4 += r3   ///  r4 = r4.wrapping_add(r3)
+    /*   699 */ r1 = 1_i32 as i64 as u64
+    /*   700 */ if r2 > r4 { pc += 1 }
+    // lbb_701:
+    /*   701 */ r1 = 0_i32 as i64 as u64
+    // lbb_702:
+    /*   702 */ r1 &= 1   ///  r1 = r1 & (1_i32 as i64 as u64)
+    /*   703 */ if r1 != (0_i32 as i64 as u64) { pc += 35 }
+    // lbb_704:
+    /*   704 */ r1 = *(r6 + 0x8) as u64   ///  r1 = *((r6 as i64).wrapping_add(8) as *const u64) as u64
+    /*   705 */ r7 = r1
+    /*   706 */ r7 <<= 1   ///  r7 = r7.wrapping_shl(1)
+    /*   707 */ if r7 > r4 { pc += 1 }
+    // lbb_708:
+    /*   708 */ r7 = r4
+    // lbb_709:
+    /*   709 */ if r7 > (8_i32 as i64 as u64) { pc += 1 }
+    // lbb_710:
+    /*   710 */ r7 = 8_i32 as i64 as u64
+    // lbb_711:
+    /*   711 */ r2 = r7
+    /*   712 */ r2 ^= -1   ///  r2 = r2 ^ (-1_i32 as i64 as u64)
+    /*   713 */ r2 >>= 63   ///  r2 = r2.wrapping_shr(63)
+    /*   714 */ if r1 != (0_i32 as i64 as u64) { pc += 3 }
+    // lbb_715:
+    /*   715 */ r1 = 0_i32 as i64 as u64
+    /*   716 */ *(r10 - 0x10) = r1 as u64   ///  *((r10 as i64).wrapping_add(-16) as *mut u64) = r1 as u64
+    /*   717 */ if true { pc += 5 }
+    // lbb_718:
+    /*   718 */ r3 = *(r6 + 0x0) as u64   ///  r3 = *((r6 as i64).wrapping_add(0) as *const u64) as u64
+    /*   719 */ *(r10 - 0x8) = r1 as u64   ///  *((r10 as i64).wrapping_add(-8) as *mut u64) = r1 as u64
+    /*   720 */ r1 = 1_i32 as i64 as u64
+    /*   721 */ *(r10 - 0x10) = r1 as u64   ///  *((r10 as i64).wrapping_add(-16) as *mut u64) = r1 as u64
+    /*   722 */ *(r10 - 0x18) = r3 as u64   ///  *((r10 as i64).wrapping_add(-24) as *mut u64) = r3 as u64
+    // lbb_723:
+    /*   723 */ r1 = r10
+    /*   724 */ r1 += -48   ///  r1 = r1.wrapping_add(-48_i32 as i64 as u64)
+    /*   725 */ r4 = r10
+    /*   726 */ r4 += -24   ///  r4 = r4.wrapping_add(-24_i32 as i64 as u64)
+    /*   727 */ r3 = r7
+    /*   728 */ r0 = fn_0xdd5826de(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xdd5826de
+    // lbb_729:
+    /*   729 */ r1 = *(r10 - 0x28) as u64   ///  r1 = *((r10 as i64).wrapping_add(-40) as *const u64) as u64
+    /*   730 */ r2 = *(r10 - 0x30) as u64   ///  r2 = *((r10 as i64).wrapping_add(-48) as *const u64) as u64
+    /*   731 */ if r2 != (0_i32 as i64 as u64) { pc += 3 }
+    // lbb_732:
+    /*   732 */ *(r6 + 0x8) = r7 as u64   ///  *((r6 as i64).wrapping_add(8) as *mut u64) = r7 as u64
+    /*   733 */ *(r6 + 0x0) = r1 as u64   ///  *((r6 as i64).wrapping_add(0) as *mut u64) = r1 as u64
+    // lbb_734:
+    /*   734 */ return r0
+    // lbb_735:
+    /*   735 */ r2 load str located at -9223372036854775807
+    /*   737 */ if r1 == r2 { pc += -4 }
+    // lbb_738:
+    /*   738 */ if r1 != (0_i32 as i64 as u64) { pc += 2 }
+    // lbb_739:
+    /*   739 */ r0 = fn_0xa8220fca(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xa8220fca
+    // lbb_740:
+    /*   740 */ abort()
+    // lbb_741:
+    /*   741 */ r2 = *(r10 - 0x20) as u64   ///  r2 = *((r10 as i64).wrapping_add(-32) as *const u64) as u64
+    /*   742 */ r0 = fn_0xdeeafbd2(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xdeeafbd2
+    // lbb_743:
+    /*   743 */ abort()
+}
@@ -0,0 +1,68 @@
+// alloc::raw_vec::RawVec<T,A>::reserve_for_push::hb032165cc0c047eb (pc 744 to 790)
+fn alloc::raw_vec::RawVec<T,A>::reserve_for_push::hb032165cc0c047eb(r1: u64, r2: u64) -> u64 {
+    /*   744 */ r6 = r1
+    /*   745 */ r2 += 1   ///  r2 = r2.wrapping_add(1_i32 as i64 as u64)
+    /*   746 */ r1 = 1_i32 as i64 as u64
+    /*   747 */ if r2 == (0_i32 as i64 as u64) { pc += 1 }
+    // lbb_748:
+    /*   748 */ r1 = 0_i32 as i64 as u64
+    // lbb_749:
+    /*   749 */ r1 &= 1   ///  r1 = r1 & (1_i32 as i64 as u64)
+    /*   750 */ if r1 != (0_i32 as i64 as u64) { pc += 35 }
+    // lbb_751:
+    /*   751 */ r1 = *(r6 + 0x8) as u64   ///  r1 = *((r6 as i64).wrapping_add(8) as *const u64) as u64
+    /*   752 */ r7 = r1
+    /*   753 */ r7 <<= 1   ///  r7 = r7.wrapping_shl(1)
+    /*   754 */ if r7 > r2 { pc += 1 }
+    // lbb_755:
+    /*   755 */ r7 = r2
+    // lbb_756:
+    /*   756 */ if r7 > (8_i32 as i64 as u64) { pc += 1 }
+    // lbb_757:
+    /*   757 */ r7 = 8_i32 as i64 as u64
+    // lbb_758:
+    /*   758 */ r2 = r7
+    /*   759 */ r2 ^= -1   ///  r2 = r2 ^ (-1_i32 as i64 as u64)
+    /*   760 */ r2 >>= 63   ///  r2 = r2.wrapping_shr(63)
+    /*   761 */ if r1 != (0_i32 as i64 as u64) { pc += 3 }
+    // lbb_762:
+    /*   762 */ r1 = 0_i32 as i64 as u64
+    /*   763 */ *(r10 - 0x10) = r1 as u64   ///  *((r10 as i64).wrapping_add(-16) as *mut u64) = r1 as u64
+    /*   764 */ if true { pc += 5 }
+    // lbb_765:
+    /*   765 */ r3 = *(r6 + 0x0) as u64   ///  r3 = *((r6 as i64).wrapping_add(0) as *const u64) as u64
+    /*   766 */ *(r10 - 0x8) = r1 as u64   ///  *((r10 as i64).wrapping_add(-8) as *mut u64) = r1 as u64
+    /*   767 */ r1 = 1_i32 as i64 as u64
+    /*   768 */ *(r10 - 0x10) = r1 as u64   ///  *((r10 as i64).wrapping_add(-16) as *mut u64) = r1 as u64
+    /*   769 */ *(r10 - 0x18) = r3 as u64   ///  *((r10 as i64).wrapping_add(-24) as *mut u64) = r3 as u64
+    // lbb_770:
+    /*   770 */ r1 = r10
+    /*   771 */ r1 += -48   ///  r1 = r1.wrapping_add(-48_i32 as i64 as u64)
+    /*   772 */ r4 = r10
+    /*   773 */ r4 += -24   ///  r4 = r4.wrapping_add(-24_i32 as i64 as u64)
+    /*   774 */ r3 = r7
+    /*   775 */ r0 = fn_0xdd5826de(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xdd5826de
+    // lbb_776:
+    /*   776 */ r1 = *(r10 - 0x28) as u64   ///  r1 = *((r10 as i64).wrapping_add(-40) as *const u64) as u64
+    /*   777 */ r2 = *(r10 - 0x30) as u64   ///  r2 = *((r10 as i64).wrapping_add(-48) as *const u64) as u64
+    /*   778 */ if r2 != (0_i32 as i64 as u64) { pc += 3 }
+    // lbb_779:
+    /*   779 */ *(r6 + 0x8) = r7 as u64   ///  *((r6 as i64).wrapping_add(8) as *mut u64) = r7 as u64
+    /*   780 */ *(r6 + 0x0) = r1 as u64   ///  *((r6 as i64).wrapping_add(0) as *mut u64) = r1 as u64
+    // lbb_781:
+    /*   781 */ return r0
+    // lbb_782:
+    /*   782 */ r2 load str located at -9223372036854775807
+    /*   784 */ if r1 == r2 { pc += -4 }
+    // lbb_785:
+    /*   785 */ if r1 != (0_i32 as i64 as u64) { pc += 2 }
+    // lbb_786:
+    /*   786 */ r0 = fn_0xa8220fca(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xa8220fca
+    // lbb_787:
+    /*   787 */ abort()
+    // lbb_788:
+    /*   788 */ r2 = *(r10 - 0x20) as u64   ///  r2 = *((r10 as i64).wrapping_add(-32) as *const u64) as u64
+    /*   789 */ r0 = fn_0xdeeafbd2(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xdeeafbd2
+    // lbb_790:
+    /*   790 */ abort()
+}
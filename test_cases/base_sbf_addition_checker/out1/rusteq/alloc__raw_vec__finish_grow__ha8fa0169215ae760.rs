@@ -0,0 +1,63 @@
+// alloc::raw_vec::finish_grow::ha8fa0169215ae760 (pc 215 to 255)
+fn alloc::raw_vec::finish_grow::ha8fa0169215ae760(r1: u64, r2: u64, r3: u64, r4: u64) -> u64 {
+    /*   215 */ r7 = r3
+    /*   216 */ r8 = r2
+    /*   217 */ r6 = r1
+    /*   218 */ if r8 == (0_i32 as i64 as u64) { pc += 10 }
+    // lbb_219:
+    /*   219 */ r1 = *(r4 + 0x8) as u64   ///  r1 = *((r4 as i64).wrapping_add(8) as *const u64) as u64
+    /*   220 */ if r1 == (0_i32 as i64 as u64) { pc += 18 }
+    // lbb_221:
+    /*   221 */ r2 = *(r4 + 0x10) as u64   ///  r2 = *((r4 as i64).wrapping_add(16) as *const u64) as u64
+    /*   222 */ if r2 != (0_i32 as i64 as u64) { pc += 10 }
+    // lbb_223:
+    /*   223 */ if r7 == (0_i32 as i64 as u64) { pc += 25 }
+    // lbb_224:
+    /*   224 */ r1 = r7
+    /*   225 */ r2 = r8
+    /*   226 */ r0 = fn_0xd203373a(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xd203373a
+    // lbb_227:
+    /*   227 */ if r0 == (0_i32 as i64 as u64) { pc += 17 }
+    // lbb_228:
+    /*   228 */ if true { pc += 22 }
+    // lbb_229:
+    /*   229 */ *(r6 + 0x10) = r7 as u64   ///  *((r6 as i64).wrapping_add(16) as *mut u64) = r7 as u64
+    /*   230 */ r1 = 0_i32 as i64 as u64
+    /*   231 */ *(r6 + 0x8) = r1 as u64   ///  *((r6 as i64).wrapping_add(8) as *mut u64) = r1 as u64
+    /*   232 */ if true { pc += 14 }
+    // lbb_233:
+    /*   233 */ r1 = *(r4 + 0x0) as u64   ///  r1 = *((r4 as i64).wrapping_add(0) as *const u64) as u64
+    /*   234 */ r3 = r8
+    /*   235 */ r4 = r7
+    /*   236 */ r0 = fn_0x882312e2(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x882312e2
+    // lbb_237:
+    /*   237 */ if r0 == (0_i32 as i64 as u64) { pc += 7 }
+    // lbb_238:
+    /*   238 */ if true { pc += 12 }
+    // lbb_239:
+    /*   239 */ if r7 == (0_i32 as i64 as u64) { pc += 9 }
+    // lbb_240:
+    /*   240 */ r1 = r7
+    /*   241 */ r2 = r8
+    /*   242 */ r0 = fn_0xd203373a(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xd203373a
+    // lbb_243:
+    /*   243 */ if r0 == (0_i32 as i64 as u64) { pc += 1 }
+    // lbb_244:
+    /*   244 */ if true { pc += 6 }
+    // lbb_245:
+    /*   245 */ *(r6 + 0x10) = r7 as u64   ///  *((r6 as i64).wrapping_add(16) as *mut u64) = r7 as u64
+    /*   246 */ *(r6 + 0x8) = r8 as u64   ///  *((r6 as i64).wrapping_add(8) as *mut u64) = r8 as u64
+    // lbb_247:
+    /*   247 */ r1 = 1_i32 as i64 as u64
+    /*   248 */ if true { pc += 5 }
+    // lbb_249:
+    /*   249 */ r7 = 0_i32 as i64 as u64
+    /*   250 */ r0 = r8
+    // lbb_251:
+    /*   251 */ *(r6 + 0x10) = r7 as u64   ///  *((r6 as i64).wrapping_add(16) as *mut u64) = r7 as u64
+    /*   252 */ *(r6 + 0x8) = r0 as u64   ///  *((r6 as i64).wrapping_add(8) as *mut u64) = r0 as u64
+    /*   253 */ r1 = 0_i32 as i64 as u64
+    // lbb_254:
+    /*   254 */ *(r6 + 0x0) = r1 as u64   ///  *((r6 as i64).wrapping_add(0) as *mut u64) = r1 as u64
+    /*   255 */ return r0
+}
@@ -0,0 +1,32 @@
+// core::fmt::Arguments::new_v1::h57b361b5ce9f01ec (pc 1233 to 1260)
+fn core::fmt::Arguments::new_v1::h57b361b5ce9f01ec(r1: u64, r2: u64, r3: u64, r4: u64, r5: u64) -> u64 {
+    /*  1233 */ r0 = r5
+    /*  1234 */ r0 += 1   ///  r0 = r0.wrapping_add(1_i32 as i64 as u64)
+    /*  1235 */ if r3 > r0 { pc += 8 }
+    // lbb_1236:
+    /*  1236 */ if r5 > r3 { pc += 7 }
+    // lbb_1237:
+    /*  1237 */ r0 = 0_i32 as i64 as u64
+    /*  1238 */ *(r1 + 0x20) = r0 as u64   ///  *((r1 as i64).wrapping_add(32) as *mut u64) = r0 as u64
+    /*  1239 */ *(r1 + 0x8) = r3 as u64   ///  *((r1 as i64).wrapping_add(8) as *mut u64) = r3 as u64
+    /*  1240 */ *(r1 + 0x0) = r2 as u64   ///  *((r1 as i64).wrapping_add(0) as *mut u64) = r2 as u64
+    /*  1241 */ *(r1 + 0x18) = r5 as u64   ///  *((r1 as i64).wrapping_add(24) as *mut u64) = r5 as u64
+    /*  1242 */ *(r1 + 0x10) = r4 as u64   ///  *((r1 as i64).wrapping_add(16) as *mut u64) = r4 as u64
+    /*  1243 */ return r0
+    // lbb_1244:
+    /*  1244 */ r1 = 1_i32 as i64 as u64
+    /*  1245 */ *(r10 - 0x28) = r1 as u64   ///  *((r10 as i64).wrapping_add(-40) as *mut u64) = r1 as u64
+    /*  1246 */ r1 load str located at 4294985464
+    /*  1248 */ *(r10 - 0x30) = r1 as u64   ///  *((r10 as i64).wrapping_add(-48) as *mut u64) = r1 as u64
+    /*  1249 */ r1 = 0_i32 as i64 as u64
+    /*  1250 */ *(r10 - 0x10) = r1 as u64   ///  *((r10 as i64).wrapping_add(-16) as *mut u64) = r1 as u64
+    /*  1251 */ *(r10 - 0x18) = r1 as u64   ///  *((r10 as i64).wrapping_add(-24) as *mut u64) = r1 as u64
+    /*  1252 */ r1 load str located at 4294984920
+    /*  1254 */ *(r10 - 0x20) = r1 as u64   ///  *((r10 as i64).wrapping_add(-32) as *mut u64) = r1 as u64
+    /*  1255 */ r1 = r10
+    /*  1256 */ r1 += -48   ///  r1 = r1.wrapping_add(-48_i32 as i64 as u64)
+    /*  1257 */ r2 load str located at 4294985624
+    /*  1259 */ r0 = fn_0x18045f7a(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x18045f7a
+    // lbb_1260:
+    /*  1260 */ abort()
+}
@@ -0,0 +1,33 @@
+// core::fmt::Formatter::pad_integral::write_prefix::h79d2ae4c7ea4e0dc (pc 1598 to 1621)
+fn core::fmt::Formatter::pad_integral::write_prefix::h79d2ae4c7ea4e0dc(r1: u64, r2: u64, r3: u64, r4: u64, r5: u64) -> u64 {
+    /*  1598 */ r6 = r5
+    /*  1599 */ r7 = r4
+    /*  1600 */ r9 = r2
+    /*  1601 */ r8 = r1
+    /*  1602 */ r1 = r3
+    /*  1603 */ r1 <<= 32   ///  r1 = r1.wrapping_shl(32)
+    /*  1604 */ r1 >>= 32   ///  r1 = r1.wrapping_shr(32)
+    /*  1605 */ if r1 == (1114112_i32 as i64 as u64) { pc += 7 }
+    // lbb_1606:
+    /*  1606 */ r4 = *(r9 + 0x20) as u64   ///  r4 = *((r9 as i64).wrapping_add(32) as *const u64) as u64
+    /*  1607 */ r1 = r8
+    /*  1608 */ r2 = r3
+    /*  1609 */ r0 = (r4 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r4 - text_vaddr) / 8
+    // lbb_1610:
+    /*  1610 */ r1 = r0
+    /*  1611 */ r0 = 1_i32 as i64 as u64
+    /*  1612 */ if r1 != (0_i32 as i64 as u64) { pc += 2 }
+    // lbb_1613:
+    /*  1613 */ r0 = 0_i32 as i64 as u64
+    /*  1614 */ if r7 != (0_i32 as i64 as u64) { pc += 1 }
+    // lbb_1615:
+    /*  1615 */ return r0
+    // lbb_1616:
+    /*  1616 */ r4 = *(r9 + 0x18) as u64   ///  r4 = *((r9 as i64).wrapping_add(24) as *const u64) as u64
+    /*  1617 */ r1 = r8
+    /*  1618 */ r2 = r7
+    /*  1619 */ r3 = r6
+    /*  1620 */ r0 = (r4 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r4 - text_vaddr) / 8
+    // lbb_1621:
+    /*  1621 */ if true { pc += -7 }
+}
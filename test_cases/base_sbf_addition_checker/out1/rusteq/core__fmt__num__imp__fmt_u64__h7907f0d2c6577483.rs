@@ -0,0 +1,97 @@
+// core::fmt::num::imp::fmt_u64::h7907f0d2c6577483 (pc 1990 to 2077)
+fn core::fmt::num::imp::fmt_u64::h7907f0d2c6577483(r1: u64, r2: u64, r3: u64) -> u64 {
+    /*  1990 */ r4 = 39_i32 as i64 as u64
+    /*  1991 */ r5 = 10000_i32 as i64 as u64
+    /*  1992 */ if r5 > r1 { pc += 32 }
+    // lbb_1993:
+    /*  1993 */ r4 = 0_i32 as i64 as u64
+    // lbb_1994:
+    /*  1994 */ r5 = r1
+    /*  1995 */ r1 /= 10000   ///  r1 = r1 / (10000 as u64)
+    /*  1996 */ r6 = r1
+    /*  1997 */ r6 *= 10000   ///  r6 = r6.wrapping_mul(10000 as u64)
+    /*  1998 */ r0 = r5
+    /*  1999 */ r0 -= r6   ///  r0 = r0.wrapping_sub(r6)
+    /*  2000 */ r6 = r0
+    /*  2001 */ r6 &= 65535   ///  r6 = r6 & (65535_i32 as i64 as u64)
+    /*  2002 */ r6 /= 100   ///  r6 = r6 / (100 as u64)
+    /*  2003 */ r7 = r6
+    /*  2004 */ r7 *= 100   ///  r7 = r7.wrapping_mul(100 as u64)
+    /*  2005 */ r0 -= r7   ///  r0 = r0.wrapping_sub(r7)
+    /*  2006 */ r7 = r10
+    /*  2007 */ r7 += -39   ///  r7 = r7.wrapping_add(-39_i32 as i64 as u64)
+    /*  2008 */ r7 += r4   ///  r7 = r7.wrapping_add(r4)
+    /*  2009 */ r6 <<= 1   ///  r6 = r6.wrapping_shl(1)
+    /*  2010 */ r8 load str located at 4294985026
+    /*  2012 */ r8 += r6   ///  r8 = r8.wrapping_add(r6)
+    /*  2013 */ r6 = *(r8 + 0x0) as u16   ///  r6 = *((r8 as i64).wrapping_add(0) as *const u16) as u64
+    /*  2014 */ *(r7 + 0x23) = r6 as u16   ///  *((r7 as i64).wrapping_add(35) as *mut u16) = r6 as u16
+    /*  2015 */ r0 <<= 1   ///  r0 = r0.wrapping_shl(1)
+    /*  2016 */ r0 &= 65534   ///  r0 = r0 & (65534_i32 as i64 as u64)
+    /*  2017 */ r6 load str located at 4294985026
+    /*  2019 */ r6 += r0   ///  r6 = r6.wrapping_add(r0)
+    /*  2020 */ r0 = *(r6 + 0x0) as u16   ///  r0 = *((r6 as i64).wrapping_add(0) as *const u16) as u64
+    /*  2021 */ *(r7 + 0x25) = r0 as u16   ///  *((r7 as i64).wrapping_add(37) as *mut u16) = r0 as u16
+    /*  2022 */ r4 += -4   ///  r4 = r4.wrapping_add(-4_i32 as i64 as u64)
+    /*  2023 */ if r5 > (99999999_i32 as i64 as u64) { pc += -30 }
+    // lbb_2024:
+    /*  2024 */ r4 += 39   ///  r4 = r4.wrapping_add(39_i32 as i64 as u64)
+    // lbb_2025:
+    /*  2025 */ if r1 > (99_i32 as i64 as u64) { pc += 1 }
+    // lbb_2026:
+    /*  2026 */ if true { pc += 18 }
+    // lbb_2027:
+    /*  2027 */ r5 = r1
+    /*  2028 */ r5 &= 65535   ///  r5 = r5 & (65535_i32 as i64 as u64)
+    /*  2029 */ r5 /= 100   ///  r5 = r5 / (100 as u64)
+    /*  2030 */ r0 = r5
+    /*  2031 */ r0 *= 100   ///  r0 = r0.wrapping_mul(100 as u64)
+    /*  2032 */ r1 -= r0   ///  r1 = r1.wrapping_sub(r0)
+    /*  2033 */ r1 <<= 1   ///  r1 = r1.wrapping_shl(1)
+    /*  2034 */ r1 &= 65534   ///  r1 = r1 & (65534_i32 as i64 as u64)
+    /*  2035 */ r0 load str located at 4294985026
+    /*  2037 */ r0 += r1   ///  r0 = r0.wrapping_add(r1)
+    /*  2038 */ r4 += -2   ///  r4 = r4.wrapping_add(-2_i32 as i64 as u64)
+    /*  2039 */ r1 = r10
+    /*  2040 */ r1 += -39   ///  r1 = r1.wrapping_add(-39_i32 as i64 as u64)
+    /*  2041 */ r1 += r4   ///  r1 = r1.wrapping_add(r4)
+    /*  2042 */ r0 = *(r0 + 0x0) as u16   ///  r0 = *((r0 as i64).wrapping_add(0) as *const u16) as u64
+    /*  2043 */ *(r1 + 0x0) = r0 as u16   ///  *((r1 as i64).wrapping_add(0) as *mut u16) = r0 as u16
+    /*  2044 */ r1 = r5
+    // lbb_2045:
+    /*  2045 */ r5 = 10_i32 as i64 as u64
+    /*  2046 */ if r5 > r1 { pc += 11 }
+    // lbb_2047:
+    /*  2047 */ r1 <<= 1   ///  r1 = r1.wrapping_shl(1)
+    /*  2048 */ r5 load str located at 4294985026
+    /*  2050 */ r5 += r1   ///  r5 = r5.wrapping_add(r1)
+    /*  2051 */ r4 += -2   ///  r4 = r4.wrapping_add(-2_i32 as i64 as u64)
+    /*  2052 */ r1 = r10
+    /*  2053 */ r1 += -39   ///  r1 = r1.wrapping_add(-39_i32 as i64 as u64)
+    /*  2054 */ r1 += r4   ///  r1 = r1.wrapping_add(r4)
+    /*  2055 */ r5 = *(r5 + 0x0) as u16   ///  r5 = *((r5 as i64).wrapping_add(0) as *const u16) as u64
+    /*  2056 */ *(r1 + 0x0) = r5 as u16   ///  *((r1 as i64).wrapping_add(0) as *mut u16) = r5 as u16
+    /*  2057 */ if true { pc += 6 }
+    // lbb_2058:
+    /*  2058 */ r4 += -1   ///  r4 = r4.wrapping_add(-1_i32 as i64 as u64)
+    /*  2059 */ r5 = r10
+    /*  2060 */ r5 += -39   ///  r5 = r5.wrapping_add(-39_i32 as i64 as u64)
+    /*  2061 */ r5 += r4   ///  r5 = r5.wrapping_add(r4)
+    /*  2062 */ r1 += 48   ///  r1 = r1.wrapping_add(48_i32 as i64 as u64)
+    /*  2063 */ *(r5 + 0x0) = r1 as u8   ///  *((r5 as i64).wrapping_add(0) as *mut u8) = r1 as u8
+    // lbb_2064:
+    /*  2064 */ r1 = r10
+    /*  2065 */ r1 += -39   ///  r1 = r1.wrapping_add(-39_i32 as i64 as u64)
+    /*  2066 */ r1 += r4   ///  r1 = r1.wrapping_add(r4)
+    /*  2067 */ *(r10 - 0x1000) = r1 as u64   ///  *((r10 as i64).wrapping_add(-4096) as *mut u64) = r1 as u64
+    /*  2068 */ r1 = 39_i32 as i64 as u64
+    /*  2069 */ r1 -= r4   ///  r1 = r1.wrapping_sub(r4)
+    /*  2070 */ *(r10 - 0xff8) = r1 as u64   ///  *((r10 as i64).wrapping_add(-4088) as *mut u64) = r1 as u64
+    /*  2071 */ r5 = r10
+    /*  2072 */ r1 = r3
+    /*  2073 */ r3 load str located at 4294984920
+    /*  2075 */ r4 = 0_i32 as i64 as u64
+    /*  2076 */ r0 = fn_0x1f949086(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x1f949086
+    // lbb_2077:
+    /*  2077 */ return r0
+}
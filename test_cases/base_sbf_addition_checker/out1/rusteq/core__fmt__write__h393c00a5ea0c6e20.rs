@@ -0,0 +1,177 @@
+// core::fmt::write::h393c00a5ea0c6e20 (pc 1261 to 1400)
+fn core::fmt::write::h393c00a5ea0c6e20(r1: u64, r2: u64, r3: u64) -> u64 {
+    /*  1261 */ r4 = 3_i32 as i64 as u64
+    /*  1262 */ *(r10 - 0x8) = r4 as u8   ///  *((r10 as i64).wrapping_add(-8) as *mut u8) = r4 as u8
+    /*  1263 */ r4 = 32_i32 as i64 as u64
+    /*  1264 */ *(r10 - 0x10) = r4 as u64   ///  *((r10 as i64).wrapping_add(-16) as *mut u64) = r4 as u64
+    /*  1265 */ *(r10 - 0x18) = r2 as u64   ///  *((r10 as i64).wrapping_add(-24) as *mut u64) = r2 as u64
+    /*  1266 */ *(r10 - 0x20) = r1 as u64   ///  *((r10 as i64).wrapping_add(-32) as *mut u64) = r1 as u64
+    /*  1267 */ r7 = 0_i32 as i64 as u64
+    /*  1268 */ *(r10 - 0x30) = r7 as u64   ///  *((r10 as i64).wrapping_add(-48) as *mut u64) = r7 as u64
+    /*  1269 */ *(r10 - 0x40) = r7 as u64   ///  *((r10 as i64).wrapping_add(-64) as *mut u64) = r7 as u64
+    /*  1270 */ r8 = *(r3 + 0x20) as u64   ///  r8 = *((r3 as i64).wrapping_add(32) as *const u64) as u64
+    /*  1271 */ *(r10 - 0x50) = r3 as u64   ///  *((r10 as i64).wrapping_add(-80) as *mut u64) = r3 as u64
+    /*  1272 */ if r8 != (0_i32 as i64 as u64) { pc += 29 }
+    // lbb_1273:
+    /*  1273 */ r1 = *(r3 + 0x18) as u64   ///  r1 = *((r3 as i64).wrapping_add(24) as *const u64) as u64
+    /*  1274 */ if r1 == (0_i32 as i64 as u64) { pc += 107 }
+    // lbb_1275:
+    /*  1275 */ r2 = *(r10 - 0x50) as u64   ///  r2 = *((r10 as i64).wrapping_add(-80) as *const u64) as u64
+    /*  1276 */ r6 = *(r2 + 0x10) as u64   ///  r6 = *((r2 as i64).wrapping_add(16) as *const u64) as u64
+    /*  1277 */ r1 <<= 4   ///  r1 = r1.wrapping_shl(4)
+    /*  1278 */ r8 = r6
+    /*  1279 */ r8 += r1   ///  r8 = r8.wrapping_add(r1)
+    /*  1280 */ r9 = *(r2 + 0x0) as u64   ///  r9 = *((r2 as i64).wrapping_add(0) as *const u64) as u64
+    /*  1281 */ r9 += 8   ///  r9 = r9.wrapping_add(8_i32 as i64 as u64)
+    /*  1282 */ if true { pc += 16 }
+    // lbb_1283:
+    /*  1283 */ r1 = *(r10 - 0x18) as u64   ///  r1 = *((r10 as i64).wrapping_add(-24) as *const u64) as u64
+    /*  1284 */ r4 = *(r1 + 0x18) as u64   ///  r4 = *((r1 as i64).wrapping_add(24) as *const u64) as u64
+    /*  1285 */ r2 = *(r9 - 0x8) as u64   ///  r2 = *((r9 as i64).wrapping_add(-8) as *const u64) as u64
+    /*  1286 */ r1 = *(r10 - 0x20) as u64   ///  r1 = *((r10 as i64).wrapping_add(-32) as *const u64) as u64
+    /*  1287 */ r0 = (r4 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r4 - text_vaddr) / 8
+    // lbb_1288:
+    /*  1288 */ if r0 != (0_i32 as i64 as u64) { pc += 110 }
+    // lbb_1289:
+    /*  1289 */ r3 = *(r6 + 0x8) as u64   ///  r3 = *((r6 as i64).wrapping_add(8) as *const u64) as u64
+    /*  1290 */ r1 = *(r6 + 0x0) as u64   ///  r1 = *((r6 as i64).wrapping_add(0) as *const u64) as u64
+    /*  1291 */ r2 = r10
+    /*  1292 */ r2 += -64   ///  r2 = r2.wrapping_add(-64_i32 as i64 as u64)
+    /*  1293 */ r0 = (r3 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r3 - text_vaddr) / 8
+    // lbb_1294:
+    /*  1294 */ if r0 != (0_i32 as i64 as u64) { pc += 104 }
+    // lbb_1295:
+    /*  1295 */ r7 += 1   ///  r7 = r7.wrapping_add(1_i32 as i64 as u64)
+    /*  1296 */ r9 += 16   ///  r9 = r9.wrapping_add(16_i32 as i64 as u64)
+    /*  1297 */ r6 += 16   ///  r6 = r6.wrapping_add(16_i32 as i64 as u64)
+    /*  1298 */ if r6 == r8 { pc += 83 }
+    // lbb_1299:
+    /*  1299 */ r3 = *(r9 + 0x0) as u64   ///  r3 = *((r9 as i64).wrapping_add(0) as *const u64) as u64
+    /*  1300 */ if r3 == (0_i32 as i64 as u64) { pc += -12 }
+    // lbb_1301:
+    /*  1301 */ if true { pc += -19 }
+    // lbb_1302:
+    /*  1302 */ r9 = *(r3 + 0x28) as u64   ///  r9 = *((r3 as i64).wrapping_add(40) as *const u64) as u64
+    /*  1303 */ if r9 == (0_i32 as i64 as u64) { pc += 78 }
+    // lbb_1304:
+    /*  1304 */ r8 += 24   ///  r8 = r8.wrapping_add(24_i32 as i64 as u64)
+    /*  1305 */ r9 *= 56   ///  r9 = r9.wrapping_mul(56 as u64)
+    /*  1306 */ r1 = *(r10 - 0x50) as u64   ///  r1 = *((r10 as i64).wrapping_add(-80) as *const u64) as u64
+    /*  1307 */ r2 = *(r1 + 0x10) as u64   ///  r2 = *((r1 as i64).wrapping_add(16) as *const u64) as u64
+    /*  1308 */ *(r10 - 0x48) = r2 as u64   ///  *((r10 as i64).wrapping_add(-72) as *mut u64) = r2 as u64
+    /*  1309 */ r6 = *(r1 + 0x0) as u64   ///  r6 = *((r1 as i64).wrapping_add(0) as *const u64) as u64
+    /*  1310 */ r6 += 8   ///  r6 = r6.wrapping_add(8_i32 as i64 as u64)
+    /*  1311 */ if true { pc += 17 }
+    // lbb_1312:
+    /*  1312 */ *(r10 - 0x28) = r1 as u64   ///  *((r10 as i64).wrapping_add(-40) as *mut u64) = r1 as u64
+    /*  1313 */ *(r10 - 0x30) = r2 as u64   ///  *((r10 as i64).wrapping_add(-48) as *mut u64) = r2 as u64
+    /*  1314 */ r1 = *(r8 + 0x8) as u64   ///  r1 = *((r8 as i64).wrapping_add(8) as *const u64) as u64
+    /*  1315 */ r1 <<= 4   ///  r1 = r1.wrapping_shl(4)
+    /*  1316 */ r2 = *(r10 - 0x48) as u64   ///  r2 = *((r10 as i64).wrapping_add(-72) as *const u64) as u64
+    /*  1317 */ r2 += r1   ///  r2 = r2.wrapping_add(r1)
+    /*  1318 */ r3 = *(r2 + 0x8) as u64   ///  r3 = *((r2 as i64).wrapping_add(8) as *const u64) as u64
+    /*  1319 */ r1 = *(r2 + 0x0) as u64   ///  r1 = *((r2 as i64).wrapping_add(0) as *const u64) as u64
+    /*  1320 */ r2 = r10
+    /*  1321 */ r2 += -64   ///  r2 = r2.wrapping_add(-64_i32 as i64 as u64)
+    /*  1322 */ r0 = (r3 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r3 - text_vaddr) / 8
+    // lbb_1323:
+    /*  1323 */ if r0 != (0_i32 as i64 as u64) { pc += 75 }
+    // lbb_1324:
+    /*  1324 */ r7 += 1   ///  r7 = r7.wrapping_add(1_i32 as i64 as u64)
+    /*  1325 */ r8 += 56   ///  r8 = r8.wrapping_add(56_i32 as i64 as u64)
+    /*  1326 */ r6 += 16   ///  r6 = r6.wrapping_add(16_i32 as i64 as u64)
+    /*  1327 */ r9 += -56   ///  r9 = r9.wrapping_add(-56_i32 as i64 as u64)
+    /*  1328 */ if r9 == (0_i32 as i64 as u64) { pc += 53 }
+    // lbb_1329:
+    /*  1329 */ r3 = *(r6 + 0x0) as u64   ///  r3 = *((r6 as i64).wrapping_add(0) as *const u64) as u64
+    /*  1330 */ if r3 == (0_i32 as i64 as u64) { pc += 6 }
+    // lbb_1331:
+    /*  1331 */ r1 = *(r10 - 0x18) as u64   ///  r1 = *((r10 as i64).wrapping_add(-24) as *const u64) as u64
+    /*  1332 */ r4 = *(r1 + 0x18) as u64   ///  r4 = *((r1 as i64).wrapping_add(24) as *const u64) as u64
+    /*  1333 */ r2 = *(r6 - 0x8) as u64   ///  r2 = *((r6 as i64).wrapping_add(-8) as *const u64) as u64
+    /*  1334 */ r1 = *(r10 - 0x20) as u64   ///  r1 = *((r10 as i64).wrapping_add(-32) as *const u64) as u64
+    /*  1335 */ r0 = (r4 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r4 - text_vaddr) / 8
+    // lbb_1336:
+    /*  1336 */ if r0 != (0_i32 as i64 as u64) { pc += 62 }
+    // lbb_1337:
+    /*  1337 */ r1 = *(r8 + 0x10) as u32   ///  r1 = *((r8 as i64).wrapping_add(16) as *const u32) as u64
+    /*  1338 */ *(r10 - 0x10) = r1 as u32   ///  *((r10 as i64).wrapping_add(-16) as *mut u32) = r1 as u32
+    /*  1339 */ r1 = *(r8 + 0x18) as u8   ///  r1 = *((r8 as i64).wrapping_add(24) as *const u8) as u64
+    /*  1340 */ *(r10 - 0x8) = r1 as u8   ///  *((r10 as i64).wrapping_add(-8) as *mut u8) = r1 as u8
+    /*  1341 */ r1 = *(r8 + 0x14) as u32   ///  r1 = *((r8 as i64).wrapping_add(20) as *const u32) as u64
+    /*  1342 */ *(r10 - 0xc) = r1 as u32   ///  *((r10 as i64).wrapping_add(-12) as *mut u32) = r1 as u32
+    /*  1343 */ r1 = *(r8 + 0x0) as u64   ///  r1 = *((r8 as i64).wrapping_add(0) as *const u64) as u64
+    /*  1344 */ r3 = *(r8 - 0x8) as u64   ///  r3 = *((r8 as i64).wrapping_add(-8) as *const u64) as u64
+    /*  1345 */ if r3 == (0_i32 as i64 as u64) { pc += 3 }
+    // lbb_1346:
+    /*  1346 */ r2 = 0_i32 as i64 as u64
+    /*  1347 */ if r3 == (1_i32 as i64 as u64) { pc += 3 }
+    // lbb_1348:
+    /*  1348 */ if true { pc += 12 }
+    // lbb_1349:
+    /*  1349 */ r2 = 1_i32 as i64 as u64
+    /*  1350 */ if true { pc += 10 }
+    // lbb_1351:
+    /*  1351 */ r1 <<= 4   ///  r1 = r1.wrapping_shl(4)
+    /*  1352 */ r3 = *(r10 - 0x48) as u64   ///  r3 = *((r10 as i64).wrapping_add(-72) as *const u64) as u64
+    /*  1353 */ r3 += r1   ///  r3 = r3.wrapping_add(r1)
+    /*  1354 */ r4 = *(r3 + 0x8) as u64   ///  r4 = *((r3 as i64).wrapping_add(8) as *const u64) as u64
+    /*  1355 */ r5 load str located at 4294975992
+    /*  1357 */ if r4 != r5 { pc += 3 }
+    // lbb_1358:
+    /*  1358 */ r2 = 1_i32 as i64 as u64
+    /*  1359 */ r1 = *(r3 + 0x0) as u64   ///  r1 = *((r3 as i64).wrapping_add(0) as *const u64) as u64
+    /*  1360 */ r1 = *(r1 + 0x0) as u64   ///  r1 = *((r1 as i64).wrapping_add(0) as *const u64) as u64
+    // lbb_1361:
+    /*  1361 */ *(r10 - 0x38) = r1 as u64   ///  *((r10 as i64).wrapping_add(-56) as *mut u64) = r1 as u64
+    /*  1362 */ *(r10 - 0x40) = r2 as u64   ///  *((r10 as i64).wrapping_add(-64) as *mut u64) = r2 as u64
+    /*  1363 */ r1 = *(r8 - 0x10) as u64   ///  r1 = *((r8 as i64).wrapping_add(-16) as *const u64) as u64
+    /*  1364 */ r3 = *(r8 - 0x18) as u64   ///  r3 = *((r8 as i64).wrapping_add(-24) as *const u64) as u64
+    /*  1365 */ if r3 == (0_i32 as i64 as u64) { pc += 3 }
+    // lbb_1366:
+    /*  1366 */ r2 = 0_i32 as i64 as u64
+    /*  1367 */ if r3 == (1_i32 as i64 as u64) { pc += 3 }
+    // lbb_1368:
+    /*  1368 */ if true { pc += -57 }
+    // lbb_1369:
+    /*  1369 */ r2 = 1_i32 as i64 as u64
+    /*  1370 */ if true { pc += -59 }
+    // lbb_1371:
+    /*  1371 */ r1 <<= 4   ///  r1 = r1.wrapping_shl(4)
+    /*  1372 */ r3 = *(r10 - 0x48) as u64   ///  r3 = *((r10 as i64).wrapping_add(-72) as *const u64) as u64
+    /*  1373 */ r3 += r1   ///  r3 = r3.wrapping_add(r1)
+    /*  1374 */ r4 = *(r3 + 0x8) as u64   ///  r4 = *((r3 as i64).wrapping_add(8) as *const u64) as u64
+    /*  1375 */ r5 load str located at 4294975992
+    /*  1377 */ if r4 != r5 { pc += -66 }
+    // lbb_1378:
+    /*  1378 */ r2 = 1_i32 as i64 as u64
+    /*  1379 */ r1 = *(r3 + 0x0) as u64   ///  r1 = *((r3 as i64).wrapping_add(0) as *const u64) as u64
+    /*  1380 */ r1 = *(r1 + 0x0) as u64   ///  r1 = *((r1 as i64).wrapping_add(0) as *const u64) as u64
+    /*  1381 */ if true { pc += -70 }
+    // lbb_1382:
+    /*  1382 */ r1 = *(r10 - 0x50) as u64   ///  r1 = *((r10 as i64).wrapping_add(-80) as *const u64) as u64
+    /*  1383 */ r1 = *(r1 + 0x8) as u64   ///  r1 = *((r1 as i64).wrapping_add(8) as *const u64) as u64
+    /*  1384 */ if r1 > r7 { pc += 1 }
+    // lbb_1385:
+    /*  1385 */ if true { pc += 11 }
+    // lbb_1386:
+    /*  1386 */ r7 <<= 4   ///  r7 = r7.wrapping_shl(4)
+    /*  1387 */ r1 = *(r10 - 0x50) as u64   ///  r1 = *((r10 as i64).wrapping_add(-80) as *const u64) as u64
+    /*  1388 */ r1 = *(r1 + 0x0) as u64   ///  r1 = *((r1 as i64).wrapping_add(0) as *const u64) as u64
+    /*  1389 */ r1 += r7   ///  r1 = r1.wrapping_add(r7)
+    /*  1390 */ r3 = *(r1 + 0x8) as u64   ///  r3 = *((r1 as i64).wrapping_add(8) as *const u64) as u64
+    /*  1391 */ r2 = *(r1 + 0x0) as u64   ///  r2 = *((r1 as i64).wrapping_add(0) as *const u64) as u64
+    /*  1392 */ r1 = *(r10 - 0x18) as u64   ///  r1 = *((r10 as i64).wrapping_add(-24) as *const u64) as u64
+    /*  1393 */ r4 = *(r1 + 0x18) as u64   ///  r4 = *((r1 as i64).wrapping_add(24) as *const u64) as u64
+    /*  1394 */ r1 = *(r10 - 0x20) as u64   ///  r1 = *((r10 as i64).wrapping_add(-32) as *const u64) as u64
+    /*  1395 */ r0 = (r4 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r4 - text_vaddr) / 8
+    // lbb_1396:
+    /*  1396 */ if r0 != (0_i32 as i64 as u64) { pc += 2 }
+    // lbb_1397:
+    /*  1397 */ r0 = 0_i32 as i64 as u64
+    /*  1398 */ if true { pc += 1 }
+    // lbb_1399:
+    /*  1399 */ r0 = 1_i32 as i64 as u64
+    // lbb_1400:
+    /*  1400 */ return r0
+}
@@ -0,0 +1,30 @@
+// core::panicking::panic_bounds_check::h03ab7943241b3198 (pc 1175 to 1201)
+fn core::panicking::panic_bounds_check::h03ab7943241b3198(r1: u64, r2: u64, r3: u64) -> u64 {
+    /*  1175 */ r6 = r3
+    /*  1176 */ *(r10 - 0x58) = r2 as u64   ///  *((r10 as i64).wrapping_add(-88) as *mut u64) = r2 as u64
+    /*  1177 */ *(r10 - 0x60) = r1 as u64   ///  *((r10 as i64).wrapping_add(-96) as *mut u64) = r1 as u64
+    /*  1178 */ r1 = r10
+    /*  1179 */ r1 += -96   ///  r1 = r1.wrapping_add(-96_i32 as i64 as u64)
+    /*  1180 */ *(r10 - 0x10) = r1 as u64   ///  *((r10 as i64).wrapping_add(-16) as *mut u64) = r1 as u64
+    /*  1181 */ r1 load str located at 4294984248
+    /*  1183 */ *(r10 - 0x8) = r1 as u64   ///  *((r10 as i64).wrapping_add(-8) as *mut u64) = r1 as u64
+    /*  1184 */ *(r10 - 0x18) = r1 as u64   ///  *((r10 as i64).wrapping_add(-24) as *mut u64) = r1 as u64
+    /*  1185 */ r1 = r10
+    /*  1186 */ r1 += -88   ///  r1 = r1.wrapping_add(-88_i32 as i64 as u64)
+    /*  1187 */ *(r10 - 0x20) = r1 as u64   ///  *((r10 as i64).wrapping_add(-32) as *mut u64) = r1 as u64
+    /*  1188 */ r7 = r10
+    /*  1189 */ r7 += -80   ///  r7 = r7.wrapping_add(-80_i32 as i64 as u64)
+    /*  1190 */ r4 = r10
+    /*  1191 */ r4 += -32   ///  r4 = r4.wrapping_add(-32_i32 as i64 as u64)
+    /*  1192 */ r1 = r7
+    /*  1193 */ r2 load str located at 4294985560
+    /*  1195 */ r3 = 2_i32 as i64 as u64
+    /*  1196 */ r5 = 2_i32 as i64 as u64
+    /*  1197 */ r0 = fn_0xd0585269(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xd0585269
+    // lbb_1198:
+    /*  1198 */ r1 = r7
+    /*  1199 */ r2 = r6
+    /*  1200 */ r0 = fn_0x18045f7a(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x18045f7a
+    // lbb_1201:
+    /*  1201 */ abort()
+}
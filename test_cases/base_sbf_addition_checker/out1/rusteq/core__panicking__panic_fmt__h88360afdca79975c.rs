@@ -0,0 +1,16 @@
+// core::panicking::panic_fmt::h88360afdca79975c (pc 1161 to 1174)
+fn core::panicking::panic_fmt::h88360afdca79975c(r1: u64, r2: u64) -> u64 {
+    /*  1161 */ r3 = 1_i32 as i64 as u64
+    /*  1162 */ *(r10 - 0x8) = r3 as u16   ///  *((r10 as i64).wrapping_add(-8) as *mut u16) = r3 as u16
+    /*  1163 */ *(r10 - 0x10) = r2 as u64   ///  *((r10 as i64).wrapping_add(-16) as *mut u64) = r2 as u64
+    /*  1164 */ *(r10 - 0x18) = r1 as u64   ///  *((r10 as i64).wrapping_add(-24) as *mut u64) = r1 as u64
+    /*  1165 */ r1 load str located at 4294985528
+    /*  1167 */ *(r10 - 0x20) = r1 as u64   ///  *((r10 as i64).wrapping_add(-32) as *mut u64) = r1 as u64
+    /*  1168 */ r1 load str located at 4294984920
+    /*  1170 */ *(r10 - 0x28) = r1 as u64   ///  *((r10 as i64).wrapping_add(-40) as *mut u64) = r1 as u64
+    /*  1171 */ r1 = r10
+    /*  1172 */ r1 += -40   ///  r1 = r1.wrapping_add(-40_i32 as i64 as u64)
+    /*  1173 */ r0 = fn_0x96f9adb8(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x96f9adb8
+    // lbb_1174:
+    /*  1174 */ abort()
+}
@@ -0,0 +1,29 @@
+// custom_panic (pc 186 to 212)
+fn custom_panic(r1: u64) -> u64 {
+    /*   186 */ *(r10 - 0x90) = r1 as u64   ///  *((r10 as i64).wrapping_add(-144) as *mut u64) = r1 as u64
+    /*   187 */ r1 = 0_i32 as i64 as u64
+    /*   188 */ *(r10 - 0x50) = r1 as u64   ///  *((r10 as i64).wrapping_add(-80) as *mut u64) = r1 as u64
+    /*   189 */ r1 load str located at 4294985280
+    /*   191 */ *(r10 - 0x70) = r1 as u64   ///  *((r10 as i64).wrapping_add(-112) as *mut u64) = r1 as u64
+    /*   192 */ r1 = 1_i32 as i64 as u64
+    /*   193 */ *(r10 - 0x68) = r1 as u64   ///  *((r10 as i64).wrapping_add(-104) as *mut u64) = r1 as u64
+    /*   194 */ *(r10 - 0x58) = r1 as u64   ///  *((r10 as i64).wrapping_add(-88) as *mut u64) = r1 as u64
+    /*   195 */ r1 = r10
+    /*   196 */ r1 += -64   ///  r1 = r1.wrapping_add(-64_i32 as i64 as u64)
+    /*   197 */ *(r10 - 0x60) = r1 as u64   ///  *((r10 as i64).wrapping_add(-96) as *mut u64) = r1 as u64
+    /*   198 */ r1 load str located at 4294967584
+    /*   200 */ *(r10 - 0x38) = r1 as u64   ///  *((r10 as i64).wrapping_add(-56) as *mut u64) = r1 as u64
+    /*   201 */ r1 = r10
+    /*   202 */ r1 += -144   ///  r1 = r1.wrapping_add(-144_i32 as i64 as u64)
+    /*   203 */ *(r10 - 0x40) = r1 as u64   ///  *((r10 as i64).wrapping_add(-64) as *mut u64) = r1 as u64
+    /*   204 */ r1 = r10
+    /*   205 */ r1 += -136   ///  r1 = r1.wrapping_add(-136_i32 as i64 as u64)
+    /*   206 */ r2 = r10
+    /*   207 */ r2 += -112   ///  r2 = r2.wrapping_add(-112_i32 as i64 as u64)
+    /*   208 */ r0 = fn_0x7c7ccf5f(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x7c7ccf5f
+    // lbb_209:
+    /*   209 */ r1 = *(r10 - 0x88) as u64   ///  r1 = *((r10 as i64).wrapping_add(-136) as *const u64) as u64
+    /*   210 */ r2 = *(r10 - 0x78) as u64   ///  r2 = *((r10 as i64).wrapping_add(-120) as *const u64) as u64
+    /*   211 */ r0 = sol_log_(r1, r2)
+    /*   212 */ return r0
+}
@@ -0,0 +1,136 @@
+// entrypoint (pc 3 to 125)
+fn entrypoint(r1: u64) -> u64 {
+    /*     3 */ r2 = r1
+    /*     4 */ r1 = r10
+    /*     5 */ r1 += -96   ///  r1 = r1.wrapping_add(-96_i32 as i64 as u64)
+    /*     6 */ r0 = fn_0xbc1c3f2c(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xbc1c3f2c
+    // lbb_7:
+    /*     7 */ r7 = *(r10 - 0x48) as u64   ///  r7 = *((r10 as i64).wrapping_add(-72) as *const u64) as u64
+    /*     8 */ r8 = *(r10 - 0x58) as u64   ///  r8 = *((r10 as i64).wrapping_add(-88) as *const u64) as u64
+    /*     9 */ r1 = *(r10 - 0x38) as u64   ///  r1 = *((r10 as i64).wrapping_add(-56) as *const u64) as u64
+    /*    10 */ r2 = 8_i32 as i64 as u64
+    /*    11 */ if r2 > r1 { pc += 79 }
+    // lbb_12:
+    /*    12 */ r1 = *(r10 - 0x40) as u64   ///  r1 = *((r10 as i64).wrapping_add(-64) as *const u64) as u64
+    /*    13 */ r2 = *(r1 + 0x0) as u32   ///  r2 = *((r1 as i64).wrapping_add(0) as *const u32) as u64
+    /*    14 */ *(r10 - 0xa8) = r2 as u32   ///  *((r10 as i64).wrapping_add(-168) as *mut u32) = r2 as u32
+    /*    15 */ r1 = *(r1 + 0x4) as u32   ///  r1 = *((r1 as i64).wrapping_add(4) as *const u32) as u64
+    /*    16 */ *(r10 - 0xa4) = r1 as u32   ///  *((r10 as i64).wrapping_add(-164) as *mut u32) = r1 as u32
+    /*    17 */ r1 = 0_i32 as i64 as u64
+    /*    18 */ *(r10 - 0x40) = r1 as u64   ///  *((r10 as i64).wrapping_add(-64) as *mut u64) = r1 as u64
+    /*    19 */ r1 load str located at 4294985232
+    /*    21 */ *(r10 - 0x60) = r1 as u64   ///  *((r10 as i64).wrapping_add(-96) as *mut u64) = r1 as u64
+    /*    22 */ r1 = 2_i32 as i64 as u64
+    /*    23 */ *(r10 - 0x58) = r1 as u64   ///  *((r10 as i64).wrapping_add(-88) as *mut u64) = r1 as u64
+    /*    24 */ *(r10 - 0x48) = r1 as u64   ///  *((r10 as i64).wrapping_add(-72) as *mut u64) = r1 as u64
+    /*    25 */ r1 = r10
+    /*    26 */ r1 += -136   ///  r1 = r1.wrapping_add(-136_i32 as i64 as u64)
+    /*    27 */ *(r10 - 0x50) = r1 as u64   ///  *((r10 as i64).wrapping_add(-80) as *mut u64) = r1 as u64
+    /*    28 */ r1 = r10
+    /*    29 */ r1 += -164   ///  r1 = r1.wrapping_add(-164_i32 as i64 as u64)
+    /*    30 */ *(r10 - 0x78) = r1 as u64   ///  *((r10 as i64).wrapping_add(-120) as *mut u64) = r1 as u64
+    /*    31 */ r1 load str located at 4294984208
+    /*    33 */ *(r10 - 0x70) = r1 as u64   ///  *((r10 as i64).wrapping_add(-112) as *mut u64) = r1 as u64
+    /*    34 */ *(r10 - 0x80) = r1 as u64   ///  *((r10 as i64).wrapping_add(-128) as *mut u64) = r1 as u64
+    /*    35 */ r1 = r10
+    /*    36 */ r1 += -168   ///  r1 = r1.wrapping_add(-168_i32 as i64 as u64)
+    /*    37 */ *(r10 - 0x88) = r1 as u64   ///  *((r10 as i64).wrapping_add(-136) as *mut u64) = r1 as u64
+    /*    38 */ r1 = r10
+    /*    39 */ r1 += -160   ///  r1 = r1.wrapping_add(-160_i32 as i64 as u64)
+    /*    40 */ r2 = r10
+    /*    41 */ r2 += -96   ///  r2 = r2.wrapping_add(-96_i32 as i64 as u64)
+    /*    42 */ r0 = fn_0x7c7ccf5f(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x7c7ccf5f
+    // lbb_43:
+    /*    43 */ r1 = *(r10 - 0xa0) as u64   ///  r1 = *((r10 as i64).wrapping_add(-160) as *const u64) as u64
+    /*    44 */ r2 = *(r10 - 0x90) as u64   ///  r2 = *((r10 as i64).wrapping_add(-144) as *const u64) as u64
+    /*    45 */ r0 = sol_log_(r1, r2)
+    /*    46 */ r1 = *(r10 - 0xa8) as u32   ///  r1 = *((r10 as i64).wrapping_add(-168) as *const u32) as u64
+    /*    47 */ r2 = *(r10 - 0xa4) as u32   ///  r2 = *((r10 as i64).wrapping_add(-164) as *const u32) as u64
+    /*    48 */ r2 += r1   ///  r2 = r2.wrapping_add(r1)
+    /*    49 */ r2 <<= 32   ///  r2 = r2.wrapping_shl(32)
+    /*    50 */ r2 >>= 32   ///  r2 = r2.wrapping_shr(32)
+    /*    51 */ if r2 != (1337_i32 as i64 as u64) { pc += 6 }
+    // lbb_52:
+    /*    52 */ r1 load str located at 4294984672
+    /*    54 */ r2 = 8_i32 as i64 as u64
+    /*    55 */ r0 = sol_log_(r1, r2)
+    /*    56 */ r1 = 987654321_i32 as i64 as u64
+    /*    57 */ if true { pc += 5 }
+    // lbb_58:
+    /*    58 */ r1 load str located at 4294984680
+    /*    60 */ r2 = 9_i32 as i64 as u64
+    /*    61 */ r0 = sol_log_(r1, r2)
+    /*    62 */ r1 = 123456789_i32 as i64 as u64
+    // lbb_63:
+    /*    63 */ *(r10 - 0x68) = r1 as u64   ///  *((r10 as i64).wrapping_add(-104) as *mut u64) = r1 as u64
+    /*    64 */ r1 load str located at 4294985264
+    /*    66 */ *(r10 - 0x60) = r1 as u64   ///  *((r10 as i64).wrapping_add(-96) as *mut u64) = r1 as u64
+    /*    67 */ r1 = 1_i32 as i64 as u64
+    /*    68 */ *(r10 - 0x58) = r1 as u64   ///  *((r10 as i64).wrapping_add(-88) as *mut u64) = r1 as u64
+    /*    69 */ *(r10 - 0x48) = r1 as u64   ///  *((r10 as i64).wrapping_add(-72) as *mut u64) = r1 as u64
+    /*    70 */ r1 = r10
+    /*    71 */ r1 += -160   ///  r1 = r1.wrapping_add(-160_i32 as i64 as u64)
+    /*    72 */ *(r10 - 0x50) = r1 as u64   ///  *((r10 as i64).wrapping_add(-80) as *mut u64) = r1 as u64
+    /*    73 */ r1 load str located at 4294984248
+    /*    75 */ *(r10 - 0x98) = r1 as u64   ///  *((r10 as i64).wrapping_add(-152) as *mut u64) = r1 as u64
+    /*    76 */ r1 = r10
+    /*    77 */ r1 += -104   ///  r1 = r1.wrapping_add(-104_i32 as i64 as u64)
+    /*    78 */ *(r10 - 0xa0) = r1 as u64   ///  *((r10 as i64).wrapping_add(-160) as *mut u64) = r1 as u64
+    /*    79 */ r6 = 0_i32 as i64 as u64
+    /*    80 */ *(r10 - 0x40) = r6 as u64   ///  *((r10 as i64).wrapping_add(-64) as *mut u64) = r6 as u64
+    /*    81 */ r1 = r10
+    /*    82 */ r1 += -136   ///  r1 = r1.wrapping_add(-136_i32 as i64 as u64)
+    /*    83 */ r2 = r10
+    /*    84 */ r2 += -96   ///  r2 = r2.wrapping_add(-96_i32 as i64 as u64)
+    /*    85 */ r0 = fn_0x7c7ccf5f(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x7c7ccf5f
+    // lbb_86:
+    /*    86 */ r1 = *(r10 - 0x88) as u64   ///  r1 = *((r10 as i64).wrapping_add(-136) as *const u64) as u64
+    /*    87 */ r2 = *(r10 - 0x78) as u64   ///  r2 = *((r10 as i64).wrapping_add(-120) as *const u64) as u64
+    /*    88 */ r0 = sol_log_(r1, r2)
+    /*    89 */ if r7 == (0_i32 as i64 as u64) { pc += 17 }
+    // lbb_90:
+    /*    90 */ if true { pc += 11 }
+    // lbb_91:
+    /*    91 */ r1 load str located at 4294984692
+    /*    93 */ r2 = 37_i32 as i64 as u64
+    /*    94 */ r0 = sol_log_(r1, r2)
+    /*    95 */ r1 = 2_i32 as i64 as u64
+    /*    96 */ *(r10 - 0xc8) = r1 as u32   ///  *((r10 as i64).wrapping_add(-200) as *mut u32) = r1 as u32
+    /*    97 */ r1 = r10
+    /*    98 */ r1 += -200   ///  r1 = r1.wrapping_add(-200_i32 as i64 as u64)
+    /*    99 */ r0 = fn_0x491f45b(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x491f45b
+    // lbb_100:
+    /*   100 */ r6 = r0
+    /*   101 */ if r7 == (0_i32 as i64 as u64) { pc += 5 }
+    // lbb_102:
+    /*   102 */ r8 += 16   ///  r8 = r8.wrapping_add(16_i32 as i64 as u64)
+    /*   103 */ if true { pc += 5 }
+    // lbb_104:
+    /*   104 */ r8 += 48   ///  r8 = r8.wrapping_add(48_i32 as i64 as u64)
+    /*   105 */ r7 += -1   ///  r7 = r7.wrapping_add(-1_i32 as i64 as u64)
+    /*   106 */ if r7 != (0_i32 as i64 as u64) { pc += 2 }
+    // lbb_107:
+    /*   107 */ r0 = r6
+    /*   108 */ return r0
+    // lbb_109:
+    /*   109 */ r1 = *(r8 + 0x0) as u64   ///  r1 = *((r8 as i64).wrapping_add(0) as *const u64) as u64
+    /*   110 */ r2 = *(r8 - 0x8) as u64   ///  r2 = *((r8 as i64).wrapping_add(-8) as *const u64) as u64
+    /*   111 */ r3 = *(r2 + 0x0) as u64   ///  r3 = *((r2 as i64).wrapping_add(0) as *const u64) as u64
+    /*   112 */ r3 += -1   ///  r3 = r3.wrapping_add(-1_i32 as i64 as u64)
+    /*   113 */ *(r2 + 0x0) = r3 as u64   ///  *((r2 as i64).wrapping_add(0) as *mut u64) = r3 as u64
+    /*   114 */ if r3 != (0_i32 as i64 as u64) { pc += 3 }
+    // lbb_115:
+    /*   115 */ r3 = *(r2 + 0x8) as u64   ///  r3 = *((r2 as i64).wrapping_add(8) as *const u64) as u64
+    /*   116 */ r3 += -1   ///  r3 = r3.wrapping_add(-1_i32 as i64 as u64)
+    /*   117 */ *(r2 + 0x8) = r3 as u64   ///  *((r2 as i64).wrapping_add(8) as *mut u64) = r3 as u64
+    // lbb_118:
+    /*   118 */ r2 = *(r1 + 0x0) as u64   ///  r2 = *((r1 as i64).wrapping_add(0) as *const u64) as u64
+    /*   119 */ r2 += -1   ///  r2 = r2.wrapping_add(-1_i32 as i64 as u64)
+    /*   120 */ *(r1 + 0x0) = r2 as u64   ///  *((r1 as i64).wrapping_add(0) as *mut u64) = r2 as u64
+    /*   121 */ if r2 != (0_i32 as i64 as u64) { pc += -18 }
+    // lbb_122:
+    /*   122 */ r2 = *(r1 + 0x8) as u64   ///  r2 = *((r1 as i64).wrapping_add(8) as *const u64) as u64
+    /*   123 */ r2 += -1   ///  r2 = r2.wrapping_add(-1_i32 as i64 as u64)
+    /*   124 */ *(r1 + 0x8) = r2 as u64   ///  *((r1 as i64).wrapping_add(8) as *mut u64) = r2 as u64
+    /*   125 */ if true { pc += -22 }
+}
@@ -0,0 +1,7 @@
+// function_1048 (pc 1048 to 1050)
+fn function_1048() -> u64 {
+    /*  1048 */ r0 = fn_0xbac0e7b6(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xbac0e7b6
+    // lbb_1049:
+    /*  1049 */ r0 = 0_i32 as i64 as u64
+    /*  1050 */ return r0
+}
@@ -0,0 +1,6 @@
+// function_1051 (pc 1051 to 1052)
+fn function_1051(r1: u64) -> u64 {
+    /*  1051 */ r1 = *(r1 + 0x0) as u64   ///  r1 = *((r1 as i64).wrapping_add(0) as *const u64) as u64
+    // lbb_1052:
+    /*  1052 */ if true { pc += -1 }
+}
@@ -0,0 +1,4 @@
+// function_152 (pc 152 to 152)
+fn function_152() -> u64 {
+    /*   152 */ return r0
+}
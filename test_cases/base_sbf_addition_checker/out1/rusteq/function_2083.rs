@@ -0,0 +1,9 @@
+// function_2083 (pc 2083 to 2087)
+fn function_2083(r1: u64, r2: u64) -> u64 {
+    /*  2083 */ r3 = r2
+    /*  2084 */ r1 = *(r1 + 0x0) as u64   ///  r1 = *((r1 as i64).wrapping_add(0) as *const u64) as u64
+    /*  2085 */ r2 = 1_i32 as i64 as u64
+    /*  2086 */ r0 = fn_0x704e6372(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x704e6372
+    // lbb_2087:
+    /*  2087 */ return r0
+}
@@ -0,0 +1,10 @@
+// function_2088 (pc 2088 to 2093)
+fn function_2088(r1: u64) -> u64 {
+    /*  2088 */ r3 = *(r1 + 0x0) as u64   ///  r3 = *((r1 as i64).wrapping_add(0) as *const u64) as u64
+    /*  2089 */ r1 = *(r1 + 0x8) as u64   ///  r1 = *((r1 as i64).wrapping_add(8) as *const u64) as u64
+    /*  2090 */ r4 = *(r1 + 0x18) as u64   ///  r4 = *((r1 as i64).wrapping_add(24) as *const u64) as u64
+    /*  2091 */ r1 = r3
+    /*  2092 */ r0 = (r4 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r4 - text_vaddr) / 8
+    // lbb_2093:
+    /*  2093 */ return r0
+}
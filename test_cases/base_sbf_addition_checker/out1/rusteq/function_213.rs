@@ -0,0 +1,6 @@
+// function_213 (pc 213 to 214)
+fn function_213() -> u64 {
+    /*   213 */ r0 = fn_0x19c7ece5(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x19c7ece5
+    // lbb_214:
+    /*   214 */ return r0
+}
@@ -0,0 +1,6 @@
+// function_659 (pc 659 to 660)
+fn function_659() -> u64 {
+    /*   659 */ r0 = fn_0x3ff690f(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x3ff690f
+    // lbb_660:
+    /*   660 */ abort()
+}
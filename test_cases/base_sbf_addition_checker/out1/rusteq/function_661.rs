@@ -0,0 +1,6 @@
+// function_661 (pc 661 to 662)
+fn function_661() -> u64 {
+    /*   661 */ r0 = fn_0x7d10564c(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x7d10564c
+    // lbb_662:
+    /*   662 */ abort()
+}
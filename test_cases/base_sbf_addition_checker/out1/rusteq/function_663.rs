@@ -0,0 +1,5 @@
+// function_663 (pc 663 to 664)
+fn function_663() -> u64 {
+    /*   663 */ r0 = sol_log_(r1, r2)
+    /*   664 */ return r0
+}
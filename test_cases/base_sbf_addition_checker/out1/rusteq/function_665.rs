@@ -0,0 +1,7 @@
+// function_665 (pc 665 to 667)
+fn function_665() -> u64 {
+    /*   665 */ r0 = fn_0x76e56781(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x76e56781
+    // lbb_666:
+    /*   666 */ abort()
+    /*   667 */ abort()
+}
@@ -0,0 +1,5 @@
+// function_668 (pc 668 to 669)
+fn function_668() -> u64 {
+    /*   668 */ abort()
+    /*   669 */ abort()
+}
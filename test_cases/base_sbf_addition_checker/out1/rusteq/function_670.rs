@@ -0,0 +1,10 @@
+// function_670 (pc 670 to 675)
+fn function_670() -> u64 {
+    /*   670 */ r1 load str located at 4294984746
+    /*   672 */ r2 = 46_i32 as i64 as u64
+    /*   673 */ r0 = fn_0x1e996cd4(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x1e996cd4
+    // lbb_674:
+    /*   674 */ r0 = fn_0x3d9ddab5(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x3d9ddab5
+    // lbb_675:
+    /*   675 */ abort()
+}
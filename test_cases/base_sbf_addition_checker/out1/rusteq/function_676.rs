@@ -0,0 +1,11 @@
+// function_676 (pc 676 to 682)
+fn function_676() -> u64 {
+    /*   676 */ r0 = fn_0xa4175322(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xa4175322
+    // lbb_677:
+    /*   677 */ abort()
+    /*   678 */ r3 = r2
+    /*   679 */ r2 load str located at 4294985320
+    /*   681 */ r0 = fn_0xadc9dcba(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xadc9dcba
+    // lbb_682:
+    /*   682 */ return r0
+}
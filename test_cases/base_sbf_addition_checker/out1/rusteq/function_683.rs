@@ -0,0 +1,4 @@
+// function_683 (pc 683 to 683)
+fn function_683() -> u64 {
+    /*   683 */ return r0
+}
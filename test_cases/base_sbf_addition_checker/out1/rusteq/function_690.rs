@@ -0,0 +1,9 @@
+// function_690 (pc 690 to 695)
+fn function_690(r1: u64, r2: u64) -> u64 {
+    /*   690 */ r1 = r2
+    /*   691 */ r2 load str located at 4294984792
+    /*   693 */ r3 = 5_i32 as i64 as u64
+    /*   694 */ r0 = fn_0x48368372(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x48368372
+    // lbb_695:
+    /*   695 */ return r0
+}
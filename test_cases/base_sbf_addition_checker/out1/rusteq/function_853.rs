@@ -0,0 +1,9 @@
+// function_853 (pc 853 to 857)
+fn function_853(r1: u64, r2: u64) -> u64 {
+    /*   853 */ r3 = r1
+    /*   854 */ r1 = r2
+    /*   855 */ r2 = r3
+    /*   856 */ r0 = fn_0x77aa2b22(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x77aa2b22
+    // lbb_857:
+    /*   857 */ abort()
+}
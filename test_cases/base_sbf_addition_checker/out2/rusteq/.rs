@@ -0,0 +1,10 @@
+//  (pc 1795 to 1800)
+fn (r1: u64) -> u64 {
+    /*  1795 */ r4 = *(r1 + 0x20) as u64   ///  r4 = *((r1 as i64).wrapping_add(32) as *const u64) as u64
+    /*  1796 */ r1 = *(r1 + 0x28) as u64   ///  r1 = *((r1 as i64).wrapping_add(40) as *const u64) as u64
+    /*  1797 */ r5 = *(r1 + 0x18) as u64   ///  r5 = *((r1 as i64).wrapping_add(24) as *const u64) as u64
+    /*  1798 */ r1 = r4
+    /*  1799 */ r0 = (r5 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r5 - text_vaddr) / 8
+    // lbb_1800:
+    /*  1800 */ return r0
+}
@@ -0,0 +1,43 @@
+// __rust_realloc (pc 153 to 185)
+fn __rust_realloc(r1: u64, r2: u64, r3: u64, r4: u64) -> u64 {
+    /*   153 */ r5 = r2
+    /*   154 */ r2 = r1
+    /*   155 */ r1 load str located at 12884901888
+    /*   157 */ r1 = *(r1 + 0x0) as u64   ///  r1 = *((r1 as i64).wrapping_add(0) as *const u64) as u64
+    /*   158 */ r6 load str located at 12884934656
+    /*   160 */ if r1 == (0_i32 as i64 as u64) { pc += 1 }
+    // lbb_161:
+    /*   161 */ r6 = r1
+    // lbb_162:
+    /*   162 */ r1 = r6
+    /*   163 */ r1 -= r4   ///  r1 = r1.wrapping_sub(r4)
+    /*   164 */ r0 = 0_i32 as i64 as u64
+    /*   165 */ r7 = 1_i32 as i64 as u64
+    /*   166 */ if r1 > r6 { pc += 1 }
+    // lbb_167:
+    /*   167 */ r7 = 0_i32 as i64 as u64
+    // lbb_168:
+    /*   168 */ r6 = 0_i32 as i64 as u64
+    /*   169 */ if r7 != (0_i32 as i64 as u64) { pc += 1 }
+    // lbb_170:
+    /*   170 */ r6 = r1
+    // lbb_171:
+    /*   171 */ r3 = -r3   ///  r3 = (r3 as i64).wrapping_neg() as u64
+    /*   172 */ r6 &= r3   ///  r6 = r6 & r3
+    /*   173 */ r1 load str located at 12884901896
+    /*   175 */ if r1 > r6 { pc += 9 }
+    // lbb_176:
+    /*   176 */ r1 load str located at 12884901888
+    /*   178 */ *(r1 + 0x0) = r6 as u64   ///  *((r1 as i64).wrapping_add(0) as *mut u64) = r6 as u64
+    /*   179 */ if r4 > r5 { pc += 1 }
+    // lbb_180:
+    /*   180 */ r5 = r4
+    // lbb_181:
+    /*   181 */ r1 = r6
+    /*   182 */ r3 = r5
+    /*   183 */ r0 = fn_0xd83872e0(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xd83872e0
+    // lbb_184:
+    /*   184 */ r0 = r6
+    // lbb_185:
+    /*   185 */ return r0
+}
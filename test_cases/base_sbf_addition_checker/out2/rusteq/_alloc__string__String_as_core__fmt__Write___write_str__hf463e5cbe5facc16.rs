@@ -0,0 +1,28 @@
+// <alloc::string::String as core::fmt::Write>::write_str::hf463e5cbe5facc16 (pc 1027 to 1047)
+fn <alloc::string::String as core::fmt::Write>::write_str::hf463e5cbe5facc16(r1: u64, r2: u64, r3: u64) -> u64 {
+    /*  1027 */ r6 = r3
+    /*  1028 */ r8 = r2
+    /*  1029 */ r7 = r1
+    /*  1030 */ r9 = *(r7 + 0x10) as u64   ///  r9 = *((r7 as i64).wrapping_add(16) as *const u64) as u64
+    /*  1031 */ r1 = *(r7 + 0x8) as u64   ///  r1 = *((r7 as i64).wrapping_add(8) as *const u64) as u64
+    /*  1032 */ r1 -= r9   ///  r1 = r1.wrapping_sub(r9)
+    /*  1033 */ if r1 >= r6 { pc += 5 }
+    // lbb_1034:
+    /*  1034 */ r1 = r7
+    /*  1035 */ r2 = r9
+    /*  1036 */ r3 = r6
+    /*  1037 */ r0 = fn_0x8d820abb(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x8d820abb
+    // lbb_1038:
+    /*  1038 */ r9 = *(r7 + 0x10) as u64   ///  r9 = *((r7 as i64).wrapping_add(16) as *const u64) as u64
+    // lbb_1039:
+    /*  1039 */ r1 = *(r7 + 0x0) as u64   ///  r1 = *((r7 as i64).wrapping_add(0) as *const u64) as u64
+    /*  1040 */ r1 += r9   ///  r1 = r1.wrapping_add(r9)
+    /*  1041 */ r2 = r8
+    /*  1042 */ r3 = r6
+    /*  1043 */ r0 = fn_0xd83872e0(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xd83872e0
+    // lbb_1044:
+    /*  1044 */ r9 += r6   ///  r9 = r9.wrapping_add(r6)
+    /*  1045 */ *(r7 + 0x10) = r9 as u64   ///  *((r7 as i64).wrapping_add(16) as *mut u64) = r9 as u64
+    /*  1046 */ r0 = 0_i32 as i64 as u64
+    /*  1047 */ return r0
+}
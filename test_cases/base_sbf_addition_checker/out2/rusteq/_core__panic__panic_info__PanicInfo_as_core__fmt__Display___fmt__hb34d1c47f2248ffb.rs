@@ -0,0 +1,111 @@
+// <core::panic::panic_info::PanicInfo as core::fmt::Display>::fmt::hb34d1c47f2248ffb (pc 1061 to 1160)
+fn <core::panic::panic_info::PanicInfo as core::fmt::Display>::fmt::hb34d1c47f2248ffb(r1: u64, r2: u64) -> u64 {
+    /*  1061 */ r8 = r1
+    /*  1062 */ r6 = *(r2 + 0x20) as u64   ///  r6 = *((r2 as i64).wrapping_add(32) as *const u64) as u64
+    /*  1063 */ r7 = *(r2 + 0x28) as u64   ///  r7 = *((r2 as i64).wrapping_add(40) as *const u64) as u64
+    /*  1064 */ r9 = *(r7 + 0x18) as u64   ///  r9 = *((r7 as i64).wrapping_add(24) as *const u64) as u64
+    /*  1065 */ r1 = r6
+    /*  1066 */ r2 load str located at 4294984992
+    /*  1068 */ r3 = 12_i32 as i64 as u64
+    /*  1069 */ r0 = (r9 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r9 - text_vaddr) / 8
+    // lbb_1070:
+    /*  1070 */ r1 = r0
+    /*  1071 */ r0 = 1_i32 as i64 as u64
+    /*  1072 */ if r1 != (0_i32 as i64 as u64) { pc += 87 }
+    // lbb_1073:
+    /*  1073 */ r1 = *(r8 + 0x18) as u64   ///  r1 = *((r8 as i64).wrapping_add(24) as *const u64) as u64
+    /*  1074 */ r2 = 0_i32 as i64 as u64
+    /*  1075 */ *(r10 - 0x70) = r2 as u64   ///  *((r10 as i64).wrapping_add(-112) as *mut u64) = r2 as u64
+    /*  1076 */ r2 load str located at 4294985480
+    /*  1078 */ *(r10 - 0x90) = r2 as u64   ///  *((r10 as i64).wrapping_add(-144) as *mut u64) = r2 as u64
+    /*  1079 */ r2 = 3_i32 as i64 as u64
+    /*  1080 */ *(r10 - 0x88) = r2 as u64   ///  *((r10 as i64).wrapping_add(-136) as *mut u64) = r2 as u64
+    /*  1081 */ *(r10 - 0x78) = r2 as u64   ///  *((r10 as i64).wrapping_add(-120) as *mut u64) = r2 as u64
+    /*  1082 */ r2 = r10
+    /*  1083 */ r2 += -96   ///  r2 = r2.wrapping_add(-96_i32 as i64 as u64)
+    /*  1084 */ *(r10 - 0x80) = r2 as u64   ///  *((r10 as i64).wrapping_add(-128) as *mut u64) = r2 as u64
+    /*  1085 */ r2 = r1
+    /*  1086 */ r2 += 20   ///  r2 = r2.wrapping_add(20_i32 as i64 as u64)
+    /*  1087 */ *(r10 - 0x40) = r2 as u64   ///  *((r10 as i64).wrapping_add(-64) as *mut u64) = r2 as u64
+    /*  1088 */ r2 load str located at 4294984208
+    /*  1090 */ *(r10 - 0x38) = r2 as u64   ///  *((r10 as i64).wrapping_add(-56) as *mut u64) = r2 as u64
+    /*  1091 */ *(r10 - 0x48) = r2 as u64   ///  *((r10 as i64).wrapping_add(-72) as *mut u64) = r2 as u64
+    /*  1092 */ r2 = r1
+    /*  1093 */ r2 += 16   ///  r2 = r2.wrapping_add(16_i32 as i64 as u64)
+    /*  1094 */ *(r10 - 0x50) = r2 as u64   ///  *((r10 as i64).wrapping_add(-80) as *mut u64) = r2 as u64
+    /*  1095 */ r2 load str located at 4294984336
+    /*  1097 */ *(r10 - 0x58) = r2 as u64   ///  *((r10 as i64).wrapping_add(-88) as *mut u64) = r2 as u64
+    /*  1098 */ *(r10 - 0x60) = r1 as u64   ///  *((r10 as i64).wrapping_add(-96) as *mut u64) = r1 as u64
+    /*  1099 */ r3 = r10
+    /*  1100 */ r3 += -144   ///  r3 = r3.wrapping_add(-144_i32 as i64 as u64)
+    /*  1101 */ r1 = r6
+    /*  1102 */ r2 = r7
+    /*  1103 */ r0 = fn_0xadc9dcba(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xadc9dcba
+    // lbb_1104:
+    /*  1104 */ r1 = r0
+    /*  1105 */ r0 = 1_i32 as i64 as u64
+    /*  1106 */ if r1 != (0_i32 as i64 as u64) { pc += 53 }
+    // lbb_1107:
+    /*  1107 */ r1 = *(r8 + 0x10) as u64   ///  r1 = *((r8 as i64).wrapping_add(16) as *const u64) as u64
+    /*  1108 */ if r1 == (0_i32 as i64 as u64) { pc += 20 }
+    // lbb_1109:
+    /*  1109 */ *(r10 - 0xa8) = r1 as u64   ///  *((r10 as i64).wrapping_add(-168) as *mut u64) = r1 as u64
+    /*  1110 */ r1 = r6
+    /*  1111 */ r2 load str located at 4294985004
+    /*  1113 */ r3 = 2_i32 as i64 as u64
+    /*  1114 */ r0 = (r9 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r9 - text_vaddr) / 8
+    // lbb_1115:
+    /*  1115 */ r1 = r0
+    /*  1116 */ r0 = 1_i32 as i64 as u64
+    /*  1117 */ if r1 != (0_i32 as i64 as u64) { pc += 42 }
+    // lbb_1118:
+    /*  1118 */ r8 = r10
+    /*  1119 */ r8 += -96   ///  r8 = r8.wrapping_add(-96_i32 as i64 as u64)
+    /*  1120 */ r1 = r8
+    /*  1121 */ r2 = *(r10 - 0xa8) as u64   ///  r2 = *((r10 as i64).wrapping_add(-168) as *const u64) as u64
+    /*  1122 */ r3 = 48_i32 as i64 as u64
+    /*  1123 */ r0 = fn_0xd83872e0(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xd83872e0
+    // lbb_1124:
+    /*  1124 */ r1 = r6
+    /*  1125 */ r2 = r7
+    /*  1126 */ r3 = r8
+    /*  1127 */ r0 = fn_0xadc9dcba(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xadc9dcba
+    // lbb_1128:
+    /*  1128 */ if true { pc += 31 }
+    // lbb_1129:
+    /*  1129 */ r7 = *(r8 + 0x0) as u64   ///  r7 = *((r8 as i64).wrapping_add(0) as *const u64) as u64
+    /*  1130 */ r1 = *(r8 + 0x8) as u64   ///  r1 = *((r8 as i64).wrapping_add(8) as *const u64) as u64
+    /*  1131 */ r3 = *(r1 + 0x18) as u64   ///  r3 = *((r1 as i64).wrapping_add(24) as *const u64) as u64
+    /*  1132 */ r1 = r10
+    /*  1133 */ r1 += -160   ///  r1 = r1.wrapping_add(-160_i32 as i64 as u64)
+    /*  1134 */ r2 = r7
+    /*  1135 */ r0 = (r3 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r3 - text_vaddr) / 8
+    // lbb_1136:
+    /*  1136 */ r1 load str located at -163230743173927068
+    /*  1138 */ r2 = *(r10 - 0x98) as u64   ///  r2 = *((r10 as i64).wrapping_add(-152) as *const u64) as u64
+    /*  1139 */ r2 ^= r1   ///  r2 = r2 ^ r1
+    /*  1140 */ r1 load str located at -4493808902380553279
+    /*  1142 */ r3 = *(r10 - 0xa0) as u64   ///  r3 = *((r10 as i64).wrapping_add(-160) as *const u64) as u64
+    /*  1143 */ r3 ^= r1   ///  r3 = r3 ^ r1
+    /*  1144 */ r3 |= r2   ///  r3 = r3 | r2
+    /*  1145 */ r0 = 0_i32 as i64 as u64
+    /*  1146 */ if r3 == (0_i32 as i64 as u64) { pc += 1 }
+    // lbb_1147:
+    /*  1147 */ if true { pc += 12 }
+    // lbb_1148:
+    /*  1148 */ r1 = r6
+    /*  1149 */ r2 load str located at 4294985004
+    /*  1151 */ r3 = 2_i32 as i64 as u64
+    /*  1152 */ r0 = (r9 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r9 - text_vaddr) / 8
+    // lbb_1153:
+    /*  1153 */ r1 = r0
+    /*  1154 */ r0 = 1_i32 as i64 as u64
+    /*  1155 */ if r1 != (0_i32 as i64 as u64) { pc += 4 }
+    // lbb_1156:
+    /*  1156 */ r3 = *(r7 + 0x8) as u64   ///  r3 = *((r7 as i64).wrapping_add(8) as *const u64) as u64
+    /*  1157 */ r2 = *(r7 + 0x0) as u64   ///  r2 = *((r7 as i64).wrapping_add(0) as *const u64) as u64
+    /*  1158 */ r1 = r6
+    /*  1159 */ r0 = (r9 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r9 - text_vaddr) / 8
+    // lbb_1160:
+    /*  1160 */ return r0
+}
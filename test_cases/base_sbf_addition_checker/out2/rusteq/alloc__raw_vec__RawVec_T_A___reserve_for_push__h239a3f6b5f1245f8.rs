@@ -0,0 +1,74 @@
+// alloc::raw_vec::RawVec<T,A>::reserve_for_push::h239a3f6b5f1245f8 (pc 256 to 307)
+fn alloc::raw_vec::RawVec<T,A>::reserve_for_push::h239a3f6b5f1245f8(r1: u64, r2: u64) -> u64 {
+    /*   256 */ r6 = r1
+    /*   257 */ r2 += 1   ///  r2 = r2.wrapping_add(1_i32 as i64 as u64)
+    /*   258 */ r1 = 1_i32 as i64 as u64
+    /*   259 */ if r2 == (0_i32 as i64 as u64) { pc += 1 }
+    // lbb_260:
+    /*   260 */ r1 = 0_i32 as i64 as u64
+    // lbb_261:
+    /*   261 */ r1 &= 1   ///  r1 = r1 & (1_i32 as i64 as u64)
+    /*   262 */ if r1 != (0_i32 as i64 as u64) { pc += 40 }
+    // lbb_263:
+    /*   263 */ r1 = *(r6 + 0x8) as u64   ///  r1 = *((r6 as i64).wrapping_add(8) as *const u64) as u64
+    /*   264 */ r7 = r1
+    /*   265 */ r7 <<= 1   ///  r7 = r7.wrapping_shl(1)
+    /*   266 */ if r7 > r2 { pc += 1 }
+    // lbb_267:
+    /*   267 */ r7 = r2
+    // lbb_268:
+    /*   268 */ if r7 > (4_i32 as i64 as u64) { pc += 1 }
+    // lbb_269:
+    /*   269 */ r7 = 4_i32 as i64 as u64
+    // lbb_270:
+    /*   270 */ r2 = 1_i32 as i64 as u64
+    /*   271 */ r3 load str located at 192153584101141163
+    /*   273 */ if r3 > r7 { pc += 1 }
+    // lbb_274:
+    /*   274 */ r2 = 0_i32 as i64 as u64
+    // lbb_275:
+    /*   275 */ r3 = r7
+    /*   276 */ r3 *= 48   ///  r3 = r3.wrapping_mul(48 as u64)
+    /*   277 */ r2 <<= 3   ///  r2 = r2.wrapping_shl(3)
+    /*   278 */ if r1 != (0_i32 as i64 as u64) { pc += 3 }
+    // lbb_279:
+    /*   279 */ r1 = 0_i32 as i64 as u64
+    /*   280 */ *(r10 - 0x10) = r1 as u64   ///  *((r10 as i64).wrapping_add(-16) as *mut u64) = r1 as u64
+    /*   281 */ if true { pc += 6 }
+    // lbb_282:
+    /*   282 */ r4 = *(r6 + 0x0) as u64   ///  r4 = *((r6 as i64).wrapping_add(0) as *const u64) as u64
+    /*   283 */ r1 *= 48   ///  r1 = r1.wrapping_mul(48 as u64)
+    /*   284 */ *(r10 - 0x8) = r1 as u64   ///  *((r10 as i64).wrapping_add(-8) as *mut u64) = r1 as u64
+    /*   285 */ r1 = 8_i32 as i64 as u64
+    /*   286 */ *(r10 - 0x10) = r1 as u64   ///  *((r10 as i64).wrapping_add(-16) as *mut u64) = r1 as u64
+    /*   287 */ *(r10 - 0x18) = r4 as u64   ///  *((r10 as i64).wrapping_add(-24) as *mut u64) = r4 as u64
+    // lbb_288:
+    /*   288 */ r1 = r10
+    /*   289 */ r1 += -48   ///  r1 = r1.wrapping_add(-48_i32 as i64 as u64)
+    /*   290 */ r4 = r10
+    /*   291 */ r4 += -24   ///  r4 = r4.wrapping_add(-24_i32 as i64 as u64)
+    /*   292 */ r0 = fn_0x7f0e2ab6(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x7f0e2ab6
+    // lbb_293:
+    /*   293 */ r1 = *(r10 - 0x28) as u64   ///  r1 = *((r10 as i64).wrapping_add(-40) as *const u64) as u64
+    /*   294 */ r2 = *(r10 - 0x30) as u64   ///  r2 = *((r10 as i64).wrapping_add(-48) as *const u64) as u64
+    /*   295 */ if r2 != (0_i32 as i64 as u64) { pc += 3 }
+    // lbb_296:
+    /*   296 */ *(r6 + 0x8) = r7 as u64   ///  *((r6 as i64).wrapping_add(8) as *mut u64) = r7 as u64
+    /*   297 */ *(r6 + 0x0) = r1 as u64   ///  *((r6 as i64).wrapping_add(0) as *mut u64) = r1 as u64
+    // lbb_298:
+    /*   298 */ return r0
+    // lbb_299:
+    /*   299 */ r2 load str located at -9223372036854775807
+    /*   301 */ if r1 == r2 { pc += -4 }
+    // lbb_302:
+    /*   302 */ if r1 != (0_i32 as i64 as u64) { pc += 2 }
+    // lbb_303:
+    /*   303 */ r0 = fn_0xa8220fca(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xa8220fca
+    // lbb_304:
+    /*   304 */ abort()
+    // lbb_305:
+    /*   305 */ r2 = *(r10 - 0x20) as u64   ///  r2 = *((r10 as i64).wrapping_add(-32) as *const u64) as u64
+    /*   306 */ r0 = fn_0xdeeafbd2(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xdeeafbd2
+    // lbb_307:
+    /*   307 */ abort()
+}
@@ -0,0 +1,18 @@
+// alloc::raw_vec::capacity_overflow::hc3c1796744b60038 (pc 836 to 852)
+fn alloc::raw_vec::capacity_overflow::hc3c1796744b60038() -> u64 {
+    /*   836 */ r1 = 1_i32 as i64 as u64
+    /*   837 */ *(r10 - 0x28) = r1 as u64   ///  *((r10 as i64).wrapping_add(-40) as *mut u64) = r1 as u64
+    /*   838 */ r1 load str located at 4294985368
+    /*   840 */ *(r10 - 0x30) = r1 as u64   ///  *((r10 as i64).wrapping_add(-48) as *mut u64) = r1 as u64
+    /*   841 */ r1 = 0_i32 as i64 as u64
+    /*   842 */ *(r10 - 0x10) = r1 as u64   ///  *((r10 as i64).wrapping_add(-16) as *mut u64) = r1 as u64
+    /*   843 */ *(r10 - 0x18) = r1 as u64   ///  *((r10 as i64).wrapping_add(-24) as *mut u64) = r1 as u64
+    /*   844 */ r1 load str located at 4294984792
+    /*   846 */ *(r10 - 0x20) = r1 as u64   ///  *((r10 as i64).wrapping_add(-32) as *mut u64) = r1 as u64
+    /*   847 */ r1 = r10
+    /*   848 */ r1 += -48   ///  r1 = r1.wrapping_add(-48_i32 as i64 as u64)
+    /*   849 */ r2 load str located at 4294985384
+    /*   851 */ r0 = fn_0x18045f7a(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x18045f7a
+    // lbb_852:
+    /*   852 */ abort()
+}
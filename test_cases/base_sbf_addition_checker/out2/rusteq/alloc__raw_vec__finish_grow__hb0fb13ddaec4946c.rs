@@ -0,0 +1,64 @@
+// alloc::raw_vec::finish_grow::hb0fb13ddaec4946c (pc 791 to 835)
+fn alloc::raw_vec::finish_grow::hb0fb13ddaec4946c(r1: u64, r2: u64, r3: u64, r4: u64) -> u64 {
+    /*   791 */ r7 = r3
+    /*   792 */ r6 = r1
+    /*   793 */ if r2 == (0_i32 as i64 as u64) { pc += 13 }
+    // lbb_794:
+    /*   794 */ r1 = *(r4 + 0x8) as u64   ///  r1 = *((r4 as i64).wrapping_add(8) as *const u64) as u64
+    /*   795 */ if r1 == (0_i32 as i64 as u64) { pc += 27 }
+    // lbb_796:
+    /*   796 */ r2 = *(r4 + 0x10) as u64   ///  r2 = *((r4 as i64).wrapping_add(16) as *const u64) as u64
+    /*   797 */ if r2 != (0_i32 as i64 as u64) { pc += 14 }
+    // lbb_798:
+    /*   798 */ r1 = 0_i32 as i64 as u64
+    /*   799 */ r0 = 1_i32 as i64 as u64
+    /*   800 */ if r7 == (0_i32 as i64 as u64) { pc += 30 }
+    // lbb_801:
+    /*   801 */ r1 = r7
+    /*   802 */ r2 = 1_i32 as i64 as u64
+    /*   803 */ r0 = fn_0xd203373a(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xd203373a
+    // lbb_804:
+    /*   804 */ r1 = r7
+    /*   805 */ if r0 == (0_i32 as i64 as u64) { pc += 13 }
+    // lbb_806:
+    /*   806 */ if true { pc += 24 }
+    // lbb_807:
+    /*   807 */ *(r6 + 0x10) = r7 as u64   ///  *((r6 as i64).wrapping_add(16) as *mut u64) = r7 as u64
+    /*   808 */ r1 = 0_i32 as i64 as u64
+    /*   809 */ *(r6 + 0x8) = r1 as u64   ///  *((r6 as i64).wrapping_add(8) as *mut u64) = r1 as u64
+    /*   810 */ r1 = 1_i32 as i64 as u64
+    /*   811 */ if true { pc += 22 }
+    // lbb_812:
+    /*   812 */ r1 = *(r4 + 0x0) as u64   ///  r1 = *((r4 as i64).wrapping_add(0) as *const u64) as u64
+    /*   813 */ r3 = 1_i32 as i64 as u64
+    /*   814 */ r4 = r7
+    /*   815 */ r0 = fn_0x882312e2(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x882312e2
+    // lbb_816:
+    /*   816 */ r1 = r7
+    /*   817 */ if r0 == (0_i32 as i64 as u64) { pc += 1 }
+    // lbb_818:
+    /*   818 */ if true { pc += 12 }
+    // lbb_819:
+    /*   819 */ *(r6 + 0x10) = r7 as u64   ///  *((r6 as i64).wrapping_add(16) as *mut u64) = r7 as u64
+    /*   820 */ r1 = 1_i32 as i64 as u64
+    /*   821 */ *(r6 + 0x8) = r1 as u64   ///  *((r6 as i64).wrapping_add(8) as *mut u64) = r1 as u64
+    /*   822 */ if true { pc += 11 }
+    // lbb_823:
+    /*   823 */ r1 = 0_i32 as i64 as u64
+    /*   824 */ r0 = 1_i32 as i64 as u64
+    /*   825 */ if r7 == (0_i32 as i64 as u64) { pc += 5 }
+    // lbb_826:
+    /*   826 */ r1 = r7
+    /*   827 */ r2 = 1_i32 as i64 as u64
+    /*   828 */ r0 = fn_0xd203373a(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xd203373a
+    // lbb_829:
+    /*   829 */ r1 = r7
+    /*   830 */ if r0 == (0_i32 as i64 as u64) { pc += -12 }
+    // lbb_831:
+    /*   831 */ *(r6 + 0x10) = r1 as u64   ///  *((r6 as i64).wrapping_add(16) as *mut u64) = r1 as u64
+    /*   832 */ *(r6 + 0x8) = r0 as u64   ///  *((r6 as i64).wrapping_add(8) as *mut u64) = r0 as u64
+    /*   833 */ r1 = 0_i32 as i64 as u64
+    // lbb_834:
+    /*   834 */ *(r6 + 0x0) = r1 as u64   ///  *((r6 as i64).wrapping_add(0) as *mut u64) = r1 as u64
+    /*   835 */ return r0
+}
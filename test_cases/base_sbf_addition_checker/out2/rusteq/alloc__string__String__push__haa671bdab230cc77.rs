@@ -0,0 +1,108 @@
+// alloc::string::String::push::haa671bdab230cc77 (pc 938 to 1026)
+fn alloc::string::String::push::haa671bdab230cc77(r1: u64, r2: u64) -> u64 {
+    /*   938 */ r7 = r2
+    /*   939 */ r6 = r1
+    /*   940 */ r1 = r7
+    /*   941 */ r1 <<= 32   ///  r1 = r1.wrapping_shl(32)
+    /*   942 */ r1 >>= 32   ///  r1 = r1.wrapping_shr(32)
+    /*   943 */ r2 = 128_i32 as i64 as u64
+    /*   944 */ if r2 > r1 { pc += 24 }
+    // lbb_945:
+    /*   945 */ r2 = 0_i32 as i64 as u64
+    /*   946 */ *(r10 - 0x4) = r2 as u32   ///  *((r10 as i64).wrapping_add(-4) as *mut u32) = r2 as u32
+    /*   947 */ r2 = 2048_i32 as i64 as u64
+    /*   948 */ if r2 > r1 { pc += 32 }
+    // lbb_949:
+    /*   949 */ r1 = r7
+    /*   950 */ r1 <<= 32   ///  r1 = r1.wrapping_shl(32)
+    /*   951 */ r1 >>= 32   ///  r1 = r1.wrapping_shr(32)
+    /*   952 */ r2 = 65536_i32 as i64 as u64
+    /*   953 */ if r2 > r1 { pc += 1 }
+    // lbb_954:
+    /*   954 */ if true { pc += 35 }
+    // lbb_955:
+    /*   955 */ r1 = r7
+    /*   956 */ r1 &= 63   ///  r1 = r1 & (63_i32 as i64 as u64)
+    /*   957 */ r1 |= 128   ///  r1 = r1 | (128_i32 as i64 as u64)
+    /*   958 */ *(r10 - 0x2) = r1 as u8   ///  *((r10 as i64).wrapping_add(-2) as *mut u8) = r1 as u8
+    /*   959 */ r1 = r7
+    /*   960 */ r1 >>= 12   ///  r1 = r1.wrapping_shr(12)
+    /*   961 */ r1 |= 224   ///  r1 = r1 | (224_i32 as i64 as u64)
+    /*   962 */ *(r10 - 0x4) = r1 as u8   ///  *((r10 as i64).wrapping_add(-4) as *mut u8) = r1 as u8
+    /*   963 */ r7 >>= 6   ///  r7 = r7.wrapping_shr(6)
+    /*   964 */ r7 &= 63   ///  r7 = r7 & (63_i32 as i64 as u64)
+    /*   965 */ r7 |= 128   ///  r7 = r7 | (128_i32 as i64 as u64)
+    /*   966 */ *(r10 - 0x3) = r7 as u8   ///  *((r10 as i64).wrapping_add(-3) as *mut u8) = r7 as u8
+    /*   967 */ r7 = 3_i32 as i64 as u64
+    /*   968 */ if true { pc += 40 }
+    // lbb_969:
+    /*   969 */ r2 = *(r6 + 0x10) as u64   ///  r2 = *((r6 as i64).wrapping_add(16) as *const u64) as u64
+    /*   970 */ r1 = *(r6 + 0x8) as u64   ///  r1 = *((r6 as i64).wrapping_add(8) as *const u64) as u64
+    /*   971 */ if r2 != r1 { pc += 3 }
+    // lbb_972:
+    /*   972 */ r1 = r6
+    /*   973 */ r0 = fn_0x362b333e(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x362b333e
+    // lbb_974:
+    /*   974 */ r2 = *(r6 + 0x10) as u64   ///  r2 = *((r6 as i64).wrapping_add(16) as *const u64) as u64
+    // lbb_975:
+    /*   975 */ r1 = *(r6 + 0x0) as u64   ///  r1 = *((r6 as i64).wrapping_add(0) as *const u64) as u64
+    /*   976 */ r1 += r2   ///  r1 = r1.wrapping_add(r2)
+    /*   977 */ *(r1 + 0x0) = r7 as u8   ///  *((r1 as i64).wrapping_add(0) as *mut u8) = r7 as u8
+    /*   978 */ r2 += 1   ///  r2 = r2.wrapping_add(1_i32 as i64 as u64)
+    /*   979 */ *(r6 + 0x10) = r2 as u64   ///  *((r6 as i64).wrapping_add(16) as *mut u64) = r2 as u64
+    /*   980 */ if true { pc += 45 }
+    // lbb_981:
+    /*   981 */ r1 = r7
+    /*   982 */ r1 &= 63   ///  r1 = r1 & (63_i32 as i64 as u64)
+    /*   983 */ r1 |= 128   ///  r1 = r1 | (128_i32 as i64 as u64)
+    /*   984 */ *(r10 - 0x3) = r1 as u8   ///  *((r10 as i64).wrapping_add(-3) as *mut u8) = r1 as u8
+    /*   985 */ r7 >>= 6   ///  r7 = r7.wrapping_shr(6)
+    /*   986 */ r7 |= 192   ///  r7 = r7 | (192_i32 as i64 as u64)
+    /*   987 */ *(r10 - 0x4) = r7 as u8   ///  *((r10 as i64).wrapping_add(-4) as *mut u8) = r7 as u8
+    /*   988 */ r7 = 2_i32 as i64 as u64
+    /*   989 */ if true { pc += 19 }
+    // lbb_990:
+    /*   990 */ r1 = r7
+    /*   991 */ r1 &= 63   ///  r1 = r1 & (63_i32 as i64 as u64)
+    /*   992 */ r1 |= 128   ///  r1 = r1 | (128_i32 as i64 as u64)
+    /*   993 */ *(r10 - 0x1) = r1 as u8   ///  *((r10 as i64).wrapping_add(-1) as *mut u8) = r1 as u8
+    /*   994 */ r1 = r7
+    /*   995 */ r1 >>= 6   ///  r1 = r1.wrapping_shr(6)
+    /*   996 */ r1 &= 63   ///  r1 = r1 & (63_i32 as i64 as u64)
+    /*   997 */ r1 |= 128   ///  r1 = r1 | (128_i32 as i64 as u64)
+    /*   998 */ *(r10 - 0x2) = r1 as u8   ///  *((r10 as i64).wrapping_add(-2) as *mut u8) = r1 as u8
+    /*   999 */ r1 = r7
+    /*  1000 */ r1 >>= 12   ///  r1 = r1.wrapping_shr(12)
+    /*  1001 */ r1 &= 63   ///  r1 = r1 & (63_i32 as i64 as u64)
+    /*  1002 */ r1 |= 128   ///  r1 = r1 | (128_i32 as i64 as u64)
+    /*  1003 */ *(r10 - 0x3) = r1 as u8   ///  *((r10 as i64).wrapping_add(-3) as *mut u8) = r1 as u8
+    /*  1004 */ r7 >>= 18   ///  r7 = r7.wrapping_shr(18)
+    /*  1005 */ r7 &= 7   ///  r7 = r7 & (7_i32 as i64 as u64)
+    /*  1006 */ r7 |= 240   ///  r7 = r7 | (240_i32 as i64 as u64)
+    /*  1007 */ *(r10 - 0x4) = r7 as u8   ///  *((r10 as i64).wrapping_add(-4) as *mut u8) = r7 as u8
+    /*  1008 */ r7 = 4_i32 as i64 as u64
+    // lbb_1009:
+    /*  1009 */ r8 = *(r6 + 0x10) as u64   ///  r8 = *((r6 as i64).wrapping_add(16) as *const u64) as u64
+    /*  1010 */ r1 = *(r6 + 0x8) as u64   ///  r1 = *((r6 as i64).wrapping_add(8) as *const u64) as u64
+    /*  1011 */ r1 -= r8   ///  r1 = r1.wrapping_sub(r8)
+    /*  1012 */ if r1 >= r7 { pc += 5 }
+    // lbb_1013:
+    /*  1013 */ r1 = r6
+    /*  1014 */ r2 = r8
+    /*  1015 */ r3 = r7
+    /*  1016 */ r0 = fn_0x8d820abb(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x8d820abb
+    // lbb_1017:
+    /*  1017 */ r8 = *(r6 + 0x10) as u64   ///  r8 = *((r6 as i64).wrapping_add(16) as *const u64) as u64
+    // lbb_1018:
+    /*  1018 */ r1 = *(r6 + 0x0) as u64   ///  r1 = *((r6 as i64).wrapping_add(0) as *const u64) as u64
+    /*  1019 */ r1 += r8   ///  r1 = r1.wrapping_add(r8)
+    /*  1020 */ r2 = r10
+    /*  1021 */ r2 += -4   ///  r2 = r2.wrapping_add(-4_i32 as i64 as u64)
+    /*  1022 */ r3 = r7
+    /*  1023 */ r0 = fn_0xd83872e0(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xd83872e0
+    // lbb_1024:
+    /*  1024 */ r8 += r7   ///  r8 = r8.wrapping_add(r7)
+    /*  1025 */ *(r6 + 0x10) = r8 as u64   ///  *((r6 as i64).wrapping_add(16) as *mut u64) = r8 as u64
+    // lbb_1026:
+    /*  1026 */ return r0
+}
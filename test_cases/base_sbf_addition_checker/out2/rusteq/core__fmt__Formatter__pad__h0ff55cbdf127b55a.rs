@@ -0,0 +1,236 @@
+// core::fmt::Formatter::pad::h0ff55cbdf127b55a (pc 1622 to 1794)
+fn core::fmt::Formatter::pad::h0ff55cbdf127b55a(r1: u64, r2: u64, r3: u64) -> u64 {
+    /*  1622 */ r8 = r1
+    /*  1623 */ r5 = *(r8 + 0x10) as u64   ///  r5 = *((r8 as i64).wrapping_add(16) as *const u64) as u64
+    /*  1624 */ r1 = *(r8 + 0x0) as u64   ///  r1 = *((r8 as i64).wrapping_add(0) as *const u64) as u64
+    /*  1625 */ r4 = r1
+    /*  1626 */ r4 |= r5   ///  r4 = r4 | r5
+    /*  1627 */ if r4 == (0_i32 as i64 as u64) { pc += 51 }
+    // lbb_1628:
+    /*  1628 */ *(r10 - 0x8) = r2 as u64   ///  *((r10 as i64).wrapping_add(-8) as *mut u64) = r2 as u64
+    /*  1629 */ *(r10 - 0x10) = r3 as u64   ///  *((r10 as i64).wrapping_add(-16) as *mut u64) = r3 as u64
+    /*  1630 */ if r5 == (0_i32 as i64 as u64) { pc += 68 }
+    // lbb_1631:
+    /*  1631 */ r7 = *(r10 - 0x8) as u64   ///  r7 = *((r10 as i64).wrapping_add(-8) as *const u64) as u64
+    /*  1632 */ r3 = r7
+    /*  1633 */ r2 = *(r10 - 0x10) as u64   ///  r2 = *((r10 as i64).wrapping_add(-16) as *const u64) as u64
+    /*  1634 */ r3 += r2   ///  r3 = r3.wrapping_add(r2)
+    /*  1635 */ r2 = 0_i32 as i64 as u64
+    /*  1636 */ r4 = *(r8 + 0x18) as u64   ///  r4 = *((r8 as i64).wrapping_add(24) as *const u64) as u64
+    /*  1637 */ if r4 == (0_i32 as i64 as u64) { pc += 26 }
+    // lbb_1638:
+    /*  1638 */ r6 = 0_i32 as i64 as u64
+    /*  1639 */ r7 = *(r10 - 0x8) as u64   ///  r7 = *((r10 as i64).wrapping_add(-8) as *const u64) as u64
+    // lbb_1640:
+    /*  1640 */ r9 = r7
+    /*  1641 */ if r9 == r3 { pc += 57 }
+    // lbb_1642:
+    /*  1642 */ r7 = r9
+    /*  1643 */ r7 += 1   ///  r7 = r7.wrapping_add(1_i32 as i64 as u64)
+    /*  1644 */ r5 = *(r9 + 0x0) as u8   ///  r5 = *((r9 as i64).wrapping_add(0) as *const u8) as u64
+    /*  1645 */ r0 = r5
+    /*  1646 */ r0 <<= 56   ///  r0 = r0.wrapping_shl(56)
+    /*  1647 */ r0 >>= 56 (signed)   ///  r0 = (r0 as i64).wrapping_shr(56)
+    /*  1648 */ if (r0 as i64) > (-1 as i32 as i64) { pc += 11 }
+    // lbb_1649:
+    /*  1649 */ r7 = r9
+    /*  1650 */ r7 += 2   ///  r7 = r7.wrapping_add(2_i32 as i64 as u64)
+    /*  1651 */ r0 = r5
+    /*  1652 */ r5 = 224_i32 as i64 as u64
+    /*  1653 */ if r5 > r0 { pc += 6 }
+    // lbb_1654:
+    /*  1654 */ r7 = r9
+    /*  1655 */ r7 += 3   ///  r7 = r7.wrapping_add(3_i32 as i64 as u64)
+    /*  1656 */ r5 = 240_i32 as i64 as u64
+    /*  1657 */ if r5 > r0 { pc += 2 }
+    // lbb_1658:
+    /*  1658 */ r7 = r9
+    /*  1659 */ r7 += 4   ///  r7 = r7.wrapping_add(4_i32 as i64 as u64)
+    // lbb_1660:
+    /*  1660 */ r6 += 1   ///  r6 = r6.wrapping_add(1_i32 as i64 as u64)
+    /*  1661 */ r2 -= r9   ///  r2 = r2.wrapping_sub(r9)
+    /*  1662 */ r2 += r7   ///  r2 = r2.wrapping_add(r7)
+    /*  1663 */ if r4 > r6 { pc += -24 }
+    // lbb_1664:
+    /*  1664 */ if r7 == r3 { pc += 34 }
+    // lbb_1665:
+    /*  1665 */ r3 = *(r7 + 0x0) as u8   ///  r3 = *((r7 as i64).wrapping_add(0) as *const u8) as u64
+    /*  1666 */ r4 = r3
+    /*  1667 */ r4 <<= 56   ///  r4 = r4.wrapping_shl(56)
+    /*  1668 */ r4 >>= 56 (signed)   ///  r4 = (r4 as i64).wrapping_shr(56)
+    /*  1669 */ if (r4 as i64) > (-1 as i32 as i64) { pc += 2 }
+    // lbb_1670:
+    /*  1670 */ r4 = 224_i32 as i64 as u64
+    /*  1671 */ if r4 > r3 { pc += 0 }
+    // lbb_1672:
+    /*  1672 */ r0 = *(r10 - 0x10) as u64   ///  r0 = *((r10 as i64).wrapping_add(-16) as *const u64) as u64
+    /*  1673 */ r6 = *(r10 - 0x8) as u64   ///  r6 = *((r10 as i64).wrapping_add(-8) as *const u64) as u64
+    /*  1674 */ if r2 == (0_i32 as i64 as u64) { pc += 17 }
+    // lbb_1675:
+    /*  1675 */ if r0 > r2 { pc += 8 }
+    // lbb_1676:
+    /*  1676 */ r3 = 0_i32 as i64 as u64
+    /*  1677 */ if r2 == r0 { pc += 14 }
+    // lbb_1678:
+    /*  1678 */ if true { pc += 14 }
+    // lbb_1679:
+    /*  1679 */ r1 = *(r8 + 0x20) as u64   ///  r1 = *((r8 as i64).wrapping_add(32) as *const u64) as u64
+    /*  1680 */ r4 = *(r8 + 0x28) as u64   ///  r4 = *((r8 as i64).wrapping_add(40) as *const u64) as u64
+    /*  1681 */ r4 = *(r4 + 0x18) as u64   ///  r4 = *((r4 as i64).wrapping_add(24) as *const u64) as u64
+    /*  1682 */ r0 = (r4 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r4 - text_vaddr) / 8
+    // lbb_1683:
+    /*  1683 */ if true { pc += 109 }
+    // lbb_1684:
+    /*  1684 */ r4 = r6
+    /*  1685 */ r4 += r2   ///  r4 = r4.wrapping_add(r2)
+    /*  1686 */ r3 = 0_i32 as i64 as u64
+    /*  1687 */ r4 = *(r4 + 0x0) as u8   ///  r4 = *((r4 as i64).wrapping_add(0) as *const u8) as u64
+    /*  1688 */ r4 <<= 56   ///  r4 = r4.wrapping_shl(56)
+    /*  1689 */ r4 >>= 56 (signed)   ///  r4 = (r4 as i64).wrapping_shr(56)
+    /*  1690 */ r5 = -64_i32 as i64 as u64
+    /*  1691 */ if (r5 as i64) > (r4 as i64) { pc += 1 }
+    // lbb_1692:
+    /*  1692 */ r3 = r6
+    // lbb_1693:
+    /*  1693 */ if r3 == (0_i32 as i64 as u64) { pc += 1 }
+    // lbb_1694:
+    /*  1694 */ r0 = r2
+    // lbb_1695:
+    /*  1695 */ *(r10 - 0x10) = r0 as u64   ///  *((r10 as i64).wrapping_add(-16) as *mut u64) = r0 as u64
+    /*  1696 */ if r3 == (0_i32 as i64 as u64) { pc += 1 }
+    // lbb_1697:
+    /*  1697 */ r6 = r3
+    // lbb_1698:
+    /*  1698 */ *(r10 - 0x8) = r6 as u64   ///  *((r10 as i64).wrapping_add(-8) as *mut u64) = r6 as u64
+    // lbb_1699:
+    /*  1699 */ if r1 != (0_i32 as i64 as u64) { pc += 7 }
+    // lbb_1700:
+    /*  1700 */ r1 = *(r8 + 0x20) as u64   ///  r1 = *((r8 as i64).wrapping_add(32) as *const u64) as u64
+    /*  1701 */ r2 = *(r8 + 0x28) as u64   ///  r2 = *((r8 as i64).wrapping_add(40) as *const u64) as u64
+    /*  1702 */ r4 = *(r2 + 0x18) as u64   ///  r4 = *((r2 as i64).wrapping_add(24) as *const u64) as u64
+    /*  1703 */ r2 = *(r10 - 0x8) as u64   ///  r2 = *((r10 as i64).wrapping_add(-8) as *const u64) as u64
+    /*  1704 */ r3 = *(r10 - 0x10) as u64   ///  r3 = *((r10 as i64).wrapping_add(-16) as *const u64) as u64
+    /*  1705 */ r0 = (r4 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r4 - text_vaddr) / 8
+    // lbb_1706:
+    /*  1706 */ if true { pc += 86 }
+    // lbb_1707:
+    /*  1707 */ r9 = *(r8 + 0x8) as u64   ///  r9 = *((r8 as i64).wrapping_add(8) as *const u64) as u64
+    /*  1708 */ r1 = 32_i32 as i64 as u64
+    /*  1709 */ r6 = *(r10 - 0x10) as u64   ///  r6 = *((r10 as i64).wrapping_add(-16) as *const u64) as u64
+    /*  1710 */ r7 = *(r10 - 0x8) as u64   ///  r7 = *((r10 as i64).wrapping_add(-8) as *const u64) as u64
+    /*  1711 */ if r1 > r6 { pc += 4 }
+    // lbb_1712:
+    /*  1712 */ r1 = r7
+    /*  1713 */ r2 = r6
+    /*  1714 */ r0 = fn_0xff074caf(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xff074caf
+    // lbb_1715:
+    /*  1715 */ if true { pc += 9 }
+    // lbb_1716:
+    /*  1716 */ r0 = 0_i32 as i64 as u64
+    /*  1717 */ if r6 == (0_i32 as i64 as u64) { pc += 7 }
+    // lbb_1718:
+    /*  1718 */ r1 = r7
+    /*  1719 */ r2 = r6
+    /*  1720 */ if true { pc += 13 }
+    // lbb_1721:
+    /*  1721 */ r0 += r3   ///  r0 = r0.wrapping_add(r3)
+    /*  1722 */ r1 += 1   ///  r1 = r1.wrapping_add(1_i32 as i64 as u64)
+    /*  1723 */ r2 += -1   ///  r2 = r2.wrapping_add(-1_i32 as i64 as u64)
+    /*  1724 */ if r2 != (0_i32 as i64 as u64) { pc += 9 }
+    // lbb_1725:
+    /*  1725 */ if r0 >= r9 { pc += 15 }
+    // lbb_1726:
+    /*  1726 */ r9 -= r0   ///  r9 = r9.wrapping_sub(r0)
+    /*  1727 */ r6 = 0_i32 as i64 as u64
+    /*  1728 */ r1 = *(r8 + 0x38) as u8   ///  r1 = *((r8 as i64).wrapping_add(56) as *const u8) as u64
+    /*  1729 */ if (r1 as i64) > (1 as i32 as i64) { pc += 18 }
+    // lbb_1730:
+    /*  1730 */ if r1 == (0_i32 as i64 as u64) { pc += 23 }
+    // lbb_1731:
+    /*  1731 */ r6 = r9
+    /*  1732 */ r9 = 0_i32 as i64 as u64
+    /*  1733 */ if true { pc += 20 }
+    // lbb_1734:
+    /*  1734 */ r4 = *(r1 + 0x0) as u8   ///  r4 = *((r1 as i64).wrapping_add(0) as *const u8) as u64
+    /*  1735 */ r4 <<= 56   ///  r4 = r4.wrapping_shl(56)
+    /*  1736 */ r4 >>= 56 (signed)   ///  r4 = (r4 as i64).wrapping_shr(56)
+    /*  1737 */ r3 = 1_i32 as i64 as u64
+    /*  1738 */ if (r4 as i64) > (-65 as i32 as i64) { pc += -18 }
+    // lbb_1739:
+    /*  1739 */ r3 = 0_i32 as i64 as u64
+    /*  1740 */ if true { pc += -20 }
+    // lbb_1741:
+    /*  1741 */ r1 = *(r8 + 0x20) as u64   ///  r1 = *((r8 as i64).wrapping_add(32) as *const u64) as u64
+    /*  1742 */ r2 = *(r8 + 0x28) as u64   ///  r2 = *((r8 as i64).wrapping_add(40) as *const u64) as u64
+    /*  1743 */ r4 = *(r2 + 0x18) as u64   ///  r4 = *((r2 as i64).wrapping_add(24) as *const u64) as u64
+    /*  1744 */ r2 = r7
+    /*  1745 */ r3 = r6
+    /*  1746 */ r0 = (r4 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r4 - text_vaddr) / 8
+    // lbb_1747:
+    /*  1747 */ if true { pc += 45 }
+    // lbb_1748:
+    /*  1748 */ if r1 == (2_i32 as i64 as u64) { pc += 1 }
+    // lbb_1749:
+    /*  1749 */ if true { pc += 4 }
+    // lbb_1750:
+    /*  1750 */ r6 = r9
+    /*  1751 */ r6 >>= 1   ///  r6 = r6.wrapping_shr(1)
+    /*  1752 */ r9 += 1   ///  r9 = r9.wrapping_add(1_i32 as i64 as u64)
+    /*  1753 */ r9 >>= 1   ///  r9 = r9.wrapping_shr(1)
+    // lbb_1754:
+    /*  1754 */ *(r10 - 0x18) = r9 as u64   ///  *((r10 as i64).wrapping_add(-24) as *mut u64) = r9 as u64
+    /*  1755 */ r6 += 1   ///  r6 = r6.wrapping_add(1_i32 as i64 as u64)
+    /*  1756 */ r9 = *(r8 + 0x30) as u32   ///  r9 = *((r8 as i64).wrapping_add(48) as *const u32) as u64
+    /*  1757 */ r7 = *(r8 + 0x28) as u64   ///  r7 = *((r8 as i64).wrapping_add(40) as *const u64) as u64
+    /*  1758 */ r8 = *(r8 + 0x20) as u64   ///  r8 = *((r8 as i64).wrapping_add(32) as *const u64) as u64
+    // lbb_1759:
+    /*  1759 */ r6 += -1   ///  r6 = r6.wrapping_add(-1_i32 as i64 as u64)
+    /*  1760 */ if r6 == (0_i32 as i64 as u64) { pc += 8 }
+    // lbb_1761:
+    /*  1761 */ r3 = *(r7 + 0x20) as u64   ///  r3 = *((r7 as i64).wrapping_add(32) as *const u64) as u64
+    /*  1762 */ r1 = r8
+    /*  1763 */ r2 = r9
+    /*  1764 */ r0 = (r3 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r3 - text_vaddr) / 8
+    // lbb_1765:
+    /*  1765 */ r1 = r0
+    /*  1766 */ r0 = 1_i32 as i64 as u64
+    /*  1767 */ if r1 != (0_i32 as i64 as u64) { pc += 25 }
+    // lbb_1768:
+    /*  1768 */ if true { pc += -10 }
+    // lbb_1769:
+    /*  1769 */ r4 = *(r7 + 0x18) as u64   ///  r4 = *((r7 as i64).wrapping_add(24) as *const u64) as u64
+    /*  1770 */ r1 = r8
+    /*  1771 */ r2 = *(r10 - 0x8) as u64   ///  r2 = *((r10 as i64).wrapping_add(-8) as *const u64) as u64
+    /*  1772 */ r3 = *(r10 - 0x10) as u64   ///  r3 = *((r10 as i64).wrapping_add(-16) as *const u64) as u64
+    /*  1773 */ r0 = (r4 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r4 - text_vaddr) / 8
+    // lbb_1774:
+    /*  1774 */ r1 = r0
+    /*  1775 */ r0 = 1_i32 as i64 as u64
+    /*  1776 */ if r1 != (0_i32 as i64 as u64) { pc += 16 }
+    // lbb_1777:
+    /*  1777 */ r6 = 0_i32 as i64 as u64
+    // lbb_1778:
+    /*  1778 */ r2 = *(r10 - 0x18) as u64   ///  r2 = *((r10 as i64).wrapping_add(-24) as *const u64) as u64
+    /*  1779 */ r1 = r2
+    /*  1780 */ if r2 == r6 { pc += 8 }
+    // lbb_1781:
+    /*  1781 */ r3 = *(r7 + 0x20) as u64   ///  r3 = *((r7 as i64).wrapping_add(32) as *const u64) as u64
+    /*  1782 */ r1 = r8
+    /*  1783 */ r2 = r9
+    /*  1784 */ r0 = (r3 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r3 - text_vaddr) / 8
+    // lbb_1785:
+    /*  1785 */ r6 += 1   ///  r6 = r6.wrapping_add(1_i32 as i64 as u64)
+    /*  1786 */ if r0 == (0_i32 as i64 as u64) { pc += -9 }
+    // lbb_1787:
+    /*  1787 */ r6 += -1   ///  r6 = r6.wrapping_add(-1_i32 as i64 as u64)
+    /*  1788 */ r1 = r6
+    // lbb_1789:
+    /*  1789 */ r0 = 1_i32 as i64 as u64
+    /*  1790 */ r2 = *(r10 - 0x18) as u64   ///  r2 = *((r10 as i64).wrapping_add(-24) as *const u64) as u64
+    /*  1791 */ if r2 > r1 { pc += 1 }
+    // lbb_1792:
+    /*  1792 */ r0 = 0_i32 as i64 as u64
+    // lbb_1793:
+    /*  1793 */ r0 &= 1   ///  r0 = r0 & (1_i32 as i64 as u64)
+    /*  1794 */ return r0
+}
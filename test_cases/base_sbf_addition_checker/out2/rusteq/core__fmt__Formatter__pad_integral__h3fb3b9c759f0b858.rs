@@ -0,0 +1,259 @@
+// core::fmt::Formatter::pad_integral::h3fb3b9c759f0b858 (pc 1401 to 1597)
+fn core::fmt::Formatter::pad_integral::h3fb3b9c759f0b858(r1: u64, r2: u64, r3: u64, r4: u64, r5: u64) -> u64 {
+    /*  1401 */ r0 = r4
+    /*  1402 */ r8 = r1
+    /*  1403 */ r9 = *(r5 - 0xff8) as u64   ///  r9 = *((r5 as i64).wrapping_add(-4088) as *const u64) as u64
+    /*  1404 */ *(r10 - 0x20) = r9 as u64   ///  *((r10 as i64).wrapping_add(-32) as *mut u64) = r9 as u64
+    /*  1405 */ if r2 != (0_i32 as i64 as u64) { pc += 5 }
+    // lbb_1406:
+    /*  1406 */ r1 = 45_i32 as i64 as u64
+    /*  1407 */ *(r10 - 0x18) = r1 as u64   ///  *((r10 as i64).wrapping_add(-24) as *mut u64) = r1 as u64
+    /*  1408 */ r7 = *(r8 + 0x34) as u32   ///  r7 = *((r8 as i64).wrapping_add(52) as *const u32) as u64
+    /*  1409 */ r9 += 1   ///  r9 = r9.wrapping_add(1_i32 as i64 as u64)
+    /*  1410 */ if true { pc += 6 }
+    // lbb_1411:
+    /*  1411 */ r1 = 1114112_i32 as i64 as u64
+    /*  1412 */ *(r10 - 0x18) = r1 as u64   ///  *((r10 as i64).wrapping_add(-24) as *mut u64) = r1 as u64
+    /*  1413 */ r7 = *(r8 + 0x34) as u32   ///  r7 = *((r8 as i64).wrapping_add(52) as *const u32) as u64
+    /*  1414 */ r1 = r7
+    /*  1415 */ r1 &= 1   ///  r1 = r1 & (1_i32 as i64 as u64)
+    /*  1416 */ if r1 != (0_i32 as i64 as u64) { pc += 60 }
+    // lbb_1417:
+    /*  1417 */ r1 = *(r5 - 0x1000) as u64   ///  r1 = *((r5 as i64).wrapping_add(-4096) as *const u64) as u64
+    /*  1418 */ *(r10 - 0x28) = r1 as u64   ///  *((r10 as i64).wrapping_add(-40) as *mut u64) = r1 as u64
+    /*  1419 */ r4 = 0_i32 as i64 as u64
+    /*  1420 */ r1 = r7
+    /*  1421 */ r1 &= 4   ///  r1 = r1 & (4_i32 as i64 as u64)
+    /*  1422 */ if r1 == (0_i32 as i64 as u64) { pc += 1 }
+    // lbb_1423:
+    /*  1423 */ if true { pc += 27 }
+    // lbb_1424:
+    /*  1424 */ r1 = *(r8 + 0x0) as u64   ///  r1 = *((r8 as i64).wrapping_add(0) as *const u64) as u64
+    /*  1425 */ if r1 != (0_i32 as i64 as u64) { pc += 10 }
+    // lbb_1426:
+    /*  1426 */ r9 = *(r8 + 0x28) as u64   ///  r9 = *((r8 as i64).wrapping_add(40) as *const u64) as u64
+    /*  1427 */ r6 = *(r8 + 0x20) as u64   ///  r6 = *((r8 as i64).wrapping_add(32) as *const u64) as u64
+    /*  1428 */ r1 = r6
+    /*  1429 */ r2 = r9
+    /*  1430 */ r3 = *(r10 - 0x18) as u64   ///  r3 = *((r10 as i64).wrapping_add(-24) as *const u64) as u64
+    /*  1431 */ r5 = r0
+    /*  1432 */ r0 = fn_0x69925312(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x69925312
+    // lbb_1433:
+    /*  1433 */ r7 = 1_i32 as i64 as u64
+    /*  1434 */ if r0 != (0_i32 as i64 as u64) { pc += 39 }
+    // lbb_1435:
+    /*  1435 */ if true { pc += 32 }
+    // lbb_1436:
+    /*  1436 */ r6 = *(r8 + 0x8) as u64   ///  r6 = *((r8 as i64).wrapping_add(8) as *const u64) as u64
+    /*  1437 */ if r6 > r9 { pc += 1 }
+    // lbb_1438:
+    /*  1438 */ if true { pc += 20 }
+    // lbb_1439:
+    /*  1439 */ r7 &= 8   ///  r7 = r7 & (8_i32 as i64 as u64)
+    /*  1440 */ if r7 == (0_i32 as i64 as u64) { pc += 1 }
+    // lbb_1441:
+    /*  1441 */ if true { pc += 63 }
+    // lbb_1442:
+    /*  1442 */ r6 -= r9   ///  r6 = r6.wrapping_sub(r9)
+    /*  1443 */ r9 = *(r8 + 0x38) as u8   ///  r9 = *((r8 as i64).wrapping_add(56) as *const u8) as u64
+    /*  1444 */ *(r10 - 0x30) = r0 as u64   ///  *((r10 as i64).wrapping_add(-48) as *mut u64) = r0 as u64
+    /*  1445 */ *(r10 - 0x38) = r4 as u64   ///  *((r10 as i64).wrapping_add(-56) as *mut u64) = r4 as u64
+    /*  1446 */ if (r9 as i64) > (1 as i32 as i64) { pc += 85 }
+    // lbb_1447:
+    /*  1447 */ if r9 == (0_i32 as i64 as u64) { pc += 103 }
+    // lbb_1448:
+    /*  1448 */ r9 = r6
+    /*  1449 */ r6 = 0_i32 as i64 as u64
+    /*  1450 */ if true { pc += 100 }
+    // lbb_1451:
+    /*  1451 */ r1 = 32_i32 as i64 as u64
+    /*  1452 */ *(r10 - 0x8) = r3 as u64   ///  *((r10 as i64).wrapping_add(-8) as *mut u64) = r3 as u64
+    /*  1453 */ if r1 > r0 { pc += 28 }
+    // lbb_1454:
+    /*  1454 */ r1 = r3
+    /*  1455 */ r6 = r0
+    /*  1456 */ r2 = r0
+    /*  1457 */ r0 = fn_0xff074caf(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xff074caf
+    // lbb_1458:
+    /*  1458 */ if true { pc += 34 }
+    // lbb_1459:
+    /*  1459 */ r9 = *(r8 + 0x28) as u64   ///  r9 = *((r8 as i64).wrapping_add(40) as *const u64) as u64
+    /*  1460 */ r6 = *(r8 + 0x20) as u64   ///  r6 = *((r8 as i64).wrapping_add(32) as *const u64) as u64
+    /*  1461 */ r1 = r6
+    /*  1462 */ r2 = r9
+    /*  1463 */ r3 = *(r10 - 0x18) as u64   ///  r3 = *((r10 as i64).wrapping_add(-24) as *const u64) as u64
+    /*  1464 */ r5 = r0
+    /*  1465 */ r0 = fn_0x69925312(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x69925312
+    // lbb_1466:
+    /*  1466 */ r7 = 1_i32 as i64 as u64
+    /*  1467 */ if r0 != (0_i32 as i64 as u64) { pc += 6 }
+    // lbb_1468:
+    /*  1468 */ r4 = *(r9 + 0x18) as u64   ///  r4 = *((r9 as i64).wrapping_add(24) as *const u64) as u64
+    /*  1469 */ r1 = r6
+    /*  1470 */ r2 = *(r10 - 0x28) as u64   ///  r2 = *((r10 as i64).wrapping_add(-40) as *const u64) as u64
+    /*  1471 */ r3 = *(r10 - 0x20) as u64   ///  r3 = *((r10 as i64).wrapping_add(-32) as *const u64) as u64
+    /*  1472 */ r0 = (r4 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r4 - text_vaddr) / 8
+    // lbb_1473:
+    /*  1473 */ r7 = r0
+    // lbb_1474:
+    /*  1474 */ r7 &= 1   ///  r7 = r7 & (1_i32 as i64 as u64)
+    /*  1475 */ r0 = r7
+    /*  1476 */ return r0
+    // lbb_1477:
+    /*  1477 */ r1 = 43_i32 as i64 as u64
+    /*  1478 */ *(r10 - 0x18) = r1 as u64   ///  *((r10 as i64).wrapping_add(-24) as *mut u64) = r1 as u64
+    /*  1479 */ r9 = *(r10 - 0x20) as u64   ///  r9 = *((r10 as i64).wrapping_add(-32) as *const u64) as u64
+    /*  1480 */ r9 += 1   ///  r9 = r9.wrapping_add(1_i32 as i64 as u64)
+    /*  1481 */ if true { pc += -65 }
+    // lbb_1482:
+    /*  1482 */ r1 = r0
+    /*  1483 */ r0 = 0_i32 as i64 as u64
+    /*  1484 */ r6 = r1
+    /*  1485 */ if r1 == (0_i32 as i64 as u64) { pc += 7 }
+    // lbb_1486:
+    /*  1486 */ r1 = *(r10 - 0x8) as u64   ///  r1 = *((r10 as i64).wrapping_add(-8) as *const u64) as u64
+    /*  1487 */ r2 = r6
+    /*  1488 */ if true { pc += 9 }
+    // lbb_1489:
+    /*  1489 */ r0 += r3   ///  r0 = r0.wrapping_add(r3)
+    /*  1490 */ r1 += 1   ///  r1 = r1.wrapping_add(1_i32 as i64 as u64)
+    /*  1491 */ r2 += -1   ///  r2 = r2.wrapping_add(-1_i32 as i64 as u64)
+    /*  1492 */ if r2 != (0_i32 as i64 as u64) { pc += 5 }
+    // lbb_1493:
+    /*  1493 */ r0 += r9   ///  r0 = r0.wrapping_add(r9)
+    /*  1494 */ r4 = *(r10 - 0x8) as u64   ///  r4 = *((r10 as i64).wrapping_add(-8) as *const u64) as u64
+    /*  1495 */ r9 = r0
+    /*  1496 */ r0 = r6
+    /*  1497 */ if true { pc += -74 }
+    // lbb_1498:
+    /*  1498 */ r4 = *(r1 + 0x0) as u8   ///  r4 = *((r1 as i64).wrapping_add(0) as *const u8) as u64
+    /*  1499 */ r4 <<= 56   ///  r4 = r4.wrapping_shl(56)
+    /*  1500 */ r4 >>= 56 (signed)   ///  r4 = (r4 as i64).wrapping_shr(56)
+    /*  1501 */ r3 = 1_i32 as i64 as u64
+    /*  1502 */ if (r4 as i64) > (-65 as i32 as i64) { pc += -14 }
+    // lbb_1503:
+    /*  1503 */ r3 = 0_i32 as i64 as u64
+    /*  1504 */ if true { pc += -16 }
+    // lbb_1505:
+    /*  1505 */ r1 = *(r8 + 0x30) as u32   ///  r1 = *((r8 as i64).wrapping_add(48) as *const u32) as u64
+    /*  1506 */ *(r10 - 0x40) = r1 as u64   ///  *((r10 as i64).wrapping_add(-64) as *mut u64) = r1 as u64
+    /*  1507 */ r1 = 48_i32 as i64 as u64
+    /*  1508 */ *(r8 + 0x30) = r1 as u32   ///  *((r8 as i64).wrapping_add(48) as *mut u32) = r1 as u32
+    /*  1509 */ r1 = *(r8 + 0x38) as u8   ///  r1 = *((r8 as i64).wrapping_add(56) as *const u8) as u64
+    /*  1510 */ *(r10 - 0x48) = r1 as u64   ///  *((r10 as i64).wrapping_add(-72) as *mut u64) = r1 as u64
+    /*  1511 */ r7 = 1_i32 as i64 as u64
+    /*  1512 */ *(r8 + 0x38) = r7 as u8   ///  *((r8 as i64).wrapping_add(56) as *mut u8) = r7 as u8
+    /*  1513 */ r1 = *(r8 + 0x20) as u64   ///  r1 = *((r8 as i64).wrapping_add(32) as *const u64) as u64
+    /*  1514 */ r2 = *(r8 + 0x28) as u64   ///  r2 = *((r8 as i64).wrapping_add(40) as *const u64) as u64
+    /*  1515 */ *(r10 - 0x8) = r1 as u64   ///  *((r10 as i64).wrapping_add(-8) as *mut u64) = r1 as u64
+    /*  1516 */ *(r10 - 0x10) = r2 as u64   ///  *((r10 as i64).wrapping_add(-16) as *mut u64) = r2 as u64
+    /*  1517 */ r3 = *(r10 - 0x18) as u64   ///  r3 = *((r10 as i64).wrapping_add(-24) as *const u64) as u64
+    /*  1518 */ r5 = r0
+    /*  1519 */ r0 = fn_0x69925312(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x69925312
+    // lbb_1520:
+    /*  1520 */ if r0 != (0_i32 as i64 as u64) { pc += -47 }
+    // lbb_1521:
+    /*  1521 */ r6 -= r9   ///  r6 = r6.wrapping_sub(r9)
+    /*  1522 */ r6 += 1   ///  r6 = r6.wrapping_add(1_i32 as i64 as u64)
+    // lbb_1523:
+    /*  1523 */ r6 += -1   ///  r6 = r6.wrapping_add(-1_i32 as i64 as u64)
+    /*  1524 */ if r6 == (0_i32 as i64 as u64) { pc += 9 }
+    // lbb_1525:
+    /*  1525 */ r1 = *(r10 - 0x10) as u64   ///  r1 = *((r10 as i64).wrapping_add(-16) as *const u64) as u64
+    /*  1526 */ r3 = *(r1 + 0x20) as u64   ///  r3 = *((r1 as i64).wrapping_add(32) as *const u64) as u64
+    /*  1527 */ r1 = *(r10 - 0x8) as u64   ///  r1 = *((r10 as i64).wrapping_add(-8) as *const u64) as u64
+    /*  1528 */ r2 = 48_i32 as i64 as u64
+    /*  1529 */ r0 = (r3 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r3 - text_vaddr) / 8
+    // lbb_1530:
+    /*  1530 */ if r0 != (0_i32 as i64 as u64) { pc += -57 }
+    // lbb_1531:
+    /*  1531 */ if true { pc += -9 }
+    // lbb_1532:
+    /*  1532 */ if r9 == (2_i32 as i64 as u64) { pc += 14 }
+    // lbb_1533:
+    /*  1533 */ if true { pc += -86 }
+    // lbb_1534:
+    /*  1534 */ r1 = *(r10 - 0x10) as u64   ///  r1 = *((r10 as i64).wrapping_add(-16) as *const u64) as u64
+    /*  1535 */ r4 = *(r1 + 0x18) as u64   ///  r4 = *((r1 as i64).wrapping_add(24) as *const u64) as u64
+    /*  1536 */ r1 = *(r10 - 0x8) as u64   ///  r1 = *((r10 as i64).wrapping_add(-8) as *const u64) as u64
+    /*  1537 */ r2 = *(r10 - 0x28) as u64   ///  r2 = *((r10 as i64).wrapping_add(-40) as *const u64) as u64
+    /*  1538 */ r3 = *(r10 - 0x20) as u64   ///  r3 = *((r10 as i64).wrapping_add(-32) as *const u64) as u64
+    /*  1539 */ r0 = (r4 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r4 - text_vaddr) / 8
+    // lbb_1540:
+    /*  1540 */ if r0 != (0_i32 as i64 as u64) { pc += -67 }
+    // lbb_1541:
+    /*  1541 */ r1 = *(r10 - 0x48) as u64   ///  r1 = *((r10 as i64).wrapping_add(-72) as *const u64) as u64
+    /*  1542 */ *(r8 + 0x38) = r1 as u8   ///  *((r8 as i64).wrapping_add(56) as *mut u8) = r1 as u8
+    /*  1543 */ r1 = *(r10 - 0x40) as u64   ///  r1 = *((r10 as i64).wrapping_add(-64) as *const u64) as u64
+    /*  1544 */ *(r8 + 0x30) = r1 as u32   ///  *((r8 as i64).wrapping_add(48) as *mut u32) = r1 as u32
+    /*  1545 */ r7 = 0_i32 as i64 as u64
+    /*  1546 */ if true { pc += -73 }
+    // lbb_1547:
+    /*  1547 */ r9 = r6
+    /*  1548 */ r9 >>= 1   ///  r9 = r9.wrapping_shr(1)
+    /*  1549 */ r6 += 1   ///  r6 = r6.wrapping_add(1_i32 as i64 as u64)
+    /*  1550 */ r6 >>= 1   ///  r6 = r6.wrapping_shr(1)
+    // lbb_1551:
+    /*  1551 */ r9 += 1   ///  r9 = r9.wrapping_add(1_i32 as i64 as u64)
+    /*  1552 */ r1 = *(r8 + 0x30) as u32   ///  r1 = *((r8 as i64).wrapping_add(48) as *const u32) as u64
+    /*  1553 */ *(r10 - 0x10) = r1 as u64   ///  *((r10 as i64).wrapping_add(-16) as *mut u64) = r1 as u64
+    /*  1554 */ r1 = *(r8 + 0x28) as u64   ///  r1 = *((r8 as i64).wrapping_add(40) as *const u64) as u64
+    /*  1555 */ *(r10 - 0x8) = r1 as u64   ///  *((r10 as i64).wrapping_add(-8) as *mut u64) = r1 as u64
+    /*  1556 */ r8 = *(r8 + 0x20) as u64   ///  r8 = *((r8 as i64).wrapping_add(32) as *const u64) as u64
+    /*  1557 */ r7 = 1_i32 as i64 as u64
+    // lbb_1558:
+    /*  1558 */ r9 += -1   ///  r9 = r9.wrapping_add(-1_i32 as i64 as u64)
+    /*  1559 */ if r9 == (0_i32 as i64 as u64) { pc += 7 }
+    // lbb_1560:
+    /*  1560 */ r1 = *(r10 - 0x8) as u64   ///  r1 = *((r10 as i64).wrapping_add(-8) as *const u64) as u64
+    /*  1561 */ r3 = *(r1 + 0x20) as u64   ///  r3 = *((r1 as i64).wrapping_add(32) as *const u64) as u64
+    /*  1562 */ r1 = r8
+    /*  1563 */ r2 = *(r10 - 0x10) as u64   ///  r2 = *((r10 as i64).wrapping_add(-16) as *const u64) as u64
+    /*  1564 */ r0 = (r3 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r3 - text_vaddr) / 8
+    // lbb_1565:
+    /*  1565 */ if r0 != (0_i32 as i64 as u64) { pc += -92 }
+    // lbb_1566:
+    /*  1566 */ if true { pc += -9 }
+    // lbb_1567:
+    /*  1567 */ r1 = r8
+    /*  1568 */ r2 = *(r10 - 0x8) as u64   ///  r2 = *((r10 as i64).wrapping_add(-8) as *const u64) as u64
+    /*  1569 */ r3 = *(r10 - 0x18) as u64   ///  r3 = *((r10 as i64).wrapping_add(-24) as *const u64) as u64
+    /*  1570 */ r4 = *(r10 - 0x38) as u64   ///  r4 = *((r10 as i64).wrapping_add(-56) as *const u64) as u64
+    /*  1571 */ r5 = *(r10 - 0x30) as u64   ///  r5 = *((r10 as i64).wrapping_add(-48) as *const u64) as u64
+    /*  1572 */ r0 = fn_0x69925312(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x69925312
+    // lbb_1573:
+    /*  1573 */ r7 = 1_i32 as i64 as u64
+    /*  1574 */ if r0 != (0_i32 as i64 as u64) { pc += -101 }
+    // lbb_1575:
+    /*  1575 */ r1 = *(r10 - 0x8) as u64   ///  r1 = *((r10 as i64).wrapping_add(-8) as *const u64) as u64
+    /*  1576 */ r4 = *(r1 + 0x18) as u64   ///  r4 = *((r1 as i64).wrapping_add(24) as *const u64) as u64
+    /*  1577 */ r1 = r8
+    /*  1578 */ r2 = *(r10 - 0x28) as u64   ///  r2 = *((r10 as i64).wrapping_add(-40) as *const u64) as u64
+    /*  1579 */ r3 = *(r10 - 0x20) as u64   ///  r3 = *((r10 as i64).wrapping_add(-32) as *const u64) as u64
+    /*  1580 */ r0 = (r4 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r4 - text_vaddr) / 8
+    // lbb_1581:
+    /*  1581 */ if r0 != (0_i32 as i64 as u64) { pc += -108 }
+    // lbb_1582:
+    /*  1582 */ r7 = 0_i32 as i64 as u64
+    // lbb_1583:
+    /*  1583 */ r1 = r6
+    /*  1584 */ if r6 == r7 { pc += 9 }
+    // lbb_1585:
+    /*  1585 */ r1 = *(r10 - 0x8) as u64   ///  r1 = *((r10 as i64).wrapping_add(-8) as *const u64) as u64
+    /*  1586 */ r3 = *(r1 + 0x20) as u64   ///  r3 = *((r1 as i64).wrapping_add(32) as *const u64) as u64
+    /*  1587 */ r1 = r8
+    /*  1588 */ r2 = *(r10 - 0x10) as u64   ///  r2 = *((r10 as i64).wrapping_add(-16) as *const u64) as u64
+    /*  1589 */ r0 = (r3 as fn)(r1, r2, r3, r4, r5)   ///  pc = (r3 - text_vaddr) / 8
+    // lbb_1590:
+    /*  1590 */ r7 += 1   ///  r7 = r7.wrapping_add(1_i32 as i64 as u64)
+    /*  1591 */ if r0 == (0_i32 as i64 as u64) { pc += -9 }
+    // lbb_1592:
+    /*  1592 */ r7 += -1   ///  r7 = r7.wrapping_add(-1_i32 as i64 as u64)
+    /*  1593 */ r1 = r7
+    // lbb_1594:
+    /*  1594 */ r7 = 1_i32 as i64 as u64
+    /*  1595 */ if r6 > r1 { pc += -122 }
+    // lbb_1596:
+    /*  1596 */ r7 = 0_i32 as i64 as u64
+    /*  1597 */ if true { pc += -124 }
+}
@@ -0,0 +1,33 @@
+// core::result::unwrap_failed::h2044b5150b644a52 (pc 1202 to 1232)
+fn core::result::unwrap_failed::h2044b5150b644a52(r1: u64, r2: u64, r3: u64, r4: u64, r5: u64) -> u64 {
+    /*  1202 */ r6 = r5
+    /*  1203 */ *(r10 - 0x68) = r2 as u64   ///  *((r10 as i64).wrapping_add(-104) as *mut u64) = r2 as u64
+    /*  1204 */ *(r10 - 0x70) = r1 as u64   ///  *((r10 as i64).wrapping_add(-112) as *mut u64) = r1 as u64
+    /*  1205 */ *(r10 - 0x58) = r4 as u64   ///  *((r10 as i64).wrapping_add(-88) as *mut u64) = r4 as u64
+    /*  1206 */ *(r10 - 0x60) = r3 as u64   ///  *((r10 as i64).wrapping_add(-96) as *mut u64) = r3 as u64
+    /*  1207 */ r1 load str located at 4294984288
+    /*  1209 */ *(r10 - 0x8) = r1 as u64   ///  *((r10 as i64).wrapping_add(-8) as *mut u64) = r1 as u64
+    /*  1210 */ r1 = r10
+    /*  1211 */ r1 += -96   ///  r1 = r1.wrapping_add(-96_i32 as i64 as u64)
+    /*  1212 */ *(r10 - 0x10) = r1 as u64   ///  *((r10 as i64).wrapping_add(-16) as *mut u64) = r1 as u64
+    /*  1213 */ r1 load str located at 4294984336
+    /*  1215 */ *(r10 - 0x18) = r1 as u64   ///  *((r10 as i64).wrapping_add(-24) as *mut u64) = r1 as u64
+    /*  1216 */ r1 = r10
+    /*  1217 */ r1 += -112   ///  r1 = r1.wrapping_add(-112_i32 as i64 as u64)
+    /*  1218 */ *(r10 - 0x20) = r1 as u64   ///  *((r10 as i64).wrapping_add(-32) as *mut u64) = r1 as u64
+    /*  1219 */ r7 = r10
+    /*  1220 */ r7 += -80   ///  r7 = r7.wrapping_add(-80_i32 as i64 as u64)
+    /*  1221 */ r4 = r10
+    /*  1222 */ r4 += -32   ///  r4 = r4.wrapping_add(-32_i32 as i64 as u64)
+    /*  1223 */ r1 = r7
+    /*  1224 */ r2 load str located at 4294985592
+    /*  1226 */ r3 = 2_i32 as i64 as u64
+    /*  1227 */ r5 = 2_i32 as i64 as u64
+    /*  1228 */ r0 = fn_0xd0585269(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xd0585269
+    // lbb_1229:
+    /*  1229 */ r1 = r7
+    /*  1230 */ r2 = r6
+    /*  1231 */ r0 = fn_0x18045f7a(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x18045f7a
+    // lbb_1232:
+    /*  1232 */ abort()
+}
@@ -0,0 +1,220 @@
+// core::str::count::do_count_chars::hf2c865dd48e9a906 (pc 1801 to 1989)
+fn core::str::count::do_count_chars::hf2c865dd48e9a906(r1: u64, r2: u64) -> u64 {
+    /*  1801 */ r7 = r1
+    /*  1802 */ r7 += 7   ///  r7 = r7.wrapping_add(7_i32 as i64 as u64)
+    /*  1803 */ r7 &= -8   ///  r7 = r7 & (-8_i32 as i64 as u64)
+    /*  1804 */ r4 = r7
+    /*  1805 */ r4 -= r1   ///  r4 = r4.wrapping_sub(r1)
+    /*  1806 */ if r4 > r2 { pc += 138 }
+    // lbb_1807:
+    /*  1807 */ r5 = r2
+    /*  1808 */ r5 -= r4   ///  r5 = r5.wrapping_sub(r4)
+    /*  1809 */ r3 = 8_i32 as i64 as u64
+    /*  1810 */ if r3 > r5 { pc += 134 }
+    // lbb_1811:
+    /*  1811 */ *(r10 - 0x8) = r4 as u64   ///  *((r10 as i64).wrapping_add(-8) as *mut u64) = r4 as u64
+    /*  1812 */ r2 = r5
+    /*  1813 */ r2 &= 7   ///  r2 = r2 & (7_i32 as i64 as u64)
+    /*  1814 */ r0 = 0_i32 as i64 as u64
+    /*  1815 */ r3 = 0_i32 as i64 as u64
+    /*  1816 */ if r7 == r1 { pc += 7 }
+    // lbb_1817:
+    /*  1817 */ r6 = r1
+    /*  1818 */ r6 -= r7   ///  r6 = r6.wrapping_sub(r7)
+    /*  1819 */ r7 = r1
+    /*  1820 */ if true { pc += 12 }
+    // lbb_1821:
+    /*  1821 */ r3 += r9   ///  r3 = r3.wrapping_add(r9)
+    /*  1822 */ r7 += 1   ///  r7 = r7.wrapping_add(1_i32 as i64 as u64)
+    /*  1823 */ if r8 != (1_i32 as i64 as u64) { pc += 9 }
+    // lbb_1824:
+    /*  1824 */ r4 = *(r10 - 0x8) as u64   ///  r4 = *((r10 as i64).wrapping_add(-8) as *const u64) as u64
+    /*  1825 */ r1 += r4   ///  r1 = r1.wrapping_add(r4)
+    /*  1826 */ if r2 == (0_i32 as i64 as u64) { pc += 21 }
+    // lbb_1827:
+    /*  1827 */ r0 = r5
+    /*  1828 */ r0 &= -8   ///  r0 = r0 & (-8_i32 as i64 as u64)
+    /*  1829 */ r4 = r1
+    /*  1830 */ r4 += r0   ///  r4 = r4.wrapping_add(r0)
+    /*  1831 */ r0 = 0_i32 as i64 as u64
+    /*  1832 */ if true { pc += 20 }
+    // lbb_1833:
+    /*  1833 */ r4 = *(r7 + 0x0) as u8   ///  r4 = *((r7 as i64).wrapping_add(0) as *const u8) as u64
+    /*  1834 */ r4 <<= 56   ///  r4 = r4.wrapping_shl(56)
+    /*  1835 */ r4 >>= 56 (signed)   ///  r4 = (r4 as i64).wrapping_shr(56)
+    /*  1836 */ r8 = 1_i32 as i64 as u64
+    /*  1837 */ r9 = 1_i32 as i64 as u64
+    /*  1838 */ if (r4 as i64) > (-65 as i32 as i64) { pc += 1 }
+    // lbb_1839:
+    /*  1839 */ r9 = 0_i32 as i64 as u64
+    // lbb_1840:
+    /*  1840 */ r6 += 1   ///  r6 = r6.wrapping_add(1_i32 as i64 as u64)
+    /*  1841 */ if r6 == (0_i32 as i64 as u64) { pc += -21 }
+    // lbb_1842:
+    /*  1842 */ r8 = 0_i32 as i64 as u64
+    /*  1843 */ if true { pc += -23 }
+    // lbb_1844:
+    /*  1844 */ r0 += r6   ///  r0 = r0.wrapping_add(r6)
+    /*  1845 */ r4 += 1   ///  r4 = r4.wrapping_add(1_i32 as i64 as u64)
+    /*  1846 */ r2 += -1   ///  r2 = r2.wrapping_add(-1_i32 as i64 as u64)
+    /*  1847 */ if r2 != (0_i32 as i64 as u64) { pc += 5 }
+    // lbb_1848:
+    /*  1848 */ r5 >>= 3   ///  r5 = r5.wrapping_shr(3)
+    /*  1849 */ r0 += r3   ///  r0 = r0.wrapping_add(r3)
+    /*  1850 */ r6 load str located at 72340172838076673
+    /*  1852 */ if true { pc += 73 }
+    // lbb_1853:
+    /*  1853 */ r7 = *(r4 + 0x0) as u8   ///  r7 = *((r4 as i64).wrapping_add(0) as *const u8) as u64
+    /*  1854 */ r7 <<= 56   ///  r7 = r7.wrapping_shl(56)
+    /*  1855 */ r7 >>= 56 (signed)   ///  r7 = (r7 as i64).wrapping_shr(56)
+    /*  1856 */ r6 = 1_i32 as i64 as u64
+    /*  1857 */ if (r7 as i64) > (-65 as i32 as i64) { pc += -14 }
+    // lbb_1858:
+    /*  1858 */ r6 = 0_i32 as i64 as u64
+    /*  1859 */ if true { pc += -16 }
+    // lbb_1860:
+    /*  1860 */ r8 = *(r2 + 0x0) as u64   ///  r8 = *((r2 as i64).wrapping_add(0) as *const u64) as u64
+    /*  1861 */ r7 = r8
+    /*  1862 */ r7 >>= 6   ///  r7 = r7.wrapping_shr(6)
+    /*  1863 */ r8 ^= -1   ///  r8 = r8 ^ (-1_i32 as i64 as u64)
+    /*  1864 */ r8 >>= 7   ///  r8 = r8.wrapping_shr(7)
+    /*  1865 */ r8 |= r7   ///  r8 = r8 | r7
+    /*  1866 */ r8 &= r6   ///  r8 = r8 & r6
+    /*  1867 */ r8 += r4   ///  r8 = r8.wrapping_add(r4)
+    /*  1868 */ r4 = *(r2 + 0x8) as u64   ///  r4 = *((r2 as i64).wrapping_add(8) as *const u64) as u64
+    /*  1869 */ r7 = r4
+    /*  1870 */ r7 >>= 6   ///  r7 = r7.wrapping_shr(6)
+    /*  1871 */ r4 ^= -1   ///  r4 = r4 ^ (-1_i32 as i64 as u64)
+    /*  1872 */ r4 >>= 7   ///  r4 = r4.wrapping_shr(7)
+    /*  1873 */ r4 |= r7   ///  r4 = r4 | r7
+    /*  1874 */ r4 &= r6   ///  r4 = r4 & r6
+    /*  1875 */ r4 += r8   ///  r4 = r4.wrapping_add(r8)
+    /*  1876 */ r8 = *(r2 + 0x10) as u64   ///  r8 = *((r2 as i64).wrapping_add(16) as *const u64) as u64
+    /*  1877 */ r7 = r8
+    /*  1878 */ r7 >>= 6   ///  r7 = r7.wrapping_shr(6)
+    /*  1879 */ r8 ^= -1   ///  r8 = r8 ^ (-1_i32 as i64 as u64)
+    /*  1880 */ r8 >>= 7   ///  r8 = r8.wrapping_shr(7)
+    /*  1881 */ r8 |= r7   ///  r8 = r8 | r7
+    /*  1882 */ r8 &= r6   ///  r8 = r8 & r6
+    /*  1883 */ r8 += r4   ///  r8 = r8.wrapping_add(r4)
+    /*  1884 */ r4 = *(r2 + 0x18) as u64   ///  r4 = *((r2 as i64).wrapping_add(24) as *const u64) as u64
+    /*  1885 */ r7 = r4
+    /*  1886 */ r7 >>= 6   ///  r7 = r7.wrapping_shr(6)
+    /*  1887 */ r4 ^= -1   ///  r4 = r4 ^ (-1_i32 as i64 as u64)
+    /*  1888 */ r4 >>= 7   ///  r4 = r4.wrapping_shr(7)
+    /*  1889 */ r4 |= r7   ///  r4 = r4 | r7
+    /*  1890 */ r4 &= r6   ///  r4 = r4 & r6
+    /*  1891 */ r4 += r8   ///  r4 = r4.wrapping_add(r8)
+    /*  1892 */ r2 += 32   ///  r2 = r2.wrapping_add(32_i32 as i64 as u64)
+    /*  1893 */ if r2 != r1 { pc += -34 }
+    // lbb_1894:
+    /*  1894 */ r1 = r9
+    /*  1895 */ r1 += r5   ///  r1 = r1.wrapping_add(r5)
+    /*  1896 */ r8 = *(r10 - 0x8) as u64   ///  r8 = *((r10 as i64).wrapping_add(-8) as *const u64) as u64
+    /*  1897 */ r2 = r8
+    /*  1898 */ r2 &= 3   ///  r2 = r2 & (3_i32 as i64 as u64)
+    /*  1899 */ r5 = r3
+    /*  1900 */ r5 -= r8   ///  r5 = r5.wrapping_sub(r8)
+    /*  1901 */ r7 = r4
+    /*  1902 */ *(r10 - 0x10) = r9 as u64   ///  *((r10 as i64).wrapping_add(-16) as *mut u64) = r9 as u64
+    /*  1903 */ r9 = r3
+    /*  1904 */ r3 load str located at 71777214294589695
+    /*  1906 */ r7 &= r3   ///  r7 = r7 & r3
+    /*  1907 */ r4 >>= 8   ///  r4 = r4.wrapping_shr(8)
+    /*  1908 */ r4 &= r3   ///  r4 = r4 & r3
+    /*  1909 */ r4 += r7   ///  r4 = r4.wrapping_add(r7)
+    /*  1910 */ r7 load str located at 281479271743489
+    /*  1912 */ r4 *= r7   ///  r4 = r4.wrapping_mul(r7)
+    /*  1913 */ r4 >>= 48   ///  r4 = r4.wrapping_shr(48)
+    /*  1914 */ r4 += r0   ///  r4 = r4.wrapping_add(r0)
+    /*  1915 */ r0 = r4
+    /*  1916 */ if r2 == (0_i32 as i64 as u64) { pc += 9 }
+    // lbb_1917:
+    /*  1917 */ r0 = 0_i32 as i64 as u64
+    /*  1918 */ r6 = *(r10 - 0x10) as u64   ///  r6 = *((r10 as i64).wrapping_add(-16) as *const u64) as u64
+    /*  1919 */ if r2 == (0_i32 as i64 as u64) { pc += 57 }
+    // lbb_1920:
+    /*  1920 */ r8 &= 252   ///  r8 = r8 & (252_i32 as i64 as u64)
+    /*  1921 */ r8 <<= 3   ///  r8 = r8.wrapping_shl(3)
+    /*  1922 */ r1 = 192_i32 as i64 as u64
+    /*  1923 */ if r1 > r9 { pc += 34 }
+    // lbb_1924:
+    /*  1924 */ r9 = 192_i32 as i64 as u64
+    /*  1925 */ if true { pc += 32 }
+    // lbb_1926:
+    /*  1926 */ r3 = r5
+    /*  1927 */ r9 = r1
+    /*  1928 */ if r3 == (0_i32 as i64 as u64) { pc += 60 }
+    // lbb_1929:
+    /*  1929 */ r1 = 192_i32 as i64 as u64
+    /*  1930 */ r2 = r3
+    /*  1931 */ if r1 > r3 { pc += 1 }
+    // lbb_1932:
+    /*  1932 */ r2 = 192_i32 as i64 as u64
+    // lbb_1933:
+    /*  1933 */ r5 = r2
+    /*  1934 */ r5 <<= 3   ///  r5 = r5.wrapping_shl(3)
+    /*  1935 */ r4 = 0_i32 as i64 as u64
+    /*  1936 */ r1 = 4_i32 as i64 as u64
+    /*  1937 */ *(r10 - 0x8) = r2 as u64   ///  *((r10 as i64).wrapping_add(-8) as *mut u64) = r2 as u64
+    /*  1938 */ if r1 > r2 { pc += -45 }
+    // lbb_1939:
+    /*  1939 */ r2 = r5
+    /*  1940 */ r2 &= 2016   ///  r2 = r2 & (2016_i32 as i64 as u64)
+    /*  1941 */ r1 = r9
+    /*  1942 */ r1 += r2   ///  r1 = r1.wrapping_add(r2)
+    /*  1943 */ r2 = r9
+    /*  1944 */ if true { pc += -85 }
+    // lbb_1945:
+    /*  1945 */ r0 = 0_i32 as i64 as u64
+    /*  1946 */ if r2 == (0_i32 as i64 as u64) { pc += 42 }
+    // lbb_1947:
+    /*  1947 */ r4 = *(r1 + 0x0) as u8   ///  r4 = *((r1 as i64).wrapping_add(0) as *const u8) as u64
+    /*  1948 */ r4 <<= 56   ///  r4 = r4.wrapping_shl(56)
+    /*  1949 */ r4 >>= 56 (signed)   ///  r4 = (r4 as i64).wrapping_shr(56)
+    /*  1950 */ r3 = 1_i32 as i64 as u64
+    /*  1951 */ if (r4 as i64) > (-65 as i32 as i64) { pc += 1 }
+    // lbb_1952:
+    /*  1952 */ r3 = 0_i32 as i64 as u64
+    // lbb_1953:
+    /*  1953 */ r0 += r3   ///  r0 = r0.wrapping_add(r3)
+    /*  1954 */ r1 += 1   ///  r1 = r1.wrapping_add(1_i32 as i64 as u64)
+    /*  1955 */ r2 += -1   ///  r2 = r2.wrapping_add(-1_i32 as i64 as u64)
+    /*  1956 */ if r2 == (0_i32 as i64 as u64) { pc += 32 }
+    // lbb_1957:
+    /*  1957 */ if true { pc += -11 }
+    // lbb_1958:
+    /*  1958 */ r6 += r8   ///  r6 = r6.wrapping_add(r8)
+    /*  1959 */ r2 = 0_i32 as i64 as u64
+    /*  1960 */ r9 &= 3   ///  r9 = r9 & (3_i32 as i64 as u64)
+    /*  1961 */ r9 <<= 3   ///  r9 = r9.wrapping_shl(3)
+    /*  1962 */ r1 load str located at 72340172838076673
+    // lbb_1964:
+    /*  1964 */ r0 = *(r6 + 0x0) as u64   ///  r0 = *((r6 as i64).wrapping_add(0) as *const u64) as u64
+    /*  1965 */ r5 = r0
+    /*  1966 */ r5 >>= 6   ///  r5 = r5.wrapping_shr(6)
+    /*  1967 */ r0 ^= -1   ///  r0 = r0 ^ (-1_i32 as i64 as u64)
+    /*  1968 */ r0 >>= 7   ///  r0 = r0.wrapping_shr(7)
+    /*  1969 */ r0 |= r5   ///  r0 = r0 | r5
+    /*  1970 */ r0 &= r1   ///  r0 = r0 & r1
+    /*  1971 */ r0 += r2   ///  r0 = r0.wrapping_add(r2)
+    /*  1972 */ r6 += 8   ///  r6 = r6.wrapping_add(8_i32 as i64 as u64)
+    /*  1973 */ r9 += -8   ///  r9 = r9.wrapping_add(-8_i32 as i64 as u64)
+    /*  1974 */ r2 = r0
+    /*  1975 */ if r9 == (0_i32 as i64 as u64) { pc += 1 }
+    // lbb_1976:
+    /*  1976 */ if true { pc += -13 }
+    // lbb_1977:
+    /*  1977 */ r1 load str located at 71777214294589695
+    /*  1979 */ r2 = r0
+    /*  1980 */ r2 &= r1   ///  r2 = r2 & r1
+    /*  1981 */ r0 >>= 8   ///  r0 = r0.wrapping_shr(8)
+    /*  1982 */ r0 &= r1   ///  r0 = r0 & r1
+    /*  1983 */ r0 += r2   ///  r0 = r0.wrapping_add(r2)
+    /*  1984 */ r1 load str located at 281479271743489
+    /*  1986 */ r0 *= r1   ///  r0 = r0.wrapping_mul(r1)
+    /*  1987 */ r0 >>= 48   ///  r0 = r0.wrapping_shr(48)
+    /*  1988 */ r0 += r4   ///  r0 = r0.wrapping_add(r4)
+    // lbb_1989:
+    /*  1989 */ return r0
+}
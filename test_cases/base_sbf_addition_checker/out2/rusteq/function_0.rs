@@ -0,0 +1,7 @@
+// function_0 (pc 0 to 2)
+fn function_0(r1: u64) -> u64 {
+    /*     0 */ r1 = *(r1 + 0x0) as u64   ///  r1 = *((r1 as i64).wrapping_add(0) as *const u64) as u64
+    /*     1 */ r0 = fn_0xf4a1c22d(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xf4a1c22d
+    // lbb_2:
+    /*     2 */ return r0
+}
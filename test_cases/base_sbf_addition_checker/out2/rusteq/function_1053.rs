@@ -0,0 +1,4 @@
+// function_1053 (pc 1053 to 1053)
+fn function_1053() -> u64 {
+    /*  1053 */ return r0
+}
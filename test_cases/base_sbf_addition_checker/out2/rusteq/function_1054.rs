@@ -0,0 +1,8 @@
+// function_1054 (pc 1054 to 1060)
+fn function_1054(r1: u64) -> u64 {
+    /*  1054 */ r2 load str located at -4683688258424109734
+    /*  1056 */ *(r1 + 0x8) = r2 as u64   ///  *((r1 as i64).wrapping_add(8) as *mut u64) = r2 as u64
+    /*  1057 */ r2 load str located at -666276445414819638
+    /*  1059 */ *(r1 + 0x0) = r2 as u64   ///  *((r1 as i64).wrapping_add(0) as *mut u64) = r2 as u64
+    /*  1060 */ return r0
+}
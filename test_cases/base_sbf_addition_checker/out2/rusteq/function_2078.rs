@@ -0,0 +1,9 @@
+// function_2078 (pc 2078 to 2082)
+fn function_2078(r1: u64, r2: u64) -> u64 {
+    /*  2078 */ r3 = r2
+    /*  2079 */ r1 = *(r1 + 0x0) as u32   ///  r1 = *((r1 as i64).wrapping_add(0) as *const u32) as u64
+    /*  2080 */ r2 = 1_i32 as i64 as u64
+    /*  2081 */ r0 = fn_0x704e6372(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x704e6372
+    // lbb_2082:
+    /*  2082 */ return r0
+}
@@ -0,0 +1,10 @@
+// function_2094 (pc 2094 to 2099)
+fn function_2094(r1: u64, r2: u64) -> u64 {
+    /*  2094 */ r4 = r2
+    /*  2095 */ r3 = *(r1 + 0x8) as u64   ///  r3 = *((r1 as i64).wrapping_add(8) as *const u64) as u64
+    /*  2096 */ r2 = *(r1 + 0x0) as u64   ///  r2 = *((r1 as i64).wrapping_add(0) as *const u64) as u64
+    /*  2097 */ r1 = r4
+    /*  2098 */ r0 = fn_0x16ef43c9(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x16ef43c9
+    // lbb_2099:
+    /*  2099 */ return r0
+}
@@ -0,0 +1,11 @@
+// function_684 (pc 684 to 689)
+fn function_684(r1: u64) -> u64 {
+    /*   684 */ r2 = *(r1 + 0x8) as u64   ///  r2 = *((r1 as i64).wrapping_add(8) as *const u64) as u64
+    /*   685 */ if r2 == (0_i32 as i64 as u64) { pc += 3 }
+    // lbb_686:
+    /*   686 */ r1 = *(r1 + 0x0) as u64   ///  r1 = *((r1 as i64).wrapping_add(0) as *const u64) as u64
+    /*   687 */ r3 = 1_i32 as i64 as u64
+    /*   688 */ r0 = fn_0xb0572554(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xb0572554
+    // lbb_689:
+    /*   689 */ return r0
+}
@@ -0,0 +1,44 @@
+// memcpy (pc 2100 to 2133)
+fn memcpy(r1: u64, r2: u64, r3: u64) -> u64 {
+    /*  2100 */ r6 = r1
+    /*  2101 */ r4 = r3
+    /*  2102 */ r4 >>= 3   ///  r4 = r4.wrapping_shr(3)
+    /*  2103 */ r1 = r4
+    /*  2104 */ r1 *= -7   ///  r1 = r1.wrapping_mul(-7 as u64)
+    /*  2105 */ r1 += r3   ///  r1 = r1.wrapping_add(r3)
+    /*  2106 */ if r1 > (15_i32 as i64 as u64) { pc += 24 }
+    // lbb_2107:
+    /*  2107 */ r1 = 0_i32 as i64 as u64
+    /*  2108 */ r5 = 8_i32 as i64 as u64
+    /*  2109 */ if r5 > r3 { pc += 10 }
+    // lbb_2110:
+    /*  2110 */ r5 = 0_i32 as i64 as u64
+    // lbb_2111:
+    /*  2111 */ r0 = r6
+    /*  2112 */ r0 += r1   ///  r0 = r0.wrapping_add(r1)
+    /*  2113 */ r7 = r2
+    /*  2114 */ r7 += r1   ///  r7 = r7.wrapping_add(r1)
+    /*  2115 */ r7 = *(r7 + 0x0) as u64   ///  r7 = *((r7 as i64).wrapping_add(0) as *const u64) as u64
+    /*  2116 */ *(r0 + 0x0) = r7 as u64   ///  *((r0 as i64).wrapping_add(0) as *mut u64) = r7 as u64
+    /*  2117 */ r1 += 8   ///  r1 = r1.wrapping_add(8_i32 as i64 as u64)
+    /*  2118 */ r5 += 1   ///  r5 = r5.wrapping_add(1_i32 as i64 as u64)
+    /*  2119 */ if r4 > r5 { pc += -9 }
+    // lbb_2120:
+    /*  2120 */ if (r1 as i64) >= (r3 as i64) { pc += 8 }
+    // lbb_2121:
+    /*  2121 */ r4 = r6
+    /*  2122 */ r4 += r1   ///  r4 = r4.wrapping_add(r1)
+    /*  2123 */ r5 = r2
+    /*  2124 */ r5 += r1   ///  r5 = r5.wrapping_add(r1)
+    /*  2125 */ r5 = *(r5 + 0x0) as u8   ///  r5 = *((r5 as i64).wrapping_add(0) as *const u8) as u64
+    /*  2126 */ *(r4 + 0x0) = r5 as u8   ///  *((r4 as i64).wrapping_add(0) as *mut u8) = r5 as u8
+    /*  2127 */ r1 += 1   ///  r1 = r1.wrapping_add(1_i32 as i64 as u64)
+    /*  2128 */ if (r3 as i64) > (r1 as i64) { pc += -8 }
+    // lbb_2129:
+    /*  2129 */ r0 = r6
+    /*  2130 */ return r0
+    // lbb_2131:
+    /*  2131 */ r1 = r6
+    /*  2132 */ r0 = sol_memcpy_(r1, r2, r3)
+    /*  2133 */ if true { pc += -5 }
+}
@@ -0,0 +1,295 @@
+// solana_program::entrypoint::deserialize::h7b1132848611f500 (pc 308 to 553)
+fn solana_program::entrypoint::deserialize::h7b1132848611f500(r1: u64, r2: u64) -> u64 {
+    /*   308 */ r4 = r2
+    /*   309 */ *(r10 - 0x68) = r1 as u64   ///  *((r10 as i64).wrapping_add(-104) as *mut u64) = r1 as u64
+    /*   310 */ r6 = *(r4 + 0x0) as u64   ///  r6 = *((r4 as i64).wrapping_add(0) as *const u64) as u64
+    /*   311 */ if r6 != (0_i32 as i64 as u64) { pc += 24 }
+    // lbb_312:
+    /*   312 */ r1 = 0_i32 as i64 as u64
+    /*   313 */ *(r10 - 0x10) = r1 as u64   ///  *((r10 as i64).wrapping_add(-16) as *mut u64) = r1 as u64
+    /*   314 */ *(r10 - 0x18) = r6 as u64   ///  *((r10 as i64).wrapping_add(-24) as *mut u64) = r6 as u64
+    /*   315 */ r8 = 8_i32 as i64 as u64
+    /*   316 */ *(r10 - 0x20) = r8 as u64   ///  *((r10 as i64).wrapping_add(-32) as *mut u64) = r8 as u64
+    // lbb_317:
+    /*   317 */ r1 = r4
+    /*   318 */ r1 += r8   ///  r1 = r1.wrapping_add(r8)
+    /*   319 */ r1 = *(r1 + 0x0) as u64   ///  r1 = *((r1 as i64).wrapping_add(0) as *const u64) as u64
+    /*   320 */ r2 = *(r10 - 0x10) as u64   ///  r2 = *((r10 as i64).wrapping_add(-16) as *const u64) as u64
+    /*   321 */ r3 = *(r10 - 0x68) as u64   ///  r3 = *((r10 as i64).wrapping_add(-104) as *const u64) as u64
+    /*   322 */ *(r3 + 0x18) = r2 as u64   ///  *((r3 as i64).wrapping_add(24) as *mut u64) = r2 as u64
+    /*   323 */ r2 = *(r10 - 0x18) as u64   ///  r2 = *((r10 as i64).wrapping_add(-24) as *const u64) as u64
+    /*   324 */ *(r3 + 0x10) = r2 as u64   ///  *((r3 as i64).wrapping_add(16) as *mut u64) = r2 as u64
+    /*   325 */ r2 = *(r10 - 0x20) as u64   ///  r2 = *((r10 as i64).wrapping_add(-32) as *const u64) as u64
+    /*   326 */ *(r3 + 0x8) = r2 as u64   ///  *((r3 as i64).wrapping_add(8) as *mut u64) = r2 as u64
+    /*   327 */ r8 += 8   ///  r8 = r8.wrapping_add(8_i32 as i64 as u64)
+    /*   328 */ r2 = r4
+    /*   329 */ r2 += r8   ///  r2 = r2.wrapping_add(r8)
+    /*   330 */ *(r3 + 0x20) = r2 as u64   ///  *((r3 as i64).wrapping_add(32) as *mut u64) = r2 as u64
+    /*   331 */ *(r3 + 0x28) = r1 as u64   ///  *((r3 as i64).wrapping_add(40) as *mut u64) = r1 as u64
+    /*   332 */ r1 += r8   ///  r1 = r1.wrapping_add(r8)
+    /*   333 */ r4 += r1   ///  r4 = r4.wrapping_add(r1)
+    /*   334 */ *(r3 + 0x0) = r4 as u64   ///  *((r3 as i64).wrapping_add(0) as *mut u64) = r4 as u64
+    /*   335 */ return r0
+    // lbb_336:
+    /*   336 */ r1 load str located at 192153584101141163
+    /*   338 */ if r1 > r6 { pc += 2 }
+    // lbb_339:
+    /*   339 */ r0 = fn_0xa8220fca(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xa8220fca
+    // lbb_340:
+    /*   340 */ abort()
+    // lbb_341:
+    /*   341 */ r7 = r6
+    /*   342 */ r7 *= 48   ///  r7 = r7.wrapping_mul(48 as u64)
+    /*   343 */ r8 = 8_i32 as i64 as u64
+    /*   344 */ r0 = 8_i32 as i64 as u64
+    /*   345 */ *(r10 - 0x30) = r4 as u64   ///  *((r10 as i64).wrapping_add(-48) as *mut u64) = r4 as u64
+    /*   346 */ if r7 == (0_i32 as i64 as u64) { pc += 9 }
+    // lbb_347:
+    /*   347 */ r1 = r7
+    /*   348 */ r2 = 8_i32 as i64 as u64
+    /*   349 */ r0 = fn_0xd203373a(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xd203373a
+    // lbb_350:
+    /*   350 */ r4 = *(r10 - 0x30) as u64   ///  r4 = *((r10 as i64).wrapping_add(-48) as *const u64) as u64
+    /*   351 */ if r0 != (0_i32 as i64 as u64) { pc += 4 }
+    // lbb_352:
+    /*   352 */ r1 = 8_i32 as i64 as u64
+    /*   353 */ r2 = r7
+    /*   354 */ r0 = fn_0xdeeafbd2(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xdeeafbd2
+    // lbb_355:
+    /*   355 */ abort()
+    // lbb_356:
+    /*   356 */ *(r10 - 0x18) = r6 as u64   ///  *((r10 as i64).wrapping_add(-24) as *mut u64) = r6 as u64
+    /*   357 */ *(r10 - 0x20) = r0 as u64   ///  *((r10 as i64).wrapping_add(-32) as *mut u64) = r0 as u64
+    /*   358 */ r1 = 0_i32 as i64 as u64
+    /*   359 */ *(r10 - 0x10) = r1 as u64   ///  *((r10 as i64).wrapping_add(-16) as *mut u64) = r1 as u64
+    /*   360 */ r2 = 0_i32 as i64 as u64
+    /*   361 */ r3 = 0_i32 as i64 as u64
+    /*   362 */ *(r10 - 0x60) = r6 as u64   ///  *((r10 as i64).wrapping_add(-96) as *mut u64) = r6 as u64
+    /*   363 */ if true { pc += 29 }
+    // lbb_364:
+    /*   364 */ r1 = r2
+    /*   365 */ r1 *= 48   ///  r1 = r1.wrapping_mul(48 as u64)
+    /*   366 */ r3 = r0
+    /*   367 */ r3 += r1   ///  r3 = r3.wrapping_add(r1)
+    /*   368 */ r1 = *(r10 - 0x58) as u64   ///  r1 = *((r10 as i64).wrapping_add(-88) as *const u64) as u64
+    /*   369 */ *(r3 + 0x2a) = r1 as u8   ///  *((r3 as i64).wrapping_add(42) as *mut u8) = r1 as u8
+    /*   370 */ r1 = *(r10 - 0x50) as u64   ///  r1 = *((r10 as i64).wrapping_add(-80) as *const u64) as u64
+    /*   371 */ *(r3 + 0x29) = r1 as u8   ///  *((r3 as i64).wrapping_add(41) as *mut u8) = r1 as u8
+    /*   372 */ r1 = *(r10 - 0x48) as u64   ///  r1 = *((r10 as i64).wrapping_add(-72) as *const u64) as u64
+    /*   373 */ *(r3 + 0x28) = r1 as u8   ///  *((r3 as i64).wrapping_add(40) as *mut u8) = r1 as u8
+    /*   374 */ r1 = *(r10 - 0x40) as u64   ///  r1 = *((r10 as i64).wrapping_add(-64) as *const u64) as u64
+    /*   375 */ *(r3 + 0x20) = r1 as u64   ///  *((r3 as i64).wrapping_add(32) as *mut u64) = r1 as u64
+    /*   376 */ r1 = *(r10 - 0x38) as u64   ///  r1 = *((r10 as i64).wrapping_add(-56) as *const u64) as u64
+    /*   377 */ *(r3 + 0x18) = r1 as u64   ///  *((r3 as i64).wrapping_add(24) as *mut u64) = r1 as u64
+    /*   378 */ *(r3 + 0x10) = r9 as u64   ///  *((r3 as i64).wrapping_add(16) as *mut u64) = r9 as u64
+    /*   379 */ *(r3 + 0x8) = r6 as u64   ///  *((r3 as i64).wrapping_add(8) as *mut u64) = r6 as u64
+    /*   380 */ *(r3 + 0x0) = r7 as u64   ///  *((r3 as i64).wrapping_add(0) as *mut u64) = r7 as u64
+    /*   381 */ r1 = *(r10 - 0x5) as u32   ///  r1 = *((r10 as i64).wrapping_add(-5) as *const u32) as u64
+    /*   382 */ *(r3 + 0x2b) = r1 as u32   ///  *((r3 as i64).wrapping_add(43) as *mut u32) = r1 as u32
+    /*   383 */ r1 = *(r10 - 0x1) as u8   ///  r1 = *((r10 as i64).wrapping_add(-1) as *const u8) as u64
+    /*   384 */ *(r3 + 0x2f) = r1 as u8   ///  *((r3 as i64).wrapping_add(47) as *mut u8) = r1 as u8
+    // lbb_385:
+    /*   385 */ r2 += 1   ///  r2 = r2.wrapping_add(1_i32 as i64 as u64)
+    /*   386 */ *(r10 - 0x10) = r2 as u64   ///  *((r10 as i64).wrapping_add(-16) as *mut u64) = r2 as u64
+    /*   387 */ r3 = *(r10 - 0x28) as u64   ///  r3 = *((r10 as i64).wrapping_add(-40) as *const u64) as u64
+    /*   388 */ r3 += 1   ///  r3 = r3.wrapping_add(1_i32 as i64 as u64)
+    /*   389 */ r8 += 8   ///  r8 = r8.wrapping_add(8_i32 as i64 as u64)
+    /*   390 */ r1 = *(r10 - 0x60) as u64   ///  r1 = *((r10 as i64).wrapping_add(-96) as *const u64) as u64
+    /*   391 */ if r1 > r3 { pc += 1 }
+    // lbb_392:
+    /*   392 */ if true { pc += -76 }
+    // lbb_393:
+    /*   393 */ r1 = r4
+    /*   394 */ r1 += r8   ///  r1 = r1.wrapping_add(r8)
+    /*   395 */ r1 = *(r1 + 0x0) as u8   ///  r1 = *((r1 as i64).wrapping_add(0) as *const u8) as u64
+    /*   396 */ *(r10 - 0x28) = r3 as u64   ///  *((r10 as i64).wrapping_add(-40) as *mut u64) = r3 as u64
+    /*   397 */ if r1 == (255_i32 as i64 as u64) { pc += 51 }
+    // lbb_398:
+    /*   398 */ if r2 > r1 { pc += 1 }
+    // lbb_399:
+    /*   399 */ if true { pc += 150 }
+    // lbb_400:
+    /*   400 */ r5 = r2
+    /*   401 */ r1 *= 48   ///  r1 = r1.wrapping_mul(48 as u64)
+    /*   402 */ r2 = r0
+    /*   403 */ r2 += r1   ///  r2 = r2.wrapping_add(r1)
+    /*   404 */ r6 = *(r2 + 0x8) as u64   ///  r6 = *((r2 as i64).wrapping_add(8) as *const u64) as u64
+    /*   405 */ r3 = *(r6 + 0x0) as u64   ///  r3 = *((r6 as i64).wrapping_add(0) as *const u64) as u64
+    /*   406 */ r3 += 1   ///  r3 = r3.wrapping_add(1_i32 as i64 as u64)
+    /*   407 */ r4 = 1_i32 as i64 as u64
+    /*   408 */ if r3 == (0_i32 as i64 as u64) { pc += 1 }
+    // lbb_409:
+    /*   409 */ r4 = 0_i32 as i64 as u64
+    // lbb_410:
+    /*   410 */ r7 = *(r2 + 0x0) as u64   ///  r7 = *((r2 as i64).wrapping_add(0) as *const u64) as u64
+    /*   411 */ *(r6 + 0x0) = r3 as u64   ///  *((r6 as i64).wrapping_add(0) as *mut u64) = r3 as u64
+    /*   412 */ if r4 != (1_i32 as i64 as u64) { pc += 2 }
+    // lbb_413:
+    /*   413 */ abort()
+    /*   414 */ abort()
+    // lbb_415:
+    /*   415 */ r2 = r0
+    /*   416 */ r2 += r1   ///  r2 = r2.wrapping_add(r1)
+    /*   417 */ r9 = *(r2 + 0x10) as u64   ///  r9 = *((r2 as i64).wrapping_add(16) as *const u64) as u64
+    /*   418 */ r2 = *(r9 + 0x0) as u64   ///  r2 = *((r9 as i64).wrapping_add(0) as *const u64) as u64
+    /*   419 */ r2 += 1   ///  r2 = r2.wrapping_add(1_i32 as i64 as u64)
+    /*   420 */ r3 = 1_i32 as i64 as u64
+    /*   421 */ if r2 == (0_i32 as i64 as u64) { pc += 1 }
+    // lbb_422:
+    /*   422 */ r3 = 0_i32 as i64 as u64
+    // lbb_423:
+    /*   423 */ *(r9 + 0x0) = r2 as u64   ///  *((r9 as i64).wrapping_add(0) as *mut u64) = r2 as u64
+    /*   424 */ if r3 != (1_i32 as i64 as u64) { pc += 1 }
+    // lbb_425:
+    /*   425 */ if true { pc += -13 }
+    // lbb_426:
+    /*   426 */ r2 = r0
+    /*   427 */ r2 += r1   ///  r2 = r2.wrapping_add(r1)
+    /*   428 */ r1 = *(r2 + 0x2a) as u8   ///  r1 = *((r2 as i64).wrapping_add(42) as *const u8) as u64
+    /*   429 */ *(r10 - 0x58) = r1 as u64   ///  *((r10 as i64).wrapping_add(-88) as *mut u64) = r1 as u64
+    /*   430 */ r1 = *(r2 + 0x29) as u8   ///  r1 = *((r2 as i64).wrapping_add(41) as *const u8) as u64
+    /*   431 */ *(r10 - 0x50) = r1 as u64   ///  *((r10 as i64).wrapping_add(-80) as *mut u64) = r1 as u64
+    /*   432 */ r1 = *(r2 + 0x28) as u8   ///  r1 = *((r2 as i64).wrapping_add(40) as *const u8) as u64
+    /*   433 */ *(r10 - 0x48) = r1 as u64   ///  *((r10 as i64).wrapping_add(-72) as *mut u64) = r1 as u64
+    /*   434 */ r1 = *(r2 + 0x20) as u64   ///  r1 = *((r2 as i64).wrapping_add(32) as *const u64) as u64
+    /*   435 */ *(r10 - 0x40) = r1 as u64   ///  *((r10 as i64).wrapping_add(-64) as *mut u64) = r1 as u64
+    /*   436 */ r1 = *(r2 + 0x18) as u64   ///  r1 = *((r2 as i64).wrapping_add(24) as *const u64) as u64
+    /*   437 */ *(r10 - 0x38) = r1 as u64   ///  *((r10 as i64).wrapping_add(-56) as *mut u64) = r1 as u64
+    /*   438 */ r1 = *(r10 - 0x18) as u64   ///  r1 = *((r10 as i64).wrapping_add(-24) as *const u64) as u64
+    /*   439 */ r2 = r5
+    /*   440 */ r4 = *(r10 - 0x30) as u64   ///  r4 = *((r10 as i64).wrapping_add(-48) as *const u64) as u64
+    /*   441 */ if r2 != r1 { pc += -78 }
+    // lbb_442:
+    /*   442 */ r1 = r10
+    /*   443 */ r1 += -32   ///  r1 = r1.wrapping_add(-32_i32 as i64 as u64)
+    /*   444 */ r0 = fn_0xb2ab22aa(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xb2ab22aa
+    // lbb_445:
+    /*   445 */ r4 = *(r10 - 0x30) as u64   ///  r4 = *((r10 as i64).wrapping_add(-48) as *const u64) as u64
+    /*   446 */ r0 = *(r10 - 0x20) as u64   ///  r0 = *((r10 as i64).wrapping_add(-32) as *const u64) as u64
+    /*   447 */ r2 = *(r10 - 0x10) as u64   ///  r2 = *((r10 as i64).wrapping_add(-16) as *const u64) as u64
+    /*   448 */ if true { pc += -85 }
+    // lbb_449:
+    /*   449 */ *(r10 - 0x38) = r2 as u64   ///  *((r10 as i64).wrapping_add(-56) as *mut u64) = r2 as u64
+    /*   450 */ r6 = r8
+    /*   451 */ r6 += r4   ///  r6 = r6.wrapping_add(r4)
+    /*   452 */ r1 = *(r6 + 0x3) as u8   ///  r1 = *((r6 as i64).wrapping_add(3) as *const u8) as u64
+    /*   453 */ *(r10 - 0x50) = r1 as u64   ///  *((r10 as i64).wrapping_add(-80) as *mut u64) = r1 as u64
+    /*   454 */ r1 = *(r6 + 0x2) as u8   ///  r1 = *((r6 as i64).wrapping_add(2) as *const u8) as u64
+    /*   455 */ *(r10 - 0x48) = r1 as u64   ///  *((r10 as i64).wrapping_add(-72) as *mut u64) = r1 as u64
+    /*   456 */ r1 = *(r6 + 0x1) as u8   ///  r1 = *((r6 as i64).wrapping_add(1) as *const u8) as u64
+    /*   457 */ *(r10 - 0x40) = r1 as u64   ///  *((r10 as i64).wrapping_add(-64) as *mut u64) = r1 as u64
+    /*   458 */ r1 = 32_i32 as i64 as u64
+    /*   459 */ r2 = 8_i32 as i64 as u64
+    /*   460 */ r0 = fn_0xd203373a(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xd203373a
+    // lbb_461:
+    /*   461 */ if r0 != (0_i32 as i64 as u64) { pc += 4 }
+    // lbb_462:
+    /*   462 */ r1 = 8_i32 as i64 as u64
+    /*   463 */ r2 = 32_i32 as i64 as u64
+    /*   464 */ r0 = fn_0xdeeafbd2(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xdeeafbd2
+    // lbb_465:
+    /*   465 */ abort()
+    // lbb_466:
+    /*   466 */ r1 = 0_i32 as i64 as u64
+    /*   467 */ *(r0 + 0x10) = r1 as u64   ///  *((r0 as i64).wrapping_add(16) as *mut u64) = r1 as u64
+    /*   468 */ r1 = 1_i32 as i64 as u64
+    /*   469 */ *(r0 + 0x8) = r1 as u64   ///  *((r0 as i64).wrapping_add(8) as *mut u64) = r1 as u64
+    /*   470 */ *(r0 + 0x0) = r1 as u64   ///  *((r0 as i64).wrapping_add(0) as *mut u64) = r1 as u64
+    /*   471 */ r1 = r6
+    /*   472 */ r1 += 72   ///  r1 = r1.wrapping_add(72_i32 as i64 as u64)
+    /*   473 */ *(r10 - 0x58) = r0 as u64   ///  *((r10 as i64).wrapping_add(-88) as *mut u64) = r0 as u64
+    /*   474 */ *(r0 + 0x18) = r1 as u64   ///  *((r0 as i64).wrapping_add(24) as *mut u64) = r1 as u64
+    /*   475 */ r9 = *(r6 + 0x50) as u64   ///  r9 = *((r6 as i64).wrapping_add(80) as *const u64) as u64
+    /*   476 */ *(r6 + 0x4) = r9 as u32   ///  *((r6 as i64).wrapping_add(4) as *mut u32) = r9 as u32
+    /*   477 */ r1 = 40_i32 as i64 as u64
+    /*   478 */ r2 = 8_i32 as i64 as u64
+    /*   479 */ r0 = fn_0xd203373a(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xd203373a
+    // lbb_480:
+    /*   480 */ r7 = r0
+    /*   481 */ if r7 != (0_i32 as i64 as u64) { pc += 4 }
+    // lbb_482:
+    /*   482 */ r1 = 8_i32 as i64 as u64
+    /*   483 */ r2 = 40_i32 as i64 as u64
+    /*   484 */ r0 = fn_0xdeeafbd2(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xdeeafbd2
+    // lbb_485:
+    /*   485 */ abort()
+    // lbb_486:
+    /*   486 */ r1 = *(r10 - 0x50) as u64   ///  r1 = *((r10 as i64).wrapping_add(-80) as *const u64) as u64
+    /*   487 */ r3 = r1
+    /*   488 */ r2 = 0_i32 as i64 as u64
+    /*   489 */ r1 = 1_i32 as i64 as u64
+    /*   490 */ r5 = 1_i32 as i64 as u64
+    /*   491 */ r4 = *(r10 - 0x30) as u64   ///  r4 = *((r10 as i64).wrapping_add(-48) as *const u64) as u64
+    /*   492 */ if r3 != (0_i32 as i64 as u64) { pc += 1 }
+    // lbb_493:
+    /*   493 */ r5 = 0_i32 as i64 as u64
+    // lbb_494:
+    /*   494 */ r3 = *(r10 - 0x48) as u64   ///  r3 = *((r10 as i64).wrapping_add(-72) as *const u64) as u64
+    /*   495 */ *(r7 + 0x10) = r2 as u64   ///  *((r7 as i64).wrapping_add(16) as *mut u64) = r2 as u64
+    /*   496 */ r2 = 1_i32 as i64 as u64
+    /*   497 */ if r3 != (0_i32 as i64 as u64) { pc += 1 }
+    // lbb_498:
+    /*   498 */ r2 = 0_i32 as i64 as u64
+    // lbb_499:
+    /*   499 */ *(r10 - 0x48) = r2 as u64   ///  *((r10 as i64).wrapping_add(-72) as *mut u64) = r2 as u64
+    /*   500 */ r2 = *(r10 - 0x40) as u64   ///  r2 = *((r10 as i64).wrapping_add(-64) as *const u64) as u64
+    /*   501 */ r3 = 1_i32 as i64 as u64
+    /*   502 */ if r2 != (0_i32 as i64 as u64) { pc += 1 }
+    // lbb_503:
+    /*   503 */ r3 = 0_i32 as i64 as u64
+    // lbb_504:
+    /*   504 */ *(r10 - 0x40) = r3 as u64   ///  *((r10 as i64).wrapping_add(-64) as *mut u64) = r3 as u64
+    /*   505 */ r2 = r6
+    /*   506 */ r2 += 40   ///  r2 = r2.wrapping_add(40_i32 as i64 as u64)
+    /*   507 */ *(r10 - 0x50) = r2 as u64   ///  *((r10 as i64).wrapping_add(-80) as *mut u64) = r2 as u64
+    /*   508 */ r6 += 8   ///  r6 = r6.wrapping_add(8_i32 as i64 as u64)
+    /*   509 */ *(r7 + 0x8) = r1 as u64   ///  *((r7 as i64).wrapping_add(8) as *mut u64) = r1 as u64
+    /*   510 */ *(r7 + 0x0) = r1 as u64   ///  *((r7 as i64).wrapping_add(0) as *mut u64) = r1 as u64
+    /*   511 */ r1 = r8
+    /*   512 */ r1 += r4   ///  r1 = r1.wrapping_add(r4)
+    /*   513 */ r1 += 88   ///  r1 = r1.wrapping_add(88_i32 as i64 as u64)
+    /*   514 */ *(r7 + 0x18) = r1 as u64   ///  *((r7 as i64).wrapping_add(24) as *mut u64) = r1 as u64
+    /*   515 */ *(r7 + 0x20) = r9 as u64   ///  *((r7 as i64).wrapping_add(32) as *mut u64) = r9 as u64
+    /*   516 */ r8 += r9   ///  r8 = r8.wrapping_add(r9)
+    /*   517 */ r8 += 10335   ///  r8 = r8.wrapping_add(10335_i32 as i64 as u64)
+    /*   518 */ r8 &= -8   ///  r8 = r8 & (-8_i32 as i64 as u64)
+    /*   519 */ r1 = r4
+    /*   520 */ r1 += r8   ///  r1 = r1.wrapping_add(r8)
+    /*   521 */ r9 = *(r1 + 0x0) as u64   ///  r9 = *((r1 as i64).wrapping_add(0) as *const u64) as u64
+    /*   522 */ r1 = *(r10 - 0x18) as u64   ///  r1 = *((r10 as i64).wrapping_add(-24) as *const u64) as u64
+    /*   523 */ r2 = *(r10 - 0x38) as u64   ///  r2 = *((r10 as i64).wrapping_add(-56) as *const u64) as u64
+    /*   524 */ if r2 != r1 { pc += 7 }
+    // lbb_525:
+    /*   525 */ r1 = r10
+    /*   526 */ r1 += -32   ///  r1 = r1.wrapping_add(-32_i32 as i64 as u64)
+    /*   527 */ *(r10 - 0x38) = r5 as u64   ///  *((r10 as i64).wrapping_add(-56) as *mut u64) = r5 as u64
+    /*   528 */ r0 = fn_0xb2ab22aa(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xb2ab22aa
+    // lbb_529:
+    /*   529 */ r5 = *(r10 - 0x38) as u64   ///  r5 = *((r10 as i64).wrapping_add(-56) as *const u64) as u64
+    /*   530 */ r4 = *(r10 - 0x30) as u64   ///  r4 = *((r10 as i64).wrapping_add(-48) as *const u64) as u64
+    /*   531 */ r2 = *(r10 - 0x10) as u64   ///  r2 = *((r10 as i64).wrapping_add(-16) as *const u64) as u64
+    // lbb_532:
+    /*   532 */ r1 = r2
+    /*   533 */ r1 *= 48   ///  r1 = r1.wrapping_mul(48 as u64)
+    /*   534 */ r0 = *(r10 - 0x20) as u64   ///  r0 = *((r10 as i64).wrapping_add(-32) as *const u64) as u64
+    /*   535 */ r3 = r0
+    /*   536 */ r3 += r1   ///  r3 = r3.wrapping_add(r1)
+    /*   537 */ *(r3 + 0x2a) = r5 as u8   ///  *((r3 as i64).wrapping_add(42) as *mut u8) = r5 as u8
+    /*   538 */ r1 = *(r10 - 0x48) as u64   ///  r1 = *((r10 as i64).wrapping_add(-72) as *const u64) as u64
+    /*   539 */ *(r3 + 0x29) = r1 as u8   ///  *((r3 as i64).wrapping_add(41) as *mut u8) = r1 as u8
+    /*   540 */ r1 = *(r10 - 0x40) as u64   ///  r1 = *((r10 as i64).wrapping_add(-64) as *const u64) as u64
+    /*   541 */ *(r3 + 0x28) = r1 as u8   ///  *((r3 as i64).wrapping_add(40) as *mut u8) = r1 as u8
+    /*   542 */ *(r3 + 0x20) = r9 as u64   ///  *((r3 as i64).wrapping_add(32) as *mut u64) = r9 as u64
+    /*   543 */ r1 = *(r10 - 0x50) as u64   ///  r1 = *((r10 as i64).wrapping_add(-80) as *const u64) as u64
+    /*   544 */ *(r3 + 0x18) = r1 as u64   ///  *((r3 as i64).wrapping_add(24) as *mut u64) = r1 as u64
+    /*   545 */ *(r3 + 0x10) = r7 as u64   ///  *((r3 as i64).wrapping_add(16) as *mut u64) = r7 as u64
+    /*   546 */ r1 = *(r10 - 0x58) as u64   ///  r1 = *((r10 as i64).wrapping_add(-88) as *const u64) as u64
+    /*   547 */ *(r3 + 0x8) = r1 as u64   ///  *((r3 as i64).wrapping_add(8) as *mut u64) = r1 as u64
+    /*   548 */ *(r3 + 0x0) = r6 as u64   ///  *((r3 as i64).wrapping_add(0) as *mut u64) = r6 as u64
+    /*   549 */ if true { pc += -165 }
+    // lbb_550:
+    /*   550 */ r3 load str located at 4294985296
+    /*   552 */ r0 = fn_0x7049eaee(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0x7049eaee
+    // lbb_553:
+    /*   553 */ abort()
+}
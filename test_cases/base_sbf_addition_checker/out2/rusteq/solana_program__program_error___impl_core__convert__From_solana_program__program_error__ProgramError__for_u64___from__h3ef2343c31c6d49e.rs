@@ -0,0 +1,134 @@
+// solana_program::program_error::<impl core::convert::From<solana_program::program_error::ProgramError> for u64>::from::h3ef2343c31c6d49e (pc 554 to 658)
+fn solana_program::program_error::<impl core::convert::From<solana_program::program_error::ProgramError> for u64>::from::h3ef2343c31c6d49e(r1: u64) -> u64 {
+    /*   554 */ r2 = *(r1 + 0x0) as u32   ///  r2 = *((r1 as i64).wrapping_add(0) as *const u32) as u64
+    /*   555 */ if (r2 as i64) > (11 as i32 as i64) { pc += 9 }
+    // lbb_556:
+    /*   556 */ if (r2 as i64) > (5 as i32 as i64) { pc += 15 }
+    // lbb_557:
+    /*   557 */ if (r2 as i64) > (2 as i32 as i64) { pc += 26 }
+    // lbb_558:
+    /*   558 */ if r2 == (0_i32 as i64 as u64) { pc += 45 }
+    // lbb_559:
+    /*   559 */ r6 load str located at 8589934592
+    /*   561 */ if r2 == (1_i32 as i64 as u64) { pc += 89 }
+    // lbb_562:
+    /*   562 */ r6 load str located at 12884901888
+    /*   564 */ if true { pc += 86 }
+    // lbb_565:
+    /*   565 */ if (r2 as i64) > (17 as i32 as i64) { pc += 12 }
+    // lbb_566:
+    /*   566 */ if (r2 as i64) > (14 as i32 as i64) { pc += 22 }
+    // lbb_567:
+    /*   567 */ if r2 == (12_i32 as i64 as u64) { pc += 42 }
+    // lbb_568:
+    /*   568 */ if r2 == (13_i32 as i64 as u64) { pc += 44 }
+    // lbb_569:
+    /*   569 */ r6 load str located at 64424509440
+    /*   571 */ if true { pc += 79 }
+    // lbb_572:
+    /*   572 */ if (r2 as i64) > (8 as i32 as i64) { pc += 21 }
+    // lbb_573:
+    /*   573 */ if r2 == (6_i32 as i64 as u64) { pc += 42 }
+    // lbb_574:
+    /*   574 */ if r2 == (7_i32 as i64 as u64) { pc += 44 }
+    // lbb_575:
+    /*   575 */ r6 load str located at 38654705664
+    /*   577 */ if true { pc += 73 }
+    // lbb_578:
+    /*   578 */ if (r2 as i64) > (20 as i32 as i64) { pc += 20 }
+    // lbb_579:
+    /*   579 */ if r2 == (18_i32 as i64 as u64) { pc += 42 }
+    // lbb_580:
+    /*   580 */ if r2 == (19_i32 as i64 as u64) { pc += 44 }
+    // lbb_581:
+    /*   581 */ r6 load str located at 90194313216
+    /*   583 */ if true { pc += 67 }
+    // lbb_584:
+    /*   584 */ if r2 == (3_i32 as i64 as u64) { pc += 43 }
+    // lbb_585:
+    /*   585 */ if r2 == (4_i32 as i64 as u64) { pc += 45 }
+    // lbb_586:
+    /*   586 */ r6 load str located at 25769803776
+    /*   588 */ if true { pc += 62 }
+    // lbb_589:
+    /*   589 */ if r2 == (15_i32 as i64 as u64) { pc += 44 }
+    // lbb_590:
+    /*   590 */ if r2 == (16_i32 as i64 as u64) { pc += 46 }
+    // lbb_591:
+    /*   591 */ r6 load str located at 77309411328
+    /*   593 */ if true { pc += 57 }
+    // lbb_594:
+    /*   594 */ if r2 == (9_i32 as i64 as u64) { pc += 45 }
+    // lbb_595:
+    /*   595 */ if r2 == (10_i32 as i64 as u64) { pc += 47 }
+    // lbb_596:
+    /*   596 */ r6 load str located at 51539607552
+    /*   598 */ if true { pc += 52 }
+    // lbb_599:
+    /*   599 */ if r2 == (21_i32 as i64 as u64) { pc += 46 }
+    // lbb_600:
+    /*   600 */ if r2 == (22_i32 as i64 as u64) { pc += 48 }
+    // lbb_601:
+    /*   601 */ r6 load str located at 103079215104
+    /*   603 */ if true { pc += 47 }
+    // lbb_604:
+    /*   604 */ r6 load str located at 4294967296
+    /*   606 */ r3 = *(r1 + 0x4) as u32   ///  r3 = *((r1 as i64).wrapping_add(4) as *const u32) as u64
+    /*   607 */ if r3 == (0_i32 as i64 as u64) { pc += 43 }
+    // lbb_608:
+    /*   608 */ r6 = r3
+    /*   609 */ if true { pc += 41 }
+    // lbb_610:
+    /*   610 */ r6 load str located at 55834574848
+    /*   612 */ if true { pc += 38 }
+    // lbb_613:
+    /*   613 */ r6 load str located at 60129542144
+    /*   615 */ if true { pc += 35 }
+    // lbb_616:
+    /*   616 */ r6 load str located at 30064771072
+    /*   618 */ if true { pc += 32 }
+    // lbb_619:
+    /*   619 */ r6 load str located at 34359738368
+    /*   621 */ if true { pc += 29 }
+    // lbb_622:
+    /*   622 */ r6 load str located at 81604378624
+    /*   624 */ if true { pc += 26 }
+    // lbb_625:
+    /*   625 */ r6 load str located at 85899345920
+    /*   627 */ if true { pc += 23 }
+    // lbb_628:
+    /*   628 */ r6 load str located at 17179869184
+    /*   630 */ if true { pc += 20 }
+    // lbb_631:
+    /*   631 */ r6 load str located at 21474836480
+    /*   633 */ if true { pc += 17 }
+    // lbb_634:
+    /*   634 */ r6 load str located at 68719476736
+    /*   636 */ if true { pc += 14 }
+    // lbb_637:
+    /*   637 */ r6 load str located at 73014444032
+    /*   639 */ if true { pc += 11 }
+    // lbb_640:
+    /*   640 */ r6 load str located at 42949672960
+    /*   642 */ if true { pc += 8 }
+    // lbb_643:
+    /*   643 */ r6 load str located at 47244640256
+    /*   645 */ if true { pc += 5 }
+    // lbb_646:
+    /*   646 */ r6 load str located at 94489280512
+    /*   648 */ if true { pc += 2 }
+    // lbb_649:
+    /*   649 */ r6 load str located at 98784247808
+    // lbb_651:
+    /*   651 */ if r2 != (14_i32 as i64 as u64) { pc += 5 }
+    // lbb_652:
+    /*   652 */ r2 = *(r1 + 0x10) as u64   ///  r2 = *((r1 as i64).wrapping_add(16) as *const u64) as u64
+    /*   653 */ if r2 == (0_i32 as i64 as u64) { pc += 3 }
+    // lbb_654:
+    /*   654 */ r1 = *(r1 + 0x8) as u64   ///  r1 = *((r1 as i64).wrapping_add(8) as *const u64) as u64
+    /*   655 */ r3 = 1_i32 as i64 as u64
+    /*   656 */ r0 = fn_0xb0572554(r1, r2, r3, r4, r5)   ///  syscall or function registered with the key 0xb0572554
+    // lbb_657:
+    /*   657 */ r0 = r6
+    /*   658 */ return r0
+}
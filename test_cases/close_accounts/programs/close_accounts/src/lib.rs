@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+
+declare_id!("C1ose11111111111111111111111111111111111111");
+
+/// Each instruction closes a `Vault`, safely or not. The vulnerable ones are prefixed with
+/// `vulnerable_`.
+#[program]
+pub mod close_accounts {
+    use super::*;
+
+    pub fn open(ctx: Context<Open>) -> Result<()> {
+        ctx.accounts.vault.authority = ctx.accounts.authority.key();
+        Ok(())
+    }
+
+    /// The lamports are moved out, but the data is left as is: the vault can be refunded in the
+    /// same transaction and used again.
+    pub fn vulnerable_manual_close(ctx: Context<ManualClose>) -> Result<()> {
+        let vault = ctx.accounts.vault.to_account_info();
+        let destination = ctx.accounts.authority.to_account_info();
+        **destination.lamports.borrow_mut() += vault.lamports();
+        **ctx.accounts.vault.to_account_info().lamports.borrow_mut() = 0;
+        Ok(())
+    }
+
+    /// Same, with the data wiped and the account given back to the System Program.
+    pub fn manual_close(ctx: Context<ManualClose>) -> Result<()> {
+        let vault = ctx.accounts.vault.to_account_info();
+        let destination = ctx.accounts.authority.to_account_info();
+        **destination.lamports.borrow_mut() += vault.lamports();
+        **vault.lamports.borrow_mut() = 0;
+        vault.try_borrow_mut_data()?.fill(0);
+        vault.assign(&system_program::ID);
+        Ok(())
+    }
+
+    /// `close = receiver`, where `receiver` is any account: anyone can collect the rent.
+    pub fn vulnerable_close_to_anyone(_ctx: Context<CloseToAnyone>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn close(_ctx: Context<Close>) -> Result<()> {
+        Ok(())
+    }
+
+    /// `init_if_needed` on the closed `Vault` type: a closed vault can be reopened.
+    pub fn vulnerable_reopen(ctx: Context<Reopen>) -> Result<()> {
+        ctx.accounts.vault.authority = ctx.accounts.authority.key();
+        Ok(())
+    }
+
+    /// The lamports of the vault are moved out, but the data of another account is wiped.
+    pub fn vulnerable_wipe_other(ctx: Context<WipeOther>) -> Result<()> {
+        let vault = ctx.accounts.vault.to_account_info();
+        let other = ctx.accounts.other.to_account_info();
+        **ctx.accounts.authority.to_account_info().lamports.borrow_mut() += vault.lamports();
+        **vault.lamports.borrow_mut() = 0;
+        other.try_borrow_mut_data()?.fill(0);
+        other.assign(&system_program::ID);
+        Ok(())
+    }
+
+    /// `close = receiver`, where the `constraint` of `receiver` doesn't involve it.
+    pub fn vulnerable_close_to_unrelated_constraint(
+        _ctx: Context<CloseToUnrelatedConstraint>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// `close = receiver`, where `receiver` must be the authority of the vault.
+    pub fn close_to_constrained(_ctx: Context<CloseToConstrained>) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Open<'info> {
+    #[account(init, payer = authority, space = 8 + 32)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManualClose<'info> {
+    #[account(mut, has_one = authority)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseToAnyone<'info> {
+    #[account(mut, has_one = authority, close = receiver)]
+    pub vault: Account<'info, Vault>,
+    pub authority: Signer<'info>,
+    /// CHECK: rent receiver
+    #[account(mut)]
+    pub receiver: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Close<'info> {
+    #[account(mut, has_one = authority, close = authority)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Reopen<'info> {
+    #[account(init_if_needed, payer = authority, space = 8 + 32)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WipeOther<'info> {
+    #[account(mut, has_one = authority)]
+    pub vault: Account<'info, Vault>,
+    /// CHECK: wiped account
+    #[account(mut)]
+    pub other: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseToUnrelatedConstraint<'info> {
+    #[account(mut, has_one = authority, close = receiver)]
+    pub vault: Account<'info, Vault>,
+    pub authority: Signer<'info>,
+    /// CHECK: rent receiver
+    #[account(mut, constraint = authority.key() != Pubkey::default())]
+    pub receiver: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseToConstrained<'info> {
+    #[account(mut, has_one = authority, close = receiver)]
+    pub vault: Account<'info, Vault>,
+    pub authority: Signer<'info>,
+    /// CHECK: rent receiver
+    #[account(mut, constraint = receiver.key() == vault.authority)]
+    pub receiver: UncheckedAccount<'info>,
+}
+
+#[account]
+pub struct Vault {
+    pub authority: Pubkey,
+}
@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke;
+use anchor_spl::token::{self, Token};
+
+declare_id!("CPiProgram111111111111111111111111111111111");
+
+/// Each instruction makes a CPI to a program taken from its accounts, checked or not. The
+/// vulnerable ones are prefixed with `vulnerable_`.
+#[program]
+pub mod cpi_programs {
+    use super::*;
+
+    /// The program is a `Program<'info, Token>`.
+    pub fn typed_transfer(ctx: Context<Transfer>, amount: u64) -> Result<()> {
+        let accounts = token::Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), accounts),
+            amount,
+        )
+    }
+
+    /// Any program can be passed, and gets the signature of the authority.
+    pub fn vulnerable_transfer(ctx: Context<Transfer>, amount: u64) -> Result<()> {
+        let accounts = token::Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new(ctx.accounts.any_program.to_account_info(), accounts),
+            amount,
+        )
+    }
+
+    /// The program is pinned with `address =`.
+    pub fn pinned_transfer(ctx: Context<Transfer>, amount: u64) -> Result<()> {
+        let accounts = token::Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new(ctx.accounts.pinned_program.to_account_info(), accounts),
+            amount,
+        )
+    }
+
+    /// Raw `invoke` of an unchecked program.
+    pub fn vulnerable_invoke(ctx: Context<Transfer>, data: Vec<u8>) -> Result<()> {
+        let instruction = Instruction {
+            program_id: *ctx.accounts.mut_program.key,
+            accounts: vec![],
+            data,
+        };
+        invoke(&instruction, &[ctx.accounts.authority.to_account_info()])?;
+        Ok(())
+    }
+
+    /// Same, but the program id is checked first.
+    pub fn required_invoke(ctx: Context<Transfer>, data: Vec<u8>) -> Result<()> {
+        require_keys_eq!(ctx.accounts.any_program.key(), token::ID);
+        let instruction = Instruction {
+            program_id: *ctx.accounts.any_program.key,
+            accounts: vec![],
+            data,
+        };
+        invoke(&instruction, &[ctx.accounts.authority.to_account_info()])?;
+        Ok(())
+    }
+
+    /// The program is pinned by a `constraint` on its key.
+    pub fn constrained_invoke(ctx: Context<Transfer>, data: Vec<u8>) -> Result<()> {
+        let instruction = Instruction {
+            program_id: *ctx.accounts.constrained_program.key,
+            accounts: vec![],
+            data,
+        };
+        invoke(&instruction, &[ctx.accounts.authority.to_account_info()])?;
+        Ok(())
+    }
+
+    /// The `constraint` of the program doesn't check its key.
+    pub fn vulnerable_constrained_invoke(ctx: Context<Transfer>, data: Vec<u8>) -> Result<()> {
+        let instruction = Instruction {
+            program_id: *ctx.accounts.executable_program.key,
+            accounts: vec![],
+            data,
+        };
+        invoke(&instruction, &[ctx.accounts.authority.to_account_info()])?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Transfer<'info> {
+    /// CHECK: token account, checked by the token program
+    #[account(mut)]
+    pub from: AccountInfo<'info>,
+    /// CHECK: token account, checked by the token program
+    #[account(mut)]
+    pub to: AccountInfo<'info>,
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    /// CHECK: not checked
+    pub any_program: AccountInfo<'info>,
+    /// CHECK: pinned to the token program
+    #[account(address = token::ID)]
+    pub pinned_program: UncheckedAccount<'info>,
+    /// CHECK: not checked
+    #[account(mut)]
+    pub mut_program: UncheckedAccount<'info>,
+    /// CHECK: pinned to the token program
+    #[account(constraint = constrained_program.key() == token::ID)]
+    pub constrained_program: AccountInfo<'info>,
+    /// CHECK: only checked to be a program
+    #[account(constraint = authority.key() != Pubkey::default(), executable)]
+    pub executable_program: AccountInfo<'info>,
+}
@@ -0,0 +1,112 @@
+#![no_std]
+
+use pinocchio::{
+    account_info::AccountInfo, no_allocator, nostd_panic_handler, program_entrypoint,
+    program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+
+program_entrypoint!(process_instruction);
+no_allocator!();
+nostd_panic_handler!();
+
+pinocchio_pubkey::declare_id!("Vau1t11111111111111111111111111111111111111");
+
+#[repr(C)]
+pub struct Vault {
+    pub owner: Pubkey,
+    pub balance: u64,
+}
+
+impl Vault {
+    pub const LEN: usize = core::mem::size_of::<Vault>();
+
+    pub fn load(account: &AccountInfo) -> Result<&mut Self, ProgramError> {
+        let data = unsafe { account.borrow_mut_data_unchecked() };
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { &mut *(data.as_mut_ptr() as *mut Self) })
+    }
+}
+
+#[repr(C)]
+pub struct WithdrawData {
+    pub amount: u64,
+}
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    match data.split_first() {
+        Some((0, amount)) => deposit(program_id, accounts, amount),
+        Some((1, amount)) => withdraw(program_id, accounts, amount),
+        Some((2, amount)) => vulnerable_withdraw(accounts, amount),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn parse_amount(data: &[u8]) -> Result<u64, ProgramError> {
+    let bytes = data
+        .try_into()
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn check_signer(account: &AccountInfo) -> ProgramResult {
+    if !account.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+fn deposit(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [owner, vault, _system_program] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if !owner.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !vault.is_owned_by(program_id) {
+        return Err(ProgramError::IllegalOwner);
+    }
+    let vault_state = Vault::load(vault)?;
+    vault_state.balance = vault_state
+        .balance
+        .checked_add(parse_amount(data)?)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    Ok(())
+}
+
+fn withdraw(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [owner, vault, destination, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    check_signer(owner)?;
+    if unsafe { vault.owner() } != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    let vault_state = Vault::load(vault)?;
+    if vault_state.owner != *owner.key() {
+        return Err(ProgramError::IncorrectAuthority);
+    }
+    let amount = parse_amount(data)?;
+    *vault.try_borrow_mut_lamports()? -= amount;
+    *destination.try_borrow_mut_lamports()? += amount;
+    Ok(())
+}
+
+fn vulnerable_withdraw(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [authority, vault, destination] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let vault_state = Vault::load(vault)?;
+    if vault_state.owner != *authority.key() {
+        return Err(ProgramError::IncorrectAuthority);
+    }
+    let amount = parse_amount(data)?;
+    *vault.try_borrow_mut_lamports()? -= amount;
+    *destination.try_borrow_mut_lamports()? += amount;
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Rea11oc1111111111111111111111111111111111111");
+
+/// Each instruction grows the data of a vault with `realloc(_, false)`, the new bytes being
+/// zeroed (or not) by the instruction or by one of its helpers. The vulnerable ones are prefixed
+/// with `vulnerable_`.
+#[program]
+pub mod reallocs {
+    use super::*;
+
+    /// `grow` leaves the new bytes as they are.
+    pub fn vulnerable_grow(ctx: Context<Resize>, new_len: usize) -> Result<()> {
+        grow(&ctx.accounts.vault, new_len)
+    }
+
+    /// Same, through two helpers.
+    pub fn vulnerable_grow_nested(ctx: Context<Resize>, new_len: usize) -> Result<()> {
+        let extra = new_len - ctx.accounts.vault.data_len();
+        extend(&ctx.accounts.vault, extra)
+    }
+
+    /// The new bytes are zeroed in the instruction.
+    pub fn grow_inline(ctx: Context<Resize>, new_len: usize) -> Result<()> {
+        let vault = &ctx.accounts.vault;
+        let old_len = vault.data_len();
+        vault.realloc(new_len, false)?;
+        let mut data = vault.try_borrow_mut_data()?;
+        for i in old_len..new_len {
+            data[i] = 0;
+        }
+        Ok(())
+    }
+
+    /// The new bytes are zeroed by a helper called by the reallocating helper.
+    pub fn grow_zeroed_in_callee(ctx: Context<Resize>, new_len: usize) -> Result<()> {
+        grow_zeroed(&ctx.accounts.vault, new_len)
+    }
+
+    /// The reallocation is done by a helper, and the new bytes are zeroed by the instruction.
+    pub fn grow_zeroed_in_caller(ctx: Context<Resize>, new_len: usize) -> Result<()> {
+        let vault = &ctx.accounts.vault;
+        let old_len = vault.data_len();
+        resize(vault, new_len)?;
+        zero_tail(vault, old_len)
+    }
+
+    /// `realloc(_, true)` zeroes the new bytes itself.
+    pub fn grow_zero_init(ctx: Context<Resize>, new_len: usize) -> Result<()> {
+        ctx.accounts.vault.realloc(new_len, true)?;
+        Ok(())
+    }
+}
+
+fn grow(account: &AccountInfo, new_len: usize) -> Result<()> {
+    account.realloc(new_len, false)?;
+    Ok(())
+}
+
+fn extend(account: &AccountInfo, extra: usize) -> Result<()> {
+    grow_raw(account, account.data_len() + extra)
+}
+
+fn grow_raw(account: &AccountInfo, new_len: usize) -> Result<()> {
+    account.realloc(new_len, false)?;
+    Ok(())
+}
+
+fn grow_zeroed(account: &AccountInfo, new_len: usize) -> Result<()> {
+    let old_len = account.data_len();
+    account.realloc(new_len, false)?;
+    zero_tail(account, old_len)
+}
+
+fn resize(account: &AccountInfo, new_len: usize) -> Result<()> {
+    account.realloc(new_len, false)?;
+    Ok(())
+}
+
+fn zero_tail(account: &AccountInfo, from: usize) -> Result<()> {
+    account.try_borrow_mut_data()?[from..].fill(0);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Resize<'info> {
+    /// CHECK: resized by the instructions
+    #[account(mut)]
+    pub vault: AccountInfo<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+declare_id!("Recap11111111111111111111111111111111111111");
+
+#[program]
+pub mod recap_anchor {
+    use super::*;
+
+    pub fn deposit(ctx: Context<Deposit>, vault_bump: u8, amount: u64) -> Result<()> {
+        ctx.accounts.user_state.amount += amount;
+        ctx.accounts.user_state.vault_bump = vault_bump;
+        Ok(())
+    }
+
+    pub fn transfer(ctx: Context<Transfer>, amount: u64) -> Result<()> {
+        let _ = (ctx, amount);
+        Ok(())
+    }
+
+    pub fn close_state(ctx: Context<CloseState>) -> Result<()> {
+        let _ = ctx;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(vault_bump: u8)]
+pub struct Deposit<'info> {
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserState::INIT_SPACE,
+        seeds = [b"state", user.key().as_ref()],
+        bump
+    )]
+    pub user_state: Account<'info, UserState>,
+    /// CHECK: lamports vault
+    #[account(mut, seeds = [b"vault", user.key().as_ref()], bump = vault_bump)]
+    pub vault: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Transfer<'info> {
+    #[account(mut, token::mint = mint, token::authority = authority)]
+    pub from: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub to: Box<Account<'info, TokenAccount>>,
+    pub mint: Account<'info, Mint>,
+    /// CHECK: the IDL below claims it signs, but nothing enforces it
+    pub authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseState<'info> {
+    #[account(mut, close = user, seeds = [b"state", user.key().as_ref()], bump)]
+    pub user_state: Account<'info, UserState>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct UserState {
+    pub amount: u64,
+    pub vault_bump: u8,
+}
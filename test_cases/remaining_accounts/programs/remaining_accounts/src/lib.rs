@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke, program_pack::Pack, system_instruction};
+
+declare_id!("Remain1111111111111111111111111111111111111");
+
+/// Each instruction reads accounts from `remaining_accounts`, with or without validating them.
+/// The vulnerable ones are prefixed with `vulnerable_`.
+#[program]
+pub mod remaining_accounts {
+    use super::*;
+
+    /// The vault is deserialized from any account the caller passes.
+    pub fn vulnerable_read_vault(ctx: Context<Pay>) -> Result<()> {
+        let account = &ctx.remaining_accounts[0];
+        let data = account.try_borrow_data()?;
+        let vault = Vault::try_deserialize(&mut &data[..])?;
+        msg!("balance: {}", vault.balance);
+        Ok(())
+    }
+
+    pub fn read_vault(ctx: Context<Pay>) -> Result<()> {
+        let account = &ctx.remaining_accounts[0];
+        require_keys_eq!(*account.owner, crate::ID);
+        let data = account.try_borrow_data()?;
+        let vault = Vault::try_deserialize(&mut &data[..])?;
+        msg!("balance: {}", vault.balance);
+        Ok(())
+    }
+
+    pub fn read_vault_with_anchor(ctx: Context<Pay>) -> Result<()> {
+        let vault: Account<Vault> = Account::try_from(&ctx.remaining_accounts[0])?;
+        msg!("balance: {}", vault.balance);
+        Ok(())
+    }
+
+    /// Lamports are sent to whatever recipients are passed.
+    pub fn vulnerable_pay_all(ctx: Context<Pay>, amount: u64) -> Result<()> {
+        for recipient in ctx.remaining_accounts.iter() {
+            invoke(
+                &system_instruction::transfer(ctx.accounts.payer.key, recipient.key, amount),
+                &[ctx.accounts.payer.to_account_info(), recipient.clone()],
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn pay_all(ctx: Context<Pay>, amount: u64) -> Result<()> {
+        let recipients = &ctx.remaining_accounts;
+        for recipient in recipients.iter() {
+            require!(
+                ctx.accounts.config.recipients.contains(recipient.key),
+                ErrorCode::UnknownRecipient
+            );
+            invoke(
+                &system_instruction::transfer(ctx.accounts.payer.key, recipient.key, amount),
+                &[ctx.accounts.payer.to_account_info(), recipient.clone()],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// The mint is unpacked without checking it is owned by the token program.
+    pub fn vulnerable_mint_supply(ctx: Context<Pay>) -> Result<()> {
+        let accounts = &mut ctx.remaining_accounts.iter();
+        let mint = next_account_info(accounts)?;
+        let state = anchor_spl::token::spl_token::state::Mint::unpack(&mint.data.borrow())?;
+        msg!("supply: {}", state.supply);
+        Ok(())
+    }
+
+    /// Only the number of remaining accounts is used.
+    pub fn count(ctx: Context<Pay>) -> Result<()> {
+        require!(ctx.remaining_accounts.len() <= 8, ErrorCode::TooManyAccounts);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Pay<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct Vault {
+    pub balance: u64,
+}
+
+#[account]
+pub struct Config {
+    pub recipients: Vec<Pubkey>,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Unknown recipient")]
+    UnknownRecipient,
+    #[msg("Too many accounts")]
+    TooManyAccounts,
+}
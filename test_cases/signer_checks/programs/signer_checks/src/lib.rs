@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Signer1111111111111111111111111111111111111");
+
+/// Each instruction takes lamports out of a vault, with or without a signature from its owner.
+/// The vulnerable ones are prefixed with `vulnerable_`.
+#[program]
+pub mod signer_checks {
+    use super::*;
+
+    /// Nobody signs: anyone can empty the vault.
+    pub fn vulnerable_withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        **ctx.accounts.vault.try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.owner.try_borrow_mut_lamports()? += amount;
+        Ok(())
+    }
+
+    /// The admin is a `Signer`.
+    pub fn admin_withdraw(ctx: Context<AdminWithdraw>, amount: u64) -> Result<()> {
+        **ctx.accounts.vault.try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.admin.try_borrow_mut_lamports()? += amount;
+        Ok(())
+    }
+
+    /// The owner is checked with a `signer` constraint.
+    pub fn constrained_withdraw(ctx: Context<ConstrainedWithdraw>, amount: u64) -> Result<()> {
+        **ctx.accounts.vault.try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.owner.try_borrow_mut_lamports()? += amount;
+        Ok(())
+    }
+
+    /// The owner is checked in the handler.
+    pub fn checked_withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        require!(ctx.accounts.owner.is_signer, ErrorCode::AccountNotSigner);
+        **ctx.accounts.vault.try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.owner.try_borrow_mut_lamports()? += amount;
+        Ok(())
+    }
+
+    /// The owner is checked in a `constraint`.
+    pub fn constraint_withdraw(ctx: Context<ConstraintWithdraw>, amount: u64) -> Result<()> {
+        **ctx.accounts.vault.try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.owner.try_borrow_mut_lamports()? += amount;
+        Ok(())
+    }
+
+    /// The account named `signer` is never checked: anyone can empty the vault.
+    pub fn vulnerable_named_signer(ctx: Context<NamedSigner>, amount: u64) -> Result<()> {
+        **ctx.accounts.vault.try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.signer.try_borrow_mut_lamports()? += amount;
+        Ok(())
+    }
+
+    /// Nothing is written.
+    pub fn log(ctx: Context<Withdraw>) -> Result<()> {
+        msg!("vault: {}", ctx.accounts.vault.key());
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    /// CHECK: only holds lamports
+    #[account(mut)]
+    pub vault: AccountInfo<'info>,
+    /// CHECK: only receives lamports
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AdminWithdraw<'info> {
+    /// CHECK: only holds lamports
+    #[account(mut)]
+    pub vault: AccountInfo<'info>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConstrainedWithdraw<'info> {
+    /// CHECK: only holds lamports
+    #[account(mut)]
+    pub vault: AccountInfo<'info>,
+    /// CHECK: must sign the transaction
+    #[account(mut, signer)]
+    pub owner: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConstraintWithdraw<'info> {
+    /// CHECK: only holds lamports
+    #[account(mut)]
+    pub vault: AccountInfo<'info>,
+    /// CHECK: must sign the transaction
+    #[account(mut, constraint = owner.is_signer)]
+    pub owner: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct NamedSigner<'info> {
+    /// CHECK: only holds lamports
+    #[account(mut)]
+    pub vault: AccountInfo<'info>,
+    /// CHECK: only receives lamports
+    #[account(mut)]
+    pub signer: AccountInfo<'info>,
+}
@@ -0,0 +1,96 @@
+use steel::*;
+
+declare_id!("Cnt111111111111111111111111111111111111111");
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+pub enum CounterAccount {
+    Counter = 0,
+}
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, TryFromPrimitive)]
+pub enum CounterInstruction {
+    Initialize = 0,
+    Add = 1,
+    Reset = 2,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct Counter {
+    pub authority: Pubkey,
+    pub value: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct Add {
+    pub amount: [u8; 8],
+}
+
+account!(CounterAccount, Counter);
+instruction!(CounterInstruction, Add);
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let (ix, data) = parse_instruction(&crate::ID, program_id, data)?;
+    match ix {
+        CounterInstruction::Initialize => process_initialize(accounts),
+        CounterInstruction::Add => process_add(accounts, data),
+        CounterInstruction::Reset => process_reset(accounts),
+    }
+}
+
+fn process_initialize(accounts: &[AccountInfo]) -> ProgramResult {
+    let [signer_info, counter_info, system_program] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    signer_info.is_signer()?;
+    counter_info
+        .is_empty()?
+        .is_writable()?
+        .has_seeds(&[b"counter"], &crate::ID)?;
+    system_program.is_program(&system_program::ID)?;
+    create_program_account::<Counter>(
+        counter_info,
+        system_program,
+        signer_info,
+        &crate::ID,
+        &[b"counter"],
+    )?;
+    let counter = counter_info.as_account_mut::<Counter>(&crate::ID)?;
+    counter.authority = *signer_info.key;
+    Ok(())
+}
+
+fn process_add(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let args = Add::try_from_bytes(data)?;
+    let [signer_info, counter_info] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    signer_info.is_signer()?;
+    let counter = counter_info
+        .is_writable()?
+        .as_account_mut::<Counter>(&crate::ID)?
+        .assert_mut(|c| c.authority == *signer_info.key)?;
+    counter.value += u64::from_le_bytes(args.amount);
+    Ok(())
+}
+
+fn process_reset(accounts: &[AccountInfo]) -> ProgramResult {
+    let [authority_info, counter_info] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let counter = Counter::try_from_bytes_mut(&mut counter_info.data.borrow_mut())?;
+    if counter.authority != *authority_info.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    counter.value = 0;
+    Ok(())
+}
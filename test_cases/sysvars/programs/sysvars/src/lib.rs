@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+#[allow(deprecated)]
+use anchor_lang::solana_program::sysvar::recent_blockhashes::RecentBlockhashes;
+
+declare_id!("Sysvar1111111111111111111111111111111111111");
+
+const UNLOCK_TIME: i64 = 1_735_689_600;
+
+/// Each instruction relies on the clock or on a sysvar, safely or not. The vulnerable ones are
+/// prefixed with `vulnerable_`.
+#[program]
+pub mod sysvars {
+    use super::*;
+
+    /// The unlock date is hardcoded.
+    pub fn vulnerable_unlock(ctx: Context<Unlock>) -> Result<()> {
+        if Clock::get()?.unix_timestamp < 1_735_689_600 {
+            return err!(ErrorCode::Locked);
+        }
+        ctx.accounts.vault.unlocked = true;
+        Ok(())
+    }
+
+    pub fn vulnerable_unlock_require(ctx: Context<Unlock>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= UNLOCK_TIME, ErrorCode::Locked);
+        ctx.accounts.vault.unlocked = true;
+        Ok(())
+    }
+
+    /// The unlock date is stored in the vault, with a margin.
+    pub fn unlock(ctx: Context<Unlock>) -> Result<()> {
+        let clock = Clock::get()?;
+        require_gte!(clock.unix_timestamp, ctx.accounts.vault.unlock_at, ErrorCode::Locked);
+        ctx.accounts.vault.unlocked = true;
+        Ok(())
+    }
+
+    pub fn vulnerable_rent(ctx: Context<Fund>, space: u64) -> Result<()> {
+        let rent = Rent::from_account_info(&ctx.accounts.rent)?;
+        ctx.accounts.vault.reserve = rent.minimum_balance(space as usize);
+        Ok(())
+    }
+
+    pub fn rent(ctx: Context<Fund>, space: u64) -> Result<()> {
+        ctx.accounts.vault.reserve = Rent::get()?.minimum_balance(space as usize);
+        Ok(())
+    }
+
+    /// Picks a "random" winner from the recent blockhashes.
+    pub fn vulnerable_draw(ctx: Context<Draw>) -> Result<()> {
+        let blockhashes = ctx.accounts.recent_blockhashes.to_account_info();
+        let data = blockhashes.try_borrow_data()?;
+        ctx.accounts.vault.winner = data[8];
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Unlock<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+}
+
+#[derive(Accounts)]
+pub struct Fund<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    /// CHECK: read with `Rent::from_account_info`
+    pub rent: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Draw<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    pub recent_blockhashes: Sysvar<'info, RecentBlockhashes>,
+}
+
+#[account]
+pub struct Vault {
+    pub unlock_at: i64,
+    pub unlocked: bool,
+    pub reserve: u64,
+    pub winner: u8,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("The vault is locked")]
+    Locked,
+}